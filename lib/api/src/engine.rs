@@ -16,7 +16,7 @@ use std::sync::Arc;
 #[allow(unused_imports)]
 pub use wasmer_compiler::{Artifact, CompilerConfig, EngineInner, Features, Tunables};
 #[cfg(feature = "sys")]
-use wasmer_types::DeserializeError;
+use wasmer_types::{CompileError, DeserializeError, Target};
 
 #[cfg(feature = "js")]
 use crate::js::engine as engine_imp;
@@ -103,6 +103,50 @@ impl Engine {
         let bytes = std::fs::read(file_ref)?;
         Ok(Arc::new(Artifact::deserialize(&self.0, bytes.into())?))
     }
+
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Validates and compiles a WebAssembly binary straight into serialized
+    /// module bytes, without creating a `Module`.
+    ///
+    /// The result can be loaded with `Module::deserialize`.
+    pub fn precompile_module(&self, bytes: impl IntoBytes) -> Result<Vec<u8>, CompileError> {
+        self.0.precompile_module(&bytes.into_bytes())
+    }
+
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Like [`Self::precompile_module`], but compiling for the given target
+    /// and tunables instead of the ones of this engine, for instance to
+    /// cross-compile modules for other platforms.
+    pub fn precompile_module_for(
+        &self,
+        bytes: impl IntoBytes,
+        target: &Target,
+        tunables: &dyn Tunables,
+    ) -> Result<Vec<u8>, CompileError> {
+        self.0
+            .precompile_module_for(&bytes.into_bytes(), target, tunables)
+    }
+
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Converts a module serialized by another Wasmer release, or for another
+    /// target, into one that this engine can deserialize.
+    ///
+    /// See [`Artifact::convert`].
+    pub fn convert_artifact(&self, bytes: impl IntoBytes) -> Result<Vec<u8>, DeserializeError> {
+        self.0.convert_artifact(&bytes.into_bytes())
+    }
+
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Increments the epoch of this engine.
+    ///
+    /// Code compiled with epoch interruption enabled traps with
+    /// `TrapCode::Interrupt` once the epoch reaches the deadline of its
+    /// store (see [`Store::set_epoch_deadline`](crate::Store::set_epoch_deadline)).
+    /// This is cheap and can be called from any thread, for instance from
+    /// a timer.
+    pub fn increment_epoch(&self) {
+        self.0.increment_epoch()
+    }
 }
 
 impl AsEngineRef for Engine {
@@ -2,6 +2,7 @@
 use crate::js::trap::Trap;
 #[cfg(feature = "jsc")]
 use crate::jsc::trap::Trap;
+use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
 use thiserror::Error;
@@ -25,6 +26,13 @@ pub enum LinkError {
     #[cfg_attr(feature = "std", error("Error while importing {0:?}.{1:?}: {2}"))]
     Import(String, String, ImportError),
 
+    /// Several imports are missing or have incompatible types.
+    #[cfg_attr(
+        feature = "std",
+        error("{} imports can't be satisfied:{}", .0.len(), display_import_errors(.0))
+    )]
+    Imports(Vec<(String, String, ImportError)>),
+
     /// A trap ocurred during linking.
     #[cfg_attr(feature = "std", error("RuntimeError occurred during linking: {0}"))]
     Trap(#[source] RuntimeError),
@@ -33,6 +41,47 @@ pub enum LinkError {
     Resource(String),
 }
 
+impl LinkError {
+    /// Creates an error for imports which can't be satisfied, with the
+    /// `Import` variant if there is only one of them.
+    pub(crate) fn from_import_errors(mut errors: Vec<(String, String, ImportError)>) -> Self {
+        if errors.len() == 1 {
+            let (module, name, error) = errors.remove(0);
+            Self::Import(module, name, error)
+        } else {
+            Self::Imports(errors)
+        }
+    }
+
+    /// Returns the imports which can't be satisfied, as their module and
+    /// field names, and the reason why they can't be satisfied: either they
+    /// are missing, or the type of the provided extern doesn't match the
+    /// type of the import.
+    ///
+    /// All the imports of a module are checked, so this lists every
+    /// missing or incompatible import at once.
+    pub fn import_errors(&self) -> Vec<(&str, &str, &ImportError)> {
+        match self {
+            Self::Import(module, name, error) => vec![(module, name, error)],
+            Self::Imports(errors) => errors
+                .iter()
+                .map(|(module, name, error)| (module.as_str(), name.as_str(), error))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn display_import_errors(errors: &[(String, String, ImportError)]) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    for (module, name, error) in errors {
+        let _ = write!(out, "\n  {module:?}.{name:?}: {error}");
+    }
+    out
+}
+
 /// An error while instantiating a module.
 ///
 /// This is not a common WebAssembly error, however
@@ -98,6 +147,29 @@ impl std::error::Error for RuntimeStringError {
     }
 }
 
+/// A user error carrying an embedder-defined payload, see
+/// [`RuntimeError::with_payload`].
+struct RuntimePayloadError {
+    message: String,
+    payload: Box<dyn Any + Send + Sync>,
+}
+
+impl fmt::Debug for RuntimePayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RuntimePayloadError")
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RuntimePayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RuntimePayloadError {}
+
 pub(crate) struct RuntimeErrorInner {
     /// The source error
     pub(crate) source: Trap,
@@ -105,6 +177,9 @@ pub(crate) struct RuntimeErrorInner {
     trap_code: Option<TrapCode>,
     /// The reconstructed Wasm trace (from the native trace and the `GlobalFrameInfo`).
     wasm_trace: Vec<FrameInfo>,
+    /// The coredump of the instance, if captured.
+    #[cfg(feature = "sys")]
+    pub(crate) coredump: Option<crate::sys::Coredump>,
 }
 
 impl RuntimeError {
@@ -145,6 +220,8 @@ impl RuntimeError {
                 source,
                 wasm_trace,
                 trap_code,
+                #[cfg(feature = "sys")]
+                coredump: None,
             }),
         }
     }
@@ -160,6 +237,40 @@ impl RuntimeError {
         }
     }
 
+    /// Creates a custom user error with the given `message`, carrying an
+    /// embedder-defined `payload`, such as an error code enum.
+    ///
+    /// Like the errors created with [`RuntimeError::user`], it can be
+    /// returned by a host function, passed through Wasm frames, and the
+    /// payload later retrieved using the `payload` method.
+    ///
+    /// # Example
+    /// ```
+    /// #[derive(Debug, PartialEq)]
+    /// enum Exit {
+    ///     Code(i32),
+    /// }
+    ///
+    /// let trap = wasmer::RuntimeError::with_payload("exited", Exit::Code(2));
+    /// assert_eq!("exited", trap.message());
+    /// assert_eq!(Some(&Exit::Code(2)), trap.payload::<Exit>());
+    /// assert_eq!(None, trap.payload::<i32>());
+    /// ```
+    pub fn with_payload<I: Into<String>, P: Any + Send + Sync>(message: I, payload: P) -> Self {
+        Self::user(Box::new(RuntimePayloadError {
+            message: message.into(),
+            payload: Box::new(payload),
+        }))
+    }
+
+    /// Returns the payload of this error, if it was created with
+    /// [`RuntimeError::with_payload`] and a payload of type `P`.
+    pub fn payload<P: Any>(&self) -> Option<&P> {
+        self.downcast_ref::<RuntimePayloadError>()?
+            .payload
+            .downcast_ref::<P>()
+    }
+
     /// Returns a reference the `message` stored in `Trap`.
     pub fn message(&self) -> String {
         if let Some(trap_code) = self.inner.trap_code {
@@ -171,10 +282,23 @@ impl RuntimeError {
 
     /// Returns a list of function frames in WebAssembly code that led to this
     /// trap happening.
+    ///
+    /// The frames of errors returned by host functions are only reliable
+    /// when the module was compiled with Cranelift: the other compilers
+    /// don't describe how to unwind their host function trampolines, so
+    /// the list may be empty or incomplete.
     pub fn trace(&self) -> &[FrameInfo] {
         &self.inner.wasm_trace
     }
 
+    /// Returns the coredump captured when this trap reached the host, if
+    /// the engine was configured to capture one with
+    /// [`NativeEngineExt::set_coredump_on_trap`](crate::NativeEngineExt::set_coredump_on_trap).
+    #[cfg(feature = "sys")]
+    pub fn coredump(&self) -> Option<&crate::sys::Coredump> {
+        self.inner.coredump.as_ref()
+    }
+
     /// Returns trap code, if it's a Trap
     pub fn to_trap(self) -> Option<TrapCode> {
         self.inner.trap_code
@@ -242,6 +366,10 @@ impl fmt::Display for RuntimeError {
                 func_index,
                 frame.module_offset()
             )?;
+            if let Some(location) = frame.source_location() {
+                writeln!(f)?;
+                write!(f, "        at {}", location)?;
+            }
         }
         Ok(())
     }
@@ -274,6 +402,10 @@ pub enum AtomicsError {
     TooManyWaiters,
     /// Atomic operations are disabled.
     AtomicsDisabled,
+    /// The atomic access is out of the bounds of the memory.
+    OutOfBounds,
+    /// The atomic access is not naturally aligned.
+    Unaligned,
 }
 
 impl std::fmt::Display for AtomicsError {
@@ -282,6 +414,8 @@ impl std::fmt::Display for AtomicsError {
             Self::Unimplemented => write!(f, "Atomic operations are not supported"),
             Self::TooManyWaiters => write!(f, "Too many waiters for address"),
             Self::AtomicsDisabled => write!(f, "Atomic operations are disabled"),
+            Self::OutOfBounds => write!(f, "Atomic access out of bounds"),
+            Self::Unaligned => write!(f, "Unaligned atomic access"),
         }
     }
 }
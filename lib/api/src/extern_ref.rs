@@ -13,6 +13,30 @@ use crate::vm::VMExternRef;
 #[derive(Debug, Clone)]
 #[repr(transparent)]
 /// An opaque reference to some data. This reference can be passed through Wasm.
+///
+/// The data is owned by the [`Store`](crate::Store), and an `ExternRef` is
+/// only a handle to it: cloning it, passing it to WebAssembly, or storing it
+/// in a table or a global all refer to the same data. The data isn't
+/// garbage collected, it lives as long as the store and is dropped with it,
+/// which is when the drop hook registered with
+/// [`ExternRef::new_with_drop_hook`] is called.
+///
+/// # Example
+///
+/// ```
+/// # use wasmer::{ExternRef, Store};
+/// # let mut store = Store::default();
+/// #
+/// struct Connection {
+///     queries: usize,
+/// }
+///
+/// let conn = ExternRef::new(&mut store, Connection { queries: 0 });
+/// conn.downcast_mut::<Connection>(&mut store).unwrap().queries += 1;
+///
+/// assert_eq!(conn.downcast::<Connection>(&store).unwrap().queries, 1);
+/// assert!(conn.downcast::<String>(&store).is_none());
+/// ```
 pub struct ExternRef(pub(crate) extern_ref_imp::ExternRef);
 
 impl ExternRef {
@@ -24,6 +48,24 @@ impl ExternRef {
         Self(extern_ref_imp::ExternRef::new(store, value))
     }
 
+    /// Make a new extern reference, calling `on_drop` with the value when
+    /// the store is dropped.
+    ///
+    /// This can be used to release resources held by the host, like
+    /// connections, once WebAssembly can't use them anymore.
+    pub fn new_with_drop_hook<T>(
+        store: &mut impl AsStoreMut,
+        value: T,
+        on_drop: impl FnOnce(T) + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Any + Send + Sync + 'static + Sized,
+    {
+        Self(extern_ref_imp::ExternRef::new_with_drop_hook(
+            store, value, on_drop,
+        ))
+    }
+
     /// Try to downcast to the given value.
     pub fn downcast<'a, T>(&self, store: &'a impl AsStoreRef) -> Option<&'a T>
     where
@@ -32,6 +74,14 @@ impl ExternRef {
         self.0.downcast(store)
     }
 
+    /// Try to mutably downcast to the given value.
+    pub fn downcast_mut<'a, T>(&self, store: &'a mut impl AsStoreMut) -> Option<&'a mut T>
+    where
+        T: Any + Send + Sync + 'static + Sized,
+    {
+        self.0.downcast_mut(store)
+    }
+
     pub(crate) fn vm_externref(&self) -> VMExternRef {
         self.0.vm_externref()
     }
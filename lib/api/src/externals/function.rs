@@ -15,6 +15,85 @@ use wasmer_types::RawValue;
 
 use crate::native_type::WasmTypeList;
 
+/// The future returned by asynchronous host functions, see
+/// [`Function::new_with_env_async`].
+#[cfg(feature = "sys")]
+pub type HostFuture<'a> = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<Vec<Value>, RuntimeError>> + Send + 'a>,
+>;
+
+/// The state of a call made with [`Function::call_resumable`].
+#[cfg(feature = "sys")]
+#[derive(Debug)]
+pub enum CallState {
+    /// The call returned these results.
+    Finished(Box<[Value]>),
+    /// The call is suspended by a pending asynchronous host function.
+    Suspended(ResumableCall),
+}
+
+/// A call made with [`Function::call_resumable`] which is suspended, and can
+/// be resumed with [`ResumableCall::resume`].
+///
+/// If this is dropped, the suspended call is abandoned like a dropped
+/// [`Function::call_async`] future.
+#[cfg(feature = "sys")]
+pub struct ResumableCall(pub(crate) function_impl::ResumableCall);
+
+#[cfg(feature = "sys")]
+impl ResumableCall {
+    /// Resumes the suspended call, polling the pending host future again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` isn't the store the call was made with.
+    pub fn resume(self, store: &mut impl AsStoreMut) -> Result<CallState, RuntimeError> {
+        self.0.resume(store)
+    }
+}
+
+#[cfg(feature = "sys")]
+impl std::fmt::Debug for ResumableCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumableCall").finish_non_exhaustive()
+    }
+}
+
+/// Returns a future which is pending the first time it is polled, for
+/// asynchronous host functions to suspend the calling WebAssembly code.
+///
+/// When the code was called with [`Function::call_resumable`], this returns
+/// control to the embedder until the call is resumed. When it was called
+/// with [`Function::call_async`], this yields to the executor.
+#[cfg(feature = "sys")]
+pub fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+/// The future returned by [`yield_now`].
+#[cfg(feature = "sys")]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct YieldNow(bool);
+
+#[cfg(feature = "sys")]
+impl std::future::Future for YieldNow {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
+
 /// The `HostFunction` trait represents the set of functions that
 /// can be used as host function. To uphold this statement, it is
 /// necessary for a function to be transformed into a
@@ -56,6 +135,15 @@ pub struct WithoutEnv;
 
 impl HostFunctionKind for WithoutEnv {}
 
+/// An empty struct to help Rust typing to determine
+/// when a `HostFunction` takes its environment by reference, see
+/// [`Function::new_typed_raw`].
+#[cfg(feature = "sys")]
+pub struct WithRawEnv;
+
+#[cfg(feature = "sys")]
+impl HostFunctionKind for WithRawEnv {}
+
 mod private {
     //! Sealing the HostFunctionKind because it shouldn't be implemented
     //! by any type outside.
@@ -64,6 +152,8 @@ mod private {
     pub trait HostFunctionKindSealed {}
     impl HostFunctionKindSealed for super::WithEnv {}
     impl HostFunctionKindSealed for super::WithoutEnv {}
+    #[cfg(feature = "sys")]
+    impl HostFunctionKindSealed for super::WithRawEnv {}
 }
 
 /// A WebAssembly `function` instance.
@@ -156,7 +246,69 @@ impl Function {
         Self(function_impl::Function::new_with_env(store, env, ty, func))
     }
 
+    /// Creates a new asynchronous host `Function` (dynamic) with the provided
+    /// signature.
+    ///
+    /// See [`Function::new_with_env_async`].
+    #[cfg(feature = "sys")]
+    pub fn new_async<FT, F>(store: &mut impl AsStoreMut, ty: FT, func: F) -> Self
+    where
+        FT: Into<FunctionType>,
+        F: for<'a> Fn(&'a [Value]) -> HostFuture<'a> + 'static + Send + Sync,
+    {
+        let env = FunctionEnv::new(&mut store.as_store_mut(), ());
+        Self::new_with_env_async(store, &env, ty, move |_env, args| func(args))
+    }
+
+    /// Creates a new asynchronous host `Function` (dynamic) with the provided
+    /// signature.
+    ///
+    /// While the returned future is pending, the calling WebAssembly code is
+    /// suspended and the future returned by [`Function::call_async`] or
+    /// [`TypedFunction::call_async`] is pending as well, so no thread is
+    /// blocked. Similarly, [`Function::call_resumable`] returns control to
+    /// the caller. Calling this function synchronously results in a trap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmer::{Function, FunctionEnv, FunctionType, Type, Store, Value};
+    /// # let mut store = Store::default();
+    /// # let env = FunctionEnv::new(&mut store, ());
+    /// #
+    /// let signature = FunctionType::new(vec![Type::I32], vec![Type::I32]);
+    ///
+    /// let f = Function::new_with_env_async(&mut store, &env, &signature, |_env, args| {
+    ///     Box::pin(async move {
+    ///         // Wait for some I/O here.
+    ///         Ok(vec![Value::I32(args[0].unwrap_i32() + 1)])
+    ///     })
+    /// });
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn new_with_env_async<FT, F, T: Send + 'static>(
+        store: &mut impl AsStoreMut,
+        env: &FunctionEnv<T>,
+        ty: FT,
+        func: F,
+    ) -> Self
+    where
+        FT: Into<FunctionType>,
+        F: for<'a> Fn(FunctionEnvMut<'a, T>, &'a [Value]) -> HostFuture<'a> + 'static + Send + Sync,
+    {
+        Self(function_impl::Function::new_with_env_async(
+            store, env, ty, func,
+        ))
+    }
+
     /// Creates a new host `Function` from a native function.
+    ///
+    /// The function can take up to 26 parameters, and return a tuple of up
+    /// to 26 values or an array of any length, like `[i32; 32]`.
+    ///
+    /// With the `sys` backend, functions returning more than one value are
+    /// called from WebAssembly through a dynamic trampoline, so like dynamic
+    /// functions, they cannot be used in tables.
     pub fn new_typed<F, Args, Rets>(store: &mut impl AsStoreMut, func: F) -> Self
     where
         F: HostFunction<(), Args, Rets, WithoutEnv> + 'static + Send + Sync,
@@ -199,6 +351,48 @@ impl Function {
         ))
     }
 
+    /// Creates a new host `Function` from a typed function which gets its
+    /// data directly, for host functions called often enough for the call
+    /// overhead to matter.
+    ///
+    /// WebAssembly calls the function through a thin `extern "C"` wrapper,
+    /// with the same native ABI as calls between WebAssembly functions: the
+    /// arguments and results aren't boxed, and `data` is reached through the
+    /// function context pointer rather than looked up in the store.
+    ///
+    /// In exchange, the function doesn't get access to the store, so it
+    /// can't call back into WebAssembly or use memories and other store
+    /// objects, and it runs on the WebAssembly stack rather than switching
+    /// to the host stack, so it shouldn't use a lot of stack space. It can
+    /// still trap by returning an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::{Function, Store};
+    /// # let mut store = Store::default();
+    /// #
+    /// fn count(calls: &mut u64, x: i32) -> i32 {
+    ///     *calls += 1;
+    ///     x * 2
+    /// }
+    ///
+    /// let f = Function::new_typed_raw(&mut store, 0u64, count);
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn new_typed_raw<T: Send + 'static, F, Args, Rets>(
+        store: &mut impl AsStoreMut,
+        data: T,
+        func: F,
+    ) -> Self
+    where
+        F: HostFunction<T, Args, Rets, WithRawEnv> + 'static + Send + Sync,
+        Args: WasmTypeList,
+        Rets: WasmTypeList,
+    {
+        Self(function_impl::Function::new_typed_raw(store, data, func))
+    }
+
     /// Returns the [`FunctionType`] of the `Function`.
     ///
     /// # Example
@@ -302,6 +496,76 @@ impl Function {
         self.0.call(store, params)
     }
 
+    /// Call the function asynchronously.
+    ///
+    /// This is the same as [`Function::call`], except that the WebAssembly
+    /// code is suspended instead of blocking the thread while an
+    /// asynchronous host function is pending, see
+    /// [`Function::new_with_env_async`].
+    ///
+    /// If the returned future is dropped before completion, the suspended
+    /// call is abandoned: the pending host futures are leaked and the
+    /// instance may be left in an inconsistent state.
+    #[cfg(feature = "sys")]
+    pub async fn call_async(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: &[Value],
+    ) -> Result<Box<[Value]>, RuntimeError> {
+        self.0.call_async(store, params).await
+    }
+
+    /// Call the function, returning control to the caller whenever an
+    /// asynchronous host function is pending.
+    ///
+    /// This drives the call like [`Function::call_async`], without needing
+    /// an executor: while a host future is pending, the WebAssembly code is
+    /// suspended and [`CallState::Suspended`] is returned. The store can be
+    /// used in the meantime, and the call continues when resumed with
+    /// [`ResumableCall::resume`]. Host functions can suspend the call on
+    /// purpose with [`yield_now`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmer::{imports, wat2wasm, CallState, Function, FunctionType, Instance, Module, Store, Type, Value};
+    /// # let mut store = Store::default();
+    /// let wasm_bytes = wat2wasm(r#"
+    /// (module
+    ///   (import "host" "pause" (func $pause))
+    ///   (func (export "run") (result i32)
+    ///     (call $pause)
+    ///     (i32.const 42)))
+    /// "#.as_bytes()).unwrap();
+    /// let module = Module::new(&store, wasm_bytes).unwrap();
+    /// let pause = Function::new_async(&mut store, FunctionType::new([], []), |_args| {
+    ///     Box::pin(async {
+    ///         wasmer::yield_now().await;
+    ///         Ok(vec![])
+    ///     })
+    /// });
+    /// let import_object = imports! { "host" => { "pause" => pause } };
+    /// let instance = Instance::new(&mut store, &module, &import_object).unwrap();
+    /// let run = instance.exports.get_function("run").unwrap();
+    ///
+    /// let suspended = match run.call_resumable(&mut store, &[]).unwrap() {
+    ///     CallState::Suspended(call) => call,
+    ///     CallState::Finished(_) => unreachable!(),
+    /// };
+    /// match suspended.resume(&mut store).unwrap() {
+    ///     CallState::Finished(results) => assert_eq!(results.to_vec(), vec![Value::I32(42)]),
+    ///     CallState::Suspended(_) => unreachable!(),
+    /// }
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn call_resumable(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: &[Value],
+    ) -> Result<CallState, RuntimeError> {
+        self.0.call_resumable(store, params)
+    }
+
     #[doc(hidden)]
     #[allow(missing_docs)]
     pub fn call_raw(
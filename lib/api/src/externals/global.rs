@@ -149,6 +149,43 @@ impl Global {
         self.0.set(store, val)
     }
 
+    /// Freezes the global, so that it can't be changed anymore: writes
+    /// from WebAssembly code trap with [`TrapCode::FrozenGlobal`], and
+    /// [`Global::set`] returns an error.
+    ///
+    /// WebAssembly code only checks whether the globals it writes are frozen
+    /// when it was compiled with [`CompilerConfig::enable_global_freezing`],
+    /// writes from code compiled without it still succeed.
+    ///
+    /// This is meant for configuration values injected by the embedder in
+    /// mutable globals. A frozen global can't be unfrozen.
+    ///
+    /// [`TrapCode::FrozenGlobal`]: crate::TrapCode::FrozenGlobal
+    /// [`CompilerConfig::enable_global_freezing`]: crate::CompilerConfig::enable_global_freezing
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::{Global, Store, Value};
+    /// # let mut store = Store::default();
+    /// #
+    /// let g = Global::new_mut(&mut store, Value::I32(1));
+    /// g.freeze(&mut store);
+    ///
+    /// assert!(g.is_frozen(&store));
+    /// assert!(g.set(&mut store, Value::I32(2)).is_err());
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn freeze(&self, store: &mut impl AsStoreMut) {
+        self.0.freeze(store)
+    }
+
+    /// Returns whether the global was frozen with [`Global::freeze`].
+    #[cfg(feature = "sys")]
+    pub fn is_frozen(&self, store: &impl AsStoreRef) -> bool {
+        self.0.is_frozen(store)
+    }
+
     pub(crate) fn from_vm_extern(store: &mut impl AsStoreMut, vm_extern: VMExternGlobal) -> Self {
         Self(global_impl::Global::from_vm_extern(store, vm_extern))
     }
@@ -5,6 +5,7 @@ use crate::jsc::externals::memory as memory_impl;
 #[cfg(feature = "sys")]
 use crate::sys::externals::memory as memory_impl;
 
+use super::memory_atomics::MemoryAtomics;
 use super::memory_view::MemoryView;
 use crate::exports::{ExportError, Exportable};
 use crate::store::{AsStoreMut, AsStoreRef};
@@ -137,6 +138,38 @@ impl Memory {
         Ok(())
     }
 
+    /// Makes the bytes of this memory in `range` read-only, or writable
+    /// again if `read_only` is false, to protect data such as code or
+    /// constants loaded into the memory from stray writes.
+    ///
+    /// The range must be aligned to the page size of the host, so ranges
+    /// of whole WebAssembly pages can always be protected. WebAssembly code
+    /// writing to a read-only range traps with
+    /// `TrapCode::HeapAccessOutOfBounds`. The host must not write to it
+    /// either: such writes aren't checked and crash the process. The
+    /// ranges stay read-only when the memory grows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::{Memory, MemoryType, Store, WASM_PAGE_SIZE};
+    /// # let mut store = Store::default();
+    /// #
+    /// let m = Memory::new(&mut store, MemoryType::new(2, None, false)).unwrap();
+    /// m.set_read_only(&mut store, 0..WASM_PAGE_SIZE as u64, true).unwrap();
+    /// // Misaligned ranges are rejected.
+    /// assert!(m.set_read_only(&mut store, 1..10, true).is_err());
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn set_read_only(
+        &self,
+        store: &mut impl AsStoreMut,
+        range: std::ops::Range<u64>,
+        read_only: bool,
+    ) -> Result<(), MemoryError> {
+        self.0.set_read_only(store, range, read_only)
+    }
+
     /// Attempts to duplicate this memory (if its clonable) in a new store
     /// (copied memory)
     pub fn copy_to_store(
@@ -200,6 +233,31 @@ impl Memory {
         self.0.as_shared(store)
     }
 
+    /// Get a [`MemoryAtomics`], to access the memory atomically and to
+    /// wait for and notify WebAssembly threads from the host.
+    ///
+    /// Only returns `Some(_)` if the memory is shared, and if the target
+    /// backend supports shared memory operations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::{Memory, MemoryType, Store};
+    /// # let mut store = Store::default();
+    /// #
+    /// let m = Memory::new(&mut store, MemoryType::new(1, Some(1), true)).unwrap();
+    /// let atomics = m.atomics(&store).unwrap();
+    ///
+    /// assert_eq!(atomics.fetch_add32(8, 2).unwrap(), 0);
+    /// assert_eq!(atomics.load32(8).unwrap(), 2);
+    /// // The value isn't 0, so this doesn't wait.
+    /// assert_eq!(atomics.wait32(8, 0, None).unwrap(), 1);
+    /// ```
+    pub fn atomics<'a>(&self, store: &'a impl AsStoreRef) -> Option<MemoryAtomics<'a>> {
+        let shared = self.as_shared(store)?;
+        Some(MemoryAtomics::new(self.view(store), shared))
+    }
+
     /// To `VMExtern`.
     pub(crate) fn to_vm_extern(&self) -> VMExtern {
         self.0.to_vm_extern()
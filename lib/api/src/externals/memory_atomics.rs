@@ -0,0 +1,139 @@
+use super::memory::{MemoryLocation, SharedMemory};
+use super::memory_view::MemoryView;
+use crate::AtomicsError;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Atomic operations on a shared `memory`, for host threads coordinating
+/// with WebAssembly threads.
+///
+/// The operations have the same semantics as the corresponding
+/// instructions of the WebAssembly threads proposal: they are sequentially
+/// consistent, and the accessed addresses must be naturally aligned.
+///
+/// Like a [`MemoryView`], this must not be used anymore after the memory is
+/// grown. It is created using the [`Memory::atomics`](crate::Memory::atomics)
+/// method.
+#[derive(Debug)]
+pub struct MemoryAtomics<'a> {
+    view: MemoryView<'a>,
+    shared: SharedMemory,
+}
+
+macro_rules! atomic_ops {
+    ($atomic:ident, $ty:ty, $load:ident, $store:ident, $swap:ident, $fetch_add:ident, $compare_exchange:ident, $wait:ident) => {
+        #[doc = concat!("Atomically loads the `", stringify!($ty), "` at `offset`.")]
+        pub fn $load(&self, offset: u64) -> Result<$ty, AtomicsError> {
+            Ok(self.atomic::<$atomic>(offset)?.load(Ordering::SeqCst))
+        }
+
+        #[doc = concat!("Atomically stores a `", stringify!($ty), "` at `offset`.")]
+        pub fn $store(&self, offset: u64, value: $ty) -> Result<(), AtomicsError> {
+            self.atomic::<$atomic>(offset)?.store(value, Ordering::SeqCst);
+            Ok(())
+        }
+
+        #[doc = concat!("Atomically replaces the `", stringify!($ty), "` at `offset`, returning the previous value.")]
+        pub fn $swap(&self, offset: u64, value: $ty) -> Result<$ty, AtomicsError> {
+            Ok(self.atomic::<$atomic>(offset)?.swap(value, Ordering::SeqCst))
+        }
+
+        #[doc = concat!("Atomically adds to the `", stringify!($ty), "` at `offset`, wrapping around on overflow, and returns the previous value.")]
+        pub fn $fetch_add(&self, offset: u64, value: $ty) -> Result<$ty, AtomicsError> {
+            Ok(self.atomic::<$atomic>(offset)?.fetch_add(value, Ordering::SeqCst))
+        }
+
+        #[doc = concat!("Atomically replaces the `", stringify!($ty), "` at `offset` if it is equal to `expected`, returning the previous value.")]
+        pub fn $compare_exchange(
+            &self,
+            offset: u64,
+            expected: $ty,
+            replacement: $ty,
+        ) -> Result<$ty, AtomicsError> {
+            let atomic = self.atomic::<$atomic>(offset)?;
+            Ok(
+                match atomic.compare_exchange(
+                    expected,
+                    replacement,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(previous) | Err(previous) => previous,
+                },
+            )
+        }
+
+        #[doc = concat!("Waits for a notification on `offset`, if the `", stringify!($ty), "` stored there is equal to `expected`.")]
+        ///
+        /// Returns 0 if the thread was woken up by a notification, 1 if the
+        /// value wasn't equal to `expected`, and 2 if the timeout elapsed.
+        pub fn $wait(
+            &self,
+            offset: u64,
+            expected: $ty,
+            timeout: Option<Duration>,
+        ) -> Result<u32, AtomicsError> {
+            if self.$load(offset)? != expected {
+                return Ok(1);
+            }
+            self.shared.wait(Self::location(offset)?, timeout)
+        }
+    };
+}
+
+impl<'a> MemoryAtomics<'a> {
+    pub(crate) fn new(view: MemoryView<'a>, shared: SharedMemory) -> Self {
+        Self { view, shared }
+    }
+
+    atomic_ops!(
+        AtomicU32,
+        u32,
+        load32,
+        store32,
+        swap32,
+        fetch_add32,
+        compare_exchange32,
+        wait32
+    );
+    atomic_ops!(
+        AtomicU64,
+        u64,
+        load64,
+        store64,
+        swap64,
+        fetch_add64,
+        compare_exchange64,
+        wait64
+    );
+
+    /// Wakes up to `count` threads waiting on `offset`, returning the
+    /// number of threads woken up.
+    pub fn notify(&self, offset: u64, count: u32) -> Result<u32, AtomicsError> {
+        self.atomic::<AtomicU32>(offset)?;
+        self.shared.notify(Self::location(offset)?, count)
+    }
+
+    /// Returns the atomic at `offset`, checking that it is in bounds and
+    /// naturally aligned.
+    fn atomic<T>(&self, offset: u64) -> Result<&T, AtomicsError> {
+        let size = std::mem::size_of::<T>() as u64;
+        if offset % size != 0 {
+            return Err(AtomicsError::Unaligned);
+        }
+        match offset.checked_add(size) {
+            Some(end) if end <= self.view.data_size() => {}
+            _ => return Err(AtomicsError::OutOfBounds),
+        }
+        // The memory is shared, so it's only ever accessed through atomics
+        // or racy WebAssembly accesses, and the atomic is aligned and in
+        // bounds.
+        Ok(unsafe { &*(self.view.data_ptr().add(offset as usize) as *const T) })
+    }
+
+    fn location(offset: u64) -> Result<MemoryLocation, AtomicsError> {
+        u32::try_from(offset)
+            .map(MemoryLocation::new_32)
+            .map_err(|_| AtomicsError::OutOfBounds)
+    }
+}
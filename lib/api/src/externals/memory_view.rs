@@ -3,7 +3,8 @@ use crate::store::AsStoreRef;
 use crate::MemoryAccessError;
 use std::mem::MaybeUninit;
 use std::ops::Range;
-use wasmer_types::Pages;
+use std::slice;
+use wasmer_types::{Pages, ValueType};
 
 #[cfg(feature = "js")]
 use crate::js::externals::memory_view as memory_view_impl;
@@ -144,6 +145,92 @@ impl<'a> MemoryView<'a> {
         self.0.write_u8(offset, val)
     }
 
+    /// Copies values from the memory at the given offset into `buf`, with a
+    /// single bounds check and bulk copy.
+    ///
+    /// This is a faster alternative to reading the values one by one through
+    /// a [`WasmSlice`](crate::WasmSlice). If the values don't all fit in the
+    /// memory, a `MemoryAccessError` is returned and `buf` is left unchanged.
+    ///
+    /// This method is guaranteed to be safe (from the host side) in the face of
+    /// concurrent writes.
+    pub fn copy_to_slice<T: ValueType>(
+        &self,
+        offset: u64,
+        buf: &mut [T],
+    ) -> Result<(), MemoryAccessError> {
+        let size = std::mem::size_of_val(buf);
+        let bytes =
+            unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut MaybeUninit<u8>, size) };
+        self.0.read_uninit(offset, bytes)?;
+        Ok(())
+    }
+
+    /// Copies the values of `data` to the memory at the given offset, with a
+    /// single bounds check and bulk copy.
+    ///
+    /// This is a faster alternative to writing the values one by one through
+    /// a [`WasmSlice`](crate::WasmSlice). If the values don't all fit in the
+    /// memory, a `MemoryAccessError` is returned and the memory is left
+    /// unchanged.
+    ///
+    /// This method is guaranteed to be safe (from the host side) in the face of
+    /// concurrent reads/writes.
+    pub fn copy_from_slice<T: ValueType>(
+        &self,
+        offset: u64,
+        data: &[T],
+    ) -> Result<(), MemoryAccessError> {
+        let size = std::mem::size_of_val(data);
+        let bytes = unsafe { slice::from_raw_parts(data.as_ptr() as *const u8, size) };
+        self.0.write(offset, bytes)
+    }
+
+    /// Copies the bytes of the `src` range to `dest` within the memory, like
+    /// the `memory.copy` instruction.
+    ///
+    /// The ranges may overlap. If either of them is out of the bounds of the
+    /// memory, a `MemoryAccessError` is returned and nothing is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::{Memory, MemoryType, Store};
+    /// # let mut store = Store::default();
+    /// let m = Memory::new(&mut store, MemoryType::new(1, None, false)).unwrap();
+    /// let view = m.view(&store);
+    /// view.write(0, b"hello").unwrap();
+    /// view.copy_within(0..5, 2).unwrap();
+    ///
+    /// assert_eq!(view.copy_range_to_vec(0..7).unwrap(), b"hehello");
+    /// ```
+    pub fn copy_within(&self, src: Range<u64>, dest: u64) -> Result<(), MemoryAccessError> {
+        let len = src
+            .end
+            .checked_sub(src.start)
+            .ok_or(MemoryAccessError::HeapOutOfBounds)?;
+        self.0.copy_within(src.start, dest, len)
+    }
+
+    /// Returns an iterator over the bytes of the `range` of the memory, in
+    /// chunks of `chunk_size` bytes.
+    ///
+    /// The last chunk is shorter if the length of the range isn't a multiple
+    /// of `chunk_size`. The iterator yields a `MemoryAccessError` and stops
+    /// if the range is out of the bounds of the memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn chunks(&self, range: Range<u64>, chunk_size: usize) -> MemoryChunks<'_> {
+        assert_ne!(chunk_size, 0, "chunk size must be non-zero");
+        MemoryChunks {
+            view: self,
+            range,
+            chunk_size,
+        }
+    }
+
     /// Copies the memory and returns it as a vector of bytes
     pub fn copy_to_vec(&self) -> Result<Vec<u8>, MemoryAccessError> {
         self.copy_range_to_vec(0..self.data_size())
@@ -151,17 +238,17 @@ impl<'a> MemoryView<'a> {
 
     /// Copies a range of the memory and returns it as a vector of bytes
     pub fn copy_range_to_vec(&self, range: Range<u64>) -> Result<Vec<u8>, MemoryAccessError> {
-        let mut new_memory = Vec::new();
-        let mut offset = range.start;
         let end = range.end.min(self.data_size());
-        let mut chunk = [0u8; 40960];
-        while offset < end {
-            let remaining = end - offset;
-            let sublen = remaining.min(chunk.len() as u64) as usize;
-            self.read(offset, &mut chunk[..sublen])?;
-            new_memory.extend_from_slice(&chunk[..sublen]);
-            offset += sublen as u64;
+        let len = end.saturating_sub(range.start);
+        if len == 0 {
+            return Ok(Vec::new());
         }
+        let mut new_memory = Vec::with_capacity(len as usize);
+        self.read_uninit(
+            range.start,
+            &mut new_memory.spare_capacity_mut()[..len as usize],
+        )?;
+        unsafe { new_memory.set_len(len as usize) };
         Ok(new_memory)
     }
 
@@ -181,3 +268,35 @@ impl<'a> MemoryView<'a> {
         Ok(())
     }
 }
+
+/// An iterator over the bytes of a range of a [`MemoryView`], in chunks.
+///
+/// It is created using the [`MemoryView::chunks`] method.
+#[derive(Debug)]
+pub struct MemoryChunks<'a> {
+    view: &'a MemoryView<'a>,
+    range: Range<u64>,
+    chunk_size: usize,
+}
+
+impl<'a> Iterator for MemoryChunks<'a> {
+    type Item = Result<Vec<u8>, MemoryAccessError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.start >= self.range.end {
+            return None;
+        }
+        let len = (self.range.end - self.range.start).min(self.chunk_size as u64);
+        let mut chunk = vec![0; len as usize];
+        match self.view.read(self.range.start, &mut chunk) {
+            Ok(()) => {
+                self.range.start += len;
+                Some(Ok(chunk))
+            }
+            Err(err) => {
+                self.range.start = self.range.end;
+                Some(Err(err))
+            }
+        }
+    }
+}
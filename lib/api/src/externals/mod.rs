@@ -1,13 +1,17 @@
 pub(crate) mod function;
 mod global;
 pub(crate) mod memory;
+mod memory_atomics;
 mod memory_view;
 mod table;
 
+#[cfg(feature = "sys")]
+pub use self::function::{yield_now, CallState, HostFuture, ResumableCall, YieldNow};
 pub use self::function::{Function, HostFunction};
 pub use self::global::Global;
 pub use self::memory::{Memory, MemoryLocation, SharedMemory};
-pub use self::memory_view::MemoryView;
+pub use self::memory_atomics::MemoryAtomics;
+pub use self::memory_view::{MemoryChunks, MemoryView};
 pub use self::table::Table;
 
 use crate::exports::{ExportError, Exportable};
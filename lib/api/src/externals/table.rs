@@ -82,6 +82,54 @@ impl Table {
         self.0.grow(store, delta, init)
     }
 
+    /// Sets the `len` elements of the `Table` starting at `index` to `val`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds of the table, or if
+    /// `val` doesn't have the type of the table elements.
+    pub fn fill(
+        &self,
+        store: &mut impl AsStoreMut,
+        index: u32,
+        val: Value,
+        len: u32,
+    ) -> Result<(), RuntimeError> {
+        self.0.fill(store, index, val, len)
+    }
+
+    /// Sets the elements of the `Table` starting at `index` to `values`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds of the table, or if
+    /// one of the `values` doesn't have the type of the table elements.
+    pub fn copy_from_slice(
+        &self,
+        store: &mut impl AsStoreMut,
+        index: u32,
+        values: &[Value],
+    ) -> Result<(), RuntimeError> {
+        self.0.copy_from_slice(store, index, values)
+    }
+
+    /// Returns an iterator over the elements of the `Table`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::{Store, Table, TableType, Type, Value};
+    /// # let mut store = Store::default();
+    /// let ty = TableType::new(Type::FuncRef, 2, None);
+    /// let table = Table::new(&mut store, ty, Value::FuncRef(None)).unwrap();
+    ///
+    /// assert!(table.iter(&mut store).all(|element| element.unwrap_funcref().is_none()));
+    /// ```
+    pub fn iter<'a, S: AsStoreMut>(&'a self, store: &'a mut S) -> impl Iterator<Item = Value> + 'a {
+        let size = self.size(store);
+        (0..size).map_while(move |index| self.get(store, index))
+    }
+
     /// Copies the `len` elements of `src_table` starting at `src_index`
     /// to the destination table `dst_table` at index `dst_index`.
     ///
@@ -1,9 +1,20 @@
-use std::{any::Any, fmt::Debug, marker::PhantomData};
+use std::{
+    any::Any,
+    fmt::Debug,
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+};
 
 use crate::vm::VMFunctionEnvironment;
 
 use crate::store::{AsStoreMut, AsStoreRef, StoreHandle, StoreMut, StoreObjects, StoreRef};
 
+const BORROWED: &str = "the function environment is borrowed by `with_data_and_store`";
+
+/// Stands in for the data of a function environment while it's borrowed by
+/// [`FunctionEnvMut::with_data_and_store`].
+struct Borrowed;
+
 #[derive(Debug)]
 #[repr(transparent)]
 /// An opaque reference to a function environment.
@@ -37,7 +48,7 @@ impl<T> FunctionEnv<T> {
             .get(store.as_store_ref().objects())
             .as_ref()
             .downcast_ref::<T>()
-            .unwrap()
+            .expect(BORROWED)
     }
 
     #[allow(dead_code)] // This function is only used in js
@@ -57,7 +68,7 @@ impl<T> FunctionEnv<T> {
             .get_mut(store.objects_mut())
             .as_mut()
             .downcast_mut::<T>()
-            .unwrap()
+            .expect(BORROWED)
     }
 
     /// Convert it into a `FunctionEnvMut`
@@ -145,6 +156,28 @@ impl<T: Send + 'static> FunctionEnvMut<'_, T> {
         let data = unsafe { &mut *data };
         (data, self.store_mut.as_store_mut())
     }
+
+    /// Calls `f` with mutable references to both the host state and the
+    /// attached Store, for instance to call exports kept in the host state.
+    ///
+    /// Unlike with [`data_and_store_mut`](Self::data_and_store_mut), the host
+    /// state is moved out of the Store while `f` runs, so if the called
+    /// WebAssembly code calls back into a host function using the same
+    /// environment, accessing the host state from there panics rather than
+    /// aliasing the reference given to `f`.
+    pub fn with_data_and_store<R>(&mut self, f: impl FnOnce(&mut T, &mut StoreMut<'_>) -> R) -> R {
+        let handle = &self.func_env.handle;
+        let mut contents = handle
+            .get_mut(self.store_mut.objects_mut())
+            .replace(Box::new(Borrowed));
+        let data = contents.downcast_mut::<T>().expect(BORROWED);
+        let mut store = self.store_mut.as_store_mut();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(data, &mut store)));
+        handle
+            .get_mut(self.store_mut.objects_mut())
+            .replace(contents);
+        result.unwrap_or_else(|panic| panic::resume_unwind(panic))
+    }
 }
 
 impl<T> AsStoreRef for FunctionEnvMut<'_, T> {
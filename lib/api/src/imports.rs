@@ -1,10 +1,22 @@
 //! The import module contains the implementation data structures and helper functions used to
 //! manipulate and access a wasm module's imports including memories, tables, globals, and
 //! functions.
+#[cfg(feature = "sys")]
+use crate::store::{AsStoreMut, StoreMut};
 use crate::{Exports, Extern, LinkError, Module};
 use std::collections::HashMap;
 use std::fmt;
+#[cfg(feature = "sys")]
+use std::sync::Arc;
 use wasmer_types::ImportError;
+#[cfg(feature = "sys")]
+use wasmer_types::ImportType;
+
+/// A resolver for the imports which aren't defined in an [`Imports`], see
+/// [`Imports::set_resolver`].
+#[cfg(feature = "sys")]
+pub type ImportResolver =
+    Arc<dyn Fn(&mut StoreMut<'_>, &ImportType) -> Option<Extern> + Send + Sync>;
 
 /// All of the import data used when instantiating.
 ///
@@ -36,6 +48,8 @@ use wasmer_types::ImportError;
 #[derive(Clone, Default)]
 pub struct Imports {
     pub(crate) map: HashMap<(String, String), Extern>,
+    #[cfg(feature = "sys")]
+    pub(crate) resolver: Option<ImportResolver>,
 }
 
 impl Imports {
@@ -120,6 +134,42 @@ impl Imports {
             .insert((ns.to_string(), name.to_string()), val.into());
     }
 
+    /// Sets a resolver, called when instantiating a module for each import
+    /// which isn't defined in this `Imports`, to provide it on demand.
+    ///
+    /// The resolver gets the type of the import, including its namespace
+    /// and name, and returns `None` if it can't provide it. The externs it
+    /// returns aren't added to this `Imports`.
+    ///
+    /// # Usage
+    /// ```
+    /// # use wasmer::{imports, Extern, ExternType, Function, Imports, Instance, Module, Store};
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module
+    ///     (import "env" "log_i32" (func (param i32)))
+    ///     (import "env" "log_i64" (func (param i64))))"#)?;
+    ///
+    /// // Stub out every function the module imports from `env`.
+    /// let mut import_object = Imports::new();
+    /// import_object.set_resolver(|store, import| match import.ty() {
+    ///     ExternType::Function(ty) if import.module() == "env" => {
+    ///         Some(Function::new(store, ty, |_| Ok(vec![])).into())
+    ///     }
+    ///     _ => None,
+    /// });
+    /// let instance = Instance::new(&mut store, &module, &import_object)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn set_resolver<F>(&mut self, resolver: F)
+    where
+        F: Fn(&mut StoreMut<'_>, &ImportType) -> Option<Extern> + Send + Sync + 'static,
+    {
+        self.resolver = Some(Arc::new(resolver));
+    }
+
     /// Returns the contents of a namespace as an `Exports`.
     ///
     /// Returns `None` if the namespace doesn't exist.
@@ -140,26 +190,56 @@ impl Imports {
     /// Resolve and return a vector of imports in the order they are defined in the `module`'s source code.
     ///
     /// This means the returned `Vec<Extern>` might be a subset of the imports contained in `self`.
+    /// If imports are missing, the returned error lists all of them. The
+    /// resolver set with `set_resolver` isn't used, as it needs a store.
     #[allow(clippy::result_large_err)]
     pub fn imports_for_module(&self, module: &Module) -> Result<Vec<Extern>, LinkError> {
         let mut ret = vec![];
-        for import in module.imports() {
-            if let Some(imp) = self
-                .map
+        let mut missing = vec![];
+        let externs = module.imports().map(|import| {
+            self.map
                 .get(&(import.module().to_string(), import.name().to_string()))
-            {
-                ret.push(imp.clone());
-            } else {
-                return Err(LinkError::Import(
+                .cloned()
+        });
+        for (import, imp) in module.imports().zip(externs) {
+            match imp {
+                Some(imp) => ret.push(imp),
+                None => missing.push((
                     import.module().to_string(),
                     import.name().to_string(),
                     ImportError::UnknownImport(import.ty().clone()),
-                ));
+                )),
             }
         }
+        if !missing.is_empty() {
+            return Err(LinkError::from_import_errors(missing));
+        }
         Ok(ret)
     }
 
+    /// Returns the externs for the imports of the `module`, in order, or
+    /// `None` for the missing ones. The imports which aren't defined are
+    /// given by the resolver, if any.
+    #[cfg(feature = "sys")]
+    pub(crate) fn externs_for_module(
+        &self,
+        store: &mut impl AsStoreMut,
+        module: &Module,
+    ) -> Vec<Option<Extern>> {
+        module
+            .imports()
+            .map(|import| {
+                self.map
+                    .get(&(import.module().to_string(), import.name().to_string()))
+                    .cloned()
+                    .or_else(|| {
+                        let resolver = self.resolver.as_ref()?;
+                        resolver(&mut store.as_store_mut(), &import)
+                    })
+            })
+            .collect()
+    }
+
     /// Iterates through all the imports in this structure
     pub fn iter(&self) -> ImportsIterator<'_> {
         ImportsIterator::new(self)
@@ -259,6 +339,25 @@ impl fmt::Debug for Imports {
 ///     n
 /// }
 /// ```
+///
+/// The imports which aren't listed can be provided on demand by a resolver,
+/// see [`Imports::set_resolver`], given last after `@resolve`:
+///
+/// ```
+/// # use wasmer::{Extern, ExternType, Global, Store, Value};
+/// # let mut store = Store::default();
+/// use wasmer::imports;
+///
+/// let import_object = imports! {
+///     "env" => {
+///         "answer" => Global::new(&mut store, Value::I32(42)),
+///     },
+///     @resolve |store, import| match import.ty() {
+///         ExternType::Global(_) => Some(Global::new(store, Value::I32(0)).into()),
+///         _ => None,
+///     }
+/// };
+/// ```
 #[macro_export]
 macro_rules! imports {
     ( $( $ns_name:expr => $ns:tt ),* $(,)? ) => {
@@ -275,6 +374,13 @@ macro_rules! imports {
             import_object
         }
     };
+    ( $( $ns_name:expr => $ns:tt , )* @resolve $resolver:expr $(,)? ) => {
+        {
+            let mut import_object = $crate::imports! { $( $ns_name => $ns ),* };
+            import_object.set_resolver($resolver);
+            import_object
+        }
+    };
 }
 
 #[macro_export]
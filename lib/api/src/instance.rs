@@ -1,10 +1,18 @@
 use crate::exports::Exports;
 use crate::module::Module;
-use crate::{Extern, InstantiationError};
+use crate::{Extern, InstantiationError, Value};
 use std::fmt;
 
 use crate::imports::Imports;
 use crate::store::AsStoreMut;
+#[cfg(feature = "sys")]
+use crate::store::AsStoreRef;
+#[cfg(feature = "sys")]
+use crate::sys::{Coredump, InstanceSnapshot, MemoryUsage, ReloadError, SnapshotError};
+#[cfg(feature = "sys")]
+use crate::RuntimeError;
+#[cfg(feature = "sys")]
+use wasmer_types::Pages;
 
 #[cfg(feature = "js")]
 use crate::js::instance as instance_imp;
@@ -67,11 +75,14 @@ impl Instance {
         imports: &Imports,
     ) -> Result<Self, InstantiationError> {
         let (_inner, exports) = instance_imp::Instance::new(store, module, imports)?;
-        Ok(Self {
+        let instance = Self {
             _inner,
             module: module.clone(),
             exports,
-        })
+        };
+        #[cfg(feature = "sys")]
+        store.as_store_mut().notify_instance_created(&instance);
+        Ok(instance)
     }
 
     /// Creates a new `Instance` from a WebAssembly [`Module`] and a
@@ -91,17 +102,237 @@ impl Instance {
         externs: &[Extern],
     ) -> Result<Self, InstantiationError> {
         let (_inner, exports) = instance_imp::Instance::new_by_index(store, module, externs)?;
-        Ok(Self {
+        let instance = Self {
             _inner,
             module: module.clone(),
             exports,
-        })
+        };
+        #[cfg(feature = "sys")]
+        store.as_store_mut().notify_instance_created(&instance);
+        Ok(instance)
     }
 
     /// Gets the [`Module`] associated with this instance.
     pub fn module(&self) -> &Module {
         &self.module
     }
+
+    /// Checks whether this `Instance` can be used with the given store.
+    #[cfg(feature = "sys")]
+    pub fn is_from_store(&self, store: &impl AsStoreRef) -> bool {
+        self._inner.is_from_store(store)
+    }
+
+    /// Returns the names and current values of the globals exported by this
+    /// instance, in the order of the exports.
+    ///
+    /// ```
+    /// # use wasmer::{imports, Instance, Module, Store, Value};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module
+    ///     (global (export "version") i32 (i32.const 3))
+    ///     (global (export "count") (mut i64) (i64.const 0)))"#)?;
+    /// let instance = Instance::new(&mut store, &module, &imports! {})?;
+    /// assert_eq!(
+    ///     instance.globals(&mut store),
+    ///     [
+    ///         ("version".to_string(), Value::I32(3)),
+    ///         ("count".to_string(), Value::I64(0)),
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn globals(&self, store: &mut impl AsStoreMut) -> Vec<(String, Value)> {
+        self.exports
+            .iter()
+            .globals()
+            .map(|(name, global)| (name.clone(), global.get(store)))
+            .collect()
+    }
+
+    #[cfg(feature = "sys")]
+    /// Returns the current sizes of the memories defined by this instance,
+    /// in the order of their index. Imported memories aren't included.
+    ///
+    /// Memories never shrink, so these are also the largest sizes the
+    /// memories have reached so far.
+    ///
+    /// ```
+    /// # use wasmer::{imports, Instance, Module, Pages, Store, TypedFunction};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module
+    ///     (memory 1)
+    ///     (func (export "grow") (drop (memory.grow (i32.const 2)))))"#)?;
+    /// let instance = Instance::new(&mut store, &module, &imports! {})?;
+    /// let grow: TypedFunction<(), ()> = instance.exports.get_typed_function(&store, "grow")?;
+    /// grow.call(&mut store)?;
+    /// assert_eq!(instance.memory_sizes(&store), [Pages(3)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn memory_sizes(&self, store: &impl AsStoreRef) -> Vec<Pages> {
+        self._inner.memory_sizes(store)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Returns the current sizes, in elements, of the tables defined by
+    /// this instance, in the order of their index. Imported tables aren't
+    /// included.
+    pub fn table_sizes(&self, store: &impl AsStoreRef) -> Vec<u32> {
+        self._inner.table_sizes(store)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Measures the memory used by this instance: the sizes of its memories
+    /// and tables, imported or defined, with the largest sizes reached by
+    /// the memories, the size of the data segments of its module, and the
+    /// usage of its stack when the module exports `__stack_pointer`.
+    ///
+    /// ```
+    /// # use wasmer::{imports, Instance, Module, Pages, Store, TypedFunction};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module
+    ///     (memory 1)
+    ///     (data (i32.const 0) "hello")
+    ///     (func (export "grow") (drop (memory.grow (i32.const 2)))))"#)?;
+    /// let instance = Instance::new(&mut store, &module, &imports! {})?;
+    /// let grow: TypedFunction<(), ()> = instance.exports.get_typed_function(&store, "grow")?;
+    /// grow.call(&mut store)?;
+    /// let usage = instance.memory_usage(&store);
+    /// assert_eq!(usage.memories[0].minimum, Pages(1));
+    /// assert_eq!(usage.memories[0].peak, Pages(3));
+    /// assert_eq!(usage.data_segments_size, 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn memory_usage(&self, store: &impl AsStoreRef) -> MemoryUsage {
+        self._inner.memory_usage(store, &self.module)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Captures a coredump of this instance, with the call stack of
+    /// `error`: the contents of its memories and the values of its globals,
+    /// imported or defined.
+    ///
+    /// The state is captured as it is now, so this should be called right
+    /// after the trap. To capture it when the trap happens instead, see
+    /// [`NativeEngineExt::set_coredump_on_trap`](crate::NativeEngineExt::set_coredump_on_trap).
+    pub fn coredump(&self, store: &impl AsStoreRef, error: &RuntimeError) -> Coredump {
+        self._inner.coredump(store, error)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Takes a snapshot of the state of this instance: the contents of the
+    /// memories and tables it defines, and the values of its mutable
+    /// globals.
+    ///
+    /// Returns an error if the state refers to values that can't be
+    /// captured: non-null `externref`s, or functions which aren't functions
+    /// of this instance.
+    ///
+    /// ```
+    /// # use wasmer::{imports, Instance, Module, Store, TypedFunction};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module
+    ///     (global $count (mut i32) (i32.const 0))
+    ///     (func (export "next") (result i32)
+    ///         (global.set $count (i32.add (global.get $count) (i32.const 1)))
+    ///         (global.get $count)))"#)?;
+    /// let instance = Instance::new(&mut store, &module, &imports! {})?;
+    /// let next: TypedFunction<(), i32> = instance.exports.get_typed_function(&store, "next")?;
+    /// next.call(&mut store)?;
+    /// let snapshot = instance.snapshot(&store)?;
+    ///
+    /// // Clone the warmed-up instance.
+    /// let clone = Instance::new(&mut store, &module, &imports! {})?;
+    /// clone.restore(&mut store, &snapshot)?;
+    /// let next: TypedFunction<(), i32> = clone.exports.get_typed_function(&store, "next")?;
+    /// assert_eq!(next.call(&mut store)?, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self, store: &impl AsStoreRef) -> Result<InstanceSnapshot, SnapshotError> {
+        self._inner.snapshot(store)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Restores a snapshot taken with [`Instance::snapshot`] into this
+    /// instance, which must be an instance of the same module.
+    ///
+    /// The memories and tables are grown to the size they had in the
+    /// snapshot if needed, so they must not be larger than in the snapshot.
+    /// The snapshot is checked against the instance before restoring it,
+    /// but if growing a memory or a table fails, the instance may be left
+    /// partially restored.
+    pub fn restore(
+        &self,
+        store: &mut impl AsStoreMut,
+        snapshot: &InstanceSnapshot,
+    ) -> Result<(), SnapshotError> {
+        self._inner.restore(store, snapshot)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Hot reloads this instance: instantiates a new version of its module,
+    /// and carries the memories and mutable globals defined by this instance
+    /// over to the new instance, which is returned.
+    ///
+    /// The new module must define the same number of memories and mutable
+    /// globals, with the same types, and its memories must not start larger
+    /// than the current ones. Its tables are initialized by its own element
+    /// segments, so that indirect calls go to the new code. Functions
+    /// referenced from mutable globals are re-linked to the function the new
+    /// module imports or exports with the same name, or replaced by null
+    /// references if there is none.
+    ///
+    /// The `start` function of the new module runs before the state is
+    /// carried over. This instance is left untouched, and its exports keep
+    /// referring to its own memories and globals, so the exports of the new
+    /// instance should be used from then on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmer::{imports, Instance, Module, Store, TypedFunction};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let v1 = Module::new(&store, r#"(module
+    ///     (global $counter (mut i32) (i32.const 0))
+    ///     (func (export "next") (result i32)
+    ///         (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+    ///         (global.get $counter)))"#)?;
+    /// let instance = Instance::new(&mut store, &v1, &imports! {})?;
+    /// let next: TypedFunction<(), i32> = instance.exports.get_typed_function(&store, "next")?;
+    /// assert_eq!(next.call(&mut store)?, 1);
+    ///
+    /// // Count by ten from now on, without losing the counter.
+    /// let v2 = Module::new(&store, r#"(module
+    ///     (global $counter (mut i32) (i32.const 0))
+    ///     (func (export "next") (result i32)
+    ///         (global.set $counter (i32.add (global.get $counter) (i32.const 10)))
+    ///         (global.get $counter)))"#)?;
+    /// let instance = instance.reload(&mut store, &v2, &imports! {})?;
+    /// let next: TypedFunction<(), i32> = instance.exports.get_typed_function(&store, "next")?;
+    /// assert_eq!(next.call(&mut store)?, 11);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn reload(
+        &self,
+        store: &mut impl AsStoreMut,
+        module: &Module,
+        imports: &Imports,
+    ) -> Result<Self, ReloadError> {
+        let instance = Self::new(store, module, imports)?;
+        self._inner.transfer_state(store, &instance._inner)?;
+        Ok(instance)
+    }
 }
 
 impl fmt::Debug for Instance {
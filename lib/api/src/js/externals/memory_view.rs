@@ -244,6 +244,28 @@ impl<'a> MemoryView<'a> {
         Ok(())
     }
 
+    /// Copies `src` to `dest` within the memory.
+    pub fn copy_within(&self, src: u64, dest: u64, len: u64) -> Result<(), MemoryAccessError> {
+        let src_end = src.checked_add(len).ok_or(MemoryAccessError::Overflow)?;
+        let dest_end = dest.checked_add(len).ok_or(MemoryAccessError::Overflow)?;
+        if src_end.max(dest_end) > self.size {
+            tracing::warn!(
+                "attempted to copy ({} bytes) beyond the bounds of the memory view ({} > {})",
+                len,
+                src_end.max(dest_end),
+                self.size
+            );
+            Err(MemoryAccessError::HeapOutOfBounds)?;
+        }
+        let src: i32 = src.try_into().map_err(|_| MemoryAccessError::Overflow)?;
+        let src_end: i32 = src_end
+            .try_into()
+            .map_err(|_| MemoryAccessError::Overflow)?;
+        let dest: i32 = dest.try_into().map_err(|_| MemoryAccessError::Overflow)?;
+        self.view.copy_within(dest, src, src_end);
+        Ok(())
+    }
+
     /// Copies the memory and returns it as a vector of bytes
     #[allow(unused)]
     pub fn copy_to_vec(&self) -> Result<Vec<u8>, MemoryAccessError> {
@@ -240,6 +240,14 @@ impl VMFunctionEnvironment {
     pub fn as_mut(&mut self) -> &mut (dyn Any + Send + 'static) {
         &mut *self.contents
     }
+
+    /// Replaces the underlying value, returning the previous one.
+    pub fn replace(
+        &mut self,
+        contents: Box<dyn Any + Send + 'static>,
+    ) -> Box<dyn Any + Send + 'static> {
+        std::mem::replace(&mut self.contents, contents)
+    }
 }
 
 pub(crate) struct VMExternRef;
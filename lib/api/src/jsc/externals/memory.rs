@@ -296,6 +296,34 @@ impl<'a> MemoryBuffer<'a> {
         }
         Ok(())
     }
+
+    pub(crate) fn copy_within(
+        &self,
+        src: u64,
+        dest: u64,
+        len: u64,
+    ) -> Result<(), MemoryAccessError> {
+        let src_end = src.checked_add(len).ok_or(MemoryAccessError::Overflow)?;
+        let dest_end = dest.checked_add(len).ok_or(MemoryAccessError::Overflow)?;
+        if src_end.max(dest_end) > self.len.try_into().unwrap() {
+            warn!(
+                "attempted to copy ({} bytes) beyond the bounds of the memory view ({} > {})",
+                len,
+                src_end.max(dest_end),
+                self.len
+            );
+            return Err(MemoryAccessError::HeapOutOfBounds);
+        }
+        // Like the `memory.copy` instruction, the ranges may overlap.
+        unsafe {
+            std::ptr::copy(
+                self.base.add(src as usize),
+                self.base.add(dest as usize),
+                len as usize,
+            );
+        }
+        Ok(())
+    }
 }
 
 // We can't use a normal memcpy here because it has undefined behavior if the
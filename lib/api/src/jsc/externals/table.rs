@@ -137,6 +137,44 @@ impl Table {
         }
     }
 
+    pub fn fill(
+        &self,
+        store: &mut impl AsStoreMut,
+        index: u32,
+        val: Value,
+        len: u32,
+    ) -> Result<(), RuntimeError> {
+        if index
+            .checked_add(len)
+            .map_or(true, |end| end > self.size(store))
+        {
+            return Err(RuntimeError::new("table index out of bounds"));
+        }
+        for i in index..index + len {
+            self.set(store, i, val.clone())?;
+        }
+        Ok(())
+    }
+
+    pub fn copy_from_slice(
+        &self,
+        store: &mut impl AsStoreMut,
+        index: u32,
+        values: &[Value],
+    ) -> Result<(), RuntimeError> {
+        if u32::try_from(values.len())
+            .ok()
+            .and_then(|len| index.checked_add(len))
+            .map_or(true, |end| end > self.size(store))
+        {
+            return Err(RuntimeError::new("table index out of bounds"));
+        }
+        for (i, val) in (index..).zip(values) {
+            self.set(store, i, val.clone())?;
+        }
+        Ok(())
+    }
+
     pub fn copy(
         _store: &mut impl AsStoreMut,
         _dst_table: &Self,
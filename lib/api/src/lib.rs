@@ -485,8 +485,11 @@ mod jsc;
 #[cfg(feature = "jsc")]
 pub use jsc::*;
 
+#[cfg(feature = "sys")]
+pub use crate::externals::{yield_now, CallState, HostFuture, ResumableCall, YieldNow};
 pub use crate::externals::{
-    Extern, Function, Global, HostFunction, Memory, MemoryLocation, MemoryView, SharedMemory, Table,
+    Extern, Function, Global, HostFunction, Memory, MemoryAtomics, MemoryChunks, MemoryLocation,
+    MemoryView, SharedMemory, Table,
 };
 pub use access::WasmSliceAccess;
 pub use engine::{AsEngineRef, Engine, EngineRef};
@@ -494,18 +497,24 @@ pub use errors::{AtomicsError, InstantiationError, LinkError, RuntimeError};
 pub use exports::{ExportError, Exportable, Exports, ExportsIterator};
 pub use extern_ref::ExternRef;
 pub use function_env::{FunctionEnv, FunctionEnvMut};
+#[cfg(feature = "sys")]
+pub use imports::ImportResolver;
 pub use imports::Imports;
 pub use instance::Instance;
 pub use into_bytes::IntoBytes;
 pub use mem_access::{MemoryAccessError, WasmRef, WasmSlice, WasmSliceIter};
+#[cfg(feature = "sys")]
+pub use module::ElementSegment;
 pub use module::{IoCompileError, Module};
-pub use native_type::{FromToNativeWasmType, NativeWasmTypeInto, WasmTypeList};
+pub use native_type::{FromToNativeWasmType, IntoResult, NativeWasmTypeInto, WasmTypeList};
 pub use ptr::{Memory32, Memory64, MemorySize, WasmPtr, WasmPtr64};
+#[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+pub use store::InterruptHandle;
 pub use store::{
     AsStoreMut, AsStoreRef, OnCalledHandler, Store, StoreId, StoreMut, StoreObjects, StoreRef,
 };
 #[cfg(feature = "sys")]
-pub use store::{TrapHandlerFn, Tunables};
+pub use store::{CallHook, CallHookFn, TrapHandlerFn, Tunables};
 #[cfg(any(feature = "sys", feature = "jsc"))]
 pub use target_lexicon::{Architecture, CallingConvention, OperatingSystem, Triple, HOST};
 pub use typed_function::TypedFunction;
@@ -517,10 +526,10 @@ pub use wasmer_derive::ValueType;
 // TODO: OnCalledAction is needed for asyncify. It will be refactored with https://github.com/wasmerio/wasmer/issues/3451
 pub use wasmer_types::{
     is_wasm, Bytes, CompileError, CpuFeature, DeserializeError, ExportIndex, ExportType,
-    ExternType, FrameInfo, FunctionType, GlobalInit, GlobalType, ImportType, LocalFunctionIndex,
-    MemoryError, MemoryType, MiddlewareError, Mutability, OnCalledAction, Pages,
-    ParseCpuFeatureError, SerializeError, TableType, Target, Type, ValueType, WasmError,
-    WasmResult, WASM_MAX_PAGES, WASM_MIN_PAGES, WASM_PAGE_SIZE,
+    ExternType, FrameInfo, FunctionType, GlobalInit, GlobalType, ImportError, ImportType,
+    LocalFunctionIndex, MemoryError, MemoryType, MiddlewareError, Mutability, OnCalledAction,
+    Pages, ParseCpuFeatureError, SerializeError, SourceLocation, TableType, Target, TrapCode, Type,
+    ValueType, WasmError, WasmResult, WASM_MAX_PAGES, WASM_MIN_PAGES, WASM_PAGE_SIZE,
 };
 #[cfg(feature = "wat")]
 pub use wat::parse_bytes as wat2wasm;
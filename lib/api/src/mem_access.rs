@@ -189,6 +189,17 @@ impl<'a, T: ValueType> WasmSlice<'a, T> {
         WasmPtr::new(self.offset)
     }
 
+    /// Get a `WasmPtr` for this `WasmSlice`.
+    #[inline]
+    pub fn as_ptr<M: MemorySize>(self) -> WasmPtr<T, M> {
+        let offset: M::Offset = self
+            .offset
+            .try_into()
+            .map_err(|_| "invalid offset into memory")
+            .unwrap();
+        WasmPtr::<T, M>::new(offset)
+    }
+
     /// Get the number of elements in this slice.
     #[inline]
     pub fn len(self) -> u64 {
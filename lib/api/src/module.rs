@@ -9,7 +9,8 @@ use thiserror::Error;
 #[cfg(feature = "wat")]
 use wasmer_types::WasmError;
 use wasmer_types::{
-    CompileError, DeserializeError, ExportsIterator, ImportsIterator, ModuleInfo, SerializeError,
+    CompileError, DeserializeError, ExportsIterator, FrameInfo, ImportsIterator, ModuleInfo,
+    SerializeError,
 };
 use wasmer_types::{ExportType, ImportType};
 
@@ -33,6 +34,34 @@ pub enum IoCompileError {
     Compile(#[from] CompileError),
 }
 
+/// An element segment of a [`Module`], see [`Module::element_segments`].
+///
+/// The elements are indices of functions in the module's function index
+/// space, which starts with the imported functions, or `None` for null
+/// references.
+#[cfg(feature = "sys")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementSegment {
+    /// A segment copied into a table when the module is instantiated.
+    Active {
+        /// The index of the table the segment is copied into.
+        table_index: u32,
+        /// The index of the global whose value is added to `offset`, if any.
+        base: Option<u32>,
+        /// The index in the table of the first element.
+        offset: usize,
+        /// The elements of the segment.
+        elements: Box<[Option<u32>]>,
+    },
+    /// A segment copied into tables by `table.init` instructions.
+    Passive {
+        /// The index of the segment in the module.
+        index: u32,
+        /// The elements of the segment.
+        elements: Box<[Option<u32>]>,
+    },
+}
+
 /// A WebAssembly Module contains stateless WebAssembly
 /// code that has already been compiled and can be instantiated
 /// multiple times.
@@ -41,6 +70,14 @@ pub enum IoCompileError {
 ///
 /// Cloning a module is cheap: it does a shallow copy of the compiled
 /// contents rather than a deep copy.
+///
+/// ## Sharing a module
+///
+/// A module can be instantiated in any [`Store`](crate::Store) and it can
+/// be sent and shared across threads. Stores using the engine it was
+/// compiled with, or a clone of that engine, share its compiled code
+/// without copying it. The module can be loaded into another engine with
+/// [`Module::clone_into`].
 #[derive(Clone, PartialEq, Eq)]
 pub struct Module(pub(crate) module_imp::Module);
 
@@ -219,6 +256,21 @@ impl Module {
         Ok(())
     }
 
+    /// Serializes a module and encrypts it with AES-256-GCM, using the
+    /// current key of `provider`.
+    ///
+    /// The result can be loaded back with [`Module::deserialize_encrypted`].
+    /// The identifier of the key is stored in clear next to the encrypted
+    /// artifact, so the provider can rotate keys.
+    #[cfg(feature = "artifact-encryption")]
+    pub fn serialize_encrypted(
+        &self,
+        provider: &dyn crate::ArtifactKeyProvider,
+    ) -> Result<Bytes, SerializeError> {
+        let serialized = self.0.serialize()?;
+        Ok(wasmer_compiler::encrypt_artifact(&serialized, provider)?.into())
+    }
+
     /// Deserializes a serialized module binary into a `Module`.
     ///
     /// Note: You should usually prefer the safer [`Module::deserialize`].
@@ -291,6 +343,24 @@ impl Module {
         Ok(Self(module_imp::Module::deserialize(engine, bytes)?))
     }
 
+    /// Decrypts and deserializes a module previously serialized with
+    /// [`Module::serialize_encrypted`], getting the key it was encrypted
+    /// with from `provider`.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::deserialize`]. Decrypting the artifact only proves it was
+    /// produced by someone holding the key.
+    #[cfg(feature = "artifact-encryption")]
+    pub unsafe fn deserialize_encrypted(
+        engine: &impl AsEngineRef,
+        bytes: impl IntoBytes,
+        provider: &dyn crate::ArtifactKeyProvider,
+    ) -> Result<Self, DeserializeError> {
+        let serialized = wasmer_compiler::decrypt_artifact(&bytes.into_bytes(), provider)?;
+        Self::deserialize(engine, serialized)
+    }
+
     /// Deserializes a serialized Module located in a `Path` into a `Module`.
     /// > Note: the module has to be serialized before with the `serialize` method.
     ///
@@ -457,6 +527,182 @@ impl Module {
         self.0.custom_sections(name)
     }
 
+    /// Get the data of the custom section named `name`.
+    ///
+    /// Unlike [`Module::custom_sections`], the data isn't copied: it is
+    /// borrowed from the compiled module, and is also available on modules
+    /// created with [`Module::deserialize`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// # use wasmer::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let store = Store::default();
+    /// // A custom section named "producers" holding the bytes "wasmer".
+    /// let wasm = [
+    ///     0, 97, 115, 109, 1, 0, 0, 0, 0, 16, 9, 112, 114, 111, 100, 117, 99, 101, 114, 115,
+    ///     119, 97, 115, 109, 101, 114,
+    /// ];
+    /// let module = Module::new(&store, wasm)?;
+    /// assert_eq!(module.custom_section("producers"), Some(&b"wasmer"[..]));
+    /// assert_eq!(module.custom_section("missing"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn custom_section(&self, name: &str) -> Option<&[u8]> {
+        self.0.custom_section(name)
+    }
+
+    /// Iterate over the names and data of all the custom sections of the
+    /// module, in the order they appear in it.
+    ///
+    /// As with [`Module::custom_section`], the data is borrowed rather than
+    /// copied.
+    #[cfg(feature = "sys")]
+    pub fn all_custom_sections(&self) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+        self.0.all_custom_sections()
+    }
+
+    /// Returns the element segments of the module: the active segments in
+    /// the order they are applied at instantiation, then the passive ones.
+    ///
+    /// Declarative segments, which only declare functions usable with
+    /// `ref.func`, are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let store = Store::default();
+    /// let wat = r#"(module
+    ///     (table 2 funcref)
+    ///     (func $f)
+    ///     (elem (i32.const 1) func $f)
+    /// )"#;
+    /// let module = Module::new(&store, wat)?;
+    /// let segments: Vec<_> = module.element_segments().collect();
+    /// assert_eq!(
+    ///     segments,
+    ///     [ElementSegment::Active {
+    ///         table_index: 0,
+    ///         base: None,
+    ///         offset: 1,
+    ///         elements: Box::new([Some(0)]),
+    ///     }]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn element_segments(&self) -> impl Iterator<Item = ElementSegment> + '_ {
+        use wasmer_types::entity::packed_option::ReservedValue;
+        use wasmer_types::FunctionIndex;
+
+        let elements = |functions: &[FunctionIndex]| {
+            functions
+                .iter()
+                .map(|index| {
+                    if index.is_reserved_value() {
+                        None
+                    } else {
+                        Some(index.as_u32())
+                    }
+                })
+                .collect()
+        };
+        let info = self.info();
+        let active = info
+            .table_initializers
+            .iter()
+            .map(move |init| ElementSegment::Active {
+                table_index: init.table_index.as_u32(),
+                base: init.base.map(|base| base.as_u32()),
+                offset: init.offset,
+                elements: elements(&init.elements),
+            });
+        let mut passive: Vec<_> = info.passive_elements.iter().collect();
+        passive.sort_by_key(|(index, _)| **index);
+        active.chain(
+            passive
+                .into_iter()
+                .map(move |(index, functions)| ElementSegment::Passive {
+                    index: index.as_u32(),
+                    elements: elements(functions),
+                }),
+        )
+    }
+
+    /// Returns this module loaded into `engine`, so its compiled code lives
+    /// in the code memory of that engine.
+    ///
+    /// If `engine` is the engine of this module, or a clone of it, this is a
+    /// cheap clone of the module. Otherwise, the compiled code is copied into
+    /// `engine` without being recompiled, which fails if `engine` targets
+    /// another platform.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmer::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let store = Store::default();
+    /// let module = Module::new(&store, "(module)")?;
+    ///
+    /// let mut other_store = Store::default();
+    /// let module = module.clone_into(&other_store)?;
+    /// let instance = Instance::new(&mut other_store, &module, &imports! {})?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn clone_into(&self, engine: &impl AsEngineRef) -> Result<Self, DeserializeError> {
+        Ok(Self(self.0.clone_into(engine)?))
+    }
+
+    /// Returns the address range of the machine code of each function
+    /// defined in the module, so profilers and crash reporters embedded in
+    /// the host can attribute samples to WebAssembly functions.
+    ///
+    /// Functions are identified by their index in the module, which
+    /// counts the imported functions first. Functions whose code length
+    /// isn't known, such as the ones of modules linked statically into the
+    /// executable, are skipped.
+    #[cfg(feature = "sys")]
+    pub fn function_address_ranges(
+        &self,
+    ) -> impl Iterator<Item = (u32, std::ops::Range<usize>)> + '_ {
+        self.0
+            .function_address_ranges()
+            .map(|(index, range)| (index.as_u32(), range))
+    }
+
+    /// Resolves an address in the machine code of the module to the
+    /// function containing it and the corresponding offset in the
+    /// WebAssembly module, as in the [`RuntimeError::trace`] of traps.
+    ///
+    /// Returns `None` if the address isn't in a function of the module.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// # use wasmer::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let store = Store::default();
+    /// let module = Module::new(&store, r#"(module (func $answer (export "answer")))"#)?;
+    /// let (_, range) = module.function_address_ranges().next().unwrap();
+    /// let frame = module.symbolicate(range.start).unwrap();
+    /// assert_eq!(frame.function_name(), Some("answer"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "sys")]
+    pub fn symbolicate(&self, pc: usize) -> Option<FrameInfo> {
+        self.0.symbolicate(pc)
+    }
+
     /// The ABI of the [`ModuleInfo`] is very unstable, we refactor it very often.
     /// This function is public because in some cases it can be useful to get some
     /// extra information from the module.
@@ -396,7 +396,7 @@ pub fn parse_import_section<'data>(
                 maximum,
             }) => {
                 if memory64 {
-                    unimplemented!("64bit memory not implemented yet");
+                    return Err("64-bit memories are not supported".to_string());
                 }
                 module_info.declare_memory_import(
                     MemoryType {
@@ -484,7 +484,7 @@ pub fn parse_memory_section(
             maximum,
         } = entry.map_err(transform_err)?;
         if memory64 {
-            unimplemented!("64bit memory not implemented yet");
+            return Err("64-bit memories are not supported".to_string());
         }
         module_info.declare_memory(MemoryType {
             minimum: Pages(initial as u32),
@@ -773,6 +773,82 @@ impl_wasmtypelist!([C] S24, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A
 impl_wasmtypelist!([C] S25, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16, A17, A18, A19, A20, A21, A22, A23, A24, A25);
 impl_wasmtypelist!([C] S26, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16, A17, A18, A19, A20, A21, A22, A23, A24, A25, A26);
 
+/// A structure with a C-compatible representation that can hold an array
+/// of Wasm values of the same type. This type is used by
+/// `WasmTypeList::CStruct` for arrays.
+#[repr(C)]
+pub struct SArray<T, const N: usize>(
+    [<<T as FromToNativeWasmType>::Native as NativeWasmType>::Abi; N],
+)
+where
+    T: FromToNativeWasmType;
+
+// The Wasm types of an array, as a constant so that they can be borrowed
+// for `'static`.
+struct ArrayWasmTypes<T, const N: usize>(std::marker::PhantomData<T>);
+
+impl<T, const N: usize> ArrayWasmTypes<T, N>
+where
+    T: FromToNativeWasmType,
+{
+    const TYPES: &'static [Type; N] = &[T::Native::WASM_TYPE; N];
+}
+
+// Implement `WasmTypeList` for arrays of any length, so that signatures
+// with more parameters or results than the largest tuple can still be
+// used, as long as the values have the same type.
+impl<T, const N: usize> WasmTypeList for [T; N]
+where
+    T: FromToNativeWasmType,
+{
+    type CStruct = SArray<T, N>;
+
+    type Array = [RawValue; N];
+
+    fn size() -> u32 {
+        N as _
+    }
+
+    unsafe fn from_array(store: &mut impl AsStoreMut, array: Self::Array) -> Self {
+        array.map(|raw| FromToNativeWasmType::from_native(NativeWasmTypeInto::from_raw(store, raw)))
+    }
+
+    unsafe fn from_slice(
+        store: &mut impl AsStoreMut,
+        slice: &[RawValue],
+    ) -> Result<Self, TryFromSliceError> {
+        Ok(Self::from_array(store, slice.try_into()?))
+    }
+
+    unsafe fn into_array(self, store: &mut impl AsStoreMut) -> Self::Array {
+        self.map(|value| value.to_native().into_raw(store))
+    }
+
+    fn empty_array() -> Self::Array {
+        [RawValue { i32: 0 }; N]
+    }
+
+    unsafe fn from_c_struct(store: &mut impl AsStoreMut, c_struct: Self::CStruct) -> Self {
+        c_struct
+            .0
+            .map(|abi| FromToNativeWasmType::from_native(NativeWasmTypeInto::from_abi(store, abi)))
+    }
+
+    unsafe fn into_c_struct(self, store: &mut impl AsStoreMut) -> Self::CStruct {
+        SArray(self.map(|value| value.to_native().into_abi(store)))
+    }
+
+    unsafe fn write_c_struct_to_ptr(c_struct: Self::CStruct, ptr: *mut RawValue) {
+        for (n, abi) in c_struct.0.into_iter().enumerate() {
+            *ptr.add(n).cast() = abi;
+        }
+    }
+
+    fn wasm_types() -> &'static [Type] {
+        ArrayWasmTypes::<T, N>::TYPES
+    }
+}
+
 #[cfg(test)]
 mod test_wasm_type_list {
     use super::*;
@@ -814,6 +890,7 @@ mod test_wasm_type_list {
         assert_eq!(<()>::empty_array().len(), 0);
         assert_eq!(<i32>::empty_array().len(), 1);
         assert_eq!(<(i32, i64)>::empty_array().len(), 2);
+        assert_eq!(<[i32; 40]>::empty_array().len(), 40);
     }
     /*
     #[test]
@@ -849,6 +926,13 @@ mod test_wasm_type_list {
             [Type::I32, Type::I64, Type::F32, Type::F64]
         );
     }
+
+    #[test]
+    fn test_wasm_types_for_arrays() {
+        assert_eq!(<[i32; 0]>::wasm_types(), []);
+        assert_eq!(<[f64; 3]>::wasm_types(), [Type::F64, Type::F64, Type::F64]);
+        assert_eq!(<[i64; 32]>::wasm_types(), [Type::I64; 32]);
+    }
 }
 /*
     #[allow(non_snake_case)]
@@ -57,6 +57,11 @@ pub type WasmPtr64<T> = WasmPtr<T, Memory64>;
 ///     derefed_ptr.write(inner_val).expect("pointer in bounds");
 /// }
 /// ```
+///
+/// Pointers passed as `i64` are represented by [`WasmPtr64`]. Their offsets
+/// are bounds checked against the whole memory, so offsets beyond 4 GiB
+/// never wrap around. Note that modules declaring 64-bit memories can't be
+/// compiled yet.
 #[repr(transparent)]
 pub struct WasmPtr<T, M: MemorySize = Memory32> {
     offset: M::Offset,
@@ -12,6 +12,10 @@ use wasmer_vm::init_traps;
 #[cfg(feature = "sys")]
 pub use wasmer_vm::TrapHandlerFn;
 
+#[cfg(feature = "sys")]
+use wasmer_types::Pages;
+#[cfg(feature = "sys")]
+use crate::sys::events::{HookId, InstanceCreatedEvent, MemoryGrowEvent, StoreEvents, TrapEvent};
 #[cfg(feature = "sys")]
 pub use wasmer_vm::{StoreHandle, StoreObjects};
 
@@ -21,6 +25,52 @@ pub use crate::js::store::{StoreHandle, StoreObjects};
 #[cfg(feature = "jsc")]
 pub use crate::jsc::store::{StoreHandle, StoreObjects};
 
+/// The boundary between the host and WebAssembly crossed by a call, passed
+/// to the call hook of a store (see [`Store::call_hook`]).
+#[cfg(feature = "sys")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallHook {
+    /// The host is calling a WebAssembly function.
+    CallingWasm,
+    /// A WebAssembly function called by the host is returning.
+    ReturningFromWasm,
+    /// WebAssembly code is calling a host function.
+    CallingHost,
+    /// A host function called by WebAssembly code is returning.
+    ReturningFromHost,
+}
+
+/// Hook called when a call crosses the boundary between the host and
+/// WebAssembly, with the type of the callee.
+#[cfg(feature = "sys")]
+pub type CallHookFn = Box<
+    dyn FnMut(StoreMut<'_>, CallHook, &crate::FunctionType) -> Result<(), crate::RuntimeError>
+        + Send
+        + Sync,
+>;
+
+/// A handle to interrupt the code running in a [`Store`] from any thread,
+/// returned by [`Store::interrupt_handle`].
+///
+/// Interrupting a store sets its epoch deadline to the current epoch, so
+/// that code compiled with epoch interruption enabled traps with
+/// `TrapCode::Interrupt` when entering a function or a loop. Other stores
+/// sharing the same engine are not affected. The deadline stays reached
+/// until it is set again with [`Store::set_epoch_deadline`].
+#[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+#[derive(Debug, Clone)]
+pub struct InterruptHandle {
+    epoch: std::sync::Arc<wasmer_vm::VMEpoch>,
+}
+
+#[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+impl InterruptHandle {
+    /// Interrupts the code running in the store.
+    pub fn interrupt(&self) {
+        self.epoch.set_deadline(0);
+    }
+}
+
 /// Call handler for a store.
 // TODO: better documentation!
 pub type OnCalledHandler = Box<
@@ -41,6 +91,12 @@ pub(crate) struct StoreInner {
     pub(crate) trap_handler: Option<Box<TrapHandlerFn<'static>>>,
     #[derivative(Debug = "ignore")]
     pub(crate) on_called: Option<OnCalledHandler>,
+    #[cfg(feature = "sys")]
+    #[derivative(Debug = "ignore")]
+    pub(crate) call_hook: Option<CallHookFn>,
+    #[cfg(feature = "sys")]
+    #[derivative(Debug = "ignore")]
+    pub(crate) events: StoreEvents,
 }
 
 /// The store represents all global state that can be manipulated by
@@ -64,17 +120,203 @@ impl Store {
         #[cfg(feature = "sys")]
         init_traps();
 
+        let engine = engine.into();
+        #[allow(unused_mut)]
+        let mut objects = StoreObjects::default();
+        #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+        objects.set_epoch_counter(engine.0.epoch_counter().clone());
+
         Self {
             inner: Box::new(StoreInner {
-                objects: Default::default(),
-                engine: engine.into(),
+                objects,
+                engine,
                 #[cfg(feature = "sys")]
                 trap_handler: None,
                 on_called: None,
+                #[cfg(feature = "sys")]
+                call_hook: None,
+                #[cfg(feature = "sys")]
+                events: StoreEvents::default(),
             }),
         }
     }
 
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Sets the epoch deadline of this store to `ticks` epochs after the
+    /// current epoch of its engine.
+    ///
+    /// Once the deadline is reached, code compiled with epoch interruption
+    /// enabled traps with `TrapCode::Interrupt` when entering a function or
+    /// a loop. There is no deadline by default.
+    pub fn set_epoch_deadline(&mut self, ticks: u64) {
+        self.inner.objects.epoch().set_deadline(ticks);
+    }
+
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Returns a handle which interrupts the code running in this store,
+    /// and can be used from any thread.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle {
+            epoch: self.inner.objects.epoch().clone(),
+        }
+    }
+
+    #[cfg(feature = "sys")]
+    /// Adds fuel to this store.
+    ///
+    /// The first call enables fuel consumption: the instances created
+    /// afterwards from modules compiled with the metering middleware of
+    /// `wasmer-middlewares` share the fuel of the store, instead of the
+    /// initial limit of the middleware. Once the fuel is exhausted, they
+    /// trap with `TrapCode::OutOfFuel`, and adding fuel allows them to run
+    /// again.
+    pub fn add_fuel(&mut self, fuel: u64) {
+        self.inner.objects.add_fuel(fuel);
+    }
+
+    #[cfg(feature = "sys")]
+    /// Returns the fuel consumed by this store since fuel consumption was
+    /// enabled with [`Store::add_fuel`], or `None` if it isn't enabled.
+    pub fn fuel_consumed(&self) -> Option<u64> {
+        self.inner.objects.fuel_consumed()
+    }
+
+    #[cfg(feature = "sys")]
+    /// Sets a callback called after a memory of this store has grown, with
+    /// the memory and its old and new sizes.
+    ///
+    /// The callback is called both when the memory is grown by the host and
+    /// by WebAssembly code with `memory.grow`, so it can be used to
+    /// invalidate the raw pointers and views of the memory, or to record
+    /// metrics. It is not called when growing the memory fails or when it
+    /// is grown by 0 pages.
+    ///
+    /// Setting a callback replaces the previous one, see
+    /// [`Store::on_memory_grow_event`] to subscribe several hooks.
+    pub fn on_memory_grow(
+        &mut self,
+        callback: impl FnMut(crate::Memory, Pages, Pages) + Send + Sync + 'static,
+    ) {
+        let inner = &mut *self.inner;
+        inner
+            .events
+            .set_memory_grow_callback(&mut inner.objects, callback)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Subscribes a hook called after a memory of this store has grown, like
+    /// the callback of [`Store::on_memory_grow`], with a [`MemoryGrowEvent`].
+    ///
+    /// Unlike that callback, hooks are added to the ones already subscribed,
+    /// until they are removed with [`Store::remove_hook`].
+    pub fn on_memory_grow_event(
+        &mut self,
+        hook: impl FnMut(&MemoryGrowEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        let inner = &mut *self.inner;
+        inner.events.on_memory_grow(&mut inner.objects, hook)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Subscribes a hook called when a call of the host to a WebAssembly
+    /// function of this store traps, or when the start function of a module
+    /// traps while it is instantiated, with the error the call fails with.
+    ///
+    /// The hook observes the traps without changing the result of the
+    /// calls, so it can be used to log them or to record metrics without
+    /// wrapping each call.
+    ///
+    /// # Usage
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use wasmer::{imports, Instance, Module, Store, TypedFunction};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module (func (export "run") unreachable))"#)?;
+    /// let instance = Instance::new(&mut store, &module, &imports! {})?;
+    /// let run: TypedFunction<(), ()> = instance.exports.get_typed_function(&store, "run")?;
+    ///
+    /// let traps = Arc::new(Mutex::new(Vec::new()));
+    /// let recorded = traps.clone();
+    /// store.on_trap(move |event| recorded.lock().unwrap().push(event.error.clone()));
+    /// run.call(&mut store).unwrap_err();
+    /// assert_eq!(traps.lock().unwrap().len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_trap(&mut self, hook: impl FnMut(&TrapEvent) + Send + Sync + 'static) -> HookId {
+        self.inner.events.on_trap(hook)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Subscribes a hook called when an instance is created in this store,
+    /// once its start function has run.
+    pub fn on_instance_created(
+        &mut self,
+        hook: impl FnMut(&InstanceCreatedEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        self.inner.events.on_instance_created(hook)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Removes a hook subscribed to the events of this store, returns
+    /// `false` if it was already removed.
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        self.inner.events.remove(id)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Sets a hook called whenever a call crosses the boundary between the
+    /// host and WebAssembly, with the direction of the call and the type
+    /// of the callee.
+    ///
+    /// The hook is called when the host calls a WebAssembly function and
+    /// when that function returns, even if it trapped, and likewise when
+    /// WebAssembly code calls a host function and when it returns. If the
+    /// hook returns an error, the call fails with it, as a trap when the
+    /// callee is a host function. The calls made by the hook itself don't
+    /// call it again.
+    ///
+    /// # Usage
+    /// ```
+    /// # use wasmer::{imports, CallHook, Instance, Module, Store, TypedFunction};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut store = Store::default();
+    /// let module = Module::new(&store, r#"(module (func (export "run")))"#)?;
+    /// let instance = Instance::new(&mut store, &module, &imports! {})?;
+    /// let run: TypedFunction<(), ()> = instance.exports.get_typed_function(&store, "run")?;
+    ///
+    /// let mut depth = 0;
+    /// store.call_hook(move |_store, hook, _ty| {
+    ///     match hook {
+    ///         CallHook::CallingWasm | CallHook::ReturningFromHost => depth += 1,
+    ///         CallHook::ReturningFromWasm | CallHook::CallingHost => depth -= 1,
+    ///     }
+    ///     Ok(())
+    /// });
+    /// run.call(&mut store)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_hook(
+        &mut self,
+        hook: impl FnMut(StoreMut<'_>, CallHook, &crate::FunctionType) -> Result<(), crate::RuntimeError>
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.inner.call_hook = Some(Box::new(hook));
+    }
+
+    #[cfg(feature = "sys")]
+    /// Removes the call hook of this store and returns it.
+    ///
+    /// A hook set afterwards can call the returned one, to add its logic
+    /// to the hook of the embedder instead of replacing it.
+    pub fn take_call_hook(&mut self) -> Option<CallHookFn> {
+        self.inner.call_hook.take()
+    }
+
     #[cfg(feature = "sys")]
     /// Set the trap handler in this store.
     pub fn set_trap_handler(&mut self, handler: Option<Box<TrapHandlerFn<'static>>>) {
@@ -198,6 +440,15 @@ pub struct StoreMut<'a> {
     pub(crate) inner: &'a mut StoreInner,
 }
 
+// SAFETY: a `StoreMut` is an exclusive borrow of the `StoreInner` owned by a
+// `Store`, which is `Send` itself. Sending a `StoreMut` to another thread is
+// thus the same as sending the `&mut Store` it borrows from: nothing else can
+// access the store until it is dropped. This lets asynchronous host functions
+// keep their `FunctionEnvMut` across `.await` points, as the future of
+// `Function::call_async` may be polled from any thread. `StoreMut` is not
+// `Sync`, so it is never shared between threads.
+unsafe impl Send for StoreMut<'_> {}
+
 impl<'a> StoreMut<'a> {
     /// Returns the [`Engine`].
     pub fn engine(&self) -> &Engine {
@@ -210,6 +461,164 @@ impl<'a> StoreMut<'a> {
         a.inner.objects.id() == b.inner.objects.id()
     }
 
+    #[cfg(all(feature = "sys", not(target_arch = "wasm32")))]
+    /// Sets the epoch deadline of this store to `ticks` epochs after the
+    /// current epoch of its engine.
+    ///
+    /// See [`Store::set_epoch_deadline`].
+    pub fn set_epoch_deadline(&mut self, ticks: u64) {
+        self.inner.objects.epoch().set_deadline(ticks);
+    }
+
+    #[cfg(feature = "sys")]
+    /// Adds fuel to this store.
+    ///
+    /// See [`Store::add_fuel`].
+    pub fn add_fuel(&mut self, fuel: u64) {
+        self.inner.objects.add_fuel(fuel);
+    }
+
+    #[cfg(feature = "sys")]
+    /// Returns the fuel consumed by this store since fuel consumption was
+    /// enabled, or `None` if it isn't enabled.
+    ///
+    /// See [`Store::fuel_consumed`].
+    pub fn fuel_consumed(&self) -> Option<u64> {
+        self.inner.objects.fuel_consumed()
+    }
+
+    #[cfg(feature = "sys")]
+    /// Sets a callback called after a memory of this store has grown, with
+    /// the memory and its old and new sizes.
+    ///
+    /// See [`Store::on_memory_grow`].
+    pub fn on_memory_grow(
+        &mut self,
+        callback: impl FnMut(crate::Memory, Pages, Pages) + Send + Sync + 'static,
+    ) {
+        let inner = &mut *self.inner;
+        inner
+            .events
+            .set_memory_grow_callback(&mut inner.objects, callback)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Subscribes a hook called after a memory of this store has grown.
+    ///
+    /// See [`Store::on_memory_grow_event`].
+    pub fn on_memory_grow_event(
+        &mut self,
+        hook: impl FnMut(&MemoryGrowEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        let inner = &mut *self.inner;
+        inner.events.on_memory_grow(&mut inner.objects, hook)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Subscribes a hook called when a WebAssembly call traps.
+    ///
+    /// See [`Store::on_trap`].
+    pub fn on_trap(&mut self, hook: impl FnMut(&TrapEvent) + Send + Sync + 'static) -> HookId {
+        self.inner.events.on_trap(hook)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Subscribes a hook called when an instance is created.
+    ///
+    /// See [`Store::on_instance_created`].
+    pub fn on_instance_created(
+        &mut self,
+        hook: impl FnMut(&InstanceCreatedEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        self.inner.events.on_instance_created(hook)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Removes a hook subscribed to the events of this store.
+    ///
+    /// See [`Store::remove_hook`].
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        self.inner.events.remove(id)
+    }
+
+    #[cfg(feature = "sys")]
+    /// Sets a hook called whenever a call crosses the boundary between the
+    /// host and WebAssembly.
+    ///
+    /// See [`Store::call_hook`].
+    pub fn call_hook(
+        &mut self,
+        hook: impl FnMut(StoreMut<'_>, CallHook, &crate::FunctionType) -> Result<(), crate::RuntimeError>
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.inner.call_hook = Some(Box::new(hook));
+    }
+
+    #[cfg(feature = "sys")]
+    /// Removes the call hook of this store and returns it.
+    ///
+    /// See [`Store::take_call_hook`].
+    pub fn take_call_hook(&mut self) -> Option<CallHookFn> {
+        self.inner.call_hook.take()
+    }
+
+    #[cfg(feature = "sys")]
+    /// Calls the trap hooks of this store, if any, for a call of `function`
+    /// that fails with `error`.
+    pub(crate) fn notify_trap(
+        &mut self,
+        function: impl FnOnce() -> Option<crate::Function>,
+        error: &crate::RuntimeError,
+    ) {
+        if self.inner.events.has_trap_hooks() {
+            let event = TrapEvent {
+                function: function(),
+                error: error.clone(),
+            };
+            self.inner.events.trap(&event);
+        }
+    }
+
+    #[cfg(feature = "sys")]
+    /// Calls the instance created hooks of this store, if any.
+    pub(crate) fn notify_instance_created(&mut self, instance: &crate::Instance) {
+        if self.inner.events.has_instance_created_hooks() {
+            let event = InstanceCreatedEvent {
+                instance: instance.clone(),
+            };
+            self.inner.events.instance_created(&event);
+        }
+    }
+
+    #[cfg(feature = "sys")]
+    /// Whether a call hook is set in this store.
+    pub(crate) fn has_call_hook(&self) -> bool {
+        self.inner.call_hook.is_some()
+    }
+
+    #[cfg(feature = "sys")]
+    /// Calls the call hook of this store, if any, for a callee of type
+    /// `ty`. The hook is taken out of the store while it runs, so that it
+    /// isn't called for its own calls.
+    pub(crate) fn invoke_call_hook(
+        &mut self,
+        hook: CallHook,
+        ty: &crate::FunctionType,
+    ) -> Result<(), crate::RuntimeError> {
+        let mut call_hook = match self.inner.call_hook.take() {
+            Some(call_hook) => call_hook,
+            None => return Ok(()),
+        };
+        let result = call_hook(self.as_store_mut(), hook, ty);
+        // The hook may have been replaced while it ran.
+        if self.inner.call_hook.is_none() {
+            self.inner.call_hook = Some(call_hook);
+        }
+        result
+    }
+
     #[allow(unused)]
     pub(crate) fn engine_and_objects_mut(&mut self) -> (&Engine, &mut StoreObjects) {
         (&self.inner.engine, &mut self.inner.objects)
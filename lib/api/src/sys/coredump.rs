@@ -0,0 +1,314 @@
+//! Coredumps of instances, in the format of the WebAssembly tool
+//! conventions, see
+//! <https://github.com/WebAssembly/tool-conventions/blob/main/Coredump.md>.
+//!
+//! A coredump is a WebAssembly module whose memories and globals hold the
+//! state of the instance, with custom sections describing the process and
+//! the call stack, so that it can be opened by debuggers like `wasmgdb`
+//! along with the original module.
+
+use crate::Value;
+use std::slice;
+use wasmer_types::{FrameInfo, GlobalType, Mutability, RawValue, Type, WASM_PAGE_SIZE};
+use wasmer_vm::{LinearMemory, StoreObjects, VMInstance};
+
+/// The magic header and version of WebAssembly modules.
+const HEADER: &[u8; 8] = b"\0asm\x01\0\0\0";
+
+const CUSTOM_SECTION: u8 = 0;
+const MEMORY_SECTION: u8 = 5;
+const GLOBAL_SECTION: u8 = 6;
+const DATA_SECTION: u8 = 11;
+
+/// Data segments are split on runs of zero bytes at least this long.
+const MIN_ZERO_RUN: usize = 16;
+
+/// A frame of the call stack of a coredump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CoredumpFrame {
+    func_index: u32,
+    code_offset: u32,
+}
+
+/// A coredump of an [`Instance`](crate::Instance): the call stack of a
+/// trap, and the contents of the memories and the values of the globals of
+/// the instance.
+///
+/// It is captured when a trap reaches the host if the engine was
+/// configured with
+/// [`NativeEngineExt::set_coredump_on_trap`](crate::NativeEngineExt::set_coredump_on_trap),
+/// and is then returned by [`RuntimeError::coredump`](crate::RuntimeError::coredump),
+/// or explicitly with [`Instance::coredump`](crate::Instance::coredump).
+///
+/// The values of the locals and of the operand stack of the frames can't
+/// be recovered from the compiled code, so they are left empty.
+#[derive(Debug, Clone)]
+pub struct Coredump {
+    executable_name: String,
+    frames: Vec<CoredumpFrame>,
+    memories: Vec<Vec<u8>>,
+    globals: Vec<(GlobalType, RawValue)>,
+}
+
+impl Coredump {
+    /// Captures the state of `instance`, with the call stack of `trace`.
+    pub(crate) fn capture(
+        objects: &StoreObjects,
+        instance: &VMInstance,
+        trace: &[FrameInfo],
+    ) -> Self {
+        let executable_name = instance.module_ref().name.clone().unwrap_or_default();
+
+        let frames = trace
+            .iter()
+            .map(|frame| CoredumpFrame {
+                func_index: frame.func_index(),
+                code_offset: frame.func_offset() as u32,
+            })
+            .collect();
+
+        let memories = instance
+            .memories()
+            .map(|memory| {
+                let definition = unsafe { memory.get(objects).vmmemory().as_ref() };
+                unsafe { slice::from_raw_parts(definition.base, definition.current_length) }
+                    .to_vec()
+            })
+            .collect();
+
+        let globals = instance
+            .globals()
+            .map(|global| {
+                let global = global.get(objects);
+                (*global.ty(), unsafe { global.vmglobal().as_ref().val })
+            })
+            .collect();
+
+        Self {
+            executable_name,
+            frames,
+            memories,
+            globals,
+        }
+    }
+
+    /// The name of the executable, which defaults to the name of the
+    /// module.
+    pub fn executable_name(&self) -> &str {
+        &self.executable_name
+    }
+
+    /// Sets the name of the executable recorded in the coredump.
+    pub fn set_executable_name(&mut self, executable_name: impl Into<String>) {
+        self.executable_name = executable_name.into();
+    }
+
+    /// Returns the contents of a memory of the instance, imported or
+    /// defined, by index.
+    pub fn memory(&self, index: u32) -> Option<&[u8]> {
+        self.memories.get(index as usize).map(Vec::as_slice)
+    }
+
+    /// Returns the value of a global of the instance, imported or defined,
+    /// by index.
+    ///
+    /// References can't be captured, so they are reported as null.
+    pub fn global(&self, index: u32) -> Option<Value> {
+        let (ty, val) = self.globals.get(index as usize)?;
+        Some(unsafe {
+            match ty.ty {
+                Type::I32 => Value::I32(val.i32),
+                Type::I64 => Value::I64(val.i64),
+                Type::F32 => Value::F32(val.f32),
+                Type::F64 => Value::F64(val.f64),
+                Type::V128 => Value::V128(val.u128),
+                Type::FuncRef => Value::FuncRef(None),
+                Type::ExternRef => Value::ExternRef(None),
+            }
+        })
+    }
+
+    /// Serializes the coredump into a WebAssembly module, to be written to
+    /// a `.coredump` file.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut module = HEADER.to_vec();
+
+        // process-info ::= 0x0 executable-name:name
+        let mut core = vec![0];
+        write_name(&mut core, &self.executable_name);
+        write_custom_section(&mut module, "core", &core);
+
+        // thread-info ::= 0x0 thread-name:name frames:vec(frame)
+        // frame ::= 0x0 funcidx:u32 codeoffset:u32 locals:vec(value)
+        //           stack:vec(value)
+        let mut corestack = vec![0];
+        write_name(&mut corestack, "main");
+        write_u32(&mut corestack, self.frames.len() as u32);
+        for frame in &self.frames {
+            corestack.push(0);
+            write_u32(&mut corestack, frame.func_index);
+            write_u32(&mut corestack, frame.code_offset);
+            write_u32(&mut corestack, 0);
+            write_u32(&mut corestack, 0);
+        }
+        write_custom_section(&mut module, "corestack", &corestack);
+
+        if !self.memories.is_empty() {
+            let mut memories = Vec::new();
+            write_u32(&mut memories, self.memories.len() as u32);
+            for memory in &self.memories {
+                memories.push(0);
+                write_u32(&mut memories, (memory.len() / WASM_PAGE_SIZE) as u32);
+            }
+            write_section(&mut module, MEMORY_SECTION, &memories);
+        }
+
+        if !self.globals.is_empty() {
+            let mut globals = Vec::new();
+            write_u32(&mut globals, self.globals.len() as u32);
+            for (ty, val) in &self.globals {
+                write_global(&mut globals, ty, val);
+            }
+            write_section(&mut module, GLOBAL_SECTION, &globals);
+        }
+
+        let segments = self
+            .memories
+            .iter()
+            .enumerate()
+            .flat_map(|(index, memory)| {
+                data_segments(memory)
+                    .into_iter()
+                    .map(move |(offset, bytes)| (index as u32, offset, bytes))
+            })
+            .collect::<Vec<_>>();
+        if !segments.is_empty() {
+            let mut data = Vec::new();
+            write_u32(&mut data, segments.len() as u32);
+            for (memory_index, offset, bytes) in segments {
+                if memory_index == 0 {
+                    data.push(0);
+                } else {
+                    data.push(2);
+                    write_u32(&mut data, memory_index);
+                }
+                data.push(0x41); // i32.const
+                write_i64(&mut data, offset as i32 as i64);
+                data.push(0x0b); // end
+                write_u32(&mut data, bytes.len() as u32);
+                data.extend_from_slice(bytes);
+            }
+            write_section(&mut module, DATA_SECTION, &data);
+        }
+
+        module
+    }
+}
+
+/// Splits the contents of a memory into the data segments initializing
+/// its non-zero bytes, as offsets and bytes.
+fn data_segments(memory: &[u8]) -> Vec<(usize, &[u8])> {
+    let mut segments = Vec::new();
+    let mut start = None;
+    let mut zeros = 0;
+    for (offset, byte) in memory.iter().enumerate() {
+        if *byte != 0 {
+            start.get_or_insert(offset);
+            zeros = 0;
+        } else if let Some(segment_start) = start {
+            zeros += 1;
+            if zeros == MIN_ZERO_RUN {
+                segments.push((segment_start, &memory[segment_start..=offset - zeros]));
+                start = None;
+            }
+        }
+    }
+    if let Some(segment_start) = start {
+        segments.push((segment_start, &memory[segment_start..memory.len() - zeros]));
+    }
+    segments
+}
+
+fn write_global(out: &mut Vec<u8>, ty: &GlobalType, val: &RawValue) {
+    let (type_byte, init) = unsafe {
+        match ty.ty {
+            Type::I32 => {
+                let mut init = vec![0x41];
+                write_i64(&mut init, val.i32 as i64);
+                (0x7f, init)
+            }
+            Type::I64 => {
+                let mut init = vec![0x42];
+                write_i64(&mut init, val.i64);
+                (0x7e, init)
+            }
+            Type::F32 => {
+                let mut init = vec![0x43];
+                init.extend_from_slice(&val.f32.to_le_bytes());
+                (0x7d, init)
+            }
+            Type::F64 => {
+                let mut init = vec![0x44];
+                init.extend_from_slice(&val.f64.to_le_bytes());
+                (0x7c, init)
+            }
+            Type::V128 => {
+                let mut init = vec![0xfd, 0x0c];
+                init.extend_from_slice(&val.u128.to_le_bytes());
+                (0x7b, init)
+            }
+            Type::FuncRef => (0x70, vec![0xd0, 0x70]),
+            Type::ExternRef => (0x6f, vec![0xd0, 0x6f]),
+        }
+    };
+    out.push(type_byte);
+    out.push(match ty.mutability {
+        Mutability::Const => 0,
+        Mutability::Var => 1,
+    });
+    out.extend_from_slice(&init);
+    out.push(0x0b); // end
+}
+
+fn write_section(out: &mut Vec<u8>, id: u8, contents: &[u8]) {
+    out.push(id);
+    write_u32(out, contents.len() as u32);
+    out.extend_from_slice(contents);
+}
+
+fn write_custom_section(out: &mut Vec<u8>, name: &str, contents: &[u8]) {
+    let mut section = Vec::with_capacity(name.len() + 1 + contents.len());
+    write_name(&mut section, name);
+    section.extend_from_slice(contents);
+    write_section(out, CUSTOM_SECTION, &section);
+}
+
+fn write_name(out: &mut Vec<u8>, name: &str) {
+    write_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+}
+
+fn write_u32(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_i64(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
@@ -2,7 +2,7 @@ use std::{path::Path, sync::Arc};
 
 use shared_buffer::OwnedBuffer;
 pub use wasmer_compiler::{
-    Artifact, BaseTunables, CompilerConfig, Engine, EngineBuilder, Tunables,
+    Artifact, BaseTunables, CompilerConfig, Engine, EngineBuilder, ProfilingStrategy, Tunables,
 };
 #[cfg(feature = "compiler")]
 use wasmer_types::Features;
@@ -84,6 +84,33 @@ pub trait NativeEngineExt {
     /// Get a reference to attached Tunable of this engine
     fn tunables(&self) -> &dyn Tunables;
 
+    /// Limit the total number of bytes of compiled code kept alive by this
+    /// engine, or remove the limit with `None`.
+    ///
+    /// See [`wasmer_compiler::Engine::set_code_memory_limit`].
+    fn set_code_memory_limit(&self, limit: Option<usize>);
+
+    /// Returns the total number of bytes of compiled code kept alive by
+    /// this engine.
+    fn code_memory_size(&self) -> usize;
+
+    /// Describe the code compiled from now on to native debuggers, with the
+    /// `gdb-jit` feature.
+    ///
+    /// See [`wasmer_compiler::Engine::set_debug_info`].
+    fn set_debug_info(&self, debug_info: bool);
+
+    /// Report the functions compiled from now on to profilers like `perf`.
+    ///
+    /// See [`wasmer_compiler::Engine::set_profiling`].
+    fn set_profiling(&self, strategies: &[ProfilingStrategy]) -> std::io::Result<()>;
+
+    /// Capture a coredump when a WebAssembly trap reaches the host, which
+    /// is then available with [`RuntimeError::coredump`](crate::RuntimeError::coredump).
+    ///
+    /// See [`wasmer_compiler::Engine::set_coredump_on_trap`].
+    fn set_coredump_on_trap(&self, coredump_on_trap: bool);
+
     /// Load a serialized WebAssembly module from a memory mapped file and deserialize it.
     ///
     /// NOTE: you should almost always prefer [`Self::deserialize_from_mmapped_file`].
@@ -127,6 +154,26 @@ impl NativeEngineExt for crate::engine::Engine {
         self.0.tunables()
     }
 
+    fn set_code_memory_limit(&self, limit: Option<usize>) {
+        self.0.set_code_memory_limit(limit)
+    }
+
+    fn code_memory_size(&self) -> usize {
+        self.0.code_memory_size()
+    }
+
+    fn set_debug_info(&self, debug_info: bool) {
+        self.0.set_debug_info(debug_info)
+    }
+
+    fn set_profiling(&self, strategies: &[ProfilingStrategy]) -> std::io::Result<()> {
+        self.0.set_profiling(strategies)
+    }
+
+    fn set_coredump_on_trap(&self, coredump_on_trap: bool) {
+        self.0.set_coredump_on_trap(coredump_on_trap)
+    }
+
     unsafe fn deserialize_from_mmapped_file_unchecked(
         &self,
         file_ref: &Path,
@@ -138,7 +185,7 @@ impl NativeEngineExt for crate::engine::Engine {
                 .map_err(|e| DeserializeError::Generic(format!("{e:?}")))?,
         )?);
         Ok(crate::Module(super::module::Module::from_artifact(
-            artifact,
+            self, artifact,
         )))
     }
 
@@ -153,7 +200,7 @@ impl NativeEngineExt for crate::engine::Engine {
                 .map_err(|e| DeserializeError::Generic(format!("{e:?}")))?,
         )?);
         Ok(crate::Module(super::module::Module::from_artifact(
-            artifact,
+            self, artifact,
         )))
     }
 
@@ -1,5 +1,9 @@
+use crate::store::AsStoreRef;
+use crate::sys::coredump::Coredump;
 use crate::{LinkError, RuntimeError};
-use wasmer_vm::Trap;
+use std::sync::Arc;
+use wasmer_types::TrapCode;
+use wasmer_vm::{Trap, VMFunctionContext, VMInstance};
 
 impl From<wasmer_compiler::LinkError> for LinkError {
     fn from(other: wasmer_compiler::LinkError) -> Self {
@@ -7,6 +11,7 @@ impl From<wasmer_compiler::LinkError> for LinkError {
             wasmer_compiler::LinkError::Import(namespace, name, error) => {
                 Self::Import(namespace, name, error)
             }
+            wasmer_compiler::LinkError::Imports(errors) => Self::Imports(errors),
             wasmer_compiler::LinkError::Trap(e) => Self::Trap(e.into()),
             wasmer_compiler::LinkError::Resource(e) => Self::Resource(e),
         }
@@ -22,3 +27,60 @@ impl From<Trap> for RuntimeError {
         Self::new_from_source(trap, wasm_trace, trap_code)
     }
 }
+
+impl RuntimeError {
+    /// Creates a `RuntimeError` from a trap raised by code running in the
+    /// given store, during a call to the function with the context
+    /// `callee`.
+    ///
+    /// If the engine captures coredumps, the instance of the callee is the
+    /// one captured.
+    pub(crate) fn from_store_trap(
+        store: &impl AsStoreRef,
+        trap: Trap,
+        callee: VMFunctionContext,
+    ) -> Self {
+        let store = store.as_store_ref();
+        let instance = if store.engine().0.coredump_on_trap() {
+            let vmctx = unsafe { callee.vmctx };
+            store
+                .objects()
+                .iter_instances()
+                .find(|instance| instance.vmctx_ptr() == vmctx)
+        } else {
+            None
+        };
+        Self::from_instance_trap(&store, trap, instance)
+    }
+
+    /// Creates a `RuntimeError` from a trap raised by code of `instance`
+    /// running in the given store, reporting `TrapCode::OutOfFuel` if the
+    /// store ran out of fuel, and capturing a coredump of the instance if
+    /// the engine is configured to.
+    pub(crate) fn from_instance_trap(
+        store: &impl AsStoreRef,
+        trap: Trap,
+        instance: Option<&VMInstance>,
+    ) -> Self {
+        if trap.is::<Self>() {
+            return trap.downcast::<Self>().unwrap();
+        }
+        let store = store.as_store_ref();
+        let (wasm_trace, mut trap_code) = wasmer_compiler::get_trace_and_trapcode(&trap);
+        // The metering middleware stops the execution with an `unreachable`
+        // once the fuel is exhausted.
+        if trap_code == Some(TrapCode::UnreachableCodeReached)
+            && store.objects().is_out_of_fuel()
+        {
+            trap_code = Some(TrapCode::OutOfFuel);
+        }
+        let mut error = Self::new_from_source(trap, wasm_trace, trap_code);
+        // Errors raised by host functions aren't crashes of the guest.
+        let capture = trap_code.is_some() && store.engine().0.coredump_on_trap();
+        if let Some(instance) = instance.filter(|_| capture) {
+            let coredump = Coredump::capture(store.objects(), instance, error.trace());
+            Arc::get_mut(&mut error.inner).unwrap().coredump = Some(coredump);
+        }
+        error
+    }
+}
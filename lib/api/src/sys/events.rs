@@ -0,0 +1,187 @@
+//! The hooks subscribed to the lifecycle events of the objects of a store,
+//! see [`Store::on_trap`](crate::Store::on_trap),
+//! [`Store::on_memory_grow_event`](crate::Store::on_memory_grow_event) and
+//! [`Store::on_instance_created`](crate::Store::on_instance_created).
+
+use std::sync::{Arc, Mutex};
+
+use wasmer_types::{Pages, StoreId};
+use wasmer_vm::{MemoryGrowCallback, StoreHandle, StoreObjects};
+
+use crate::{Function, Instance, Memory, RuntimeError};
+
+/// Identifies a hook subscribed to the events of a store, to remove it
+/// with [`Store::remove_hook`](crate::Store::remove_hook).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookId(u64);
+
+/// A trap raised by WebAssembly code.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TrapEvent {
+    /// The function called by the host, whose call trapped, or `None` for a
+    /// trap of the start function of a module being instantiated.
+    pub function: Option<Function>,
+    /// The error the call fails with.
+    pub error: RuntimeError,
+}
+
+/// A memory that has grown, by the host or by WebAssembly code with
+/// `memory.grow`.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct MemoryGrowEvent {
+    /// The memory that has grown.
+    pub memory: Memory,
+    /// The size of the memory before it has grown.
+    pub old_size: Pages,
+    /// The size of the memory after it has grown.
+    pub new_size: Pages,
+}
+
+/// An instance that was created, once its start function has run.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct InstanceCreatedEvent {
+    /// The instance, with its module and its exports.
+    pub instance: Instance,
+}
+
+type Hooks<E> = Vec<(HookId, Box<dyn FnMut(&E) + Send + Sync>)>;
+
+type MemoryGrowFn = Box<dyn FnMut(Memory, Pages, Pages) + Send + Sync>;
+
+/// The callback set with [`Store::on_memory_grow`](crate::Store::on_memory_grow)
+/// and the hooks subscribed to the growth of the memories.
+#[derive(Default)]
+struct MemoryGrowHooks {
+    callback: Option<MemoryGrowFn>,
+    hooks: Hooks<MemoryGrowEvent>,
+}
+
+/// The hooks subscribed to the events of a store.
+#[derive(Default)]
+pub(crate) struct StoreEvents {
+    next_id: u64,
+    on_trap: Hooks<TrapEvent>,
+    on_instance_created: Hooks<InstanceCreatedEvent>,
+    // Shared with the memory grow callback of the objects of the store,
+    // which is only installed once a hook is subscribed.
+    on_memory_grow: Option<Arc<Mutex<MemoryGrowHooks>>>,
+}
+
+impl StoreEvents {
+    fn next_id(&mut self) -> HookId {
+        self.next_id += 1;
+        HookId(self.next_id)
+    }
+
+    pub(crate) fn on_trap(
+        &mut self,
+        hook: impl FnMut(&TrapEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        let id = self.next_id();
+        self.on_trap.push((id, Box::new(hook)));
+        id
+    }
+
+    pub(crate) fn on_instance_created(
+        &mut self,
+        hook: impl FnMut(&InstanceCreatedEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        let id = self.next_id();
+        self.on_instance_created.push((id, Box::new(hook)));
+        id
+    }
+
+    /// Sets the memory grow callback, replacing the previous one.
+    pub(crate) fn set_memory_grow_callback(
+        &mut self,
+        objects: &mut StoreObjects,
+        callback: impl FnMut(Memory, Pages, Pages) + Send + Sync + 'static,
+    ) {
+        self.memory_grow_hooks(objects).lock().unwrap().callback = Some(Box::new(callback));
+    }
+
+    pub(crate) fn on_memory_grow(
+        &mut self,
+        objects: &mut StoreObjects,
+        hook: impl FnMut(&MemoryGrowEvent) + Send + Sync + 'static,
+    ) -> HookId {
+        let id = self.next_id();
+        let hooks = self.memory_grow_hooks(objects);
+        hooks.lock().unwrap().hooks.push((id, Box::new(hook)));
+        id
+    }
+
+    /// The memory grow callback and hooks, with the callback of the
+    /// objects of the store dispatching to them installed on first use.
+    fn memory_grow_hooks(&mut self, objects: &mut StoreObjects) -> &Arc<Mutex<MemoryGrowHooks>> {
+        self.on_memory_grow.get_or_insert_with(|| {
+            let hooks = Arc::new(Mutex::new(MemoryGrowHooks::default()));
+            objects.set_memory_grow_callback(Some(memory_grow_callback(
+                objects.id(),
+                hooks.clone(),
+            )));
+            hooks
+        })
+    }
+
+    /// Removes a hook, returns `false` if it was already removed.
+    pub(crate) fn remove(&mut self, id: HookId) -> bool {
+        fn remove<E>(hooks: &mut Hooks<E>, id: HookId) -> bool {
+            let len = hooks.len();
+            hooks.retain(|(hook_id, _)| *hook_id != id);
+            hooks.len() < len
+        }
+
+        remove(&mut self.on_trap, id)
+            || remove(&mut self.on_instance_created, id)
+            || self
+                .on_memory_grow
+                .as_ref()
+                .map_or(false, |hooks| remove(&mut hooks.lock().unwrap().hooks, id))
+    }
+
+    pub(crate) fn has_trap_hooks(&self) -> bool {
+        !self.on_trap.is_empty()
+    }
+
+    pub(crate) fn has_instance_created_hooks(&self) -> bool {
+        !self.on_instance_created.is_empty()
+    }
+
+    pub(crate) fn trap(&mut self, event: &TrapEvent) {
+        for (_, hook) in self.on_trap.iter_mut() {
+            hook(event);
+        }
+    }
+
+    pub(crate) fn instance_created(&mut self, event: &InstanceCreatedEvent) {
+        for (_, hook) in self.on_instance_created.iter_mut() {
+            hook(event);
+        }
+    }
+}
+
+fn memory_grow_callback(id: StoreId, hooks: Arc<Mutex<MemoryGrowHooks>>) -> MemoryGrowCallback {
+    Box::new(move |handle, old_size, new_size| {
+        // The handle comes from the objects of the store with this id.
+        let handle = unsafe { StoreHandle::from_internal(id, handle) };
+        let memory = Memory(crate::sys::externals::memory::Memory { handle });
+        let mut hooks = hooks.lock().unwrap();
+        if let Some(callback) = hooks.callback.as_mut() {
+            callback(memory.clone(), old_size, new_size);
+        }
+        if !hooks.hooks.is_empty() {
+            let event = MemoryGrowEvent {
+                memory,
+                old_size,
+                new_size,
+            };
+            for (_, hook) in hooks.hooks.iter_mut() {
+                hook(&event);
+            }
+        }
+    })
+}
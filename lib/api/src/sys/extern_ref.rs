@@ -20,6 +20,22 @@ impl ExternRef {
         }
     }
 
+    pub fn new_with_drop_hook<T>(
+        store: &mut impl AsStoreMut,
+        value: T,
+        on_drop: impl FnOnce(T) + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Any + Send + Sync + 'static + Sized,
+    {
+        Self {
+            handle: StoreHandle::new(
+                store.objects_mut(),
+                VMExternObj::with_drop_hook(value, on_drop),
+            ),
+        }
+    }
+
     pub fn downcast<'a, T>(&self, store: &'a impl AsStoreRef) -> Option<&'a T>
     where
         T: Any + Send + Sync + 'static + Sized,
@@ -30,6 +46,16 @@ impl ExternRef {
             .downcast_ref::<T>()
     }
 
+    pub fn downcast_mut<'a, T>(&self, store: &'a mut impl AsStoreMut) -> Option<&'a mut T>
+    where
+        T: Any + Send + Sync + 'static + Sized,
+    {
+        self.handle
+            .get_mut(store.objects_mut())
+            .as_mut()
+            .downcast_mut::<T>()
+    }
+
     pub(crate) fn vm_externref(&self) -> VMExternRef {
         VMExternRef(self.handle.internal_handle())
     }
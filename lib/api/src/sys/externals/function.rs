@@ -1,16 +1,24 @@
-use crate::externals::function::{HostFunction, WithEnv, WithoutEnv};
+use crate::externals::function::{HostFunction, WithEnv, WithRawEnv, WithoutEnv};
 use crate::native_type::{FromToNativeWasmType, IntoResult, NativeWasmTypeInto, WasmTypeList};
-use crate::store::{AsStoreMut, AsStoreRef, StoreInner, StoreMut};
+use crate::store::{AsStoreMut, AsStoreRef, CallHook, StoreInner, StoreMut};
 use crate::sys::engine::NativeEngineExt;
 use crate::vm::{VMExternFunction, VMFunctionCallback};
-use crate::{FunctionEnv, FunctionEnvMut, FunctionType, RuntimeError, Value};
+use crate::{
+    CallState, FunctionEnv, FunctionEnvMut, FunctionType, HostFuture, RuntimeError, Value,
+};
+use std::error::Error;
+use std::future::Future;
 use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
 use std::{cell::UnsafeCell, cmp::max, ffi::c_void};
-use wasmer_types::{NativeWasmType, RawValue};
+use wasmer_types::{NativeWasmType, RawValue, Type};
 use wasmer_vm::{
-    on_host_stack, raise_user_trap, resume_panic, wasmer_call_trampoline, MaybeInstanceOwned,
-    StoreHandle, VMCallerCheckedAnyfunc, VMContext, VMDynamicFunctionContext, VMExtern, VMFuncRef,
-    VMFunction, VMFunctionContext, VMFunctionKind, VMTrampoline,
+    block_on_wasm_stack, on_host_stack, raise_user_trap, resume_panic, wasmer_call_trampoline,
+    wasmer_call_trampoline_async, AsyncCall, MaybeInstanceOwned, StoreHandle,
+    VMCallerCheckedAnyfunc, VMContext, VMDynamicFunctionContext, VMExtern, VMFuncRef, VMFunction,
+    VMFunctionContext, VMFunctionKind, VMTrampoline,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -45,6 +53,7 @@ impl Function {
         let wrapper = move |values_vec: *mut RawValue| -> Result<(), RuntimeError> {
             unsafe {
                 let mut store = StoreMut::from_raw(raw_store as *mut StoreInner);
+                store.invoke_call_hook(CallHook::CallingHost, &func_ty)?;
                 let mut args = Vec::with_capacity(func_ty.params().len());
                 for (i, ty) in func_ty.params().iter().enumerate() {
                     args.push(Value::from_raw(&mut store, *ty, *values_vec.add(i)));
@@ -54,7 +63,9 @@ impl Function {
                     store_mut,
                     func_env: func_env.clone(),
                 };
-                let returns = func(env, &args)?;
+                let returns = func(env, &args);
+                store.invoke_call_hook(CallHook::ReturningFromHost, &func_ty)?;
+                let returns = returns?;
 
                 // We need to dynamically check that the returns
                 // match the expected types, as well as expected length.
@@ -72,9 +83,99 @@ impl Function {
             }
             Ok(())
         };
+        Self::new_dynamic(
+            store,
+            function_type,
+            DynamicFunction {
+                func: wrapper,
+                on_wasm_stack: false,
+            },
+        )
+    }
+
+    pub fn new_with_env_async<FT, F, T: Send + 'static>(
+        store: &mut impl AsStoreMut,
+        env: &FunctionEnv<T>,
+        ty: FT,
+        func: F,
+    ) -> Self
+    where
+        FT: Into<FunctionType>,
+        F: for<'a> Fn(FunctionEnvMut<'a, T>, &'a [Value]) -> HostFuture<'a> + 'static + Send + Sync,
+    {
+        let function_type = ty.into();
+        let func_ty = function_type.clone();
+        let func_env = env.clone();
+        let raw_store = store.as_store_mut().as_raw() as *mut u8;
+        // The wrapper runs on the Wasm stack so that it can be suspended while
+        // the future is pending, everything else happens on the host stack.
+        let wrapper = move |values_vec: *mut RawValue| -> Result<(), RuntimeError> {
+            unsafe {
+                let args = on_host_stack(|| {
+                    let mut store = StoreMut::from_raw(raw_store as *mut StoreInner);
+                    store.invoke_call_hook(CallHook::CallingHost, &func_ty)?;
+                    let mut args = Vec::with_capacity(func_ty.params().len());
+                    for (i, ty) in func_ty.params().iter().enumerate() {
+                        args.push(Value::from_raw(&mut store, *ty, *values_vec.add(i)));
+                    }
+                    Ok::<_, RuntimeError>(args)
+                })?;
+                let mut future = on_host_stack(|| {
+                    let store_mut = StoreMut::from_raw(raw_store as *mut StoreInner);
+                    let env = FunctionEnvMut {
+                        store_mut,
+                        func_env: func_env.clone(),
+                    };
+                    func(env, &args)
+                });
+                let returns = block_on_wasm_stack(|cx| future.as_mut().poll(cx));
+                on_host_stack(move || drop(future));
+                on_host_stack(|| {
+                    let mut store = StoreMut::from_raw(raw_store as *mut StoreInner);
+                    store.invoke_call_hook(CallHook::ReturningFromHost, &func_ty)
+                })?;
+                let returns = returns.ok_or_else(|| {
+                    RuntimeError::new("async host functions can only be called from `call_async` or `call_resumable`")
+                })??;
+
+                on_host_stack(|| {
+                    let store = StoreMut::from_raw(raw_store as *mut StoreInner);
+                    let return_types = returns.iter().map(|ret| ret.ty());
+                    if return_types.ne(func_ty.results().iter().copied()) {
+                        return Err(RuntimeError::new(format!(
+                            "Dynamic function returned wrong signature. Expected {:?} but got {:?}",
+                            func_ty.results(),
+                            returns.iter().map(|ret| ret.ty())
+                        )));
+                    }
+                    for (i, ret) in returns.iter().enumerate() {
+                        *values_vec.add(i) = ret.as_raw(&store);
+                    }
+                    Ok(())
+                })
+            }
+        };
+        Self::new_dynamic(
+            store,
+            function_type,
+            DynamicFunction {
+                func: wrapper,
+                on_wasm_stack: true,
+            },
+        )
+    }
+
+    fn new_dynamic<F>(
+        store: &mut impl AsStoreMut,
+        function_type: FunctionType,
+        ctx: DynamicFunction<F>,
+    ) -> Self
+    where
+        F: Fn(*mut RawValue) -> Result<(), RuntimeError> + 'static,
+    {
         let mut host_data = Box::new(VMDynamicFunctionContext {
             address: std::ptr::null(),
-            ctx: DynamicFunction { func: wrapper },
+            ctx,
         });
         host_data.address = host_data.ctx.func_body_ptr();
 
@@ -124,33 +225,9 @@ impl Function {
             func,
         });
         let function_type = FunctionType::new(Args::wasm_types(), Rets::wasm_types());
-
-        let type_index = store
-            .as_store_mut()
-            .engine()
-            .0
-            .register_signature(&function_type);
-        let vmctx = VMFunctionContext {
-            host_env: host_data.as_ref() as *const _ as *mut c_void,
-        };
         let call_trampoline =
             <F as HostFunction<(), Args, Rets, WithoutEnv>>::call_trampoline_address();
-        let anyfunc = VMCallerCheckedAnyfunc {
-            func_ptr,
-            type_index,
-            vmctx,
-            call_trampoline,
-        };
-
-        let vm_function = VMFunction {
-            anyfunc: MaybeInstanceOwned::Host(Box::new(UnsafeCell::new(anyfunc))),
-            kind: VMFunctionKind::Static,
-            signature: function_type,
-            host_data,
-        };
-        Self {
-            handle: StoreHandle::new(store.as_store_mut().objects_mut(), vm_function),
-        }
+        Self::new_static(store, function_type, host_data, func_ptr, call_trampoline)
     }
 
     pub fn new_typed_with_env<T: Send + 'static, F, Args, Rets>(
@@ -170,6 +247,59 @@ impl Function {
             func,
         });
         let function_type = FunctionType::new(Args::wasm_types(), Rets::wasm_types());
+        let call_trampoline =
+            <F as HostFunction<T, Args, Rets, WithEnv>>::call_trampoline_address();
+        Self::new_static(store, function_type, host_data, func_ptr, call_trampoline)
+    }
+
+    pub fn new_typed_raw<T: Send + 'static, F, Args, Rets>(
+        store: &mut impl AsStoreMut,
+        data: T,
+        func: F,
+    ) -> Self
+    where
+        F: HostFunction<T, Args, Rets, WithRawEnv> + 'static + Send + Sync,
+        Args: WasmTypeList,
+        Rets: WasmTypeList,
+    {
+        let func_ptr = func.function_callback();
+        let host_data = Box::new(RawFunction {
+            raw_store: store.as_store_mut().as_raw() as *mut u8,
+            data: UnsafeCell::new(data),
+            func,
+        });
+        let function_type = FunctionType::new(Args::wasm_types(), Rets::wasm_types());
+        let call_trampoline =
+            <F as HostFunction<T, Args, Rets, WithRawEnv>>::call_trampoline_address();
+        Self::new_static(store, function_type, host_data, func_ptr, call_trampoline)
+    }
+
+    fn new_static<H: 'static>(
+        store: &mut impl AsStoreMut,
+        function_type: FunctionType,
+        host_data: Box<H>,
+        func_ptr: VMFunctionCallback,
+        call_trampoline: VMTrampoline,
+    ) -> Self {
+        // The compilers don't return multiple values the way the native ABI
+        // returns a C struct, so such functions are called through a dynamic
+        // trampoline, which then calls the host function from the host side.
+        if function_type.results().len() > 1 {
+            let wrapper = move |values_vec: *mut RawValue| -> Result<(), RuntimeError> {
+                let vmctx = host_data.as_ref() as *const _ as *mut VMContext;
+                unsafe { call_trampoline(vmctx, func_ptr, values_vec) };
+                Ok(())
+            };
+            // The host function already switches to the host stack itself.
+            return Self::new_dynamic(
+                store,
+                function_type,
+                DynamicFunction {
+                    func: wrapper,
+                    on_wasm_stack: true,
+                },
+            );
+        }
 
         let type_index = store
             .as_store_mut()
@@ -179,8 +309,6 @@ impl Function {
         let vmctx = VMFunctionContext {
             host_env: host_data.as_ref() as *const _ as *mut c_void,
         };
-        let call_trampoline =
-            <F as HostFunction<T, Args, Rets, WithEnv>>::call_trampoline_address();
         let anyfunc = VMCallerCheckedAnyfunc {
             func_ptr,
             type_index,
@@ -213,6 +341,21 @@ impl Function {
         params: &[Value],
         results: &mut [Value],
     ) -> Result<(), RuntimeError> {
+        let values_vec = self.raw_params(store, params, results)?;
+
+        // Invoke the call
+        self.call_wasm_raw(store, trampoline, values_vec, results)?;
+        Ok(())
+    }
+
+    /// Check the parameters and results against the signature, and store the
+    /// parameters into a buffer large enough for the results.
+    fn raw_params(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: &[Value],
+        results: &[Value],
+    ) -> Result<Vec<RawValue>, RuntimeError> {
         let format_types_for_error_message = |items: &[Value]| {
             items
                 .iter()
@@ -254,12 +397,98 @@ impl Function {
             }
             *slot = arg.as_raw(store);
         }
+        Ok(values_vec)
+    }
+
+    async fn call_wasm_async(
+        &self,
+        store: &mut impl AsStoreMut,
+        trampoline: VMTrampoline,
+        params: &[Value],
+        results: &mut [Value],
+    ) -> Result<(), RuntimeError> {
+        let values_vec = self.raw_params(store, params, results)?;
+        let values_vec = self
+            .call_wasm_raw_async(store, trampoline, values_vec)
+            .await?;
+
+        // Load the return values out of `values_vec`.
+        let signature = self.ty(store);
+        for (index, &value_type) in signature.results().iter().enumerate() {
+            unsafe {
+                results[index] = Value::from_raw(store, value_type, values_vec[index]);
+            }
+        }
 
-        // Invoke the call
-        self.call_wasm_raw(store, trampoline, values_vec, results)?;
         Ok(())
     }
 
+    /// Start calling the trampoline asynchronously.
+    ///
+    /// # Safety
+    ///
+    /// `params` must not be moved or dropped before the returned call is.
+    unsafe fn start_call_async(
+        &self,
+        store: &impl AsStoreRef,
+        trampoline: VMTrampoline,
+        params: &mut [RawValue],
+    ) -> AsyncCall<'static, ()> {
+        let storeref = store.as_store_ref();
+        let vm_function = self.handle.get(storeref.objects());
+        let config = storeref.engine().tunables().vmconfig();
+        wasmer_call_trampoline_async(
+            storeref.signal_handler(),
+            config,
+            vm_function.anyfunc.as_ptr().as_ref().vmctx,
+            trampoline,
+            vm_function.anyfunc.as_ptr().as_ref().func_ptr,
+            params.as_mut_ptr() as *mut u8,
+        )
+    }
+
+    /// Call the trampoline asynchronously, returning the buffer holding the
+    /// results.
+    pub(crate) async fn call_wasm_raw_async(
+        &self,
+        store: &mut impl AsStoreMut,
+        trampoline: VMTrampoline,
+        mut params: Vec<RawValue>,
+    ) -> Result<Vec<RawValue>, RuntimeError> {
+        self.call_hook(store, CallHook::CallingWasm)?;
+        // Call the trampoline.
+        let result = {
+            let mut r;
+            loop {
+                let call = unsafe { self.start_call_async(store, trampoline, &mut params) };
+                r = call.await;
+                let store_mut = store.as_store_mut();
+                if let Some(callback) = store_mut.inner.on_called.take() {
+                    match callback(store_mut) {
+                        Ok(wasmer_types::OnCalledAction::InvokeAgain) => {
+                            continue;
+                        }
+                        Ok(wasmer_types::OnCalledAction::Finish) => {
+                            break;
+                        }
+                        Ok(wasmer_types::OnCalledAction::Trap(trap)) => {
+                            return Err(RuntimeError::user(trap));
+                        }
+                        Err(trap) => return Err(RuntimeError::user(trap)),
+                    }
+                }
+                break;
+            }
+            r
+        };
+        let hook_result = self.call_hook(store, CallHook::ReturningFromWasm);
+        if let Err(error) = result {
+            return Err(self.trap_error(store, error));
+        }
+        hook_result?;
+        Ok(params)
+    }
+
     fn call_wasm_raw(
         &self,
         store: &mut impl AsStoreMut,
@@ -267,6 +496,7 @@ impl Function {
         mut params: Vec<RawValue>,
         results: &mut [Value],
     ) -> Result<(), RuntimeError> {
+        self.call_hook(store, CallHook::CallingWasm)?;
         // Call the trampoline.
         let result = {
             let mut r;
@@ -304,9 +534,11 @@ impl Function {
             }
             r
         };
+        let hook_result = self.call_hook(store, CallHook::ReturningFromWasm);
         if let Err(error) = result {
-            return Err(error.into());
+            return Err(self.trap_error(store, error));
         }
+        hook_result?;
 
         // Load the return values out of `values_vec`.
         let signature = self.ty(store);
@@ -319,6 +551,40 @@ impl Function {
         Ok(())
     }
 
+    /// Calls the call hook of the store, if any, with the type of this
+    /// function.
+    pub(crate) fn call_hook(
+        &self,
+        store: &mut impl AsStoreMut,
+        hook: CallHook,
+    ) -> Result<(), RuntimeError> {
+        let mut store = store.as_store_mut();
+        if !store.has_call_hook() {
+            return Ok(());
+        }
+        let ty = self.ty(&store);
+        store.invoke_call_hook(hook, &ty)
+    }
+
+    /// Converts a trap of a call of this function into its error, calling
+    /// the trap hooks of the store.
+    pub(crate) fn trap_error(
+        &self,
+        store: &mut impl AsStoreMut,
+        trap: wasmer_vm::Trap,
+    ) -> RuntimeError {
+        let error = {
+            let store = store.as_store_ref();
+            let vm_function = self.handle.get(store.objects());
+            let vmctx = unsafe { vm_function.anyfunc.as_ptr().as_ref().vmctx };
+            RuntimeError::from_store_trap(&store, trap, vmctx)
+        };
+        store
+            .as_store_mut()
+            .notify_trap(|| Some(crate::Function(self.clone())), &error);
+        error
+    }
+
     pub fn result_arity(&self, store: &impl AsStoreRef) -> usize {
         self.ty(store).results().len()
     }
@@ -341,6 +607,51 @@ impl Function {
         Ok(results.into_boxed_slice())
     }
 
+    pub async fn call_async(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: &[Value],
+    ) -> Result<Box<[Value]>, RuntimeError> {
+        let trampoline = unsafe {
+            self.handle
+                .get(store.as_store_ref().objects())
+                .anyfunc
+                .as_ptr()
+                .as_ref()
+                .call_trampoline
+        };
+        let mut results = vec![Value::null(); self.result_arity(store)];
+        self.call_wasm_async(store, trampoline, params, &mut results)
+            .await?;
+        Ok(results.into_boxed_slice())
+    }
+
+    pub fn call_resumable(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: &[Value],
+    ) -> Result<CallState, RuntimeError> {
+        let trampoline = unsafe {
+            self.handle
+                .get(store.as_store_ref().objects())
+                .anyfunc
+                .as_ptr()
+                .as_ref()
+                .call_trampoline
+        };
+        let results = vec![Value::null(); self.result_arity(store)];
+        let mut values_vec = self.raw_params(store, params, &results)?;
+        self.call_hook(store, CallHook::CallingWasm)?;
+        let call = unsafe { self.start_call_async(store, trampoline, &mut values_vec) };
+        ResumableCall {
+            function: self.clone(),
+            trampoline,
+            call,
+            values_vec,
+        }
+        .resume(store)
+    }
+
     #[doc(hidden)]
     #[allow(missing_docs)]
     pub fn call_raw(
@@ -361,10 +672,44 @@ impl Function {
         Ok(results.into_boxed_slice())
     }
 
+    /// Make sure the function can be called with the Wasm ABI, so that it
+    /// can be used as a funcref.
+    ///
+    /// Dynamic functions only get a Wasm ABI address once they are imported,
+    /// pointing to a dynamic trampoline of the importing module. For the ones
+    /// that haven't been imported yet, a module importing just this function
+    /// is compiled and instantiated to provide the trampoline, which is then
+    /// kept alive by the store.
+    pub(crate) fn ensure_funcref(&self, store: &mut impl AsStoreMut) -> Result<(), RuntimeError> {
+        let vm_function = self.handle.get(store.as_store_ref().objects());
+        if vm_function.kind == VMFunctionKind::Static
+            || !unsafe { vm_function.anyfunc.as_ptr().as_ref() }
+                .func_ptr
+                .is_null()
+        {
+            return Ok(());
+        }
+        let wasm = import_module(&vm_function.signature);
+        let module = crate::Module::from_binary(&store.as_store_ref(), &wasm).map_err(|e| {
+            RuntimeError::new(format!(
+                "a compiler is needed to use dynamic functions as funcrefs: {}",
+                e
+            ))
+        })?;
+        let function = crate::Extern::Function(crate::Function(self.clone()));
+        crate::Instance::new_by_index(store, &module, &[function])
+            .map_err(|e| RuntimeError::new(e.to_string()))?;
+        Ok(())
+    }
+
     pub(crate) fn vm_funcref(&self, store: &impl AsStoreRef) -> VMFuncRef {
         let vm_function = self.handle.get(store.as_store_ref().objects());
-        if vm_function.kind == VMFunctionKind::Dynamic {
-            panic!("dynamic functions cannot be used in tables or as funcrefs");
+        if vm_function.kind == VMFunctionKind::Dynamic
+            && unsafe { vm_function.anyfunc.as_ptr().as_ref() }
+                .func_ptr
+                .is_null()
+        {
+            panic!("dynamic functions must be imported or stored in a table before being used as funcrefs");
         }
         VMFuncRef(vm_function.anyfunc.as_ptr())
     }
@@ -407,9 +752,83 @@ impl Function {
     }
 }
 
+/// A call started by [`Function::call_resumable`] which is suspended.
+pub struct ResumableCall {
+    function: Function,
+    trampoline: VMTrampoline,
+    // Declared before `values_vec` so that it is dropped first.
+    call: AsyncCall<'static, ()>,
+    values_vec: Vec<RawValue>,
+}
+
+/// The suspended call is driven by `ResumableCall::resume`, so there is no
+/// task to wake up.
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+impl ResumableCall {
+    pub fn resume(mut self, store: &mut impl AsStoreMut) -> Result<CallState, RuntimeError> {
+        assert!(
+            self.function.is_from_store(store),
+            "`ResumableCall` resumed with another `Store`"
+        );
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let result = loop {
+            let result = match Pin::new(&mut self.call).poll(&mut cx) {
+                Poll::Pending => {
+                    return Ok(CallState::Suspended(crate::ResumableCall(self)));
+                }
+                Poll::Ready(result) => result,
+            };
+            let store_mut = store.as_store_mut();
+            if let Some(callback) = store_mut.inner.on_called.take() {
+                match callback(store_mut) {
+                    Ok(wasmer_types::OnCalledAction::InvokeAgain) => {
+                        self.call = unsafe {
+                            self.function.start_call_async(
+                                store,
+                                self.trampoline,
+                                &mut self.values_vec,
+                            )
+                        };
+                        continue;
+                    }
+                    Ok(wasmer_types::OnCalledAction::Finish) => {}
+                    Ok(wasmer_types::OnCalledAction::Trap(trap)) => {
+                        return Err(RuntimeError::user(trap));
+                    }
+                    Err(trap) => return Err(RuntimeError::user(trap)),
+                }
+            }
+            break result;
+        };
+        let hook_result = self.function.call_hook(store, CallHook::ReturningFromWasm);
+        if let Err(error) = result {
+            return Err(self.function.trap_error(store, error));
+        }
+        hook_result?;
+
+        let signature = self.function.ty(store);
+        let results = signature
+            .results()
+            .iter()
+            .zip(&self.values_vec)
+            .map(|(ty, raw)| unsafe { Value::from_raw(store, *ty, *raw) })
+            .collect();
+        Ok(CallState::Finished(results))
+    }
+}
+
 /// Host state for a dynamic function.
 pub(crate) struct DynamicFunction<F> {
     func: F,
+    /// Whether `func` must be called on the Wasm stack rather than on the
+    /// host stack, so that it can suspend it.
+    on_wasm_stack: bool,
 }
 
 impl<F> DynamicFunction<F>
@@ -422,8 +841,12 @@ where
         this: &mut VMDynamicFunctionContext<Self>,
         values_vec: *mut RawValue,
     ) {
-        let result =
-            on_host_stack(|| panic::catch_unwind(AssertUnwindSafe(|| (this.ctx.func)(values_vec))));
+        let call = || panic::catch_unwind(AssertUnwindSafe(|| (this.ctx.func)(values_vec)));
+        let result = if this.ctx.on_wasm_stack {
+            call()
+        } else {
+            on_host_stack(call)
+        };
 
         match result {
             Ok(Ok(())) => {}
@@ -452,6 +875,21 @@ where
     }
 }
 
+/// Calls the call hook of the store, if any, for a static host function
+/// with the given parameter types.
+unsafe fn static_call_hook<Rets: WasmTypeList>(
+    raw_store: *mut u8,
+    hook: CallHook,
+    params: &[Type],
+) -> Result<(), RuntimeError> {
+    let mut store = StoreMut::from_raw(raw_store as *mut StoreInner);
+    if !store.has_call_hook() {
+        return Ok(());
+    }
+    let ty = FunctionType::new(params, Rets::wasm_types());
+    on_host_stack(|| store.invoke_call_hook(hook, &ty))
+}
+
 /// Represents a low-level Wasm static host function. See
 /// [`crate::Function::new_typed`] and
 /// [`crate::Function::new_typed_with_env`] to learn more.
@@ -461,6 +899,14 @@ pub(crate) struct StaticFunction<F, T> {
     pub(crate) func: F,
 }
 
+/// Represents a low-level Wasm static host function which gets its data
+/// by reference. See [`crate::Function::new_typed_raw`] to learn more.
+pub(crate) struct RawFunction<F, T> {
+    pub(crate) raw_store: *mut u8,
+    pub(crate) data: UnsafeCell<T>,
+    pub(crate) func: F,
+}
+
 macro_rules! impl_host_function {
         ( [$c_struct_representation:ident]
            $c_struct_name:ident,
@@ -496,6 +942,8 @@ macro_rules! impl_host_function {
                         let result = on_host_stack(|| {
                             // println!("func wrapper1");
                             panic::catch_unwind(AssertUnwindSafe(|| {
+                                let params = [ $( <$x::Native as NativeWasmType>::WASM_TYPE ),* ];
+                                static_call_hook::<Rets>(env.raw_store, CallHook::CallingHost, &params)?;
                                 $(
                                     let $x = FromToNativeWasmType::from_native(NativeWasmTypeInto::from_abi(&mut store, $x));
                                 )*
@@ -506,13 +954,96 @@ macro_rules! impl_host_function {
                                     func_env: env.env.clone(),
                                 };
                                 // println!("func wrapper3");
-                                (env.func)(f_env, $($x),* ).into_result()
+                                let result = (env.func)(f_env, $($x),* ).into_result();
+                                static_call_hook::<Rets>(env.raw_store, CallHook::ReturningFromHost, &params)?;
+                                result.map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)
                             }))
                         });
 
                         match result {
                             Ok(Ok(result)) => return result.into_c_struct(&mut store),
-                            Ok(Err(trap)) => raise_user_trap(Box::new(trap)),
+                            Ok(Err(trap)) => raise_user_trap(trap),
+                            Err(panic) => resume_panic(panic) ,
+                        }
+                    }
+
+                    func_wrapper::< T, $( $x, )* Rets, RetsAsResult, Self > as VMFunctionCallback
+                }
+
+                #[allow(non_snake_case)]
+                fn call_trampoline_address() -> VMTrampoline {
+                    unsafe extern "C" fn call_trampoline<
+                        $( $x: FromToNativeWasmType, )*
+                        Rets: WasmTypeList,
+                    >(
+                        vmctx: *mut VMContext,
+                        body: VMFunctionCallback,
+                        args: *mut RawValue,
+                    ) {
+                            let body: unsafe extern "C" fn(
+                                vmctx: *mut VMContext,
+                                $( $x: <$x::Native as NativeWasmType>::Abi, )*
+                            ) -> Rets::CStruct
+                                = std::mem::transmute(body);
+
+                            let mut _n = 0;
+                            $(
+                                let $x = *args.add(_n).cast();
+                                _n += 1;
+                            )*
+
+                            let results = body(vmctx, $( $x ),*);
+                            Rets::write_c_struct_to_ptr(results, args);
+                    }
+
+                    call_trampoline::<$( $x, )* Rets>
+                }
+
+            }
+
+            // Implement `HostFunction` for a function with a reference to its data that has the
+            // same arity than the tuple.
+            #[allow(unused_parens)]
+            impl< $( $x, )* Rets, RetsAsResult, T: Send + 'static, Func >
+                HostFunction<T, ( $( $x ),* ), Rets, WithRawEnv>
+            for
+                Func
+            where
+                $( $x: FromToNativeWasmType, )*
+                Rets: WasmTypeList,
+                RetsAsResult: IntoResult<Rets>,
+                Func: Fn(&mut T, $( $x , )*) -> RetsAsResult + 'static,
+            {
+                #[allow(non_snake_case)]
+                fn function_callback(&self) -> VMFunctionCallback {
+                    /// This is a function that wraps the real host
+                    /// function. Its address will be used inside the
+                    /// runtime. Unlike the other wrappers, it stays on
+                    /// the Wasm stack.
+                    unsafe extern "C" fn func_wrapper<T: Send + 'static, $( $x, )* Rets, RetsAsResult, Func>( env: &RawFunction<Func, T>, $( $x: <$x::Native as NativeWasmType>::Abi, )* ) -> Rets::CStruct
+                    where
+                        $( $x: FromToNativeWasmType, )*
+                        Rets: WasmTypeList,
+                        RetsAsResult: IntoResult<Rets>,
+                        Func: Fn(&mut T, $( $x , )*) -> RetsAsResult + 'static,
+                    {
+                        let mut store = StoreMut::from_raw(env.raw_store as *mut _);
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                            let params = [ $( <$x::Native as NativeWasmType>::WASM_TYPE ),* ];
+                            static_call_hook::<Rets>(env.raw_store, CallHook::CallingHost, &params)?;
+                            $(
+                                let $x = FromToNativeWasmType::from_native(NativeWasmTypeInto::from_abi(&mut store, $x));
+                            )*
+                            // The function has no access to the store, so
+                            // it can't be reentered while it runs.
+                            let result = (env.func)(&mut *env.data.get(), $($x),* ).into_result();
+                            static_call_hook::<Rets>(env.raw_store, CallHook::ReturningFromHost, &params)?;
+                            result.map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)
+                        }));
+
+                        match result {
+                            Ok(Ok(result)) => return result.into_c_struct(&mut store),
+                            Ok(Err(trap)) => raise_user_trap(trap),
                             Err(panic) => resume_panic(panic) ,
                         }
                     }
@@ -580,16 +1111,20 @@ macro_rules! impl_host_function {
                         let result = on_host_stack(|| {
                             // println!("func wrapper1");
                             panic::catch_unwind(AssertUnwindSafe(|| {
+                                let params = [ $( <$x::Native as NativeWasmType>::WASM_TYPE ),* ];
+                                static_call_hook::<Rets>(env.raw_store, CallHook::CallingHost, &params)?;
                                 $(
                                     let $x = FromToNativeWasmType::from_native(NativeWasmTypeInto::from_abi(&mut store, $x));
                                 )*
-                                (env.func)($($x),* ).into_result()
+                                let result = (env.func)($($x),* ).into_result();
+                                static_call_hook::<Rets>(env.raw_store, CallHook::ReturningFromHost, &params)?;
+                                result.map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)
                             }))
                         });
 
                         match result {
                             Ok(Ok(result)) => return result.into_c_struct(&mut store),
-                            Ok(Err(trap)) => raise_user_trap(Box::new(trap)),
+                            Ok(Err(trap)) => raise_user_trap(trap),
                             Err(panic) => resume_panic(panic) ,
                         }
                     }
@@ -659,3 +1194,44 @@ impl_host_function!([C] S23, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12,
 impl_host_function!([C] S24, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16, A17, A18, A19, A20, A21, A22, A23, A24);
 impl_host_function!([C] S25, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16, A17, A18, A19, A20, A21, A22, A23, A24, A25);
 impl_host_function!([C] S26, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13, A14, A15, A16, A17, A18, A19, A20, A21, A22, A23, A24, A25, A26);
+
+/// The binary of a module only importing a function of type `ty`.
+fn import_module(ty: &FunctionType) -> Vec<u8> {
+    fn leb128(bytes: &mut Vec<u8>, mut value: usize) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                return;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+    fn section(wasm: &mut Vec<u8>, id: u8, contents: &[u8]) {
+        wasm.push(id);
+        leb128(wasm, contents.len());
+        wasm.extend_from_slice(contents);
+    }
+    fn val_types(bytes: &mut Vec<u8>, types: &[Type]) {
+        leb128(bytes, types.len());
+        bytes.extend(types.iter().map(|ty| match ty {
+            Type::I32 => 0x7f,
+            Type::I64 => 0x7e,
+            Type::F32 => 0x7d,
+            Type::F64 => 0x7c,
+            Type::V128 => 0x7b,
+            Type::FuncRef => 0x70,
+            Type::ExternRef => 0x6f,
+        }));
+    }
+
+    let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+    let mut types = vec![0x01, 0x60];
+    val_types(&mut types, ty.params());
+    val_types(&mut types, ty.results());
+    section(&mut wasm, 1, &types);
+    // One import of function type 0 named "" "".
+    section(&mut wasm, 2, &[0x01, 0x00, 0x00, 0x00, 0x00]);
+    wasm
+}
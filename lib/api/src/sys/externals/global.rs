@@ -58,6 +58,9 @@ impl Global {
         if self.ty(store).mutability != Mutability::Var {
             return Err(RuntimeError::new("Attempted to set an immutable global"));
         }
+        if self.is_frozen(store) {
+            return Err(RuntimeError::new("Attempted to set a frozen global"));
+        }
         if val.ty() != self.ty(store).ty {
             return Err(RuntimeError::new(format!(
                 "Attempted to operate on a global of type {expected} as a global of type {found}",
@@ -75,6 +78,27 @@ impl Global {
         Ok(())
     }
 
+    pub fn freeze(&self, store: &mut impl AsStoreMut) {
+        unsafe {
+            self.handle
+                .get_mut(store.objects_mut())
+                .vmglobal()
+                .as_mut()
+                .frozen = 1;
+        }
+    }
+
+    pub fn is_frozen(&self, store: &impl AsStoreRef) -> bool {
+        unsafe {
+            self.handle
+                .get(store.as_store_ref().objects())
+                .vmglobal()
+                .as_ref()
+                .frozen
+                != 0
+        }
+    }
+
     pub(crate) fn from_vm_extern(store: &mut impl AsStoreMut, vm_extern: VMExternGlobal) -> Self {
         Self {
             handle: unsafe {
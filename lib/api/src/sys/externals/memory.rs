@@ -2,6 +2,7 @@ use std::{
     convert::TryInto,
     marker::PhantomData,
     mem::{self, MaybeUninit},
+    ops::Range,
     slice,
 };
 
@@ -52,7 +53,11 @@ impl Memory {
     where
         IntoPages: Into<Pages>,
     {
-        self.handle.get_mut(store.objects_mut()).grow(delta.into())
+        store
+            .objects_mut()
+            .grow_memory(self.handle.internal_handle(), |memory| {
+                memory.grow(delta.into())
+            })
     }
 
     pub fn grow_at_least(
@@ -60,9 +65,11 @@ impl Memory {
         store: &mut impl AsStoreMut,
         min_size: u64,
     ) -> Result<(), MemoryError> {
-        self.handle
-            .get_mut(store.objects_mut())
-            .grow_at_least(min_size)
+        store
+            .objects_mut()
+            .grow_memory(self.handle.internal_handle(), |memory| {
+                memory.grow_at_least(min_size)
+            })
     }
 
     pub fn reset(&self, store: &mut impl AsStoreMut) -> Result<(), MemoryError> {
@@ -70,6 +77,23 @@ impl Memory {
         Ok(())
     }
 
+    pub fn set_read_only(
+        &self,
+        store: &mut impl AsStoreMut,
+        range: Range<u64>,
+        read_only: bool,
+    ) -> Result<(), MemoryError> {
+        let to_usize = |offset: u64| {
+            usize::try_from(offset).map_err(|_| MemoryError::InvalidMemory {
+                reason: format!("the range {range:?} is out of the bounds of the memory"),
+            })
+        };
+        let range = to_usize(range.start)?..to_usize(range.end)?;
+        self.handle
+            .get_mut(store.objects_mut())
+            .set_read_only(range, read_only)
+    }
+
     pub(crate) fn from_vm_extern(store: &impl AsStoreRef, vm_extern: VMExternMemory) -> Self {
         Self {
             handle: unsafe {
@@ -247,6 +271,34 @@ impl<'a> MemoryBuffer<'a> {
         }
         Ok(())
     }
+
+    pub(crate) fn copy_within(
+        &self,
+        src: u64,
+        dest: u64,
+        len: u64,
+    ) -> Result<(), MemoryAccessError> {
+        let src_end = src.checked_add(len).ok_or(MemoryAccessError::Overflow)?;
+        let dest_end = dest.checked_add(len).ok_or(MemoryAccessError::Overflow)?;
+        if src_end.max(dest_end) > self.len.try_into().unwrap() {
+            warn!(
+                "attempted to copy ({} bytes) beyond the bounds of the memory view ({} > {})",
+                len,
+                src_end.max(dest_end),
+                self.len
+            );
+            return Err(MemoryAccessError::HeapOutOfBounds);
+        }
+        // Like the `memory.copy` instruction, the ranges may overlap.
+        unsafe {
+            std::ptr::copy(
+                self.base.add(src as usize),
+                self.base.add(dest as usize),
+                len as usize,
+            );
+        }
+        Ok(())
+    }
 }
 
 // We can't use a normal memcpy here because it has undefined behavior if the
@@ -160,6 +160,11 @@ impl<'a> MemoryView<'a> {
         Ok(())
     }
 
+    /// Copies `src` to `dest` within the memory.
+    pub fn copy_within(&self, src: u64, dest: u64, len: u64) -> Result<(), MemoryAccessError> {
+        self.buffer.copy_within(src, dest, len)
+    }
+
     #[allow(unused)]
     /// Copies the memory and returns it as a vector of bytes
     pub fn copy_to_vec(&self) -> Result<Vec<u8>, MemoryAccessError> {
@@ -1,8 +1,8 @@
 use crate::store::{AsStoreMut, AsStoreRef};
 use crate::sys::engine::NativeEngineExt;
-use crate::TableType;
 use crate::Value;
 use crate::{vm::VMExternTable, ExternRef, Function, RuntimeError};
+use crate::{TableType, Type};
 use wasmer_vm::{StoreHandle, TableElement, Trap, VMExtern, VMTable};
 
 #[derive(Debug, Clone)]
@@ -20,18 +20,28 @@ fn set_table_item(
 
 fn value_to_table_element(
     store: &mut impl AsStoreMut,
+    ty: Type,
     val: Value,
 ) -> Result<wasmer_vm::TableElement, RuntimeError> {
     if !val.is_from_store(store) {
         return Err(RuntimeError::new("cannot pass Value across contexts"));
     }
+    if val.ty() != ty {
+        return Err(RuntimeError::new(format!(
+            "cannot store a value of type `{}` in a table of type `{}`",
+            val.ty(),
+            ty
+        )));
+    }
     Ok(match val {
         Value::ExternRef(extern_ref) => {
             wasmer_vm::TableElement::ExternRef(extern_ref.map(|e| e.vm_externref()))
         }
-        Value::FuncRef(func_ref) => {
-            wasmer_vm::TableElement::FuncRef(func_ref.map(|f| f.vm_funcref(store)))
+        Value::FuncRef(Some(function)) => {
+            function.0.ensure_funcref(store)?;
+            wasmer_vm::TableElement::FuncRef(Some(function.vm_funcref(store)))
         }
+        Value::FuncRef(None) => wasmer_vm::TableElement::FuncRef(None),
         _ => return Err(RuntimeError::new("val is not reference")),
     })
 }
@@ -53,7 +63,7 @@ impl Table {
         ty: TableType,
         init: Value,
     ) -> Result<Self, RuntimeError> {
-        let item = value_to_table_element(&mut store, init)?;
+        let item = value_to_table_element(&mut store, ty.ty, init)?;
         let mut store = store.as_store_mut();
         let tunables = store.engine().tunables();
         let style = tunables.table_style(&ty);
@@ -86,7 +96,7 @@ impl Table {
         index: u32,
         val: Value,
     ) -> Result<(), RuntimeError> {
-        let item = value_to_table_element(store, val)?;
+        let item = value_to_table_element(store, self.ty(store).ty, val)?;
         set_table_item(self.handle.get_mut(store.objects_mut()), index, item)
     }
 
@@ -100,13 +110,46 @@ impl Table {
         delta: u32,
         init: Value,
     ) -> Result<u32, RuntimeError> {
-        let item = value_to_table_element(store, init)?;
+        let item = value_to_table_element(store, self.ty(store).ty, init)?;
         self.handle
             .get_mut(store.objects_mut())
             .grow(delta, item)
             .ok_or_else(|| RuntimeError::new(format!("failed to grow table by `{}`", delta)))
     }
 
+    pub fn fill(
+        &self,
+        store: &mut impl AsStoreMut,
+        index: u32,
+        val: Value,
+        len: u32,
+    ) -> Result<(), RuntimeError> {
+        let item = value_to_table_element(store, self.ty(store).ty, val)?;
+        self.handle
+            .get_mut(store.objects_mut())
+            .fill(index, item, len)
+            .map_err(Into::<Trap>::into)?;
+        Ok(())
+    }
+
+    pub fn copy_from_slice(
+        &self,
+        store: &mut impl AsStoreMut,
+        index: u32,
+        values: &[Value],
+    ) -> Result<(), RuntimeError> {
+        let ty = self.ty(store).ty;
+        let items = values
+            .iter()
+            .map(|val| value_to_table_element(store, ty, val.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.handle
+            .get_mut(store.objects_mut())
+            .copy_from_slice(index, &items)
+            .map_err(Into::<Trap>::into)?;
+        Ok(())
+    }
+
     pub fn copy(
         store: &mut impl AsStoreMut,
         dst_table: &Self,
@@ -120,6 +163,11 @@ impl Table {
                 "cross-`Store` table copies are not supported",
             ));
         }
+        if dst_table.ty(store).ty != src_table.ty(store).ty {
+            return Err(RuntimeError::new(
+                "cannot copy elements between tables of different types",
+            ));
+        }
         if dst_table.handle.internal_handle() == src_table.handle.internal_handle() {
             let table = dst_table.handle.get_mut(store.objects_mut());
             table.copy_within(dst_index, src_index, len)
@@ -1,15 +1,19 @@
 use crate::errors::InstantiationError;
 use crate::exports::Exports;
 use crate::module::Module;
-use wasmer_vm::{StoreHandle, VMInstance};
+use crate::sys::coredump::Coredump;
+use crate::sys::memory_usage::MemoryUsage;
+use crate::sys::snapshot::{InstanceSnapshot, SnapshotError};
+use wasmer_types::Pages;
+use wasmer_vm::{LinearMemory, StoreHandle, VMInstance};
 
 use crate::imports::Imports;
-use crate::store::AsStoreMut;
-use crate::Extern;
+use crate::store::{AsStoreMut, AsStoreRef};
+use crate::{Extern, RuntimeError};
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct Instance {
-    _handle: StoreHandle<VMInstance>,
+    handle: StoreHandle<VMInstance>,
 }
 
 #[cfg(test)]
@@ -45,19 +49,28 @@ impl Instance {
         module: &Module,
         imports: &Imports,
     ) -> Result<(Self, Exports), InstantiationError> {
-        let externs = imports
-            .imports_for_module(module)
-            .map_err(InstantiationError::Link)?;
-        let mut handle = module.0.instantiate(store, &externs)?;
+        let externs = imports.externs_for_module(store, module);
+        if externs.iter().any(Option::is_none) {
+            Self::check_imports(store, module, &externs)?;
+        }
+        let externs = externs.into_iter().flatten().collect::<Vec<_>>();
+        let mut handle = module
+            .0
+            .instantiate(store, &externs)
+            .map_err(|err| Self::instantiation_error(store, err))?;
         let exports = Self::get_exports(store, module, &mut handle);
 
         let instance = Self {
-            _handle: StoreHandle::new(store.objects_mut(), handle),
+            handle: StoreHandle::new(store.objects_mut(), handle),
         };
 
         Ok((instance, exports))
     }
 
+    pub(crate) fn is_from_store(&self, store: &impl AsStoreRef) -> bool {
+        self.handle.store_id() == store.as_store_ref().objects().id()
+    }
+
     #[allow(clippy::result_large_err)]
     pub(crate) fn new_by_index(
         store: &mut impl AsStoreMut,
@@ -65,15 +78,114 @@ impl Instance {
         externs: &[Extern],
     ) -> Result<(Self, Exports), InstantiationError> {
         let externs = externs.to_vec();
-        let mut handle = module.0.instantiate(store, &externs)?;
+        let mut handle = module
+            .0
+            .instantiate(store, &externs)
+            .map_err(|err| Self::instantiation_error(store, err))?;
         let exports = Self::get_exports(store, module, &mut handle);
         let instance = Self {
-            _handle: StoreHandle::new(store.objects_mut(), handle),
+            handle: StoreHandle::new(store.objects_mut(), handle),
         };
 
         Ok((instance, exports))
     }
 
+    /// Converts an error of the instantiation of a module, calling the trap
+    /// hooks of the store if its start function trapped.
+    fn instantiation_error(
+        store: &mut impl AsStoreMut,
+        error: InstantiationError,
+    ) -> InstantiationError {
+        if let InstantiationError::Start(trap) = &error {
+            store.as_store_mut().notify_trap(|| None, trap);
+        }
+        error
+    }
+
+    /// Reports the missing imports along with the incompatible ones.
+    #[allow(clippy::result_large_err)]
+    fn check_imports(
+        store: &mut impl AsStoreMut,
+        module: &Module,
+        externs: &[Option<Extern>],
+    ) -> Result<(), InstantiationError> {
+        if externs
+            .iter()
+            .flatten()
+            .any(|extern_| !extern_.is_from_store(store))
+        {
+            return Err(InstantiationError::DifferentStores);
+        }
+        let vm_externs = externs
+            .iter()
+            .map(|extern_| extern_.as_ref().map(Extern::to_vm_extern))
+            .collect::<Vec<_>>();
+        let vm_externs = vm_externs.iter().map(Option::as_ref).collect::<Vec<_>>();
+        wasmer_compiler::check_imports(module.info(), &vm_externs, store.objects_mut())
+            .map_err(|err| InstantiationError::Link(err.into()))
+    }
+
+    pub(crate) fn coredump(&self, store: &impl AsStoreRef, error: &RuntimeError) -> Coredump {
+        let store = store.as_store_ref();
+        let objects = store.objects();
+        Coredump::capture(objects, self.handle.get(objects), error.trace())
+    }
+
+    pub(crate) fn snapshot(
+        &self,
+        store: &impl AsStoreRef,
+    ) -> Result<InstanceSnapshot, SnapshotError> {
+        InstanceSnapshot::take(store, &self.handle.internal_handle())
+    }
+
+    pub(crate) fn restore(
+        &self,
+        store: &mut impl AsStoreMut,
+        snapshot: &InstanceSnapshot,
+    ) -> Result<(), SnapshotError> {
+        snapshot.restore(store, &self.handle.internal_handle())
+    }
+
+    pub(crate) fn transfer_state(
+        &self,
+        store: &mut impl AsStoreMut,
+        to: &Self,
+    ) -> Result<(), SnapshotError> {
+        InstanceSnapshot::transfer(
+            store,
+            &self.handle.internal_handle(),
+            &to.handle.internal_handle(),
+        )
+    }
+
+    pub(crate) fn memory_sizes(&self, store: &impl AsStoreRef) -> Vec<Pages> {
+        let objects = store.as_store_ref().objects();
+        let instance = self.handle.get(objects);
+        instance
+            .local_memories()
+            .map(|memory| memory.get(objects).size())
+            .collect()
+    }
+
+    pub(crate) fn table_sizes(&self, store: &impl AsStoreRef) -> Vec<u32> {
+        let objects = store.as_store_ref().objects();
+        let instance = self.handle.get(objects);
+        instance
+            .local_tables()
+            .map(|table| table.get(objects).size())
+            .collect()
+    }
+
+    pub(crate) fn memory_usage(&self, store: &impl AsStoreRef, module: &Module) -> MemoryUsage {
+        let objects = store.as_store_ref().objects();
+        MemoryUsage::capture(
+            objects,
+            module.info(),
+            self.handle.get(objects),
+            module.0.data_segments_size(),
+        )
+    }
+
     fn get_exports(
         store: &mut impl AsStoreMut,
         module: &Module,
@@ -0,0 +1,175 @@
+//! Reports of the memory used by instances, to size the limits of the
+//! guests: the sizes of their memories and tables, with the largest sizes
+//! reached by the memories, and the space used by their data and stack.
+
+use std::slice;
+use wasmer_types::entity::EntityRef;
+use wasmer_types::{ExportIndex, GlobalIndex, GlobalInit, ModuleInfo, Pages, Type};
+use wasmer_vm::{LinearMemory, StoreObjects, VMInstance};
+
+/// The memory used by an [`Instance`](crate::Instance), see
+/// [`Instance::memory_usage`](crate::Instance::memory_usage).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The memories of the instance, imported or defined, in the order of
+    /// their index.
+    pub memories: Vec<LinearMemoryUsage>,
+    /// The tables of the instance, imported or defined, in the order of
+    /// their index.
+    pub tables: Vec<TableUsage>,
+    /// The number of bytes of the data segments of the module, active and
+    /// passive.
+    pub data_segments_size: u64,
+    /// The usage of the stack of the first memory, when the module exports
+    /// its `__stack_pointer` global.
+    pub stack: Option<StackUsage>,
+}
+
+/// The size of a linear memory, in pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearMemoryUsage {
+    /// The size of the memory when it was created.
+    pub minimum: Pages,
+    /// The largest size the memory can grow to.
+    pub maximum: Option<Pages>,
+    /// The current size of the memory.
+    pub current: Pages,
+    /// The largest size reached by the memory since it was created, its
+    /// high-water mark.
+    pub peak: Pages,
+}
+
+/// The size of a table, in elements.
+///
+/// Tables never shrink, so their current size is also the largest they
+/// have reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableUsage {
+    /// The size of the table when it was created.
+    pub minimum: u32,
+    /// The largest size the table can grow to.
+    pub maximum: Option<u32>,
+    /// The current size of the table.
+    pub current: u32,
+}
+
+/// The usage of the stack that compilers like LLVM keep in the linear
+/// memory, below the address held by the `__stack_pointer` global, in
+/// bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackUsage {
+    /// The size of the region of the memory reserved for the stack, between
+    /// `__stack_low` (or `__data_end`) and `__stack_high` (or the initial
+    /// stack pointer).
+    pub size: u64,
+    /// The bytes of the stack currently in use.
+    pub current: u64,
+    /// The largest number of bytes of the stack used so far.
+    ///
+    /// The memory of the stack is zeroed until it is used, so this is found
+    /// from the lowest non-zero byte of the stack region. It can be slightly
+    /// underestimated when the deepest frames only wrote zeroes.
+    pub peak: u64,
+}
+
+impl MemoryUsage {
+    /// Measures the memory used by `instance`.
+    pub(crate) fn capture(
+        objects: &StoreObjects,
+        info: &ModuleInfo,
+        instance: &VMInstance,
+        data_segments_size: u64,
+    ) -> Self {
+        // The type of a memory reports its current size as the minimum,
+        // the minimum it was created with comes from the module.
+        let memories = instance
+            .memories()
+            .zip(info.memories.values())
+            .map(|(memory, ty)| {
+                let vm_memory = memory.get(objects);
+                LinearMemoryUsage {
+                    minimum: ty.minimum,
+                    maximum: ty.maximum,
+                    current: vm_memory.size(),
+                    peak: objects.memory_peak(memory),
+                }
+            })
+            .collect();
+
+        let tables = instance
+            .tables()
+            .map(|table| {
+                let table = table.get(objects);
+                TableUsage {
+                    minimum: table.ty().minimum,
+                    maximum: table.ty().maximum,
+                    current: table.size(),
+                }
+            })
+            .collect();
+
+        Self {
+            memories,
+            tables,
+            data_segments_size,
+            stack: stack_usage(objects, info, instance),
+        }
+    }
+}
+
+/// Measures the stack of the first memory, delimited by the globals that
+/// `wasm-ld` generates when they are exported.
+fn stack_usage(
+    objects: &StoreObjects,
+    info: &ModuleInfo,
+    instance: &VMInstance,
+) -> Option<StackUsage> {
+    let exported_global = |name: &str| match info.exports.get(name)? {
+        ExportIndex::Global(index) => Some(*index),
+        _ => None,
+    };
+    let value = |index: GlobalIndex| {
+        let global = instance.globals().nth(index.index())?.get(objects);
+        let val = unsafe { global.vmglobal().as_ref().val };
+        match global.ty().ty {
+            Type::I32 => Some(unsafe { val.u32 } as u64),
+            Type::I64 => Some(unsafe { val.u64 }),
+            _ => None,
+        }
+    };
+    let initial_value = |index: GlobalIndex| {
+        match info.global_initializers.get(info.local_global_index(index)?)? {
+            GlobalInit::I32Const(value) => Some(*value as u32 as u64),
+            GlobalInit::I64Const(value) => Some(*value as u64),
+            _ => None,
+        }
+    };
+
+    let stack_pointer = exported_global("__stack_pointer")?;
+    let current = value(stack_pointer)?;
+    let high = exported_global("__stack_high")
+        .and_then(value)
+        .or_else(|| initial_value(stack_pointer))?;
+    // The data can be above the stack, which then starts at address 0.
+    let low = exported_global("__stack_low")
+        .or_else(|| exported_global("__data_end"))
+        .and_then(value)
+        .filter(|low| *low < high)
+        .unwrap_or(0);
+
+    let memory = instance.memories().next()?.get(objects);
+    let definition = unsafe { memory.vmmemory().as_ref() };
+    let memory = unsafe { slice::from_raw_parts(definition.base, definition.current_length) };
+    let region = memory.get(low as usize..high as usize)?;
+    let deepest = region
+        .iter()
+        .position(|byte| *byte != 0)
+        .map_or(high, |offset| low + offset as u64);
+
+    let current = high.saturating_sub(current);
+    Some(StackUsage {
+        size: high - low,
+        current,
+        peak: (high - deepest).max(current),
+    })
+}
@@ -1,20 +1,35 @@
+pub(crate) mod coredump;
 pub(crate) mod engine;
 pub(crate) mod errors;
+pub(crate) mod events;
 pub(crate) mod extern_ref;
 pub(crate) mod externals;
 pub(crate) mod instance;
 pub(crate) mod mem_access;
+pub(crate) mod memory_usage;
 pub(crate) mod module;
+pub(crate) mod shared_instance;
+pub(crate) mod snapshot;
 pub(super) mod tunables;
 pub(crate) mod typed_function;
 
+pub use crate::sys::coredump::Coredump;
 pub use crate::sys::engine::{get_default_compiler_config, NativeEngineExt};
+pub use crate::sys::events::{HookId, InstanceCreatedEvent, MemoryGrowEvent, TrapEvent};
+pub use crate::sys::memory_usage::{LinearMemoryUsage, MemoryUsage, StackUsage, TableUsage};
+pub use crate::sys::shared_instance::{SharedInstance, SharedInstanceError, SharedInstanceGuard};
+pub use crate::sys::snapshot::{InstanceSnapshot, ReloadError, SnapshotError};
 pub use crate::sys::tunables::BaseTunables;
 #[cfg(feature = "compiler")]
 pub use wasmer_compiler::{
     wasmparser, CompilerConfig, FunctionMiddleware, MiddlewareReaderState, ModuleMiddleware,
 };
-pub use wasmer_compiler::{Artifact, EngineBuilder, Features, Tunables};
+pub use wasmer_compiler::{Artifact, EngineBuilder, Features, ProfilingStrategy, Tunables};
+#[cfg(feature = "artifact-encryption")]
+pub use wasmer_compiler::{
+    ArtifactKeyProvider, CallbackKeyProvider, EnvKeyProvider, KeyProviderError, StaticKeyProvider,
+    ARTIFACT_KEY_LEN,
+};
 #[cfg(feature = "cranelift")]
 pub use wasmer_compiler_cranelift::{Cranelift, CraneliftOptLevel};
 #[cfg(feature = "llvm")]
@@ -1,19 +1,28 @@
+use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
 
 use bytes::Bytes;
 use wasmer_compiler::{Artifact, ArtifactCreate};
 use wasmer_types::{
-    CompileError, DeserializeError, ExportsIterator, ImportsIterator, ModuleInfo, SerializeError,
+    CompileError, DataInitializerLike, DeserializeError, ExportIndex, ExportsIterator, FrameInfo,
+    FunctionIndex, ImportsIterator, ModuleInfo, SerializeError,
 };
 use wasmer_types::{ExportType, ImportType};
+use wasmer_vm::StoreObjects;
 
 use crate::{
     engine::AsEngineRef, sys::engine::NativeEngineExt, vm::VMInstance, AsStoreMut, AsStoreRef,
-    InstantiationError, IntoBytes,
+    Engine, InstantiationError, IntoBytes, RuntimeError,
 };
 
-#[derive(Clone, PartialEq, Eq)]
+/// The globals exported by the modules compiled with the metering
+/// middleware of `wasmer-middlewares`, that use the fuel of the store once
+/// fuel consumption is enabled.
+const METERING_REMAINING_POINTS: &str = "wasmer_metering_remaining_points";
+const METERING_POINTS_EXHAUSTED: &str = "wasmer_metering_points_exhausted";
+
+#[derive(Clone)]
 pub struct Module {
     // The field ordering here is actually significant because of the drop
     // order: we want to drop the artifact before dropping the engine.
@@ -30,8 +39,33 @@ pub struct Module {
     // In the future, this code should be refactored to properly describe the
     // ownership of the code and its metadata.
     artifact: Arc<Artifact>,
+    engine: Engine,
+}
+
+#[cfg(test)]
+mod send_sync_test {
+    use super::*;
+
+    // Only here to statically ensure that `Module` can be shared across
+    // threads. Will fail to compile otherwise.
+    #[allow(dead_code)]
+    fn module_is_send_sync(module: Module) {
+        fn is_send_sync(t: impl Send + Sync) {
+            let _ = t;
+        }
+
+        is_send_sync(module);
+    }
+}
+
+impl PartialEq for Module {
+    fn eq(&self, other: &Self) -> bool {
+        self.artifact == other.artifact
+    }
 }
 
+impl Eq for Module {}
+
 impl Module {
     pub(crate) fn from_binary(
         engine: &impl AsEngineRef,
@@ -57,7 +91,7 @@ impl Module {
     #[cfg(feature = "compiler")]
     fn compile(engine: &impl AsEngineRef, binary: &[u8]) -> Result<Self, CompileError> {
         let artifact = engine.as_engine_ref().engine().0.compile(binary)?;
-        Ok(Self::from_artifact(artifact))
+        Ok(Self::from_artifact(engine, artifact))
     }
 
     #[cfg(not(feature = "compiler"))]
@@ -82,7 +116,7 @@ impl Module {
             .engine()
             .0
             .deserialize_unchecked(bytes.into())?;
-        Ok(Self::from_artifact(artifact))
+        Ok(Self::from_artifact(engine, artifact))
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
@@ -96,7 +130,7 @@ impl Module {
             .engine()
             .0
             .deserialize(bytes.into())?;
-        Ok(Self::from_artifact(artifact))
+        Ok(Self::from_artifact(engine, artifact))
     }
 
     pub unsafe fn deserialize_from_file_unchecked(
@@ -108,7 +142,7 @@ impl Module {
             .engine()
             .0
             .deserialize_from_file_unchecked(path.as_ref())?;
-        Ok(Self::from_artifact(artifact))
+        Ok(Self::from_artifact(engine, artifact))
     }
 
     pub unsafe fn deserialize_from_file(
@@ -120,11 +154,37 @@ impl Module {
             .engine()
             .0
             .deserialize_from_file(path.as_ref())?;
-        Ok(Self::from_artifact(artifact))
+        Ok(Self::from_artifact(engine, artifact))
+    }
+
+    pub(super) fn from_artifact(engine: &impl AsEngineRef, artifact: Arc<Artifact>) -> Self {
+        Self {
+            artifact,
+            engine: engine.as_engine_ref().engine().clone(),
+        }
     }
 
-    pub(super) fn from_artifact(artifact: Arc<Artifact>) -> Self {
-        Self { artifact }
+    pub(crate) fn clone_into(&self, engine: &impl AsEngineRef) -> Result<Self, DeserializeError> {
+        let engine = engine.as_engine_ref();
+        let engine = engine.engine();
+        if wasmer_compiler::Engine::same(&self.engine.0, &engine.0) {
+            return Ok(self.clone());
+        }
+        let (from, to) = (self.engine.0.target(), engine.0.target());
+        if from.triple() != to.triple() {
+            return Err(DeserializeError::Incompatible(format!(
+                "the module was compiled for {}, but the engine targets {}",
+                from.triple(),
+                to.triple()
+            )));
+        }
+        // The compiled code is copied and linked again in the code memory of
+        // the other engine, it isn't recompiled.
+        let bytes = self
+            .serialize()
+            .map_err(|err| DeserializeError::Generic(err.to_string()))?;
+        let artifact = unsafe { engine.0.deserialize(bytes.into()) }?;
+        Ok(Self::from_artifact(engine, artifact))
     }
 
     #[allow(clippy::result_large_err)]
@@ -157,6 +217,7 @@ impl Module {
                     .collect::<Vec<_>>(),
                 objects,
             )?;
+            self.share_fuel(&mut instance_handle, objects);
 
             // After the instance handle is created, we need to initialize
             // the data, call the start function and so. However, if any
@@ -164,12 +225,44 @@ impl Module {
             // as some of the Instance elements may have placed in other
             // instance tables.
             self.artifact
-                .finish_instantiation(config, signal_handler, &mut instance_handle)?;
+                .finish_instantiation(config, signal_handler, &mut instance_handle)
+                .map_err(|error| match error {
+                    wasmer_compiler::InstantiationError::Start(trap) => {
+                        InstantiationError::Start(RuntimeError::from_instance_trap(
+                            store,
+                            trap,
+                            Some(&instance_handle),
+                        ))
+                    }
+                    error => error.into(),
+                })?;
 
             Ok(instance_handle)
         }
     }
 
+    /// Makes the metering globals of a new instance use the fuel of the
+    /// store, if fuel consumption is enabled.
+    fn share_fuel(&self, instance: &mut VMInstance, objects: &StoreObjects) {
+        let (remaining, exhausted) = match objects.fuel_globals() {
+            Some(globals) => globals,
+            None => return,
+        };
+        let module = self.info();
+        for (name, global) in [
+            (METERING_REMAINING_POINTS, remaining),
+            (METERING_POINTS_EXHAUSTED, exhausted),
+        ] {
+            if let Some(ExportIndex::Global(index)) = module.exports.get(name) {
+                if let Some(local_index) = module.local_global_index(*index) {
+                    if module.globals[*index] == *global.get(objects).ty() {
+                        unsafe { instance.share_global(local_index, global, objects) };
+                    }
+                }
+            }
+        }
+    }
+
     pub(crate) fn name(&self) -> Option<&str> {
         self.info().name.as_deref()
     }
@@ -195,6 +288,41 @@ impl Module {
         self.info().custom_sections(name)
     }
 
+    pub(crate) fn custom_section(&self, name: &str) -> Option<&[u8]> {
+        self.info().custom_section(name)
+    }
+
+    pub(crate) fn all_custom_sections(&self) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+        self.info().all_custom_sections()
+    }
+
+    pub(crate) fn function_address_ranges(
+        &self,
+    ) -> impl Iterator<Item = (FunctionIndex, Range<usize>)> + '_ {
+        self.artifact.function_address_ranges()
+    }
+
+    pub(crate) fn symbolicate(&self, pc: usize) -> Option<FrameInfo> {
+        self.artifact.symbolicate(pc)
+    }
+
+    /// The number of bytes of the data segments of the module, active and
+    /// passive.
+    pub(crate) fn data_segments_size(&self) -> u64 {
+        let active = self
+            .artifact
+            .data_initializers()
+            .map(|initializer| initializer.data().len() as u64)
+            .sum::<u64>();
+        let passive = self
+            .info()
+            .passive_data
+            .values()
+            .map(|data| data.len() as u64)
+            .sum::<u64>();
+        active + passive
+    }
+
     pub(crate) fn info(&self) -> &ModuleInfo {
         self.artifact.module_info()
     }
@@ -0,0 +1,217 @@
+use crate::{Instance, Store};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread::{self, ThreadId};
+use thiserror::Error;
+
+/// An error while entering a [`SharedInstance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum SharedInstanceError {
+    /// The instance is already entered by the current thread, for example
+    /// from a host function called by the instance.
+    #[error("the instance is already entered by the current thread")]
+    Reentrant,
+    /// The instance is entered by another thread.
+    #[error("the instance is entered by another thread")]
+    Busy,
+    /// A thread panicked while the instance was entered, so its state may
+    /// be inconsistent.
+    #[error("a thread panicked while the instance was entered")]
+    Poisoned,
+}
+
+/// A [`Store`] and an [`Instance`] of it, which can be shared between
+/// threads.
+///
+/// A `Store` can be used by a single thread at a time, and the instances
+/// of a store can only be used along with it. A `SharedInstance` keeps
+/// both together behind a lock: [`SharedInstance::lock`] waits for the
+/// other threads to be done with the instance, and gives exclusive access
+/// to the store and the instance until the returned guard is dropped.
+///
+/// Handles are cheap to clone, and all the clones refer to the same store
+/// and instance. Entering the instance again from the thread that entered
+/// it, which would otherwise deadlock, fails with
+/// [`SharedInstanceError::Reentrant`].
+///
+/// # Usage
+///
+/// ```
+/// # use wasmer::*;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut store = Store::default();
+/// let module = Module::new(&store, r#"(module
+///     (global $count (mut i32) (i32.const 0))
+///     (func (export "increment") (result i32)
+///         (global.set $count (i32.add (global.get $count) (i32.const 1)))
+///         (global.get $count)))"#)?;
+/// let instance = Instance::new(&mut store, &module, &imports! {})?;
+/// let shared = SharedInstance::new(store, instance);
+///
+/// let threads: Vec<_> = (0..4)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         std::thread::spawn(move || {
+///             let mut guard = shared.lock().unwrap();
+///             let (store, instance) = guard.parts();
+///             let increment: TypedFunction<(), i32> =
+///                 instance.exports.get_typed_function(store, "increment").unwrap();
+///             increment.call(store).unwrap();
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     thread.join().unwrap();
+/// }
+///
+/// let (mut store, instance) = shared.into_inner().unwrap();
+/// let increment: TypedFunction<(), i32> =
+///     instance.exports.get_typed_function(&store, "increment")?;
+/// assert_eq!(increment.call(&mut store)?, 5);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SharedInstance {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    parts: Mutex<(Store, Instance)>,
+    /// The thread which entered the instance, if any.
+    owner: Mutex<Option<ThreadId>>,
+}
+
+impl SharedInstance {
+    /// Creates a `SharedInstance` from an instance and the store it belongs
+    /// to.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `instance` doesn't belong to `store`.
+    pub fn new(store: Store, instance: Instance) -> Self {
+        assert!(
+            instance.is_from_store(&store),
+            "the instance doesn't belong to the store"
+        );
+        Self {
+            inner: Arc::new(Inner {
+                parts: Mutex::new((store, instance)),
+                owner: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Enters the instance, waiting for the thread which entered it, if
+    /// any, to be done with it.
+    pub fn lock(&self) -> Result<SharedInstanceGuard<'_>, SharedInstanceError> {
+        self.check_reentrancy()?;
+        let parts = self
+            .inner
+            .parts
+            .lock()
+            .map_err(|_| SharedInstanceError::Poisoned)?;
+        Ok(self.enter(parts))
+    }
+
+    /// Enters the instance if no other thread entered it, failing with
+    /// [`SharedInstanceError::Busy`] otherwise.
+    pub fn try_lock(&self) -> Result<SharedInstanceGuard<'_>, SharedInstanceError> {
+        self.check_reentrancy()?;
+        let parts = match self.inner.parts.try_lock() {
+            Ok(parts) => parts,
+            Err(TryLockError::WouldBlock) => return Err(SharedInstanceError::Busy),
+            Err(TryLockError::Poisoned(_)) => return Err(SharedInstanceError::Poisoned),
+        };
+        Ok(self.enter(parts))
+    }
+
+    /// Returns the store and the instance if this is the last handle to
+    /// them, or else gives the handle back.
+    ///
+    /// A store whose instance was poisoned by a panic is returned as well.
+    pub fn into_inner(self) -> Result<(Store, Instance), Self> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(inner) => Ok(inner
+                .parts
+                .into_inner()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())),
+            Err(inner) => Err(Self { inner }),
+        }
+    }
+
+    fn check_reentrancy(&self) -> Result<(), SharedInstanceError> {
+        // Only the current thread can store its own id, so this can't race
+        // with the thread which entered the instance.
+        if *self.owner() == Some(thread::current().id()) {
+            return Err(SharedInstanceError::Reentrant);
+        }
+        Ok(())
+    }
+
+    fn enter<'a>(&'a self, parts: MutexGuard<'a, (Store, Instance)>) -> SharedInstanceGuard<'a> {
+        *self.owner() = Some(thread::current().id());
+        SharedInstanceGuard {
+            parts,
+            shared: self,
+        }
+    }
+
+    fn owner(&self) -> MutexGuard<'_, Option<ThreadId>> {
+        self.inner
+            .owner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for SharedInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedInstance")
+            .field("owner", &*self.owner())
+            .finish()
+    }
+}
+
+/// Exclusive access to the store and the instance of a
+/// [`SharedInstance`], until it is dropped.
+pub struct SharedInstanceGuard<'a> {
+    parts: MutexGuard<'a, (Store, Instance)>,
+    shared: &'a SharedInstance,
+}
+
+impl SharedInstanceGuard<'_> {
+    /// Returns the store of the instance.
+    pub fn store(&self) -> &Store {
+        &self.parts.0
+    }
+
+    /// Returns the store of the instance, mutably.
+    pub fn store_mut(&mut self) -> &mut Store {
+        &mut self.parts.0
+    }
+
+    /// Returns the instance.
+    pub fn instance(&self) -> &Instance {
+        &self.parts.1
+    }
+
+    /// Returns the store, mutably, along with the instance, to call the
+    /// functions of the instance.
+    pub fn parts(&mut self) -> (&mut Store, &Instance) {
+        let (store, instance) = &mut *self.parts;
+        (store, instance)
+    }
+}
+
+impl Drop for SharedInstanceGuard<'_> {
+    fn drop(&mut self) {
+        *self.shared.owner() = None;
+    }
+}
+
+impl fmt::Debug for SharedInstanceGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedInstanceGuard").finish()
+    }
+}
@@ -0,0 +1,531 @@
+use crate::store::{AsStoreMut, AsStoreRef};
+use crate::InstantiationError;
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::slice;
+use thiserror::Error;
+use wasmer_types::{
+    ExportIndex, FunctionIndex, ImportIndex, ModuleInfo, Mutability, Pages, RawValue, Type,
+    WASM_PAGE_SIZE,
+};
+use wasmer_vm::{
+    InternalStoreHandle, LinearMemory, StoreObjects, TableElement, VMFuncRef, VMInstance,
+};
+
+/// The magic header of serialized snapshots, followed by the version of
+/// the format.
+const MAGIC: &[u8; 16] = b"\0wasmer-snapshot";
+const VERSION: u32 = 1;
+
+/// The encoding of null references in serialized snapshots.
+const NULL: u32 = u32::MAX;
+
+/// An error while taking, restoring or deserializing an
+/// [`InstanceSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SnapshotError {
+    /// The state of the instance contains a value which can't be captured.
+    #[error("the instance state can't be captured: {0}")]
+    Unsupported(String),
+    /// The snapshot wasn't taken from an instance of the same module.
+    #[error("the snapshot doesn't match the instance: {0}")]
+    Incompatible(String),
+    /// The serialized snapshot is malformed.
+    #[error("invalid snapshot: {0}")]
+    Invalid(String),
+}
+
+/// An error while hot reloading an instance with
+/// [`Instance::reload`](crate::Instance::reload).
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    /// The new module couldn't be instantiated.
+    #[error(transparent)]
+    Instantiation(#[from] InstantiationError),
+    /// The state of the instance can't be carried over to the new instance.
+    #[error(transparent)]
+    State(#[from] SnapshotError),
+}
+
+/// The value of a mutable global in a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GlobalSnapshot {
+    /// The raw bits of a numeric value, or a null `externref`.
+    Raw(u128),
+    /// A function of the instance, by index.
+    FuncRef(Option<u32>),
+}
+
+/// A snapshot of the state of an [`Instance`](crate::Instance): the
+/// contents of the memories and tables it defines, and the values of its
+/// mutable globals.
+///
+/// It is taken using [`Instance::snapshot`](crate::Instance::snapshot),
+/// and can be restored into any instance of the same module with
+/// [`Instance::restore`](crate::Instance::restore), in the same store or
+/// in another one. It can also be serialized, to be restored in another
+/// process.
+///
+/// Functions referenced from tables and globals are recorded by their
+/// index in the module, so they must be functions of the instance, defined
+/// or imported. The imported memories, tables and globals aren't part of
+/// the snapshot, as they are owned by the host or by other instances.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceSnapshot {
+    memories: Vec<Vec<u8>>,
+    tables: Vec<Vec<Option<u32>>>,
+    globals: Vec<(u32, GlobalSnapshot)>,
+}
+
+impl InstanceSnapshot {
+    pub(crate) fn take(
+        store: &impl AsStoreRef,
+        instance: &InternalStoreHandle<VMInstance>,
+    ) -> Result<Self, SnapshotError> {
+        Self::capture(store, instance, true)
+    }
+
+    pub(crate) fn restore(
+        &self,
+        store: &mut impl AsStoreMut,
+        instance: &InternalStoreHandle<VMInstance>,
+    ) -> Result<(), SnapshotError> {
+        self.apply(store, instance, true)
+    }
+
+    /// Carries the memories and mutable globals of the instance `from` over
+    /// to `to`, an instance of a new version of its module. The tables of
+    /// `to` are left as initialized by its module, so that they refer to its
+    /// own functions.
+    ///
+    /// The indices of the functions may differ between the two versions, so
+    /// functions referenced from globals are matched by the name they are
+    /// imported or exported with, and are dropped if `to` has no function
+    /// with that name.
+    pub(crate) fn transfer(
+        store: &mut impl AsStoreMut,
+        from: &InternalStoreHandle<VMInstance>,
+        to: &InternalStoreHandle<VMInstance>,
+    ) -> Result<(), SnapshotError> {
+        let mut snapshot = Self::capture(store, from, false)?;
+        {
+            let store = store.as_store_ref();
+            let objects = store.objects();
+            let old_names = function_names(from.get(objects).module_ref());
+            let new_names = function_names(to.get(objects).module_ref())
+                .into_iter()
+                .collect::<HashMap<_, _>>();
+            for (_, value) in snapshot.globals.iter_mut() {
+                if let GlobalSnapshot::FuncRef(function) = value {
+                    *function = function.and_then(|function| {
+                        old_names
+                            .iter()
+                            .filter(|(_, index)| *index == function)
+                            .find_map(|(name, _)| new_names.get(name).copied())
+                    });
+                }
+            }
+        }
+        snapshot.apply(store, to, false)
+    }
+
+    fn capture(
+        store: &impl AsStoreRef,
+        instance: &InternalStoreHandle<VMInstance>,
+        with_tables: bool,
+    ) -> Result<Self, SnapshotError> {
+        let store = store.as_store_ref();
+        let objects = store.objects();
+        let instance = instance.get(objects);
+        let functions = function_indices(instance);
+        let function_index = |func_ref: Option<VMFuncRef>| match func_ref {
+            None => Ok(None),
+            Some(func_ref) => functions
+                .get(&func_ref_key(func_ref))
+                .map(|index| Some(*index))
+                .ok_or_else(|| {
+                    SnapshotError::Unsupported(
+                        "references to functions of the host or of another instance".to_string(),
+                    )
+                }),
+        };
+
+        let memories = instance
+            .local_memories()
+            .map(|memory| {
+                let definition = unsafe { memory.get(objects).vmmemory().as_ref() };
+                unsafe { slice::from_raw_parts(definition.base, definition.current_length) }
+                    .to_vec()
+            })
+            .collect();
+
+        let tables = instance
+            .local_tables()
+            .filter(|_| with_tables)
+            .map(|table| {
+                let table = table.get(objects);
+                (0..table.size())
+                    .map(|index| match table.get(index) {
+                        Some(TableElement::FuncRef(func_ref)) => function_index(func_ref),
+                        Some(TableElement::ExternRef(None)) => Ok(None),
+                        _ => Err(SnapshotError::Unsupported(
+                            "non-null externrefs".to_string(),
+                        )),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let globals = instance
+            .local_globals()
+            .enumerate()
+            .filter(|(_, global)| global.get(objects).ty().mutability == Mutability::Var)
+            .map(|(index, global)| {
+                let global = global.get(objects);
+                let val = unsafe { global.vmglobal().as_ref().val };
+                let value = match global.ty().ty {
+                    Type::FuncRef => GlobalSnapshot::FuncRef(function_index(unsafe {
+                        VMFuncRef::from_raw(val)
+                    })?),
+                    Type::ExternRef if unsafe { val.externref } != 0 => {
+                        return Err(SnapshotError::Unsupported(
+                            "non-null externrefs".to_string(),
+                        ))
+                    }
+                    _ => GlobalSnapshot::Raw(unsafe { val.u128 }),
+                };
+                Ok((index as u32, value))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            memories,
+            tables,
+            globals,
+        })
+    }
+
+    fn apply(
+        &self,
+        store: &mut impl AsStoreMut,
+        instance: &InternalStoreHandle<VMInstance>,
+        with_tables: bool,
+    ) -> Result<(), SnapshotError> {
+        let mut store = store.as_store_mut();
+        let objects = store.objects_mut();
+        let (memories, tables, globals, func_refs) = {
+            let instance = instance.get(objects);
+            let func_refs = instance
+                .module_ref()
+                .functions
+                .keys()
+                .map(|index| instance.func_ref(index))
+                .collect::<Vec<_>>();
+            (
+                instance.local_memories().collect::<Vec<_>>(),
+                instance
+                    .local_tables()
+                    .filter(|_| with_tables)
+                    .collect::<Vec<_>>(),
+                instance.local_globals().collect::<Vec<_>>(),
+                func_refs,
+            )
+        };
+        self.check(objects, &memories, &tables, &globals, func_refs.len())?;
+        let func_ref = |index: Option<u32>| index.and_then(|index| func_refs[index as usize]);
+
+        for (memory, data) in memories.into_iter().zip(&self.memories) {
+            let size = memory.get(objects).size().bytes().0;
+            if size < data.len() {
+                let delta = ((data.len() - size) / WASM_PAGE_SIZE) as u32;
+                objects
+                    .grow_memory(memory, |memory| memory.grow(delta.into()))
+                    .map_err(|err| SnapshotError::Incompatible(err.to_string()))?;
+            }
+            let definition = unsafe { memory.get(objects).vmmemory().as_ref() };
+            unsafe { slice::from_raw_parts_mut(definition.base, definition.current_length) }
+                .copy_from_slice(data);
+        }
+
+        for (table, elements) in tables.into_iter().zip(&self.tables) {
+            let table = table.get_mut(objects);
+            let null = match table.ty().ty {
+                Type::FuncRef => TableElement::FuncRef(None),
+                _ => TableElement::ExternRef(None),
+            };
+            let delta = elements.len() as u32 - table.size();
+            if delta > 0 && table.grow(delta, null.clone()).is_none() {
+                return Err(SnapshotError::Incompatible(format!(
+                    "the table can't grow to {} elements",
+                    elements.len()
+                )));
+            }
+            for (index, element) in elements.iter().enumerate() {
+                let element = match null {
+                    TableElement::FuncRef(_) => TableElement::FuncRef(func_ref(*element)),
+                    _ => null.clone(),
+                };
+                table.set(index as u32, element).unwrap();
+            }
+        }
+
+        for (index, value) in &self.globals {
+            let global = globals[*index as usize].get(objects);
+            let val = match value {
+                GlobalSnapshot::Raw(bits) => RawValue { u128: *bits },
+                GlobalSnapshot::FuncRef(index) => {
+                    func_ref(*index).map_or(RawValue { funcref: 0 }, |func_ref| func_ref.into_raw())
+                }
+            };
+            unsafe { global.vmglobal().as_mut().val = val };
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the snapshot can be restored into an instance, before
+    /// modifying it.
+    fn check(
+        &self,
+        objects: &StoreObjects,
+        memories: &[InternalStoreHandle<wasmer_vm::VMMemory>],
+        tables: &[InternalStoreHandle<wasmer_vm::VMTable>],
+        globals: &[InternalStoreHandle<wasmer_vm::VMGlobal>],
+        num_functions: usize,
+    ) -> Result<(), SnapshotError> {
+        let incompatible = |reason: String| Err(SnapshotError::Incompatible(reason));
+        if memories.len() != self.memories.len()
+            || tables.len() != self.tables.len()
+            || globals
+                .iter()
+                .filter(|global| global.get(objects).ty().mutability == Mutability::Var)
+                .count()
+                != self.globals.len()
+        {
+            return incompatible("the instance has different memories, tables or globals".into());
+        }
+        for (memory, data) in memories.iter().zip(&self.memories) {
+            let memory = memory.get(objects);
+            let size = memory.size().bytes().0;
+            // Memories can't grow to the whole 32-bit address space, and the
+            // page count must fit in 32 bits for `grow`.
+            let pages = data.len() / WASM_PAGE_SIZE;
+            let maximum = memory.ty().maximum.unwrap_or_else(Pages::max_value);
+            if size > data.len()
+                || data.len() % WASM_PAGE_SIZE != 0
+                || (size < data.len()
+                    && (pages > maximum.0 as usize || pages >= Pages::max_value().0 as usize))
+            {
+                return incompatible(format!(
+                    "a memory of {size} bytes can't be restored to {} bytes",
+                    data.len()
+                ));
+            }
+        }
+        for (table, elements) in tables.iter().zip(&self.tables) {
+            let table = table.get(objects);
+            let size = table.size() as usize;
+            let maximum = table.ty().maximum.unwrap_or(u32::MAX);
+            if size > elements.len() || elements.len() > maximum as usize {
+                return incompatible(format!(
+                    "a table of {size} elements can't be restored to {} elements",
+                    elements.len()
+                ));
+            }
+            let funcref = table.ty().ty == Type::FuncRef;
+            if elements
+                .iter()
+                .flatten()
+                .any(|index| !funcref || *index as usize >= num_functions)
+            {
+                return incompatible("a table refers to an unknown function".into());
+            }
+        }
+        for (index, value) in &self.globals {
+            let ty = match globals.get(*index as usize) {
+                Some(global) => *global.get(objects).ty(),
+                None => return incompatible(format!("there is no global {index}")),
+            };
+            let valid = match value {
+                GlobalSnapshot::FuncRef(index) => {
+                    ty.ty == Type::FuncRef && index.map_or(true, |i| (i as usize) < num_functions)
+                }
+                GlobalSnapshot::Raw(bits) => {
+                    ty.ty != Type::FuncRef
+                        && (ty.ty != Type::ExternRef || *bits == 0)
+                        && ty.mutability == Mutability::Var
+                }
+            };
+            if !valid {
+                return incompatible(format!("the global {index} has a different type"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the snapshot into bytes, which can be deserialized with
+    /// [`InstanceSnapshot::deserialize`].
+    pub fn serialize(&self) -> Bytes {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.memories.len() as u32).to_le_bytes());
+        for data in &self.memories {
+            bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+        bytes.extend_from_slice(&(self.tables.len() as u32).to_le_bytes());
+        for elements in &self.tables {
+            bytes.extend_from_slice(&(elements.len() as u32).to_le_bytes());
+            for element in elements {
+                bytes.extend_from_slice(&element.unwrap_or(NULL).to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&(self.globals.len() as u32).to_le_bytes());
+        for (index, value) in &self.globals {
+            bytes.extend_from_slice(&index.to_le_bytes());
+            match value {
+                GlobalSnapshot::Raw(bits) => {
+                    bytes.push(0);
+                    bytes.extend_from_slice(&bits.to_le_bytes());
+                }
+                GlobalSnapshot::FuncRef(index) => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(&index.unwrap_or(NULL).to_le_bytes());
+                }
+            }
+        }
+        bytes.into()
+    }
+
+    /// Deserializes a snapshot serialized with
+    /// [`InstanceSnapshot::serialize`].
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let mut reader = Reader(bytes);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(SnapshotError::Invalid("not a snapshot".to_string()));
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(SnapshotError::Invalid(format!(
+                "unsupported version {version}"
+            )));
+        }
+        let memories = (0..reader.u32()?)
+            .map(|_| {
+                let len = reader.u64()?;
+                let len = len
+                    .try_into()
+                    .map_err(|_| SnapshotError::Invalid("memory too large".to_string()))?;
+                Ok(reader.take(len)?.to_vec())
+            })
+            .collect::<Result<_, _>>()?;
+        let tables = (0..reader.u32()?)
+            .map(|_| {
+                (0..reader.u32()?)
+                    .map(|_| reader.reference())
+                    .collect::<Result<_, _>>()
+            })
+            .collect::<Result<_, _>>()?;
+        let globals = (0..reader.u32()?)
+            .map(|_| {
+                let index = reader.u32()?;
+                let value = match reader.take(1)?[0] {
+                    0 => GlobalSnapshot::Raw(u128::from_le_bytes(reader.array()?)),
+                    1 => GlobalSnapshot::FuncRef(reader.reference()?),
+                    kind => {
+                        return Err(SnapshotError::Invalid(format!(
+                            "unknown global kind {kind}"
+                        )))
+                    }
+                };
+                Ok((index, value))
+            })
+            .collect::<Result<_, _>>()?;
+        if !reader.0.is_empty() {
+            return Err(SnapshotError::Invalid("trailing bytes".to_string()));
+        }
+        Ok(Self {
+            memories,
+            tables,
+            globals,
+        })
+    }
+}
+
+/// Returns the indices of the functions of an instance, keyed by their
+/// code and context.
+fn function_indices(instance: &VMInstance) -> HashMap<(usize, usize), u32> {
+    instance
+        .module_ref()
+        .functions
+        .keys()
+        .filter_map(|index: FunctionIndex| {
+            let func_ref = instance.func_ref(index)?;
+            Some((func_ref_key(func_ref), index.as_u32()))
+        })
+        .collect()
+}
+
+/// The names under which the functions of a module are imported, as
+/// `(Some(module), field)`, or exported, as `(None, name)`.
+fn function_names(module: &ModuleInfo) -> Vec<((Option<&str>, &str), u32)> {
+    let imports = module
+        .imports
+        .iter()
+        .filter_map(|(key, index)| match index {
+            ImportIndex::Function(index) => Some((
+                (Some(key.module.as_str()), key.field.as_str()),
+                index.as_u32(),
+            )),
+            _ => None,
+        });
+    let exports = module
+        .exports
+        .iter()
+        .filter_map(|(name, index)| match index {
+            ExportIndex::Function(index) => Some(((None, name.as_str()), index.as_u32())),
+            _ => None,
+        });
+    imports.chain(exports).collect()
+}
+
+/// Identifies a function by its code and context, as the same function may
+/// be referenced through different `VMFuncRef`s.
+fn func_ref_key(func_ref: VMFuncRef) -> (usize, usize) {
+    let anyfunc = unsafe { func_ref.0.as_ref() };
+    (anyfunc.func_ptr as usize, unsafe {
+        anyfunc.vmctx.host_env as usize
+    })
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.0.len() < len {
+            return Err(SnapshotError::Invalid("unexpected end".to_string()));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn reference(&mut self) -> Result<Option<u32>, SnapshotError> {
+        let index = self.u32()?;
+        Ok(if index == NULL { None } else { Some(index) })
+    }
+}
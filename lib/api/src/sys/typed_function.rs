@@ -2,7 +2,7 @@ use crate::{FromToNativeWasmType, RuntimeError, TypedFunction, WasmTypeList};
 use wasmer_types::RawValue;
 
 use crate::native_type::NativeWasmTypeInto;
-use crate::store::{AsStoreMut, AsStoreRef};
+use crate::store::{AsStoreMut, AsStoreRef, CallHook};
 use crate::sys::engine::NativeEngineExt;
 
 macro_rules! impl_native_traits {
@@ -48,6 +48,7 @@ macro_rules! impl_native_traits {
                     rets_list.as_mut()
                 };
 
+                self.func.0.call_hook(store, CallHook::CallingWasm)?;
                 let mut r;
                 loop {
                     let storeref = store.as_store_ref();
@@ -73,7 +74,9 @@ macro_rules! impl_native_traits {
                     }
                     break;
                 }
-                r?;
+                let hook_result = self.func.0.call_hook(store, CallHook::ReturningFromWasm);
+                r.map_err(|trap| self.func.0.trap_error(store, trap))?;
+                hook_result?;
 
                 let num_rets = rets_list.len();
                 if !using_rets_array && num_rets > 0 {
@@ -101,6 +104,38 @@ macro_rules! impl_native_traits {
                 // Ok(Rets::from_c_struct(results))
             }
 
+            /// Call the typed func asynchronously and return results.
+            ///
+            /// See [`crate::Function::call_async`].
+            #[allow(clippy::too_many_arguments)]
+            pub async fn call_async(&self, store: &mut impl AsStoreMut, $( $x: $x, )* ) -> Result<Rets, RuntimeError> {
+                let trampoline = unsafe {
+                    self.func.0
+                        .handle
+                        .get(store.as_store_ref().objects())
+                        .anyfunc
+                        .as_ptr()
+                        .as_ref()
+                        .call_trampoline
+                };
+                // Ensure all parameters come from the same context.
+                if $(!FromToNativeWasmType::is_from_store(&$x, store) ||)* false {
+                    return Err(RuntimeError::new(
+                        "cross-`Store` values are not supported",
+                    ));
+                }
+                let mut rets_list_array = Rets::empty_array();
+                let num_rets = rets_list_array.as_mut().len();
+                let mut params_list = vec![ $( $x.to_native().into_raw(store) ),* ];
+                if params_list.len() < num_rets {
+                    params_list.resize(num_rets, RawValue { i32: 0 });
+                }
+
+                let values_vec = self.func.0.call_wasm_raw_async(store, trampoline, params_list).await?;
+                rets_list_array.as_mut().copy_from_slice(&values_vec[..num_rets]);
+                Ok(unsafe { Rets::from_array(store, rets_list_array) })
+            }
+
             #[doc(hidden)]
             #[allow(missing_docs)]
             #[allow(unused_mut)]
@@ -130,6 +165,7 @@ macro_rules! impl_native_traits {
                     rets_list.as_mut()
                 };
 
+                self.func.0.call_hook(store, CallHook::CallingWasm)?;
                 let mut r;
                 loop {
                     let storeref = store.as_store_ref();
@@ -156,7 +192,9 @@ macro_rules! impl_native_traits {
                     }
                     break;
                 }
-                r?;
+                let hook_result = self.func.0.call_hook(store, CallHook::ReturningFromWasm);
+                r.map_err(|trap| self.func.0.trap_error(store, trap))?;
+                hook_result?;
 
                 let num_rets = rets_list.len();
                 if !using_rets_array && num_rets > 0 {
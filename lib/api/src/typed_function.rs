@@ -7,10 +7,10 @@
 //! let add_one = instance.exports.get_function("function_name")?;
 //! let add_one_native: TypedFunction<i32, i32> = add_one.native().unwrap();
 //! ```
-use crate::{Function, WasmTypeList};
+use crate::{FromToNativeWasmType, Function, RuntimeError, Value, WasmTypeList};
 use std::marker::PhantomData;
 
-use crate::store::AsStoreRef;
+use crate::store::{AsStoreMut, AsStoreRef};
 
 /// A WebAssembly function that can be called natively
 /// (using the Native ABI).
@@ -40,3 +40,60 @@ where
         self.func
     }
 }
+
+// Functions taking an array of parameters go through `Function::call`,
+// as they can have more parameters than the largest tuple.
+impl<T, const N: usize, Rets> TypedFunction<[T; N], Rets>
+where
+    T: FromToNativeWasmType,
+    Rets: WasmTypeList,
+{
+    /// Call the typed func and return results.
+    pub fn call(&self, store: &mut impl AsStoreMut, params: [T; N]) -> Result<Rets, RuntimeError> {
+        let params = self.params_to_values(store, params)?;
+        let results = self.func.call(store, &params)?;
+        self.results_from_values(store, &results)
+    }
+
+    /// Call the typed func asynchronously and return results.
+    ///
+    /// See [`crate::Function::call_async`].
+    #[cfg(feature = "sys")]
+    pub async fn call_async(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: [T; N],
+    ) -> Result<Rets, RuntimeError> {
+        let params = self.params_to_values(store, params)?;
+        let results = self.func.call_async(store, &params).await?;
+        self.results_from_values(store, &results)
+    }
+
+    fn params_to_values(
+        &self,
+        store: &mut impl AsStoreMut,
+        params: [T; N],
+    ) -> Result<Vec<Value>, RuntimeError> {
+        // Ensure all parameters come from the same context.
+        if params.iter().any(|param| !param.is_from_store(store)) {
+            return Err(RuntimeError::new("cross-`Store` values are not supported"));
+        }
+        let types = <[T; N]>::wasm_types();
+        let raw = unsafe { params.into_array(store) };
+        Ok(types
+            .iter()
+            .zip(raw)
+            .map(|(ty, raw)| unsafe { Value::from_raw(store, *ty, raw) })
+            .collect())
+    }
+
+    fn results_from_values(
+        &self,
+        store: &mut impl AsStoreMut,
+        results: &[Value],
+    ) -> Result<Rets, RuntimeError> {
+        let raw: Vec<_> = results.iter().map(|value| value.as_raw(store)).collect();
+        unsafe { Rets::from_slice(store, &raw) }
+            .map_err(|_| RuntimeError::new("unexpected number of results"))
+    }
+}
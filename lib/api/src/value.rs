@@ -240,6 +240,12 @@ impl From<f64> for Value {
     }
 }
 
+impl From<u128> for Value {
+    fn from(val: u128) -> Self {
+        Self::V128(val)
+    }
+}
+
 impl From<Function> for Value {
     fn from(val: Function) -> Self {
         Self::FuncRef(Some(val))
@@ -268,6 +274,7 @@ const NOT_I32: &str = "Value is not of Wasm type i32";
 const NOT_I64: &str = "Value is not of Wasm type i64";
 const NOT_F32: &str = "Value is not of Wasm type f32";
 const NOT_F64: &str = "Value is not of Wasm type f64";
+const NOT_V128: &str = "Value is not of Wasm type v128";
 const NOT_FUNCREF: &str = "Value is not of Wasm type funcref";
 const NOT_EXTERNREF: &str = "Value is not of Wasm type externref";
 
@@ -319,6 +326,14 @@ impl TryFrom<Value> for f64 {
     }
 }
 
+impl TryFrom<Value> for u128 {
+    type Error = &'static str;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value.v128().ok_or(NOT_V128)
+    }
+}
+
 impl TryFrom<Value> for Option<Function> {
     type Error = &'static str;
 
@@ -472,4 +487,15 @@ mod tests {
         let result = f64::try_from(value);
         assert_eq!(result.unwrap_err(), "Value is not of Wasm type f64");
     }
+
+    #[test]
+    fn convert_value_to_u128() {
+        let value = Value::from(u128::MAX);
+        let result = u128::try_from(value);
+        assert_eq!(result.unwrap(), u128::MAX);
+
+        let value = Value::I64(42);
+        let result = u128::try_from(value);
+        assert_eq!(result.unwrap_err(), "Value is not of Wasm type v128");
+    }
 }
@@ -0,0 +1,240 @@
+#![cfg(feature = "sys")]
+
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use wasmer::*;
+
+/// A future which is pending the first time it is polled.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn thread_waker() -> Waker {
+    Arc::new(ThreadWaker(thread::current())).into()
+}
+
+/// Runs a future to completion on the current thread, returning its output
+/// and the number of times it was polled.
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let waker = thread_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    let mut polls = 0;
+    loop {
+        polls += 1;
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return (output, polls),
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+const WAT: &str = r#"(module
+    (import "host" "sleep_add" (func $sleep_add (param i32 i32) (result i32)))
+    (func (export "run") (param i32) (result i32)
+        (call $sleep_add (call $sleep_add (local.get 0) (i32.const 1)) (i32.const 2))))"#;
+
+fn instantiate(store: &mut Store, calls: Arc<AtomicUsize>) -> Result<Instance, String> {
+    let module = Module::new(store, WAT).map_err(|e| format!("{e:?}"))?;
+    let ty = FunctionType::new(vec![Type::I32, Type::I32], vec![Type::I32]);
+    let sleep_add = Function::new_async(store, ty, move |args| {
+        let calls = calls.clone();
+        Box::pin(async move {
+            YieldOnce(false).await;
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![Value::I32(
+                args[0].unwrap_i32() + args[1].unwrap_i32(),
+            )])
+        })
+    });
+    let imports = imports! {
+        "host" => {
+            "sleep_add" => sleep_add,
+        },
+    };
+    Instance::new(store, &module, &imports).map_err(|e| format!("{e:?}"))
+}
+
+#[test]
+fn async_host_function_suspends_wasm() -> Result<(), String> {
+    let mut store = Store::default();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let instance = instantiate(&mut store, calls.clone())?;
+    let run: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let (result, polls) = block_on(run.call_async(&mut store, 39));
+    assert_eq!(result.map_err(|e| format!("{e:?}"))?, 42);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    // Each host call was pending once.
+    assert_eq!(polls, 3);
+
+    let run = instance
+        .exports
+        .get_function("run")
+        .map_err(|e| format!("{e:?}"))?;
+    let (result, _) = block_on(run.call_async(&mut store, &[Value::I32(0)]));
+    assert_eq!(
+        result.map_err(|e| format!("{e:?}"))?.to_vec(),
+        vec![Value::I32(3)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn async_call_resumes_on_another_thread() -> Result<(), String> {
+    let mut store = Store::default();
+    let instance = instantiate(&mut store, Arc::new(AtomicUsize::new(0)))?;
+    let run: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let mut call = Box::pin(run.call_async(&mut store, 1));
+    let waker = thread_waker();
+    assert!(call
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker))
+        .is_pending());
+    let result = thread::scope(|scope| scope.spawn(move || block_on(call).0).join().unwrap());
+    assert_eq!(result.map_err(|e| format!("{e:?}"))?, 4);
+
+    // Abandoning a suspended call doesn't prevent further calls.
+    let mut call = Box::pin(run.call_async(&mut store, 1));
+    assert!(call
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker))
+        .is_pending());
+    drop(call);
+    let (result, _) = block_on(run.call_async(&mut store, 2));
+    assert_eq!(result.map_err(|e| format!("{e:?}"))?, 5);
+
+    Ok(())
+}
+
+#[test]
+fn async_host_function_called_synchronously_traps() -> Result<(), String> {
+    let mut store = Store::default();
+    let instance = instantiate(&mut store, Arc::new(AtomicUsize::new(0)))?;
+    let run: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let err = run.call(&mut store, 1).unwrap_err();
+    assert!(err.message().contains("call_async"), "{}", err.message());
+
+    Ok(())
+}
+
+#[test]
+fn resumable_call_returns_control_to_embedder() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let env = FunctionEnv::new(&mut store, 0);
+    let ty = FunctionType::new(vec![Type::I32, Type::I32], vec![Type::I32]);
+    let sleep_add = Function::new_with_env_async(&mut store, &env, ty, |mut env, args| {
+        *env.data_mut() += 1;
+        Box::pin(async move {
+            yield_now().await;
+            Ok(vec![Value::I32(
+                args[0].unwrap_i32() + args[1].unwrap_i32(),
+            )])
+        })
+    });
+    let imports = imports! {
+        "host" => {
+            "sleep_add" => sleep_add,
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+    let run = instance
+        .exports
+        .get_function("run")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let mut state = run
+        .call_resumable(&mut store, &[Value::I32(39)])
+        .map_err(|e| format!("{e:?}"))?;
+    let mut suspensions = 0;
+    let results = loop {
+        match state {
+            CallState::Finished(results) => break results,
+            CallState::Suspended(call) => {
+                suspensions += 1;
+                // The store can be used while the call is suspended.
+                assert_eq!(*env.as_ref(&store), suspensions);
+                state = call.resume(&mut store).map_err(|e| format!("{e:?}"))?;
+            }
+        }
+    };
+    assert_eq!(results.to_vec(), vec![Value::I32(42)]);
+    assert_eq!(suspensions, 2);
+
+    // Abandoning a suspended call doesn't prevent further calls.
+    let state = run
+        .call_resumable(&mut store, &[Value::I32(1)])
+        .map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(state, CallState::Suspended(_)));
+    drop(state);
+    let (result, _) = block_on(run.call_async(&mut store, &[Value::I32(2)]));
+    assert_eq!(
+        result.map_err(|e| format!("{e:?}"))?.to_vec(),
+        vec![Value::I32(5)]
+    );
+
+    // Calls which don't suspend finish right away, and traps are reported.
+    let module = Module::new(
+        &store,
+        r#"(module
+            (func (export "answer") (result i32) (i32.const 42))
+            (func (export "fail") unreachable))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let answer = instance
+        .exports
+        .get_function("answer")
+        .map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(
+        answer.call_resumable(&mut store, &[]),
+        Ok(CallState::Finished(results)) if results[..] == [Value::I32(42)]
+    ));
+    let fail = instance
+        .exports
+        .get_function("fail")
+        .map_err(|e| format!("{e:?}"))?;
+    let err = fail.call_resumable(&mut store, &[]).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::UnreachableCodeReached));
+
+    Ok(())
+}
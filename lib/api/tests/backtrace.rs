@@ -0,0 +1,127 @@
+#![cfg(feature = "sys")]
+
+use wasmer::sys::NativeEngineExt;
+use wasmer::*;
+
+const WAT: &str = r#"(module $app
+    (func $inner (unreachable))
+    (func (export "run") (call $inner)))"#;
+
+fn run_trap(store: &mut Store, module: &Module) -> Result<RuntimeError, String> {
+    let instance = Instance::new(store, module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let run: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+    run.call(store).err().ok_or("expected a trap".to_string())
+}
+
+fn check_trace(error: &RuntimeError, module_name: &str) {
+    let trace = error.trace();
+    assert_eq!(trace.len(), 2);
+
+    assert_eq!(trace[0].module_name(), module_name);
+    assert_eq!(trace[0].func_index(), 0);
+    // Named by the `name` section.
+    assert_eq!(trace[0].function_name(), Some("inner"));
+
+    assert_eq!(trace[1].module_name(), module_name);
+    assert_eq!(trace[1].func_index(), 1);
+    // Named by its export.
+    assert_eq!(trace[1].function_name(), Some("run"));
+    // The call instruction comes after the unreachable one in the module.
+    assert!(trace[1].module_offset() > trace[0].module_offset());
+    assert!(trace[1].func_offset() > 0);
+
+    let message = error.to_string();
+    assert!(
+        message.contains(&format!(
+            "at inner ({module_name}[0]:0x{:x})",
+            trace[0].module_offset()
+        )),
+        "{message}"
+    );
+    assert!(
+        message.contains(&format!(
+            "at run ({module_name}[1]:0x{:x})",
+            trace[1].module_offset()
+        )),
+        "{message}"
+    );
+}
+
+#[test]
+fn backtrace_is_symbolicated() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let error = run_trap(&mut store, &module)?;
+    check_trace(&error, "app");
+    Ok(())
+}
+
+#[test]
+fn backtrace_uses_renamed_module() -> Result<(), String> {
+    let mut store = Store::default();
+    let mut module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    assert!(module.set_name("renamed"));
+    let error = run_trap(&mut store, &module)?;
+    check_trace(&error, "renamed");
+    Ok(())
+}
+
+#[test]
+fn backtrace_of_headless_module() -> Result<(), String> {
+    let store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let serialized = module.serialize().map_err(|e| format!("{e:?}"))?;
+
+    let mut store = Store::new(Engine::headless());
+    let module =
+        unsafe { Module::deserialize(&store, serialized) }.map_err(|e| format!("{e:?}"))?;
+    let error = run_trap(&mut store, &module)?;
+    check_trace(&error, "app");
+    Ok(())
+}
+
+/// The module of [`WAT`], with the DWARF debugging information of a
+/// `src/lib.rs` where `inner` is at line 42 and `run` at line 43.
+const WAT_WITH_DWARF: &str = r#"(module $app
+    (func $inner (unreachable))
+    (func (export "run") (call $inner))
+    (@custom ".debug_abbrev" "\01\11\00\10\17\00\00\00")
+    (@custom ".debug_info" "\0c\00\00\00\04\00\00\00\00\00\04\01\00\00\00\00")
+    (@custom ".debug_line"
+        "\3e\00\00\00\04\00\22\00\00\00\01\01\01\fb\0e\0d"
+        "\00\01\01\01\01\00\00\00\01\00\00\01"
+        "src\00\00"
+        "lib.rs\00\01\00\00\00"
+        "\00\05\02\00\00\00\00\03\29\05\05\01"
+        "\02\05\03\01\01"
+        "\02\0b\00\01\01"))"#;
+
+#[test]
+fn backtrace_has_source_locations() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT_WITH_DWARF).map_err(|e| format!("{e:?}"))?;
+    let error = run_trap(&mut store, &module)?;
+    check_trace(&error, "app");
+
+    let trace = error.trace();
+    assert_eq!(
+        trace[0].source_location(),
+        Some(&SourceLocation::new("src/lib.rs".to_string(), 42, Some(5)))
+    );
+    assert_eq!(
+        trace[1].source_location(),
+        Some(&SourceLocation::new("src/lib.rs".to_string(), 43, Some(5)))
+    );
+    let message = error.to_string();
+    assert!(message.contains("at src/lib.rs:42:5"), "{message}");
+    assert!(message.contains("at src/lib.rs:43:5"), "{message}");
+
+    // Modules without debugging information have no source locations.
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let error = run_trap(&mut store, &module)?;
+    assert!(error.trace().iter().all(|frame| frame.source_location().is_none()));
+    Ok(())
+}
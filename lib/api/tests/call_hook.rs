@@ -0,0 +1,109 @@
+#![cfg(feature = "sys")]
+
+use std::sync::{Arc, Mutex};
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (import "env" "typed" (func $typed (param i32) (result i32)))
+    (import "env" "dynamic" (func $dynamic (param i64)))
+    (func (export "run") (param i32) (result i32)
+        (call $dynamic (i64.const 1))
+        (call $typed (local.get 0))))"#;
+
+type Events = Arc<Mutex<Vec<(CallHook, FunctionType)>>>;
+
+fn instantiate(store: &mut Store) -> Result<Instance, String> {
+    let module = Module::new(store, WAT).map_err(|e| format!("{e:?}"))?;
+    let imports = imports! {
+        "env" => {
+            "typed" => Function::new_typed(store, |x: i32| x + 1),
+            "dynamic" => Function::new(store, FunctionType::new([Type::I64], []), |_| Ok(vec![])),
+        },
+    };
+    Instance::new(store, &module, &imports).map_err(|e| format!("{e:?}"))
+}
+
+#[test]
+fn call_hook_sees_every_boundary() -> Result<(), String> {
+    let mut store = Store::default();
+    let instance = instantiate(&mut store)?;
+    let run: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let events = Events::default();
+    let recorded = events.clone();
+    store.call_hook(move |_, hook, ty| {
+        recorded.lock().unwrap().push((hook, ty.clone()));
+        Ok(())
+    });
+    assert_eq!(run.call(&mut store, 41).map_err(|e| format!("{e:?}"))?, 42);
+
+    let run_ty = FunctionType::new([Type::I32], [Type::I32]);
+    let typed_ty = FunctionType::new([Type::I32], [Type::I32]);
+    let dynamic_ty = FunctionType::new([Type::I64], []);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            (CallHook::CallingWasm, run_ty.clone()),
+            (CallHook::CallingHost, dynamic_ty.clone()),
+            (CallHook::ReturningFromHost, dynamic_ty),
+            (CallHook::CallingHost, typed_ty.clone()),
+            (CallHook::ReturningFromHost, typed_ty),
+            (CallHook::ReturningFromWasm, run_ty.clone()),
+        ]
+    );
+
+    // Untyped calls are hooked too.
+    events.lock().unwrap().clear();
+    let run = instance
+        .exports
+        .get_function("run")
+        .map_err(|e| format!("{e:?}"))?;
+    run.call(&mut store, &[Value::I32(1)])
+        .map_err(|e| format!("{e:?}"))?;
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 6);
+    assert_eq!(events[0], (CallHook::CallingWasm, run_ty.clone()));
+    assert_eq!(events[5], (CallHook::ReturningFromWasm, run_ty));
+
+    Ok(())
+}
+
+#[test]
+fn call_hook_errors_fail_the_call() -> Result<(), String> {
+    let mut store = Store::default();
+    let instance = instantiate(&mut store)?;
+    let run: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+
+    // Entering WebAssembly can be refused.
+    store.call_hook(|_, hook, _| match hook {
+        CallHook::CallingWasm => Err(RuntimeError::new("not now")),
+        _ => Ok(()),
+    });
+    let err = run.call(&mut store, 1).unwrap_err();
+    assert_eq!(err.message(), "not now");
+
+    // Failing when a host function is called traps in WebAssembly, and
+    // WebAssembly still reports returning.
+    let returned = Arc::new(Mutex::new(false));
+    let returned2 = returned.clone();
+    store.call_hook(move |_, hook, ty| match hook {
+        CallHook::CallingHost if ty.params() == [Type::I32] => Err(RuntimeError::new("reentrancy")),
+        CallHook::ReturningFromWasm => {
+            *returned2.lock().unwrap() = true;
+            Ok(())
+        }
+        _ => Ok(()),
+    });
+    let err = run.call(&mut store, 1).unwrap_err();
+    assert_eq!(err.message(), "reentrancy");
+    assert!(*returned.lock().unwrap());
+
+    Ok(())
+}
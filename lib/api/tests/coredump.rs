@@ -0,0 +1,63 @@
+#![cfg(feature = "sys")]
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (memory (export "memory") 1)
+    (global $counter (mut i32) (i32.const 0))
+    (global $scale f64 (f64.const 1.5))
+    (func $fail
+        unreachable)
+    (func (export "crash")
+        (global.set $counter (i32.const 7))
+        (i32.store (i32.const 100) (i32.const 0xdead))
+        (call $fail)))"#;
+
+fn crash(store: &mut Store) -> Result<(Instance, RuntimeError), String> {
+    let module = Module::new(store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = Instance::new(store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let crash: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(store, "crash")
+        .map_err(|e| format!("{e:?}"))?;
+    let error = crash.call(store).unwrap_err();
+    Ok((instance, error))
+}
+
+fn check_coredump(store: &Store, coredump: &Coredump) -> Result<(), String> {
+    let memory = coredump.memory(0).unwrap();
+    assert_eq!(memory.len(), WASM_PAGE_SIZE);
+    assert_eq!(memory[100..104], 0xdead_u32.to_le_bytes());
+    assert_eq!(coredump.global(0), Some(Value::I32(7)));
+    assert_eq!(coredump.global(1), Some(Value::F64(1.5)));
+    assert_eq!(coredump.global(2), None);
+
+    // The coredump is a valid module, with the process and the stack in
+    // custom sections.
+    let module = Module::new(store, coredump.serialize()).map_err(|e| format!("{e:?}"))?;
+    assert_eq!(module.custom_sections("core").count(), 1);
+    assert_eq!(module.custom_sections("corestack").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn coredump_on_trap() -> Result<(), String> {
+    let mut store = Store::default();
+    store.engine().set_coredump_on_trap(true);
+    let (_, error) = crash(&mut store)?;
+    assert_eq!(error.clone().to_trap(), Some(TrapCode::UnreachableCodeReached));
+    let coredump = error.coredump().unwrap();
+    check_coredump(&store, coredump)
+}
+
+#[test]
+fn coredump_of_instance() -> Result<(), String> {
+    let mut store = Store::default();
+    let (instance, error) = crash(&mut store)?;
+    // Coredumps are only captured on traps when enabled.
+    assert!(error.coredump().is_none());
+    let mut coredump = instance.coredump(&store, &error);
+    coredump.set_executable_name("crash.wasm");
+    assert_eq!(coredump.executable_name(), "crash.wasm");
+    check_coredump(&store, &coredump)
+}
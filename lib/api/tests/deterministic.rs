@@ -0,0 +1,53 @@
+#![cfg(all(feature = "sys", feature = "compiler"))]
+
+use wasmer::*;
+
+const CANONICAL_NAN: u32 = 0x7fc0_0000;
+
+fn check_deterministic(config: impl CompilerConfig + 'static) -> Result<(), String> {
+    let mut store = Store::new(wasmer::sys::EngineBuilder::new(config).deterministic());
+    let module = Module::new(
+        &store,
+        r#"(module
+            (func (export "div") (param f32 f32) (result i32)
+                (i32.reinterpret_f32 (f32.div (local.get 0) (local.get 1)))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let div: TypedFunction<(f32, f32), i32> = instance
+        .exports
+        .get_typed_function(&store, "div")
+        .map_err(|e| format!("{e:?}"))?;
+    let nan = div
+        .call(&mut store, 0.0, 0.0)
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(nan as u32, CANONICAL_NAN);
+    assert_eq!(
+        div.call(&mut store, 1.0, 2.0)
+            .map_err(|e| format!("{e:?}"))?,
+        0.5f32.to_bits() as i32
+    );
+
+    // Shared memories and atomics are rejected.
+    assert!(Module::new(&store, "(module (memory 1 1 shared))").is_err());
+    assert!(Module::new(
+        &store,
+        r#"(module (memory 1)
+            (func (result i32) (i32.atomic.load (i32.const 0))))"#
+    )
+    .is_err());
+    Ok(())
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn deterministic_cranelift() -> Result<(), String> {
+    check_deterministic(Cranelift::default())
+}
+
+#[cfg(feature = "singlepass")]
+#[test]
+fn deterministic_singlepass() -> Result<(), String> {
+    check_deterministic(Singlepass::default())
+}
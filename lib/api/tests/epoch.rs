@@ -0,0 +1,144 @@
+#![cfg(all(feature = "sys", feature = "compiler"))]
+
+use std::thread;
+use std::time::Duration;
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (func (export "spin") (loop (br 0)))
+    (func $recurse (export "recurse") (param i32) (result i32)
+        (if (result i32) (local.get 0)
+            (then (call $recurse (i32.sub (local.get 0) (i32.const 1))))
+            (else (i32.const 0))))
+    (func (export "add") (param i32 i32) (result i32)
+        (i32.add (local.get 0) (local.get 1))))"#;
+
+fn instantiate(config: impl CompilerConfig + 'static) -> Result<(Store, Instance), String> {
+    let mut config = config;
+    config.enable_epoch_interruption();
+    let mut store = Store::new(wasmer::sys::EngineBuilder::new(config));
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    Ok((store, instance))
+}
+
+fn check_epoch_interruption(config: impl CompilerConfig + 'static) -> Result<(), String> {
+    let (mut store, instance) = instantiate(config)?;
+    let add: TypedFunction<(i32, i32), i32> = instance
+        .exports
+        .get_typed_function(&store, "add")
+        .map_err(|e| format!("{e:?}"))?;
+    let spin: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "spin")
+        .map_err(|e| format!("{e:?}"))?;
+    let recurse: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "recurse")
+        .map_err(|e| format!("{e:?}"))?;
+
+    // Without a deadline, nothing is interrupted.
+    assert_eq!(add.call(&mut store, 1, 2).map_err(|e| format!("{e:?}"))?, 3);
+
+    // A runaway loop is interrupted by another thread.
+    store.set_epoch_deadline(1);
+    let engine = store.engine().clone();
+    let ticker = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        engine.increment_epoch();
+    });
+    let err = spin.call(&mut store).unwrap_err();
+    ticker.join().unwrap();
+    assert_eq!(err.to_trap(), Some(TrapCode::Interrupt));
+
+    // The deadline stays reached until it is set again.
+    let err = add.call(&mut store, 1, 2).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::Interrupt));
+    store.set_epoch_deadline(1);
+    assert_eq!(add.call(&mut store, 1, 2).map_err(|e| format!("{e:?}"))?, 3);
+
+    // Functions without loops are checked on entry.
+    store.engine().increment_epoch();
+    let err = recurse.call(&mut store, 10).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::Interrupt));
+
+    Ok(())
+}
+
+fn check_interrupt_handle(config: impl CompilerConfig + 'static) -> Result<(), String> {
+    let (mut store, instance) = instantiate(config)?;
+    let spin: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "spin")
+        .map_err(|e| format!("{e:?}"))?;
+    let add: TypedFunction<(i32, i32), i32> = instance
+        .exports
+        .get_typed_function(&store, "add")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let handle = store.interrupt_handle();
+    let interrupter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        handle.interrupt();
+    });
+    let err = spin.call(&mut store).unwrap_err();
+    interrupter.join().unwrap();
+    assert_eq!(err.to_trap(), Some(TrapCode::Interrupt));
+
+    // Other stores of the same engine aren't interrupted.
+    let mut other = Store::new(store.engine().clone());
+    let module = Module::new(&other, WAT).map_err(|e| format!("{e:?}"))?;
+    let other_instance =
+        Instance::new(&mut other, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let other_add: TypedFunction<(i32, i32), i32> = other_instance
+        .exports
+        .get_typed_function(&other, "add")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(
+        other_add
+            .call(&mut other, 1, 2)
+            .map_err(|e| format!("{e:?}"))?,
+        3
+    );
+
+    // The store runs again once its deadline is reset.
+    assert!(add.call(&mut store, 1, 2).is_err());
+    store.set_epoch_deadline(u64::MAX);
+    assert_eq!(add.call(&mut store, 1, 2).map_err(|e| format!("{e:?}"))?, 3);
+
+    Ok(())
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn interrupt_handle_cranelift() -> Result<(), String> {
+    check_interrupt_handle(Cranelift::default())
+}
+
+#[cfg(feature = "singlepass")]
+#[test]
+fn interrupt_handle_singlepass() -> Result<(), String> {
+    check_interrupt_handle(Singlepass::default())
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn epoch_interruption_cranelift() -> Result<(), String> {
+    check_epoch_interruption(Cranelift::default())
+}
+
+#[cfg(feature = "singlepass")]
+#[test]
+fn epoch_interruption_singlepass() -> Result<(), String> {
+    check_epoch_interruption(Singlepass::default())
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn epoch_interruption_llvm_is_rejected() -> Result<(), String> {
+    let err = instantiate(LLVM::default()).map(|_| ()).unwrap_err();
+    assert!(err.contains("UnsupportedFeature"), "{err}");
+    Ok(())
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "sys")]
+
+use std::sync::{Arc, Mutex};
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (memory (export "memory") 1)
+    (func (export "grow") (param i32) (result i32)
+        (memory.grow (local.get 0)))
+    (func (export "trap") unreachable))"#;
+
+#[test]
+fn instance_created_hooks_see_new_instances() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+
+    let created = Arc::new(Mutex::new(Vec::new()));
+    let recorded = created.clone();
+    store.on_instance_created(move |event| recorded.lock().unwrap().push(event.instance.clone()));
+
+    let first = Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let second =
+        Instance::new_by_index(&mut store, &module, &[]).map_err(|e| format!("{e:?}"))?;
+
+    assert_eq!(*created.lock().unwrap(), vec![first, second]);
+    Ok(())
+}
+
+#[test]
+fn trap_hooks_see_failing_calls() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let trap = instance
+        .exports
+        .get_function("trap")
+        .map_err(|e| format!("{e:?}"))?
+        .clone();
+    let grow: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "grow")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let traps = Arc::new(Mutex::new(Vec::new()));
+    let recorded = traps.clone();
+    store.on_trap(move |event| recorded.lock().unwrap().push(event.clone()));
+
+    grow.call(&mut store, 0).map_err(|e| format!("{e:?}"))?;
+    let error = trap.call(&mut store, &[]).unwrap_err();
+    let typed: TypedFunction<(), ()> = trap.typed(&store).map_err(|e| format!("{e:?}"))?;
+    typed.call(&mut store).unwrap_err();
+
+    let traps = traps.lock().unwrap();
+    assert_eq!(traps.len(), 2);
+    assert_eq!(traps[0].function.as_ref(), Some(&trap));
+    assert_eq!(traps[0].error.clone().to_trap(), Some(TrapCode::UnreachableCodeReached));
+    assert_eq!(traps[0].error.message(), error.message());
+    assert_eq!(traps[1].function.as_ref(), Some(&trap));
+    Ok(())
+}
+
+#[test]
+fn trap_hooks_see_start_function_traps() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, r#"(module (func $start unreachable) (start $start))"#)
+        .map_err(|e| format!("{e:?}"))?;
+
+    let traps = Arc::new(Mutex::new(Vec::new()));
+    let recorded = traps.clone();
+    store.on_trap(move |event| recorded.lock().unwrap().push(event.clone()));
+    let created = Arc::new(Mutex::new(0));
+    let counted = created.clone();
+    store.on_instance_created(move |_| *counted.lock().unwrap() += 1);
+
+    let error = Instance::new(&mut store, &module, &imports! {}).unwrap_err();
+    assert!(matches!(error, InstantiationError::Start(_)));
+
+    let traps = traps.lock().unwrap();
+    assert_eq!(traps.len(), 1);
+    assert!(traps[0].function.is_none());
+    assert_eq!(*created.lock().unwrap(), 0);
+    Ok(())
+}
+
+#[test]
+fn hooks_can_be_subscribed_and_removed() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let grow: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "grow")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let grown = Arc::new(Mutex::new(Vec::new()));
+    let first = store.on_memory_grow_event({
+        let grown = grown.clone();
+        move |event| grown.lock().unwrap().push(("first", event.old_size, event.new_size))
+    });
+    store.on_memory_grow_event({
+        let grown = grown.clone();
+        move |event| grown.lock().unwrap().push(("second", event.old_size, event.new_size))
+    });
+
+    assert_eq!(grow.call(&mut store, 1).map_err(|e| format!("{e:?}"))?, 1);
+    assert!(store.remove_hook(first));
+    assert!(!store.remove_hook(first));
+    assert_eq!(grow.call(&mut store, 1).map_err(|e| format!("{e:?}"))?, 2);
+
+    assert_eq!(
+        *grown.lock().unwrap(),
+        vec![
+            ("first", Pages(1), Pages(2)),
+            ("second", Pages(1), Pages(2)),
+            ("second", Pages(2), Pages(3)),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn memory_grow_hooks_are_called_with_the_callback() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let grow: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "grow")
+        .map_err(|e| format!("{e:?}"))?;
+
+    let grown = Arc::new(Mutex::new(Vec::new()));
+    store.on_memory_grow_event({
+        let grown = grown.clone();
+        move |event| grown.lock().unwrap().push(("hook", event.new_size))
+    });
+    store.on_memory_grow({
+        let grown = grown.clone();
+        move |_, _, new_size| grown.lock().unwrap().push(("first", new_size))
+    });
+    store.on_memory_grow({
+        let grown = grown.clone();
+        move |_, _, new_size| grown.lock().unwrap().push(("second", new_size))
+    });
+
+    assert_eq!(grow.call(&mut store, 1).map_err(|e| format!("{e:?}"))?, 1);
+
+    assert_eq!(
+        *grown.lock().unwrap(),
+        vec![("second", Pages(2)), ("hook", Pages(2))]
+    );
+    Ok(())
+}
@@ -123,7 +123,43 @@ fn table_get() -> Result<(), String> {
 
 #[universal_test]
 fn table_set() -> Result<(), String> {
-    // Table set not yet tested
+    // Tables are not yet fully supported in Wasm
+    #[cfg(feature = "sys")]
+    {
+        let mut store = Store::default();
+        let table_type = TableType {
+            ty: Type::FuncRef,
+            minimum: 2,
+            maximum: None,
+        };
+        let table = Table::new(&mut store, table_type, Value::FuncRef(None))
+            .map_err(|e| format!("{e:?}"))?;
+        let f = Function::new_typed(&mut store, |num: i32| num + 1);
+        table
+            .set(&mut store, 1, Value::FuncRef(Some(f)))
+            .map_err(|e| format!("{e:?}"))?;
+        assert!(table.get(&mut store, 0).unwrap().unwrap_funcref().is_none());
+        let f = table.get(&mut store, 1).unwrap().unwrap_funcref().clone();
+        let f: TypedFunction<i32, i32> = f.unwrap().typed(&store).map_err(|e| format!("{e:?}"))?;
+        assert_eq!(f.call(&mut store, 1).map_err(|e| format!("{e:?}"))?, 2);
+
+        // Out of bounds indices and values of the wrong type are rejected.
+        assert!(table.set(&mut store, 2, Value::FuncRef(None)).is_err());
+        assert!(table.set(&mut store, 0, Value::ExternRef(None)).is_err());
+
+        let dynamic = Function::new(
+            &mut store,
+            FunctionType::new(vec![Type::I32], vec![Type::I32]),
+            |args| Ok(vec![Value::I32(args[0].unwrap_i32() * 2)]),
+        );
+        table
+            .set(&mut store, 0, Value::FuncRef(Some(dynamic)))
+            .map_err(|e| format!("{e:?}"))?;
+        let f = table.get(&mut store, 0).unwrap().unwrap_funcref().clone();
+        let f: TypedFunction<i32, i32> = f.unwrap().typed(&store).map_err(|e| format!("{e:?}"))?;
+        assert_eq!(f.call(&mut store, 21).map_err(|e| format!("{e:?}"))?, 42);
+    }
+
     Ok(())
 }
 
@@ -157,7 +193,224 @@ fn table_grow() -> Result<(), String> {
 
 #[universal_test]
 fn table_copy() -> Result<(), String> {
-    // TODO: table copy test not yet implemented
+    // Tables are not yet fully supported in Wasm
+    #[cfg(feature = "sys")]
+    {
+        let mut store = Store::default();
+        let table_type = TableType {
+            ty: Type::ExternRef,
+            minimum: 4,
+            maximum: None,
+        };
+        let src = Table::new(&mut store, table_type, Value::ExternRef(None))
+            .map_err(|e| format!("{e:?}"))?;
+        let dst = Table::new(&mut store, table_type, Value::ExternRef(None))
+            .map_err(|e| format!("{e:?}"))?;
+        for i in 0..4 {
+            let value = Value::ExternRef(Some(ExternRef::new(&mut store, i)));
+            src.set(&mut store, i, value)
+                .map_err(|e| format!("{e:?}"))?;
+        }
+        let element = |store: &mut Store, table: &Table, index| {
+            table
+                .get(store, index)
+                .unwrap()
+                .unwrap_externref()
+                .as_ref()
+                .map(|e| *e.downcast::<u32>(store).unwrap())
+        };
+
+        Table::copy(&mut store, &dst, 1, &src, 2, 2).map_err(|e| format!("{e:?}"))?;
+        assert_eq!(element(&mut store, &dst, 0), None);
+        assert_eq!(element(&mut store, &dst, 1), Some(2));
+        assert_eq!(element(&mut store, &dst, 2), Some(3));
+        assert_eq!(element(&mut store, &dst, 3), None);
+
+        // Overlapping copies within a table.
+        Table::copy(&mut store, &src, 1, &src, 0, 3).map_err(|e| format!("{e:?}"))?;
+        assert_eq!(element(&mut store, &src, 3), Some(2));
+        assert!(Table::copy(&mut store, &dst, 3, &src, 0, 2).is_err());
+
+        let funcs = TableType {
+            ty: Type::FuncRef,
+            minimum: 4,
+            maximum: None,
+        };
+        let funcs =
+            Table::new(&mut store, funcs, Value::FuncRef(None)).map_err(|e| format!("{e:?}"))?;
+        assert!(Table::copy(&mut store, &funcs, 0, &src, 0, 1).is_err());
+    }
+
+    Ok(())
+}
+
+#[universal_test]
+fn table_fill() -> Result<(), String> {
+    // Tables are not yet fully supported in Wasm
+    #[cfg(feature = "sys")]
+    {
+        let mut store = Store::default();
+        let table_type = TableType {
+            ty: Type::FuncRef,
+            minimum: 4,
+            maximum: None,
+        };
+        let table = Table::new(&mut store, table_type, Value::FuncRef(None))
+            .map_err(|e| format!("{e:?}"))?;
+        let f = Function::new_typed(&mut store, |num: i32| num + 1);
+        table
+            .fill(&mut store, 1, Value::FuncRef(Some(f.clone())), 2)
+            .map_err(|e| format!("{e:?}"))?;
+        let filled: Vec<bool> = (0..4)
+            .map(|i| table.get(&mut store, i).unwrap().unwrap_funcref().is_some())
+            .collect();
+        assert_eq!(filled, [false, true, true, false]);
+        assert!(table
+            .fill(&mut store, 3, Value::FuncRef(Some(f)), 2)
+            .is_err());
+    }
+
+    Ok(())
+}
+
+#[universal_test]
+fn table_iter_and_copy_from_slice() -> Result<(), String> {
+    // Tables are not yet fully supported in Wasm
+    #[cfg(feature = "sys")]
+    {
+        let mut store = Store::default();
+        let table_type = TableType {
+            ty: Type::FuncRef,
+            minimum: 4,
+            maximum: None,
+        };
+        let table = Table::new(&mut store, table_type, Value::FuncRef(None))
+            .map_err(|e| format!("{e:?}"))?;
+        let f = Function::new_typed(&mut store, |num: i32| num + 1);
+        let g = Function::new_typed(&mut store, |num: i32| num - 1);
+        table
+            .copy_from_slice(
+                &mut store,
+                1,
+                &[
+                    Value::FuncRef(Some(f.clone())),
+                    Value::FuncRef(Some(g.clone())),
+                ],
+            )
+            .map_err(|e| format!("{e:?}"))?;
+        let elements: Vec<Option<Function>> = table
+            .iter(&mut store)
+            .map(|element| element.unwrap_funcref().clone())
+            .collect();
+        let results = elements
+            .iter()
+            .map(|f| {
+                f.as_ref()
+                    .map(|f| {
+                        f.call(&mut store, &[Value::I32(10)])
+                            .map(|r| r[0].unwrap_i32())
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{e:?}"))?;
+        assert_eq!(results, [None, Some(11), Some(9), None]);
+
+        // Nothing is set when the range is out of bounds, or when a value
+        // has the wrong type.
+        assert!(table
+            .copy_from_slice(&mut store, 3, &[Value::FuncRef(None), Value::FuncRef(None)])
+            .is_err());
+        assert!(table
+            .copy_from_slice(&mut store, 0, &[Value::FuncRef(None), Value::I32(0)])
+            .is_err());
+        let filled: Vec<bool> = table
+            .iter(&mut store)
+            .map(|element| element.unwrap_funcref().is_some())
+            .collect();
+        assert_eq!(filled, [false, true, true, false]);
+    }
+
+    Ok(())
+}
+
+#[universal_test]
+fn table_patch_indirect_calls() -> Result<(), String> {
+    // Tables are not yet fully supported in Wasm
+    #[cfg(feature = "sys")]
+    {
+        let mut store = Store::default();
+        let wat = r#"(module
+            (type $unop (func (param i32) (result i32)))
+            (import "env" "table" (table 1 funcref))
+            (table $own (export "own") 1 funcref)
+            (func $double (type $unop) (i32.mul (local.get 0) (i32.const 2)))
+            (elem (table $own) (i32.const 0) func $double)
+            (func (export "call") (param i32 i32) (result i32)
+                (call_indirect (type $unop) (local.get 1) (local.get 0)))
+            (func (export "call_own") (param i32 i32) (result i32)
+                (call_indirect $own (type $unop) (local.get 1) (local.get 0))))"#;
+        let module = Module::new(&store, wat).map_err(|e| format!("{e:?}"))?;
+        let table_type = TableType {
+            ty: Type::FuncRef,
+            minimum: 1,
+            maximum: None,
+        };
+        let imported = Table::new(&mut store, table_type, Value::FuncRef(None))
+            .map_err(|e| format!("{e:?}"))?;
+        let imports = imports! {
+            "env" => {
+                "table" => imported.clone(),
+            },
+        };
+        let instance =
+            Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+        let call: TypedFunction<(i32, i32), i32> = instance
+            .exports
+            .get_typed_function(&store, "call")
+            .map_err(|e| format!("{e:?}"))?;
+        let call_own: TypedFunction<(i32, i32), i32> = instance
+            .exports
+            .get_typed_function(&store, "call_own")
+            .map_err(|e| format!("{e:?}"))?;
+        let own = instance
+            .exports
+            .get_table("own")
+            .map_err(|e| format!("{e:?}"))?;
+
+        // Uninitialized element.
+        assert!(call.call(&mut store, 0, 1).is_err());
+
+        // Grow the imported table with a host function.
+        let add_one = Function::new_typed(&mut store, |num: i32| num + 1);
+        let old_size = imported
+            .grow(&mut store, 1, Value::FuncRef(Some(add_one)))
+            .map_err(|e| format!("{e:?}"))?;
+        assert_eq!(old_size, 1);
+        assert_eq!(
+            call.call(&mut store, 1, 1).map_err(|e| format!("{e:?}"))?,
+            2
+        );
+
+        // Copy the guest's own function into the imported table.
+        Table::copy(&mut store, &imported, 0, own, 0, 1).map_err(|e| format!("{e:?}"))?;
+        assert_eq!(
+            call.call(&mut store, 0, 5).map_err(|e| format!("{e:?}"))?,
+            10
+        );
+
+        // Patch the exported table with a host function.
+        let negate = Function::new_typed(&mut store, |num: i32| -num);
+        own.set(&mut store, 0, Value::FuncRef(Some(negate)))
+            .map_err(|e| format!("{e:?}"))?;
+        assert_eq!(
+            call_own
+                .call(&mut store, 0, 5)
+                .map_err(|e| format!("{e:?}"))?,
+            -5
+        );
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,104 @@
+#![cfg(all(feature = "sys", feature = "compiler"))]
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (import "env" "limit" (global $limit (mut i32)))
+    (global $count (export "count") (mut i64) (i64.const 0))
+    (global (export "version") i32 (i32.const 3))
+    (func (export "set_limit") (param i32) (global.set $limit (local.get 0)))
+    (func (export "increment")
+        (global.set $count (i64.add (global.get $count) (i64.const 1)))))"#;
+
+fn check_frozen_globals(mut config: impl CompilerConfig + 'static) -> Result<(), String> {
+    config.enable_global_freezing();
+    let mut store = Store::new(wasmer::sys::EngineBuilder::new(config));
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let limit = Global::new_mut(&mut store, Value::I32(10));
+    let imports = imports! {
+        "env" => {
+            "limit" => limit.clone(),
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+    let set_limit: TypedFunction<i32, ()> = instance
+        .exports
+        .get_typed_function(&store, "set_limit")
+        .map_err(|e| format!("{e:?}"))?;
+    let increment: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "increment")
+        .map_err(|e| format!("{e:?}"))?;
+    let count = instance
+        .exports
+        .get_global("count")
+        .map_err(|e| format!("{e:?}"))?
+        .clone();
+
+    set_limit
+        .call(&mut store, 20)
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(limit.get(&mut store), Value::I32(20));
+
+    // Imported globals.
+    limit.freeze(&mut store);
+    let err = set_limit.call(&mut store, 30).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::FrozenGlobal));
+    assert_eq!(limit.get(&mut store), Value::I32(20));
+    assert!(limit.set(&mut store, Value::I32(30)).is_err());
+
+    // Exported globals.
+    increment.call(&mut store).map_err(|e| format!("{e:?}"))?;
+    assert!(!count.is_frozen(&store));
+    count.freeze(&mut store);
+    assert!(count.is_frozen(&store));
+    let err = increment.call(&mut store).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::FrozenGlobal));
+
+    assert_eq!(
+        instance.globals(&mut store),
+        [
+            ("count".to_string(), Value::I64(1)),
+            ("version".to_string(), Value::I32(3)),
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn frozen_globals_cranelift() -> Result<(), String> {
+    check_frozen_globals(Cranelift::default())
+}
+
+#[cfg(feature = "singlepass")]
+#[test]
+fn frozen_globals_singlepass() -> Result<(), String> {
+    check_frozen_globals(Singlepass::default())
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn frozen_globals_are_not_checked_by_default() -> Result<(), String> {
+    let mut store = Store::new(Cranelift::default());
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let limit = Global::new_mut(&mut store, Value::I32(10));
+    let imports = imports! {
+        "env" => {
+            "limit" => limit.clone(),
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+    let set_limit: TypedFunction<i32, ()> = instance
+        .exports
+        .get_typed_function(&store, "set_limit")
+        .map_err(|e| format!("{e:?}"))?;
+
+    limit.freeze(&mut store);
+    set_limit
+        .call(&mut store, 20)
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(limit.get(&mut store), Value::I32(20));
+    assert!(limit.set(&mut store, Value::I32(30)).is_err());
+    Ok(())
+}
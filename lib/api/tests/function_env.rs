@@ -41,3 +41,60 @@ fn data_and_store_mut() -> Result<(), String> {
 
     Ok(())
 }
+
+#[universal_test]
+fn with_data_and_store() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+            (func $notify (import "env" "notify") (param i32))
+            (func (export "double") (param i32) (result i32)
+                (i32.mul (local.get 0) (i32.const 2)))
+            (func (export "run") (param i32)
+                (call $notify (local.get 0))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+
+    struct Env {
+        double: Option<TypedFunction<i32, i32>>,
+        results: Vec<i32>,
+    }
+    let env = FunctionEnv::new(
+        &mut store,
+        Env {
+            double: None,
+            results: Vec::new(),
+        },
+    );
+    fn notify(mut env: FunctionEnvMut<Env>, value: i32) -> Result<(), RuntimeError> {
+        env.with_data_and_store(|data, store| {
+            let doubled = data.double.as_ref().unwrap().call(store, value)?;
+            data.results.push(doubled);
+            Ok(())
+        })
+    }
+    let imports = imports! {
+        "env" => {
+            "notify" => Function::new_typed_with_env(&mut store, &env, notify),
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+    env.as_mut(&mut store).double = Some(
+        instance
+            .exports
+            .get_typed_function(&store, "double")
+            .map_err(|e| format!("{e:?}"))?,
+    );
+
+    let run: TypedFunction<i32, ()> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+    run.call(&mut store, 3).map_err(|e| format!("{e:?}"))?;
+    run.call(&mut store, 5).map_err(|e| format!("{e:?}"))?;
+    // The data is back in the store once the call returns.
+    assert_eq!(env.as_ref(&store).results, vec![6, 10]);
+
+    Ok(())
+}
@@ -149,3 +149,257 @@ fn back_and_forth_with_imports() -> Result<()> {
 
     Ok(())
 }
+
+#[universal_test]
+fn typed_functions_with_arrays() -> Result<()> {
+    let mut store = Store::default();
+    // More parameters and results than the largest tuple.
+    let i32s = "i32 ".repeat(32);
+    let locals = (0..32)
+        .map(|i| format!("(local.get {i})"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let module = Module::new(
+        &store,
+        format!(
+            r#"(module
+            (func $spread (import "env" "spread") (param i32) (result {i32s}))
+            (func (export "spread") (param i32) (result {i32s})
+                (call $spread (local.get 0)))
+            (func (export "sum") (param {i32s}) (result i32)
+                {locals}
+                {adds})
+        )"#,
+            adds = "(i32.add) ".repeat(31),
+        ),
+    )?;
+
+    let import_object = imports! {
+        "env" => {
+            "spread" => Function::new_typed(&mut store, |x: i32| -> [i32; 32] {
+                std::array::from_fn(|i| x + i as i32)
+            }),
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let spread: TypedFunction<i32, [i32; 32]> =
+        instance.exports.get_typed_function(&store, "spread")?;
+    let values = spread.call(&mut store, 10)?;
+    assert_eq!(values, std::array::from_fn(|i| 10 + i as i32));
+
+    let sum: TypedFunction<[i32; 32], i32> = instance.exports.get_typed_function(&store, "sum")?;
+    assert_eq!(sum.call(&mut store, values)?, (10..42).sum::<i32>());
+
+    Ok(())
+}
+
+#[universal_test]
+fn host_function_with_multiple_results() -> Result<()> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        br#"(module
+            (func $divmod (import "env" "divmod") (param i32 i32) (result i32 i32 i64))
+            (func (export "divmod") (param i32 i32) (result i32 i32 i64)
+                (call $divmod (local.get 0) (local.get 1)))
+        )"#,
+    )?;
+
+    let import_object = imports! {
+        "env" => {
+            "divmod" => Function::new_typed(&mut store, |a: i32, b: i32| (a / b, a % b, i64::MAX)),
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let divmod: TypedFunction<(i32, i32), (i32, i32, i64)> =
+        instance.exports.get_typed_function(&store, "divmod")?;
+    assert_eq!(divmod.call(&mut store, 17, 5)?, (3, 2, i64::MAX));
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn raw_host_functions() -> Result<()> {
+    #[derive(Default)]
+    struct Counter {
+        calls: u64,
+        total: i64,
+    }
+
+    fn add(counter: &mut Counter, value: i64) -> i64 {
+        counter.calls += 1;
+        counter.total += value;
+        counter.total
+    }
+
+    fn check(_: &mut (), value: i32) -> Result<i32, RuntimeError> {
+        if value < 0 {
+            return Err(RuntimeError::new("negative"));
+        }
+        Ok(value)
+    }
+
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        br#"(module
+            (func $add (import "env" "add") (param i64) (result i64))
+            (func $check (import "env" "check") (param i32) (result i32))
+            (table 1 funcref)
+            (elem (i32.const 0) $add)
+            (type $t (func (param i64) (result i64)))
+            (func (export "add_three") (result i64)
+                (drop (call $add (i64.const 1)))
+                (drop (call $add (i64.const 2)))
+                (call_indirect (type $t) (i64.const 3) (i32.const 0)))
+            (func (export "check") (param i32) (result i32)
+                (call $check (local.get 0)))
+        )"#,
+    )?;
+
+    let add = Function::new_typed_raw(&mut store, Counter::default(), add);
+    let check = Function::new_typed_raw(&mut store, (), check);
+    assert_eq!(add.ty(&store), FunctionType::new([Type::I64], [Type::I64]));
+
+    let import_object = imports! {
+        "env" => {
+            "add" => add.clone(),
+            "check" => check,
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let add_three: TypedFunction<(), i64> =
+        instance.exports.get_typed_function(&store, "add_three")?;
+    assert_eq!(add_three.call(&mut store)?, 6);
+    // The data is kept between calls, including calls from the host.
+    assert_eq!(add.call(&mut store, &[Value::I64(4)])?[0], Value::I64(10));
+    assert_eq!(add_three.call(&mut store)?, 16);
+
+    let check: TypedFunction<i32, i32> = instance.exports.get_typed_function(&store, "check")?;
+    assert_eq!(check.call(&mut store, 7)?, 7);
+    let error = check.call(&mut store, -1).unwrap_err();
+    assert_eq!(error.message(), "negative");
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn host_trap_payloads() -> Result<()> {
+    #[derive(Debug, PartialEq)]
+    enum HostError {
+        Exit(i32),
+        Denied { path: String },
+    }
+
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        br#"(module
+            (func $exit (import "env" "exit") (param i32))
+            (func $open (import "env" "open"))
+            (func $inner (param i32) (call $exit (local.get 0)))
+            (func (export "exit") (param i32) (call $inner (local.get 0)))
+            (func (export "open") (call $open))
+        )"#,
+    )?;
+    let exit = Function::new_typed(&mut store, |code: i32| -> Result<(), RuntimeError> {
+        Err(RuntimeError::with_payload("exit", HostError::Exit(code)))
+    });
+    let open = Function::new(&mut store, FunctionType::new([], []), |_| {
+        Err(RuntimeError::with_payload(
+            "permission denied",
+            HostError::Denied {
+                path: "/etc".to_string(),
+            },
+        ))
+    });
+    let import_object = imports! {
+        "env" => {
+            "exit" => exit,
+            "open" => open,
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let exit: TypedFunction<i32, ()> = instance.exports.get_typed_function(&store, "exit")?;
+    let error = exit.call(&mut store, 3).unwrap_err();
+    assert_eq!(error.message(), "exit");
+    assert_eq!(error.payload::<HostError>(), Some(&HostError::Exit(3)));
+    assert_eq!(error.payload::<i32>(), None);
+    assert_eq!(error.trace().len(), 2);
+
+    let open = instance.exports.get_function("open")?;
+    let error = open.call(&mut store, &[]).unwrap_err();
+    assert_eq!(
+        error.payload::<HostError>(),
+        Some(&HostError::Denied {
+            path: "/etc".to_string()
+        })
+    );
+
+    Ok(())
+}
+
+#[cfg(all(feature = "sys", feature = "cranelift"))]
+#[test]
+fn dynamic_host_function_trap_trace() -> Result<()> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        br#"(module $m
+            (func $fail (import "env" "fail"))
+            (func $inner (call $fail))
+            (func (export "run") (call $inner))
+        )"#,
+    )?;
+    let fail = Function::new(&mut store, FunctionType::new([], []), |_| {
+        Err(RuntimeError::new("failed"))
+    });
+    let import_object = imports! {
+        "env" => {
+            "fail" => fail,
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let run = instance.exports.get_function("run")?;
+    let error = run.call(&mut store, &[]).unwrap_err();
+    let trace = error.trace();
+    assert_eq!(trace.len(), 2);
+    assert_eq!(trace[0].module_name(), "m");
+    assert_eq!(trace[0].func_index(), 1);
+    assert_eq!(trace[0].function_name(), Some("inner"));
+    assert_eq!(trace[1].func_index(), 2);
+
+    Ok(())
+}
+
+#[universal_test]
+fn host_function_with_multiple_results_in_table() -> Result<()> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        br#"(module
+            (type $divmod (func (param i32 i32) (result i32 i32)))
+            (table (export "table") 1 funcref)
+            (func (export "divmod") (param i32 i32) (result i32 i32)
+                (call_indirect (type $divmod) (local.get 0) (local.get 1) (i32.const 0)))
+        )"#,
+    )?;
+    let instance = Instance::new(&mut store, &module, &imports! {})?;
+
+    let divmod = Function::new_typed(&mut store, |a: i32, b: i32| (a / b, a % b));
+    let table = instance.exports.get_table("table")?;
+    table.set(&mut store, 0, Value::FuncRef(Some(divmod)))?;
+
+    let divmod: TypedFunction<(i32, i32), (i32, i32)> =
+        instance.exports.get_typed_function(&store, "divmod")?;
+    assert_eq!(divmod.call(&mut store, 17, 5)?, (3, 2));
+
+    Ok(())
+}
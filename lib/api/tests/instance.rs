@@ -78,3 +78,241 @@ fn unit_native_function_env() -> Result<(), String> {
 
     Ok(())
 }
+
+#[universal_test]
+fn instantiation_reports_all_unsatisfied_imports() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+    (import "env" "f" (func (param i32)))
+    (import "env" "g" (func))
+    (import "env" "memory" (memory 1))
+    (import "env" "global" (global i32)))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+
+    // A single missing import keeps the `Import` variant.
+    let imports = imports! {
+        "env" => {
+            "f" => Function::new_typed(&mut store, |_: i32| {}),
+            "g" => Function::new_typed(&mut store, || {}),
+            "memory" => Memory::new(&mut store, MemoryType::new(1, None, false)).unwrap(),
+        },
+    };
+    match Instance::new(&mut store, &module, &imports) {
+        Err(InstantiationError::Link(LinkError::Import(
+            module,
+            name,
+            ImportError::UnknownImport(_),
+        ))) => {
+            assert_eq!((module.as_str(), name.as_str()), ("env", "global"));
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    #[cfg(feature = "sys")]
+    {
+        let imports = imports! {
+            "env" => {
+                "f" => Function::new_typed(&mut store, || {}),
+                "global" => Global::new(&mut store, Value::I64(0)),
+            },
+        };
+        let err = match Instance::new(&mut store, &module, &imports) {
+            Err(InstantiationError::Link(err)) => err,
+            other => panic!("unexpected result: {other:?}"),
+        };
+        let errors = err.import_errors();
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            errors[0],
+            (
+                "env",
+                "f",
+                ImportError::IncompatibleType(ExternType::Function(_), ExternType::Function(_))
+            )
+        ));
+        assert!(matches!(
+            errors[1],
+            ("env", "g", ImportError::UnknownImport(_))
+        ));
+        assert!(matches!(
+            errors[2],
+            (
+                "env",
+                "memory",
+                ImportError::UnknownImport(ExternType::Memory(_))
+            )
+        ));
+        assert!(matches!(
+            errors[3],
+            ("env", "global", ImportError::IncompatibleType(_, _))
+        ));
+        let message = err.to_string();
+        assert!(
+            message.contains("4 imports can't be satisfied"),
+            "{message}"
+        );
+        assert!(message.contains("\"env\".\"memory\""), "{message}");
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn imports_resolver_provides_missing_imports() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+    (import "env" "add" (func $add (param i32 i32) (result i32)))
+    (import "env" "base" (global $base i32))
+    (import "wasi" "exit" (func (param i32)))
+    (func (export "run") (result i32)
+        (call $add (global.get $base) (i32.const 2))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+
+    // Explicit imports take precedence over the resolver.
+    let imports = imports! {
+        "env" => {
+            "base" => Global::new(&mut store, Value::I32(40)),
+        },
+        @resolve |store, import| match (import.module(), import.name()) {
+            ("env", "add") => Some(Function::new_typed(store, |a: i32, b: i32| a + b).into()),
+            ("env", "base") => Some(Global::new(store, Value::I32(0)).into()),
+            ("wasi", _) => Some(Function::new_typed(store, |_: i32| {}).into()),
+            _ => None,
+        }
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+    let run: TypedFunction<(), i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(run.call(&mut store).map_err(|e| format!("{e:?}"))?, 42);
+
+    // The imports the resolver doesn't provide are reported as missing.
+    let mut imports = Imports::new();
+    imports.set_resolver(|store, import| match import.module() {
+        "env" => Some(Global::new(store, Value::I32(0)).into()),
+        _ => None,
+    });
+    let err = match Instance::new(&mut store, &module, &imports) {
+        Err(InstantiationError::Link(err)) => err,
+        other => panic!("unexpected result: {other:?}"),
+    };
+    let errors = err.import_errors();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        errors[0],
+        ("env", "add", ImportError::IncompatibleType(_, _))
+    ));
+    assert!(matches!(
+        errors[1],
+        ("wasi", "exit", ImportError::UnknownImport(_))
+    ));
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn memory_and_table_sizes() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+    (import "env" "table" (table 2 funcref))
+    (memory 2)
+    (table $t 1 funcref)
+    (func (export "grow")
+        (drop (memory.grow (i32.const 3)))
+        (drop (table.grow $t (ref.null func) (i32.const 4)))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let table = Table::new(
+        &mut store,
+        TableType::new(Type::FuncRef, 2, None),
+        Value::FuncRef(None),
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let imports = imports! {
+        "env" => {
+            "table" => table,
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+    assert_eq!(instance.memory_sizes(&store), [Pages(2)]);
+    assert_eq!(instance.table_sizes(&store), [1]);
+
+    let grow: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "grow")
+        .map_err(|e| format!("{e:?}"))?;
+    grow.call(&mut store).map_err(|e| format!("{e:?}"))?;
+    assert_eq!(instance.memory_sizes(&store), [Pages(5)]);
+    assert_eq!(instance.table_sizes(&store), [5]);
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn memory_usage() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+    (memory (export "memory") 1 4)
+    (table 2 funcref)
+    (global $sp (export "__stack_pointer") (mut i32) (i32.const 1024))
+    (global (export "__data_end") i32 (i32.const 256))
+    (data (i32.const 0) "data")
+    (func (export "run")
+        (drop (memory.grow (i32.const 1)))
+        ;; Push a frame of 64 bytes, and only pop 48 of them.
+        (global.set $sp (i32.sub (global.get $sp) (i32.const 64)))
+        (i32.store (global.get $sp) (i32.const 1))
+        (global.set $sp (i32.add (global.get $sp) (i32.const 48)))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let instance = Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let run: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+    run.call(&mut store).map_err(|e| format!("{e:?}"))?;
+
+    let usage = instance.memory_usage(&store);
+    assert_eq!(
+        usage.memories,
+        [LinearMemoryUsage {
+            minimum: Pages(1),
+            maximum: Some(Pages(4)),
+            current: Pages(2),
+            peak: Pages(2),
+        }]
+    );
+    assert_eq!(
+        usage.tables,
+        [TableUsage {
+            minimum: 2,
+            maximum: None,
+            current: 2,
+        }]
+    );
+    assert_eq!(usage.data_segments_size, 4);
+    assert_eq!(
+        usage.stack,
+        Some(StackUsage {
+            size: 768,
+            current: 16,
+            peak: 64,
+        })
+    );
+
+    Ok(())
+}
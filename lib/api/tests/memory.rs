@@ -76,3 +76,292 @@ fn test_shared_memory_disable_atomics() {
     let err = mem.wait(MemoryLocation::new_32(1), None).unwrap_err();
     assert_eq!(err, AtomicsError::AtomicsDisabled);
 }
+
+#[cfg(feature = "sys")]
+#[test]
+fn test_shared_memory_atomics_from_host() {
+    use wasmer::{AtomicsError, TypedFunction};
+
+    let mut store = Store::default();
+    let wat = r#"(module
+(import "host" "memory" (memory 1 1 shared))
+(func (export "wait") (result i32)
+  (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const -1)))
+(func (export "add") (param i32) (result i32)
+  (i32.atomic.rmw.add (i32.const 8) (local.get 0)))
+)"#;
+    let module = Module::new(&store, wat).unwrap();
+    let mem = Memory::new(&mut store, MemoryType::new(1, Some(1), true)).unwrap();
+    let imports = imports! {
+        "host" => {
+            "memory" => mem.clone(),
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &imports).unwrap();
+    let wait: TypedFunction<(), i32> = instance.exports.get_typed_function(&store, "wait").unwrap();
+    let add: TypedFunction<i32, i32> = instance.exports.get_typed_function(&store, "add").unwrap();
+
+    // The host thread accesses the memory through its own store.
+    let mut host_store = Store::default();
+    let host_mem = mem.share_in_store(&store, &mut host_store).unwrap();
+    let atomics = host_mem.atomics(&host_store).unwrap();
+
+    assert_eq!(atomics.load32(2), Err(AtomicsError::Unaligned));
+    assert_eq!(atomics.load64(65536), Err(AtomicsError::OutOfBounds));
+
+    atomics.store32(8, 40).unwrap();
+    assert_eq!(add.call(&mut store, 2).unwrap(), 40);
+    assert_eq!(atomics.compare_exchange32(8, 42, 1).unwrap(), 42);
+    assert_eq!(atomics.swap64(8, 7).unwrap(), 1);
+    assert_eq!(atomics.wait32(8, 0, None).unwrap(), 1);
+    assert_eq!(
+        atomics
+            .wait64(8, 7, Some(std::time::Duration::from_millis(10)))
+            .unwrap(),
+        2
+    );
+
+    // A WebAssembly thread waiting on the memory is woken up by the host.
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| wait.call(&mut store).unwrap());
+        while atomics.notify(0, 1).unwrap() == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(waiter.join().unwrap(), 0);
+    });
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn test_memory_grow_callback() {
+    use std::sync::Mutex;
+    use wasmer::{Pages, TypedFunction};
+
+    let mut store = Store::default();
+    let wat = r#"(module
+(memory (export "memory") 1 4)
+(func (export "grow") (param i32) (result i32)
+  (memory.grow (local.get 0)))
+)"#;
+    let module = Module::new(&store, wat).unwrap();
+    let instance = Instance::new(&mut store, &module, &imports! {}).unwrap();
+    let memory = instance.exports.get_memory("memory").unwrap().clone();
+    let grow: TypedFunction<i32, i32> =
+        instance.exports.get_typed_function(&store, "grow").unwrap();
+
+    let grown = Arc::new(Mutex::new(Vec::new()));
+    store.on_memory_grow({
+        let grown = grown.clone();
+        move |memory, old_size, new_size| grown.lock().unwrap().push((memory, old_size, new_size))
+    });
+
+    memory.grow(&mut store, 1).unwrap();
+    assert_eq!(grow.call(&mut store, 1).unwrap(), 2);
+    // Failed and empty growths aren't notified.
+    assert_eq!(grow.call(&mut store, 2).unwrap(), -1);
+    assert_eq!(grow.call(&mut store, 0).unwrap(), 3);
+    memory.grow(&mut store, 1).unwrap();
+    memory.grow(&mut store, 1).unwrap_err();
+
+    let grown = grown.lock().unwrap();
+    assert_eq!(
+        grown
+            .iter()
+            .map(|(_, old_size, new_size)| (*old_size, *new_size))
+            .collect::<Vec<_>>(),
+        vec![
+            (Pages(1), Pages(2)),
+            (Pages(2), Pages(3)),
+            (Pages(3), Pages(4)),
+        ]
+    );
+    assert!(grown.iter().all(|(grown, _, _)| *grown == memory));
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn test_memory_read_only_ranges() {
+    use wasmer::sys::{BaseTunables, NativeEngineExt};
+    use wasmer::{Engine, Pages, TrapCode, TypedFunction, WASM_PAGE_SIZE};
+
+    let wat = r#"(module
+(import "host" "memory" (memory 1))
+(func (export "store") (param i32 i32)
+  (i32.store (local.get 0) (local.get 1)))
+(func (export "load") (param i32) (result i32)
+  (i32.load (local.get 0)))
+(func (export "fill") (param i32 i32)
+  (memory.fill (local.get 0) (i32.const 7) (local.get 1)))
+)"#;
+    let page = WASM_PAGE_SIZE as u64;
+
+    // Host memories without a maximum are dynamic with these tunables, so
+    // they move when they grow.
+    let mut engine = Engine::default();
+    engine.set_tunables(BaseTunables {
+        static_memory_bound: Pages(1),
+        static_memory_offset_guard_size: 0,
+        dynamic_memory_offset_guard_size: 0,
+    });
+    let mut store = Store::new(engine);
+    let memory = Memory::new(&mut store, MemoryType::new(2, None, false)).unwrap();
+    memory.view(&store).write(8, &[1, 0, 0, 0]).unwrap();
+
+    assert!(memory.set_read_only(&mut store, 8..page, true).is_err());
+    assert!(memory.set_read_only(&mut store, 0..3 * page, true).is_err());
+    memory.set_read_only(&mut store, 0..2 * page, true).unwrap();
+    memory.grow(&mut store, 1).unwrap();
+
+    let module = Module::new(&store, wat).unwrap();
+    let imports = imports! {
+        "host" => {
+            "memory" => memory.clone(),
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &imports).unwrap();
+    let store_i32: TypedFunction<(i32, i32), ()> = instance
+        .exports
+        .get_typed_function(&store, "store")
+        .unwrap();
+    let load: TypedFunction<i32, i32> =
+        instance.exports.get_typed_function(&store, "load").unwrap();
+    let fill: TypedFunction<(i32, i32), ()> =
+        instance.exports.get_typed_function(&store, "fill").unwrap();
+
+    // The first pages stay read-only after the memory moved.
+    let err = store_i32.call(&mut store, 8, 2).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::HeapAccessOutOfBounds));
+    let err = fill.call(&mut store, 0, 16).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::HeapAccessOutOfBounds));
+    assert_eq!(load.call(&mut store, 8).unwrap(), 1);
+    store_i32.call(&mut store, 2 * page as i32, 3).unwrap();
+    assert_eq!(load.call(&mut store, 2 * page as i32).unwrap(), 3);
+
+    // Part of the range can be made writable again.
+    memory.set_read_only(&mut store, 0..page, false).unwrap();
+    store_i32.call(&mut store, 8, 2).unwrap();
+    assert_eq!(load.call(&mut store, 8).unwrap(), 2);
+    let err = store_i32.call(&mut store, page as i32, 2).unwrap_err();
+    assert_eq!(err.to_trap(), Some(TrapCode::HeapAccessOutOfBounds));
+}
+
+#[test]
+fn test_memory_view_bulk_copies() {
+    use wasmer::MemoryAccessError;
+
+    let mut store = Store::default();
+    let mem = Memory::new(&mut store, MemoryType::new(1, None, false)).unwrap();
+    let view = mem.view(&store);
+    let size = view.data_size();
+
+    view.copy_from_slice(8, &[1u32, 2, 3]).unwrap();
+    let mut values = [0u32; 3];
+    view.copy_to_slice(8, &mut values).unwrap();
+    assert_eq!(values, [1, 2, 3]);
+    assert_eq!(
+        view.copy_range_to_vec(8..12).unwrap(),
+        1u32.to_le_bytes().to_vec()
+    );
+    assert!(matches!(
+        view.copy_from_slice(size - 4, &[0u64]),
+        Err(MemoryAccessError::HeapOutOfBounds)
+    ));
+    assert!(matches!(
+        view.copy_to_slice(u64::MAX, &mut values),
+        Err(MemoryAccessError::Overflow)
+    ));
+
+    // Overlapping copies in both directions.
+    view.write(0, b"abcdef").unwrap();
+    view.copy_within(0..4, 2).unwrap();
+    assert_eq!(view.copy_range_to_vec(0..6).unwrap(), b"ababcd");
+    view.copy_within(2..6, 0).unwrap();
+    assert_eq!(view.copy_range_to_vec(0..6).unwrap(), b"abcdcd");
+    assert!(matches!(
+        view.copy_within(0..8, size - 4),
+        Err(MemoryAccessError::HeapOutOfBounds)
+    ));
+    assert!(matches!(
+        view.copy_within(size - 4..size + 4, 0),
+        Err(MemoryAccessError::HeapOutOfBounds)
+    ));
+
+    let chunks = view.chunks(0..6, 4).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chunks, vec![b"abcd".to_vec(), b"cd".to_vec()]);
+    let chunks = view.chunks(size - 8..size + 4, 8).collect::<Vec<_>>();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].as_ref().unwrap(), &vec![0; 8]);
+    assert!(matches!(chunks[1], Err(MemoryAccessError::HeapOutOfBounds)));
+}
+
+#[test]
+fn test_wasm_ptr64() {
+    use wasmer::{
+        Function, FunctionEnv, FunctionEnvMut, MemoryAccessError, TypedFunction, WasmPtr64,
+    };
+
+    let mut store = Store::default();
+    // The guest passes 64-bit pointers, like a guest with a 64-bit memory.
+    let wat = r#"(module
+(import "host" "sum" (func $sum (param i64 i64) (result i64)))
+(memory (export "memory") 1)
+(func (export "run") (param i64) (result i64)
+  (i64.store (i32.const 8) (i64.const 40))
+  (i64.store (i32.const 16) (i64.const 2))
+  (call $sum (local.get 0) (i64.const 2)))
+)"#;
+    let module = Module::new(&store, wat).unwrap();
+    let env = FunctionEnv::new(&mut store, None::<Memory>);
+    let sum = Function::new_typed_with_env(
+        &mut store,
+        &env,
+        |env: FunctionEnvMut<Option<Memory>>, ptr: WasmPtr64<u64>, len: u64| -> i64 {
+            let memory = env.data().as_ref().unwrap();
+            let view = memory.view(&env);
+            match ptr.slice(&view, len).and_then(|slice| slice.read_to_vec()) {
+                Ok(values) => values.iter().sum::<u64>() as i64,
+                Err(MemoryAccessError::HeapOutOfBounds) => -1,
+                Err(_) => -2,
+            }
+        },
+    );
+    let imports = imports! {
+        "host" => {
+            "sum" => sum,
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &imports).unwrap();
+    let memory = instance.exports.get_memory("memory").unwrap().clone();
+    *env.as_mut(&mut store) = Some(memory.clone());
+    let run: TypedFunction<i64, i64> = instance.exports.get_typed_function(&store, "run").unwrap();
+
+    assert_eq!(run.call(&mut store, 8).unwrap(), 42);
+    // Offsets beyond 4 GiB are bounds checked instead of wrapping around.
+    assert_eq!(run.call(&mut store, (1 << 32) + 8).unwrap(), -1);
+    assert_eq!(run.call(&mut store, -1).unwrap(), -2);
+
+    let view = memory.view(&store);
+    let ptr = WasmPtr64::<u64>::new(16);
+    assert_eq!(ptr.read(&view).unwrap(), 2);
+    assert_eq!(
+        ptr.slice(&view, 1).unwrap().as_ptr::<wasmer::Memory64>(),
+        ptr
+    );
+    assert!(matches!(
+        WasmPtr64::<u64>::new(1 << 32).read(&view),
+        Err(MemoryAccessError::HeapOutOfBounds)
+    ));
+}
+
+#[cfg(all(feature = "sys", feature = "cranelift"))]
+#[test]
+fn test_memory64_modules_are_rejected() {
+    use wasmer::sys::{EngineBuilder, Features};
+    use wasmer::{CompileError, Cranelift};
+
+    let mut features = Features::new();
+    features.memory64(true);
+    let store = Store::new(EngineBuilder::new(Cranelift::default()).set_features(Some(features)));
+    let err = Module::new(&store, "(module (memory i64 1))").unwrap_err();
+    assert!(matches!(err, CompileError::Wasm(_)), "{err:?}");
+}
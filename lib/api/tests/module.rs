@@ -290,3 +290,382 @@ fn module_custom_sections() -> Result<(), String> {
     );
     Ok(())
 }
+
+#[cfg(feature = "sys")]
+#[test]
+fn module_convert_artifact() -> Result<(), String> {
+    use wasmer_compiler::ArtifactBuild;
+
+    let store = Store::default();
+    let wasm = wat2wasm(br#"(module (func (export "answer") (result i32) i32.const 42))"#)
+        .map_err(|e| format!("{e:?}"))?;
+    let module = Module::new(&store, &wasm).map_err(|e| format!("{e:?}"))?;
+    let serialized = module.serialize().map_err(|e| format!("{e:?}"))?.to_vec();
+    assert_eq!(ArtifactBuild::embedded_wasm(&serialized), None);
+
+    // Without the original module, the artifact is transcoded.
+    let converted = store
+        .engine()
+        .convert_artifact(serialized.clone())
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(ArtifactBuild::embedded_wasm(&converted), None);
+
+    // With the original module, the artifact is recompiled and the module
+    // is embedded again.
+    let mut embedded = serialized.clone();
+    ArtifactBuild::embed_wasm(&mut embedded, &wasm);
+    let converted = store
+        .engine()
+        .convert_artifact(embedded.clone())
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(ArtifactBuild::embedded_wasm(&converted), Some(&wasm[..]));
+
+    let module = unsafe { Module::deserialize(&store, converted) }.map_err(|e| format!("{e:?}"))?;
+    assert_eq!(module.exports().count(), 1);
+
+    // Only artifacts embedding the original module can be converted for
+    // another target.
+    #[cfg(feature = "cranelift")]
+    {
+        use std::str::FromStr;
+
+        let triple = if matches!(Triple::host().architecture, Architecture::Aarch64(_)) {
+            "x86_64-unknown-linux-gnu"
+        } else {
+            "aarch64-unknown-linux-gnu"
+        };
+        let target = Target::new(
+            Triple::from_str(triple).map_err(|e| format!("{e:?}"))?,
+            CpuFeature::set(),
+        );
+        let engine: Engine = wasmer::sys::EngineBuilder::new(Cranelift::default())
+            .set_target(Some(target))
+            .into();
+        assert!(matches!(
+            engine.convert_artifact(serialized),
+            Err(DeserializeError::Incompatible(_))
+        ));
+        let converted = engine
+            .convert_artifact(embedded)
+            .map_err(|e| format!("{e:?}"))?;
+        assert_eq!(ArtifactBuild::embedded_wasm(&converted), Some(&wasm[..]));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(feature = "cranelift", target_arch = "x86_64"))]
+#[test]
+fn module_position_independent_artifact() -> Result<(), String> {
+    let wat = r#"(module
+        (func $double (param i32) (result i32) (i32.mul (local.get 0) (i32.const 2)))
+        (func (export "run") (param i32) (result i32) (call $double (local.get 0))))"#;
+
+    let mut config = Cranelift::default();
+    config.enable_pic();
+    let engine = Engine::from(wasmer::sys::EngineBuilder::new(config));
+    let mut store = Store::new(engine.clone());
+    let module = Module::new(&store, wat).map_err(|e| format!("{e:?}"))?;
+    let serialized = module.serialize().map_err(|e| format!("{e:?}"))?;
+    let artifact =
+        unsafe { engine.deserialize(serialized.clone()) }.map_err(|e| format!("{e:?}"))?;
+    assert!(artifact.is_position_independent());
+
+    let module =
+        unsafe { Module::deserialize(&store, serialized) }.map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let run: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "run")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(run.call(&mut store, 21).map_err(|e| format!("{e:?}"))?, 42);
+
+    // Without PIC, the libcall addresses are patched into the code.
+    let engine = Engine::from(wasmer::sys::EngineBuilder::new(Cranelift::default()));
+    let module = Module::new(&engine, wat).map_err(|e| format!("{e:?}"))?;
+    let serialized = module.serialize().map_err(|e| format!("{e:?}"))?;
+    let artifact = unsafe { engine.deserialize(serialized) }.map_err(|e| format!("{e:?}"))?;
+    assert!(!artifact.is_position_independent());
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn engine_precompile_module() -> Result<(), String> {
+    let wasm = wat2wasm(
+        br#"(module (func (export "add_one") (param i32) (result i32)
+            (i32.add (local.get 0) (i32.const 1))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+
+    let engine = Engine::default();
+    let serialized = engine
+        .precompile_module(&wasm[..])
+        .map_err(|e| format!("{e:?}"))?;
+    let mut store = Store::new(engine);
+    let module =
+        unsafe { Module::deserialize(&store, serialized) }.map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let add_one: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(&store, "add_one")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(
+        add_one.call(&mut store, 41).map_err(|e| format!("{e:?}"))?,
+        42
+    );
+
+    // Invalid modules are rejected.
+    assert!(store
+        .engine()
+        .precompile_module(&b"\0asm\x01\0\0\0\x01"[..])
+        .is_err());
+
+    // Cross-compile for another platform.
+    let triple: Triple = "aarch64-unknown-linux-gnu".parse().unwrap();
+    let target = Target::new(triple, CpuFeature::set());
+    let tunables = wasmer::sys::BaseTunables::for_target(&target);
+    let serialized = store
+        .engine()
+        .precompile_module_for(&wasm[..], &target, &tunables)
+        .map_err(|e| format!("{e:?}"))?;
+    assert!(wasmer::sys::Artifact::is_deserializable(&serialized));
+
+    Ok(())
+}
+
+#[cfg(feature = "artifact-encryption")]
+#[test]
+fn module_encrypted_artifact() -> Result<(), String> {
+    let store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module (func (export "answer") (result i32) i32.const 42))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+
+    let provider = StaticKeyProvider::new("device-key", [42; ARTIFACT_KEY_LEN]);
+    let encrypted = module
+        .serialize_encrypted(&provider)
+        .map_err(|e| format!("{e:?}"))?;
+    assert!(unsafe { Module::deserialize(&store, encrypted.clone()) }.is_err());
+
+    let module = unsafe { Module::deserialize_encrypted(&store, encrypted.clone(), &provider) }
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(module.exports().count(), 1);
+
+    let wrong = StaticKeyProvider::new("device-key", [0; ARTIFACT_KEY_LEN]);
+    assert!(unsafe { Module::deserialize_encrypted(&store, encrypted, &wrong) }.is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn module_code_memory_limit() -> Result<(), String> {
+    let store = Store::default();
+    let engine = store.engine();
+    let wat_a = r#"(module (func (export "a") (result i32) i32.const 1))"#;
+    let wat_b = r#"(module (func (export "b") (result i32) i32.const 2))"#;
+    let wat_c = r#"(module (func (export "c") (result i32) i32.const 3))"#;
+
+    let module_a = Module::new(&store, wat_a).map_err(|e| format!("{e:?}"))?;
+    let limit = 2 * engine.code_memory_size();
+    engine.set_code_memory_limit(Some(limit));
+
+    // Modules compiled while a limit is set are cached.
+    let module_b = Module::new(&store, wat_b).map_err(|e| format!("{e:?}"))?;
+    assert_eq!(
+        module_b,
+        Module::new(&store, wat_b).map_err(|e| format!("{e:?}"))?
+    );
+    drop(module_b);
+
+    // `module_b` is not used anymore, so it gets evicted.
+    let module_c = Module::new(&store, wat_c).map_err(|e| format!("{e:?}"))?;
+    assert!(engine.code_memory_size() <= limit);
+
+    // The code of `module_a` and `module_c` is in use, so the limit can't be
+    // honored.
+    assert!(Module::new(&store, wat_b).is_err());
+
+    drop(module_a);
+    drop(module_c);
+    Module::new(&store, wat_b).map_err(|e| format!("{e:?}"))?;
+    assert!(engine.code_memory_size() <= limit);
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn module_clone_into() -> Result<(), String> {
+    let wat = r#"(module (func (export "answer") (result i32) i32.const 42))"#;
+    let store = Store::default();
+    let module = Module::new(&store, wat).map_err(|e| format!("{e:?}"))?;
+
+    let answer = |store: &mut Store, module: &Module| -> Result<i32, String> {
+        let instance = Instance::new(store, module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+        let answer: TypedFunction<(), i32> = instance
+            .exports
+            .get_typed_function(store, "answer")
+            .map_err(|e| format!("{e:?}"))?;
+        answer.call(store).map_err(|e| format!("{e:?}"))
+    };
+
+    // Stores using a clone of the engine, on any thread, share the module.
+    let engine = store.engine().clone();
+    let shared = module.clone();
+    let result = std::thread::spawn(move || answer(&mut Store::new(engine), &shared))
+        .join()
+        .unwrap()?;
+    assert_eq!(result, 42);
+    let same = module.clone_into(&store).map_err(|e| format!("{e:?}"))?;
+    assert_eq!(same, module);
+
+    // Other engines get their own copy of the module.
+    let mut other_store = Store::default();
+    let copy = module
+        .clone_into(&other_store)
+        .map_err(|e| format!("{e:?}"))?;
+    assert_ne!(copy, module);
+    assert_eq!(answer(&mut other_store, &copy)?, 42);
+
+    #[cfg(feature = "cranelift")]
+    {
+        use std::str::FromStr;
+
+        let triple = if matches!(Triple::host().architecture, Architecture::Aarch64(_)) {
+            "x86_64-unknown-linux-gnu"
+        } else {
+            "aarch64-unknown-linux-gnu"
+        };
+        let target = Target::new(
+            Triple::from_str(triple).map_err(|e| format!("{e:?}"))?,
+            CpuFeature::set(),
+        );
+        let engine: Engine = wasmer::sys::EngineBuilder::new(Cranelift::default())
+            .set_target(Some(target))
+            .into();
+        assert!(matches!(
+            module.clone_into(&engine),
+            Err(DeserializeError::Incompatible(_))
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn module_element_segments() -> Result<(), String> {
+    let store = Store::default();
+    let wat = r#"(module
+        (import "env" "f" (func $imported))
+        (import "env" "base" (global $base i32))
+        (table 4 funcref)
+        (func $a)
+        (func $b)
+        (elem (i32.const 0) func $a $b)
+        (elem (global.get $base) func $imported)
+        (elem $p1 funcref (ref.func $b) (ref.null func))
+        (elem $p0 func $a)
+        (elem declare func $b))"#;
+    let module = Module::new(&store, wat).map_err(|e| format!("{e:?}"))?;
+    let segments: Vec<_> = module.element_segments().collect();
+    assert_eq!(
+        segments,
+        [
+            ElementSegment::Active {
+                table_index: 0,
+                base: None,
+                offset: 0,
+                elements: Box::new([Some(1), Some(2)]),
+            },
+            ElementSegment::Active {
+                table_index: 0,
+                base: Some(0),
+                offset: 0,
+                elements: Box::new([Some(0)]),
+            },
+            ElementSegment::Passive {
+                index: 2,
+                elements: Box::new([Some(2), None]),
+            },
+            ElementSegment::Passive {
+                index: 3,
+                elements: Box::new([Some(1)]),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn module_custom_sections_are_borrowed_and_serialized() -> Result<(), String> {
+    let store = Store::default();
+    let wasm = wat2wasm(br#"(module (@custom "first" "abc") (@custom "second" "defg"))"#)
+        .map_err(|e| format!("{e:?}"))?;
+    let module = Module::new(&store, wasm).map_err(|e| format!("{e:?}"))?;
+
+    let check = |module: &Module| {
+        assert_eq!(module.custom_section("first"), Some(&b"abc"[..]));
+        assert_eq!(module.custom_section("second"), Some(&b"defg"[..]));
+        assert_eq!(module.custom_section("third"), None);
+        assert_eq!(
+            module.all_custom_sections().collect::<Vec<_>>(),
+            vec![("first", &b"abc"[..]), ("second", &b"defg"[..])]
+        );
+    };
+    check(&module);
+
+    let serialized = module.serialize().map_err(|e| format!("{e:?}"))?;
+    let module =
+        unsafe { Module::deserialize(&store, serialized) }.map_err(|e| format!("{e:?}"))?;
+    check(&module);
+    Ok(())
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn module_symbolicate() -> Result<(), String> {
+    let store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module $app
+            (import "env" "f" (func))
+            (func $first (result i32) i32.const 1)
+            (func (export "second") (result i32) i32.const 2))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+
+    let ranges: Vec<_> = module.function_address_ranges().collect();
+    assert_eq!(
+        ranges.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        [1, 2]
+    );
+    for (index, range) in &ranges {
+        assert!(!range.is_empty());
+        for pc in [range.start, range.end - 1] {
+            let frame = module.symbolicate(pc).ok_or("unknown address")?;
+            assert_eq!(frame.module_name(), "app");
+            assert_eq!(frame.func_index(), *index);
+        }
+    }
+    let first = module.symbolicate(ranges[0].1.start).unwrap();
+    assert_eq!(first.function_name(), Some("first"));
+    let second = module.symbolicate(ranges[1].1.start).unwrap();
+    assert_eq!(second.function_name(), Some("second"));
+    assert!(second.module_offset() > first.module_offset());
+
+    // Addresses of other modules aren't resolved.
+    let other = Module::new(&store, "(module (func))").map_err(|e| format!("{e:?}"))?;
+    let (_, range) = other.function_address_ranges().next().unwrap();
+    assert!(module.symbolicate(range.start).is_none());
+    assert!(other.symbolicate(range.start).is_some());
+    Ok(())
+}
@@ -487,4 +487,48 @@ pub mod reference_types {
 
         Ok(())
     }
+
+    #[universal_test]
+    fn extern_ref_downcast_and_drop_hook() -> Result<()> {
+        let mut store = Store::default();
+        let wat = r#"(module
+    (table $table (export "table") 1 1 externref)
+    (func (export "stash") (param $er externref)
+          (table.set $table (i32.const 0) (local.get $er)))
+)"#;
+        let module = Module::new(&store, wat)?;
+        let instance = Instance::new(&mut store, &module, &imports! {})?;
+        let stash: TypedFunction<Option<ExternRef>, ()> =
+            instance.exports.get_typed_function(&store, "stash")?;
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let er = {
+            let dropped = dropped.clone();
+            ExternRef::new_with_drop_hook(&mut store, vec![1u8, 2], move |data: Vec<u8>| {
+                assert_eq!(data, [1, 2, 3]);
+                dropped.store(true, Ordering::SeqCst);
+            })
+        };
+        stash.call(&mut store, Some(er.clone()))?;
+
+        // The table holds a reference to the same data.
+        let table: &Table = instance.exports.get_table("table")?;
+        let stashed = table.get(&mut store, 0).unwrap().unwrap_externref().clone();
+        stashed
+            .unwrap()
+            .downcast_mut::<Vec<u8>>(&mut store)
+            .unwrap()
+            .push(3);
+        assert_eq!(er.downcast::<Vec<u8>>(&store).unwrap(), &[1, 2, 3]);
+        assert!(er.downcast_mut::<String>(&mut store).is_none());
+
+        // Clearing the table doesn't drop the data, the store does.
+        table.set(&mut store, 0, Value::ExternRef(None))?;
+        assert!(!dropped.load(Ordering::SeqCst));
+        drop(instance);
+        drop(store);
+        assert!(dropped.load(Ordering::SeqCst));
+
+        Ok(())
+    }
 }
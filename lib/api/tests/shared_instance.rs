@@ -0,0 +1,119 @@
+#![cfg(feature = "sys")]
+
+use std::sync::{Arc, Mutex};
+
+use wasmer::*;
+
+#[test]
+fn shared_instance_is_entered_by_one_thread_at_a_time() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, "(module (memory (export \"memory\") 1))")
+        .map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let shared = SharedInstance::new(store, instance);
+
+    let guard = shared.lock().map_err(|e| format!("{e:?}"))?;
+    // The current thread already entered the instance.
+    assert_eq!(shared.lock().unwrap_err(), SharedInstanceError::Reentrant);
+    assert_eq!(
+        shared.try_lock().unwrap_err(),
+        SharedInstanceError::Reentrant
+    );
+    // Other threads have to wait.
+    let other = shared.clone();
+    std::thread::spawn(move || {
+        assert_eq!(other.try_lock().unwrap_err(), SharedInstanceError::Busy)
+    })
+    .join()
+    .unwrap();
+    drop(guard);
+
+    let other = shared.clone();
+    std::thread::spawn(move || {
+        let mut guard = other.try_lock().unwrap();
+        let (store, instance) = guard.parts();
+        let memory = instance.exports.get_memory("memory").unwrap();
+        memory.view(store).write_u8(0, 42).unwrap();
+    })
+    .join()
+    .unwrap();
+
+    // Handles are still alive, so the store can't be taken back.
+    let other = shared.clone();
+    let shared = shared.into_inner().unwrap_err();
+    drop(other);
+    let (store, instance) = shared
+        .into_inner()
+        .map_err(|_| "the instance is still shared")?;
+    let memory = instance
+        .exports
+        .get_memory("memory")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(
+        memory
+            .view(&store)
+            .read_u8(0)
+            .map_err(|e| format!("{e:?}"))?,
+        42
+    );
+    Ok(())
+}
+
+#[test]
+fn shared_instance_reentrancy_from_host_function() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+            (import "env" "reenter" (func $reenter))
+            (func (export "run") (call $reenter)))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let handle: Arc<Mutex<Option<SharedInstance>>> = Default::default();
+    let result: Arc<Mutex<Option<SharedInstanceError>>> = Default::default();
+    let reenter = {
+        let handle = handle.clone();
+        let result = result.clone();
+        Function::new_typed(&mut store, move || {
+            let shared = handle.lock().unwrap().clone().unwrap();
+            *result.lock().unwrap() = shared.lock().err();
+        })
+    };
+    let instance = Instance::new(
+        &mut store,
+        &module,
+        &imports! { "env" => { "reenter" => reenter } },
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let shared = SharedInstance::new(store, instance);
+    *handle.lock().unwrap() = Some(shared.clone());
+
+    {
+        let mut guard = shared.lock().map_err(|e| format!("{e:?}"))?;
+        let (store, instance) = guard.parts();
+        let run = instance
+            .exports
+            .get_function("run")
+            .map_err(|e| format!("{e:?}"))?;
+        run.call(store, &[]).map_err(|e| format!("{e:?}"))?;
+    }
+    assert_eq!(
+        *result.lock().unwrap(),
+        Some(SharedInstanceError::Reentrant)
+    );
+
+    // Once the guard is dropped, the instance can be entered again.
+    shared.lock().map_err(|e| format!("{e:?}"))?;
+    handle.lock().unwrap().take();
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "the instance doesn't belong to the store")]
+fn shared_instance_checks_the_store() {
+    let mut store = Store::default();
+    let module = Module::new(&store, "(module)").unwrap();
+    let instance = Instance::new(&mut store, &module, &imports! {}).unwrap();
+    SharedInstance::new(Store::default(), instance);
+}
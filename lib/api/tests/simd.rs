@@ -0,0 +1,70 @@
+#![cfg(all(feature = "sys", feature = "compiler"))]
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (import "host" "add_lanes" (func $add_lanes (param v128 i32) (result v128)))
+    (func (export "add") (param v128 v128) (result v128)
+        (i32x4.add (local.get 0) (local.get 1)))
+    (func (export "add_lanes") (param v128 i32) (result v128)
+        (call $add_lanes (local.get 0) (local.get 1))))"#;
+
+const LANES: u128 = 0x00000004_00000003_00000002_00000001;
+
+fn splat(lane: u32) -> u128 {
+    (0..4).fold(0, |v, _| v << 32 | lane as u128)
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn v128_dynamic_calls() -> Result<(), String> {
+    let mut store = Store::new(wasmer::sys::EngineBuilder::new(Cranelift::default()));
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let ty = FunctionType::new(vec![Type::V128, Type::I32], vec![Type::V128]);
+    let add_lanes = Function::new(&mut store, ty, |args| {
+        let lane = args[1].unwrap_i32() as u32;
+        Ok(vec![Value::V128(args[0].unwrap_v128() + splat(lane))])
+    });
+    let imports = imports! {
+        "host" => {
+            "add_lanes" => add_lanes,
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &imports).map_err(|e| format!("{e:?}"))?;
+
+    let add = instance
+        .exports
+        .get_function("add")
+        .map_err(|e| format!("{e:?}"))?;
+    let result = add
+        .call(&mut store, &[Value::from(LANES), Value::V128(splat(10))])
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(
+        result.to_vec(),
+        vec![Value::V128(0x0000000e_0000000d_0000000c_0000000b)]
+    );
+
+    let add_lanes = instance
+        .exports
+        .get_function("add_lanes")
+        .map_err(|e| format!("{e:?}"))?;
+    let result = add_lanes
+        .call(&mut store, &[Value::V128(LANES), Value::I32(1)])
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(
+        u128::try_from(result[0].clone()),
+        Ok(0x00000005_00000004_00000003_00000002)
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "singlepass")]
+#[test]
+fn v128_rejected_by_singlepass() -> Result<(), String> {
+    let store = Store::new(wasmer::sys::EngineBuilder::new(Singlepass::default()));
+    match Module::new(&store, WAT) {
+        Err(CompileError::Validate(_)) => Ok(()),
+        other => Err(format!("unexpected result: {other:?}")),
+    }
+}
@@ -0,0 +1,370 @@
+#![cfg(feature = "sys")]
+
+use wasmer::*;
+
+const WAT: &str = r#"(module
+    (import "host" "answer" (func $answer (result i32)))
+    (memory (export "memory") 1)
+    (table (export "table") 2 funcref)
+    (global $counter (export "counter") (mut i32) (i32.const 0))
+    (global $callback (export "callback") (mut funcref) (ref.null func))
+    (elem declare func $one $answer)
+    (func $one (result i32) (i32.const 1))
+    (func (export "warm_up")
+        (global.set $counter (i32.const 7))
+        (global.set $callback (ref.func $answer))
+        (i32.store (i32.const 100) (i32.const 0xdead))
+        (drop (memory.grow (i32.const 1)))
+        (table.set (i32.const 0) (ref.func $one))
+        (table.set (i32.const 1) (ref.func $answer))
+        (drop (table.grow (ref.func $one) (i32.const 1))))
+    (func (export "call") (param i32) (result i32)
+        (call_indirect (result i32) (local.get 0))))"#;
+
+fn instantiate(store: &mut Store, module: &Module) -> Result<Instance, String> {
+    let imports = imports! {
+        "host" => {
+            "answer" => Function::new_typed(store, || 42),
+        },
+    };
+    Instance::new(store, module, &imports).map_err(|e| format!("{e:?}"))
+}
+
+fn check_state(store: &mut Store, instance: &Instance) -> Result<(), String> {
+    let memory = instance
+        .exports
+        .get_memory("memory")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(memory.view(store).size(), Pages(2));
+    let mut value = [0u32];
+    memory
+        .view(store)
+        .copy_to_slice(100, &mut value)
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(value, [0xdead]);
+    let counter = instance
+        .exports
+        .get_global("counter")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(counter.get(store), Value::I32(7));
+    let callback = instance
+        .exports
+        .get_global("callback")
+        .map_err(|e| format!("{e:?}"))?;
+    match callback.get(store) {
+        Value::FuncRef(Some(callback)) => assert_eq!(
+            callback.call(store, &[]).map_err(|e| format!("{e:?}"))?[0],
+            Value::I32(42)
+        ),
+        other => panic!("unexpected callback {other:?}"),
+    }
+    let table = instance
+        .exports
+        .get_table("table")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(table.size(store), 3);
+    let call: TypedFunction<i32, i32> = instance
+        .exports
+        .get_typed_function(store, "call")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(call.call(store, 0).map_err(|e| format!("{e:?}"))?, 1);
+    assert_eq!(call.call(store, 1).map_err(|e| format!("{e:?}"))?, 42);
+    assert_eq!(call.call(store, 2).map_err(|e| format!("{e:?}"))?, 1);
+    Ok(())
+}
+
+#[test]
+fn snapshot_and_restore() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = instantiate(&mut store, &module)?;
+    let fresh = instance.snapshot(&store).map_err(|e| format!("{e:?}"))?;
+    let warm_up: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "warm_up")
+        .map_err(|e| format!("{e:?}"))?;
+    warm_up.call(&mut store).map_err(|e| format!("{e:?}"))?;
+    let snapshot = instance.snapshot(&store).map_err(|e| format!("{e:?}"))?;
+    check_state(&mut store, &instance)?;
+
+    // Clone the instance in the same store.
+    let clone = instantiate(&mut store, &module)?;
+    clone
+        .restore(&mut store, &snapshot)
+        .map_err(|e| format!("{e:?}"))?;
+    check_state(&mut store, &clone)?;
+
+    // Migrate it to another store through the serialized snapshot.
+    let bytes = snapshot.serialize();
+    let snapshot = InstanceSnapshot::deserialize(&bytes).map_err(|e| format!("{e:?}"))?;
+    let mut other_store = Store::default();
+    let other_module = module
+        .clone_into(&other_store)
+        .map_err(|e| format!("{e:?}"))?;
+    let migrated = instantiate(&mut other_store, &other_module)?;
+    migrated
+        .restore(&mut other_store, &snapshot)
+        .map_err(|e| format!("{e:?}"))?;
+    check_state(&mut other_store, &migrated)?;
+
+    assert!(matches!(
+        InstanceSnapshot::deserialize(&bytes[..bytes.len() - 1]),
+        Err(SnapshotError::Invalid(_))
+    ));
+    assert!(matches!(
+        InstanceSnapshot::deserialize(b"not a snapshot"),
+        Err(SnapshotError::Invalid(_))
+    ));
+
+    // The memory can't shrink back to its initial size.
+    assert!(matches!(
+        instance.restore(&mut store, &fresh),
+        Err(SnapshotError::Incompatible(_))
+    ));
+
+    // Snapshots only apply to instances of the same module.
+    let other_module = Module::new(&store, "(module (memory 1))").map_err(|e| format!("{e:?}"))?;
+    let other =
+        Instance::new(&mut store, &other_module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(
+        other.restore(&mut store, &snapshot),
+        Err(SnapshotError::Incompatible(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn snapshot_unsupported_references() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = instantiate(&mut store, &module)?;
+    let table = instance
+        .exports
+        .get_table("table")
+        .map_err(|e| format!("{e:?}"))?;
+    let host = Function::new_typed(&mut store, || 0);
+    table
+        .set(&mut store, 0, Value::FuncRef(Some(host)))
+        .map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(
+        instance.snapshot(&store),
+        Err(SnapshotError::Unsupported(_))
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "cranelift")]
+#[test]
+fn failed_restore_leaves_the_instance_untouched() -> Result<(), String> {
+    use wasmer::sys::{EngineBuilder, Features};
+
+    let mut features = Features::new();
+    features.multi_memory(true);
+    let mut store =
+        Store::new(EngineBuilder::new(Cranelift::default()).set_features(Some(features)));
+    let wat = |maximum: u32| {
+        format!(
+            r#"(module
+                (memory (export "first") 1)
+                (memory (export "second") 1 {maximum}))"#
+        )
+    };
+    let memory = |instance: &Instance, name: &str| {
+        instance
+            .exports
+            .get_memory(name)
+            .cloned()
+            .map_err(|e| format!("{e:?}"))
+    };
+
+    let module = Module::new(&store, wat(3)).map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    memory(&instance, "first")?
+        .view(&store)
+        .write(100, &[1, 2, 3, 4])
+        .map_err(|e| format!("{e:?}"))?;
+    memory(&instance, "second")?
+        .grow(&mut store, 2)
+        .map_err(|e| format!("{e:?}"))?;
+    let snapshot = instance.snapshot(&store).map_err(|e| format!("{e:?}"))?;
+
+    // The second memory of this instance can't grow to 3 pages.
+    let module = Module::new(&store, wat(2)).map_err(|e| format!("{e:?}"))?;
+    let other = Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(
+        other.restore(&mut store, &snapshot),
+        Err(SnapshotError::Incompatible(_))
+    ));
+    let mut value = [0u8; 4];
+    memory(&other, "first")?
+        .view(&store)
+        .read(100, &mut value)
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(value, [0; 4]);
+    assert_eq!(memory(&other, "second")?.view(&store).size(), Pages(1));
+
+    Ok(())
+}
+
+#[test]
+fn reload_preserves_memory_and_globals() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(&store, WAT).map_err(|e| format!("{e:?}"))?;
+    let instance = instantiate(&mut store, &module)?;
+    let warm_up: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "warm_up")
+        .map_err(|e| format!("{e:?}"))?;
+    warm_up.call(&mut store).map_err(|e| format!("{e:?}"))?;
+
+    // The new version has an extra function, which shifts the indices, and
+    // a table initialized by an element segment.
+    let new_module = Module::new(
+        &store,
+        r#"(module
+            (import "host" "answer" (func $answer (result i32)))
+            (memory (export "memory") 1)
+            (table (export "table") 2 funcref)
+            (global $counter (export "counter") (mut i32) (i32.const 0))
+            (global $callback (export "callback") (mut funcref) (ref.null func))
+            (elem (i32.const 0) $two $three)
+            (func $two (result i32) (i32.const 2))
+            (func $three (result i32) (i32.const 3))
+            (func (export "call") (param i32) (result i32)
+                (call_indirect (result i32) (local.get 0)))
+            (func (export "load") (result i32) (i32.load (i32.const 100))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let imports = imports! {
+        "host" => {
+            "answer" => Function::new_typed(&mut store, || 42),
+        },
+    };
+    let reloaded = instance
+        .reload(&mut store, &new_module, &imports)
+        .map_err(|e| format!("{e:?}"))?;
+
+    let memory = reloaded
+        .exports
+        .get_memory("memory")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(memory.view(&store).size(), Pages(2));
+    let load: TypedFunction<(), i32> = reloaded
+        .exports
+        .get_typed_function(&store, "load")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(load.call(&mut store).map_err(|e| format!("{e:?}"))?, 0xdead);
+    let counter = reloaded
+        .exports
+        .get_global("counter")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(counter.get(&mut store), Value::I32(7));
+    // The callback still refers to the `host.answer` import, even though
+    // its index changed.
+    let callback = reloaded
+        .exports
+        .get_global("callback")
+        .map_err(|e| format!("{e:?}"))?;
+    match callback.get(&mut store) {
+        Value::FuncRef(Some(callback)) => assert_eq!(
+            callback
+                .call(&mut store, &[])
+                .map_err(|e| format!("{e:?}"))?[0],
+            Value::I32(42)
+        ),
+        other => panic!("unexpected callback {other:?}"),
+    }
+
+    // The table refers to the new functions.
+    let table = reloaded
+        .exports
+        .get_table("table")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(table.size(&store), 2);
+    let call: TypedFunction<i32, i32> = reloaded
+        .exports
+        .get_typed_function(&store, "call")
+        .map_err(|e| format!("{e:?}"))?;
+    assert_eq!(call.call(&mut store, 0).map_err(|e| format!("{e:?}"))?, 2);
+    assert_eq!(call.call(&mut store, 1).map_err(|e| format!("{e:?}"))?, 3);
+
+    // The old instance is untouched.
+    check_state(&mut store, &instance)?;
+
+    // A module with a different global layout is rejected.
+    let incompatible = Module::new(&store, "(module (memory 1))").map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(
+        instance.reload(&mut store, &incompatible, &imports! {}),
+        Err(ReloadError::State(SnapshotError::Incompatible(_)))
+    ));
+    // And so is one which can't be instantiated.
+    assert!(matches!(
+        instance.reload(&mut store, &new_module, &imports! {}),
+        Err(ReloadError::Instantiation(InstantiationError::Link(_)))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn reload_relinks_functions_by_name() -> Result<(), String> {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+            (global $exported (export "exported") (mut funcref) (ref.null func))
+            (global $internal (export "internal") (mut funcref) (ref.null func))
+            (elem declare func $seven $eight)
+            (func $seven (export "seven") (result i32) (i32.const 7))
+            (func $eight (result i32) (i32.const 8))
+            (func (export "warm_up")
+                (global.set $exported (ref.func $seven))
+                (global.set $internal (ref.func $eight))))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let instance =
+        Instance::new(&mut store, &module, &imports! {}).map_err(|e| format!("{e:?}"))?;
+    let warm_up: TypedFunction<(), ()> = instance
+        .exports
+        .get_typed_function(&store, "warm_up")
+        .map_err(|e| format!("{e:?}"))?;
+    warm_up.call(&mut store).map_err(|e| format!("{e:?}"))?;
+
+    // The functions are swapped, so relinking by index would be wrong.
+    let new_module = Module::new(
+        &store,
+        r#"(module
+            (global (export "exported") (mut funcref) (ref.null func))
+            (global (export "internal") (mut funcref) (ref.null func))
+            (func (result i32) (i32.const 80))
+            (func (export "seven") (result i32) (i32.const 70)))"#,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let reloaded = instance
+        .reload(&mut store, &new_module, &imports! {})
+        .map_err(|e| format!("{e:?}"))?;
+
+    let exported = reloaded
+        .exports
+        .get_global("exported")
+        .map_err(|e| format!("{e:?}"))?;
+    match exported.get(&mut store) {
+        Value::FuncRef(Some(function)) => assert_eq!(
+            function
+                .call(&mut store, &[])
+                .map_err(|e| format!("{e:?}"))?[0],
+            Value::I32(70)
+        ),
+        other => panic!("unexpected function {other:?}"),
+    }
+    // Functions which aren't imported or exported can't be matched.
+    let internal = reloaded
+        .exports
+        .get_global("internal")
+        .map_err(|e| format!("{e:?}"))?;
+    assert!(matches!(internal.get(&mut store), Value::FuncRef(None)));
+
+    Ok(())
+}
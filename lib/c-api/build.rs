@@ -346,7 +346,11 @@ fn shared_object_dir() -> PathBuf {
 
     assert_eq!(shared_object_dir.file_name(), Some(OsStr::new("build")));
     shared_object_dir.pop();
-    shared_object_dir.pop(); // "debug" or "release"
+
+    // "debug", "release", or the name of a custom profile such as
+    // "release-minimal" (`PROFILE` is "release" for the latter).
+    let profile_dir = shared_object_dir.file_name().unwrap().to_owned();
+    shared_object_dir.pop();
 
     // We either find `target` or the target triple if cross-compiling.
     if shared_object_dir.file_name() != Some(OsStr::new("target")) {
@@ -358,7 +362,7 @@ fn shared_object_dir() -> PathBuf {
         }
     }
 
-    shared_object_dir.push(env::var("PROFILE").unwrap());
+    shared_object_dir.push(profile_dir);
 
     shared_object_dir
 }
@@ -1,12 +1,16 @@
 #[cfg(feature = "compiler")]
 pub use super::unstable::engine::wasmer_is_compiler_available;
-pub use super::unstable::engine::{wasm_config_set_features, wasm_config_set_target};
+pub use super::unstable::engine::{
+    wasm_config_enable_epoch_interruption, wasm_config_set_features, wasm_config_set_target,
+    wasm_config_set_tunables,
+};
 use super::unstable::features::wasmer_features_t;
 #[cfg(feature = "middlewares")]
 pub use super::unstable::middlewares::wasm_config_push_middleware;
 #[cfg(feature = "middlewares")]
 use super::unstable::middlewares::wasmer_middleware_t;
 use super::unstable::target_lexicon::wasmer_target_t;
+use super::unstable::tunables::wasmer_tunables_t;
 use crate::error::update_last_error;
 use cfg_if::cfg_if;
 #[cfg(not(any(feature = "compiler", feature = "compiler-headless")))]
@@ -83,8 +87,10 @@ pub struct wasm_config_t {
     #[cfg(feature = "middlewares")]
     pub(super) middlewares: Vec<wasmer_middleware_t>,
     pub(super) nan_canonicalization: bool,
+    pub(super) epoch_interruption: bool,
     pub(super) features: Option<Box<wasmer_features_t>>,
     pub(super) target: Option<Box<wasmer_target_t>>,
+    pub(super) tunables: Option<Box<wasmer_tunables_t>>,
 }
 
 /// Create a new default Wasmer configuration.
@@ -420,6 +426,10 @@ pub extern "C" fn wasm_engine_new_with_config(
                 compiler_config.canonicalize_nans(true);
             }
 
+            if config.epoch_interruption {
+                compiler_config.enable_epoch_interruption();
+            }
+
             let inner: Engine =
                          {
                             let mut builder = EngineBuilder::new(compiler_config);
@@ -432,7 +442,14 @@ pub extern "C" fn wasm_engine_new_with_config(
                                 builder = builder.set_features(Some(features.inner));
                             }
 
-                            builder.engine()
+                            let mut engine = builder.engine();
+
+                            if let Some(tunables) = config.tunables {
+                                let tunables = tunables.for_target(engine.target());
+                                engine.set_tunables(tunables);
+                            }
+
+                            engine
                         };
             Some(Box::new(wasm_engine_t { inner }))
         } else {
@@ -449,7 +466,14 @@ pub extern "C" fn wasm_engine_new_with_config(
                                 builder = builder.set_features(Some(features.inner));
                             }
 
-                            builder.engine()
+                            let mut engine = builder.engine();
+
+                            if let Some(tunables) = config.tunables {
+                                let tunables = tunables.for_target(engine.target());
+                                engine.set_tunables(tunables);
+                            }
+
+                            engine
                     };
             #[cfg(not(any(feature = "compiler-headless", feature="compiler")))]
             let inner: Engine = Engine::default();
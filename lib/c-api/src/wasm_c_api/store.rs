@@ -1,6 +1,8 @@
 use super::engine::wasm_engine_t;
 use std::cell::UnsafeCell;
 use std::rc::Rc;
+#[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+use wasmer_api::InterruptHandle;
 use wasmer_api::{AsStoreMut, AsStoreRef, Store, StoreMut, StoreRef as BaseStoreRef};
 
 #[derive(Clone)]
@@ -22,6 +24,10 @@ impl StoreRef {
 #[allow(non_camel_case_types)]
 pub struct wasm_store_t {
     pub(crate) inner: StoreRef,
+    /// Kept outside of `inner` so that the store can be interrupted
+    /// from another thread while it runs.
+    #[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+    pub(crate) interrupt: InterruptHandle,
 }
 
 /// Creates a new WebAssembly store given a specific [engine][super::engine].
@@ -37,6 +43,8 @@ pub unsafe extern "C" fn wasm_store_new(
     let store = Store::new(&engine.inner);
 
     Some(Box::new(wasm_store_t {
+        #[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+        interrupt: store.interrupt_handle(),
         inner: StoreRef {
             inner: Rc::new(UnsafeCell::new(store)),
         },
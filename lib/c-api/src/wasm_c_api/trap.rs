@@ -29,6 +29,16 @@ pub unsafe extern "C" fn wasm_trap_new(
     _store: &mut wasm_store_t,
     message: &wasm_message_t,
 ) -> Option<Box<wasm_trap_t>> {
+    let runtime_error = RuntimeError::new(trap_message(message)?);
+
+    let trap = runtime_error.into();
+
+    Some(Box::new(trap))
+}
+
+/// Converts a trap message to a `String`, or returns `None` if it isn't
+/// well-formed.
+pub(crate) fn trap_message(message: &wasm_message_t) -> Option<String> {
     let message_bytes = message.as_slice();
 
     // The trap message is typed with `wasm_message_t` which is a
@@ -36,9 +46,9 @@ pub unsafe extern "C" fn wasm_trap_new(
     // null-terminated string. `RuntimeError` must contain a valid
     // Rust `String` that doesn't contain a null byte. We must ensure
     // this behavior.
-    let runtime_error = match CString::new(message_bytes) {
+    match CString::new(message_bytes) {
         // The string is well-formed and doesn't contain a nul byte.
-        Ok(cstring) => RuntimeError::new(cstring.into_string().ok()?),
+        Ok(cstring) => cstring.into_string().ok(),
 
         // The string is well-formed but is nul-terminated. Let's
         // create a `String` which is null-terminated too.
@@ -46,16 +56,12 @@ pub unsafe extern "C" fn wasm_trap_new(
             let mut vec = nul_error.into_vec();
             vec.pop();
 
-            RuntimeError::new(String::from_utf8(vec).ok()?)
+            String::from_utf8(vec).ok()
         }
 
         // The string not well-formed.
-        Err(_) => return None,
-    };
-
-    let trap = runtime_error.into();
-
-    Some(Box::new(trap))
+        Err(_) => None,
+    }
 }
 
 /// Deletes a trap.
@@ -122,12 +128,18 @@ pub unsafe extern "C" fn wasm_trap_message(
 }
 
 /// Gets the origin frame attached to the trap.
+///
+/// The frames of traps returned by host functions are only reliable when
+/// the module was compiled with Cranelift (see `unstable::frame`).
 #[no_mangle]
 pub unsafe extern "C" fn wasm_trap_origin(trap: &wasm_trap_t) -> Option<Box<wasm_frame_t>> {
     trap.inner.trace().first().map(Into::into).map(Box::new)
 }
 
 /// Gets the trace (as a list of frames) attached to the trap.
+///
+/// The frames of traps returned by host functions are only reliable when
+/// the module was compiled with Cranelift (see `unstable::frame`).
 #[no_mangle]
 pub unsafe extern "C" fn wasm_trap_trace(
     trap: &wasm_trap_t,
@@ -7,6 +7,7 @@ use super::super::engine::{wasm_config_t, wasmer_engine_t};
 
 use super::features::wasmer_features_t;
 use super::target_lexicon::wasmer_target_t;
+use super::tunables::wasmer_tunables_t;
 
 /// Unstable non-standard Wasmer-specific API to update the
 /// configuration to specify a particular target for the engine.
@@ -98,6 +99,81 @@ pub extern "C" fn wasm_config_set_features(
     config.features = Some(features);
 }
 
+/// Unstable non-standard Wasmer-specific API to update the
+/// configuration to specify particular tunables for the engine.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// int main() {
+///     // Create the configuration.
+///     wasm_config_t* config = wasm_config_new();
+///
+///     // Set the tunables.
+///     {
+///         wasmer_tunables_t* tunables = wasmer_tunables_new();
+///         wasmer_tunables_static_memory_bound(tunables, 0x4000);
+///         wasmer_tunables_dynamic_memory_offset_guard_size(tunables, 0x2000);
+///
+///         wasm_config_set_tunables(config, tunables);
+///     }
+///
+///     // Create the engine.
+///     wasm_engine_t* engine = wasm_engine_new_with_config(config);
+///
+///     // Check we have an engine!
+///     assert(engine);
+///
+///     // Memories are still usable with the new tunables.
+///     wasm_store_t* store = wasm_store_new(engine);
+///     wasm_limits_t limits = { 1, 2 };
+///     wasm_memorytype_t* memory_type = wasm_memorytype_new(&limits);
+///     wasm_memory_t* memory = wasm_memory_new(store, memory_type);
+///     assert(memory);
+///     assert(wasm_memory_grow(memory, 1));
+///     assert(!wasm_memory_grow(memory, 1));
+///
+///     // Free everything.
+///     wasm_memory_delete(memory);
+///     wasm_memorytype_delete(memory_type);
+///     wasm_store_delete(store);
+///     wasm_engine_delete(engine);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub extern "C" fn wasm_config_set_tunables(
+    config: &mut wasm_config_t,
+    tunables: Box<wasmer_tunables_t>,
+) {
+    config.tunables = Some(tunables);
+}
+
+/// Updates the configuration to enable epoch-based interruption.
+///
+/// The compiled code then checks the epoch deadline of its store when
+/// entering functions and loops, so that it can be interrupted with
+/// `wasmer_store_interrupt` or `wasmer_engine_increment_epoch`.
+///
+/// This is a Wasmer-specific function.
+///
+/// # Example
+///
+/// See the documentation of the [`interrupt`](super::interrupt) module.
+#[no_mangle]
+pub extern "C" fn wasm_config_enable_epoch_interruption(config: &mut wasm_config_t) {
+    config.epoch_interruption = true;
+}
+
 /// Updates the configuration to enable NaN canonicalization.
 ///
 /// This is a Wasmer-specific function.
@@ -20,6 +20,9 @@
 //!     // And also the memory64 feature.     
 //!     wasmer_features_memory64(features, true);
 //!
+//!     // And extended constant expressions.
+//!     wasmer_features_extended_const(features, true);
+//!
 //!     wasmer_features_delete(features);
 //!
 //!     return 0;
@@ -362,3 +365,101 @@ pub extern "C" fn wasmer_features_memory64(
 
     true
 }
+
+/// Configures whether the WebAssembly exception handling proposal
+/// will be enabled.
+///
+/// The [WebAssembly exception handling proposal][proposal] is not
+/// currently fully standardized and is undergoing development.
+/// Support for this feature can be enabled through this method for
+/// appropriate WebAssembly modules.
+///
+/// This feature gates the `try`, `catch` and `throw` instructions.
+///
+/// This is `false` by default.
+///
+/// [proposal]: https://github.com/WebAssembly/exception-handling
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_features_exceptions(
+    features: Option<&mut wasmer_features_t>,
+    enable: bool,
+) -> bool {
+    let features = match features {
+        Some(features) => features,
+        _ => return false,
+    };
+
+    features.inner.exceptions = enable;
+
+    true
+}
+
+/// Configures whether the WebAssembly relaxed SIMD proposal will be
+/// enabled.
+///
+/// The [WebAssembly relaxed SIMD proposal][proposal] is not currently
+/// fully standardized and is undergoing development. Support for this
+/// feature can be enabled through this method for appropriate
+/// WebAssembly modules.
+///
+/// This feature adds SIMD instructions whose results may depend on
+/// the host platform.
+///
+/// This is `false` by default.
+///
+/// [proposal]: https://github.com/WebAssembly/relaxed-simd
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_features_relaxed_simd(
+    features: Option<&mut wasmer_features_t>,
+    enable: bool,
+) -> bool {
+    let features = match features {
+        Some(features) => features,
+        _ => return false,
+    };
+
+    features.inner.relaxed_simd = enable;
+
+    true
+}
+
+/// Configures whether the WebAssembly extended constant expressions
+/// proposal will be enabled.
+///
+/// The [WebAssembly extended constant expressions proposal][proposal]
+/// is not currently fully standardized and is undergoing development.
+/// Support for this feature can be enabled through this method for
+/// appropriate WebAssembly modules.
+///
+/// This feature allows `i32.add`, `i32.sub`, `i32.mul` and their
+/// `i64` counterparts in constant expressions.
+///
+/// This is `false` by default.
+///
+/// [proposal]: https://github.com/WebAssembly/extended-const
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_features_extended_const(
+    features: Option<&mut wasmer_features_t>,
+    enable: bool,
+) -> bool {
+    let features = match features {
+        Some(features) => features,
+        _ => return false,
+    };
+
+    features.inner.extended_const = enable;
+
+    true
+}
@@ -0,0 +1,168 @@
+//! Unstable non-standard Wasmer-specific API to symbolicate the
+//! frames of a trap, i.e. to get the names of their module and
+//! function.
+//!
+//! The frames returned by `wasm_trap_origin` and `wasm_trap_trace`
+//! describe the WebAssembly functions the trap went through, the
+//! innermost first, including for traps returned by host functions.
+//!
+//! The frames of traps returned by host functions are only reliable
+//! when the module was compiled with Cranelift, as the host function
+//! is unwound back to the WebAssembly code through the unwind
+//! information of its trampoline. With Singlepass and LLVM, the trace
+//! of such a trap may be empty or incomplete, and `wasm_trap_origin`
+//! may return `NULL`. Traps raised by the WebAssembly code itself are
+//! not affected.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use wasmer_inline_c::assert_c;
+//! # fn main() {
+//! #    (assert_c! {
+//! # #include "tests/wasmer.h"
+//! #
+//! wasm_trap_t* host_fail(const wasm_val_vec_t* arguments, wasm_val_vec_t* results) {
+//!     (void) arguments;
+//!     (void) results;
+//!
+//!     wasm_message_t message;
+//!     wasm_name_new_from_string_nt(&message, "failed");
+//!     wasm_trap_t* trap = wasm_trap_new(NULL, &message);
+//!     wasm_name_delete(&message);
+//!
+//!     return trap;
+//! }
+//!
+//! int main() {
+//!     // Create the engine and the store.
+//!     wasm_engine_t* engine = wasm_engine_new();
+//!     wasm_store_t* store = wasm_store_new(engine);
+//!
+//!     // Create a WebAssembly module calling the host function from
+//!     // `$inner`.
+//!     wasm_byte_vec_t wat;
+//!     wasmer_byte_vec_new_from_string(
+//!         &wat,
+//!         "(module $m\n"
+//!         "  (import \"env\" \"fail\" (func $fail))\n"
+//!         "  (func $inner (call $fail))\n"
+//!         "  (func (export \"run\") (call $inner)))"
+//!     );
+//!     wasm_byte_vec_t wasm;
+//!     wat2wasm(&wat, &wasm);
+//!
+//!     wasm_module_t* module = wasm_module_new(store, &wasm);
+//!     assert(module);
+//!
+//!     wasm_functype_t* fail_type = wasm_functype_new_0_0();
+//!     wasm_func_t* fail = wasm_func_new(store, fail_type, host_fail);
+//!     wasm_functype_delete(fail_type);
+//!
+//!     wasm_extern_t* externs[] = { wasm_func_as_extern(fail) };
+//!     wasm_extern_vec_t imports = WASM_ARRAY_VEC(externs);
+//!     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+//!     assert(instance);
+//!
+//!     wasm_extern_vec_t exports;
+//!     wasm_instance_exports(instance, &exports);
+//!     const wasm_func_t* run = wasm_extern_as_func(exports.data[0]);
+//!
+//!     // Call `run`, which traps.
+//!     wasm_val_vec_t arguments = WASM_EMPTY_VEC;
+//!     wasm_val_vec_t results = WASM_EMPTY_VEC;
+//!     wasm_trap_t* trap = wasm_func_call(run, &arguments, &results);
+//!     assert(trap);
+//!
+//!     // The trace goes through `$inner`, then `run`.
+//!     wasm_frame_vec_t trace;
+//!     wasm_trap_trace(trap, &trace);
+//!     assert(trace.size == 2);
+//!
+//!     wasm_name_t name;
+//!     wasmer_frame_module_name(trace.data[0], &name);
+//!     wasmer_assert_name(&name, "m");
+//!     wasm_name_delete(&name);
+//!
+//!     assert(wasm_frame_func_index(trace.data[0]) == 1);
+//!     assert(wasmer_frame_func_name(trace.data[0], &name));
+//!     wasmer_assert_name(&name, "inner");
+//!     wasm_name_delete(&name);
+//!
+//!     // Exported functions are named after their export.
+//!     assert(wasm_frame_func_index(trace.data[1]) == 2);
+//!     assert(wasmer_frame_func_name(trace.data[1], &name));
+//!     wasmer_assert_name(&name, "run");
+//!     wasm_name_delete(&name);
+//!
+//!     // The origin is the innermost frame.
+//!     wasm_frame_t* origin = wasm_trap_origin(trap);
+//!     assert(origin);
+//!     assert(wasm_frame_func_index(origin) == 1);
+//!     assert(wasm_frame_module_offset(origin) == wasm_frame_module_offset(trace.data[0]));
+//!     wasm_frame_delete(origin);
+//!
+//!     wasm_frame_vec_delete(&trace);
+//!     wasm_trap_delete(trap);
+//!     wasm_extern_vec_delete(&exports);
+//!     wasm_instance_delete(instance);
+//!     wasm_func_delete(fail);
+//!     wasm_module_delete(module);
+//!     wasm_byte_vec_delete(&wasm);
+//!     wasm_byte_vec_delete(&wat);
+//!     wasm_store_delete(store);
+//!     wasm_engine_delete(engine);
+//!
+//!     return 0;
+//! }
+//! #    })
+//! #    .success();
+//! # }
+//! ```
+
+use super::super::types::{wasm_frame_t, wasm_name_t};
+
+/// Gets the name of the module of `frame`.
+///
+/// It is the name from the `name` section of the module, or the
+/// one set with [`wasmer_module_set_name`], or else `<module>`.
+///
+/// [`wasmer_module_set_name`]: super::module::wasmer_module_set_name
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_frame_module_name(
+    frame: &wasm_frame_t,
+    // own
+    out: &mut wasm_name_t,
+) {
+    out.set_buffer(frame.info.module_name().as_bytes().to_vec());
+}
+
+/// Gets the name of the function of `frame`, from the `name`
+/// section of the module, or else from the first export of the
+/// function.
+///
+/// Returns `false` if the function has no name, in which case `out`
+/// is left untouched.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_frame_func_name(
+    frame: &wasm_frame_t,
+    // own
+    out: &mut wasm_name_t,
+) -> bool {
+    match frame.info.function_name() {
+        Some(name) => {
+            out.set_buffer(name.as_bytes().to_vec());
+
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,142 @@
+//! Unstable non-standard Wasmer-specific API to read the resources
+//! used by an instance, e.g. to report the consumption of each guest.
+//!
+//! Only the memories and tables defined by the instance are
+//! accounted, the imported ones belong to the host. To read the
+//! metering points of an instance, see
+//! [`wasmer_metering_get_remaining_points`].
+//!
+//! [`wasmer_metering_get_remaining_points`]: super::middlewares::metering::wasmer_metering_get_remaining_points
+//!
+//! # Example
+//!
+//! ```rust
+//! # use wasmer_inline_c::assert_c;
+//! # fn main() {
+//! #    (assert_c! {
+//! # #include "tests/wasmer.h"
+//! #
+//! int main() {
+//!     // Create the engine and the store.
+//!     wasm_engine_t* engine = wasm_engine_new();
+//!     wasm_store_t* store = wasm_store_new(engine);
+//!
+//!     // Create a module with a memory and a table it can grow.
+//!     wasm_byte_vec_t wat;
+//!     wasmer_byte_vec_new_from_string(
+//!         &wat,
+//!         "(module\n"
+//!         "  (memory 1)\n"
+//!         "  (table 2 funcref)\n"
+//!         "  (func (export \"grow\")\n"
+//!         "    (drop (memory.grow (i32.const 2)))\n"
+//!         "    (drop (table.grow (ref.null func) (i32.const 3)))))"
+//!     );
+//!     wasm_byte_vec_t wasm;
+//!     wat2wasm(&wat, &wasm);
+//!
+//!     wasm_module_t* module = wasm_module_new(store, &wasm);
+//!     assert(module);
+//!
+//!     wasm_extern_vec_t imports = WASM_EMPTY_VEC;
+//!     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+//!     assert(instance);
+//!
+//!     // Read the initial sizes.
+//!     assert(wasmer_instance_memory_pages(instance) == 1);
+//!     assert(wasmer_instance_table_count(instance) == 1);
+//!
+//!     uint32_t size;
+//!     assert(wasmer_instance_table_size(instance, 0, &size));
+//!     assert(size == 2);
+//!     assert(!wasmer_instance_table_size(instance, 1, &size));
+//!
+//!     // Grow the memory and the table.
+//!     wasm_extern_vec_t exports;
+//!     wasm_instance_exports(instance, &exports);
+//!     const wasm_func_t* grow = wasm_extern_as_func(exports.data[0]);
+//!
+//!     wasm_val_vec_t arguments = WASM_EMPTY_VEC;
+//!     wasm_val_vec_t results = WASM_EMPTY_VEC;
+//!     wasm_trap_t* trap = wasm_func_call(grow, &arguments, &results);
+//!     assert(!trap);
+//!
+//!     // The new sizes are reported.
+//!     assert(wasmer_instance_memory_pages(instance) == 3);
+//!     assert(wasmer_instance_table_size(instance, 0, &size));
+//!     assert(size == 5);
+//!
+//!     wasm_extern_vec_delete(&exports);
+//!     wasm_instance_delete(instance);
+//!     wasm_module_delete(module);
+//!     wasm_byte_vec_delete(&wasm);
+//!     wasm_byte_vec_delete(&wat);
+//!     wasm_store_delete(store);
+//!     wasm_engine_delete(engine);
+//!
+//!     return 0;
+//! }
+//! #    })
+//! #    .success();
+//! # }
+//! ```
+
+use super::super::instance::wasm_instance_t;
+
+/// Returns the total size, in pages, of the memories defined by
+/// `instance`.
+///
+/// Memories never shrink, so it is also the largest size the
+/// memories have reached so far.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_memory_pages(instance: &wasm_instance_t) -> u64 {
+    instance
+        .inner
+        .memory_sizes(&instance.store.store())
+        .iter()
+        .map(|pages| u64::from(pages.0))
+        .sum()
+}
+
+/// Returns the number of tables defined by `instance`.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_table_count(instance: &wasm_instance_t) -> usize {
+    instance.inner.table_sizes(&instance.store.store()).len()
+}
+
+/// Reads the size, in elements, of the table defined by `instance`
+/// at `index`, in the order of their definition.
+///
+/// Returns `false` if there is no such table, in which case `size`
+/// is left untouched.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_instance_table_size(
+    instance: &wasm_instance_t,
+    index: usize,
+    size: &mut u32,
+) -> bool {
+    match instance
+        .inner
+        .table_sizes(&instance.store.store())
+        .get(index)
+    {
+        Some(table_size) => {
+            *size = *table_size;
+
+            true
+        }
+        None => false,
+    }
+}
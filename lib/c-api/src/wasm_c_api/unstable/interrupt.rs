@@ -0,0 +1,129 @@
+//! Unstable non-standard Wasmer-specific API to interrupt the code
+//! running in a store, e.g. to implement timeouts.
+//!
+//! The modules must be compiled with epoch interruption enabled, see
+//! [`wasm_config_enable_epoch_interruption`]. An interrupted call
+//! returns a trap for which [`wasmer_trap_is_interrupt`] returns
+//! `true`.
+//!
+//! [`wasm_config_enable_epoch_interruption`]: super::engine::wasm_config_enable_epoch_interruption
+//! [`wasmer_trap_is_interrupt`]: super::trap::wasmer_trap_is_interrupt
+//!
+//! # Example
+//!
+//! ```rust
+//! # use wasmer_inline_c::assert_c;
+//! # fn main() {
+//! #    (assert_c! {
+//! # #include "tests/wasmer.h"
+//! # #include <pthread.h>
+//! # #include <unistd.h>
+//! #
+//! // Interrupt the store after 50ms, from another thread.
+//! void* timeout(void* store) {
+//!     usleep(50000);
+//!     wasmer_store_interrupt(store);
+//!
+//!     return NULL;
+//! }
+//!
+//! int main() {
+//!     // Enable epoch interruption in the configuration.
+//!     wasm_config_t* config = wasm_config_new();
+//!     wasm_config_enable_epoch_interruption(config);
+//!
+//!     wasm_engine_t* engine = wasm_engine_new_with_config(config);
+//!     wasm_store_t* store = wasm_store_new(engine);
+//!
+//!     // Create a module running forever.
+//!     wasm_byte_vec_t wat;
+//!     wasmer_byte_vec_new_from_string(&wat, "(module (func (export \"spin\") (loop (br 0))))");
+//!     wasm_byte_vec_t wasm;
+//!     wat2wasm(&wat, &wasm);
+//!
+//!     wasm_module_t* module = wasm_module_new(store, &wasm);
+//!     assert(module);
+//!
+//!     wasm_extern_vec_t imports = WASM_EMPTY_VEC;
+//!     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+//!     assert(instance);
+//!
+//!     wasm_extern_vec_t exports;
+//!     wasm_instance_exports(instance, &exports);
+//!     const wasm_func_t* spin = wasm_extern_as_func(exports.data[0]);
+//!
+//!     // Run it until it is interrupted.
+//!     pthread_t thread;
+//!     pthread_create(&thread, NULL, timeout, store);
+//!
+//!     wasm_val_vec_t arguments = WASM_EMPTY_VEC;
+//!     wasm_val_vec_t results = WASM_EMPTY_VEC;
+//!     wasm_trap_t* trap = wasm_func_call(spin, &arguments, &results);
+//!     pthread_join(thread, NULL);
+//!
+//!     assert(trap);
+//!     assert(wasmer_trap_is_interrupt(trap));
+//!     wasm_trap_delete(trap);
+//!
+//!     // Let the store run again.
+//!     wasmer_store_set_epoch_deadline(store, UINT64_MAX);
+//!
+//!     wasm_extern_vec_delete(&exports);
+//!     wasm_instance_delete(instance);
+//!     wasm_module_delete(module);
+//!     wasm_byte_vec_delete(&wasm);
+//!     wasm_byte_vec_delete(&wat);
+//!     wasm_store_delete(store);
+//!     wasm_engine_delete(engine);
+//!
+//!     return 0;
+//! }
+//! #    })
+//! #    .success();
+//! # }
+//! ```
+
+use super::super::engine::wasm_engine_t;
+use super::super::store::wasm_store_t;
+
+/// Interrupts the code running in `store`.
+///
+/// This function can be called from any thread, while the store is
+/// running on another one. The running code traps when it enters a
+/// function or a loop, and so do later calls until the deadline is
+/// reset with [`wasmer_store_set_epoch_deadline`]. Other stores
+/// sharing the same engine are not affected.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_store_interrupt(store: &wasm_store_t) {
+    store.interrupt.interrupt();
+}
+
+/// Sets the epoch deadline of `store` to `ticks` epochs after the
+/// current epoch of its engine. `UINT64_MAX` removes the deadline,
+/// which is the default.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_store_set_epoch_deadline(store: &mut wasm_store_t, ticks: u64) {
+    store.inner.store_mut().set_epoch_deadline(ticks);
+}
+
+/// Increments the epoch of `engine`, interrupting the code running in
+/// the stores whose epoch deadline is reached.
+///
+/// This function is cheap and can be called from any thread, for
+/// instance from a timer.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_engine_increment_epoch(engine: &wasm_engine_t) {
+    engine.inner.increment_epoch();
+}
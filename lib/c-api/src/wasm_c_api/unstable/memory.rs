@@ -0,0 +1,460 @@
+//! Unstable non-standard Wasmer-specific API to use shared memories,
+//! as defined by the WebAssembly threads proposal, from C.
+//!
+//! A shared memory is created from a memory type built with
+//! [`wasmer_memorytype_new_shared`]. Since a store can only be used by
+//! one thread at a time, each thread runs its instances in its own
+//! store, in which the memory is imported with
+//! [`wasmer_memory_share_in_store`]. The host can then access the
+//! memory atomically, and wait for or notify the WebAssembly threads,
+//! with the `wasmer_memory_atomic_*` functions.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use wasmer_inline_c::assert_c;
+//! # fn main() {
+//! #    (assert_c! {
+//! # #include "tests/wasmer.h"
+//! # #include <pthread.h>
+//! #
+//! typedef struct {
+//!     wasm_store_t* store;
+//!     wasm_memory_t* memory;
+//!     const wasm_byte_vec_t* wasm;
+//! } worker_t;
+//!
+//! // Runs the module in its own store, on another thread.
+//! void* run_worker(void* data) {
+//!     worker_t* worker = data;
+//!
+//!     wasm_module_t* module = wasm_module_new(worker->store, worker->wasm);
+//!     assert(module);
+//!
+//!     wasm_extern_t* externs[] = { wasm_memory_as_extern(worker->memory) };
+//!     wasm_extern_vec_t imports = WASM_ARRAY_VEC(externs);
+//!     wasm_instance_t* instance = wasm_instance_new(worker->store, module, &imports, NULL);
+//!     assert(instance);
+//!
+//!     wasm_extern_vec_t exports;
+//!     wasm_instance_exports(instance, &exports);
+//!     const wasm_func_t* run = wasm_extern_as_func(exports.data[0]);
+//!
+//!     wasm_val_vec_t arguments = WASM_EMPTY_VEC;
+//!     wasm_val_vec_t results = WASM_EMPTY_VEC;
+//!     assert(!wasm_func_call(run, &arguments, &results));
+//!
+//!     wasm_extern_vec_delete(&exports);
+//!     wasm_instance_delete(instance);
+//!     wasm_module_delete(module);
+//!
+//!     return NULL;
+//! }
+//!
+//! int main() {
+//!     wasm_engine_t* engine = wasm_engine_new();
+//!     wasm_store_t* store = wasm_store_new(engine);
+//!
+//!     // Create a shared memory of one page.
+//!     wasm_limits_t limits = { 1, 1 };
+//!     wasm_memorytype_t* memory_type = wasmer_memorytype_new_shared(&limits);
+//!     assert(memory_type);
+//!     assert(wasmer_memorytype_is_shared(memory_type));
+//!
+//!     wasm_memory_t* memory = wasm_memory_new(store, memory_type);
+//!     assert(memory);
+//!     wasm_memorytype_delete(memory_type);
+//!
+//!     // Nobody notifies the address 8, so waiting on it times out
+//!     // after 1ms.
+//!     uint32_t result;
+//!     assert(wasmer_memory_atomic_wait32(memory, 8, 0, 1000000, &result));
+//!     assert(result == 2);
+//!
+//!     // Misaligned accesses fail.
+//!     uint32_t value;
+//!     assert(!wasmer_memory_atomic_load32(memory, 2, &value));
+//!     assert(wasmer_last_error_length() > 0);
+//!
+//!     // The module signals that it started by storing 1 at the address
+//!     // 0, waits for the host to change it, and then stores 42 at the
+//!     // address 4.
+//!     wasm_byte_vec_t wat;
+//!     wasmer_byte_vec_new_from_string(
+//!         &wat,
+//!         "(module\n"
+//!         "  (import \"env\" \"memory\" (memory 1 1 shared))\n"
+//!         "  (func (export \"run\")\n"
+//!         "    (i32.atomic.store (i32.const 0) (i32.const 1))\n"
+//!         "    (drop (memory.atomic.wait32 (i32.const 0) (i32.const 1) (i64.const -1)))\n"
+//!         "    (i32.atomic.store (i32.const 4) (i32.const 42))))"
+//!     );
+//!     wasm_byte_vec_t wasm;
+//!     wat2wasm(&wat, &wasm);
+//!
+//!     // Share the memory in the store of the worker thread.
+//!     worker_t worker;
+//!     worker.store = wasm_store_new(engine);
+//!     worker.memory = wasmer_memory_share_in_store(memory, worker.store);
+//!     worker.wasm = &wasm;
+//!     assert(worker.memory);
+//!
+//!     pthread_t thread;
+//!     pthread_create(&thread, NULL, run_worker, &worker);
+//!
+//!     // Wait for the module to start, then wake it up.
+//!     do {
+//!         assert(wasmer_memory_atomic_load32(memory, 0, &value));
+//!     } while (value != 1);
+//!
+//!     assert(wasmer_memory_atomic_store32(memory, 0, 2));
+//!     assert(wasmer_memory_atomic_notify(memory, 0, 1, &result));
+//!
+//!     pthread_join(thread, NULL);
+//!
+//!     // The module wrote to the memory of the host.
+//!     assert(wasmer_memory_atomic_load32(memory, 4, &value));
+//!     assert(value == 42);
+//!
+//!     wasm_memory_delete(worker.memory);
+//!     wasm_store_delete(worker.store);
+//!     wasm_byte_vec_delete(&wasm);
+//!     wasm_byte_vec_delete(&wat);
+//!     wasm_memory_delete(memory);
+//!     wasm_store_delete(store);
+//!     wasm_engine_delete(engine);
+//!
+//!     return 0;
+//! }
+//! #    })
+//! #    .success();
+//! # }
+//! ```
+
+use super::super::externals::{wasm_extern_t, wasm_memory_t};
+use super::super::store::wasm_store_t;
+use super::super::types::{wasm_limits_t, wasm_memorytype_t};
+use crate::error::update_last_error;
+use std::time::Duration;
+use wasmer_api::{AtomicsError, MemoryAtomics, MemoryType, Pages};
+
+/// Creates a new shared memory type.
+///
+/// Shared memories must have a maximum size, so this function
+/// returns `NULL` if `limits->max` is `wasm_limits_max_default`.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_memorytype_new_shared(
+    limits: &wasm_limits_t,
+) -> Option<Box<wasm_memorytype_t>> {
+    if limits.max == u32::MAX {
+        return None;
+    }
+
+    Some(Box::new(wasm_memorytype_t::new(MemoryType::new(
+        Pages(limits.min),
+        Some(Pages(limits.max)),
+        true,
+    ))))
+}
+
+/// Checks whether a memory type is shared.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_memorytype_is_shared(memory_type: &wasm_memorytype_t) -> bool {
+    memory_type.inner().memory_type.shared
+}
+
+/// Imports the shared `memory` in another store, typically the store
+/// of another thread. Both memories share the same data.
+///
+/// Returns `NULL` if `memory` isn't shared.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_share_in_store(
+    memory: &wasm_memory_t,
+    store: &mut wasm_store_t,
+) -> Option<Box<wasm_memory_t>> {
+    let shared = c_try!(memory
+        .extern_
+        .memory()
+        .share_in_store(&memory.extern_.store.store(), &mut store.inner.store_mut()));
+
+    Some(Box::new(wasm_memory_t {
+        extern_: wasm_extern_t::new(store.inner.clone(), shared.into()),
+    }))
+}
+
+/// Runs `op` on the atomics of `memory`, updating the last error if
+/// the memory isn't shared or if `op` fails.
+unsafe fn with_atomics<T>(
+    memory: &wasm_memory_t,
+    op: impl FnOnce(&MemoryAtomics) -> Result<T, AtomicsError>,
+) -> Option<T> {
+    let store = memory.extern_.store.store();
+    let atomics = match memory.extern_.memory().atomics(&store) {
+        Some(atomics) => atomics,
+        None => {
+            update_last_error("the memory is not a shared memory");
+            return None;
+        }
+    };
+
+    Some(c_try!(op(&atomics)))
+}
+
+/// Converts a timeout in nanoseconds, negative meaning no timeout.
+fn timeout(nanoseconds: i64) -> Option<Duration> {
+    u64::try_from(nanoseconds).ok().map(Duration::from_nanos)
+}
+
+/// Atomically loads the `uint32_t` at `offset` in the shared `memory`
+/// into `out`.
+///
+/// Returns `false` if `memory` isn't shared, or if `offset` is out of
+/// bounds or not aligned. The error can be read with
+/// `wasmer_last_error_message`.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_load32(
+    memory: &wasm_memory_t,
+    offset: u64,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.load32(offset))
+        .map(|value| *out = value)
+        .is_some()
+}
+
+/// Atomically loads the `uint64_t` at `offset` in the shared `memory`
+/// into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_load64(
+    memory: &wasm_memory_t,
+    offset: u64,
+    out: &mut u64,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.load64(offset))
+        .map(|value| *out = value)
+        .is_some()
+}
+
+/// Atomically stores the `uint32_t` `value` at `offset` in the shared
+/// `memory`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_store32(
+    memory: &wasm_memory_t,
+    offset: u64,
+    value: u32,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.store32(offset, value)).is_some()
+}
+
+/// Atomically stores the `uint64_t` `value` at `offset` in the shared
+/// `memory`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_store64(
+    memory: &wasm_memory_t,
+    offset: u64,
+    value: u64,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.store64(offset, value)).is_some()
+}
+
+/// Atomically adds `value` to the `uint32_t` at `offset` in the shared
+/// `memory`, wrapping around on overflow, and writes the previous
+/// value into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_fetch_add32(
+    memory: &wasm_memory_t,
+    offset: u64,
+    value: u32,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.fetch_add32(offset, value))
+        .map(|previous| *out = previous)
+        .is_some()
+}
+
+/// Atomically adds `value` to the `uint64_t` at `offset` in the shared
+/// `memory`, wrapping around on overflow, and writes the previous
+/// value into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_fetch_add64(
+    memory: &wasm_memory_t,
+    offset: u64,
+    value: u64,
+    out: &mut u64,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.fetch_add64(offset, value))
+        .map(|previous| *out = previous)
+        .is_some()
+}
+
+/// Atomically replaces the `uint32_t` at `offset` in the shared
+/// `memory` with `value`, and writes the previous value into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_swap32(
+    memory: &wasm_memory_t,
+    offset: u64,
+    value: u32,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.swap32(offset, value))
+        .map(|previous| *out = previous)
+        .is_some()
+}
+
+/// Atomically replaces the `uint64_t` at `offset` in the shared
+/// `memory` with `value`, and writes the previous value into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_swap64(
+    memory: &wasm_memory_t,
+    offset: u64,
+    value: u64,
+    out: &mut u64,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.swap64(offset, value))
+        .map(|previous| *out = previous)
+        .is_some()
+}
+
+/// Atomically replaces the `uint32_t` at `offset` in the shared
+/// `memory` with `replacement` if it is equal to `expected`, and
+/// writes the previous value into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_compare_exchange32(
+    memory: &wasm_memory_t,
+    offset: u64,
+    expected: u32,
+    replacement: u32,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| {
+        atomics.compare_exchange32(offset, expected, replacement)
+    })
+    .map(|previous| *out = previous)
+    .is_some()
+}
+
+/// Atomically replaces the `uint64_t` at `offset` in the shared
+/// `memory` with `replacement` if it is equal to `expected`, and
+/// writes the previous value into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_compare_exchange64(
+    memory: &wasm_memory_t,
+    offset: u64,
+    expected: u64,
+    replacement: u64,
+    out: &mut u64,
+) -> bool {
+    with_atomics(memory, |atomics| {
+        atomics.compare_exchange64(offset, expected, replacement)
+    })
+    .map(|previous| *out = previous)
+    .is_some()
+}
+
+/// Blocks the current thread until the address `offset` in the shared
+/// `memory` is notified, if the `uint32_t` stored there is equal to
+/// `expected`, like the `memory.atomic.wait32` instruction.
+///
+/// `timeout` is in nanoseconds; a negative value waits forever. The
+/// outcome is written into `out`: 0 if the thread was notified, 1 if
+/// the value wasn't equal to `expected`, and 2 if the timeout elapsed.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_wait32(
+    memory: &wasm_memory_t,
+    offset: u64,
+    expected: u32,
+    timeout_ns: i64,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| {
+        atomics.wait32(offset, expected, timeout(timeout_ns))
+    })
+    .map(|outcome| *out = outcome)
+    .is_some()
+}
+
+/// Blocks the current thread until the address `offset` in the shared
+/// `memory` is notified, if the `uint64_t` stored there is equal to
+/// `expected`, like the `memory.atomic.wait64` instruction.
+///
+/// See [`wasmer_memory_atomic_wait32`] for the parameters and the
+/// outcome.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_wait64(
+    memory: &wasm_memory_t,
+    offset: u64,
+    expected: u64,
+    timeout_ns: i64,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| {
+        atomics.wait64(offset, expected, timeout(timeout_ns))
+    })
+    .map(|outcome| *out = outcome)
+    .is_some()
+}
+
+/// Wakes up to `count` threads waiting on the address `offset` in the
+/// shared `memory`, like the `memory.atomic.notify` instruction, and
+/// writes the number of threads woken up into `out`.
+///
+/// See [`wasmer_memory_atomic_load32`] for the errors.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_memory_atomic_notify(
+    memory: &wasm_memory_t,
+    offset: u64,
+    count: u32,
+    out: &mut u32,
+) -> bool {
+    with_atomics(memory, |atomics| atomics.notify(offset, count))
+        .map(|woken| *out = woken)
+        .is_some()
+}
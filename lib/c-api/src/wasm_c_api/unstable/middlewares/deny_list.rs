@@ -0,0 +1,186 @@
+//! Unstable non-standard Wasmer-specific API that contains everything
+//! to create the deny-list middleware.
+//!
+//! The deny-list middleware rejects the modules using some operators,
+//! e.g. floating point instructions. Such modules fail to compile.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use wasmer_inline_c::assert_c;
+//! # fn main() {
+//! #    (assert_c! {
+//! # #include "tests/wasmer.h"
+//! #
+//! int main() {
+//!     // Deny the floating point additions.
+//!     wasmer_parser_operator_t denied[] = { F32Add, F64Add };
+//!     wasmer_deny_list_t* deny_list = wasmer_deny_list_new_from_operators(denied, 2);
+//!
+//!     // Consume `deny_list` to produce a generic `wasmer_middleware_t` value.
+//!     wasmer_middleware_t* middleware = wasmer_deny_list_as_middleware(deny_list);
+//!
+//!     // Create a new configuration, and push the middleware in it.
+//!     wasm_config_t* config = wasm_config_new();
+//!     wasm_config_push_middleware(config, middleware);
+//!
+//!     // Create the engine and the store based on the configuration.
+//!     wasm_engine_t* engine = wasm_engine_new_with_config(config);
+//!     wasm_store_t* store = wasm_store_new(engine);
+//!
+//!     // A module adding integers compiles.
+//!     {
+//!         wasm_byte_vec_t wat;
+//!         wasmer_byte_vec_new_from_string(
+//!             &wat,
+//!             "(module\n"
+//!             "  (func (param i32 i32) (result i32)\n"
+//!             "    local.get 0\n"
+//!             "    local.get 1\n"
+//!             "    i32.add))"
+//!         );
+//!         wasm_byte_vec_t wasm;
+//!         wat2wasm(&wat, &wasm);
+//!
+//!         wasm_module_t* module = wasm_module_new(store, &wasm);
+//!         assert(module);
+//!
+//!         wasm_module_delete(module);
+//!         wasm_byte_vec_delete(&wasm);
+//!         wasm_byte_vec_delete(&wat);
+//!     }
+//!
+//!     // A module adding floats doesn't.
+//!     {
+//!         wasm_byte_vec_t wat;
+//!         wasmer_byte_vec_new_from_string(
+//!             &wat,
+//!             "(module\n"
+//!             "  (func (param f32 f32) (result f32)\n"
+//!             "    local.get 0\n"
+//!             "    local.get 1\n"
+//!             "    f32.add))"
+//!         );
+//!         wasm_byte_vec_t wasm;
+//!         wat2wasm(&wat, &wasm);
+//!
+//!         wasm_module_t* module = wasm_module_new(store, &wasm);
+//!         assert(!module);
+//!         assert(wasmer_last_error_length() > 0);
+//!
+//!         wasm_byte_vec_delete(&wasm);
+//!         wasm_byte_vec_delete(&wat);
+//!     }
+//!
+//!     wasm_store_delete(store);
+//!     wasm_engine_delete(engine);
+//!
+//!     return 0;
+//! }
+//! #    })
+//! #    .success();
+//! # }
+//! ```
+
+use super::super::parser::operator::wasmer_parser_operator_t;
+use super::wasmer_middleware_t;
+use std::slice;
+use std::sync::Arc;
+use wasmer_api::wasmparser::Operator;
+use wasmer_middlewares::DenyList;
+
+/// Opaque type representing a deny-list middleware.
+///
+/// To transform this specific middleware into a generic one, please
+/// see [`wasmer_deny_list_as_middleware`].
+///
+/// # Example
+///
+/// See module's documentation.
+#[allow(non_camel_case_types, clippy::type_complexity)]
+pub struct wasmer_deny_list_t {
+    pub(crate) inner: Arc<DenyList<Box<dyn Fn(&Operator) -> bool + Send + Sync>>>,
+}
+
+/// Function type to represent a user-defined predicate implemented
+/// in C, returning `true` for the denied operators.
+///
+/// # Example
+///
+/// See module's documentation.
+#[allow(non_camel_case_types)]
+pub type wasmer_deny_list_predicate_t =
+    extern "C" fn(wasm_operator: wasmer_parser_operator_t) -> bool;
+
+/// Creates a new deny-list middleware rejecting the operators for
+/// which `is_denied` returns `true`.
+///
+/// # Example
+///
+/// See module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_deny_list_new(
+    is_denied: wasmer_deny_list_predicate_t,
+) -> Box<wasmer_deny_list_t> {
+    let is_denied = move |operator: &Operator| -> bool { is_denied(operator.into()) };
+
+    Box::new(wasmer_deny_list_t {
+        inner: Arc::new(DenyList::new(Box::new(is_denied))),
+    })
+}
+
+/// Creates a new deny-list middleware rejecting the `length`
+/// operators pointed to by `operators`.
+///
+/// The operators are copied, so the array can be freed once this
+/// function returns.
+///
+/// # Example
+///
+/// See module's documentation.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_deny_list_new_from_operators(
+    operators: *const wasmer_parser_operator_t,
+    length: usize,
+) -> Box<wasmer_deny_list_t> {
+    let denied = if length == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(operators, length).to_vec()
+    };
+    let is_denied = move |operator: &Operator| -> bool {
+        denied.contains(&wasmer_parser_operator_t::from(operator))
+    };
+
+    Box::new(wasmer_deny_list_t {
+        inner: Arc::new(DenyList::new(Box::new(is_denied))),
+    })
+}
+
+/// Deletes a [`wasmer_deny_list_t`].
+///
+/// # Example
+///
+/// See module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_deny_list_delete(_deny_list: Option<Box<wasmer_deny_list_t>>) {}
+
+/// Transforms a [`wasmer_deny_list_t`] into a generic
+/// [`wasmer_middleware_t`], to then be pushed in the configuration with
+/// [`wasm_config_push_middleware`][super::wasm_config_push_middleware].
+///
+/// This function takes ownership of `deny_list`.
+///
+/// # Example
+///
+/// See module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_deny_list_as_middleware(
+    deny_list: Option<Box<wasmer_deny_list_t>>,
+) -> Option<Box<wasmer_middleware_t>> {
+    let deny_list = deny_list?;
+
+    Some(Box::new(wasmer_middleware_t {
+        inner: deny_list.inner,
+    }))
+}
@@ -69,6 +69,9 @@
 //!     wasm_trap_t* trap = NULL;
 //!     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, &trap);
 //!     assert(instance);
+//!
+//!     // The module has been compiled with the metering middleware.
+//!     assert(wasmer_metering_is_enabled(instance));
 //!     
 //!     // Here we go. At this step, we will get the `add_two` exported function, and
 //!     // call it.
@@ -132,6 +135,7 @@
 //! ```
 
 use super::super::super::instance::wasm_instance_t;
+use super::super::super::store::wasm_store_t;
 use super::super::parser::operator::wasmer_parser_operator_t;
 use super::wasmer_middleware_t;
 use std::sync::Arc;
@@ -192,6 +196,27 @@ pub extern "C" fn wasmer_metering_new(
 #[no_mangle]
 pub extern "C" fn wasmer_metering_delete(_metering: Option<Box<wasmer_metering_t>>) {}
 
+/// Returns true if the module of `instance` has been compiled with
+/// the metering middleware, false otherwise.
+///
+/// The other functions reading or setting the metering points of an
+/// instance must only be called if this function returns true.
+///
+/// # Example
+///
+/// See module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_metering_is_enabled(instance: &wasm_instance_t) -> bool {
+    let exports = &instance.inner.exports;
+
+    exports
+        .get_global("wasmer_metering_remaining_points")
+        .is_ok()
+        && exports
+            .get_global("wasmer_metering_points_exhausted")
+            .is_ok()
+}
+
 /// Returns the remaining metering points. `u64::MAX` means
 /// points are exhausted, otherwise it returns the number of
 /// points. Notice that it could include zero! Zero doesn't mean
@@ -305,6 +330,116 @@ pub unsafe extern "C" fn wasmer_metering_set_remaining_points(
     set_remaining_points(&mut instance.store.store_mut(), &instance.inner, new_limit);
 }
 
+/// Adds fuel to a store.
+///
+/// The first call enables fuel consumption: the instances created
+/// afterwards in this store share its fuel, instead of the initial limit
+/// of their metering middleware. Once the fuel is exhausted, calls trap
+/// with an “out of fuel” message, and adding fuel allows the execution
+/// to resume.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// uint64_t cost_function(wasmer_parser_operator_t wasm_operator) {
+///     return 1;
+/// }
+///
+/// int main() {
+///     wasmer_metering_t* metering = wasmer_metering_new(0, cost_function);
+///     wasmer_middleware_t* middleware = wasmer_metering_as_middleware(metering);
+///
+///     wasm_config_t* config = wasm_config_new();
+///     wasm_config_push_middleware(config, middleware);
+///     wasm_engine_t* engine = wasm_engine_new_with_config(config);
+///     wasm_store_t* store = wasm_store_new(engine);
+///
+///     // Fuel consumption isn't enabled yet.
+///     assert(wasmer_store_fuel_consumed(store) == UINT64_MAX);
+///
+///     wasmer_store_add_fuel(store, 10);
+///
+///     wasm_byte_vec_t wat;
+///     wasmer_byte_vec_new_from_string(
+///         &wat,
+///         "(module\n"
+///         "  (func (export \"add_one\") (param i32) (result i32)\n"
+///         "    local.get 0\n"
+///         "    i32.const 1\n"
+///         "    i32.add))"
+///     );
+///     wasm_byte_vec_t wasm;
+///     wat2wasm(&wat, &wasm);
+///
+///     wasm_module_t* module = wasm_module_new(store, &wasm);
+///     assert(module);
+///
+///     wasm_extern_vec_t imports = WASM_EMPTY_VEC;
+///     wasm_trap_t* trap = NULL;
+///     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, &trap);
+///     assert(instance);
+///
+///     wasm_extern_vec_t exports;
+///     wasm_instance_exports(instance, &exports);
+///     const wasm_func_t* add_one = wasm_extern_as_func(exports.data[0]);
+///
+///     wasm_val_t arguments[1] = { WASM_I32_VAL(1) };
+///     wasm_val_t results[1] = { WASM_INIT_VAL };
+///     wasm_val_vec_t arguments_as_array = WASM_ARRAY_VEC(arguments);
+///     wasm_val_vec_t results_as_array = WASM_ARRAY_VEC(results);
+///
+///     trap = wasm_func_call(add_one, &arguments_as_array, &results_as_array);
+///     assert(trap == NULL);
+///     assert(wasmer_store_fuel_consumed(store) == 4);
+///
+///     trap = wasm_func_call(add_one, &arguments_as_array, &results_as_array);
+///     assert(trap == NULL);
+///
+///     // The store is now out of fuel.
+///     trap = wasm_func_call(add_one, &arguments_as_array, &results_as_array);
+///     assert(trap != NULL);
+///     wasm_trap_delete(trap);
+///
+///     wasm_extern_vec_delete(&exports);
+///     wasm_instance_delete(instance);
+///     wasm_module_delete(module);
+///     wasm_byte_vec_delete(&wasm);
+///     wasm_byte_vec_delete(&wat);
+///     wasm_store_delete(store);
+///     wasm_engine_delete(engine);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_store_add_fuel(store: &mut wasm_store_t, fuel: u64) {
+    store.inner.store_mut().add_fuel(fuel);
+}
+
+/// Returns the fuel consumed by a store since fuel consumption was
+/// enabled with [`wasmer_store_add_fuel`]. `u64::MAX` means fuel
+/// consumption isn't enabled.
+///
+/// # Example
+///
+/// See [`wasmer_store_add_fuel`].
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_store_fuel_consumed(store: &mut wasm_store_t) -> u64 {
+    store
+        .inner
+        .store_mut()
+        .fuel_consumed()
+        .unwrap_or(std::u64::MAX)
+}
+
 /// Transforms a [`wasmer_metering_t`] into a generic
 /// [`wasmer_middleware_t`], to then be pushed in the configuration with
 /// [`wasm_config_push_middleware`][super::wasm_config_push_middleware].
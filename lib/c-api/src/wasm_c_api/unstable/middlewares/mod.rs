@@ -1,6 +1,7 @@
 //! Unstable non-standard Wasmer-specific types to manipulate module
 //! middlewares.
 
+pub mod deny_list;
 pub mod metering;
 
 use super::super::engine::wasm_config_t;
@@ -15,7 +16,8 @@ compile_error!("The `middlewares` feature requires the `compiler` feature to be
 /// Used by `wasm_config_push_middleware`. A specific middleware is
 /// transformed into this type to get a generic middleware. See for
 /// example
-/// [`wasmer_metering_as_middleware`][metering::wasmer_metering_as_middleware].
+/// [`wasmer_metering_as_middleware`][metering::wasmer_metering_as_middleware]
+/// or [`wasmer_deny_list_as_middleware`][deny_list::wasmer_deny_list_as_middleware].
 #[derive(Debug)]
 #[allow(non_camel_case_types)]
 pub struct wasmer_middleware_t {
@@ -30,7 +32,8 @@ pub struct wasmer_middleware_t {
 ///
 /// # Example
 ///
-/// See the documentation of the [`metering`] module.
+/// See the documentation of the [`metering`] and [`deny_list`]
+/// modules.
 #[no_mangle]
 pub extern "C" fn wasm_config_push_middleware(
     config: &mut wasm_config_t,
@@ -1,10 +1,17 @@
 pub mod engine;
 pub mod features;
+pub mod frame;
+pub mod instance;
+#[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+pub mod interrupt;
+pub mod memory;
 #[cfg(feature = "middlewares")]
 pub mod middlewares;
 pub mod module;
 #[cfg(feature = "compiler")]
 pub mod parser;
 pub mod target_lexicon;
+pub mod trap;
+pub mod tunables;
 #[cfg(feature = "wasi")]
 pub mod wasi;
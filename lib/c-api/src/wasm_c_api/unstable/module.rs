@@ -2,7 +2,11 @@
 
 use super::super::engine::wasm_engine_t;
 use super::super::module::wasm_module_t;
-use super::super::types::{wasm_byte_vec_t, wasm_name_t};
+use super::super::types::{wasm_byte_vec_t, wasm_exporttype_t, wasm_importtype_t, wasm_name_t};
+use crate::error::update_last_error;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::path::Path;
 use std::ptr;
 use std::str;
 use wasmer_api::Module;
@@ -179,3 +183,382 @@ pub unsafe extern "C" fn wasmer_module_new(
 
     Some(Box::new(wasm_module_t { inner: module }))
 }
+
+/// Function type called for each import of a module, see
+/// [`wasmer_module_visit_imports`]. Returning `false` stops the
+/// iteration.
+#[allow(non_camel_case_types)]
+pub type wasmer_module_import_visitor_t =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, import_type: &wasm_importtype_t) -> bool>;
+
+/// Function type called for each export of a module, see
+/// [`wasmer_module_visit_exports`]. Returning `false` stops the
+/// iteration.
+#[allow(non_camel_case_types)]
+pub type wasmer_module_export_visitor_t =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, export_type: &wasm_exporttype_t) -> bool>;
+
+/// Unstable non-standard Wasmer-specific API to call `visitor` with
+/// each import of the module, in order, along with `user_data`.
+///
+/// Unlike `wasm_module_imports`, nothing has to be freed: the import
+/// type, i.e. the module name, the name and the complete extern type
+/// (function signature, memory or table limits, global type), is
+/// only valid during the call. Returns the number of visited imports.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// bool visit_import(void* user_data, const wasm_importtype_t* import_type) {
+///     int* functions = user_data;
+///     const wasm_externtype_t* extern_type = wasm_importtype_type(import_type);
+///
+///     if (wasm_externtype_kind(extern_type) == WASM_EXTERN_FUNC) {
+///         const wasm_functype_t* func_type = wasm_externtype_as_functype_const(extern_type);
+///         const wasm_valtype_vec_t* params = wasm_functype_params(func_type);
+///         const wasm_valtype_vec_t* results = wasm_functype_results(func_type);
+///
+///         wasmer_assert_name(wasm_importtype_module(import_type), "env");
+///         wasmer_assert_name(wasm_importtype_name(import_type), "log");
+///         assert(params->size == 2);
+///         assert(wasm_valtype_kind(params->data[0]) == WASM_I32);
+///         assert(wasm_valtype_kind(params->data[1]) == WASM_I64);
+///         assert(results->size == 1);
+///         assert(wasm_valtype_kind(results->data[0]) == WASM_F32);
+///
+///         *functions += 1;
+///     }
+///
+///     return true;
+/// }
+///
+/// bool visit_export(void* user_data, const wasm_exporttype_t* export_type) {
+///     const wasm_externtype_t* extern_type = wasm_exporttype_type(export_type);
+///
+///     if (wasm_externtype_kind(extern_type) == WASM_EXTERN_MEMORY) {
+///         const wasm_limits_t* limits = wasm_memorytype_limits(
+///             wasm_externtype_as_memorytype_const(extern_type)
+///         );
+///
+///         wasmer_assert_name(wasm_exporttype_name(export_type), "memory");
+///         assert(limits->min == 1);
+///         assert(limits->max == 2);
+///
+///         *(bool*) user_data = true;
+///
+///         // Stop there.
+///         return false;
+///     }
+///
+///     return true;
+/// }
+///
+/// int main() {
+///     // Create the engine and the store.
+///     wasm_engine_t* engine = wasm_engine_new();
+///     wasm_store_t* store = wasm_store_new(engine);
+///
+///     // Create a WebAssembly module from a WAT definition.
+///     wasm_byte_vec_t wat;
+///     wasmer_byte_vec_new_from_string(
+///         &wat,
+///         "(module\n"
+///         "  (import \"env\" \"log\" (func (param i32 i64) (result f32)))\n"
+///         "  (import \"env\" \"counter\" (global (mut i32)))\n"
+///         "  (memory (export \"memory\") 1 2)\n"
+///         "  (table (export \"table\") 1 funcref))"
+///     );
+///     wasm_byte_vec_t wasm;
+///     wat2wasm(&wat, &wasm);
+///
+///     wasm_module_t* module = wasm_module_new(store, &wasm);
+///     assert(module);
+///
+///     // Visit all the imports.
+///     int functions = 0;
+///     assert(wasmer_module_visit_imports(module, visit_import, &functions) == 2);
+///     assert(functions == 1);
+///
+///     // Visit the exports until the memory is found.
+///     bool found = false;
+///     assert(wasmer_module_visit_exports(module, visit_export, &found) == 1);
+///     assert(found);
+///
+///     // Free everything.
+///     wasm_module_delete(module);
+///     wasm_byte_vec_delete(&wasm);
+///     wasm_byte_vec_delete(&wat);
+///     wasm_store_delete(store);
+///     wasm_engine_delete(engine);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_visit_imports(
+    module: &wasm_module_t,
+    visitor: wasmer_module_import_visitor_t,
+    user_data: *mut c_void,
+) -> usize {
+    let Some(visitor) = visitor else {
+        return 0;
+    };
+
+    let mut visited = 0;
+    for import in module.inner.imports() {
+        visited += 1;
+        if !visitor(user_data, &import.into()) {
+            break;
+        }
+    }
+
+    visited
+}
+
+/// Unstable non-standard Wasmer-specific API to call `visitor` with
+/// each export of the module, in order, along with `user_data`.
+///
+/// Like with [`wasmer_module_visit_imports`], the export type is only
+/// valid during the call. Returns the number of visited exports.
+///
+/// # Example
+///
+/// See [`wasmer_module_visit_imports`].
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_visit_exports(
+    module: &wasm_module_t,
+    visitor: wasmer_module_export_visitor_t,
+    user_data: *mut c_void,
+) -> usize {
+    let Some(visitor) = visitor else {
+        return 0;
+    };
+
+    let mut visited = 0;
+    for export in module.inner.exports() {
+        visited += 1;
+        if !visitor(user_data, &export.into()) {
+            break;
+        }
+    }
+
+    visited
+}
+
+/// Serializes a module into a binary representation that can later
+/// be loaded with [`wasmer_module_deserialize`], e.g. by an engine
+/// without any compiler.
+///
+/// Unlike `wasm_module_serialize`, this function returns `false` if
+/// the module can't be serialized. The error can be read with
+/// `wasmer_last_error_message`.
+///
+/// # Example
+///
+/// See [`wasmer_module_deserialize`].
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_serialize(
+    module: &wasm_module_t,
+    // own
+    out: &mut wasm_byte_vec_t,
+) -> bool {
+    let bytes = c_try!(module.inner.serialize(); otherwise false);
+    out.set_buffer(bytes.to_vec());
+
+    true
+}
+
+/// Deserializes a module previously serialized with
+/// [`wasmer_module_serialize`] or `wasm_module_serialize`, using the
+/// provided engine instead of a store.
+///
+/// Nothing is compiled, so this also works with the headless engine.
+/// The engine must target the same platform as the one which
+/// serialized the module.
+///
+/// # Safety
+///
+/// The bytes contain machine code which is going to be executed: they
+/// must come from a trusted source, see `wasm_module_deserialize`.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// int main() {
+///     // Create the engine.
+///     wasm_engine_t* engine = wasm_engine_new();
+///
+///     // Create a WebAssembly module from a WAT definition.
+///     wasm_byte_vec_t wat;
+///     wasmer_byte_vec_new_from_string(&wat, "(module (func (export \"run\")))");
+///     wasm_byte_vec_t wasm;
+///     wat2wasm(&wat, &wasm);
+///
+///     wasm_module_t* module = wasmer_module_new(engine, &wasm);
+///     assert(module);
+///
+///     // Serialize the module into bytes.
+///     wasm_byte_vec_t serialized_module;
+///     assert(wasmer_module_serialize(module, &serialized_module));
+///     wasm_module_delete(module);
+///
+///     // And load it back.
+///     wasm_module_t* deserialized_module = wasmer_module_deserialize(engine, &serialized_module);
+///     assert(deserialized_module);
+///
+///     wasm_exporttype_vec_t export_types;
+///     wasm_module_exports(deserialized_module, &export_types);
+///     assert(export_types.size == 1);
+///
+///     // Free everything.
+///     wasm_exporttype_vec_delete(&export_types);
+///     wasm_module_delete(deserialized_module);
+///     wasm_byte_vec_delete(&serialized_module);
+///     wasm_byte_vec_delete(&wasm);
+///     wasm_byte_vec_delete(&wat);
+///     wasm_engine_delete(engine);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_deserialize(
+    engine: Option<&wasm_engine_t>,
+    bytes: Option<&wasm_byte_vec_t>,
+) -> Option<Box<wasm_module_t>> {
+    let engine: wasmer_api::Engine = engine?.inner.clone().into();
+    let bytes = bytes?;
+
+    let module = c_try!(Module::deserialize(&engine, bytes.as_slice()));
+
+    Some(Box::new(wasm_module_t { inner: module }))
+}
+
+/// Serializes a module into the file at `path`, which can later be
+/// loaded with [`wasmer_module_deserialize_from_file`].
+///
+/// This is typically done once, at install time, so that the module
+/// doesn't have to be compiled again at runtime. The function returns
+/// `false` if the module can't be serialized or the file can't be
+/// written. The error can be read with `wasmer_last_error_message`.
+///
+/// # Example
+///
+/// See [`wasmer_module_deserialize_from_file`].
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_serialize_to_file(
+    module: &wasm_module_t,
+    path: *const c_char,
+) -> bool {
+    let path = match c_path(path) {
+        Some(path) => path,
+        None => return false,
+    };
+
+    c_try!(module.inner.serialize_to_file(path); otherwise false);
+
+    true
+}
+
+/// Deserializes a module from the file at `path`, previously written
+/// with [`wasmer_module_serialize_to_file`] or by `wasmer compile`.
+///
+/// Nothing is compiled, so this also works with the headless engine.
+/// Returns `NULL` if the file can't be read or doesn't contain a
+/// module serialized for this engine.
+///
+/// # Safety
+///
+/// The file contains machine code which is going to be executed: it
+/// must come from a trusted source, see `wasm_module_deserialize`.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// int main() {
+///     // Create the engine.
+///     wasm_engine_t* engine = wasm_engine_new();
+///
+///     // At install time, compile the module and save it.
+///     {
+///         wasm_byte_vec_t wat;
+///         wasmer_byte_vec_new_from_string(&wat, "(module (func (export \"run\")))");
+///         wasm_byte_vec_t wasm;
+///         wat2wasm(&wat, &wasm);
+///
+///         wasm_module_t* module = wasmer_module_new(engine, &wasm);
+///         assert(module);
+///         assert(wasmer_module_serialize_to_file(module, "module.wasmu"));
+///
+///         wasm_module_delete(module);
+///         wasm_byte_vec_delete(&wasm);
+///         wasm_byte_vec_delete(&wat);
+///     }
+///
+///     // At runtime, load it without compiling anything.
+///     wasm_module_t* module = wasmer_module_deserialize_from_file(engine, "module.wasmu");
+///     assert(module);
+///
+///     wasm_exporttype_vec_t export_types;
+///     wasm_module_exports(module, &export_types);
+///     assert(export_types.size == 1);
+///
+///     // Loading a missing file fails.
+///     assert(!wasmer_module_deserialize_from_file(engine, "missing.wasmu"));
+///     assert(wasmer_last_error_length() > 0);
+///
+///     // Free everything.
+///     wasm_exporttype_vec_delete(&export_types);
+///     wasm_module_delete(module);
+///     wasm_engine_delete(engine);
+///     remove("module.wasmu");
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_module_deserialize_from_file(
+    engine: Option<&wasm_engine_t>,
+    path: *const c_char,
+) -> Option<Box<wasm_module_t>> {
+    let engine: wasmer_api::Engine = engine?.inner.clone().into();
+    let path = c_path(path)?;
+
+    let module = c_try!(Module::deserialize_from_file(&engine, path));
+
+    Some(Box::new(wasm_module_t { inner: module }))
+}
+
+/// Reads a path from a nul-terminated UTF-8 C string.
+unsafe fn c_path<'a>(path: *const c_char) -> Option<&'a Path> {
+    if path.is_null() {
+        update_last_error("the path is null");
+        return None;
+    }
+
+    Some(Path::new(c_try!(CStr::from_ptr(path).to_str())))
+}
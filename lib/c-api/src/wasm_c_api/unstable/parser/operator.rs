@@ -1,5 +1,6 @@
 use wasmer_api::wasmparser::Operator;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C)]
 #[allow(non_camel_case_types)]
 pub enum wasmer_parser_operator_t {
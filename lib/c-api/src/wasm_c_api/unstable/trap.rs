@@ -0,0 +1,304 @@
+//! Unstable non-standard Wasmer-specific extensions to the Wasm C API.
+
+use super::super::store::wasm_store_t;
+use super::super::trap::{trap_message, wasm_trap_t};
+use super::super::types::wasm_message_t;
+use libc::c_void;
+use std::ptr;
+use wasmer_api::{RuntimeError, TrapCode};
+
+/// Unstable non-standard Wasmer-specific API to create a trap
+/// carrying an embedder-defined `payload`, such as an error code.
+///
+/// When a host function returns such a trap, the payload is kept
+/// through the WebAssembly frames, and can be read back with
+/// [`wasmer_trap_payload`] from the trap returned to the caller.
+/// Like with `wasm_trap_new`, the store isn't used, so it can be
+/// `NULL`.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// wasm_trap_t* host_exit(const wasm_val_vec_t* arguments, wasm_val_vec_t* results) {
+///     (void) arguments;
+///     (void) results;
+///
+///     wasm_message_t message;
+///     wasm_name_new_from_string_nt(&message, "exit");
+///     wasm_trap_t* trap = wasmer_trap_new_with_payload(NULL, &message, 42);
+///     wasm_name_delete(&message);
+///
+///     return trap;
+/// }
+///
+/// int main() {
+///     // Create the engine and the store.
+///     wasm_engine_t* engine = wasm_engine_new();
+///     wasm_store_t* store = wasm_store_new(engine);
+///
+///     // Create a WebAssembly module calling the host function.
+///     wasm_byte_vec_t wat;
+///     wasmer_byte_vec_new_from_string(
+///         &wat,
+///         "(module\n"
+///         "  (import \"env\" \"exit\" (func $exit))\n"
+///         "  (func (export \"run\") (call $exit)))"
+///     );
+///     wasm_byte_vec_t wasm;
+///     wat2wasm(&wat, &wasm);
+///
+///     wasm_module_t* module = wasm_module_new(store, &wasm);
+///     assert(module);
+///
+///     wasm_functype_t* exit_type = wasm_functype_new_0_0();
+///     wasm_func_t* exit = wasm_func_new(store, exit_type, host_exit);
+///     wasm_functype_delete(exit_type);
+///
+///     wasm_extern_t* externs[] = { wasm_func_as_extern(exit) };
+///     wasm_extern_vec_t imports = WASM_ARRAY_VEC(externs);
+///     wasm_trap_t* trap = NULL;
+///     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, &trap);
+///     assert(instance);
+///
+///     wasm_extern_vec_t exports;
+///     wasm_instance_exports(instance, &exports);
+///     const wasm_func_t* run = wasm_extern_as_func(exports.data[0]);
+///
+///     // Call the function, and get the payload back from the trap.
+///     wasm_val_vec_t arguments = WASM_EMPTY_VEC;
+///     wasm_val_vec_t results = WASM_EMPTY_VEC;
+///     trap = wasm_func_call(run, &arguments, &results);
+///     assert(trap);
+///
+///     uint64_t payload = 0;
+///     assert(wasmer_trap_payload(trap, &payload));
+///     assert(payload == 42);
+///
+///     // Free everything.
+///     wasm_trap_delete(trap);
+///     wasm_extern_vec_delete(&exports);
+///     wasm_instance_delete(instance);
+///     wasm_func_delete(exit);
+///     wasm_module_delete(module);
+///     wasm_byte_vec_delete(&wasm);
+///     wasm_byte_vec_delete(&wat);
+///     wasm_store_delete(store);
+///     wasm_engine_delete(engine);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_trap_new_with_payload(
+    _store: Option<&mut wasm_store_t>,
+    message: &wasm_message_t,
+    payload: u64,
+) -> Option<Box<wasm_trap_t>> {
+    let runtime_error = RuntimeError::with_payload(trap_message(message)?, payload);
+
+    Some(Box::new(runtime_error.into()))
+}
+
+/// Unstable non-standard Wasmer-specific API to get the payload of a
+/// trap created with [`wasmer_trap_new_with_payload`].
+///
+/// Returns `false` if the trap has no payload, otherwise writes it to
+/// `out` and returns `true`.
+///
+/// # Example
+///
+/// See [`wasmer_trap_new_with_payload`].
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_trap_payload(trap: &wasm_trap_t, out: &mut u64) -> bool {
+    match trap.inner.payload::<u64>() {
+        Some(payload) => {
+            *out = *payload;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Function type to release the user data of a trap, see
+/// [`wasmer_trap_new_with_user_data`]. It can be `NULL`.
+#[allow(non_camel_case_types)]
+pub type wasmer_trap_user_data_finalizer_t = Option<unsafe extern "C" fn(data: *mut c_void)>;
+
+/// The user data of a trap, released with its finalizer when the last
+/// copy of the trap is deleted.
+struct TrapUserData {
+    data: *mut c_void,
+    finalizer: wasmer_trap_user_data_finalizer_t,
+}
+
+// The trap can be moved to and shared with other threads; the
+// embedder is responsible for the user data to support that.
+unsafe impl Send for TrapUserData {}
+unsafe impl Sync for TrapUserData {}
+
+impl Drop for TrapUserData {
+    fn drop(&mut self) {
+        if let Some(finalizer) = self.finalizer {
+            unsafe { finalizer(self.data) };
+        }
+    }
+}
+
+/// Unstable non-standard Wasmer-specific API to create a trap
+/// carrying an opaque pointer to embedder-defined `data`, such as a
+/// structure describing the error.
+///
+/// This is the same as [`wasmer_trap_new_with_payload`], but for
+/// errors which don't fit in an integer. The data can be read back
+/// with [`wasmer_trap_user_data`] from the trap returned to the
+/// caller. The trap owns the data: `finalizer`, if not `NULL`, is
+/// called with `data` once the trap and all its copies are deleted.
+///
+/// # Example
+///
+/// ```rust
+/// # use wasmer_inline_c::assert_c;
+/// # fn main() {
+/// #    (assert_c! {
+/// # #include "tests/wasmer.h"
+/// #
+/// typedef struct {
+///     int code;
+///     const char* reason;
+/// } host_error_t;
+///
+/// int finalized = 0;
+///
+/// void host_error_delete(void* error) {
+///     free(error);
+///     finalized += 1;
+/// }
+///
+/// wasm_trap_t* host_fail(const wasm_val_vec_t* arguments, wasm_val_vec_t* results) {
+///     (void) arguments;
+///     (void) results;
+///
+///     host_error_t* error = malloc(sizeof(host_error_t));
+///     error->code = 404;
+///     error->reason = "not found";
+///
+///     wasm_message_t message;
+///     wasm_name_new_from_string_nt(&message, "host error");
+///     wasm_trap_t* trap = wasmer_trap_new_with_user_data(NULL, &message, error, host_error_delete);
+///     wasm_name_delete(&message);
+///
+///     return trap;
+/// }
+///
+/// int main() {
+///     // Create the engine and the store.
+///     wasm_engine_t* engine = wasm_engine_new();
+///     wasm_store_t* store = wasm_store_new(engine);
+///
+///     // Create a WebAssembly module calling the host function.
+///     wasm_byte_vec_t wat;
+///     wasmer_byte_vec_new_from_string(
+///         &wat,
+///         "(module\n"
+///         "  (import \"env\" \"fail\" (func $fail))\n"
+///         "  (func (export \"run\") (call $fail)))"
+///     );
+///     wasm_byte_vec_t wasm;
+///     wat2wasm(&wat, &wasm);
+///
+///     wasm_module_t* module = wasm_module_new(store, &wasm);
+///     assert(module);
+///
+///     wasm_functype_t* fail_type = wasm_functype_new_0_0();
+///     wasm_func_t* fail = wasm_func_new(store, fail_type, host_fail);
+///     wasm_functype_delete(fail_type);
+///
+///     wasm_extern_t* externs[] = { wasm_func_as_extern(fail) };
+///     wasm_extern_vec_t imports = WASM_ARRAY_VEC(externs);
+///     wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+///     assert(instance);
+///
+///     wasm_extern_vec_t exports;
+///     wasm_instance_exports(instance, &exports);
+///     const wasm_func_t* run = wasm_extern_as_func(exports.data[0]);
+///
+///     // Call the function, and get the error back from the trap.
+///     wasm_val_vec_t arguments = WASM_EMPTY_VEC;
+///     wasm_val_vec_t results = WASM_EMPTY_VEC;
+///     wasm_trap_t* trap = wasm_func_call(run, &arguments, &results);
+///     assert(trap);
+///
+///     host_error_t* error = wasmer_trap_user_data(trap);
+///     assert(error);
+///     assert(error->code == 404);
+///     assert(strcmp(error->reason, "not found") == 0);
+///
+///     // The error is released with the trap.
+///     wasm_trap_delete(trap);
+///     assert(finalized == 1);
+///
+///     // Free everything.
+///     wasm_extern_vec_delete(&exports);
+///     wasm_instance_delete(instance);
+///     wasm_func_delete(fail);
+///     wasm_module_delete(module);
+///     wasm_byte_vec_delete(&wasm);
+///     wasm_byte_vec_delete(&wat);
+///     wasm_store_delete(store);
+///     wasm_engine_delete(engine);
+///
+///     return 0;
+/// }
+/// #    })
+/// #    .success();
+/// # }
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_trap_new_with_user_data(
+    _store: Option<&mut wasm_store_t>,
+    message: &wasm_message_t,
+    data: *mut c_void,
+    finalizer: wasmer_trap_user_data_finalizer_t,
+) -> Option<Box<wasm_trap_t>> {
+    let message = trap_message(message)?;
+    let runtime_error = RuntimeError::with_payload(message, TrapUserData { data, finalizer });
+
+    Some(Box::new(runtime_error.into()))
+}
+
+/// Unstable non-standard Wasmer-specific API to get the user data of
+/// a trap created with [`wasmer_trap_new_with_user_data`].
+///
+/// Returns `NULL` if the trap has no user data. The data stays owned
+/// by the trap, and must not be used after the trap is deleted.
+///
+/// # Example
+///
+/// See [`wasmer_trap_new_with_user_data`].
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_trap_user_data(trap: &wasm_trap_t) -> *mut c_void {
+    match trap.inner.payload::<TrapUserData>() {
+        Some(user_data) => user_data.data,
+        None => ptr::null_mut(),
+    }
+}
+
+/// Unstable non-standard Wasmer-specific API to check whether a trap
+/// was caused by an interruption, i.e. `wasmer_store_interrupt` or
+/// an epoch deadline being reached.
+///
+/// # Example
+///
+/// See the documentation of the [`interrupt`](super::interrupt) module.
+#[no_mangle]
+pub unsafe extern "C" fn wasmer_trap_is_interrupt(trap: &wasm_trap_t) -> bool {
+    trap.inner.clone().to_trap() == Some(TrapCode::Interrupt)
+}
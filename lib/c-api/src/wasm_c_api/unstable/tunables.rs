@@ -0,0 +1,168 @@
+//! Unstable non-standard Wasmer-specific API to configure the
+//! tunables of the engine, i.e. how linear memories are laid out.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use wasmer_inline_c::assert_c;
+//! # fn main() {
+//! #    (assert_c! {
+//! # #include "tests/wasmer.h"
+//! #
+//! int main() {
+//!     // Declare tunables.
+//!     wasmer_tunables_t* tunables = wasmer_tunables_new();
+//!
+//!     // Only reserve 1 GiB of address space for static memories.
+//!     wasmer_tunables_static_memory_bound(tunables, 0x4000);
+//!
+//!     // And use a smaller guard region after them.
+//!     wasmer_tunables_static_memory_offset_guard_size(tunables, 0x1000);
+//!
+//!     wasmer_tunables_delete(tunables);
+//!
+//!     return 0;
+//! }
+//! #    })
+//! #    .success();
+//! # }
+//! ```
+//!
+//! To go further, see
+//! [`wasm_config_set_tunables`](super::engine::wasm_config_set_tunables).
+
+#[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+use wasmer_compiler::BaseTunables;
+use wasmer_types::Pages;
+#[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+use wasmer_types::Target;
+
+/// Controls how the engine allocates linear memories.
+///
+/// Every parameter which isn't set keeps the default value for the
+/// target of the engine.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[derive(Debug, Default)]
+#[allow(non_camel_case_types)]
+pub struct wasmer_tunables_t {
+    static_memory_bound: Option<Pages>,
+    static_memory_offset_guard_size: Option<u64>,
+    dynamic_memory_offset_guard_size: Option<u64>,
+}
+
+#[cfg(any(feature = "compiler", feature = "compiler-headless"))]
+impl wasmer_tunables_t {
+    /// The tunables for `target`, with the parameters that were set
+    /// overriding the defaults.
+    pub(crate) fn for_target(&self, target: &Target) -> BaseTunables {
+        let mut tunables = BaseTunables::for_target(target);
+
+        if let Some(bound) = self.static_memory_bound {
+            tunables.static_memory_bound = bound;
+        }
+
+        if let Some(size) = self.static_memory_offset_guard_size {
+            tunables.static_memory_offset_guard_size = size;
+        }
+
+        if let Some(size) = self.dynamic_memory_offset_guard_size {
+            tunables.dynamic_memory_offset_guard_size = size;
+        }
+
+        tunables
+    }
+}
+
+/// Creates a new [`wasmer_tunables_t`].
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_tunables_new() -> Box<wasmer_tunables_t> {
+    Box::default()
+}
+
+/// Delete a [`wasmer_tunables_t`].
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_tunables_delete(_tunables: Option<Box<wasmer_tunables_t>>) {}
+
+/// Sets the size, in WebAssembly pages, up to which memories are
+/// allocated statically.
+///
+/// Static memories reserve their whole address space up front and
+/// don't need explicit bounds checks. Memories whose maximum size is
+/// larger than this bound are allocated dynamically.
+///
+/// The default is 4 GiB (`0x10000` pages) on 64-bit targets.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_tunables_static_memory_bound(
+    tunables: Option<&mut wasmer_tunables_t>,
+    pages: u32,
+) -> bool {
+    let tunables = match tunables {
+        Some(tunables) => tunables,
+        _ => return false,
+    };
+
+    tunables.static_memory_bound = Some(Pages(pages));
+
+    true
+}
+
+/// Sets the size, in bytes, of the guard region reserved after
+/// static memories.
+///
+/// The default is 2 GiB on 64-bit targets.
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_tunables_static_memory_offset_guard_size(
+    tunables: Option<&mut wasmer_tunables_t>,
+    size: u64,
+) -> bool {
+    let tunables = match tunables {
+        Some(tunables) => tunables,
+        _ => return false,
+    };
+
+    tunables.static_memory_offset_guard_size = Some(size);
+
+    true
+}
+
+/// Sets the size, in bytes, of the guard region reserved after
+/// dynamic memories.
+///
+/// The default is 64 KiB (128 KiB on Windows).
+///
+/// # Example
+///
+/// See the module's documentation.
+#[no_mangle]
+pub extern "C" fn wasmer_tunables_dynamic_memory_offset_guard_size(
+    tunables: Option<&mut wasmer_tunables_t>,
+    size: u64,
+) -> bool {
+    let tunables = match tunables {
+        Some(tunables) => tunables,
+        _ => return false,
+    };
+
+    tunables.dynamic_memory_offset_guard_size = Some(size);
+
+    true
+}
@@ -13,16 +13,18 @@ use super::{
 use crate::error::update_last_error;
 use std::convert::TryFrom;
 use std::ffi::CStr;
-use std::os::raw::c_char;
+use std::io::{self, Write};
+use std::os::raw::{c_char, c_void};
 use std::slice;
 use std::sync::Arc;
 #[cfg(feature = "webc_runner")]
 use wasmer_api::{AsStoreMut, Imports, Module};
 use wasmer_wasix::{
-    default_fs_backing, get_wasi_version,
+    default_fs_backing, generate_import_object_from_env, get_wasi_version,
     runtime::task_manager::{tokio::TokioTaskManager, InlineWaker},
     virtual_fs::AsyncReadExt,
     virtual_fs::VirtualFile,
+    wasmer_wasix_types::wasi::Rights,
     Pipe, PluggableRuntime, WasiEnv, WasiEnvBuilder, WasiFunctionEnv, WasiVersion,
 };
 
@@ -32,6 +34,7 @@ pub struct wasi_config_t {
     inherit_stdout: bool,
     inherit_stderr: bool,
     inherit_stdin: bool,
+    version: Option<WasiVersion>,
     builder: WasiEnvBuilder,
     runtime: Option<tokio::runtime::Runtime>,
 }
@@ -55,6 +58,7 @@ pub unsafe extern "C" fn wasi_config_new(
         inherit_stdout: true,
         inherit_stderr: true,
         inherit_stdin: true,
+        version: None,
         builder: WasiEnv::builder(prog_name).fs(default_fs_backing()),
         runtime: Some(runtime),
     }))
@@ -87,6 +91,37 @@ pub unsafe extern "C" fn wasi_config_arg(config: &mut wasi_config_t, arg: *const
     config.builder.add_arg(arg_bytes);
 }
 
+/// Copy the environment variables of the host whose name starts
+/// with `prefix` into the guest environment.
+///
+/// Passing a null or empty `prefix` inherits the whole host
+/// environment. Variables which aren't valid UTF-8 are skipped.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_inherit_env(
+    config: &mut wasi_config_t,
+    prefix: *const c_char,
+) {
+    let prefix = if prefix.is_null() {
+        ""
+    } else {
+        match CStr::from_ptr(prefix).to_str() {
+            Ok(prefix) => prefix,
+            Err(e) => {
+                update_last_error(e);
+                return;
+            }
+        }
+    };
+
+    for (key, value) in std::env::vars_os() {
+        if let (Some(key), Some(value)) = (key.to_str(), value.to_str()) {
+            if key.starts_with(prefix) {
+                config.builder.add_env(key, value);
+            }
+        }
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wasi_config_preopen_dir(
     config: &mut wasi_config_t,
@@ -144,6 +179,94 @@ pub unsafe extern "C" fn wasi_config_mapdir(
     true
 }
 
+/// Preopen `dir` at the root of the guest filesystem, without
+/// allowing the guest to modify it.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_preopen_dir_readonly(
+    config: &mut wasi_config_t,
+    dir: *const c_char,
+) -> bool {
+    let Some(dir) = c_str(dir) else {
+        return false;
+    };
+
+    if let Err(e) = config
+        .builder
+        .add_preopen_build(|p| p.directory(dir).read(true))
+    {
+        update_last_error(e);
+        return false;
+    }
+
+    true
+}
+
+/// Expose the host directory `dir` to the guest as `alias`, without
+/// allowing the guest to modify it.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_mapdir_readonly(
+    config: &mut wasi_config_t,
+    alias: *const c_char,
+    dir: *const c_char,
+) -> bool {
+    let (Some(alias), Some(dir)) = (c_str(alias), c_str(dir)) else {
+        return false;
+    };
+
+    if let Err(e) = config
+        .builder
+        .add_preopen_build(|p| p.directory(dir).alias(alias).read(true))
+    {
+        update_last_error(e);
+        return false;
+    }
+
+    true
+}
+
+/// Expose the host directory `dir` to the guest as `alias` with
+/// exactly the given `rights`.
+///
+/// `rights` is a bitmask of WASI `__wasi_rights_t` flags (e.g.
+/// `__WASI_RIGHTS_FD_READ`). Unknown bits are ignored.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_mapdir_with_rights(
+    config: &mut wasi_config_t,
+    alias: *const c_char,
+    dir: *const c_char,
+    rights: u64,
+) -> bool {
+    let (Some(alias), Some(dir)) = (c_str(alias), c_str(dir)) else {
+        return false;
+    };
+    let rights = Rights::from_bits_truncate(rights);
+
+    if let Err(e) = config
+        .builder
+        .add_preopen_build(|p| p.directory(dir).alias(alias).rights(rights))
+    {
+        update_last_error(e);
+        return false;
+    }
+
+    true
+}
+
+unsafe fn c_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        update_last_error("unexpected null string");
+        return None;
+    }
+
+    match CStr::from_ptr(ptr).to_str() {
+        Ok(s) => Some(s),
+        Err(e) => {
+            update_last_error(e);
+            None
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn wasi_config_capture_stdout(config: &mut wasi_config_t) {
     config.inherit_stdout = false;
@@ -174,6 +297,186 @@ pub extern "C" fn wasi_config_inherit_stdin(config: &mut wasi_config_t) {
     config.inherit_stdin = true;
 }
 
+/// Feed the guest's `stdin` from a copy of `bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_set_stdin_bytes(
+    config: &mut wasi_config_t,
+    bytes: &wasm_byte_vec_t,
+) {
+    let bytes = bytes.as_slice().to_vec();
+    config.builder.set_stdin_reader(std::io::Cursor::new(bytes));
+    config.inherit_stdin = false;
+}
+
+/// Feed the guest's `stdin` from the host file descriptor `fd`.
+///
+/// The descriptor is duplicated, so the caller keeps ownership of
+/// `fd`. This is only supported on Unix.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_set_stdin_fd(config: &mut wasi_config_t, fd: i32) -> bool {
+    let Some(file) = dup_fd(fd) else {
+        return false;
+    };
+
+    config.builder.set_stdin_reader(file);
+    config.inherit_stdin = false;
+    true
+}
+
+/// Send everything the guest writes to `stdout` to the host file
+/// descriptor `fd`.
+///
+/// The descriptor is duplicated, so the caller keeps ownership of
+/// `fd`. This is only supported on Unix. A later call to
+/// `wasi_config_capture_stdout` takes precedence.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_set_stdout_fd(config: &mut wasi_config_t, fd: i32) -> bool {
+    let Some(file) = dup_fd(fd) else {
+        return false;
+    };
+
+    config.builder.set_stdout_writer(file);
+    config.inherit_stdout = true;
+    true
+}
+
+/// Send everything the guest writes to `stderr` to the host file
+/// descriptor `fd`.
+///
+/// The descriptor is duplicated, so the caller keeps ownership of
+/// `fd`. This is only supported on Unix. A later call to
+/// `wasi_config_capture_stderr` takes precedence.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_set_stderr_fd(config: &mut wasi_config_t, fd: i32) -> bool {
+    let Some(file) = dup_fd(fd) else {
+        return false;
+    };
+
+    config.builder.set_stderr_writer(file);
+    config.inherit_stderr = true;
+    true
+}
+
+#[cfg(unix)]
+unsafe fn dup_fd(fd: i32) -> Option<std::fs::File> {
+    use std::os::fd::BorrowedFd;
+
+    if fd < 0 {
+        update_last_error(format!("invalid file descriptor: {fd}"));
+        return None;
+    }
+
+    match BorrowedFd::borrow_raw(fd).try_clone_to_owned() {
+        Ok(fd) => Some(fd.into()),
+        Err(e) => {
+            update_last_error(e);
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+unsafe fn dup_fd(_fd: i32) -> Option<std::fs::File> {
+    update_last_error("file descriptors can only be used as stdio on Unix");
+    None
+}
+
+/// Function type called with the bytes the guest writes to `stdout`
+/// or `stderr`, see [`wasi_config_set_stdout_callback`].
+#[allow(non_camel_case_types)]
+pub type wasi_write_callback_t =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, data: *const c_char, len: usize)>;
+
+/// Forwards what the guest writes to a C callback.
+struct CallbackWriter {
+    callback: unsafe extern "C" fn(*mut c_void, *const c_char, usize),
+    user_data: *mut c_void,
+}
+
+// The guest may run on another thread than the one which configured
+// it; the embedder is responsible for the user data to support that.
+unsafe impl Send for CallbackWriter {}
+
+impl Write for CallbackWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        unsafe { (self.callback)(self.user_data, buf.as_ptr() as *const c_char, buf.len()) };
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Call `callback` with everything the guest writes to `stdout`, as
+/// it is written, e.g. to forward it to the host's logging.
+///
+/// `user_data` is passed back to every call. The callback runs on the
+/// thread executing the guest, and the data isn't nul-terminated.
+/// Returns `false` if `callback` is `NULL`. A later call to
+/// `wasi_config_capture_stdout` takes precedence.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_set_stdout_callback(
+    config: &mut wasi_config_t,
+    callback: wasi_write_callback_t,
+    user_data: *mut c_void,
+) -> bool {
+    let Some(callback) = callback else {
+        update_last_error("the `stdout` callback is null");
+        return false;
+    };
+
+    config.builder.set_stdout_writer(CallbackWriter {
+        callback,
+        user_data,
+    });
+    config.inherit_stdout = true;
+    true
+}
+
+/// Call `callback` with everything the guest writes to `stderr`, as
+/// it is written.
+///
+/// See [`wasi_config_set_stdout_callback`]. A later call to
+/// `wasi_config_capture_stderr` takes precedence.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_config_set_stderr_callback(
+    config: &mut wasi_config_t,
+    callback: wasi_write_callback_t,
+    user_data: *mut c_void,
+) -> bool {
+    let Some(callback) = callback else {
+        update_last_error("the `stderr` callback is null");
+        return false;
+    };
+
+    config.builder.set_stderr_writer(CallbackWriter {
+        callback,
+        user_data,
+    });
+    config.inherit_stderr = true;
+    true
+}
+
+/// Select the WASI version the imports are generated for, instead of
+/// detecting it from the module's imports.
+#[no_mangle]
+pub extern "C" fn wasi_config_set_version(
+    config: &mut wasi_config_t,
+    version: wasi_version_t,
+) -> bool {
+    match WasiVersion::try_from(version) {
+        Ok(version) => {
+            config.version = Some(version);
+            true
+        }
+        Err(e) => {
+            update_last_error(e);
+            false
+        }
+    }
+}
+
 #[repr(C)]
 pub struct wasi_filesystem_t {
     ptr: *const c_char,
@@ -232,8 +535,9 @@ unsafe fn wasi_env_with_filesystem_inner(
     let package = package_str.to_str().unwrap_or("");
     let module = &module.as_ref()?.inner;
     let imports = imports?;
+    let version = config.version;
 
-    let (wasi_env, import_object) = prepare_webc_env(
+    let (wasi_env, import_object, (stdout, stderr)) = prepare_webc_env(
         config,
         &mut store.store_mut(),
         module,
@@ -247,6 +551,9 @@ unsafe fn wasi_env_with_filesystem_inner(
     Some(Box::new(wasi_env_t {
         inner: wasi_env,
         store: store.clone(),
+        version,
+        stdout,
+        stderr,
     }))
 }
 
@@ -258,7 +565,7 @@ fn prepare_webc_env(
     bytes: &'static u8,
     len: usize,
     package_name: &str,
-) -> Option<(WasiFunctionEnv, Imports)> {
+) -> Option<(WasiFunctionEnv, Imports, CapturedStdio)> {
     use virtual_fs::static_fs::StaticFileSystem;
     use webc::v1::{FsEntryType, WebC};
 
@@ -295,16 +602,9 @@ fn prepare_webc_env(
         .collect::<Vec<_>>();
 
     let filesystem = Box::new(StaticFileSystem::init(slice, package_name)?);
+    let captured = capture_stdio(&mut config);
     let mut builder = config.builder.runtime(Arc::new(rt));
 
-    if !config.inherit_stdout {
-        builder.set_stdout(Box::new(Pipe::channel().0));
-    }
-
-    if !config.inherit_stderr {
-        builder.set_stderr(Box::new(Pipe::channel().0));
-    }
-
     builder.set_fs(filesystem);
 
     for f_name in top_level_dirs.iter() {
@@ -312,10 +612,14 @@ fn prepare_webc_env(
             .add_preopen_build(|p| p.directory(f_name).read(true).write(true).create(true))
             .ok()?;
     }
+    let version = config.version;
     let env = builder.finalize(store).ok()?;
 
-    let import_object = env.import_object(store, module).ok()?;
-    Some((env, import_object))
+    let import_object = match version {
+        Some(version) => generate_import_object_from_env(store, &env.env, version),
+        None => env.import_object(store, module).ok()?,
+    };
+    Some((env, import_object, captured))
 }
 
 #[allow(non_camel_case_types)]
@@ -323,6 +627,34 @@ pub struct wasi_env_t {
     /// cbindgen:ignore
     pub(super) inner: WasiFunctionEnv,
     pub(super) store: StoreRef,
+    /// The WASI version selected with `wasi_config_set_version`, if any.
+    version: Option<WasiVersion>,
+    /// The reading ends of the captured `stdout` and `stderr`.
+    stdout: Option<Pipe>,
+    stderr: Option<Pipe>,
+}
+
+/// The host ends of the pipes capturing `stdout` and `stderr`.
+type CapturedStdio = (Option<Pipe>, Option<Pipe>);
+
+/// Connect the guest's `stdout` and `stderr` to pipes unless they are
+/// inherited, returning the ends the host reads from.
+fn capture_stdio(config: &mut wasi_config_t) -> CapturedStdio {
+    let mut stdout = None;
+    if !config.inherit_stdout {
+        let (guest, host) = Pipe::channel();
+        config.builder.set_stdout(Box::new(guest));
+        stdout = Some(host);
+    }
+
+    let mut stderr = None;
+    if !config.inherit_stderr {
+        let (guest, host) = Pipe::channel();
+        config.builder.set_stderr(Box::new(guest));
+        stderr = Some(host);
+    }
+
+    (stdout, stderr)
 }
 
 /// Create a new WASI environment.
@@ -350,13 +682,7 @@ pub unsafe extern "C" fn wasi_env_new(
     let mut rt = PluggableRuntime::new(Arc::new(TokioTaskManager::new(runtime)));
     rt.set_engine(Some(store_mut.engine().clone()));
 
-    if !config.inherit_stdout {
-        config.builder.set_stdout(Box::new(Pipe::channel().0));
-    }
-
-    if !config.inherit_stderr {
-        config.builder.set_stderr(Box::new(Pipe::channel().0));
-    }
+    let (stdout, stderr) = capture_stdio(&mut config);
 
     // TODO: impl capturer for stdin
 
@@ -368,6 +694,9 @@ pub unsafe extern "C" fn wasi_env_new(
     Some(Box::new(wasi_env_t {
         inner: env,
         store: store.clone(),
+        version: config.version,
+        stdout,
+        stderr,
     }))
 }
 
@@ -397,6 +726,9 @@ pub unsafe extern "C" fn wasi_env_read_stdout(
     buffer_len: usize,
 ) -> isize {
     let inner_buffer = slice::from_raw_parts_mut(buffer as *mut _, buffer_len);
+    if let Some(captured) = env.stdout.as_mut() {
+        return read_captured(captured, inner_buffer);
+    }
     let store = env.store.store();
 
     let stdout = {
@@ -424,6 +756,9 @@ pub unsafe extern "C" fn wasi_env_read_stderr(
     buffer_len: usize,
 ) -> isize {
     let inner_buffer = slice::from_raw_parts_mut(buffer as *mut _, buffer_len);
+    if let Some(captured) = env.stderr.as_mut() {
+        return read_captured(captured, inner_buffer);
+    }
     let store = env.store.store();
     let stderr = {
         let data = env.inner.data(&store);
@@ -442,6 +777,55 @@ pub unsafe extern "C" fn wasi_env_read_stderr(
     }
 }
 
+/// Move everything the guest wrote to the captured `stdout` so far
+/// into `out`, which can be empty.
+///
+/// Returns `false` if `stdout` wasn't captured with
+/// `wasi_config_capture_stdout`.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_env_take_stdout(
+    env: &mut wasi_env_t,
+    // own
+    out: &mut wasm_byte_vec_t,
+) -> bool {
+    take_captured(env.stdout.as_mut(), "stdout", out)
+}
+
+/// Move everything the guest wrote to the captured `stderr` so far
+/// into `out`, which can be empty.
+///
+/// Returns `false` if `stderr` wasn't captured with
+/// `wasi_config_capture_stderr`.
+#[no_mangle]
+pub unsafe extern "C" fn wasi_env_take_stderr(
+    env: &mut wasi_env_t,
+    // own
+    out: &mut wasm_byte_vec_t,
+) -> bool {
+    take_captured(env.stderr.as_mut(), "stderr", out)
+}
+
+fn take_captured(pipe: Option<&mut Pipe>, name: &str, out: &mut wasm_byte_vec_t) -> bool {
+    let Some(pipe) = pipe else {
+        update_last_error(format!("`{name}` isn't captured"));
+        return false;
+    };
+
+    let mut bytes = Vec::new();
+    let mut chunk = [0; 4096];
+    while let Some(read @ 1..) = pipe.try_read(&mut chunk) {
+        bytes.extend_from_slice(&chunk[..read]);
+    }
+
+    out.set_buffer(bytes);
+    true
+}
+
+/// Read what the guest wrote so far, without waiting for more output.
+fn read_captured(pipe: &mut Pipe, inner_buffer: &mut [u8]) -> isize {
+    pipe.try_read(inner_buffer).unwrap_or(0) as isize
+}
+
 fn read_inner(
     wasi_file: &mut Box<dyn VirtualFile + Send + Sync + 'static>,
     inner_buffer: &mut [u8],
@@ -546,9 +930,14 @@ unsafe fn wasi_get_imports_inner(
     let store = &mut wasi_env.store;
     let module = module?;
 
-    let mut import_object = c_try!(wasi_env
-        .inner
-        .import_object(&mut store.store_mut(), &module.inner));
+    let mut import_object = match wasi_env.version {
+        Some(version) => {
+            generate_import_object_from_env(&mut store.store_mut(), &wasi_env.inner.env, version)
+        }
+        None => c_try!(wasi_env
+            .inner
+            .import_object(&mut store.store_mut(), &module.inner)),
+    };
 
     let shared_memory = module.inner.imports().memories().next().map(|a| *a.ty());
 
@@ -734,4 +1123,347 @@ mod tests {
         })
         .success();
     }
+
+    #[cfg_attr(coverage, ignore)]
+    #[test]
+    fn test_wasi_config_set_version() {
+        (assert_c! {
+            #include "tests/wasmer.h"
+
+            int main() {
+                wasm_engine_t* engine = wasm_engine_new();
+                wasm_store_t* store = wasm_store_new(engine);
+
+                wasm_byte_vec_t wat;
+                wasmer_byte_vec_new_from_string(&wat, "(module (memory (export \"memory\") 1) (func (export \"_start\")))");
+                wasm_byte_vec_t wasm;
+                wat2wasm(&wat, &wasm);
+
+                wasm_module_t* module = wasm_module_new(store, &wasm);
+                assert(module);
+                assert(wasi_get_wasi_version(module) == INVALID_VERSION);
+
+                // Without imports, the version can't be detected.
+                wasi_config_t* config = wasi_config_new("example_program");
+                wasi_env_t* wasi_env = wasi_env_new(store, config);
+                assert(wasi_env);
+                wasm_extern_vec_t imports;
+                assert(!wasi_get_imports(store, wasi_env, module, &imports));
+                wasi_env_delete(wasi_env);
+
+                config = wasi_config_new("example_program");
+                assert(!wasi_config_set_version(config, INVALID_VERSION));
+                assert(wasi_config_set_version(config, SNAPSHOT1));
+                wasi_env = wasi_env_new(store, config);
+                assert(wasi_env);
+                assert(wasi_get_imports(store, wasi_env, module, &imports));
+                assert(imports.size == 0);
+
+                wasm_extern_vec_delete(&imports);
+                wasi_env_delete(wasi_env);
+                wasm_module_delete(module);
+                wasm_byte_vec_delete(&wasm);
+                wasm_byte_vec_delete(&wat);
+                wasm_store_delete(store);
+                wasm_engine_delete(engine);
+
+                return 0;
+            }
+        })
+        .success();
+    }
+
+    #[cfg_attr(coverage, ignore)]
+    #[test]
+    fn test_wasi_config_mapdir_readonly() {
+        (assert_c! {
+            #include "tests/wasmer.h"
+
+            int main() {
+                wasi_config_t* config = wasi_config_new("example_program");
+
+                assert(wasi_config_mapdir_readonly(config, "/host", "."));
+                assert(wasi_config_preopen_dir_readonly(config, "."));
+                assert(!wasi_config_mapdir_readonly(config, NULL, "."));
+                assert(wasmer_last_error_length() > 0);
+
+                wasm_engine_t* engine = wasm_engine_new();
+                wasm_store_t* store = wasm_store_new(engine);
+                wasi_env_t* wasi_env = wasi_env_new(store, config);
+                assert(wasi_env);
+
+                wasi_env_delete(wasi_env);
+                wasm_store_delete(store);
+                wasm_engine_delete(engine);
+
+                return 0;
+            }
+        })
+        .success();
+    }
+
+    #[cfg_attr(coverage, ignore)]
+    #[test]
+    fn test_wasi_config_stdin_bytes_and_inherited_env() {
+        (assert_c! {
+            #include "tests/wasmer.h"
+            #include <stdlib.h>
+            #include <string.h>
+
+            // Echoes `stdin` to `stdout`, followed by the first environment variable.
+            static const char* WAT =
+                "(module"
+                "  (import \"wasi_snapshot_preview1\" \"fd_read\" (func $fd_read (param i32 i32 i32 i32) (result i32)))"
+                "  (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))"
+                "  (import \"wasi_snapshot_preview1\" \"environ_sizes_get\" (func $environ_sizes_get (param i32 i32) (result i32)))"
+                "  (import \"wasi_snapshot_preview1\" \"environ_get\" (func $environ_get (param i32 i32) (result i32)))"
+                "  (memory (export \"memory\") 1)"
+                "  (func (export \"_start\")"
+                "    (i32.store (i32.const 0) (i32.const 256))"
+                "    (i32.store (i32.const 4) (i32.const 64))"
+                "    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))"
+                "    (i32.store (i32.const 4) (i32.load (i32.const 8)))"
+                "    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))"
+                "    (drop (call $environ_sizes_get (i32.const 16) (i32.const 20)))"
+                "    (drop (call $environ_get (i32.const 32) (i32.const 512)))"
+                "    (i32.store (i32.const 0) (i32.const 512))"
+                "    (i32.store (i32.const 4) (i32.sub (i32.load (i32.const 20)) (i32.const 1)))"
+                "    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))";
+
+            int main() {
+                setenv("WASMER_CAPI_TEST_GREETING", "hi", 1);
+
+                wasm_engine_t* engine = wasm_engine_new();
+                wasm_store_t* store = wasm_store_new(engine);
+
+                wasm_byte_vec_t wat;
+                wasmer_byte_vec_new_from_string(&wat, WAT);
+                wasm_byte_vec_t wasm;
+                wat2wasm(&wat, &wasm);
+                wasm_module_t* module = wasm_module_new(store, &wasm);
+                assert(module);
+
+                wasi_config_t* config = wasi_config_new("example_program");
+                wasi_config_inherit_env(config, "WASMER_CAPI_TEST_");
+                wasm_byte_vec_t input;
+                wasmer_byte_vec_new_from_string(&input, "ping;");
+                wasi_config_set_stdin_bytes(config, &input);
+                wasm_byte_vec_delete(&input);
+                wasi_config_capture_stdout(config);
+
+                wasi_env_t* wasi_env = wasi_env_new(store, config);
+                assert(wasi_env);
+
+                wasm_extern_vec_t imports;
+                assert(wasi_get_imports(store, wasi_env, module, &imports));
+                wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+                assert(instance);
+                assert(wasi_env_initialize_instance(wasi_env, store, instance));
+
+                wasm_func_t* start = wasi_get_start_function(instance);
+                assert(start);
+                wasm_val_vec_t args = WASM_EMPTY_VEC;
+                wasm_val_vec_t results = WASM_EMPTY_VEC;
+                assert(!wasm_func_call(start, &args, &results));
+
+                char buffer[128] = { 0 };
+                size_t total = 0;
+                intptr_t len;
+                while ((len = wasi_env_read_stdout(wasi_env, buffer + total, sizeof(buffer) - 1 - total)) > 0) {
+                    total += len;
+                }
+                assert(len == 0);
+                assert(strcmp(buffer, "ping;WASMER_CAPI_TEST_GREETING=hi") == 0);
+
+                wasm_func_delete(start);
+                wasm_instance_delete(instance);
+                wasm_extern_vec_delete(&imports);
+                wasi_env_delete(wasi_env);
+                wasm_module_delete(module);
+                wasm_byte_vec_delete(&wasm);
+                wasm_byte_vec_delete(&wat);
+                wasm_store_delete(store);
+                wasm_engine_delete(engine);
+
+                return 0;
+            }
+        })
+        .success();
+    }
+
+    #[cfg_attr(coverage, ignore)]
+    #[cfg(unix)]
+    #[test]
+    fn test_wasi_config_set_stdout_fd() {
+        (assert_c! {
+            #include "tests/wasmer.h"
+            #include <string.h>
+            #include <unistd.h>
+
+            static const char* WAT =
+                "(module"
+                "  (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))"
+                "  (memory (export \"memory\") 1)"
+                "  (data (i32.const 16) \"hello\")"
+                "  (func (export \"_start\")"
+                "    (i32.store (i32.const 0) (i32.const 16))"
+                "    (i32.store (i32.const 4) (i32.const 5))"
+                "    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))";
+
+            int main() {
+                int fds[2];
+                assert(pipe(fds) == 0);
+
+                wasm_engine_t* engine = wasm_engine_new();
+                wasm_store_t* store = wasm_store_new(engine);
+
+                wasm_byte_vec_t wat;
+                wasmer_byte_vec_new_from_string(&wat, WAT);
+                wasm_byte_vec_t wasm;
+                wat2wasm(&wat, &wasm);
+                wasm_module_t* module = wasm_module_new(store, &wasm);
+                assert(module);
+
+                wasi_config_t* config = wasi_config_new("example_program");
+                assert(!wasi_config_set_stdout_fd(config, -1));
+                assert(wasi_config_set_stdout_fd(config, fds[1]));
+                // The configuration holds its own copy of the descriptor.
+                close(fds[1]);
+
+                wasi_env_t* wasi_env = wasi_env_new(store, config);
+                assert(wasi_env);
+
+                wasm_extern_vec_t imports;
+                assert(wasi_get_imports(store, wasi_env, module, &imports));
+                wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+                assert(instance);
+                assert(wasi_env_initialize_instance(wasi_env, store, instance));
+
+                wasm_func_t* start = wasi_get_start_function(instance);
+                assert(start);
+                wasm_val_vec_t args = WASM_EMPTY_VEC;
+                wasm_val_vec_t results = WASM_EMPTY_VEC;
+                assert(!wasm_func_call(start, &args, &results));
+
+                char buffer[16] = { 0 };
+                assert(read(fds[0], buffer, sizeof(buffer) - 1) == 5);
+                assert(strcmp(buffer, "hello") == 0);
+                close(fds[0]);
+
+                wasm_func_delete(start);
+                wasm_instance_delete(instance);
+                wasm_extern_vec_delete(&imports);
+                wasi_env_delete(wasi_env);
+                wasm_module_delete(module);
+                wasm_byte_vec_delete(&wasm);
+                wasm_byte_vec_delete(&wat);
+                wasm_store_delete(store);
+                wasm_engine_delete(engine);
+
+                return 0;
+            }
+        })
+        .success();
+    }
+
+    #[cfg_attr(coverage, ignore)]
+    #[test]
+    fn test_wasi_config_stdout_callback_and_take_stderr() {
+        (assert_c! {
+            #include "tests/wasmer.h"
+            #include <string.h>
+
+            static const char* WAT =
+                "(module"
+                "  (import \"wasi_snapshot_preview1\" \"fd_write\" (func $fd_write (param i32 i32 i32 i32) (result i32)))"
+                "  (memory (export \"memory\") 1)"
+                "  (data (i32.const 16) \"hello\")"
+                "  (data (i32.const 32) \"oops\")"
+                "  (func (export \"_start\")"
+                "    (i32.store (i32.const 0) (i32.const 16))"
+                "    (i32.store (i32.const 4) (i32.const 5))"
+                "    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))"
+                "    (i32.store (i32.const 0) (i32.const 32))"
+                "    (i32.store (i32.const 4) (i32.const 4))"
+                "    (drop (call $fd_write (i32.const 2) (i32.const 0) (i32.const 1) (i32.const 8)))))";
+
+            typedef struct {
+                char data[16];
+                size_t len;
+            } output_t;
+
+            void on_stdout(void* user_data, const char* data, size_t len) {
+                output_t* output = user_data;
+                assert(output->len + len < sizeof(output->data));
+                memcpy(output->data + output->len, data, len);
+                output->len += len;
+            }
+
+            int main() {
+                wasm_engine_t* engine = wasm_engine_new();
+                wasm_store_t* store = wasm_store_new(engine);
+
+                wasm_byte_vec_t wat;
+                wasmer_byte_vec_new_from_string(&wat, WAT);
+                wasm_byte_vec_t wasm;
+                wat2wasm(&wat, &wasm);
+                wasm_module_t* module = wasm_module_new(store, &wasm);
+                assert(module);
+
+                output_t output = { { 0 }, 0 };
+
+                wasi_config_t* config = wasi_config_new("example_program");
+                assert(!wasi_config_set_stdout_callback(config, NULL, NULL));
+                assert(wasi_config_set_stdout_callback(config, on_stdout, &output));
+                wasi_config_capture_stderr(config);
+
+                wasi_env_t* wasi_env = wasi_env_new(store, config);
+                assert(wasi_env);
+
+                wasm_extern_vec_t imports;
+                assert(wasi_get_imports(store, wasi_env, module, &imports));
+                wasm_instance_t* instance = wasm_instance_new(store, module, &imports, NULL);
+                assert(instance);
+                assert(wasi_env_initialize_instance(wasi_env, store, instance));
+
+                wasm_func_t* start = wasi_get_start_function(instance);
+                assert(start);
+                wasm_val_vec_t args = WASM_EMPTY_VEC;
+                wasm_val_vec_t results = WASM_EMPTY_VEC;
+                assert(!wasm_func_call(start, &args, &results));
+
+                // `stdout` went through the callback.
+                assert(output.len == 5);
+                assert(strcmp(output.data, "hello") == 0);
+
+                wasm_byte_vec_t stdout_bytes;
+                assert(!wasi_env_take_stdout(wasi_env, &stdout_bytes));
+
+                // `stderr` was captured.
+                wasm_byte_vec_t stderr_bytes;
+                assert(wasi_env_take_stderr(wasi_env, &stderr_bytes));
+                assert(stderr_bytes.size == 4);
+                assert(memcmp(stderr_bytes.data, "oops", 4) == 0);
+                wasm_byte_vec_delete(&stderr_bytes);
+
+                // Everything was taken.
+                assert(wasi_env_take_stderr(wasi_env, &stderr_bytes));
+                assert(stderr_bytes.size == 0);
+                wasm_byte_vec_delete(&stderr_bytes);
+
+                wasm_func_delete(start);
+                wasm_instance_delete(instance);
+                wasm_extern_vec_delete(&imports);
+                wasi_env_delete(wasi_env);
+                wasm_module_delete(module);
+                wasm_byte_vec_delete(&wasm);
+                wasm_byte_vec_delete(&wat);
+                wasm_store_delete(store);
+                wasm_engine_delete(engine);
+
+                return 0;
+            }
+        })
+        .success();
+    }
 }
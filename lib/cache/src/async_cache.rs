@@ -0,0 +1,30 @@
+//! The asynchronous counterpart of [`crate::Cache`], for caches whose
+//! operations would block the threads of an async runtime.
+
+use crate::hash::Hash;
+use std::error::Error;
+use wasmer::{Engine, Module};
+
+/// A generic cache for storing and loading compiled wasm modules without
+/// blocking, e.g. over the network.
+#[async_trait::async_trait]
+pub trait AsyncCache {
+    /// The serialization error for the implementation
+    type SerializeError: Error + Send + Sync;
+    /// The deserialization error for the implementation
+    type DeserializeError: Error + Send + Sync;
+
+    /// Loads a module using the provided [`wasmer::Engine`] and [`crate::Hash`].
+    ///
+    /// # Safety
+    /// This function is unsafe as the cache store could be tampered with.
+    async unsafe fn load_async(
+        &self,
+        engine: &Engine,
+        key: Hash,
+    ) -> Result<Module, Self::DeserializeError>;
+
+    /// Store a [`Module`] into the cache with the given [`crate::Hash`].
+    async fn store_async(&mut self, key: Hash, module: &Module)
+        -> Result<(), Self::SerializeError>;
+}
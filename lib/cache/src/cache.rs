@@ -3,6 +3,7 @@
 //! and loaded to allow skipping compilation and fast startup.
 
 use crate::hash::Hash;
+use crate::tiered::TieredCache;
 use std::error::Error;
 use wasmer::{AsEngineRef, Module};
 
@@ -25,4 +26,14 @@ pub trait Cache {
 
     /// Store a [`Module`] into the cache with the given [`crate::Hash`].
     fn store(&mut self, key: Hash, module: &Module) -> Result<(), Self::SerializeError>;
+
+    /// Chain a slower cache, used when modules aren't in this one.
+    ///
+    /// See [`TieredCache`] for more details.
+    fn with_fallback<C: Cache>(self, fallback: C) -> TieredCache<Self, C>
+    where
+        Self: Sized,
+    {
+        TieredCache::new(self, fallback)
+    }
 }
@@ -1,16 +1,23 @@
 #![cfg_attr(not(feature = "filesystem"), allow(unused))]
 use crate::cache::Cache;
 use crate::hash::Hash;
-use std::fs::{create_dir_all, File};
+use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Write};
 use std::path::PathBuf;
 use wasmer::{AsEngineRef, DeserializeError, Module, SerializeError};
 
+const LOCK_FILE: &str = ".lock";
+
 /// Representation of a directory that contains compiled wasm artifacts.
 ///
 /// The `FileSystemCache` type implements the [`Cache`] trait, which allows it to be used
 /// generically when some sort of cache is required.
 ///
+/// Several processes can share the same directory: artifacts are written to
+/// a temporary file which is then renamed into place, and an advisory lock on
+/// the `.lock` file of the directory keeps a process from deleting an
+/// artifact that another one is replacing.
+///
 /// # Usage
 ///
 /// ```
@@ -85,6 +92,49 @@ impl FileSystemCache {
     pub fn set_cache_extension(&mut self, ext: Option<impl ToString>) {
         self.ext = ext.map(|ext| ext.to_string());
     }
+
+    /// The file where the artifact of `key` is stored.
+    pub(crate) fn artifact_path(&self, key: Hash) -> PathBuf {
+        let filename = if let Some(ref ext) = self.ext {
+            format!("{}.{}", key, ext)
+        } else {
+            key.to_string()
+        };
+        self.path.join(filename)
+    }
+
+    /// The file locked while artifacts are read or replaced. A single file
+    /// is shared by all the artifacts, so that none is left behind for each
+    /// of them: deleting a lock file is racy as another process may hold it.
+    fn open_lock(&self) -> io::Result<fd_lock::RwLock<File>> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.path.join(LOCK_FILE))?;
+        Ok(fd_lock::RwLock::new(file))
+    }
+
+    /// Atomically write the serialized artifact of `key`.
+    pub(crate) fn store_bytes(&self, key: Hash, bytes: &[u8]) -> io::Result<()> {
+        let path = self.artifact_path(key);
+        // Concurrent readers never see a partially written artifact
+        let mut temp = tempfile::NamedTempFile::new_in(&self.path)?;
+        temp.write_all(bytes)?;
+        temp.flush()?;
+
+        let mut lock = self.open_lock()?;
+        let _guard = lock.write()?;
+        if let Err(e) = temp.persist(&path) {
+            // Replacing an artifact in use fails on Windows, but it was
+            // stored under the same key so it is as good as ours
+            if !path.exists() {
+                return Err(e.error);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(feature = "filesystem")]
@@ -97,32 +147,29 @@ impl Cache for FileSystemCache {
         engine: &impl AsEngineRef,
         key: Hash,
     ) -> Result<Module, Self::DeserializeError> {
-        let filename = if let Some(ref ext) = self.ext {
-            format!("{}.{}", key, ext)
-        } else {
-            key.to_string()
-        };
-        let path = self.path.join(filename);
+        let path = self.artifact_path(key);
+        if !path.exists() {
+            return Err(DeserializeError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("the artifact {} isn't cached", key),
+            )));
+        }
+
+        let lock = self.open_lock()?;
+        let _guard = lock.read()?;
         let ret = Module::deserialize_from_file(engine, path.clone());
         if ret.is_err() {
             // If an error occurs while deserializing then we can not trust it anymore
-            // so delete the cache file
+            // so delete the cache file. Holding the lock guarantees that no other
+            // process is replacing it with a good one meanwhile.
             let _ = std::fs::remove_file(path);
         }
         ret
     }
 
     fn store(&mut self, key: Hash, module: &Module) -> Result<(), Self::SerializeError> {
-        let filename = if let Some(ref ext) = self.ext {
-            format!("{}.{}", key, ext)
-        } else {
-            key.to_string()
-        };
-        let path = self.path.join(filename);
-        let mut file = File::create(path)?;
-
         let buffer = module.serialize()?;
-        file.write_all(&buffer)?;
+        self.store_bytes(key, &buffer)?;
 
         Ok(())
     }
@@ -132,6 +179,8 @@ impl Cache for FileSystemCache {
 mod tests {
     use super::*;
 
+    const WAT: &str = r#"(module (func (export "add") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))"#;
+
     #[test]
     fn test_fs_cache() {
         let dir = tempfile::tempdir().unwrap();
@@ -140,12 +189,40 @@ mod tests {
 
         let engine = wasmer::Engine::default();
 
-        let bytes = include_bytes!("../../wasix/tests/envvar.wasm");
-
-        let module = Module::from_binary(&engine, bytes).unwrap();
-        let key = Hash::generate(bytes);
+        let module = Module::new(&engine, WAT).unwrap();
+        let key = Hash::generate(WAT.as_bytes());
 
         cache.store(key, &module).unwrap();
         let _restored = unsafe { cache.load(&engine, key).unwrap() };
     }
+
+    #[test]
+    fn test_concurrent_fs_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = wasmer::Engine::default();
+        let module = Module::new(&engine, WAT).unwrap();
+        let key = Hash::generate(WAT.as_bytes());
+
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                let mut cache = FileSystemCache::new(dir.path()).unwrap();
+                let (engine, module) = (&engine, &module);
+                s.spawn(move || {
+                    for _ in 0..10 {
+                        cache.store(key, module).unwrap();
+                        // Other threads may only replace the artifact, so
+                        // it is always there and never torn
+                        let _restored = unsafe { cache.load(engine, key).unwrap() };
+                    }
+                });
+            }
+        });
+
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .filter(|name| *name != *key.to_string() && *name != LOCK_FILE)
+            .collect();
+        assert!(leftovers.is_empty(), "{:?}", leftovers);
+    }
 }
@@ -4,6 +4,7 @@ use std::{
     fmt::{self, Display, Formatter},
     str::FromStr,
 };
+use wasmer::AsEngineRef;
 
 /// A hash used as a key when loading and storing modules in a
 /// [`crate::Cache`].
@@ -24,6 +25,25 @@ impl Hash {
         let hash = blake3::hash(bytes);
         Self::new(hash.into())
     }
+
+    /// Creates a new hash from a slice of bytes and the engine compiling them.
+    ///
+    /// The hash changes with [`wasmer::Engine::deterministic_id()`], so
+    /// artifacts compiled by another version of Wasmer, with other compiler
+    /// settings or for other CPU features are not loaded by mistake.
+    pub fn generate_for_engine(engine: &impl AsEngineRef, bytes: &[u8]) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(
+            engine
+                .as_engine_ref()
+                .engine()
+                .deterministic_id()
+                .as_bytes(),
+        );
+        hasher.update(&[0]);
+        hasher.update(bytes);
+        Self::new(hasher.finalize().into())
+    }
 }
 
 impl Display for Hash {
@@ -78,4 +98,24 @@ mod tests {
             "aabbccddeeff1265aabbccddeeff1265aabbccddeeff1265aabbccddeeff1265"
         );
     }
+
+    #[test]
+    fn hash_depends_on_the_engine() {
+        use wasmer::sys::{Cranelift, CraneliftOptLevel, EngineBuilder};
+
+        let bytes = b"\0asm\x01\0\0\0";
+        let engine = wasmer::Engine::from(EngineBuilder::new(Cranelift::default()));
+        let mut config = Cranelift::default();
+        config.opt_level(CraneliftOptLevel::None);
+        let unoptimized = wasmer::Engine::from(EngineBuilder::new(config));
+        let mut config = Cranelift::default();
+        config.canonicalize_nans(true);
+        let canonicalized = wasmer::Engine::from(EngineBuilder::new(config));
+
+        let hash = Hash::generate_for_engine(&engine, bytes);
+        assert_eq!(hash, Hash::generate_for_engine(&engine.clone(), bytes));
+        assert_ne!(hash, Hash::generate(bytes));
+        assert_ne!(hash, Hash::generate_for_engine(&unoptimized, bytes));
+        assert_ne!(hash, Hash::generate_for_engine(&canonicalized, bytes));
+    }
 }
@@ -15,14 +15,26 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+#[cfg(feature = "async")]
+mod async_cache;
 mod cache;
 mod filesystem;
 mod hash;
+mod memory;
+#[cfg(feature = "remote")]
+mod remote;
+mod tiered;
 
+#[cfg(feature = "async")]
+pub use crate::async_cache::AsyncCache;
 pub use crate::cache::Cache;
 #[cfg(feature = "filesystem")]
 pub use crate::filesystem::FileSystemCache;
 pub use crate::hash::Hash;
+pub use crate::memory::MemoryCache;
+#[cfg(feature = "remote")]
+pub use crate::remote::{RemoteCache, S3Bucket};
+pub use crate::tiered::TieredCache;
 
 // We re-export those for convinience of users
 pub use wasmer::{DeserializeError, SerializeError};
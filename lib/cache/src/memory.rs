@@ -0,0 +1,188 @@
+use crate::cache::Cache;
+use crate::hash::Hash;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use wasmer::{AsEngineRef, DeserializeError, Module, SerializeError};
+
+/// A cache keeping compiled wasm artifacts in memory.
+///
+/// Artifacts are kept serialized, and deserialized with the engine given to
+/// [`Cache::load`]. When a maximum size is set, the least recently used
+/// artifacts are dropped to make room for new ones. Clones of a
+/// `MemoryCache` share the same artifacts.
+///
+/// # Usage
+///
+/// ```
+/// use wasmer::{DeserializeError, SerializeError};
+/// use wasmer_cache::{Cache, FileSystemCache, Hash, MemoryCache};
+///
+/// # use wasmer::{Module};
+/// fn store_module(module: &Module, bytes: &[u8]) -> Result<(), SerializeError> {
+///     // Keep up to 256MB of artifacts in memory, in front of the file system
+///     let mut cache = MemoryCache::new()
+///         .with_max_size(256 * 1024 * 1024)
+///         .with_fallback(FileSystemCache::new("some/directory/goes/here")?);
+///
+///     cache.store(Hash::generate(bytes), module)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCache {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    artifacts: HashMap<Hash, Artifact>,
+    size: u64,
+    max_size: Option<u64>,
+    /// Incremented every time an artifact is used
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct Artifact {
+    bytes: Arc<[u8]>,
+    last_used: u64,
+}
+
+impl MemoryCache {
+    /// Construct a new, empty, `MemoryCache`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the total size of the artifacts under `max_size` bytes.
+    pub fn with_max_size(self, max_size: u64) -> Self {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.max_size = Some(max_size);
+            state.evict();
+        }
+        self
+    }
+
+    /// The number of artifacts in the cache.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().artifacts.len()
+    }
+
+    /// Whether the cache holds no artifact.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total size of the artifacts, in bytes.
+    pub fn size(&self) -> u64 {
+        self.state.lock().unwrap().size
+    }
+
+    /// Drop every artifact.
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.artifacts.clear();
+        state.size = 0;
+    }
+
+    fn get(&self, key: Hash) -> Option<Arc<[u8]>> {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        let artifact = state.artifacts.get_mut(&key)?;
+        artifact.last_used = clock;
+        Some(artifact.bytes.clone())
+    }
+
+    fn insert(&self, key: Hash, module: &Module) -> Result<(), SerializeError> {
+        let bytes: Arc<[u8]> = module.serialize()?.to_vec().into();
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let artifact = Artifact {
+            bytes,
+            last_used: state.clock,
+        };
+        state.size += artifact.bytes.len() as u64;
+        if let Some(previous) = state.artifacts.insert(key, artifact) {
+            state.size -= previous.bytes.len() as u64;
+        }
+        state.evict();
+        Ok(())
+    }
+}
+
+impl State {
+    fn evict(&mut self) {
+        let Some(max_size) = self.max_size else {
+            return;
+        };
+        while self.size > max_size {
+            let oldest = self
+                .artifacts
+                .iter()
+                .min_by_key(|(_, artifact)| artifact.last_used)
+                .map(|(key, _)| *key);
+            let Some(artifact) = oldest.and_then(|key| self.artifacts.remove(&key)) else {
+                break;
+            };
+            self.size -= artifact.bytes.len() as u64;
+        }
+    }
+}
+
+impl Cache for MemoryCache {
+    type DeserializeError = DeserializeError;
+    type SerializeError = SerializeError;
+
+    unsafe fn load(
+        &self,
+        engine: &impl AsEngineRef,
+        key: Hash,
+    ) -> Result<Module, Self::DeserializeError> {
+        let bytes = self.get(key).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("the artifact {} isn't in memory", key),
+            )
+        })?;
+        Module::deserialize(engine, &*bytes)
+    }
+
+    fn store(&mut self, key: Hash, module: &Module) -> Result<(), Self::SerializeError> {
+        self.insert(key, module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAT: &str = r#"(module (func (export "add") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))"#;
+
+    fn module(engine: &wasmer::Engine) -> Module {
+        Module::new(engine, WAT).unwrap()
+    }
+
+    #[test]
+    fn test_memory_cache() {
+        let engine = wasmer::Engine::default();
+        let [first, second, third] = [1, 2, 3].map(|i| Hash::new([i; 32]));
+        let module = module(&engine);
+        let size = module.serialize().unwrap().len() as u64;
+        let mut cache = MemoryCache::new().with_max_size(2 * size);
+
+        cache.store(first, &module).unwrap();
+        cache.store(second, &module).unwrap();
+        // Loading an artifact makes it the most recently used one
+        let _restored = unsafe { cache.load(&engine, first).unwrap() };
+        cache.store(third, &module).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(unsafe { cache.load(&engine, first) }.is_ok());
+        assert!(unsafe { cache.load(&engine, second) }.is_err());
+        assert!(unsafe { cache.load(&engine, third) }.is_ok());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::cache::Cache;
+use crate::hash::Hash;
+use std::sync::{RwLock, RwLockReadGuard};
+use wasmer::{AsEngineRef, Module};
+
+/// A fast [`Cache`] in front of a slow one, created with
+/// [`Cache::with_fallback`].
+///
+/// Artifacts are looked up in the fast cache first, and the ones loaded from
+/// the slow cache are stored in the fast one for the next time. Stored
+/// artifacts go to both caches. Failures of the fast cache are ignored, as
+/// it is only an optimization.
+///
+/// Tiered caches can be nested to chain more than two caches.
+///
+/// # Usage
+///
+/// ```
+/// use wasmer::{DeserializeError, SerializeError};
+/// use wasmer_cache::{Cache, FileSystemCache, Hash, MemoryCache};
+///
+/// # use wasmer::{Module};
+/// fn store_module(module: &Module, bytes: &[u8]) -> Result<(), SerializeError> {
+///     let mut cache = MemoryCache::new()
+///         .with_fallback(FileSystemCache::new("some/directory/goes/here")?);
+///
+///     cache.store(Hash::generate(bytes), module)?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TieredCache<A, B> {
+    // Loads only borrow the cache, but artifacts loaded from the slow cache
+    // are stored in the fast one
+    fast: RwLock<A>,
+    slow: B,
+}
+
+impl<A, B> TieredCache<A, B> {
+    /// Construct a new `TieredCache` looking artifacts up in `fast` before `slow`.
+    pub fn new(fast: A, slow: B) -> Self {
+        Self {
+            fast: RwLock::new(fast),
+            slow,
+        }
+    }
+
+    /// The cache looked up first.
+    pub fn fast(&self) -> RwLockReadGuard<'_, A> {
+        self.fast.read().unwrap()
+    }
+
+    /// The cache looked up first.
+    pub fn fast_mut(&mut self) -> &mut A {
+        self.fast.get_mut().unwrap()
+    }
+
+    /// The cache looked up when artifacts aren't in the fast one.
+    pub fn slow(&self) -> &B {
+        &self.slow
+    }
+
+    /// The cache looked up when artifacts aren't in the fast one.
+    pub fn slow_mut(&mut self) -> &mut B {
+        &mut self.slow
+    }
+
+    /// Get the fast and the slow caches back.
+    pub fn into_inner(self) -> (A, B) {
+        (self.fast.into_inner().unwrap(), self.slow)
+    }
+}
+
+impl<A: Clone, B: Clone> Clone for TieredCache<A, B> {
+    fn clone(&self) -> Self {
+        Self::new(self.fast().clone(), self.slow.clone())
+    }
+}
+
+impl<A: Cache, B: Cache> Cache for TieredCache<A, B> {
+    type DeserializeError = B::DeserializeError;
+    type SerializeError = B::SerializeError;
+
+    unsafe fn load(
+        &self,
+        engine: &impl AsEngineRef,
+        key: Hash,
+    ) -> Result<Module, Self::DeserializeError> {
+        if let Ok(module) = self.fast().load(engine, key) {
+            return Ok(module);
+        }
+
+        let module = self.slow.load(engine, key)?;
+        let _ = self.fast.write().unwrap().store(key, &module);
+        Ok(module)
+    }
+
+    fn store(&mut self, key: Hash, module: &Module) -> Result<(), Self::SerializeError> {
+        self.slow.store(key, module)?;
+        let _ = self.fast_mut().store(key, module);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileSystemCache, MemoryCache};
+
+    const WAT: &str = r#"(module (func (export "add") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))"#;
+
+    #[test]
+    fn test_tiered_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = wasmer::Engine::default();
+        let module = Module::new(&engine, WAT).unwrap();
+        let [slow_key, stored_key] = [1, 2].map(|i| Hash::new([i; 32]));
+        FileSystemCache::new(dir.path())
+            .unwrap()
+            .store(slow_key, &module)
+            .unwrap();
+
+        let mut cache = MemoryCache::new().with_fallback(FileSystemCache::new(dir.path()).unwrap());
+        cache.store(stored_key, &module).unwrap();
+        // Artifacts loaded from the slow cache are backfilled in the fast one
+        let _restored = unsafe { cache.load(&engine, slow_key).unwrap() };
+
+        assert_eq!(cache.fast().len(), 2);
+        assert!(unsafe { cache.slow().load(&engine, stored_key) }.is_ok());
+    }
+}
@@ -101,7 +101,7 @@ fn lookup_bindings_for_package(
     match all_bindings.iter().find(|b| b.language == *language) {
         Some(b) => {
             let Bindings { url, generator, .. } = b;
-            log::debug!("Found {pkg} bindings generated by {generator} at {url}");
+            tracing::debug!("Found {pkg} bindings generated by {generator} at {url}");
 
             Ok(b.clone())
         }
@@ -450,7 +450,7 @@ impl AsyncCliCommand for CmdAppDeploy {
                                         },
                                     )?;
 
-                                    log::info!(
+                                    tracing::info!(
                                         "Using package {} ({})",
                                         app_config.package,
                                         n.full_name()
@@ -521,7 +521,7 @@ impl AsyncCliCommand for CmdAppDeploy {
                         }
                     }
                 } else {
-                    log::info!("Using package {}", app_config.package.to_string());
+                    tracing::info!("Using package {}", app_config.package.to_string());
                     DeployAppOpts {
                         app: &app_config,
                         original_config: Some(app_config.clone().to_yaml_value().unwrap()),
@@ -533,7 +533,7 @@ impl AsyncCliCommand for CmdAppDeploy {
                 }
             }
             _ => {
-                log::info!("Using package {}", app_config.package.to_string());
+                tracing::info!("Using package {}", app_config.package.to_string());
                 DeployAppOpts {
                     app: &app_config,
                     original_config: Some(app_config.clone().to_yaml_value().unwrap()),
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use wasmer::*;
+use wasmer_compiler::ArtifactBuild;
+use wasmer_types::MetadataHeader;
+
+use crate::store::StoreOptions;
+
+/// Manage compiled artifacts (`.wasmu` files)
+#[derive(clap::Subcommand, Debug)]
+pub enum CmdArtifact {
+    /// Convert an artifact for the current Wasmer release and a given target
+    Convert(CmdArtifactConvert),
+}
+
+impl CmdArtifact {
+    /// Runs logic for the `artifact` subcommand
+    pub fn execute(&self) -> Result<()> {
+        match self {
+            Self::Convert(cmd) => cmd.execute(),
+        }
+    }
+}
+
+/// Convert an artifact for the current Wasmer release and a given target.
+///
+/// If the artifact embeds the original wasm module (see
+/// `wasmer compile --embed-wasm`), the module is recompiled for the
+/// requested target. Otherwise, the artifact sections are transcoded, which
+/// only works for artifacts produced with a compatible ABI version, and for
+/// the host target.
+#[derive(Debug, Parser)]
+pub struct CmdArtifactConvert {
+    /// Input artifact
+    #[clap(name = "FILE")]
+    path: PathBuf,
+
+    /// Output file
+    #[clap(name = "OUTPUT PATH", short = 'o')]
+    output: PathBuf,
+
+    /// The artifact ABI version to convert to
+    ///
+    /// Only the ABI version of this Wasmer release is supported.
+    #[clap(long = "to-version")]
+    to_version: Option<u32>,
+
+    /// Compilation Target triple
+    #[clap(long = "target")]
+    target_triple: Option<Triple>,
+
+    #[clap(flatten)]
+    store: StoreOptions,
+
+    #[clap(short = 'm')]
+    cpu_features: Vec<CpuFeature>,
+}
+
+impl CmdArtifactConvert {
+    /// Runs logic for the `artifact convert` subcommand
+    pub fn execute(&self) -> Result<()> {
+        self.inner_execute()
+            .context(format!("failed to convert `{}`", self.path.display()))
+    }
+
+    fn inner_execute(&self) -> Result<()> {
+        if let Some(version) = self.to_version {
+            if version != MetadataHeader::CURRENT_VERSION {
+                anyhow::bail!(
+                    "this release of Wasmer can only produce artifacts with ABI version {}, not {}",
+                    MetadataHeader::CURRENT_VERSION,
+                    version
+                );
+            }
+        }
+
+        let target = self
+            .target_triple
+            .as_ref()
+            .map(|target_triple| {
+                let mut features = self
+                    .cpu_features
+                    .clone()
+                    .into_iter()
+                    .fold(CpuFeature::set(), |a, b| a | b);
+                // Cranelift requires SSE2, so we have this "hack" for now to facilitate
+                // usage
+                if target_triple.architecture == Architecture::X86_64 {
+                    features |= CpuFeature::SSE2;
+                }
+                Target::new(target_triple.clone(), features)
+            })
+            .unwrap_or_default();
+        let (store, compiler_type) = self.store.get_store_for_target(target.clone())?;
+
+        let bytes = std::fs::read(&self.path)?;
+        let version = ArtifactBuild::serialized_version(&bytes)?;
+        let embeds_wasm = ArtifactBuild::embedded_wasm(&bytes).is_some();
+
+        println!("Source ABI version: {}", version);
+        println!("Embedded wasm: {}", if embeds_wasm { "yes" } else { "no" });
+        println!("Compiler: {}", compiler_type.to_string());
+        println!("Target: {}", target.triple());
+
+        let converted = store.engine().convert_artifact(bytes)?;
+        std::fs::write(&self.output, converted)?;
+        eprintln!(
+            "✔ Artifact converted successfully to `{}`.",
+            self.output.display(),
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,22 @@
-use std::{fs, path::Path};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use bytesize::ByteSize;
 use clap::Parser;
 use wasmer_registry::wasmer_env::WasmerEnv;
+use wasmer_wasix::runtime::{
+    module_cache::{CacheStats, FileSystemCache},
+    task_manager::tokio::TokioTaskManager,
+};
+
+/// The file in the cache of compiled modules where the statistics of every
+/// `wasmer run` are added up.
+const STATS_FILE: &str = "stats.json";
 
 #[derive(Debug, Parser)]
 /// The options for the `wasmer cache` subcommand
@@ -19,25 +33,203 @@ impl Cache {
     pub fn execute(&self) -> Result<()> {
         let cache_dir = self.env.cache_dir();
 
-        match self.cmd {
+        match &self.cmd {
             Cmd::Clean => {
                 clean(&cache_dir)?;
             }
             Cmd::Dir => {
                 println!("{}", self.env.cache_dir().display());
             }
+            Cmd::Stats => {
+                stats(&compiled_dir(&self.env))?;
+            }
+            Cmd::Export(export) => {
+                export.execute(&compiled_dir(&self.env))?;
+            }
+            Cmd::Import(import) => {
+                import.execute(&compiled_dir(&self.env))?;
+            }
         }
 
         Ok(())
     }
 }
 
-#[derive(Debug, Copy, Clone, Parser)]
+#[derive(Debug, Clone, Parser)]
 enum Cmd {
     /// Clear the cache
     Clean,
     /// Display the location of the cache
     Dir,
+    /// Display statistics about the cache of compiled modules, accumulated
+    /// by the runs with `--cache-stats`
+    Stats,
+    /// Bundle compiled modules in a tarball, e.g. to warm up the cache of a
+    /// CI job
+    Export(Export),
+    /// Restore compiled modules bundled with `wasmer cache export`
+    Import(Import),
+}
+
+#[derive(Debug, Clone, Parser)]
+struct Export {
+    /// The tarball to create, compressed according to its extension (`.tar`,
+    /// `.tar.gz` or `.tar.zst`)
+    output: PathBuf,
+    /// Only export the modules compiled by engines whose id starts with this
+    /// prefix (see `wasmer cache stats`)
+    #[clap(long)]
+    engine: Vec<String>,
+    /// Only export the modules with this hash
+    #[clap(long)]
+    key: Vec<String>,
+}
+
+impl Export {
+    fn execute(&self, compiled_dir: &Path) -> Result<()> {
+        let cache = filesystem_cache(compiled_dir)?;
+        let file = File::create(&self.output)
+            .with_context(|| format!("Unable to create \"{}\"", self.output.display()))?;
+        let mut writer: Box<dyn Write> = match Compression::of(&self.output) {
+            Compression::None => Box::new(BufWriter::new(file)),
+            Compression::Gzip => Box::new(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )),
+            Compression::Zstd => Box::new(zstd::Encoder::new(file, 0)?.auto_finish()),
+        };
+
+        let modules = cache.export(&mut writer, |module| {
+            (self.engine.is_empty() || self.engine.iter().any(|e| module.engine.starts_with(e)))
+                && (self.key.is_empty() || self.key.contains(&module.key))
+        })?;
+        writer.flush()?;
+        drop(writer);
+
+        let size: u64 = modules.iter().map(|m| m.size).sum();
+        eprintln!(
+            "Exported {} modules ({}) to \"{}\"",
+            modules.len(),
+            ByteSize(size),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+struct Import {
+    /// A tarball created by `wasmer cache export`
+    input: PathBuf,
+}
+
+impl Import {
+    fn execute(&self, compiled_dir: &Path) -> Result<()> {
+        let cache = filesystem_cache(compiled_dir)?;
+        let file = File::open(&self.input)
+            .with_context(|| format!("Unable to open \"{}\"", self.input.display()))?;
+        let reader: Box<dyn Read> = match Compression::of(&self.input) {
+            Compression::None => Box::new(BufReader::new(file)),
+            Compression::Gzip => Box::new(flate2::read::GzDecoder::new(file)),
+            Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+        };
+
+        let modules = cache
+            .import(reader)
+            .with_context(|| format!("Unable to import \"{}\"", self.input.display()))?;
+
+        let size: u64 = modules.iter().map(|m| m.size).sum();
+        eprintln!("Imported {} modules ({})", modules.len(), ByteSize(size));
+        Ok(())
+    }
+}
+
+/// How a tarball is compressed.
+enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn of(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".gz") || name.ends_with(".tgz") {
+            Compression::Gzip
+        } else if name.ends_with(".zst") || name.ends_with(".tzst") {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+fn filesystem_cache(compiled_dir: &Path) -> Result<FileSystemCache> {
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    let task_manager = Arc::new(TokioTaskManager::new(runtime));
+    Ok(FileSystemCache::new(compiled_dir, task_manager))
+}
+
+/// The directory where compiled modules are cached.
+pub(crate) fn compiled_dir(env: &WasmerEnv) -> PathBuf {
+    env.cache_dir().join("compiled")
+}
+
+/// Add the statistics of a run to the ones saved in `compiled_dir`.
+pub(crate) fn record_stats(compiled_dir: &Path, stats: CacheStats) -> Result<()> {
+    if stats == CacheStats::default() {
+        return Ok(());
+    }
+
+    // Concurrent runs may race to update the file, so the totals are only
+    // indicative. Replacing the file atomically at least keeps it readable.
+    let mut total = load_stats(compiled_dir)?;
+    total += stats;
+    fs::create_dir_all(compiled_dir)?;
+    let mut temp = tempfile::NamedTempFile::new_in(compiled_dir)?;
+    serde_json::to_writer(&mut temp, &total)?;
+    temp.flush()?;
+    temp.persist(compiled_dir.join(STATS_FILE))?;
+
+    Ok(())
+}
+
+fn load_stats(compiled_dir: &Path) -> Result<CacheStats> {
+    let path = compiled_dir.join(STATS_FILE);
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .with_context(|| format!("Unable to parse \"{}\"", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(CacheStats::default()),
+        Err(e) => Err(e).with_context(|| format!("Unable to read \"{}\"", path.display())),
+    }
+}
+
+/// Print cache statistics to stderr.
+pub(crate) fn print_stats(stats: &CacheStats) {
+    let ratio = match stats.hit_ratio() {
+        Some(ratio) => format!(" ({:.1}% hit ratio)", ratio * 100.0),
+        None => String::new(),
+    };
+    eprintln!("Cache hits:     {}{ratio}", stats.hits);
+    eprintln!("Cache misses:   {}", stats.misses);
+    eprintln!("Corrupt loads:  {}", stats.corrupt_loads);
+    eprintln!("Modules stored: {}", stats.stores);
+    eprintln!("Bytes loaded:   {}", ByteSize(stats.bytes_loaded));
+    eprintln!("Bytes stored:   {}", ByteSize(stats.bytes_stored));
+}
+
+fn stats(compiled_dir: &Path) -> Result<()> {
+    let stats = load_stats(compiled_dir)?;
+    print_stats(&stats);
+
+    let modules = filesystem_cache(compiled_dir)?.modules();
+    let size: u64 = modules.iter().map(|m| m.size).sum();
+    eprintln!("Cached modules: {} ({})", modules.len(), ByteSize(size));
+
+    Ok(())
 }
 
 fn clean(cache_dir: &Path) -> Result<()> {
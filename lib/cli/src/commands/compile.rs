@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use anyhow::{Context, Result};
 use clap::Parser;
 use wasmer::*;
+use wasmer_compiler::ArtifactBuild;
 
 use crate::{common::HashAlgorithm, store::StoreOptions, warning};
 
@@ -30,6 +31,11 @@ pub struct Compile {
     /// Hashing algorithm to be used for module hash
     #[clap(long, value_enum)]
     hash_algorithm: Option<HashAlgorithm>,
+
+    /// Embed the original wasm module in the artifact, so it can later be
+    /// converted with `wasmer artifact convert`
+    #[clap(long)]
+    embed_wasm: bool,
 }
 
 impl Compile {
@@ -84,7 +90,16 @@ impl Compile {
         println!("Target: {}", target.triple());
 
         let module = Module::from_file(&store, &self.path)?;
-        module.serialize_to_file(&self.output)?;
+        if self.embed_wasm {
+            let wasm = std::fs::read(&self.path)?;
+            #[cfg(feature = "wat")]
+            let wasm = wat2wasm(&wasm)?.to_vec();
+            let mut serialized = module.serialize()?.to_vec();
+            ArtifactBuild::embed_wasm(&mut serialized, &wasm);
+            std::fs::write(&self.output, serialized)?;
+        } else {
+            module.serialize_to_file(&self.output)?;
+        }
         eprintln!(
             "✔ File compiled successfully to `{}`.",
             self.output.display(),
@@ -2126,7 +2126,7 @@ mod http_fetch {
             .context("Could not retrieve wasmer release history body")?;
 
         if status != reqwest::StatusCode::OK {
-            log::warn!(
+            tracing::warn!(
                 "Warning: Github API replied with non-200 status code: {}. Response: {}",
                 status,
                 String::from_utf8_lossy(&body),
@@ -2246,7 +2246,7 @@ mod http_fetch {
         let download_path = download_tempdir.path().join(&filename);
 
         let mut file = std::fs::File::create(&download_path)?;
-        log::debug!(
+        tracing::debug!(
             "Downloading {} to {}",
             browser_download_url,
             download_path.display()
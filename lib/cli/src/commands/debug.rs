@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use wasmer::*;
+use wasmer_middlewares::breakpoints::{Breakpoint, Debugger, Pause, Resume};
+use wasmer_middlewares::Breakpoints;
+use wasmer_types::entity::EntityRef;
+use wasmer_types::FunctionIndex;
+use wasmer_wasix::{is_wasi_module, WasiEnv, WasiError};
+
+use crate::store::StoreOptions;
+
+const HELP: &str = "\
+Commands:
+  break <function|index|0xoffset>   Set a breakpoint on a function or an offset
+  delete <function|index|0xoffset>  Remove a breakpoint
+  info                              List the breakpoints
+  run                               Start the execution
+  continue                          Resume the execution until the next breakpoint
+  step                              Execute the next operator
+  locals                            Print the locals of the current function
+  globals                           Print the globals of the instance
+  memory <address> [length]         Print the memory at an address
+  quit                              Stop the execution and exit";
+
+#[derive(Debug, Parser)]
+/// The options for the `wasmer debug` subcommand
+pub struct Debug {
+    /// File to debug
+    #[clap(name = "FILE")]
+    path: PathBuf,
+
+    /// Invoke a specified function instead of `_start`
+    #[clap(long, short = 'i')]
+    invoke: Option<String>,
+
+    /// Arguments of the module, or of the invoked function
+    #[clap(name = "ARGS")]
+    args: Vec<String>,
+
+    #[clap(flatten)]
+    store: StoreOptions,
+}
+
+impl Debug {
+    /// Runs logic for the `debug` subcommand
+    pub fn execute(&self) -> Result<()> {
+        self.inner_execute()
+            .context(format!("failed to debug `{}`", self.path.display()))
+    }
+
+    fn inner_execute(&self) -> Result<()> {
+        let (mut store, _compiler_type) = self
+            .store
+            .get_store_with_middleware(Arc::new(Breakpoints::new()))?;
+        let module = Module::from_file(&store, &self.path)?;
+        let functions = Arc::new(FunctionNames::new(&module));
+
+        let runtime = tokio::runtime::Runtime::new()?;
+        let _guard = runtime.enter();
+
+        let instance = if is_wasi_module(&module) {
+            let program_name = self
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut builder = WasiEnv::builder(program_name);
+            if self.invoke.is_none() {
+                builder = builder.args(&self.args);
+            }
+            builder.instantiate(module, &mut store)?.0
+        } else {
+            Instance::new(&mut store, &module, &imports! {})?
+        };
+
+        let names = functions.clone();
+        let debugger = Debugger::attach(&mut store, &instance, move |pause| {
+            println!(
+                "Paused in {} at {:#x}",
+                names.describe(pause.function()),
+                pause.offset()
+            );
+            on_pause(pause, &names)
+        })?;
+
+        println!("Type `help` for the list of commands, `run` to start the execution.");
+        loop {
+            let Some(line) = prompt() else {
+                return Ok(());
+            };
+            let command = match Command::parse(&line, &functions) {
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(err) => {
+                    println!("{err}");
+                    continue;
+                }
+            };
+            match command {
+                Command::Break(breakpoint) => {
+                    debugger.set_breakpoint(&mut store, breakpoint);
+                }
+                Command::Delete(breakpoint) => {
+                    if !debugger.remove_breakpoint(&mut store, breakpoint) {
+                        println!("No such breakpoint");
+                    }
+                }
+                Command::Info => print_breakpoints(&debugger.breakpoints(), &functions),
+                Command::Run => break,
+                Command::Step => {
+                    debugger.step(&mut store);
+                    break;
+                }
+                Command::Help => println!("{HELP}"),
+                Command::Quit => return Ok(()),
+                _ => println!("The program is not running"),
+            }
+        }
+
+        self.run(&mut store, &instance)
+    }
+
+    fn run(&self, store: &mut Store, instance: &Instance) -> Result<()> {
+        let result = match &self.invoke {
+            Some(name) => {
+                let func = instance.exports.get_function(name)?;
+                let params = func.ty(&*store).params().to_vec();
+                if params.len() != self.args.len() {
+                    bail!(
+                        "the function `{name}` takes {} arguments, {} were given",
+                        params.len(),
+                        self.args.len()
+                    );
+                }
+                let args = params
+                    .iter()
+                    .zip(&self.args)
+                    .map(|(ty, arg)| parse_value(*ty, arg))
+                    .collect::<Result<Vec<_>>>()?;
+                func.call(store, &args).map(|results| {
+                    for result in results.iter() {
+                        println!("{}", format_value(result));
+                    }
+                })
+            }
+            None => instance
+                .exports
+                .get_function("_start")?
+                .call(store, &[])
+                .map(|_| ()),
+        };
+
+        match result {
+            Ok(()) => println!("The program finished"),
+            Err(err) => match err.downcast::<WasiError>() {
+                Ok(WasiError::Exit(code)) => println!("The program exited with code {code}"),
+                Ok(err) => bail!(err),
+                Err(err) => bail!(err),
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Runs the commands given during a pause, until one resumes the execution.
+fn on_pause(pause: &mut Pause<'_>, functions: &FunctionNames) -> Resume {
+    loop {
+        let Some(line) = prompt() else {
+            return Resume::Abort;
+        };
+        let command = match Command::parse(&line, functions) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        };
+        match command {
+            Command::Break(breakpoint) => {
+                pause.set_breakpoint(breakpoint);
+            }
+            Command::Delete(breakpoint) => {
+                if !pause.remove_breakpoint(breakpoint) {
+                    println!("No such breakpoint");
+                }
+            }
+            Command::Info => print_breakpoints(&pause.breakpoints(), functions),
+            Command::Locals => {
+                for (index, local) in pause.locals().iter().enumerate() {
+                    match local {
+                        Some(value) => println!("  local {index}: {}", format_value(value)),
+                        None => println!("  local {index}: <unavailable>"),
+                    }
+                }
+            }
+            Command::Globals => {
+                for (index, global) in pause.globals().iter().enumerate() {
+                    println!("  global {index}: {}", format_value(global));
+                }
+            }
+            Command::Memory(address, len) => match pause.read_memory(address, len) {
+                Ok(bytes) => print_memory(address, &bytes),
+                Err(err) => println!("{err}"),
+            },
+            Command::Run => println!("The program is already running"),
+            Command::Continue => return Resume::Continue,
+            Command::Step => return Resume::Step,
+            Command::Help => println!("{HELP}"),
+            Command::Quit => return Resume::Abort,
+        }
+    }
+}
+
+/// A command of the debugger.
+enum Command {
+    Break(Breakpoint),
+    Delete(Breakpoint),
+    Info,
+    Run,
+    Continue,
+    Step,
+    Locals,
+    Globals,
+    Memory(u64, usize),
+    Help,
+    Quit,
+}
+
+impl Command {
+    /// Parses a command, returns `None` for an empty line.
+    fn parse(line: &str, functions: &FunctionNames) -> Result<Option<Self>> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(None);
+        };
+        let args: Vec<&str> = words.collect();
+        let command = match (command, args.as_slice()) {
+            ("break" | "b", [location]) => Self::Break(functions.breakpoint(location)?),
+            ("delete" | "d", [location]) => Self::Delete(functions.breakpoint(location)?),
+            ("info" | "list", []) => Self::Info,
+            ("run" | "r", []) => Self::Run,
+            ("continue" | "c", []) => Self::Continue,
+            ("step" | "s", []) => Self::Step,
+            ("locals", []) => Self::Locals,
+            ("globals", []) => Self::Globals,
+            ("memory" | "x", [address]) => Self::Memory(parse_number(address)?, 16),
+            ("memory" | "x", [address, len]) => {
+                Self::Memory(parse_number(address)?, parse_number(len)? as usize)
+            }
+            ("help" | "h", []) => Self::Help,
+            ("quit" | "q", []) => Self::Quit,
+            _ => bail!("Invalid command `{}`, type `help` for the list of commands", line.trim()),
+        };
+        Ok(Some(command))
+    }
+}
+
+/// The names of the functions of a module, from its name section and its
+/// exports.
+struct FunctionNames {
+    names: HashMap<FunctionIndex, String>,
+}
+
+impl FunctionNames {
+    fn new(module: &Module) -> Self {
+        let info = module.info();
+        let mut names = info.function_names.clone();
+        for (name, index) in &info.exports {
+            if let ExportIndex::Function(index) = index {
+                names.entry(*index).or_insert_with(|| name.clone());
+            }
+        }
+        Self { names }
+    }
+
+    /// Parses the location of a breakpoint: the name or the index of a
+    /// function, or a hexadecimal offset in the module.
+    fn breakpoint(&self, location: &str) -> Result<Breakpoint> {
+        if let Some(offset) = location.strip_prefix("0x") {
+            let offset = u32::from_str_radix(offset, 16)
+                .with_context(|| format!("Invalid offset `{location}`"))?;
+            return Ok(Breakpoint::Offset(offset));
+        }
+        if let Ok(index) = location.parse::<usize>() {
+            return Ok(Breakpoint::Function(FunctionIndex::new(index)));
+        }
+        match self.names.iter().find(|(_, name)| *name == location) {
+            Some((index, _)) => Ok(Breakpoint::Function(*index)),
+            None => bail!("No function named `{location}`"),
+        }
+    }
+
+    fn describe(&self, index: FunctionIndex) -> String {
+        match self.names.get(&index) {
+            Some(name) => format!("function {} `{name}`", index.index()),
+            None => format!("function {}", index.index()),
+        }
+    }
+}
+
+/// Reads a command from the standard input, returns `None` at its end.
+fn prompt() -> Option<String> {
+    print!("(wasmer) ");
+    io::stdout().flush().ok()?;
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+fn print_breakpoints(breakpoints: &[Breakpoint], functions: &FunctionNames) {
+    if breakpoints.is_empty() {
+        println!("No breakpoints");
+    }
+    for breakpoint in breakpoints {
+        match breakpoint {
+            Breakpoint::Function(index) => println!("  {}", functions.describe(*index)),
+            Breakpoint::Offset(offset) => println!("  offset {offset:#x}"),
+        }
+    }
+}
+
+fn print_memory(address: u64, bytes: &[u8]) {
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+        let text: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        println!(
+            "  {:#010x}: {:<47}  {text}",
+            address + line as u64 * 16,
+            hex.join(" ")
+        );
+    }
+}
+
+fn parse_number(number: &str) -> Result<u64> {
+    let parsed = match number.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => number.parse(),
+    };
+    parsed.with_context(|| format!("Invalid number `{number}`"))
+}
+
+fn parse_value(ty: Type, arg: &str) -> Result<Value> {
+    let value = match ty {
+        Type::I32 => Value::I32(arg.parse()?),
+        Type::I64 => Value::I64(arg.parse()?),
+        Type::F32 => Value::F32(arg.parse()?),
+        Type::F64 => Value::F64(arg.parse()?),
+        _ => bail!("can't pass `{arg}` as a {ty:?} argument"),
+    };
+    Ok(value)
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::I32(v) => format!("{v} (i32)"),
+        Value::I64(v) => format!("{v} (i64)"),
+        Value::F32(v) => format!("{v} (f32)"),
+        Value::F64(v) => format!("{v} (f64)"),
+        other => format!("{other:?}"),
+    }
+}
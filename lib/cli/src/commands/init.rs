@@ -377,7 +377,7 @@ fn construct_manifest(
         if !quiet {
             println!("{msg}");
         }
-        log::warn!("{msg}");
+        tracing::warn!("{msg}");
     }
 
     let package_name = package_name.unwrap_or_else(|| {
@@ -435,7 +435,7 @@ fn construct_manifest(
         if !quiet {
             println!("{msg}");
         }
-        log::warn!("{msg}");
+        tracing::warn!("{msg}");
     }
 
     let module_source = cargo_toml
@@ -1,6 +1,8 @@
 //! The commands available in the Wasmer binary.
 mod add;
 mod app;
+#[cfg(feature = "compiler")]
+mod artifact;
 mod auth;
 #[cfg(target_os = "linux")]
 mod binfmt;
@@ -14,6 +16,8 @@ mod container;
 mod create_exe;
 #[cfg(feature = "static-artifact-create")]
 mod create_obj;
+#[cfg(feature = "compiler")]
+mod debug;
 pub(crate) mod domain;
 #[cfg(feature = "static-artifact-create")]
 mod gen_c_header;
@@ -34,6 +38,8 @@ mod wast;
 use std::env::args;
 use tokio::task::JoinHandle;
 
+#[cfg(feature = "compiler")]
+pub use artifact::*;
 #[cfg(target_os = "linux")]
 pub use binfmt::*;
 use clap::{CommandFactory, Parser};
@@ -177,10 +183,14 @@ impl WasmerCmd {
             Some(Cmd::Validate(validate)) => validate.execute(),
             #[cfg(feature = "compiler")]
             Some(Cmd::Compile(compile)) => compile.execute(),
+            #[cfg(feature = "compiler")]
+            Some(Cmd::Artifact(artifact)) => artifact.execute(),
             #[cfg(any(feature = "static-artifact-create", feature = "wasmer-artifact-create"))]
             Some(Cmd::CreateExe(create_exe)) => create_exe.execute(),
             #[cfg(feature = "static-artifact-create")]
             Some(Cmd::CreateObj(create_obj)) => create_obj.execute(),
+            #[cfg(feature = "compiler")]
+            Some(Cmd::Debug(debug)) => debug.execute(),
             Some(Cmd::Config(config)) => config.execute(),
             Some(Cmd::Inspect(inspect)) => inspect.execute(),
             Some(Cmd::Init(init)) => init.execute(),
@@ -304,6 +314,11 @@ enum Cmd {
     #[cfg(feature = "compiler")]
     Compile(Compile),
 
+    /// Manage compiled artifacts
+    #[cfg(feature = "compiler")]
+    #[clap(subcommand)]
+    Artifact(CmdArtifact),
+
     /// Compile a WebAssembly binary into a native executable
     ///
     /// To use, you need to set the `WASMER_DIR` environment variable
@@ -375,6 +390,11 @@ enum Cmd {
     #[cfg(feature = "static-artifact-create")]
     GenCHeader(GenCHeader),
 
+    /// Run a WebAssembly file under an interactive debugger, pausing on
+    /// breakpoints to inspect its locals, globals and memory
+    #[cfg(feature = "compiler")]
+    Debug(debug::Debug),
+
     /// Get various configuration information needed
     /// to compile programs which use Wasmer
     Config(Config),
@@ -305,4 +305,12 @@ impl VirtualNetworking for AskingNetworking {
     ) -> Result<Vec<IpAddr>> {
         call!(self, resolve, host, port, dns_server);
     }
+
+    /// The listener was handed over by the user so there is no need to ask
+    fn adopt_tcp_listener(
+        &self,
+        listener: std::net::TcpListener,
+    ) -> Result<Box<dyn VirtualTcpListener + Sync>> {
+        self.capable.adopt_tcp_listener(listener)
+    }
 }
@@ -1,6 +1,7 @@
 #![allow(missing_docs, unused)]
 
 mod capabilities;
+mod stats;
 mod wasi;
 
 use std::{
@@ -17,11 +18,13 @@ use std::{
 };
 
 use anyhow::{anyhow, bail, Context, Error};
+use bytesize::ByteSize;
 use clap::{Parser, ValueEnum};
 use indicatif::{MultiProgress, ProgressBar};
 use once_cell::sync::Lazy;
 use tempfile::NamedTempFile;
 use url::Url;
+use virtual_fs::WriterFile;
 #[cfg(feature = "sys")]
 use wasmer::NativeEngineExt;
 use wasmer::{
@@ -42,7 +45,7 @@ use wasmer_wasix::{
     runners::{
         dcgi::{DcgiInstanceFactory, DcgiRunner},
         dproxy::DProxyRunner,
-        emscripten::EmscriptenRunner,
+        emscripten::{Ccall, CcallArg, CcallReturn, CcallValue, EmscriptenRunner},
         wasi::WasiRunner,
         wcgi::{self, AbortHandle, NoOpWcgiCallbacks, WcgiRunner},
         MappedCommand, MappedDirectory, Runner,
@@ -51,15 +54,20 @@ use wasmer_wasix::{
         module_cache::CacheError, package_loader::PackageLoader, resolver::QueryError,
         task_manager::VirtualTaskManagerExt,
     },
-    Runtime, WasiError,
+    ResourceUsage, ResourceUsageTracker, Runtime, WasiError, WasiRuntimeError, WasiRuntimeResult,
 };
 use webc::{metadata::Manifest, Container};
 
 use crate::{
-    commands::run::wasi::Wasi, common::HashAlgorithm, error::PrettyError, logging::Output,
+    commands::run::wasi::Wasi,
+    common::{HashAlgorithm, ProfilingStrategy},
+    error::PrettyError,
+    logging::Output,
     store::StoreOptions,
 };
 
+use self::stats::{CompileTime, StatsFormat, StatsRuntime};
+
 const TICK: Duration = Duration::from_millis(250);
 
 /// The unstable `wasmer run` subcommand.
@@ -79,7 +87,14 @@ pub struct Run {
     /// The function or command to invoke.
     #[clap(short, long, aliases = &["command", "invoke", "command-name"])]
     entrypoint: Option<String>,
-    /// Generate a coredump at this path if a WebAssembly trap occurs
+    /// How the value returned by the function given to `--invoke` is read
+    /// when running an Emscripten module, `string` reading it as a pointer
+    /// to a C string
+    #[clap(long = "invoke-returns", value_enum, default_value_t)]
+    invoke_returns: InvokeReturns,
+    /// Generate a coredump at this path if a WebAssembly trap occurs, with
+    /// the call stack and the memories and globals of the instance, which
+    /// can be opened with debuggers like `wasmgdb`
     #[clap(name = "COREDUMP_PATH", long)]
     coredump_on_trap: Option<PathBuf>,
     /// The file, URL, or package to run.
@@ -90,11 +105,57 @@ pub struct Run {
     /// Hashing algorithm to be used for module hash
     #[clap(long, value_enum)]
     hash_algorithm: Option<HashAlgorithm>,
+    /// Print statistics about the cache of compiled modules once the program
+    /// exits, and add them to the totals shown by `wasmer cache stats`
+    #[clap(long)]
+    cache_stats: bool,
+    /// Report the compiled functions to the `perf` profiler, with their
+    /// names from the `name` section
+    #[clap(long, value_enum, value_delimiter = ',')]
+    profiling: Vec<ProfilingStrategy>,
+    /// Print execution statistics once the program exits: the time spent
+    /// compiling, instantiating and executing it, the instructions executed
+    /// when metered, the host calls, and the growth of the memory
+    #[clap(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text"
+    )]
+    stats: Option<StatsFormat>,
+    /// The time spent loading modules, accounted for `--stats`
+    #[clap(skip)]
+    compile_time: CompileTime,
 }
 
 impl Run {
-    pub fn execute(self, output: Output) -> ! {
+    pub fn execute(mut self, output: Output) -> ! {
+        let print_usage = self.wasi.print_resource_usage;
+        let print_memory = self.wasi.print_memory_usage;
+        let stats_format = self.stats;
+        let compile_time = self.compile_time.clone();
+        let tracker = (print_usage || print_memory || stats_format.is_some())
+            .then(ResourceUsageTracker::new);
+        self.wasi.resource_usage = tracker.clone();
+
         let result = self.execute_inner(output);
+        if let Some(tracker) = tracker {
+            let usage = tracker.usage();
+            if print_usage {
+                print_resource_usage(&usage);
+            }
+            #[cfg(feature = "sys")]
+            if print_memory {
+                match tracker.memory_usage() {
+                    Some(memory_usage) => print_memory_usage(&memory_usage),
+                    None => eprintln!("memory usage: not available"),
+                }
+            }
+            if let Some(format) = stats_format {
+                stats::print_stats(format, compile_time.get(), &usage);
+            }
+        }
         exit_with_wasi_exit_code(result);
     }
 
@@ -135,6 +196,15 @@ impl Run {
             let mut engine = store.engine().clone();
             let hash_algorithm = self.hash_algorithm.unwrap_or_default().into();
             engine.set_hash_algorithm(Some(hash_algorithm));
+            let strategies = self
+                .profiling
+                .iter()
+                .map(|strategy| (*strategy).into())
+                .collect::<Vec<_>>();
+            engine
+                .set_profiling(&strategies)
+                .context("Unable to set up the profiling")?;
+            engine.set_coredump_on_trap(self.coredump_on_trap.is_some());
 
             engine
         };
@@ -148,6 +218,7 @@ impl Run {
             runtime,
             preferred_webc_version,
         )?;
+        let runtime = StatsRuntime::new(runtime, self.compile_time.clone());
 
         // This is a slow operation, so let's temporarily wrap the runtime with
         // something that displays progress
@@ -163,6 +234,8 @@ impl Run {
                 .extend(pkg.additional_host_mapped_directories.clone());
         }
 
+        self.wasi.stage_mapped_directories()?;
+
         pb.finish_and_clear();
 
         // push the TTY state so we can restore it after the program finishes
@@ -186,6 +259,26 @@ impl Run {
             }
         }
 
+        if self.cache_stats {
+            let stats = runtime.module_cache().stats();
+            if let Err(e) =
+                crate::commands::record_stats(&crate::commands::compiled_dir(&self.env), stats)
+            {
+                tracing::debug!("Unable to record the cache statistics: {e:?}");
+            }
+            crate::commands::print_stats(&stats);
+        }
+
+        // The staged modifications are dealt with even if the program failed
+        let result = match self.wasi.finish_staged_writes() {
+            Err(e) if result.is_ok() => Err(e),
+            Err(e) => {
+                tracing::error!("Unable to finish the staged writes: {e:?}");
+                result
+            }
+            Ok(()) => result,
+        };
+
         if let Err(e) = &result {
             self.maybe_save_coredump(e);
         }
@@ -202,8 +295,15 @@ impl Run {
         mut store: Store,
         runtime: Arc<dyn Runtime + Send + Sync>,
     ) -> Result<(), Error> {
+        if wasmer_wasix::is_wasi_preview2_module(module) {
+            bail!(
+                "\"{}\" imports WASI preview2 interfaces, which need the component model (WASI preview2 is not supported yet)",
+                path.display()
+            );
+        }
+
         if wasmer_emscripten::is_emscripten_module(module) {
-            self.execute_emscripten_module()
+            self.execute_emscripten_module(path, module, runtime, store)
         } else if wasmer_wasix::is_wasi_module(module) || wasmer_wasix::is_wasix_module(module) {
             self.execute_wasi_module(path, module, module_hash, runtime, store)
         } else {
@@ -303,6 +403,7 @@ impl Run {
             .args(self.args.clone())
             .addr(self.wcgi.addr)
             .envs(self.wasi.env_vars.clone())
+            .fs_policy(self.wasi.fs_policy)
             .map_directories(self.wasi.mapped_dirs.clone())
             .callbacks(Callbacks::new(self.wcgi.addr))
             .inject_packages(uses);
@@ -367,6 +468,8 @@ impl Run {
     ) -> Result<(), Error> {
         let mut runner = wasmer_wasix::runners::emscripten::EmscriptenRunner::new();
         runner.set_args(self.args.clone());
+        runner.set_mounts(self.wasi.build_emscripten_mounts()?);
+        runner.set_stdio(self.wasi.build_emscripten_stdio()?);
 
         runner.run_command(command_name, pkg, runtime)
     }
@@ -411,10 +514,15 @@ impl Run {
 
         let mut runner = WasiRunner::new();
 
-        let (is_home_mapped, is_tmp_mapped, mapped_diretories) =
+        let (is_home_mapped, is_tmp_mapped, mut mapped_diretories) =
             self.wasi.build_mapped_directories()?;
+        if !self.wasi.staged_dirs.is_empty() {
+            // They get mounted by build_mounts() instead
+            mapped_diretories.clear();
+        }
 
         runner
+            .with_fs_policy(self.wasi.fs_policy)
             .with_args(&self.args)
             .with_injected_packages(packages)
             .with_envs(self.wasi.env_vars.clone())
@@ -423,8 +531,42 @@ impl Run {
             .with_home_mapped(is_home_mapped)
             .with_tmp_mapped(is_tmp_mapped)
             .with_forward_host_env(self.wasi.forward_host_env)
+            .with_mounted_directories(self.wasi.build_mounts()?)
+            .with_listeners(self.wasi.build_listeners()?)
             .with_capabilities(self.wasi.capabilities());
 
+        for (guest, rights) in self.wasi.build_preopen_rights() {
+            runner.with_preopen_rights(guest, rights);
+        }
+        for guest in &self.wasi.case_insensitive_dirs {
+            runner.with_case_insensitive_dir(guest);
+        }
+        if let Some(clock) = self.wasi.clock {
+            runner.with_clock(clock);
+        }
+        if let Some(seed) = self.wasi.random_seed {
+            runner.with_random_seed(seed);
+        }
+        if let Some(user) = self.wasi.user_identity() {
+            runner.with_user(user);
+        }
+        if let Some(tracker) = &self.wasi.resource_usage {
+            runner.with_resource_usage(tracker.clone());
+        }
+        if let Some(tracer) = self.wasi.syscall_tracer() {
+            runner.with_syscall_tracer(tracer);
+        }
+        runner.with_proc_fs(self.wasi.proc_fs);
+        if let Some(stdout) = self.wasi.open_stdout()? {
+            runner.with_stdout(Box::new(WriterFile::new(stdout)));
+        }
+        if let Some(stderr) = self.wasi.open_stderr()? {
+            runner.with_stderr(Box::new(WriterFile::new(stderr)));
+        }
+        if let Some(secs) = self.wasi.forward_signals {
+            runner.with_signal_forwarding(Duration::from_secs(secs));
+        }
+
         #[cfg(feature = "journal")]
         {
             for trigger in self.wasi.snapshot_on.iter().cloned() {
@@ -471,8 +613,68 @@ impl Run {
     }
 
     #[tracing::instrument(skip_all)]
-    fn execute_emscripten_module(&self) -> Result<(), Error> {
-        bail!("Emscripten packages are not currently supported")
+    fn execute_emscripten_module(
+        &self,
+        wasm_path: &Path,
+        module: &Module,
+        runtime: Arc<dyn Runtime + Send + Sync>,
+        mut store: Store,
+    ) -> Result<(), Error> {
+        let program_name = wasm_path.display().to_string();
+
+        // The standard streams of the WASI environment are redirected along
+        // with the Emscripten ones
+        let stdio = self.wasi.build_emscripten_stdio()?;
+        let mounts = self.wasi.build_emscripten_mounts()?;
+
+        // Modules mixing Emscripten and WASI imports get their WASI functions
+        // from the usual WASI environment
+        let wasi = if wasmer_wasix::runners::emscripten::needs_wasi_env(module) {
+            let builder =
+                self.wasi
+                    .prepare(module, program_name.clone(), self.args.clone(), runtime)?;
+            Some(builder)
+        } else {
+            None
+        };
+
+        let Some(entrypoint) = &self.entrypoint else {
+            return wasmer_wasix::runners::emscripten::run_emscripten_module(
+                &mut store,
+                module,
+                &program_name,
+                self.args.clone(),
+                &mounts,
+                stdio,
+                wasi,
+            );
+        };
+
+        let call = ccall_from_args(module, entrypoint, &self.args, self.invoke_returns)?;
+        let value = wasmer_wasix::runners::emscripten::invoke_emscripten_module(
+            &mut store,
+            module,
+            &program_name,
+            &call,
+            &mounts,
+            stdio,
+            wasi,
+        )?;
+
+        match value {
+            CcallValue::Number(values) => println!(
+                "{}",
+                values
+                    .iter()
+                    .map(|val| val.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            CcallValue::String(Some(s)) => println!("{s}"),
+            CcallValue::String(None) => println!("(null)"),
+        }
+
+        Ok(())
     }
 
     #[allow(unused_variables)]
@@ -519,10 +721,15 @@ impl Run {
             wcgi: WcgiOptions::default(),
             stack_size: None,
             entrypoint: Some(original_executable.to_string()),
+            invoke_returns: InvokeReturns::default(),
             coredump_on_trap: None,
             input: PackageSource::infer(executable)?,
             args: args.to_vec(),
             hash_algorithm: None,
+            cache_stats: false,
+            profiling: vec![],
+            stats: None,
+            compile_time: CompileTime::default(),
         })
     }
 }
@@ -570,6 +777,52 @@ fn parse_value(s: &str, ty: wasmer_types::Type) -> Result<Value, Error> {
     Ok(value)
 }
 
+/// How the value returned by an Emscripten function is read.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InvokeReturns {
+    #[default]
+    Number,
+    String,
+}
+
+/// Builds a `ccall` of an Emscripten function, arguments of an `i32`
+/// parameter which aren't numbers being passed as strings.
+fn ccall_from_args(
+    module: &Module,
+    function: &str,
+    args: &[String],
+    returns: InvokeReturns,
+) -> Result<Ccall, Error> {
+    let func_ty = module
+        .exports()
+        .functions()
+        .find(|export| export.name() == function || export.name() == format!("_{function}"))
+        .map(|export| export.ty().clone())
+        .with_context(|| format!("The module doesn't contain a \"{function}\" function"))?;
+
+    anyhow::ensure!(
+        func_ty.params().len() == args.len(),
+        "Function expected {} arguments, but received {}",
+        func_ty.params().len(),
+        args.len(),
+    );
+
+    let mut call = Ccall::new(function).returns(match returns {
+        InvokeReturns::Number => CcallReturn::Number,
+        InvokeReturns::String => CcallReturn::String,
+    });
+    for (arg, param_type) in args.iter().zip(func_ty.params()) {
+        let arg = match parse_value(arg, *param_type) {
+            Ok(value) => CcallArg::Number(value),
+            Err(_) if *param_type == Type::I32 => CcallArg::String(arg.clone()),
+            Err(e) => return Err(e.context(format!("Unable to convert {arg:?} to {param_type:?}"))),
+        };
+        call = call.arg(arg);
+    }
+
+    Ok(call)
+}
+
 fn infer_webc_entrypoint(pkg: &BinaryPackage) -> Result<&str, Error> {
     if let Some(entrypoint) = pkg.entrypoint_cmd.as_deref() {
         return Ok(entrypoint);
@@ -744,6 +997,12 @@ impl ExecutableTarget {
         match TargetOnDisk::from_file(path)? {
             TargetOnDisk::WebAssemblyBinary | TargetOnDisk::Wat => {
                 let wasm = std::fs::read(path)?;
+                if wasmer_wasix::is_component(&wasm) {
+                    bail!(
+                        "\"{}\" is a WebAssembly component, which needs the component model (WASI preview2 is not supported yet)",
+                        path.display()
+                    );
+                }
 
                 pb.set_message("Compiling to WebAssembly");
                 let module = runtime
@@ -790,30 +1049,38 @@ fn generate_coredump(err: &Error, source_name: String, coredump_path: &Path) ->
     let err: &wasmer::RuntimeError = match err.downcast_ref() {
         Some(e) => e,
         None => {
-            log::warn!("no runtime error found to generate coredump with");
+            tracing::warn!("no runtime error found to generate coredump with");
             return Ok(());
         }
     };
 
-    let mut coredump_builder =
-        wasm_coredump_builder::CoredumpBuilder::new().executable_name(&source_name);
-
-    let mut thread_builder = wasm_coredump_builder::ThreadBuilder::new().thread_name("main");
-
-    for frame in err.trace() {
-        let coredump_frame = wasm_coredump_builder::FrameBuilder::new()
-            .codeoffset(frame.func_offset() as u32)
-            .funcidx(frame.func_index())
-            .build();
-        thread_builder.add_frame(coredump_frame);
-    }
+    let coredump = match captured_coredump(err, &source_name) {
+        Some(coredump) => coredump,
+        // Errors which didn't come from a WebAssembly trap only have a call
+        // stack.
+        None => {
+            let mut coredump_builder =
+                wasm_coredump_builder::CoredumpBuilder::new().executable_name(&source_name);
+
+            let mut thread_builder =
+                wasm_coredump_builder::ThreadBuilder::new().thread_name("main");
+
+            for frame in err.trace() {
+                let coredump_frame = wasm_coredump_builder::FrameBuilder::new()
+                    .codeoffset(frame.func_offset() as u32)
+                    .funcidx(frame.func_index())
+                    .build();
+                thread_builder.add_frame(coredump_frame);
+            }
 
-    coredump_builder.add_thread(thread_builder.build());
+            coredump_builder.add_thread(thread_builder.build());
 
-    let coredump = coredump_builder
-        .serialize()
-        .map_err(Error::msg)
-        .context("Coredump serializing failed")?;
+            coredump_builder
+                .serialize()
+                .map_err(Error::msg)
+                .context("Coredump serializing failed")?
+        }
+    };
 
     std::fs::write(coredump_path, &coredump).with_context(|| {
         format!(
@@ -825,6 +1092,21 @@ fn generate_coredump(err: &Error, source_name: String, coredump_path: &Path) ->
     Ok(())
 }
 
+/// The serialized coredump captured by the runtime when the trap happened,
+/// with the memories and globals of the instance.
+#[cfg(feature = "coredump")]
+fn captured_coredump(err: &wasmer::RuntimeError, source_name: &str) -> Option<Vec<u8>> {
+    #[cfg(feature = "sys")]
+    if let Some(coredump) = err.coredump() {
+        let mut coredump = coredump.clone();
+        coredump.set_executable_name(source_name);
+        return Some(coredump.serialize());
+    }
+    #[cfg(not(feature = "sys"))]
+    let _ = (err, source_name);
+    None
+}
+
 #[derive(Debug, Clone, Parser)]
 pub(crate) struct WcgiOptions {
     /// The address to serve on.
@@ -871,21 +1153,73 @@ impl wasmer_wasix::runners::wcgi::Callbacks for Callbacks {
     }
 }
 
+fn print_resource_usage(usage: &ResourceUsage) {
+    eprintln!("resource usage:");
+    eprintln!("  cpu time:      {:?}", usage.cpu_time);
+    eprintln!("  syscalls:      {}", usage.syscalls);
+    eprintln!("  bytes read:    {}", usage.bytes_read);
+    eprintln!("  bytes written: {}", usage.bytes_written);
+}
+
+#[cfg(feature = "sys")]
+fn print_memory_usage(usage: &wasmer::MemoryUsage) {
+    let pages = |pages: wasmer::Pages| ByteSize(pages.bytes().0 as u64);
+    eprintln!("memory usage:");
+    for (index, memory) in usage.memories.iter().enumerate() {
+        let maximum = match memory.maximum {
+            Some(maximum) => pages(maximum).to_string(),
+            None => "none".to_string(),
+        };
+        eprintln!(
+            "  memory {index}:     {} (peak {}, initial {}, maximum {maximum})",
+            pages(memory.current),
+            pages(memory.peak),
+            pages(memory.minimum),
+        );
+    }
+    for (index, table) in usage.tables.iter().enumerate() {
+        let maximum = match table.maximum {
+            Some(maximum) => maximum.to_string(),
+            None => "none".to_string(),
+        };
+        eprintln!(
+            "  table {index}:      {} elements (initial {}, maximum {maximum})",
+            table.current, table.minimum
+        );
+    }
+    eprintln!("  data segments: {}", ByteSize(usage.data_segments_size));
+    match &usage.stack {
+        Some(stack) => eprintln!(
+            "  stack:         {} (peak {}, size {})",
+            ByteSize(stack.current),
+            ByteSize(stack.peak),
+            ByteSize(stack.size),
+        ),
+        None => eprintln!("  stack:         n/a (no exported __stack_pointer)"),
+    }
+}
+
 /// Exit the current process, using the WASI exit code if the error contains
 /// one.
+///
+/// Guests that were terminated by a signal or trapped exit with
+/// `128 + <signal>` (`SIGABRT` for traps), like a native program would.
 fn exit_with_wasi_exit_code(result: Result<(), Error>) -> ! {
     let exit_code = match result {
         Ok(_) => 0,
-        Err(error) => {
-            match error.chain().find_map(get_exit_code) {
-                Some(exit_code) => exit_code.raw(),
-                None => {
+        Err(error) => match error.chain().find_map(get_runtime_result) {
+            Some(result) => {
+                if result.trap.is_some() {
                     eprintln!("{:?}", PrettyError::new(error));
-                    // Something else happened
-                    1
                 }
+                result.host_exit_code()
             }
-        }
+            None => {
+                eprintln!("{:?}", PrettyError::new(error));
+                // Something else happened
+                1
+            }
+        },
     };
 
     std::io::stdout().flush().ok();
@@ -894,14 +1228,19 @@ fn exit_with_wasi_exit_code(result: Result<(), Error>) -> ! {
     std::process::exit(exit_code);
 }
 
-fn get_exit_code(
-    error: &(dyn std::error::Error + 'static),
-) -> Option<wasmer_wasix::types::wasi::ExitCode> {
+fn get_runtime_result(error: &(dyn std::error::Error + 'static)) -> Option<WasiRuntimeResult> {
     if let Some(WasiError::Exit(exit_code)) = error.downcast_ref() {
-        return Some(*exit_code);
+        return Some(WasiRuntimeResult {
+            exit_code: *exit_code,
+            signal: None,
+            trap: None,
+        });
     }
-    if let Some(error) = error.downcast_ref::<wasmer_wasix::WasiRuntimeError>() {
-        return error.as_exit_code();
+    if let Some(error) = error.downcast_ref::<WasiRuntimeError>() {
+        return WasiRuntimeResult::from_error(error);
+    }
+    if let Some(error) = error.downcast_ref::<wasmer::RuntimeError>() {
+        return WasiRuntimeResult::from_error(&WasiRuntimeError::Runtime(error.clone()));
     }
 
     None
@@ -970,6 +1309,13 @@ impl<R: wasmer_wasix::Runtime + Send + Sync> wasmer_wasix::Runtime for Monitorin
     fn tty(&self) -> Option<&(dyn wasmer_wasix::os::TtyBridge + Send + Sync)> {
         self.runtime.tty()
     }
+
+    fn load_module<'a>(
+        &'a self,
+        wasm: &'a [u8],
+    ) -> futures::future::BoxFuture<'a, Result<Module, wasmer_wasix::SpawnError>> {
+        self.runtime.load_module(wasm)
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,160 @@
+//! The execution statistics printed by `wasmer run --stats`.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use bytesize::ByteSize;
+use clap::ValueEnum;
+use futures::future::BoxFuture;
+use wasmer::{Module, WASM_PAGE_SIZE};
+use wasmer_wasix::{runtime::TaintReason, ResourceUsage, Runtime, SpawnError};
+
+/// How the execution statistics are printed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum StatsFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// The time spent compiling modules, or loading them from the cache,
+/// accumulated by [`StatsRuntime`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CompileTime(Arc<AtomicU64>);
+
+impl CompileTime {
+    pub(crate) fn get(&self) -> Duration {
+        Duration::from_nanos(self.0.load(Ordering::Relaxed))
+    }
+
+    fn add(&self, elapsed: Duration) {
+        self.0.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// A runtime measuring the time spent loading modules, including the ones
+/// of the subprocesses spawned by the program.
+#[derive(Debug)]
+pub(crate) struct StatsRuntime<R> {
+    inner: R,
+    compile_time: CompileTime,
+}
+
+impl<R> StatsRuntime<R> {
+    pub(crate) fn new(inner: R, compile_time: CompileTime) -> Self {
+        StatsRuntime {
+            inner,
+            compile_time,
+        }
+    }
+}
+
+impl<R: Runtime + Send + Sync> Runtime for StatsRuntime<R> {
+    fn networking(&self) -> &virtual_net::DynVirtualNetworking {
+        self.inner.networking()
+    }
+
+    fn task_manager(&self) -> &Arc<dyn wasmer_wasix::VirtualTaskManager> {
+        self.inner.task_manager()
+    }
+
+    fn package_loader(
+        &self,
+    ) -> Arc<dyn wasmer_wasix::runtime::package_loader::PackageLoader + Send + Sync> {
+        self.inner.package_loader()
+    }
+
+    fn module_cache(
+        &self,
+    ) -> Arc<dyn wasmer_wasix::runtime::module_cache::ModuleCache + Send + Sync> {
+        self.inner.module_cache()
+    }
+
+    fn source(&self) -> Arc<dyn wasmer_wasix::runtime::resolver::Source + Send + Sync> {
+        self.inner.source()
+    }
+
+    fn engine(&self) -> wasmer::Engine {
+        self.inner.engine()
+    }
+
+    fn new_store(&self) -> wasmer::Store {
+        self.inner.new_store()
+    }
+
+    fn http_client(&self) -> Option<&wasmer_wasix::http::DynHttpClient> {
+        self.inner.http_client()
+    }
+
+    fn tty(&self) -> Option<&(dyn wasmer_wasix::os::TtyBridge + Send + Sync)> {
+        self.inner.tty()
+    }
+
+    fn load_module<'a>(&'a self, wasm: &'a [u8]) -> BoxFuture<'a, Result<Module, SpawnError>> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = self.inner.load_module(wasm).await;
+            self.compile_time.add(started.elapsed());
+            result
+        })
+    }
+
+    fn on_taint(&self, reason: TaintReason) {
+        self.inner.on_taint(reason)
+    }
+
+    #[cfg(feature = "journal")]
+    fn journals(&self) -> &'_ Vec<Arc<wasmer_wasix::journal::DynJournal>> {
+        self.inner.journals()
+    }
+
+    #[cfg(feature = "journal")]
+    fn active_journal(&self) -> Option<&'_ wasmer_wasix::journal::DynJournal> {
+        self.inner.active_journal()
+    }
+}
+
+/// Print the execution statistics to stderr.
+pub(crate) fn print_stats(format: StatsFormat, compile_time: Duration, usage: &ResourceUsage) {
+    let peak_memory = usage.peak_memory_pages as u64 * WASM_PAGE_SIZE as u64;
+    match format {
+        StatsFormat::Text => {
+            let instructions = match usage.instructions {
+                Some(instructions) => instructions.to_string(),
+                None => "n/a (not metered)".to_string(),
+            };
+            eprintln!("execution statistics:");
+            eprintln!("  compile time:     {compile_time:?}");
+            eprintln!("  instantiate time: {:?}", usage.instantiation_time);
+            eprintln!("  execute time:     {:?}", usage.execution_time);
+            eprintln!("  cpu time:         {:?}", usage.cpu_time);
+            eprintln!("  instructions:     {instructions}");
+            eprintln!("  host calls:       {}", usage.syscalls);
+            eprintln!("  memory grows:     {}", usage.memory_grows);
+            eprintln!(
+                "  peak memory:      {} pages ({})",
+                usage.peak_memory_pages,
+                ByteSize(peak_memory)
+            );
+        }
+        StatsFormat::Json => {
+            let stats = serde_json::json!({
+                "compile_time_us": compile_time.as_micros() as u64,
+                "instantiate_time_us": usage.instantiation_time.as_micros() as u64,
+                "execute_time_us": usage.execution_time.as_micros() as u64,
+                "cpu_time_us": usage.cpu_time.as_micros() as u64,
+                "instructions": usage.instructions,
+                "host_calls": usage.syscalls,
+                "memory_grows": usage.memory_grows,
+                "peak_memory_pages": usage.peak_memory_pages,
+                "peak_memory_bytes": peak_memory,
+            });
+            eprintln!("{stats}");
+        }
+    }
+}
@@ -9,9 +9,14 @@ use std::{
 use anyhow::{bail, Context, Result};
 use bytes::Bytes;
 use clap::Parser;
+use dialoguer::theme::ColorfulTheme;
+use is_terminal::IsTerminal;
 use tokio::runtime::Handle;
 use url::Url;
-use virtual_fs::{DeviceFile, FileSystem, PassthruFileSystem, RootFileSystemBuilder};
+use virtual_fs::{
+    DeviceFile, FileSystem, PassthruFileSystem, RootFileSystemBuilder, StagedChange,
+    StagedFileSystem,
+};
 use wasmer::{Engine, Function, Instance, Memory32, Memory64, Module, RuntimeError, Store, Value};
 use wasmer_config::package::PackageSource as PackageSpecifier;
 use wasmer_registry::wasmer_env::WasmerEnv;
@@ -27,7 +32,10 @@ use wasmer_wasix::{
     os::{tty_sys::SysTty, TtyBridge},
     rewind_ext,
     runners::MAPPED_CURRENT_DIR_DEFAULT_PATH,
-    runners::{MappedCommand, MappedDirectory},
+    runners::{
+        emscripten::{EmscriptenMount, EmscriptenStdio},
+        FsPolicy, MappedCommand, MappedDirectory, MountedDirectory,
+    },
     runtime::{
         module_cache::{FileSystemCache, ModuleCache},
         package_loader::{BuiltinPackageLoader, PackageLoader},
@@ -40,12 +48,15 @@ use wasmer_wasix::{
         },
     },
     types::__WASI_STDIN_FILENO,
-    wasmer_wasix_types::wasi::Errno,
-    PluggableRuntime, RewindState, Runtime, WasiEnv, WasiEnvBuilder, WasiError, WasiFunctionEnv,
-    WasiVersion,
+    wasmer_wasix_types::wasi::{Errno, Rights},
+    ClockSource, PluggableRuntime, ResourceUsageTracker, RewindState, Runtime, SyscallTracer,
+    UserIdentity, WasiEnv, WasiEnvBuilder, WasiError, WasiFunctionEnv, WasiVersion,
 };
 
-use crate::utils::{parse_envvar, parse_mapdir};
+use crate::utils::{
+    parse_dir, parse_envvar, parse_mapdir, parse_mount_archive, parse_overlay, OverlayMount,
+    PreopenedDirectory,
+};
 
 use super::{
     capabilities::{self, PkgCapabilityCache},
@@ -58,8 +69,20 @@ const WAPM_SOURCE_CACHE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 /// WASI Options
 pub struct Wasi {
     /// WASI pre-opened directory
-    #[clap(long = "dir", name = "DIR", group = "wasi")]
-    pub(crate) pre_opened_directories: Vec<PathBuf>,
+    ///
+    /// Append `:rights=<right>,...` to grant the directory only some rights,
+    /// e.g. `--dir /logs:rights=append` or `--dir /data:rights=read,seek,tell`.
+    /// Rights are either groups (`read`, `write`, `create`, `append` and
+    /// `list`) or individual WASI rights like `path_create_file`.
+    #[clap(long = "dir", name = "DIR", group = "wasi", value_parser = parse_dir)]
+    pub(crate) pre_opened_directories: Vec<PreopenedDirectory>,
+
+    /// Resolve the paths inside of a mapped or pre-opened directory
+    /// case-insensitively, for programs written against a Windows file
+    /// system, e.g. `--case-insensitive=/data` (the path is the one seen by
+    /// the guest)
+    #[clap(long = "case-insensitive", name = "GUEST_DIR")]
+    pub case_insensitive_dirs: Vec<String>,
 
     /// Map a host directory to a different location for the Wasm module
     #[clap(
@@ -69,6 +92,26 @@ pub struct Wasi {
     )]
     pub(crate) mapped_dirs: Vec<MappedDirectory>,
 
+    /// Mount a tar, tar.gz or zip archive as a read-only directory
+    #[clap(
+        long = "mount-archive",
+        name = "ARCHIVE:GUEST_DIR",
+        value_parser=parse_mount_archive,
+    )]
+    pub(crate) mounted_archives: Vec<MappedDirectory>,
+
+    /// Layer read-only host directories under a writable one at a guest path
+    ///
+    /// The last directory is the writable upper layer, changes to files from
+    /// the lower layers are copied up to it. Leave the upper layer empty
+    /// (e.g. `/data:./base:`) to keep the changes in memory.
+    #[clap(
+        long = "overlay",
+        name = "GUEST_DIR:LOWER_DIR:UPPER_DIR",
+        value_parser=parse_overlay,
+    )]
+    pub(crate) overlays: Vec<OverlayMount>,
+
     /// Pass custom environment variables
     #[clap(
         long = "env",
@@ -94,11 +137,112 @@ pub struct Wasi {
     #[clap(long = "map-command", name = "MAPCMD")]
     pub(super) map_commands: Vec<String>,
 
-    /// Enable networking with the host network.
+    /// Enable networking with the host network (`--net` or `--net=on`), or
+    /// disable it without asking (`--net=off`).
+    ///
+    /// Allows WASI modules to open TCP and UDP connections, create sockets,
+    /// ... When not given, the user is asked the first time the package
+    /// uses the network.
+    #[clap(
+        long = "net",
+        name = "ON_OFF",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "on",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    pub networking: Option<bool>,
+
+    /// Only allow networking with the given addresses (implies `--net`).
     ///
-    /// Allows WASI modules to open TCP and UDP connections, create sockets, ...
-    #[clap(long = "net")]
-    pub networking: bool,
+    /// Each rule is an IP address, CIDR range or host name with an optional
+    /// port, for example `10.0.0.0/8`, `1.1.1.1:53`, `[::1]:8080`,
+    /// `api.example.com:443` or `*.example.com`. Host names cover the
+    /// addresses they resolve to when the guest looks them up. The option
+    /// can be repeated.
+    #[clap(long = "net-allow", visible_alias = "allow-net", name = "NET_RULE")]
+    pub net_allow: Vec<virtual_net::NetworkRule>,
+
+    /// Deny networking with the given addresses (implies `--net`).
+    ///
+    /// Takes the same rules as `--net-allow` and takes precedence over
+    /// them. Denying a host name is best-effort, as the guest can reach its
+    /// addresses without looking it up, use IP rules to reliably deny a
+    /// host. The option can be repeated.
+    #[clap(long = "net-deny", visible_alias = "deny-net", name = "NET_DENY_RULE")]
+    pub net_deny: Vec<virtual_net::NetworkRule>,
+
+    /// Listen on the given address and hand the socket to the guest (for
+    /// example `0.0.0.0:8080`).
+    ///
+    /// The guest can accept connections on it without being allowed to bind
+    /// sockets itself. Sockets passed through systemd socket activation
+    /// (`LISTEN_FDS`) are handed over the same way. The option can be
+    /// repeated.
+    #[clap(long = "listen", name = "LISTEN_ADDR")]
+    pub listen: Vec<std::net::SocketAddr>,
+
+    /// The clock reported to the guest.
+    ///
+    /// Either `system`, `virtual` (starts at the Unix epoch and advances by
+    /// one millisecond every time it is read) or `fixed:<timestamp>` where
+    /// the timestamp is in seconds since the Unix epoch.
+    #[clap(long = "clock", name = "CLOCK")]
+    pub clock: Option<ClockSource>,
+
+    /// Seed the random numbers handed to the guest so that runs are
+    /// reproducible.
+    #[clap(long = "random-seed", name = "SEED")]
+    pub random_seed: Option<u64>,
+
+    /// The user the guest appears to run as, in the form
+    /// `<name>[:<uid>[:<gid>]]`.
+    ///
+    /// Sets `HOME`, `USER` and `LOGNAME` (unless they are set with `--env`)
+    /// and adds the user to `/etc/passwd` and `/etc/group`.
+    #[clap(long = "user", name = "USER")]
+    pub user: Option<UserIdentity>,
+
+    /// The home directory of the user given with `--user`, defaults to
+    /// `/home/<name>`.
+    #[clap(long = "home", name = "HOME", requires = "USER")]
+    pub home: Option<String>,
+
+    /// How symlinks inside of mapped directories are resolved.
+    ///
+    /// Either `permissive` (the default, symlinks are followed wherever they
+    /// point to) or `strict`, which refuses to resolve any path to something
+    /// outside of the mapped directory. On Linux 5.6 and later the checks of
+    /// `strict` are done by the kernel and can't be raced, elsewhere paths are
+    /// resolved before being handed to the host.
+    #[clap(long = "fs-policy", name = "POLICY", default_value = "permissive")]
+    pub fs_policy: FsPolicy,
+
+    /// Keep the modifications made to mapped directories in memory instead
+    /// of writing them to the host.
+    ///
+    /// Once the program exits, the modifications are either applied
+    /// (`commit`), thrown away (`discard`) or listed so you can decide what
+    /// to do with them (`ask`).
+    #[clap(long = "stage-writes", name = "MODE")]
+    pub stage_writes: Option<StageWrites>,
+
+    /// The directories staged by `--stage-writes`.
+    #[clap(skip)]
+    pub(crate) staged_dirs: Vec<StagedDirectory>,
+
+    /// Forwards SIGINT and SIGTERM to the guest so it can shut down
+    /// cleanly, terminating it if it hasn't exited after the grace period
+    /// (measured in seconds, 10 if no value is given) or when the signal is
+    /// sent again
+    #[clap(
+        long = "forward-signals",
+        name = "GRACE_PERIOD",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "10"
+    )]
+    pub forward_signals: Option<u64>,
 
     /// Disables the TTY bridge
     #[clap(long = "no-tty")]
@@ -115,6 +259,70 @@ pub struct Wasi {
     #[clap(long = "enable-cpu-backoff")]
     pub enable_cpu_backoff: Option<u64>,
 
+    /// Busy-waits for the last part of every sleep (measured in
+    /// micro-seconds, e.g. `--precise-timers=500`, or 2000 if no value is
+    /// given) so that guests wake up on time, at the expense of CPU usage
+    /// (default = off)
+    #[clap(
+        long = "precise-timers",
+        name = "MICROS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "2000"
+    )]
+    pub precise_timers: Option<u64>,
+
+    /// Prints the CPU time, number of syscalls and bytes read and written
+    /// by the guest to stderr when it exits
+    #[clap(long = "print-resource-usage")]
+    pub print_resource_usage: bool,
+
+    /// Prints the memory used by the guest to stderr when it exits: the
+    /// current and largest sizes of its memories, the sizes of its tables,
+    /// and the space used by its data segments and its stack
+    #[clap(long = "print-memory-usage")]
+    pub print_memory_usage: bool,
+
+    /// Where the resource usage of the guest is accounted, set when
+    /// `--print-resource-usage`, `--print-memory-usage` or `--stats` is
+    /// given
+    #[clap(skip)]
+    pub resource_usage: Option<ResourceUsageTracker>,
+
+    /// Prints the syscalls made by the guest to stderr with their decoded
+    /// arguments and results, only those whose name matches one of the
+    /// comma-separated patterns when given, where `*` matches any
+    /// characters (like `fd_*,path_open`)
+    #[clap(
+        long = "trace-syscalls",
+        name = "PATTERNS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "*",
+        value_delimiter = ','
+    )]
+    pub trace_syscalls: Option<Vec<String>>,
+
+    /// Only traces the syscalls that operate on this file descriptor, can
+    /// be given several times (requires `--trace-syscalls`)
+    #[clap(long = "trace-fd", name = "FD", requires = "PATTERNS")]
+    pub trace_fds: Vec<u32>,
+
+    /// Writes the standard output of the guest to a file instead of the
+    /// terminal
+    #[clap(long = "stdout", name = "STDOUT_FILE")]
+    pub stdout: Option<PathBuf>,
+
+    /// Writes the standard error of the guest to a file instead of the
+    /// terminal
+    #[clap(long = "stderr", name = "STDERR_FILE")]
+    pub stderr: Option<PathBuf>,
+
+    /// Mounts a read-only `/proc` where the guest can find its arguments,
+    /// environment, file descriptors and memory usage under `/proc/self`
+    #[clap(long = "proc-fs")]
+    pub proc_fs: bool,
+
     /// Specifies one or more journal files that Wasmer will use to restore
     /// and save the state of the WASM process as it executes.
     ///
@@ -177,6 +385,26 @@ pub struct Wasi {
     /// Require WASI modules to only import 1 version of WASI.
     #[clap(long = "deny-multiple-wasi-versions")]
     pub deny_multiple_wasi_versions: bool,
+
+    /// Bounds the size of the cache of compiled modules (e.g. `2GB`), the
+    /// least recently used ones being deleted when it is exceeded
+    #[clap(long = "cache-max-size", name = "SIZE", env = "WASMER_CACHE_MAX_SIZE")]
+    pub cache_max_size: Option<bytesize::ByteSize>,
+}
+
+/// What happens to the modifications staged with `--stage-writes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StageWrites {
+    Commit,
+    Discard,
+    Ask,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StagedDirectory {
+    host: PathBuf,
+    guest: String,
+    fs: StagedFileSystem,
 }
 
 pub struct RunProperties {
@@ -263,7 +491,7 @@ impl Wasi {
 
             // Process the --dirs flag and merge it with --mapdir.
             let mut have_current_dir = false;
-            for dir in &self.pre_opened_directories {
+            for PreopenedDirectory { path: dir, .. } in &self.pre_opened_directories {
                 let mapping = if dir == Path::new(".") {
                     if have_current_dir {
                         bail!("Cannot pre-open the current directory twice: --dir=. must only be specified once");
@@ -339,7 +567,8 @@ impl Wasi {
                 mapped_dirs.push(mapping);
             }
 
-            if !mapped_dirs.is_empty() {
+            // Staged directories are mounted along with the other mounts
+            if !mapped_dirs.is_empty() && self.staged_dirs.is_empty() {
                 // TODO: should we expose the common ancestor instead of root?
                 let fs_backing: Arc<dyn FileSystem + Send + Sync> =
                     Arc::new(PassthruFileSystem::new(default_fs_backing()));
@@ -349,7 +578,17 @@ impl Wasi {
                     } else {
                         host
                     };
-                    root_fs.mount(guest.into(), &fs_backing, host)?;
+                    if self.fs_policy == FsPolicy::Strict {
+                        // Every directory gets its own file system so that
+                        // paths can't be resolved outside of it
+                        let fs: Arc<dyn FileSystem + Send + Sync> = Arc::new(
+                            virtual_fs::host_fs::FileSystem::new(Handle::current(), &host)?
+                                .with_resolve_beneath(true),
+                        );
+                        root_fs.mount(guest.into(), &fs, "/".into())?;
+                    } else {
+                        root_fs.mount(guest.into(), &fs_backing, host)?;
+                    }
                 }
             }
 
@@ -366,8 +605,65 @@ impl Wasi {
             }
         };
 
+        for MountedDirectory { guest, fs } in self.build_mounts()? {
+            builder.add_mount(guest, Box::new(fs))?;
+        }
+
+        let preopen_rights = self.build_preopen_rights();
+        for (guest, rights) in &preopen_rights {
+            let case_insensitive = self.case_insensitive_dirs.contains(guest);
+            builder.add_preopen_build(|p| {
+                p.directory(guest)
+                    .alias(guest)
+                    .rights(*rights)
+                    .case_insensitive(case_insensitive)
+            })?;
+        }
+        for guest in &self.case_insensitive_dirs {
+            if preopen_rights.iter().all(|(dir, _)| dir != guest) {
+                builder.add_preopen_build(|p| {
+                    p.directory(guest)
+                        .alias(guest)
+                        .read(true)
+                        .write(true)
+                        .create(true)
+                        .case_insensitive(true)
+                })?;
+            }
+        }
+
+        for listener in self.build_listeners()? {
+            builder.add_preopen_listener(listener);
+        }
+
         *builder.capabilities_mut() = self.capabilities();
 
+        if let Some(clock) = self.clock {
+            builder.set_clock(clock);
+        }
+        if let Some(seed) = self.random_seed {
+            builder.set_random_seed(seed);
+        }
+        if let Some(user) = self.user_identity() {
+            builder.set_user(user);
+        }
+        if let Some(tracker) = &self.resource_usage {
+            builder.set_resource_usage(tracker.clone());
+        }
+        if let Some(tracer) = self.syscall_tracer() {
+            builder.set_syscall_tracer(tracer);
+        }
+        builder.set_proc_fs(self.proc_fs);
+        if let Some(stdout) = self.open_stdout()? {
+            builder.set_stdout_writer(stdout);
+        }
+        if let Some(stderr) = self.open_stderr()? {
+            builder.set_stderr_writer(stderr);
+        }
+        if let Some(secs) = self.forward_signals {
+            builder.set_forward_signals(Duration::from_secs(secs));
+        }
+
         #[cfg(feature = "journal")]
         {
             for trigger in self.snapshot_on.iter().cloned() {
@@ -384,6 +680,249 @@ impl Wasi {
         Ok(builder)
     }
 
+    /// Load the archives passed with `--mount-archive` and the layers passed
+    /// with `--overlay`.
+    pub fn build_mounts(&self) -> Result<Vec<MountedDirectory>, anyhow::Error> {
+        let mut mounts = Vec::new();
+
+        for MappedDirectory { host, guest } in &self.mounted_archives {
+            let fs = virtual_fs::ArchiveFileSystem::from_path(host)
+                .with_context(|| format!("Unable to load the archive at \"{}\"", host.display()))?;
+            mounts.push(MountedDirectory {
+                guest: guest.clone(),
+                fs: Arc::new(fs),
+            });
+        }
+
+        for OverlayMount {
+            guest,
+            lowers,
+            upper,
+        } in &self.overlays
+        {
+            let handle = tokio::runtime::Handle::current();
+            let host_dir = |dir: &PathBuf| {
+                virtual_fs::host_fs::FileSystem::new(handle.clone(), dir)
+                    .with_context(|| format!("Unable to open \"{}\"", dir.display()))
+            };
+
+            let upper: Box<dyn FileSystem + Send + Sync> = match upper {
+                Some(dir) => Box::new(host_dir(dir)?),
+                None => Box::<virtual_fs::mem_fs::FileSystem>::default(),
+            };
+            let lowers = lowers.iter().map(host_dir).collect::<Result<Vec<_>>>()?;
+
+            mounts.push(MountedDirectory {
+                guest: guest.clone(),
+                fs: Arc::new(virtual_fs::OverlayFileSystem::new(upper, lowers)),
+            });
+        }
+
+        for StagedDirectory { guest, fs, .. } in &self.staged_dirs {
+            mounts.push(MountedDirectory {
+                guest: guest.clone(),
+                fs: Arc::new(fs.clone()),
+            });
+        }
+
+        Ok(mounts)
+    }
+
+    /// The identity of the user the guest runs as, from `--user` and
+    /// `--home`.
+    pub fn user_identity(&self) -> Option<UserIdentity> {
+        let user = self.user.clone()?;
+        match &self.home {
+            Some(home) => Some(user.with_home(home)),
+            None => Some(user),
+        }
+    }
+
+    /// The tracer that prints the syscalls of the guest, when
+    /// `--trace-syscalls` is given.
+    pub fn syscall_tracer(&self) -> Option<SyscallTracer> {
+        let patterns = self.trace_syscalls.clone()?;
+        let tracer = SyscallTracer::new(|event| eprintln!("{event}")).with_names(patterns);
+        Some(
+            self.trace_fds
+                .iter()
+                .fold(tracer, |tracer, fd| tracer.with_fd(*fd)),
+        )
+    }
+
+    /// The guest paths of the `--dir` directories that were only granted
+    /// some rights, along with those rights.
+    pub fn build_preopen_rights(&self) -> Vec<(String, Rights)> {
+        self.pre_opened_directories
+            .iter()
+            .filter_map(|PreopenedDirectory { path, rights }| {
+                let guest = if path == Path::new(".") {
+                    MAPPED_CURRENT_DIR_DEFAULT_PATH.to_string()
+                } else {
+                    path.to_string_lossy().into_owned()
+                };
+                Some((guest, (*rights)?))
+            })
+            .collect()
+    }
+
+    /// The file given to `--stdout`, truncated.
+    pub fn open_stdout(&self) -> Result<Option<std::fs::File>> {
+        open_output(self.stdout.as_deref())
+    }
+
+    /// The file given to `--stderr`, truncated.
+    pub fn open_stderr(&self) -> Result<Option<std::fs::File>> {
+        open_output(self.stderr.as_deref())
+    }
+
+    /// Where the standard streams of Emscripten modules are written.
+    pub fn build_emscripten_stdio(&self) -> Result<EmscriptenStdio> {
+        let mut stdio = EmscriptenStdio::new();
+        if let Some(stdout) = self.open_stdout()? {
+            stdio.set_stdout_writer(stdout);
+        }
+        if let Some(stderr) = self.open_stderr()? {
+            stdio.set_stderr_writer(stderr);
+        }
+        Ok(stdio)
+    }
+
+    /// The directories made available to Emscripten modules, which access
+    /// the file system of the host directly.
+    ///
+    /// Pre-opened directories without any right to modify them are mounted
+    /// read-only.
+    pub fn build_emscripten_mounts(&self) -> Result<Vec<EmscriptenMount>> {
+        let modifying_rights = Rights::FD_WRITE
+            | Rights::FD_ALLOCATE
+            | Rights::FD_FILESTAT_SET_SIZE
+            | Rights::PATH_CREATE_DIRECTORY
+            | Rights::PATH_CREATE_FILE
+            | Rights::PATH_LINK_TARGET
+            | Rights::PATH_RENAME_TARGET
+            | Rights::PATH_SYMLINK
+            | Rights::PATH_REMOVE_DIRECTORY
+            | Rights::PATH_UNLINK_FILE;
+        let read_only: Vec<String> = self
+            .build_preopen_rights()
+            .into_iter()
+            .filter(|(_, rights)| !rights.intersects(modifying_rights))
+            .map(|(guest, _)| guest)
+            .collect();
+
+        let (have_current_dir, _, mapped_dirs) = self.build_mapped_directories()?;
+        let mut mounts: Vec<EmscriptenMount> = mapped_dirs
+            .into_iter()
+            .map(|MappedDirectory { host, guest }| EmscriptenMount {
+                read_only: read_only.contains(&guest),
+                host,
+                guest,
+            })
+            .collect();
+
+        // Relative paths are resolved against the current directory
+        if have_current_dir {
+            let current_dir = mounts
+                .iter()
+                .find(|mount| mount.guest == MAPPED_CURRENT_DIR_DEFAULT_PATH)
+                .cloned();
+            if let Some(current_dir) = current_dir {
+                mounts.push(EmscriptenMount {
+                    guest: ".".to_string(),
+                    ..current_dir
+                });
+            }
+        }
+
+        Ok(mounts)
+    }
+
+    /// Put the mapped directories behind a [`StagedFileSystem`] when
+    /// `--stage-writes` was used.
+    pub fn stage_mapped_directories(&mut self) -> Result<()> {
+        if self.stage_writes.is_none() {
+            return Ok(());
+        }
+
+        let (_, _, mapped_dirs) = self.build_mapped_directories()?;
+        self.staged_dirs = mapped_dirs
+            .into_iter()
+            .map(|dir| {
+                let host = dir.host.clone();
+                let MountedDirectory { guest, fs } = dir.mount(self.fs_policy);
+                StagedDirectory {
+                    host,
+                    guest,
+                    fs: StagedFileSystem::new(fs),
+                }
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// Commit or discard the modifications staged with `--stage-writes`.
+    pub fn finish_staged_writes(&self) -> Result<()> {
+        let Some(mode) = self.stage_writes else {
+            return Ok(());
+        };
+
+        let staged: Vec<_> = self
+            .staged_dirs
+            .iter()
+            .map(|dir| (dir, dir.fs.changes()))
+            .filter(|(_, changes)| !changes.is_empty())
+            .collect();
+        if staged.is_empty() {
+            return Ok(());
+        }
+
+        let commit = match mode {
+            StageWrites::Commit => true,
+            StageWrites::Discard => false,
+            StageWrites::Ask => {
+                eprintln!("The program made the following changes:");
+                for (dir, changes) in &staged {
+                    for change in changes {
+                        let (marker, path) = match change {
+                            StagedChange::CreateDir(path) | StagedChange::WriteFile(path) => {
+                                ("+", path)
+                            }
+                            StagedChange::Remove(path) => ("-", path),
+                        };
+                        let path = path.strip_prefix("/").unwrap_or(path);
+                        eprintln!("  {marker} {}", dir.host.join(path).display());
+                    }
+                }
+
+                if std::io::stdin().is_terminal() {
+                    dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Apply these changes?")
+                        .default(false)
+                        .interact()?
+                } else {
+                    eprintln!("Discarding the changes because stdin is not a terminal");
+                    false
+                }
+            }
+        };
+
+        for (dir, _) in staged {
+            if commit {
+                Handle::current()
+                    .block_on(dir.fs.commit())
+                    .with_context(|| {
+                        format!("Unable to apply the changes to \"{}\"", dir.host.display())
+                    })?;
+            } else {
+                dir.fs.discard()?;
+            }
+        }
+
+        Ok(())
+    }
+
     #[cfg(feature = "journal")]
     pub fn build_journals(&self) -> anyhow::Result<Vec<Arc<DynJournal>>> {
         let mut ret = Vec::new();
@@ -409,6 +948,18 @@ impl Wasi {
         Ok(Vec::new())
     }
 
+    /// Binds the `--listen` addresses and takes over the sockets passed by
+    /// systemd.
+    pub fn build_listeners(&self) -> Result<Vec<std::net::TcpListener>> {
+        let mut listeners = inherited_listeners()?;
+        for addr in &self.listen {
+            let listener = std::net::TcpListener::bind(addr)
+                .with_context(|| format!("Unable to listen on {addr}"))?;
+            listeners.push(listener);
+        }
+        Ok(listeners)
+    }
+
     pub fn build_mapped_directories(
         &self,
     ) -> Result<(bool, bool, Vec<MappedDirectory>), anyhow::Error> {
@@ -416,7 +967,7 @@ impl Wasi {
 
         // Process the --dirs flag and merge it with --mapdir.
         let mut have_current_dir = false;
-        for dir in &self.pre_opened_directories {
+        for PreopenedDirectory { path: dir, .. } in &self.pre_opened_directories {
             let mapping = if dir == Path::new(".") {
                 if have_current_dir {
                     bail!("Cannot pre-open the current directory twice: --dir=. must only be specified once");
@@ -536,6 +1087,7 @@ impl Wasi {
         caps.threading.enable_asynchronous_threading = self.enable_async_threads;
         caps.threading.enable_exponential_cpu_backoff =
             self.enable_cpu_backoff.map(Duration::from_millis);
+        caps.threading.enable_precise_timers = self.precise_timers.map(Duration::from_micros);
 
         caps
     }
@@ -554,12 +1106,27 @@ impl Wasi {
         let tokio_task_manager = Arc::new(TokioTaskManager::new(rt_or_handle.into()));
         let mut rt = PluggableRuntime::new(tokio_task_manager.clone());
 
-        let has_networking = self.networking
+        let has_networking = self.networking == Some(true)
             || capabilities::get_cached_capability(pkg_cache_path)
                 .ok()
                 .is_some_and(|v| v.enable_networking);
 
-        if has_networking {
+        if self.networking == Some(false) {
+            if !self.net_allow.is_empty() || !self.net_deny.is_empty() {
+                bail!("`--net-allow` and `--net-deny` can't be used with `--net=off`");
+            }
+            rt.set_networking_implementation(virtual_net::UnsupportedVirtualNetworking::default());
+        } else if !self.net_allow.is_empty() || !self.net_deny.is_empty() {
+            let host: virtual_net::DynVirtualNetworking =
+                Arc::new(virtual_net::host::LocalNetworking::default());
+            let net = if self.net_allow.is_empty() {
+                virtual_net::AllowListNetworking::deny_list(host, self.net_deny.iter().cloned())
+            } else {
+                virtual_net::AllowListNetworking::new(host, self.net_allow.iter().cloned())
+                    .with_denied(self.net_deny.iter().cloned())
+            };
+            rt.set_networking_implementation(net);
+        } else if has_networking {
             rt.set_networking_implementation(virtual_net::host::LocalNetworking::default());
         } else {
             let net = super::capabilities::net::AskingNetworking::new(
@@ -591,9 +1158,12 @@ impl Wasi {
 
         let registry = self.prepare_source(env, client, preferred_webc_version)?;
 
-        let cache_dir = env.cache_dir().join("compiled");
-        let module_cache = wasmer_wasix::runtime::module_cache::in_memory()
-            .with_fallback(FileSystemCache::new(cache_dir, tokio_task_manager));
+        let cache_dir = crate::commands::compiled_dir(env);
+        let mut fs_cache = FileSystemCache::new(cache_dir, tokio_task_manager);
+        if let Some(max_size) = self.cache_max_size {
+            fs_cache = fs_cache.with_max_size(max_size.as_u64());
+        }
+        let module_cache = wasmer_wasix::runtime::module_cache::in_memory().with_fallback(fs_cache);
 
         rt.set_package_loader(package_loader)
             .set_module_cache(module_cache)
@@ -626,7 +1196,7 @@ impl Wasi {
         Ok(Self {
             deny_multiple_wasi_versions: true,
             env_vars: std::env::vars().collect(),
-            pre_opened_directories: vec![dir],
+            pre_opened_directories: vec![dir.into()],
             ..Self::default()
         })
     }
@@ -702,6 +1272,14 @@ impl Wasi {
     }
 }
 
+fn open_output(path: Option<&Path>) -> Result<Option<std::fs::File>> {
+    path.map(|path| {
+        std::fs::File::create(path)
+            .with_context(|| format!("Unable to create \"{}\"", path.display()))
+    })
+    .transpose()
+}
+
 fn parse_registry(r: &str) -> Result<Url> {
     let url = wasmer_registry::format_graphql(r).parse()?;
     Ok(url)
@@ -749,3 +1327,61 @@ fn tokens_by_authority(env: &WasmerEnv) -> Result<HashMap<String, String>> {
 
     Ok(tokens)
 }
+
+/// Takes over the sockets passed with the systemd socket activation protocol,
+/// see `sd_listen_fds(3)`.
+#[cfg(unix)]
+fn inherited_listeners() -> Result<Vec<std::net::TcpListener>> {
+    use std::os::unix::io::FromRawFd;
+
+    const SD_LISTEN_FDS_START: libc::c_int = 3;
+
+    let pid = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok());
+    if pid != Some(std::process::id()) {
+        return Ok(Vec::new());
+    }
+    let count = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|count| count.parse::<libc::c_int>().ok())
+        .unwrap_or(0);
+
+    // The sockets must only be taken over once and not leak into the
+    // environment of other processes
+    for var in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(var);
+    }
+
+    (SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + count)
+        .map(|fd| {
+            let mut ty: libc::c_int = 0;
+            let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+            // SAFETY: `ty` and `len` are valid for the duration of the call
+            let ret = unsafe {
+                libc::getsockopt(
+                    fd,
+                    libc::SOL_SOCKET,
+                    libc::SO_TYPE,
+                    &mut ty as *mut libc::c_int as *mut libc::c_void,
+                    &mut len,
+                )
+            };
+            if ret != 0 || ty != libc::SOCK_STREAM {
+                bail!("The file descriptor {fd} passed in LISTEN_FDS is not a stream socket");
+            }
+
+            // SAFETY: systemd handed the file descriptor over to us
+            let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+            listener.local_addr().with_context(|| {
+                format!("The file descriptor {fd} passed in LISTEN_FDS is not a TCP socket")
+            })?;
+            Ok(listener)
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn inherited_listeners() -> Result<Vec<std::net::TcpListener>> {
+    Ok(Vec::new())
+}
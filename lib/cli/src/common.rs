@@ -63,3 +63,22 @@ impl From<HashAlgorithm> for wasmer_types::HashAlgorithm {
         }
     }
 }
+
+/// How to report the compiled functions to profilers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum ProfilingStrategy {
+    /// Write a perf map, `/tmp/perf-PID.map`
+    PerfMap,
+    /// Write a jitdump file, `/tmp/jit-PID.dump`, for `perf inject --jit`
+    JitDump,
+}
+
+#[cfg(feature = "sys")]
+impl From<ProfilingStrategy> for wasmer::sys::ProfilingStrategy {
+    fn from(value: ProfilingStrategy) -> Self {
+        match value {
+            ProfilingStrategy::PerfMap => wasmer::sys::ProfilingStrategy::PerfMap,
+            ProfilingStrategy::JitDump => wasmer::sys::ProfilingStrategy::JitDump,
+        }
+    }
+}
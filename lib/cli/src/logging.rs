@@ -4,7 +4,12 @@ use is_terminal::IsTerminal;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-const WHITELISTED_LOG_TARGETS: &[&str] = &["wasmer", "wasmer_wasix", "virtual_fs"];
+const WHITELISTED_LOG_TARGETS: &[&str] = &[
+    "wasmer",
+    "wasmer_compiler",
+    "wasmer_wasix",
+    "virtual_fs",
+];
 
 /// Control the output generated by the CLI.
 #[derive(Debug, Default, Clone, PartialEq, clap::Parser)]
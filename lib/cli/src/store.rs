@@ -88,6 +88,17 @@ pub struct CompilerOptions {
     #[clap(long)]
     llvm_debug_dir: Option<PathBuf>,
 
+    /// Make execution deterministic: canonicalize NaNs and reject the
+    /// WebAssembly features whose behavior depends on the host.
+    #[clap(long)]
+    deterministic: bool,
+
+    /// Describe the compiled code to native debuggers such as GDB and LLDB
+    /// with a line table, mapping it to the offsets of the instructions in
+    /// the WebAssembly binary (needs the `gdb-jit` feature).
+    #[clap(long)]
+    debug_info: bool,
+
     #[clap(flatten)]
     features: WasmFeatures,
 }
@@ -164,10 +175,14 @@ impl CompilerOptions {
         compiler_config: Box<dyn CompilerConfig>,
     ) -> Result<Engine> {
         let features = self.get_features(compiler_config.default_features_for_target(&target))?;
-        let engine: Engine = wasmer_compiler::EngineBuilder::new(compiler_config)
+        let mut builder = wasmer_compiler::EngineBuilder::new(compiler_config)
             .set_features(Some(features))
-            .set_target(Some(target))
-            .engine();
+            .set_target(Some(target));
+        if self.deterministic {
+            builder = builder.deterministic();
+        }
+        builder = builder.set_debug_info(self.debug_info);
+        let engine: Engine = builder.engine();
 
         Ok(engine)
     }
@@ -353,6 +368,12 @@ impl ToString for CompilerType {
 
 #[cfg(feature = "compiler")]
 impl StoreOptions {
+    /// Makes the stores deterministic, as with the `--deterministic` flag.
+    pub fn deterministic(mut self) -> Self {
+        self.compiler.deterministic = true;
+        self
+    }
+
     /// Gets the store for the host target, with the compiler name selected
     pub fn get_store(&self) -> Result<(Store, CompilerType)> {
         let target = Target::default();
@@ -367,6 +388,19 @@ impl StoreOptions {
         Ok((store, compiler_type))
     }
 
+    /// Gets the store for the host target, applying `middleware` to the
+    /// modules it compiles.
+    pub fn get_store_with_middleware(
+        &self,
+        middleware: Arc<dyn ModuleMiddleware>,
+    ) -> Result<(Store, CompilerType)> {
+        let (mut compiler_config, compiler_type) = self.compiler.get_compiler_config()?;
+        compiler_config.push_middleware(middleware);
+        let engine = self.get_engine_with_compiler(Target::default(), compiler_config)?;
+        let store = Store::new(engine);
+        Ok((store, compiler_type))
+    }
+
     #[cfg(feature = "compiler")]
     fn get_engine_with_compiler(
         &self,
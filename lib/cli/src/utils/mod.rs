@@ -13,7 +13,7 @@ use std::{
 use anyhow::{bail, Context as _, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use wasmer_wasix::runners::MappedDirectory;
+use wasmer_wasix::{runners::MappedDirectory, wasmer_wasix_types::wasi::Rights};
 
 fn retrieve_alias_pathbuf(alias: &str, real_dir: &str) -> Result<MappedDirectory> {
     let pb = PathBuf::from(&real_dir).canonicalize()?;
@@ -47,6 +47,143 @@ pub fn parse_mapdir(entry: &str) -> Result<MappedDirectory> {
     }
 }
 
+/// Parses an archive mount of the form `<archive>:<guest path>`.
+pub fn parse_mount_archive(entry: &str) -> Result<MappedDirectory> {
+    let (archive, guest) = match entry.rsplit_once(':') {
+        Some((archive, guest)) if !archive.is_empty() && guest.starts_with('/') => (archive, guest),
+        _ => bail!(
+            "Archive mounts must be of the form `<archive>:<absolute guest path>`. Found {}",
+            &entry
+        ),
+    };
+
+    let host = PathBuf::from(archive)
+        .canonicalize()
+        .with_context(|| format!("Archive \"{archive}\" does not exist"))?;
+    if !host.is_file() {
+        bail!("\"{}\" exists, but it is not a file", archive);
+    }
+
+    Ok(MappedDirectory {
+        guest: guest.to_string(),
+        host,
+    })
+}
+
+/// A stack of host directories layered at a single guest path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayMount {
+    pub guest: String,
+    /// Read-only layers, from the highest to the lowest precedence.
+    pub lowers: Vec<PathBuf>,
+    /// The writable layer, or `None` to keep writes in memory.
+    pub upper: Option<PathBuf>,
+}
+
+/// Parses an overlay of the form `<guest>:<lower>[:<lower>...]:<upper>`.
+///
+/// An empty upper layer (e.g. `/data:./base:`) keeps all writes in memory.
+pub fn parse_overlay(entry: &str) -> Result<OverlayMount> {
+    let parts = entry.split(':').collect::<Vec<_>>();
+    let (guest, layers) = match parts.split_first() {
+        Some((guest, layers)) if guest.starts_with('/') && layers.len() >= 2 => (guest, layers),
+        _ => bail!(
+            "Overlays must be of the form `<guest>:<lower>[:<lower>...]:<upper>` with an absolute guest path. Found {}",
+            &entry
+        ),
+    };
+    let (upper, lowers) = layers.split_last().unwrap();
+
+    let canonicalize_dir = |dir: &str| -> Result<PathBuf> {
+        let path = PathBuf::from(dir)
+            .canonicalize()
+            .with_context(|| format!("Directory \"{dir}\" does not exist"))?;
+        if !path.is_dir() {
+            bail!("\"{}\" exists, but it is not a directory", dir);
+        }
+        Ok(path)
+    };
+
+    let lowers = lowers
+        .iter()
+        .map(|dir| canonicalize_dir(dir))
+        .collect::<Result<Vec<_>>>()?;
+    let upper = if upper.is_empty() {
+        None
+    } else {
+        Some(canonicalize_dir(upper)?)
+    };
+
+    Ok(OverlayMount {
+        guest: guest.to_string(),
+        lowers,
+        upper,
+    })
+}
+
+/// A directory passed with `--dir`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreopenedDirectory {
+    pub path: PathBuf,
+    /// The rights granted to the directory, or `None` for full access.
+    pub rights: Option<Rights>,
+}
+
+impl From<PathBuf> for PreopenedDirectory {
+    fn from(path: PathBuf) -> Self {
+        PreopenedDirectory { path, rights: None }
+    }
+}
+
+/// Parses a directory of the form `<dir>[:rights=<right>,...]`.
+pub fn parse_dir(entry: &str) -> Result<PreopenedDirectory> {
+    match entry.rsplit_once(":rights=") {
+        Some((path, rights)) => Ok(PreopenedDirectory {
+            path: PathBuf::from(path),
+            rights: Some(parse_rights(rights)?),
+        }),
+        None => Ok(PathBuf::from(entry).into()),
+    }
+}
+
+/// Parses a comma-separated list of rights.
+///
+/// Besides the names of individual rights (e.g. `fd_readdir` or
+/// `path_create_file`), the `fd_` prefix may be left out (e.g. `seek`) and
+/// the following groups are understood:
+///
+/// - `read`: read files and list directories
+/// - `write`: modify, rename and remove files
+/// - `create`: create files and directories
+/// - `append`: create files and append to them
+/// - `list`: list directories and get the metadata of their entries
+pub fn parse_rights(list: &str) -> Result<Rights> {
+    let mut rights = Rights::empty();
+
+    for name in list.split(',').map(str::trim) {
+        rights |= match name {
+            "read" => Rights::dir_read(),
+            "write" => Rights::dir_write(),
+            "create" => Rights::dir_create(),
+            "append" => {
+                Rights::PATH_OPEN
+                    | Rights::PATH_CREATE_FILE
+                    | Rights::FD_WRITE
+                    | Rights::FD_SYNC
+                    | Rights::FD_DATASYNC
+                    | Rights::PATH_FILESTAT_GET
+                    | Rights::FD_FILESTAT_GET
+            }
+            "list" => Rights::FD_READDIR | Rights::PATH_FILESTAT_GET | Rights::FD_FILESTAT_GET,
+            _ => Rights::from_name(name)
+                .or_else(|| Rights::from_name(&format!("fd_{name}")))
+                .with_context(|| format!("\"{name}\" is not a known right"))?,
+        };
+    }
+
+    Ok(rights)
+}
+
 /// Parses an environment variable.
 pub fn parse_envvar(entry: &str) -> Result<(String, String)> {
     let entry = entry.trim();
@@ -309,4 +446,66 @@ c: c
             ("A".into(), "B=C=D".into())
         );
     }
+
+    #[test]
+    fn test_parse_overlay() {
+        let lower = tempfile::tempdir().unwrap();
+        let upper = tempfile::tempdir().unwrap();
+        let lower_path = lower.path().canonicalize().unwrap();
+        let upper_path = upper.path().canonicalize().unwrap();
+
+        let overlay = parse_overlay(&format!(
+            "/data:{}:{}",
+            lower.path().display(),
+            upper.path().display()
+        ))
+        .unwrap();
+        assert_eq!(
+            overlay,
+            OverlayMount {
+                guest: "/data".to_string(),
+                lowers: vec![lower_path.clone()],
+                upper: Some(upper_path),
+            }
+        );
+
+        let overlay = parse_overlay(&format!("/data:{}:", lower.path().display())).unwrap();
+        assert_eq!(overlay.lowers, vec![lower_path]);
+        assert_eq!(overlay.upper, None);
+
+        assert!(parse_overlay("/data").is_err());
+        assert!(parse_overlay(&format!("/data:{}", lower.path().display())).is_err());
+        assert!(parse_overlay(&format!("data:{}:", lower.path().display())).is_err());
+        assert!(parse_overlay("/data:/does/not/exist:").is_err());
+    }
+
+    #[test]
+    fn test_parse_dir() {
+        assert_eq!(
+            parse_dir("/data").unwrap(),
+            PreopenedDirectory {
+                path: PathBuf::from("/data"),
+                rights: None,
+            }
+        );
+        assert_eq!(
+            parse_dir("/data:rights=read,seek,tell").unwrap(),
+            PreopenedDirectory {
+                path: PathBuf::from("/data"),
+                rights: Some(Rights::dir_read() | Rights::FD_SEEK | Rights::FD_TELL),
+            }
+        );
+        assert_eq!(
+            parse_dir("/logs:rights=list,path_create_file")
+                .unwrap()
+                .rights,
+            Some(
+                Rights::FD_READDIR
+                    | Rights::PATH_FILESTAT_GET
+                    | Rights::FD_FILESTAT_GET
+                    | Rights::PATH_CREATE_FILE
+            )
+        );
+        assert!(parse_dir("/data:rights=fly").is_err());
+    }
 }
@@ -4,7 +4,7 @@ use crate::address_map::get_function_address_map;
 use crate::config::Cranelift;
 #[cfg(feature = "unwind")]
 use crate::dwarf::WriterRelocate;
-use crate::func_environ::{get_function_name, FuncEnvironment};
+use crate::func_environ::{get_function_name, FuncEnvironment, FROZEN_GLOBAL_TRAP};
 use crate::trampoline::{
     make_trampoline_dynamic_function, make_trampoline_function_call, FunctionBuilderContext,
 };
@@ -18,7 +18,7 @@ use cranelift_codegen::{Context, MachTrap};
 #[cfg(feature = "unwind")]
 use gimli::write::{Address, EhFrame, FrameTable};
 #[cfg(feature = "rayon")]
-use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::sync::Arc;
 use wasmer_compiler::{
     Compiler, FunctionBinaryReader, FunctionBodyData, MiddlewareBinaryReader, ModuleMiddleware,
@@ -28,8 +28,8 @@ use wasmer_types::entity::{EntityRef, PrimaryMap};
 use wasmer_types::{
     CallingConvention, Compilation, CompileError, CompileModuleInfo, CompiledFunction,
     CompiledFunctionFrameInfo, CompiledFunctionUnwindInfo, Dwarf, FunctionBody, FunctionIndex,
-    LocalFunctionIndex, ModuleInfo, Relocation, RelocationTarget, SectionIndex, SignatureIndex,
-    Target, TrapCode, TrapInformation,
+    FunctionType, LocalFunctionIndex, ModuleInfo, Relocation, RelocationTarget, SectionIndex,
+    SignatureIndex, Target, TrapCode, TrapInformation,
 };
 
 /// A compiler that compiles a WebAssembly module with Cranelift, translating the Wasm to Cranelift IR,
@@ -55,11 +55,33 @@ impl Compiler for CraneliftCompiler {
         "cranelift"
     }
 
+    fn deterministic_id(&self) -> String {
+        let config = &self.config;
+        format!(
+            "cranelift-{:?}-nan{}-pic{}-epoch{}-frozen{}-mw[{}]",
+            config.opt_level,
+            config.enable_nan_canonicalization,
+            config.enable_pic,
+            config.enable_epoch_interruption,
+            config.enable_global_freezing,
+            config
+                .middlewares
+                .iter()
+                .map(|middleware| middleware.deterministic_id())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+
     /// Get the middlewares for this compiler
     fn get_middlewares(&self) -> &[Arc<dyn ModuleMiddleware>] {
         &self.config.middlewares
     }
 
+    fn is_pic(&self) -> bool {
+        self.config.enable_pic
+    }
+
     /// Compile the module using Cranelift, producing a compilation result with
     /// associated relocations.
     fn compile_module(
@@ -125,6 +147,8 @@ impl Compiler for CraneliftCompiler {
                     &signatures,
                     &memory_styles,
                     &table_styles,
+                    self.config.enable_epoch_interruption,
+                    self.config.enable_global_freezing,
                 );
                 context.func.name = match get_function_name(func_index) {
                     ExternalName::User(nameref) => {
@@ -237,6 +261,8 @@ impl Compiler for CraneliftCompiler {
                     &signatures,
                     memory_styles,
                     table_styles,
+                    self.config.enable_epoch_interruption,
+                    self.config.enable_global_freezing,
                 );
                 context.func.name = match get_function_name(func_index) {
                     ExternalName::User(nameref) => {
@@ -336,9 +362,75 @@ impl Compiler for CraneliftCompiler {
             .into_iter()
             .unzip();
 
+        use wasmer_types::VMOffsets;
+        let offsets = VMOffsets::new_for_trampolines(frontend_config.pointer_bytes());
+        // dynamic function trampolines (only for imported functions)
+        let make_dynamic_function_trampoline =
+            |cx: &mut FunctionBuilderContext, (i, func_type): (usize, &FunctionType)| {
+                let (body, unwind_info) =
+                    make_trampoline_dynamic_function(&*isa, &offsets, cx, func_type)?;
+                let (unwind_info, fde) = match unwind_info {
+                    #[cfg(feature = "unwind")]
+                    CraneliftUnwindInfo::Fde(fde) => {
+                        if dwarf_frametable.is_some() {
+                            let fde = fde.to_fde(Address::Symbol {
+                                symbol: WriterRelocate::DYNAMIC_FUNCTION_TRAMPOLINE_SYMBOL,
+                                // We use the addend as a way to specify the
+                                // imported function index
+                                addend: i as _,
+                            });
+                            // The unwind information is inserted into the dwarf
+                            // section, so that host functions can be unwound
+                            // back to the Wasm code calling them
+                            (Some(CompiledFunctionUnwindInfo::Dwarf), Some(fde))
+                        } else {
+                            (None, None)
+                        }
+                    }
+                    #[cfg(feature = "unwind")]
+                    other => (other.maybe_into_to_windows_unwind(), None),
+
+                    #[cfg(not(feature = "unwind"))]
+                    other => (other.maybe_into_to_windows_unwind(), None::<()>),
+                };
+                Ok((FunctionBody { body, unwind_info }, fde))
+            };
+        #[cfg(not(feature = "rayon"))]
+        let mut cx = FunctionBuilderContext::new();
+        #[cfg(not(feature = "rayon"))]
+        let (dynamic_function_trampolines, dynamic_function_trampoline_fdes): (
+            Vec<_>,
+            Vec<_>,
+        ) = module
+            .imported_function_types()
+            .enumerate()
+            .map(|(i, func_type)| make_dynamic_function_trampoline(&mut cx, (i, &func_type)))
+            .collect::<Result<Vec<_>, CompileError>>()?
+            .into_iter()
+            .unzip();
+        #[cfg(feature = "rayon")]
+        let (dynamic_function_trampolines, dynamic_function_trampoline_fdes): (
+            Vec<_>,
+            Vec<_>,
+        ) = module
+            .imported_function_types()
+            .collect::<Vec<_>>()
+            .par_iter()
+            .enumerate()
+            .map_init(FunctionBuilderContext::new, |cx, (i, func_type)| {
+                make_dynamic_function_trampoline(cx, (i, func_type))
+            })
+            .collect::<Result<Vec<_>, CompileError>>()?
+            .into_iter()
+            .unzip();
+
         #[cfg(feature = "unwind")]
         let dwarf = if let Some((mut dwarf_frametable, cie_id)) = dwarf_frametable {
-            for fde in fdes.into_iter().flatten() {
+            for fde in fdes
+                .into_iter()
+                .chain(dynamic_function_trampoline_fdes)
+                .flatten()
+            {
                 dwarf_frametable.add_fde(cie_id, fde);
             }
             let mut eh_frame = EhFrame(WriterRelocate::new(target.triple().endianness().ok()));
@@ -379,37 +471,11 @@ impl Compiler for CraneliftCompiler {
             .into_iter()
             .collect::<PrimaryMap<SignatureIndex, FunctionBody>>();
 
-        use wasmer_types::VMOffsets;
-        let offsets = VMOffsets::new_for_trampolines(frontend_config.pointer_bytes());
-        // dynamic function trampolines (only for imported functions)
-        #[cfg(not(feature = "rayon"))]
-        let mut cx = FunctionBuilderContext::new();
-        #[cfg(not(feature = "rayon"))]
-        let dynamic_function_trampolines = module
-            .imported_function_types()
-            .collect::<Vec<_>>()
-            .into_iter()
-            .map(|func_type| make_trampoline_dynamic_function(&*isa, &offsets, &mut cx, &func_type))
-            .collect::<Result<Vec<_>, CompileError>>()?
-            .into_iter()
-            .collect::<PrimaryMap<FunctionIndex, FunctionBody>>();
-        #[cfg(feature = "rayon")]
-        let dynamic_function_trampolines = module
-            .imported_function_types()
-            .collect::<Vec<_>>()
-            .par_iter()
-            .map_init(FunctionBuilderContext::new, |cx, func_type| {
-                make_trampoline_dynamic_function(&*isa, &offsets, cx, func_type)
-            })
-            .collect::<Result<Vec<_>, CompileError>>()?
-            .into_iter()
-            .collect::<PrimaryMap<FunctionIndex, FunctionBody>>();
-
         Ok(Compilation {
             functions: functions.into_iter().collect(),
             custom_sections,
             function_call_trampolines,
-            dynamic_function_trampolines,
+            dynamic_function_trampolines: dynamic_function_trampolines.into_iter().collect(),
             debug: dwarf,
         })
     }
@@ -463,9 +529,9 @@ fn translate_ir_trapcode(trap: ir::TrapCode) -> TrapCode {
         ir::TrapCode::IntegerDivisionByZero => TrapCode::IntegerDivisionByZero,
         ir::TrapCode::BadConversionToInteger => TrapCode::BadConversionToInteger,
         ir::TrapCode::UnreachableCodeReached => TrapCode::UnreachableCodeReached,
-        ir::TrapCode::Interrupt => unimplemented!("Interrupts not supported"),
+        ir::TrapCode::Interrupt => TrapCode::Interrupt,
+        FROZEN_GLOBAL_TRAP => TrapCode::FrozenGlobal,
         ir::TrapCode::User(_user_code) => unimplemented!("User trap code not supported"),
-        // ir::TrapCode::Interrupt => TrapCode::Interrupt,
         // ir::TrapCode::User(user_code) => TrapCode::User(user_code),
     }
 }
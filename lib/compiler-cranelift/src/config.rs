@@ -29,10 +29,12 @@ pub enum CraneliftOptLevel {
 /// consumed by `wasmer_engine::Engine::new`.
 #[derive(Debug, Clone)]
 pub struct Cranelift {
-    enable_nan_canonicalization: bool,
+    pub(crate) enable_nan_canonicalization: bool,
     enable_verifier: bool,
-    enable_pic: bool,
-    opt_level: CraneliftOptLevel,
+    pub(crate) enable_pic: bool,
+    pub(crate) enable_epoch_interruption: bool,
+    pub(crate) enable_global_freezing: bool,
+    pub(crate) opt_level: CraneliftOptLevel,
     /// The middleware chain.
     pub(crate) middlewares: Vec<Arc<dyn ModuleMiddleware>>,
 }
@@ -46,6 +48,8 @@ impl Cranelift {
             enable_verifier: false,
             opt_level: CraneliftOptLevel::Speed,
             enable_pic: false,
+            enable_epoch_interruption: false,
+            enable_global_freezing: false,
             middlewares: vec![],
         }
     }
@@ -209,6 +213,14 @@ impl CompilerConfig for Cranelift {
         self.enable_verifier = true;
     }
 
+    fn enable_epoch_interruption(&mut self) {
+        self.enable_epoch_interruption = true;
+    }
+
+    fn enable_global_freezing(&mut self) {
+        self.enable_global_freezing = true;
+    }
+
     fn canonicalize_nans(&mut self, enable: bool) {
         self.enable_nan_canonicalization = enable;
     }
@@ -1,11 +1,11 @@
 use gimli::write::{Address, EndianVec, Result, Writer};
 use gimli::{RunTimeEndian, SectionId};
 use wasmer_types::entity::EntityRef;
-use wasmer_types::LocalFunctionIndex;
 use wasmer_types::{
     CustomSection, CustomSectionProtection, Endianness, Relocation, RelocationKind,
     RelocationTarget, SectionBody,
 };
+use wasmer_types::{FunctionIndex, LocalFunctionIndex};
 
 #[derive(Clone, Debug)]
 pub struct WriterRelocate {
@@ -15,6 +15,7 @@ pub struct WriterRelocate {
 
 impl WriterRelocate {
     pub const FUNCTION_SYMBOL: usize = 0;
+    pub const DYNAMIC_FUNCTION_TRAMPOLINE_SYMBOL: usize = 1;
     pub fn new(endianness: Option<Endianness>) -> Self {
         let endianness = match endianness {
             Some(Endianness::Little) => RunTimeEndian::Little,
@@ -63,27 +64,31 @@ impl Writer for WriterRelocate {
         match address {
             Address::Constant(val) => self.write_udata(val, size),
             Address::Symbol { symbol, addend } => {
-                // Is a function relocation
-                if symbol == Self::FUNCTION_SYMBOL {
-                    // We use the addend to detect the function index
-                    let function_index = LocalFunctionIndex::new(addend as _);
-                    let reloc_target = RelocationTarget::LocalFunc(function_index);
-                    let offset = self.len() as u32;
-                    let kind = match size {
-                        8 => RelocationKind::Abs8,
-                        _ => unimplemented!("dwarf relocation size not yet supported: {}", size),
-                    };
-                    let addend = 0;
-                    self.relocs.push(Relocation {
-                        kind,
-                        reloc_target,
-                        offset,
-                        addend,
-                    });
-                    self.write_udata(addend as _, size)
-                } else {
-                    unreachable!("Symbol {} in DWARF not recognized", symbol);
-                }
+                // We use the addend to detect the function index
+                let reloc_target = match symbol {
+                    // Is a function relocation
+                    Self::FUNCTION_SYMBOL => {
+                        RelocationTarget::LocalFunc(LocalFunctionIndex::new(addend as _))
+                    }
+                    // Is a dynamic function trampoline relocation
+                    Self::DYNAMIC_FUNCTION_TRAMPOLINE_SYMBOL => {
+                        RelocationTarget::DynamicFunctionTrampoline(FunctionIndex::new(addend as _))
+                    }
+                    _ => unreachable!("Symbol {} in DWARF not recognized", symbol),
+                };
+                let offset = self.len() as u32;
+                let kind = match size {
+                    8 => RelocationKind::Abs8,
+                    _ => unimplemented!("dwarf relocation size not yet supported: {}", size),
+                };
+                let addend = 0;
+                self.relocs.push(Relocation {
+                    kind,
+                    reloc_target,
+                    offset,
+                    addend,
+                });
+                self.write_udata(addend as _, size)
             }
         }
     }
@@ -35,6 +35,9 @@ pub fn type_of_vmtable_definition_current_elements(vmoffsets: &VMOffsets) -> ir:
     ir::Type::int(u16::from(vmoffsets.size_of_vmtable_definition_current_elements()) * 8).unwrap()
 }
 
+/// The trap code of writes to frozen globals.
+pub const FROZEN_GLOBAL_TRAP: ir::TrapCode = ir::TrapCode::User(0);
+
 /// The `FuncEnvironment` implementation for use by the `ModuleEnvironment`.
 pub struct FuncEnvironment<'module_environment> {
     /// Target-specified configuration.
@@ -121,6 +124,12 @@ pub struct FuncEnvironment<'module_environment> {
 
     /// The table styles
     table_styles: &'module_environment PrimaryMap<TableIndex, TableStyle>,
+
+    /// Whether to emit epoch deadline checks.
+    epoch_interruption: bool,
+
+    /// Whether to check if mutable globals are frozen before writing them.
+    global_freezing: bool,
 }
 
 impl<'module_environment> FuncEnvironment<'module_environment> {
@@ -130,6 +139,8 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
         signatures: &'module_environment PrimaryMap<SignatureIndex, ir::Signature>,
         memory_styles: &'module_environment PrimaryMap<MemoryIndex, MemoryStyle>,
         table_styles: &'module_environment PrimaryMap<TableIndex, TableStyle>,
+        epoch_interruption: bool,
+        global_freezing: bool,
     ) -> Self {
         Self {
             target_config,
@@ -158,6 +169,8 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
             offsets: VMOffsets::new(target_config.pointer_bytes(), module),
             memory_styles,
             table_styles,
+            epoch_interruption,
+            global_freezing,
         }
     }
 
@@ -851,6 +864,62 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
 
         (base, func_addr)
     }
+
+    /// Trap with `Interrupt` if the epoch deadline of the store is reached.
+    fn translate_epoch_check(&mut self, pos: &mut FuncCursor<'_>) {
+        if !self.epoch_interruption {
+            return;
+        }
+        let pointer_type = self.pointer_type();
+        let vmctx = self.vmctx(pos.func);
+        let base = pos.ins().global_value(pointer_type, vmctx);
+
+        let mut ptr_flags = ir::MemFlags::trusted();
+        ptr_flags.set_readonly();
+        let counter_ptr = pos.ins().load(
+            pointer_type,
+            ptr_flags,
+            base,
+            i32::try_from(self.offsets.vmctx_epoch_counter_ptr()).unwrap(),
+        );
+        let deadline_ptr = pos.ins().load(
+            pointer_type,
+            ptr_flags,
+            base,
+            i32::try_from(self.offsets.vmctx_epoch_deadline_ptr()).unwrap(),
+        );
+
+        // The epoch is incremented by other threads, so it's loaded
+        // atomically to make sure the load isn't merged with a previous one.
+        let flags = ir::MemFlags::trusted();
+        let counter = pos.ins().atomic_load(I64, flags, counter_ptr);
+        let deadline = pos.ins().load(I64, flags, deadline_ptr, 0);
+        let reached = pos
+            .ins()
+            .icmp(IntCC::UnsignedGreaterThanOrEqual, counter, deadline);
+        pos.ins().trapnz(reached, ir::TrapCode::Interrupt);
+    }
+
+    /// Loads the address of the `VMGlobalDefinition` of a global.
+    fn global_definition_addr(
+        &mut self,
+        pos: &mut FuncCursor<'_>,
+        index: GlobalIndex,
+    ) -> ir::Value {
+        let pointer_type = self.pointer_type();
+        let vmctx = self.vmctx(pos.func);
+        let base = pos.ins().global_value(pointer_type, vmctx);
+        let offset = if let Some(def_index) = self.module.local_global_index(index) {
+            self.offsets.vmctx_vmglobal_definition(def_index)
+        } else {
+            self.offsets.vmctx_vmglobal_import_definition(index)
+        };
+
+        let mut flags = ir::MemFlags::trusted();
+        flags.set_readonly();
+        pos.ins()
+            .load(pointer_type, flags, base, i32::try_from(offset).unwrap())
+    }
 }
 
 impl<'module_environment> TargetEnvironment for FuncEnvironment<'module_environment> {
@@ -1055,21 +1124,48 @@ impl<'module_environment> BaseFuncEnvironment for FuncEnvironment<'module_enviro
         Ok(*pos.func.dfg.inst_results(call_inst).first().unwrap())
     }
 
+    // With global freezing, mutable globals are custom globals, so that
+    // writes can check whether the global is frozen.
     fn translate_custom_global_get(
         &mut self,
-        mut _pos: cranelift_codegen::cursor::FuncCursor<'_>,
-        _index: GlobalIndex,
+        mut pos: cranelift_codegen::cursor::FuncCursor<'_>,
+        index: GlobalIndex,
     ) -> WasmResult<ir::Value> {
-        unreachable!("we don't make any custom globals")
+        let ty = type_to_irtype(self.module.globals[index].ty, self.target_config())?;
+        let addr = self.global_definition_addr(&mut pos, index);
+        Ok(pos.ins().load(ty, ir::MemFlags::trusted(), addr, 0))
     }
 
     fn translate_custom_global_set(
         &mut self,
-        mut _pos: cranelift_codegen::cursor::FuncCursor<'_>,
-        _index: GlobalIndex,
-        _value: ir::Value,
+        mut pos: cranelift_codegen::cursor::FuncCursor<'_>,
+        index: GlobalIndex,
+        value: ir::Value,
     ) -> WasmResult<()> {
-        unreachable!("we don't make any custom globals")
+        let ty = type_to_irtype(self.module.globals[index].ty, self.target_config())?;
+        let addr = self.global_definition_addr(&mut pos, index);
+        let flags = ir::MemFlags::trusted();
+
+        let frozen = pos.ins().load(
+            I32,
+            flags,
+            addr,
+            i32::from(self.offsets.vmglobal_definition_frozen()),
+        );
+        pos.ins().trapnz(frozen, FROZEN_GLOBAL_TRAP);
+
+        // Ensure SIMD values are cast to their default Cranelift type, I8x16.
+        let value = if pos.func.dfg.value_type(value) != ty {
+            pos.ins().bitcast(
+                ty,
+                ir::MemFlags::new().with_endianness(ir::Endianness::Little),
+                value,
+            )
+        } else {
+            value
+        };
+        pos.ins().store(flags, value, addr, 0);
+        Ok(())
     }
 
     fn make_heap(&mut self, func: &mut ir::Function, index: MemoryIndex) -> WasmResult<ir::Heap> {
@@ -1151,6 +1247,9 @@ impl<'module_environment> BaseFuncEnvironment for FuncEnvironment<'module_enviro
         func: &mut ir::Function,
         index: GlobalIndex,
     ) -> WasmResult<GlobalVariable> {
+        if self.global_freezing && self.module.globals[index].mutability.is_mutable() {
+            return Ok(GlobalVariable::Custom);
+        }
         let pointer_type = self.pointer_type();
 
         let (ptr, offset) = {
@@ -1556,6 +1655,16 @@ impl<'module_environment> BaseFuncEnvironment for FuncEnvironment<'module_enviro
         Ok(*pos.func.dfg.inst_results(call_inst).first().unwrap())
     }
 
+    fn translate_function_header(&mut self, mut pos: FuncCursor) -> WasmResult<()> {
+        self.translate_epoch_check(&mut pos);
+        Ok(())
+    }
+
+    fn translate_loop_header(&mut self, mut pos: FuncCursor) -> WasmResult<()> {
+        self.translate_epoch_check(&mut pos);
+        Ok(())
+    }
+
     fn translate_atomic_notify(
         &mut self,
         mut pos: FuncCursor,
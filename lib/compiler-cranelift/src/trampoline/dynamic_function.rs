@@ -3,7 +3,9 @@
 
 //! A trampoline generator for calling dynamic host functions from Wasm.
 
-use crate::translator::{compiled_function_unwind_info, signature_to_cranelift_ir};
+use crate::translator::{
+    compiled_function_unwind_info, signature_to_cranelift_ir, CraneliftUnwindInfo,
+};
 use cranelift_codegen::ir;
 use cranelift_codegen::ir::{
     Function, InstBuilder, MemFlags, StackSlotData, StackSlotKind, UserFuncName,
@@ -14,15 +16,18 @@ use std::cmp;
 use std::mem;
 
 use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
-use wasmer_types::{CompileError, FunctionBody, FunctionType, VMOffsets};
+use wasmer_types::{CompileError, FunctionType, VMOffsets};
 
 /// Create a trampoline for invoking a WebAssembly function.
-pub fn make_trampoline_dynamic_function(
+///
+/// The unwind information of the trampoline is returned separately, so
+/// that the caller can add its DWARF FDE to the `.eh_frame` section.
+pub(crate) fn make_trampoline_dynamic_function(
     isa: &dyn TargetIsa,
     offsets: &VMOffsets,
     fn_builder_ctx: &mut FunctionBuilderContext,
     func_type: &FunctionType,
-) -> Result<FunctionBody, CompileError> {
+) -> Result<(Vec<u8>, CraneliftUnwindInfo), CompileError> {
     let pointer_type = isa.pointer_type();
     let frontend_config = isa.frontend_config();
     let signature = signature_to_cranelift_ir(func_type, frontend_config);
@@ -108,10 +113,7 @@ pub fn make_trampoline_dynamic_function(
         .compile_and_emit(isa, &mut code_buf)
         .map_err(|error| CompileError::Codegen(error.inner.to_string()))?;
 
-    let unwind_info = compiled_function_unwind_info(isa, &context)?.maybe_into_to_windows_unwind();
+    let unwind_info = compiled_function_unwind_info(isa, &context)?;
 
-    Ok(FunctionBody {
-        body: code_buf,
-        unwind_info,
-    })
+    Ok((code_buf, unwind_info))
 }
@@ -3,7 +3,7 @@
 mod dynamic_function;
 mod function_call;
 
-pub use self::dynamic_function::make_trampoline_dynamic_function;
+pub(crate) use self::dynamic_function::make_trampoline_dynamic_function;
 pub use self::function_call::make_trampoline_function_call;
 
 pub use cranelift_frontend::FunctionBuilderContext;
@@ -439,6 +439,15 @@ pub trait FuncEnvironment: TargetEnvironment {
         count: ir::Value,
     ) -> WasmResult<ir::Value>;
 
+    /// Emit code at the beginning of every wasm function.
+    ///
+    /// This can be used to insert explicit interrupt checking, so that
+    /// recursive functions without loops can also be interrupted.
+    fn translate_function_header(&mut self, _pos: FuncCursor) -> WasmResult<()> {
+        // By default, don't emit anything.
+        Ok(())
+    }
+
     /// Emit code at the beginning of every wasm loop.
     ///
     /// This can be used to insert explicit interrupt or safepoint checking at
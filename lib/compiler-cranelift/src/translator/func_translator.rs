@@ -107,6 +107,7 @@ impl FuncTranslator {
         self.state.initialize(&builder.func.signature, exit_block);
 
         parse_local_decls(reader, &mut builder, num_params, environ)?;
+        environ.translate_function_header(builder.cursor())?;
         parse_function_body(
             module_translation_state,
             reader,
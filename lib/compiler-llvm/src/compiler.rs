@@ -76,6 +76,17 @@ impl SymbolRegistry for ShortNames {
 }
 
 impl LLVMCompiler {
+    /// The LLVM backend doesn't emit the epoch checks yet, refuse to
+    /// compile code that would never be interrupted.
+    fn check_epoch_interruption(&self) -> Result<(), CompileError> {
+        if self.config().enable_epoch_interruption {
+            return Err(CompileError::UnsupportedFeature(
+                "epoch interruption with the LLVM compiler".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     fn compile_native_object(
         &self,
         target: &Target,
@@ -85,6 +96,7 @@ impl LLVMCompiler {
         symbol_registry: &dyn SymbolRegistry,
         wasmer_metadata: &[u8],
     ) -> Result<Vec<u8>, CompileError> {
+        self.check_epoch_interruption()?;
         let target_machine = self.config().target_machine(target);
         let ctx = Context::create();
 
@@ -199,6 +211,24 @@ impl Compiler for LLVMCompiler {
         "llvm"
     }
 
+    fn deterministic_id(&self) -> String {
+        let config = &self.config;
+        format!(
+            "llvm-{:?}-nan{}-pic{}-epoch{}-frozen{}-mw[{}]",
+            config.opt_level,
+            config.enable_nan_canonicalization,
+            config.is_pic,
+            config.enable_epoch_interruption,
+            config.enable_global_freezing,
+            config
+                .middlewares
+                .iter()
+                .map(|middleware| middleware.deterministic_id())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+
     /// Get the middlewares for this compiler
     fn get_middlewares(&self) -> &[Arc<dyn ModuleMiddleware>] {
         &self.config.middlewares
@@ -234,6 +264,7 @@ impl Compiler for LLVMCompiler {
         module_translation: &ModuleTranslationState,
         function_body_inputs: PrimaryMap<LocalFunctionIndex, FunctionBodyData<'_>>,
     ) -> Result<Compilation, CompileError> {
+        self.check_epoch_interruption()?;
         //let data = Arc::new(Mutex::new(0));
         let memory_styles = &compile_info.memory_styles;
         let table_styles = &compile_info.table_styles;
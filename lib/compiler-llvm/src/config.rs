@@ -42,7 +42,11 @@ pub struct LLVM {
     pub(crate) enable_nan_canonicalization: bool,
     pub(crate) enable_verifier: bool,
     pub(crate) opt_level: LLVMOptLevel,
-    is_pic: bool,
+    pub(crate) is_pic: bool,
+    /// Epoch interruption is not supported yet, compiling a module with it
+    /// enabled fails instead of silently ignoring it.
+    pub(crate) enable_epoch_interruption: bool,
+    pub(crate) enable_global_freezing: bool,
     pub(crate) callbacks: Option<Arc<dyn LLVMCallbacks>>,
     /// The middleware chain.
     pub(crate) middlewares: Vec<Arc<dyn ModuleMiddleware>>,
@@ -57,6 +61,8 @@ impl LLVM {
             enable_verifier: false,
             opt_level: LLVMOptLevel::Aggressive,
             is_pic: false,
+            enable_epoch_interruption: false,
+            enable_global_freezing: false,
             callbacks: None,
             middlewares: vec![],
         }
@@ -259,6 +265,14 @@ impl CompilerConfig for LLVM {
         self.enable_verifier = true;
     }
 
+    fn enable_epoch_interruption(&mut self) {
+        self.enable_epoch_interruption = true;
+    }
+
+    fn enable_global_freezing(&mut self) {
+        self.enable_global_freezing = true;
+    }
+
     fn canonicalize_nans(&mut self, enable: bool) {
         self.enable_nan_canonicalization = enable;
     }
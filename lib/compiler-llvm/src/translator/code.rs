@@ -2076,6 +2076,7 @@ impl<'ctx, 'a> LLVMFunctionCodeGenerator<'ctx, 'a> {
                     GlobalCache::Mut {
                         ptr_to_value,
                         value_type,
+                        ..
                     } => {
                         let value = self.builder.build_load(*value_type, *ptr_to_value, "");
                         tbaa_label(
@@ -2100,8 +2101,47 @@ impl<'ctx, 'a> LLVMFunctionCodeGenerator<'ctx, 'a> {
                             global_index.as_u32()
                         )))
                     }
-                    GlobalCache::Mut { ptr_to_value, .. } => {
+                    GlobalCache::Mut {
+                        ptr_to_value,
+                        ptr_to_frozen,
+                        ..
+                    } => {
                         let ptr_to_value = *ptr_to_value;
+                        let ptr_to_frozen = *ptr_to_frozen;
+
+                        if self.config.enable_global_freezing {
+                            // Trap if the global is frozen.
+                            let frozen = self
+                                .builder
+                                .build_load(self.intrinsics.i32_ty, ptr_to_frozen, "frozen")
+                                .into_int_value();
+                            let frozen = self.builder.build_int_compare(
+                                IntPredicate::NE,
+                                frozen,
+                                self.intrinsics.i32_zero,
+                                "",
+                            );
+                            let frozen_block = self
+                                .context
+                                .append_basic_block(self.function, "frozen_global_block");
+                            let continue_block = self
+                                .context
+                                .append_basic_block(self.function, "global_set_continue_block");
+                            self.builder.build_conditional_branch(
+                                frozen,
+                                frozen_block,
+                                continue_block,
+                            );
+                            self.builder.position_at_end(frozen_block);
+                            self.builder.build_call(
+                                self.intrinsics.throw_trap,
+                                &[self.intrinsics.trap_frozen_global.into()],
+                                "throw",
+                            );
+                            self.builder.build_unreachable();
+                            self.builder.position_at_end(continue_block);
+                        }
+
                         let (value, info) = self.state.pop1_extra()?;
                         let value = self.apply_pending_canonicalization(value, info);
                         let store = self.builder.build_store(ptr_to_value, value);
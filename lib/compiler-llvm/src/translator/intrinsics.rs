@@ -217,6 +217,7 @@ pub struct Intrinsics<'ctx> {
     pub trap_bad_conversion_to_integer: BasicValueEnum<'ctx>,
     pub trap_unaligned_atomic: BasicValueEnum<'ctx>,
     pub trap_table_access_oob: BasicValueEnum<'ctx>,
+    pub trap_frozen_global: BasicValueEnum<'ctx>,
 
     pub experimental_stackmap: FunctionValue<'ctx>,
 
@@ -799,6 +800,9 @@ impl<'ctx> Intrinsics<'ctx> {
             trap_table_access_oob: i32_ty
                 .const_int(TrapCode::TableAccessOutOfBounds as _, false)
                 .as_basic_value_enum(),
+            trap_frozen_global: i32_ty
+                .const_int(TrapCode::FrozenGlobal as _, false)
+                .as_basic_value_enum(),
 
             experimental_stackmap: module.add_function(
                 "llvm.experimental.stackmap",
@@ -1282,6 +1286,7 @@ struct TableCache<'ctx> {
 pub enum GlobalCache<'ctx> {
     Mut {
         ptr_to_value: PointerValue<'ctx>,
+        ptr_to_frozen: PointerValue<'ctx>,
         value_type: BasicTypeEnum<'ctx>,
     },
     Const {
@@ -1661,6 +1666,20 @@ impl<'ctx, 'a> CtxType<'ctx, 'a> {
                     );
                     global_ptr
                 };
+                let frozen_offset = intrinsics
+                    .i32_ty
+                    .const_int(offsets.vmglobal_definition_frozen().into(), false);
+                let ptr_to_frozen = {
+                    let global_ptr = cache_builder
+                        .build_bitcast(global_ptr, intrinsics.i8_ptr_ty, "")
+                        .into_pointer_value();
+                    let ptr_to_frozen = unsafe {
+                        cache_builder.build_gep(intrinsics.i8_ty, global_ptr, &[frozen_offset], "")
+                    };
+                    cache_builder
+                        .build_bitcast(ptr_to_frozen, intrinsics.i32_ptr_ty, "")
+                        .into_pointer_value()
+                };
                 let global_ptr = cache_builder
                     .build_bitcast(
                         global_ptr,
@@ -1686,6 +1705,7 @@ impl<'ctx, 'a> CtxType<'ctx, 'a> {
                     }
                     Mutability::Var => GlobalCache::Mut {
                         ptr_to_value: global_ptr,
+                        ptr_to_frozen,
                         value_type: type_to_llvm(intrinsics, global_value_type)?,
                     },
                 })
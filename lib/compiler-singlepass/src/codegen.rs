@@ -98,6 +98,8 @@ struct SpecialLabelSet {
     indirect_call_null: Label,
     bad_signature: Label,
     unaligned_atomic: Label,
+    epoch_interrupt: Label,
+    frozen_global: Label,
 }
 
 /// Metadata about a floating-point value.
@@ -1060,6 +1062,48 @@ impl<'a, M: Machine> FuncGen<'a, M> {
         id
     }
 
+    /// Trap with `Interrupt` if the epoch deadline of the store is reached.
+    fn emit_epoch_check(&mut self) -> Result<(), CompileError> {
+        if !self.config.enable_epoch_interruption {
+            return Ok(());
+        }
+        let counter = self.machine.acquire_temp_gpr().unwrap();
+        let deadline = self.machine.acquire_temp_gpr().unwrap();
+        self.machine.move_location(
+            Size::S64,
+            Location::Memory(
+                self.machine.get_vmctx_reg(),
+                self.vmoffsets.vmctx_epoch_counter_ptr() as i32,
+            ),
+            Location::GPR(counter),
+        )?;
+        self.machine.move_location(
+            Size::S64,
+            Location::Memory(counter, 0),
+            Location::GPR(counter),
+        )?;
+        self.machine.move_location(
+            Size::S64,
+            Location::Memory(
+                self.machine.get_vmctx_reg(),
+                self.vmoffsets.vmctx_epoch_deadline_ptr() as i32,
+            ),
+            Location::GPR(deadline),
+        )?;
+        self.machine.move_location(
+            Size::S64,
+            Location::Memory(deadline, 0),
+            Location::GPR(deadline),
+        )?;
+        self.machine
+            .location_cmp(Size::S64, Location::GPR(deadline), Location::GPR(counter))?;
+        self.machine
+            .jmp_on_aboveequal(self.special_labels.epoch_interrupt)?;
+        self.machine.release_gpr(deadline);
+        self.machine.release_gpr(counter);
+        Ok(())
+    }
+
     fn emit_head(&mut self) -> Result<(), CompileError> {
         self.machine.emit_function_prolog()?;
 
@@ -1098,12 +1142,12 @@ impl<'a, M: Machine> FuncGen<'a, M> {
             state_diff_id,
         });
 
-        // TODO: Full preemption by explicit signal checking
-
         // We insert set StackOverflow as the default trap that can happen
         // anywhere in the function prologue.
         self.machine.insert_stackoverflow();
 
+        self.emit_epoch_check()?;
+
         if self.state.wasm_inst_offset != std::usize::MAX {
             return Err(CompileError::Codegen(
                 "emit_head: wasm_inst_offset not std::usize::MAX".to_owned(),
@@ -1144,6 +1188,8 @@ impl<'a, M: Machine> FuncGen<'a, M> {
             indirect_call_null: machine.get_label(),
             bad_signature: machine.get_label(),
             unaligned_atomic: machine.get_label(),
+            epoch_interrupt: machine.get_label(),
+            frozen_global: machine.get_label(),
         };
 
         let fsm = FunctionStateMap::new(
@@ -1291,6 +1337,16 @@ impl<'a, M: Machine> FuncGen<'a, M> {
                     )?;
                     Location::Memory(tmp, 0)
                 };
+                if self.config.enable_global_freezing {
+                    // Trap if the global is frozen.
+                    self.machine.location_cmp(
+                        Size::S32,
+                        Location::Imm32(0),
+                        Location::Memory(tmp, self.vmoffsets.vmglobal_definition_frozen() as i32),
+                    )?;
+                    self.machine
+                        .jmp_on_different(self.special_labels.frozen_global)?;
+                }
                 let ty = type_to_wp_type(self.module.globals[global_index].ty);
                 let loc = self.pop_value_released()?;
                 if ty.is_float() {
@@ -3119,8 +3175,7 @@ impl<'a, M: Machine> FuncGen<'a, M> {
                     state_diff_id,
                 });
                 self.machine.emit_label(label)?;
-
-                // TODO: Re-enable interrupt signal check without branching
+                self.emit_epoch_check()?;
             }
             Operator::Nop => {}
             Operator::MemorySize { mem, mem_byte: _ } => {
@@ -6650,6 +6705,13 @@ impl<'a, M: Machine> FuncGen<'a, M> {
             .emit_label(self.special_labels.unaligned_atomic)?;
         self.machine.emit_illegal_op(TrapCode::UnalignedAtomic)?;
 
+        self.machine
+            .emit_label(self.special_labels.epoch_interrupt)?;
+        self.machine.emit_illegal_op(TrapCode::Interrupt)?;
+
+        self.machine.emit_label(self.special_labels.frozen_global)?;
+        self.machine.emit_illegal_op(TrapCode::FrozenGlobal)?;
+
         // Notify the assembler backend to generate necessary code at end of function.
         self.machine.finalize_function()?;
 
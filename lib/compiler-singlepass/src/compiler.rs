@@ -55,11 +55,32 @@ impl Compiler for SinglepassCompiler {
         "singlepass"
     }
 
+    fn deterministic_id(&self) -> String {
+        let config = &self.config;
+        format!(
+            "singlepass-nan{}-pic{}-epoch{}-frozen{}-mw[{}]",
+            config.enable_nan_canonicalization,
+            config.enable_pic,
+            config.enable_epoch_interruption,
+            config.enable_global_freezing,
+            config
+                .middlewares
+                .iter()
+                .map(|middleware| middleware.deterministic_id())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+
     /// Get the middlewares for this compiler
     fn get_middlewares(&self) -> &[Arc<dyn ModuleMiddleware>] {
         &self.config.middlewares
     }
 
+    fn is_pic(&self) -> bool {
+        self.config.enable_pic
+    }
+
     /// Compile the module using Singlepass, producing a compilation result with
     /// associated relocations.
     fn compile_module(
@@ -9,6 +9,9 @@ use wasmer_types::{CpuFeature, Features, Target};
 #[derive(Debug, Clone)]
 pub struct Singlepass {
     pub(crate) enable_nan_canonicalization: bool,
+    pub(crate) enable_pic: bool,
+    pub(crate) enable_epoch_interruption: bool,
+    pub(crate) enable_global_freezing: bool,
     /// The middleware chain.
     pub(crate) middlewares: Vec<Arc<dyn ModuleMiddleware>>,
 }
@@ -19,6 +22,9 @@ impl Singlepass {
     pub fn new() -> Self {
         Self {
             enable_nan_canonicalization: true,
+            enable_pic: false,
+            enable_epoch_interruption: false,
+            enable_global_freezing: false,
             middlewares: vec![],
         }
     }
@@ -31,8 +37,17 @@ impl Singlepass {
 
 impl CompilerConfig for Singlepass {
     fn enable_pic(&mut self) {
-        // Do nothing, since singlepass already emits
-        // PIC code.
+        // Singlepass already emits PIC code, this only makes the
+        // artifacts position-independent.
+        self.enable_pic = true;
+    }
+
+    fn enable_epoch_interruption(&mut self) {
+        self.enable_epoch_interruption = true;
+    }
+
+    fn enable_global_freezing(&mut self) {
+        self.enable_global_freezing = true;
     }
 
     /// Transform it into the compiler
@@ -44,6 +59,9 @@ impl CompilerConfig for Singlepass {
     fn default_features_for_target(&self, _target: &Target) -> Features {
         let mut features = Features::default();
         features.multi_value(false);
+        // `v128` values are not supported, so SIMD modules are rejected when
+        // they are validated instead of failing to compile.
+        features.simd(false);
         features
     }
 
@@ -1,6 +1,9 @@
 //! Define `ArtifactBuild` to allow compiling and instantiating to be
 //! done as separate steps.
 
+use super::pic::is_position_independent;
+#[cfg(feature = "compiler")]
+use super::pic::{make_pic_libcall_trampolines, GlobalOffsetTable};
 #[cfg(feature = "compiler")]
 use super::trampoline::{libcall_trampoline_len, make_libcall_trampolines};
 use crate::ArtifactCreate;
@@ -16,6 +19,8 @@ use rkyv::option::ArchivedOption;
 use self_cell::self_cell;
 use shared_buffer::OwnedBuffer;
 use std::sync::Arc;
+#[cfg(feature = "compiler")]
+use wasmer_types::entity::EntityRef;
 use wasmer_types::entity::{ArchivedPrimaryMap, PrimaryMap};
 use wasmer_types::ArchivedOwnedDataInitializer;
 use wasmer_types::ArchivedSerializableCompilation;
@@ -23,9 +28,9 @@ use wasmer_types::ArchivedSerializableModule;
 use wasmer_types::CompileModuleInfo;
 use wasmer_types::DeserializeError;
 use wasmer_types::{
-    CompileError, CpuFeature, CustomSection, Dwarf, FunctionIndex, LocalFunctionIndex, MemoryIndex,
-    MemoryStyle, ModuleHash, ModuleInfo, OwnedDataInitializer, Relocation, SectionIndex,
-    SignatureIndex, TableIndex, TableStyle, Target,
+    CompileError, CpuFeature, CustomSection, CustomSectionProtection, Dwarf, FunctionIndex,
+    LocalFunctionIndex, MemoryIndex, MemoryStyle, ModuleHash, ModuleInfo, OwnedDataInitializer,
+    Relocation, SectionIndex, SignatureIndex, TableIndex, TableStyle, Target,
 };
 use wasmer_types::{
     CompiledFunctionFrameInfo, FunctionBody, HashAlgorithm, SerializableCompilation,
@@ -47,20 +52,74 @@ impl ArtifactBuild {
         bytes.starts_with(Self::MAGIC_HEADER)
     }
 
+    /// Trailer signature for the original wasm module embedded in a wasmu binary
+    pub const EMBEDDED_WASM_MAGIC: &'static [u8; 8] = b"wasmsrc\0";
+
+    /// Get the ABI version a serialized `ArtifactBuild` was written with.
+    ///
+    /// In contrast to deserializing the artifact, this also works for
+    /// artifacts produced by other Wasmer releases.
+    pub fn serialized_version(bytes: &[u8]) -> Result<u32, DeserializeError> {
+        if !Self::is_deserializable(bytes) {
+            return Err(DeserializeError::Incompatible(
+                "The provided bytes are not wasmer-universal".to_string(),
+            ));
+        }
+        let (version, _) = MetadataHeader::parse_any_version(&bytes[Self::MAGIC_HEADER.len()..])?;
+        Ok(version)
+    }
+
+    /// Append the original wasm module to a serialized `ArtifactBuild`.
+    ///
+    /// The module is stored after the metadata, so it is ignored when the
+    /// artifact is loaded, but it allows the artifact to be recompiled later
+    /// on (see [`Self::embedded_wasm`]).
+    ///
+    /// The trailer has the following format:
+    /// WASM (any length) + LEN (8 bytes) + EMBEDDED_WASM_MAGIC (8 bytes)
+    pub fn embed_wasm(serialized: &mut Vec<u8>, wasm: &[u8]) {
+        serialized.extend_from_slice(wasm);
+        serialized.extend_from_slice(&(wasm.len() as u64).to_le_bytes());
+        serialized.extend_from_slice(Self::EMBEDDED_WASM_MAGIC);
+    }
+
+    /// Get the original wasm module embedded in a serialized `ArtifactBuild`
+    /// with [`Self::embed_wasm`], if any.
+    pub fn embedded_wasm(bytes: &[u8]) -> Option<&[u8]> {
+        if !Self::is_deserializable(bytes) {
+            return None;
+        }
+        let rest = bytes.strip_suffix(Self::EMBEDDED_WASM_MAGIC)?;
+        let len_offset = rest.len().checked_sub(8)?;
+        let len = u64::from_le_bytes(rest[len_offset..].try_into().ok()?);
+        let start = len_offset.checked_sub(usize::try_from(len).ok()?)?;
+        if start < Self::MAGIC_HEADER.len() + MetadataHeader::LEN {
+            return None;
+        }
+        let wasm = &rest[start..len_offset];
+        if !wasm.starts_with(b"\0asm") {
+            return None;
+        }
+        Some(wasm)
+    }
+
     /// Compile a data buffer into a `ArtifactBuild`, which may then be instantiated.
     #[cfg(feature = "compiler")]
+    #[tracing::instrument(level = "debug", skip_all, fields(bytes = data.len()))]
     pub fn new(
         inner_engine: &mut EngineInner,
         data: &[u8],
         target: &Target,
         memory_styles: PrimaryMap<MemoryIndex, MemoryStyle>,
-        table_styles: PrimaryMap<TableIndex, TableStyle>,
+        mut table_styles: PrimaryMap<TableIndex, TableStyle>,
         hash_algorithm: Option<HashAlgorithm>,
     ) -> Result<Self, CompileError> {
         let environ = ModuleEnvironment::new();
         let features = inner_engine.features().clone();
 
-        let translation = environ.translate(data).map_err(CompileError::Wasm)?;
+        let translation = tracing::debug_span!("translate")
+            .in_scope(|| environ.translate(data))
+            .map_err(CompileError::Wasm)?;
 
         let compiler = inner_engine.compiler()?;
 
@@ -70,6 +129,11 @@ impl ArtifactBuild {
         middlewares
             .apply_on_module_info(&mut module)
             .map_err(|err| CompileError::MiddlewareError(err.to_string()))?;
+        // The styles were computed before the middlewares ran, the tables
+        // they appended get the only style there is.
+        while table_styles.len() < module.tables.len() {
+            table_styles.push(TableStyle::CallerChecksSignature);
+        }
 
         if let Some(hash_algorithm) = hash_algorithm {
             let hash = match hash_algorithm {
@@ -88,15 +152,22 @@ impl ArtifactBuild {
         };
 
         // Compile the Module
-        let compilation = compiler.compile_module(
-            target,
-            &compile_info,
-            // SAFETY: Calling `unwrap` is correct since
-            // `environ.translate()` above will write some data into
-            // `module_translation_state`.
-            translation.module_translation_state.as_ref().unwrap(),
-            translation.function_body_inputs,
-        )?;
+        let span = tracing::debug_span!(
+            "compile_module",
+            compiler = compiler.name(),
+            functions = translation.function_body_inputs.len(),
+        );
+        let compilation = span.in_scope(|| {
+            compiler.compile_module(
+                target,
+                &compile_info,
+                // SAFETY: Calling `unwrap` is correct since
+                // `environ.translate()` above will write some data into
+                // `module_translation_state`.
+                translation.module_translation_state.as_ref().unwrap(),
+                translation.function_body_inputs,
+            )
+        })?;
 
         let data_initializers = translation
             .data_initializers
@@ -120,9 +191,34 @@ impl ArtifactBuild {
             .iter()
             .map(|(_, section)| section.relocations.clone())
             .collect::<PrimaryMap<SectionIndex, _>>();
-        let libcall_trampolines_section = make_libcall_trampolines(target);
-        custom_section_relocations.push(libcall_trampolines_section.relocations.clone());
-        let libcall_trampolines = custom_sections.push(libcall_trampolines_section);
+        let libcall_trampolines = if compiler.is_pic() {
+            // The libcall trampolines come right before the global offset
+            // table, which has to be created last since the relocations of
+            // the other sections add slots to it.
+            let libcall_trampolines = SectionIndex::new(custom_sections.len());
+            let mut got =
+                GlobalOffsetTable::new(SectionIndex::new(libcall_trampolines.index() + 1));
+            for relocations in function_relocations.values_mut() {
+                got.make_position_independent(relocations)?;
+            }
+            for (index, section) in custom_sections.iter_mut() {
+                if section.protection == CustomSectionProtection::ReadExecute {
+                    got.make_position_independent(&mut section.relocations)?;
+                    custom_section_relocations[index] = section.relocations.clone();
+                }
+            }
+            let libcall_trampolines_section = make_pic_libcall_trampolines(target, &mut got)?;
+            custom_section_relocations.push(libcall_trampolines_section.relocations.clone());
+            custom_sections.push(libcall_trampolines_section);
+            let got_section = got.into_section();
+            custom_section_relocations.push(got_section.relocations.clone());
+            custom_sections.push(got_section);
+            libcall_trampolines
+        } else {
+            let libcall_trampolines_section = make_libcall_trampolines(target);
+            custom_section_relocations.push(libcall_trampolines_section.relocations.clone());
+            custom_sections.push(libcall_trampolines_section)
+        };
         let libcall_trampoline_len = libcall_trampoline_len(target) as u32;
         let cpu_features = compiler.get_cpu_features_used(target.cpu_features());
 
@@ -201,6 +297,20 @@ impl ArtifactBuild {
     pub fn get_frame_info_ref(&self) -> &PrimaryMap<LocalFunctionIndex, CompiledFunctionFrameInfo> {
         &self.serializable.compilation.function_frame_info
     }
+
+    /// Whether the executable code only contains relocations relative to
+    /// the artifact itself, see [`crate::CompilerConfig::enable_pic`].
+    pub fn is_position_independent(&self) -> bool {
+        let sections = self.get_custom_sections_ref();
+        is_position_independent(self.get_function_relocations().values().flatten())
+            && self
+                .get_custom_section_relocations_ref()
+                .iter()
+                .all(|(index, relocations)| {
+                    sections[index].protection != CustomSectionProtection::ReadExecute
+                        || is_position_independent(relocations.iter())
+                })
+    }
 }
 
 impl<'a> ArtifactCreate<'a> for ArtifactBuild {
@@ -212,10 +322,8 @@ impl<'a> ArtifactCreate<'a> for ArtifactBuild {
     }
 
     fn set_module_info_name(&mut self, name: String) -> bool {
-        Arc::get_mut(&mut self.serializable.compile_info.module).map_or(false, |module_info| {
-            module_info.name = Some(name.to_string());
-            true
-        })
+        Arc::make_mut(&mut self.serializable.compile_info.module).name = Some(name);
+        true
     }
 
     fn module_info(&self) -> &ModuleInfo {
@@ -410,6 +518,23 @@ impl ArtifactBuildFromArchive {
         &self.cell.borrow_dependent().compilation.function_frame_info
     }
 
+    /// Whether the executable code only contains relocations relative to
+    /// the artifact itself, see [`crate::CompilerConfig::enable_pic`].
+    pub fn is_position_independent(&self) -> bool {
+        let sections = self.get_custom_sections_ref();
+        is_position_independent(
+            self.get_function_relocations()
+                .values()
+                .flat_map(|relocations| relocations.iter()),
+        ) && self
+            .get_custom_section_relocations_ref()
+            .iter()
+            .all(|(index, relocations)| {
+                sections[index].protection != CustomSectionProtection::ReadExecute
+                    || is_position_independent(relocations.iter())
+            })
+    }
+
     /// Get Function Relocations ref
     pub fn deserialize_frame_info_ref(
         &self,
@@ -432,10 +557,8 @@ impl<'a> ArtifactCreate<'a> for ArtifactBuildFromArchive {
     }
 
     fn set_module_info_name(&mut self, name: String) -> bool {
-        Arc::get_mut(&mut self.compile_info.module).map_or(false, |module_info| {
-            module_info.name = Some(name.to_string());
-            true
-        })
+        Arc::make_mut(&mut self.compile_info.module).name = Some(name);
+        true
     }
 
     fn module_info(&self) -> &ModuleInfo {
@@ -1,9 +1,11 @@
 //! Generic Artifact abstraction for Wasmer Engines.
 
 mod artifact_builder;
+mod pic;
 #[cfg(feature = "compiler")]
 mod trampoline;
 
 pub use self::artifact_builder::{ArtifactBuild, ArtifactBuildFromArchive, ModuleFromArchive};
+pub use self::pic::*;
 #[cfg(feature = "compiler")]
 pub use self::trampoline::*;
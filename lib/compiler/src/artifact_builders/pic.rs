@@ -0,0 +1,260 @@
+//! Position-independent artifacts.
+//!
+//! In a position-independent artifact, the executable code only contains
+//! PC-relative relocations between the sections of the artifact itself, so
+//! it doesn't depend on the address it is loaded at nor on the host process.
+//! All the absolute addresses are loaded from a global offset table (GOT): a
+//! data section with one 8-byte slot per target, filled at load time by
+//! applying its `Abs8` relocations.
+
+#[cfg(feature = "compiler")]
+use super::trampoline::libcall_trampoline_len;
+use enum_iterator::IntoEnumIterator;
+#[cfg(feature = "compiler")]
+use wasmer_types::{Architecture, Target};
+use wasmer_types::{
+    CompileError, CustomSection, CustomSectionProtection, LibCall, Relocation, RelocationKind,
+    RelocationLike, RelocationTarget, SectionBody, SectionIndex,
+};
+
+// JMP [RIP + GOT slot]   FF 25 XX XX XX XX
+// INT3 padding           CC CC CC CC CC CC CC CC CC CC
+#[cfg(feature = "compiler")]
+const X86_64_PIC_TRAMPOLINE: [u8; 16] = [
+    0xff, 0x25, 0x00, 0x00, 0x00, 0x00, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+];
+
+/// Returns whether the given kind of relocation is relative to the address
+/// it is applied at, and so can be used in position-independent code.
+pub fn is_pc_relative(kind: RelocationKind) -> bool {
+    matches!(
+        kind,
+        RelocationKind::X86PCRel4
+            | RelocationKind::X86PCRel8
+            | RelocationKind::X86CallPCRel4
+            | RelocationKind::X86CallPLTRel4
+            | RelocationKind::X86GOTPCRel4
+            | RelocationKind::Arm32Call
+            | RelocationKind::Arm64Call
+            | RelocationKind::RiscvCall
+            | RelocationKind::RiscvPCRelHi20
+            | RelocationKind::RiscvPCRelLo12I
+    )
+}
+
+/// Returns whether the given relocations can be applied to
+/// position-independent code.
+pub fn is_position_independent<'a, R: RelocationLike + 'a>(
+    mut relocations: impl Iterator<Item = &'a R>,
+) -> bool {
+    relocations.all(|r| is_pc_relative(r.kind()))
+}
+
+/// A global offset table being built.
+///
+/// The first slots hold the addresses of the libcalls, in order, so that
+/// the libcall trampolines can be generated independently of the rest of
+/// the artifact.
+pub struct GlobalOffsetTable {
+    section: SectionIndex,
+    slots: Vec<RelocationTarget>,
+}
+
+impl GlobalOffsetTable {
+    /// Create a new global offset table, that will be stored in the custom
+    /// section `section`.
+    pub fn new(section: SectionIndex) -> Self {
+        Self {
+            section,
+            slots: LibCall::into_enum_iter()
+                .map(RelocationTarget::LibCall)
+                .collect(),
+        }
+    }
+
+    /// The index of the custom section holding the table.
+    pub fn section(&self) -> SectionIndex {
+        self.section
+    }
+
+    /// Get the offset of the slot holding the address of `target` in the
+    /// table, adding it if needed.
+    pub fn slot(&mut self, target: RelocationTarget) -> u32 {
+        let index = match self.slots.iter().position(|slot| *slot == target) {
+            Some(index) => index,
+            None => {
+                self.slots.push(target);
+                self.slots.len() - 1
+            }
+        };
+        (index * 8) as u32
+    }
+
+    /// Rewrite the relocations of some executable code so that absolute
+    /// addresses are loaded from the table.
+    pub fn make_position_independent(
+        &mut self,
+        relocations: &mut [Relocation],
+    ) -> Result<(), CompileError> {
+        for r in relocations.iter_mut() {
+            match r.kind {
+                RelocationKind::X86GOTPCRel4 => {
+                    let slot = self.slot(r.reloc_target);
+                    r.kind = RelocationKind::X86PCRel4;
+                    r.reloc_target = RelocationTarget::CustomSection(self.section);
+                    r.addend += slot as i64;
+                }
+                RelocationKind::X86CallPLTRel4 => {
+                    // Local functions and libcall trampolines are part of
+                    // the artifact, so they can be called directly.
+                    r.kind = RelocationKind::X86CallPCRel4;
+                }
+                kind if is_pc_relative(kind) => {}
+                kind => {
+                    return Err(CompileError::Codegen(format!(
+                        "relocation {} can't be used in position-independent code",
+                        kind
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Create the custom section holding the table, and its relocations.
+    pub fn into_section(self) -> CustomSection {
+        let relocations = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(index, target)| Relocation {
+                kind: RelocationKind::Abs8,
+                reloc_target: *target,
+                offset: (index * 8) as u32,
+                addend: 0,
+            })
+            .collect();
+        CustomSection {
+            protection: CustomSectionProtection::Read,
+            bytes: SectionBody::new_with_vec(vec![0; self.slots.len() * 8]),
+            relocations,
+        }
+    }
+}
+
+/// Creates a custom section containing position-independent libcall
+/// trampolines, loading the libcall addresses from `got`.
+///
+/// The trampolines have the same length as the ones created by
+/// [`make_libcall_trampolines`](super::make_libcall_trampolines).
+#[cfg(feature = "compiler")]
+pub fn make_pic_libcall_trampolines(
+    target: &Target,
+    got: &mut GlobalOffsetTable,
+) -> Result<CustomSection, CompileError> {
+    let mut code = vec![];
+    let mut relocations = vec![];
+    match target.triple().architecture {
+        Architecture::X86_64 => {
+            debug_assert_eq!(X86_64_PIC_TRAMPOLINE.len(), libcall_trampoline_len(target));
+            for libcall in LibCall::into_enum_iter() {
+                let slot = got.slot(RelocationTarget::LibCall(libcall));
+                relocations.push(Relocation {
+                    kind: RelocationKind::X86PCRel4,
+                    reloc_target: RelocationTarget::CustomSection(got.section()),
+                    offset: code.len() as u32 + 2,
+                    // The displacement is relative to the end of the instruction.
+                    addend: slot as i64 - 4,
+                });
+                code.extend(X86_64_PIC_TRAMPOLINE);
+            }
+        }
+        arch => {
+            return Err(CompileError::UnsupportedTarget(format!(
+                "position-independent artifacts for {}",
+                arch
+            )))
+        }
+    }
+    Ok(CustomSection {
+        protection: CustomSectionProtection::ReadExecute,
+        bytes: SectionBody::new_with_vec(code),
+        relocations,
+    })
+}
+
+#[cfg(all(test, feature = "compiler"))]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use wasmer_types::{CpuFeature, Triple};
+
+    #[test]
+    fn pic_libcall_trampolines_load_from_got() {
+        let target = Target::new(
+            Triple::from_str("x86_64-unknown-linux-gnu").unwrap(),
+            CpuFeature::set(),
+        );
+        let mut got = GlobalOffsetTable::new(SectionIndex::from_u32(1));
+        let trampolines = make_pic_libcall_trampolines(&target, &mut got).unwrap();
+        let got = got.into_section();
+        assert_eq!(got.bytes.len(), LibCall::into_enum_iter().count() * 8);
+
+        // Lay out the GOT right after the trampolines, and check that each
+        // trampoline jumps through the slot of its libcall.
+        let trampolines_address = 0x10000;
+        let got_address = trampolines_address + trampolines.bytes.len() as u64;
+        for (r, libcall) in trampolines
+            .relocations
+            .iter()
+            .zip(LibCall::into_enum_iter())
+        {
+            assert!(is_pc_relative(r.kind()));
+            let (address, disp) = r.for_address(trampolines_address as usize, got_address);
+            let slot = (address as u64 + 4).wrapping_add(disp as i32 as u64);
+            let index = ((slot - got_address) / 8) as usize;
+            assert_eq!(
+                got.relocations[index].reloc_target,
+                RelocationTarget::LibCall(libcall)
+            );
+        }
+    }
+
+    #[test]
+    fn got_relocations_are_rewritten() {
+        let mut got = GlobalOffsetTable::new(SectionIndex::from_u32(3));
+        let mut relocations = vec![
+            Relocation {
+                kind: RelocationKind::X86GOTPCRel4,
+                reloc_target: RelocationTarget::LocalFunc(
+                    wasmer_types::LocalFunctionIndex::from_u32(0),
+                ),
+                offset: 4,
+                addend: -4,
+            },
+            Relocation {
+                kind: RelocationKind::X86CallPLTRel4,
+                reloc_target: RelocationTarget::LibCall(LibCall::FloorF32),
+                offset: 12,
+                addend: -4,
+            },
+        ];
+        got.make_position_independent(&mut relocations).unwrap();
+        let slot = (LibCall::into_enum_iter().count() * 8) as i64;
+        assert_eq!(relocations[0].kind, RelocationKind::X86PCRel4);
+        assert_eq!(
+            relocations[0].reloc_target,
+            RelocationTarget::CustomSection(SectionIndex::from_u32(3))
+        );
+        assert_eq!(relocations[0].addend, slot - 4);
+        assert_eq!(relocations[1].kind, RelocationKind::X86CallPCRel4);
+
+        let mut absolute = vec![Relocation {
+            kind: RelocationKind::Abs8,
+            reloc_target: RelocationTarget::LibCall(LibCall::FloorF32),
+            offset: 0,
+            addend: 0,
+        }];
+        assert!(got.make_position_independent(&mut absolute).is_err());
+    }
+}
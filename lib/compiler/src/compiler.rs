@@ -20,9 +20,10 @@ use wasmparser::{Validator, WasmFeatures};
 pub trait CompilerConfig {
     /// Enable Position Independent Code (PIC).
     ///
-    /// This is required for shared object generation (Native Engine),
-    /// but will make the JIT Engine to fail, since PIC is not yet
-    /// supported in the JIT linking phase.
+    /// This is required for shared object generation (Native Engine).
+    /// Artifacts built by the JIT Engine with PIC only contain relocations
+    /// relative to the code itself, and load all absolute addresses from a
+    /// global offset table.
     fn enable_pic(&mut self) {
         // By default we do nothing, each backend will need to customize this
         // in case they do something special for emitting PIC code.
@@ -37,6 +38,30 @@ pub trait CompilerConfig {
         // in case they create an IR that they can verify.
     }
 
+    /// Enable epoch-based interruption.
+    ///
+    /// Compiled code checks the epoch deadline of its store when entering
+    /// functions and loops, and traps with `TrapCode::Interrupt` once it is
+    /// reached. See `Engine::increment_epoch`.
+    ///
+    /// Compilers that can't emit these checks, like LLVM, fail to compile
+    /// modules with `CompileError::UnsupportedFeature` instead.
+    fn enable_epoch_interruption(&mut self) {
+        // By default we do nothing, each backend will need to customize this
+        // to emit the epoch checks.
+    }
+
+    /// Enable the checks of frozen globals.
+    ///
+    /// Compiled code checks whether a mutable global was frozen with
+    /// `Global::freeze` before writing it, and traps with
+    /// `TrapCode::FrozenGlobal` if it was. Without it, writes from
+    /// WebAssembly code to frozen globals succeed.
+    fn enable_global_freezing(&mut self) {
+        // By default we do nothing, each backend will need to customize this
+        // to emit the frozen checks.
+    }
+
     /// Enable NaN canonicalization.
     ///
     /// NaN canonicalization is useful when trying to run WebAssembly
@@ -74,6 +99,15 @@ pub trait Compiler: Send {
     /// Note that this is an API breaking change since 3.0
     fn name(&self) -> &str;
 
+    /// Returns an id describing the configuration of this compiler.
+    ///
+    /// Compilers configured differently, for instance with different
+    /// optimization levels, generate incompatible code and must return
+    /// different ids.
+    fn deterministic_id(&self) -> String {
+        self.name().to_string()
+    }
+
     /// Validates a module.
     ///
     /// It returns the a succesful Result in case is valid, `CompileError` in case is not.
@@ -142,6 +176,13 @@ pub trait Compiler: Send {
     /// Get the middlewares for this compiler
     fn get_middlewares(&self) -> &[Arc<dyn ModuleMiddleware>];
 
+    /// Whether the compiler is configured to emit Position Independent Code.
+    ///
+    /// See [`CompilerConfig::enable_pic`].
+    fn is_pic(&self) -> bool {
+        false
+    }
+
     /// Get the CpuFeatues used by the compiler
     fn get_cpu_features_used(&self, cpu_features: &EnumSet<CpuFeature>) -> EnumSet<CpuFeature> {
         *cpu_features
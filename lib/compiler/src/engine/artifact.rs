@@ -1,32 +1,38 @@
 //! Define `Artifact`, based on `ArtifactBuild`
 //! to allow compiling and instantiating to be done as separate steps.
 
+#[cfg(feature = "gdb-jit")]
+use crate::engine::jit_debug::register as register_jit_debug;
+use crate::engine::jit_debug::JitDebugFunction;
 use crate::engine::link::link_module;
+use crate::engine::trap::function_name;
 use crate::lib::std::vec::IntoIter;
 use crate::ArtifactBuild;
 use crate::ArtifactBuildFromArchive;
 use crate::ArtifactCreate;
+use crate::CompiledFunctionFrameInfoVariant;
 use crate::Features;
 use crate::FrameInfosVariant;
 use crate::ModuleEnvironment;
 use crate::{
     register_frame_info, resolve_imports, FunctionExtent, GlobalFrameInfoRegistration,
-    InstantiationError, Tunables,
+    InstantiationError, Tunables, FRAME_INFO,
 };
+use crate::{CodeMemoryLease, Engine, EngineInner};
 #[cfg(feature = "static-artifact-create")]
 use crate::{Compiler, FunctionBodyData, ModuleTranslationState};
-use crate::{Engine, EngineInner};
 use enumset::EnumSet;
 use shared_buffer::OwnedBuffer;
 #[cfg(any(feature = "static-artifact-create", feature = "static-artifact-load"))]
 use std::mem;
+use std::ops::Range;
 use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use std::sync::Arc;
 #[cfg(feature = "static-artifact-create")]
 use wasmer_object::{emit_compilation, emit_data, get_object_for_target, Object};
 #[cfg(any(feature = "static-artifact-create", feature = "static-artifact-load"))]
 use wasmer_types::compilation::symbols::ModuleMetadata;
-use wasmer_types::entity::{BoxedSlice, PrimaryMap};
+use wasmer_types::entity::{BoxedSlice, EntityRef, PrimaryMap};
 use wasmer_types::ArchivedDataInitializerLocation;
 use wasmer_types::ArchivedOwnedDataInitializer;
 #[cfg(feature = "static-artifact-create")]
@@ -36,9 +42,9 @@ use wasmer_types::DataInitializerLocation;
 use wasmer_types::DataInitializerLocationLike;
 use wasmer_types::MetadataHeader;
 use wasmer_types::{
-    CompileError, CpuFeature, DataInitializer, DeserializeError, FunctionIndex, HashAlgorithm,
-    LocalFunctionIndex, MemoryIndex, ModuleInfo, OwnedDataInitializer, SignatureIndex, TableIndex,
-    Target,
+    CompileError, CpuFeature, DataInitializer, DeserializeError, FrameInfo, FunctionIndex,
+    HashAlgorithm, LocalFunctionIndex, MemoryIndex, ModuleInfo, OwnedDataInitializer,
+    SignatureIndex, TableIndex, Target,
 };
 use wasmer_types::{SerializableModule, SerializeError};
 use wasmer_vm::{FunctionBodyPtr, MemoryStyle, TableStyle, VMSharedSignatureIndex, VMTrampoline};
@@ -59,6 +65,9 @@ pub struct AllocatedArtifact {
     finished_dynamic_function_trampolines: BoxedSlice<FunctionIndex, FunctionBodyPtr>,
     signatures: BoxedSlice<SignatureIndex, VMSharedSignatureIndex>,
     finished_function_lengths: BoxedSlice<LocalFunctionIndex, usize>,
+    // Keeps the code memory alive, this is None for artifacts whose code is
+    // part of the executable (static artifacts).
+    code_memory_lease: Option<Arc<CodeMemoryLease>>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -117,19 +126,7 @@ impl Artifact {
         hash_algorithm: Option<HashAlgorithm>,
     ) -> Result<Self, CompileError> {
         let mut inner_engine = engine.inner_mut();
-        let environ = ModuleEnvironment::new();
-        let translation = environ.translate(data).map_err(CompileError::Wasm)?;
-        let module = translation.module;
-        let memory_styles: PrimaryMap<MemoryIndex, MemoryStyle> = module
-            .memories
-            .values()
-            .map(|memory_type| tunables.memory_style(memory_type))
-            .collect();
-        let table_styles: PrimaryMap<TableIndex, TableStyle> = module
-            .tables
-            .values()
-            .map(|table_type| tunables.table_style(table_type))
-            .collect();
+        let (memory_styles, table_styles) = Self::styles(data, tunables)?;
 
         let artifact = ArtifactBuild::new(
             &mut inner_engine,
@@ -160,6 +157,62 @@ impl Artifact {
         })
     }
 
+    /// Compile a data buffer straight into serialized artifact bytes for
+    /// the given target, without loading it.
+    #[cfg(feature = "compiler")]
+    pub fn precompile(
+        engine: &Engine,
+        data: &[u8],
+        target: &Target,
+        tunables: &dyn Tunables,
+        hash_algorithm: Option<HashAlgorithm>,
+    ) -> Result<Vec<u8>, CompileError> {
+        let mut inner_engine = engine.inner_mut();
+        inner_engine.validate(data)?;
+        let (memory_styles, table_styles) = Self::styles(data, tunables)?;
+
+        let artifact = ArtifactBuild::new(
+            &mut inner_engine,
+            data,
+            target,
+            memory_styles,
+            table_styles,
+            hash_algorithm,
+        )?;
+        artifact
+            .serialize()
+            .map_err(|e| CompileError::Codegen(e.to_string()))
+    }
+
+    /// The memory and table styles for the module in `data`.
+    #[cfg(feature = "compiler")]
+    #[allow(clippy::type_complexity)]
+    fn styles(
+        data: &[u8],
+        tunables: &dyn Tunables,
+    ) -> Result<
+        (
+            PrimaryMap<MemoryIndex, MemoryStyle>,
+            PrimaryMap<TableIndex, TableStyle>,
+        ),
+        CompileError,
+    > {
+        let environ = ModuleEnvironment::new();
+        let translation = environ.translate(data).map_err(CompileError::Wasm)?;
+        let module = translation.module;
+        let memory_styles: PrimaryMap<MemoryIndex, MemoryStyle> = module
+            .memories
+            .values()
+            .map(|memory_type| tunables.memory_style(memory_type))
+            .collect();
+        let table_styles: PrimaryMap<TableIndex, TableStyle> = module
+            .tables
+            .values()
+            .map(|table_type| tunables.table_style(table_type))
+            .collect();
+        Ok((memory_styles, table_styles))
+    }
+
     /// This indicates if the Artifact is allocated and can be run by the current
     /// host. In case it can't be run (for example, if the artifact is cross compiled to
     /// other architecture), it will return false.
@@ -192,6 +245,7 @@ impl Artifact {
     /// for the host CPU architecture.
     /// In contrast to [`Self::deserialize_unchecked`] the artifact layout is
     /// validated, which increases safety.
+    #[tracing::instrument(level = "debug", skip_all, fields(bytes = bytes.as_ref().len()))]
     pub unsafe fn deserialize(
         engine: &Engine,
         bytes: OwnedBuffer,
@@ -237,6 +291,7 @@ impl Artifact {
     /// See [`Self::deserialize`].
     /// In contrast to the above, this function skips artifact layout validation,
     /// which increases the risk of loading invalid artifacts.
+    #[tracing::instrument(level = "debug", skip_all, fields(bytes = bytes.as_ref().len()))]
     pub unsafe fn deserialize_unchecked(
         engine: &Engine,
         bytes: OwnedBuffer,
@@ -275,6 +330,11 @@ impl Artifact {
     }
 
     /// Construct a `ArtifactBuild` from component parts.
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(module = %artifact.module_info().name())
+    )]
     pub fn from_parts(
         engine_inner: &mut EngineInner,
         artifact: ArtifactBuildVariant,
@@ -323,6 +383,7 @@ impl Artifact {
             ArtifactBuildVariant::Plain(p) => link_module(
                 module_info,
                 &finished_functions,
+                &finished_dynamic_function_trampolines,
                 p.get_function_relocations()
                     .iter()
                     .map(|(k, v)| (k, v.iter())),
@@ -336,6 +397,7 @@ impl Artifact {
             ArtifactBuildVariant::Archived(a) => link_module(
                 module_info,
                 &finished_functions,
+                &finished_dynamic_function_trampolines,
                 a.get_function_relocations()
                     .iter()
                     .map(|(k, v)| (k, v.iter())),
@@ -382,6 +444,11 @@ impl Artifact {
 
         // Make all code compiled thus far executable.
         engine_inner.publish_compiled_code();
+        tracing::debug!(
+            functions = finished_functions.len(),
+            code_memory_size = engine_inner.code_memory_size(),
+            "published the compiled code"
+        );
 
         engine_inner.publish_eh_frame(eh_frame)?;
 
@@ -400,6 +467,7 @@ impl Artifact {
         let finished_dynamic_function_trampolines =
             finished_dynamic_function_trampolines.into_boxed_slice();
         let signatures = signatures.into_boxed_slice();
+        let code_memory_lease = engine_inner.lease_code_memory();
 
         let mut artifact = Self {
             id: Default::default(),
@@ -412,6 +480,7 @@ impl Artifact {
                 finished_dynamic_function_trampolines,
                 signatures,
                 finished_function_lengths,
+                code_memory_lease: Some(code_memory_lease),
             }),
         };
 
@@ -421,14 +490,150 @@ impl Artifact {
         if let Some(frame_info) = artifact.internal_take_frame_info_registration() {
             engine_inner.register_frame_info(frame_info);
         }
+        if let Some(functions) = artifact.jit_debug_functions(engine_inner.debug_info()) {
+            if let Some(profiler) = engine_inner.profiler() {
+                profiler.register(&functions);
+            }
+            #[cfg(feature = "gdb-jit")]
+            if engine_inner.debug_info() {
+                let module_name = artifact.artifact.module_info().name();
+                if let Some(jit_debug) = register_jit_debug(&module_name, &functions) {
+                    engine_inner.register_jit_debug(jit_debug);
+                }
+            }
+        }
 
         Ok(artifact)
     }
 
+    /// Describe the functions of this artifact for debuggers and profilers,
+    /// along with their line table if `debug_info` is set.
+    fn jit_debug_functions(&self, debug_info: bool) -> Option<Vec<JitDebugFunction>> {
+        let allocated = self.allocated.as_ref()?;
+        let module = self.artifact.module_info();
+        let functions = allocated
+            .finished_functions
+            .iter()
+            .zip(allocated.finished_function_lengths.values())
+            .map(|((local_index, ptr), length)| {
+                let func_index = module.func_index(local_index);
+                let frame_info = match &self.artifact {
+                    ArtifactBuildVariant::Plain(p) => p
+                        .get_frame_info_ref()
+                        .get(local_index)
+                        .map(CompiledFunctionFrameInfoVariant::Ref),
+                    ArtifactBuildVariant::Archived(a) => a
+                        .get_frame_info_ref()
+                        .get(local_index)
+                        .map(CompiledFunctionFrameInfoVariant::Archived),
+                };
+                let lines = match frame_info {
+                    Some(frame_info) if debug_info => {
+                        let address_map = frame_info.address_map();
+                        let instructions = address_map.instructions();
+                        (0..instructions.len())
+                            .map(|index| instructions.get(index))
+                            .filter(|instruction| !instruction.srcloc.is_default())
+                            .map(|instruction| (instruction.code_offset, instruction.srcloc.bits()))
+                            .collect()
+                    }
+                    _ => vec![],
+                };
+                JitDebugFunction {
+                    name: function_name(module, func_index)
+                        .unwrap_or_else(|| format!("wasm-function[{}]", func_index.index())),
+                    address: **ptr as usize,
+                    length: *length,
+                    lines,
+                }
+            })
+            .collect();
+        Some(functions)
+    }
+
+    /// Whether the executable code of this artifact only contains
+    /// relocations relative to the artifact itself, so it can be loaded at
+    /// any address without depending on the host process.
+    ///
+    /// See [`crate::CompilerConfig::enable_pic`].
+    pub fn is_position_independent(&self) -> bool {
+        match &self.artifact {
+            ArtifactBuildVariant::Plain(p) => p.is_position_independent(),
+            ArtifactBuildVariant::Archived(a) => a.is_position_independent(),
+        }
+    }
+
     /// Check if the provided bytes look like a serialized `ArtifactBuild`.
     pub fn is_deserializable(bytes: &[u8]) -> bool {
         ArtifactBuild::is_deserializable(bytes)
     }
+
+    /// Convert a serialized artifact so it can be loaded by the current
+    /// version of Wasmer on the engine target.
+    ///
+    /// If the artifact embeds its original wasm module (see
+    /// [`ArtifactBuild::embed_wasm`]), the module is recompiled with the
+    /// given engine and embedded again in the converted artifact.
+    /// Otherwise, the artifact sections are validated and transcoded, which
+    /// is only possible if it was serialized with the current ABI version
+    /// and the engine target supports its CPU features. As the code of the
+    /// artifact is kept as is, and artifacts don't record the target they
+    /// were compiled for, this is also only possible for the host target.
+    #[cfg(feature = "compiler")]
+    pub fn convert(
+        engine: &Engine,
+        bytes: &[u8],
+        tunables: &dyn Tunables,
+    ) -> Result<Vec<u8>, DeserializeError> {
+        let version = ArtifactBuild::serialized_version(bytes)?;
+
+        if let Some(wasm) = ArtifactBuild::embedded_wasm(bytes) {
+            let artifact = Self::new(engine, wasm, tunables, engine.hash_algorithm())?;
+            let mut converted = artifact
+                .serialize()
+                .map_err(|e| DeserializeError::Generic(e.to_string()))?;
+            ArtifactBuild::embed_wasm(&mut converted, wasm);
+            return Ok(converted);
+        }
+
+        if version != MetadataHeader::CURRENT_VERSION {
+            return Err(DeserializeError::Incompatible(format!(
+                "The artifact was serialized with ABI version {} (current is {}) and does not embed the original wasm module",
+                version,
+                MetadataHeader::CURRENT_VERSION
+            )));
+        }
+
+        if !engine.target().is_native() {
+            return Err(DeserializeError::Incompatible(format!(
+                "The artifact does not embed the original wasm module, so it can't be recompiled for the target {}",
+                engine.target().triple()
+            )));
+        }
+
+        let artifact = ArtifactBuildFromArchive::try_new(bytes.to_vec().into(), |bytes| {
+            let bytes =
+                Self::get_byte_slice(bytes, ArtifactBuild::MAGIC_HEADER.len(), bytes.len())?;
+
+            let metadata_len = MetadataHeader::parse(bytes)?;
+            let metadata_slice = Self::get_byte_slice(bytes, MetadataHeader::LEN, bytes.len())?;
+            let metadata_slice = Self::get_byte_slice(metadata_slice, 0, metadata_len)?;
+
+            SerializableModule::archive_from_slice_checked(metadata_slice)
+        })?;
+
+        let cpu_features = artifact.cpu_features();
+        if !engine.target().cpu_features().is_superset(cpu_features) {
+            return Err(DeserializeError::Incompatible(format!(
+                "Some CPU Features needed for the artifact are missing: {:?}",
+                cpu_features.difference(*engine.target().cpu_features())
+            )));
+        }
+
+        artifact
+            .serialize()
+            .map_err(|e| DeserializeError::Generic(e.to_string()))
+    }
 }
 
 impl PartialEq for Artifact {
@@ -453,7 +658,21 @@ impl<'a> ArtifactCreate<'a> for Artifact {
         <ArtifactBuildVariant as ArtifactCreate<'a>>::OwnedDataInitializerIterator;
 
     fn set_module_info_name(&mut self, name: String) -> bool {
-        self.artifact.set_module_info_name(name)
+        if !self.artifact.set_module_info_name(name) {
+            return false;
+        }
+        // The registered frame information holds its own copy of the module
+        // information, which has to be updated for backtraces to show the
+        // new name.
+        if let Some(allocated) = &self.allocated {
+            if let Some(ptr) = allocated.finished_functions.values().next() {
+                FRAME_INFO
+                    .write()
+                    .unwrap()
+                    .update_module_info(**ptr as usize, self.artifact.create_module_info());
+            }
+        }
+        true
     }
 
     fn create_module_info(&self) -> Arc<ModuleInfo> {
@@ -726,6 +945,44 @@ impl Artifact {
             .finished_functions
     }
 
+    /// Returns the address range of the machine code of each function
+    /// defined in this `Artifact`, so external profilers can map samples
+    /// to functions.
+    ///
+    /// Functions whose code length isn't known, such as the ones of
+    /// artifacts linked into the executable, are skipped.
+    pub fn function_address_ranges(
+        &self,
+    ) -> impl Iterator<Item = (FunctionIndex, Range<usize>)> + '_ {
+        let module_info = self.module_info();
+        self.allocated.iter().flat_map(move |allocated| {
+            allocated
+                .finished_functions
+                .iter()
+                .zip(allocated.finished_function_lengths.values())
+                .filter(|(_, length)| **length > 0)
+                .map(move |((local_index, ptr), length)| {
+                    let start = **ptr as usize;
+                    (module_info.func_index(local_index), start..start + length)
+                })
+        })
+    }
+
+    /// Resolves an address in the machine code of this `Artifact` to the
+    /// function it belongs to and the offset of the corresponding
+    /// instruction in the WebAssembly module.
+    ///
+    /// Returns `None` if the address isn't in a function of this artifact.
+    pub fn symbolicate(&self, pc: usize) -> Option<FrameInfo> {
+        if !self
+            .function_address_ranges()
+            .any(|(_, range)| range.contains(&pc))
+        {
+            return None;
+        }
+        FRAME_INFO.read().unwrap().lookup_frame_info(pc)
+    }
+
     /// Returns the function call trampolines allocated in memory of this
     /// `Artifact`, ready to be run.
     pub fn finished_function_call_trampolines(&self) -> &BoxedSlice<SignatureIndex, VMTrampoline> {
@@ -748,6 +1005,14 @@ impl Artifact {
             .finished_dynamic_function_trampolines
     }
 
+    /// Returns a handle keeping the code memory of this `Artifact` in use,
+    /// if the code was allocated by the engine.
+    pub fn code_memory_lease(&self) -> Option<Arc<CodeMemoryLease>> {
+        self.allocated
+            .as_ref()
+            .and_then(|allocated| allocated.code_memory_lease.clone())
+    }
+
     /// Returns the associated VM signatures for this `Artifact`.
     pub fn signatures(&self) -> &BoxedSlice<SignatureIndex, VMSharedSignatureIndex> {
         &self
@@ -769,6 +1034,11 @@ impl Artifact {
     ///
     /// See [`VMInstance::new`].
     #[allow(clippy::result_large_err)]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(module = %self.create_module_info().name())
+    )]
     pub unsafe fn instantiate(
         &self,
         tunables: &dyn Tunables,
@@ -826,7 +1096,7 @@ impl Artifact {
             .map_err(InstantiationError::Link)?
             .into_boxed_slice();
 
-        let handle = VMInstance::new(
+        let mut handle = VMInstance::new(
             allocator,
             module,
             context,
@@ -839,6 +1109,9 @@ impl Artifact {
             self.signatures().clone(),
         )
         .map_err(InstantiationError::Start)?;
+        if let Some(lease) = self.code_memory_lease() {
+            handle.retain(lease);
+        }
         Ok(handle)
     }
 
@@ -848,6 +1121,11 @@ impl Artifact {
     ///
     /// See [`VMInstance::finish_instantiation`].
     #[allow(clippy::result_large_err)]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(module = %self.create_module_info().name())
+    )]
     pub unsafe fn finish_instantiation(
         &self,
         config: &VMConfig,
@@ -1197,6 +1475,7 @@ impl Artifact {
                     .into_boxed_slice(),
                 signatures: signatures.into_boxed_slice(),
                 finished_function_lengths,
+                code_memory_lease: None,
             }),
         })
     }
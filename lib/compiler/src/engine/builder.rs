@@ -12,6 +12,12 @@ pub struct EngineBuilder {
     features: Option<Features>,
     /// The hashing algorithm
     hash_algorithm: Option<HashAlgorithm>,
+    /// The maximum number of bytes of compiled code
+    code_memory_limit: Option<usize>,
+    /// Whether the compiled code has to behave deterministically
+    deterministic: bool,
+    /// Whether to describe the compiled code to debuggers with a line table
+    debug_info: bool,
 }
 
 impl EngineBuilder {
@@ -25,6 +31,9 @@ impl EngineBuilder {
             target: None,
             features: None,
             hash_algorithm: None,
+            code_memory_limit: None,
+            deterministic: false,
+            debug_info: false,
         }
     }
 
@@ -35,6 +44,9 @@ impl EngineBuilder {
             target: None,
             features: None,
             hash_algorithm: None,
+            code_memory_limit: None,
+            deterministic: false,
+            debug_info: false,
         }
     }
 
@@ -56,28 +68,89 @@ impl EngineBuilder {
         self
     }
 
+    /// Set the maximum number of bytes of compiled code kept alive by the
+    /// engine (see [`Engine::set_code_memory_limit`])
+    pub fn set_code_memory_limit(mut self, code_memory_limit: Option<usize>) -> Self {
+        self.code_memory_limit = code_memory_limit;
+        self
+    }
+
+    /// Describe the compiled code to debuggers with a line table (see
+    /// [`Engine::set_debug_info`])
+    pub fn set_debug_info(mut self, debug_info: bool) -> Self {
+        self.debug_info = debug_info;
+        self
+    }
+
+    /// Make the compiled code behave the same on every host, as needed by
+    /// consensus systems.
+    ///
+    /// This canonicalizes the NaNs produced by floating point operations,
+    /// and disables the WebAssembly features whose behavior depends on the
+    /// host, so that modules using them fail to compile: threads, whose
+    /// shared memories can be raced, and relaxed SIMD. Memories keep the
+    /// default allocation strategy of the target, where they are reserved
+    /// up front and never moved when they grow.
+    ///
+    /// This only applies to the code compiled by the engine: a headless
+    /// engine, which has no compiler, loads the artifacts as they were
+    /// compiled, so they must come from a deterministic engine themselves.
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
     /// Build the `Engine` for this configuration
     #[cfg(feature = "compiler")]
     pub fn engine(self) -> Engine {
         let target = self.target.unwrap_or_default();
-        if let Some(compiler_config) = self.compiler_config {
-            let features = self
+        if let Some(mut compiler_config) = self.compiler_config {
+            let mut features = self
                 .features
                 .unwrap_or_else(|| compiler_config.default_features_for_target(&target));
+            if self.deterministic {
+                compiler_config.canonicalize_nans(true);
+                features.threads(false);
+                features.relaxed_simd = false;
+            }
             let mut engine = Engine::new(compiler_config, target, features);
 
             engine.set_hash_algorithm(self.hash_algorithm);
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                engine.set_code_memory_limit(self.code_memory_limit);
+                engine.set_debug_info(self.debug_info);
+            }
 
             engine
         } else {
-            Engine::headless()
+            if self.deterministic {
+                tracing::warn!("Headless engines can't make the artifacts they load deterministic");
+            }
+            #[allow(unused_mut)]
+            let mut engine = Engine::headless();
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                engine.set_code_memory_limit(self.code_memory_limit);
+                engine.set_debug_info(self.debug_info);
+            }
+            engine
         }
     }
 
     /// Build the `Engine` for this configuration
     #[cfg(not(feature = "compiler"))]
     pub fn engine(self) -> Engine {
-        Engine::headless()
+        if self.deterministic {
+            tracing::warn!("Headless engines can't make the artifacts they load deterministic");
+        }
+        let engine = Engine::headless();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            engine.set_code_memory_limit(self.code_memory_limit);
+            engine.set_debug_info(self.debug_info);
+        }
+        engine
     }
 
     /// The Wasm features
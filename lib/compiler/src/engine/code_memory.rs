@@ -2,8 +2,11 @@
 // Attributions: https://github.com/wasmerio/wasmer/blob/main/docs/ATTRIBUTIONS.md
 
 //! Memory management for executable code.
+#[cfg(feature = "gdb-jit")]
+use super::jit_debug::JitDebugRegistration;
 use super::unwind::UnwindRegistry;
 use crate::GlobalFrameInfoRegistration;
+use std::sync::{Arc, Weak};
 use wasmer_types::{
     compilation::unwind::CompiledFunctionUnwindInfoLike, CompiledFunctionUnwindInfoReference,
     CustomSectionLike, FunctionBodyLike,
@@ -25,9 +28,21 @@ const DATA_SECTION_ALIGNMENT: usize = 64;
 pub struct CodeMemory {
     // frame info is placed first, to ensure it's dropped before the mmap
     frame_info_registration: Option<GlobalFrameInfoRegistration>,
+    #[cfg(feature = "gdb-jit")]
+    jit_debug_registration: Option<JitDebugRegistration>,
     unwind_registry: UnwindRegistry,
     mmap: Mmap,
     start_of_nonexecutable_pages: usize,
+    lease: Option<Weak<CodeMemoryLease>>,
+}
+
+/// A handle keeping a [`CodeMemory`] in use.
+///
+/// It is held by the `Artifact` owning the code and by every instance
+/// created from it, so the engine knows when the code can be released.
+#[derive(Debug)]
+pub struct CodeMemoryLease {
+    _private: (),
 }
 
 impl CodeMemory {
@@ -38,7 +53,33 @@ impl CodeMemory {
             mmap: Mmap::new(),
             start_of_nonexecutable_pages: 0,
             frame_info_registration: None,
+            #[cfg(feature = "gdb-jit")]
+            jit_debug_registration: None,
+            lease: None,
+        }
+    }
+
+    /// The number of bytes allocated for the code and sections.
+    pub fn size(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Get a handle keeping this memory in use.
+    pub fn lease(&mut self) -> Arc<CodeMemoryLease> {
+        if let Some(lease) = self.lease.as_ref().and_then(Weak::upgrade) {
+            return lease;
         }
+        let lease = Arc::new(CodeMemoryLease { _private: () });
+        self.lease = Some(Arc::downgrade(&lease));
+        lease
+    }
+
+    /// Check if this memory was leased and all the leases were dropped,
+    /// meaning it can safely be released.
+    pub fn is_unused(&self) -> bool {
+        self.lease
+            .as_ref()
+            .map_or(false, |lease| lease.strong_count() == 0)
     }
 
     /// Mutably get the UnwindRegistry.
@@ -231,6 +272,12 @@ impl CodeMemory {
     pub fn register_frame_info(&mut self, frame_info: GlobalFrameInfoRegistration) {
         self.frame_info_registration = Some(frame_info);
     }
+
+    /// Register the code with debuggers, until the memory gets freed
+    #[cfg(feature = "gdb-jit")]
+    pub(crate) fn register_jit_debug(&mut self, jit_debug: JitDebugRegistration) {
+        self.jit_debug_registration = Some(jit_debug);
+    }
 }
 
 fn round_up(size: usize, multiple: usize) -> usize {
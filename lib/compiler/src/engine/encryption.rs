@@ -0,0 +1,309 @@
+//! Encryption of serialized artifacts at rest, using AES-256-GCM.
+//!
+//! An encrypted artifact has the following format:
+//! MAGIC_HEADER (16 bytes) + KEY_ID_LEN (1 byte) + KEY_ID (any length)
+//! + NONCE (12 bytes) + CIPHERTEXT (any length) + TAG (16 bytes)
+//!
+//! The header (magic and key identifier) is authenticated together with the
+//! ciphertext, so the key identifier can't be tampered with.
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use thiserror::Error;
+use wasmer_types::{DeserializeError, SerializeError};
+
+/// Header signature for encrypted wasmu binaries
+pub const ENCRYPTED_ARTIFACT_MAGIC_HEADER: &[u8; 16] = b"wasmer-encrypted";
+
+/// The length in bytes of the keys used to encrypt artifacts.
+pub const ARTIFACT_KEY_LEN: usize = 32;
+
+/// An error returned by an [`ArtifactKeyProvider`].
+#[derive(Error, Debug)]
+#[error("unable to get the artifact key `{key_id}`: {message}")]
+pub struct KeyProviderError {
+    /// The identifier of the requested key
+    pub key_id: String,
+    /// What went wrong
+    pub message: String,
+}
+
+impl KeyProviderError {
+    /// Create a new `KeyProviderError`.
+    pub fn new(key_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Provides the keys used to encrypt and decrypt artifacts.
+///
+/// Keys are referred to by an identifier which is stored in clear in the
+/// encrypted artifacts, so providers backed by a key management service
+/// can rotate keys while still being able to load older artifacts.
+pub trait ArtifactKeyProvider {
+    /// The identifier of the key to encrypt new artifacts with.
+    ///
+    /// Identifiers can't be longer than 255 bytes.
+    fn current_key_id(&self) -> &str;
+
+    /// Get the key with the given identifier.
+    fn key(&self, key_id: &str) -> Result<[u8; ARTIFACT_KEY_LEN], KeyProviderError>;
+}
+
+/// A provider for a single key known in advance.
+#[derive(Clone)]
+pub struct StaticKeyProvider {
+    key_id: String,
+    key: [u8; ARTIFACT_KEY_LEN],
+}
+
+impl StaticKeyProvider {
+    /// Create a provider for the given key.
+    pub fn new(key_id: impl Into<String>, key: [u8; ARTIFACT_KEY_LEN]) -> Self {
+        Self {
+            key_id: key_id.into(),
+            key,
+        }
+    }
+}
+
+impl std::fmt::Debug for StaticKeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticKeyProvider")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ArtifactKeyProvider for StaticKeyProvider {
+    fn current_key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn key(&self, key_id: &str) -> Result<[u8; ARTIFACT_KEY_LEN], KeyProviderError> {
+        if key_id != self.key_id {
+            return Err(KeyProviderError::new(key_id, "unknown key"));
+        }
+        Ok(self.key)
+    }
+}
+
+/// A provider reading hex-encoded keys from environment variables.
+///
+/// The key identifiers are the names of the environment variables.
+#[derive(Debug, Clone)]
+pub struct EnvKeyProvider {
+    var: String,
+}
+
+impl EnvKeyProvider {
+    /// Create a provider encrypting artifacts with the key stored in the
+    /// environment variable `var`.
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl ArtifactKeyProvider for EnvKeyProvider {
+    fn current_key_id(&self) -> &str {
+        &self.var
+    }
+
+    fn key(&self, key_id: &str) -> Result<[u8; ARTIFACT_KEY_LEN], KeyProviderError> {
+        let value =
+            std::env::var(key_id).map_err(|e| KeyProviderError::new(key_id, e.to_string()))?;
+        let mut key = [0; ARTIFACT_KEY_LEN];
+        hex::decode_to_slice(value.trim(), &mut key)
+            .map_err(|e| KeyProviderError::new(key_id, e.to_string()))?;
+        Ok(key)
+    }
+}
+
+/// A provider delegating to a callback, for instance to fetch keys from a
+/// key management service.
+#[derive(Clone)]
+pub struct CallbackKeyProvider<F> {
+    key_id: String,
+    callback: F,
+}
+
+impl<F> CallbackKeyProvider<F>
+where
+    F: Fn(&str) -> Result<[u8; ARTIFACT_KEY_LEN], KeyProviderError>,
+{
+    /// Create a provider encrypting artifacts with the key `key_id`, and
+    /// getting keys with `callback`.
+    pub fn new(key_id: impl Into<String>, callback: F) -> Self {
+        Self {
+            key_id: key_id.into(),
+            callback,
+        }
+    }
+}
+
+impl<F> std::fmt::Debug for CallbackKeyProvider<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackKeyProvider")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F> ArtifactKeyProvider for CallbackKeyProvider<F>
+where
+    F: Fn(&str) -> Result<[u8; ARTIFACT_KEY_LEN], KeyProviderError>,
+{
+    fn current_key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn key(&self, key_id: &str) -> Result<[u8; ARTIFACT_KEY_LEN], KeyProviderError> {
+        (self.callback)(key_id)
+    }
+}
+
+/// Check if the provided bytes look like an encrypted artifact.
+pub fn is_encrypted_artifact(bytes: &[u8]) -> bool {
+    bytes.starts_with(ENCRYPTED_ARTIFACT_MAGIC_HEADER)
+}
+
+fn aead_key(key: &[u8; ARTIFACT_KEY_LEN]) -> LessSafeKey {
+    // Only fails if the key length doesn't match the algorithm.
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).unwrap())
+}
+
+/// Encrypt a serialized artifact with the current key of `provider`.
+pub fn encrypt_artifact(
+    serialized: &[u8],
+    provider: &dyn ArtifactKeyProvider,
+) -> Result<Vec<u8>, SerializeError> {
+    let key_id = provider.current_key_id();
+    let key_id_len = u8::try_from(key_id.len()).map_err(|_| {
+        SerializeError::Generic(format!("the artifact key id `{}` is too long", key_id))
+    })?;
+    let key = provider
+        .key(key_id)
+        .map_err(|e| SerializeError::Generic(e.to_string()))?;
+
+    let mut nonce = [0; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| SerializeError::Generic("unable to generate a nonce".to_string()))?;
+
+    let mut encrypted = Vec::with_capacity(
+        ENCRYPTED_ARTIFACT_MAGIC_HEADER.len()
+            + 1
+            + key_id.len()
+            + NONCE_LEN
+            + serialized.len()
+            + AES_256_GCM.tag_len(),
+    );
+    encrypted.extend_from_slice(ENCRYPTED_ARTIFACT_MAGIC_HEADER);
+    encrypted.push(key_id_len);
+    encrypted.extend_from_slice(key_id.as_bytes());
+    let header_len = encrypted.len();
+    encrypted.extend_from_slice(&nonce);
+
+    let mut in_out = serialized.to_vec();
+    aead_key(&key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&encrypted[..header_len]),
+            &mut in_out,
+        )
+        .map_err(|_| SerializeError::Generic("unable to encrypt the artifact".to_string()))?;
+    encrypted.extend_from_slice(&in_out);
+    Ok(encrypted)
+}
+
+/// Decrypt an artifact encrypted with [`encrypt_artifact`], getting the key
+/// it was encrypted with from `provider`.
+pub fn decrypt_artifact(
+    bytes: &[u8],
+    provider: &dyn ArtifactKeyProvider,
+) -> Result<Vec<u8>, DeserializeError> {
+    if !is_encrypted_artifact(bytes) {
+        return Err(DeserializeError::Incompatible(
+            "The provided bytes are not an encrypted artifact".to_string(),
+        ));
+    }
+    let truncated = || DeserializeError::CorruptedBinary("truncated encrypted artifact".into());
+
+    let rest = &bytes[ENCRYPTED_ARTIFACT_MAGIC_HEADER.len()..];
+    let (&key_id_len, rest) = rest.split_first().ok_or_else(truncated)?;
+    let key_id_len = key_id_len as usize;
+    if rest.len() < key_id_len + NONCE_LEN {
+        return Err(truncated());
+    }
+    let (key_id, rest) = rest.split_at(key_id_len);
+    let key_id = std::str::from_utf8(key_id)
+        .map_err(|_| DeserializeError::CorruptedBinary("invalid artifact key id".into()))?;
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let header_len = ENCRYPTED_ARTIFACT_MAGIC_HEADER.len() + 1 + key_id_len;
+
+    let key = provider
+        .key(key_id)
+        .map_err(|e| DeserializeError::Generic(e.to_string()))?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext_len = aead_key(&key)
+        .open_in_place(
+            // The length was checked above.
+            Nonce::try_assume_unique_for_key(nonce).unwrap(),
+            Aad::from(&bytes[..header_len]),
+            &mut in_out,
+        )
+        .map_err(|_| {
+            DeserializeError::CorruptedBinary(
+                "unable to decrypt the artifact: wrong key or tampered data".to_string(),
+            )
+        })?
+        .len();
+    in_out.truncate(plaintext_len);
+    Ok(in_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let provider = StaticKeyProvider::new("edge-2024", [7; ARTIFACT_KEY_LEN]);
+        let encrypted = encrypt_artifact(b"wasmer-universal...", &provider).unwrap();
+        assert!(is_encrypted_artifact(&encrypted));
+        let decrypted = decrypt_artifact(&encrypted, &provider).unwrap();
+        assert_eq!(decrypted, b"wasmer-universal...");
+    }
+
+    #[test]
+    fn decrypt_with_wrong_key_fails() {
+        let provider = StaticKeyProvider::new("edge-2024", [7; ARTIFACT_KEY_LEN]);
+        let encrypted = encrypt_artifact(b"wasmer-universal...", &provider).unwrap();
+
+        let other = StaticKeyProvider::new("edge-2024", [8; ARTIFACT_KEY_LEN]);
+        assert!(matches!(
+            decrypt_artifact(&encrypted, &other),
+            Err(DeserializeError::CorruptedBinary(_))
+        ));
+
+        let callback = CallbackKeyProvider::new("edge-2024", |key_id: &str| {
+            Err(KeyProviderError::new(key_id, "revoked"))
+        });
+        assert!(matches!(
+            decrypt_artifact(&encrypted, &callback),
+            Err(DeserializeError::Generic(_))
+        ));
+    }
+
+    #[test]
+    fn tampered_key_id_is_rejected() {
+        let provider = CallbackKeyProvider::new("a", |_: &str| Ok([1; ARTIFACT_KEY_LEN]));
+        let mut encrypted = encrypt_artifact(b"wasmer-universal...", &provider).unwrap();
+        encrypted[ENCRYPTED_ARTIFACT_MAGIC_HEADER.len() + 1] = b'b';
+        assert!(decrypt_artifact(&encrypted, &provider).is_err());
+    }
+}
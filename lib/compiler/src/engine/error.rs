@@ -1,4 +1,5 @@
 //! The WebAssembly possible errors
+use std::fmt::Write;
 use thiserror::Error;
 pub use wasmer_types::ImportError;
 #[cfg(not(target_arch = "wasm32"))]
@@ -17,6 +18,10 @@ pub enum LinkError {
     #[error("Error while importing {0:?}.{1:?}: {2}")]
     Import(String, String, ImportError),
 
+    /// Several imports are missing or have incompatible types.
+    #[error("{} imports can't be satisfied:{}", .0.len(), display_import_errors(.0))]
+    Imports(Vec<(String, String, ImportError)>),
+
     #[cfg(not(target_arch = "wasm32"))]
     /// A trap ocurred during linking.
     #[error("Trap occurred during linking: {0}")]
@@ -27,6 +32,28 @@ pub enum LinkError {
     Resource(String),
 }
 
+impl LinkError {
+    /// Creates an error for imports which can't be satisfied, with the
+    /// `Import` variant if there is only one of them.
+    pub fn from_import_errors(mut errors: Vec<(String, String, ImportError)>) -> Self {
+        if errors.len() == 1 {
+            let (module, name, error) = errors.remove(0);
+            Self::Import(module, name, error)
+        } else {
+            Self::Imports(errors)
+        }
+    }
+}
+
+/// Formats import errors with one import per line.
+pub fn display_import_errors(errors: &[(String, String, ImportError)]) -> String {
+    let mut out = String::new();
+    for (module, name, error) in errors {
+        let _ = write!(out, "\n  {module:?}.{name:?}: {error}");
+    }
+    out
+}
+
 /// An error while instantiating a module.
 ///
 /// This is not a common WebAssembly error, however
@@ -5,10 +5,15 @@ use crate::engine::builder::EngineBuilder;
 use crate::Artifact;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::BaseTunables;
+#[cfg(feature = "gdb-jit")]
 #[cfg(not(target_arch = "wasm32"))]
-use crate::CodeMemory;
+use crate::engine::jit_debug::JitDebugRegistration;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::engine::profiling::{Profiler, ProfilingStrategy};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::GlobalFrameInfoRegistration;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{CodeMemory, CodeMemoryLease};
 #[cfg(feature = "compiler")]
 use crate::{Compiler, CompilerConfig};
 #[cfg(not(target_arch = "wasm32"))]
@@ -17,18 +22,22 @@ use crate::{FunctionExtent, Tunables};
 use shared_buffer::OwnedBuffer;
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use std::sync::{Arc, Mutex};
 use wasmer_types::HashAlgorithm;
 #[cfg(not(target_arch = "wasm32"))]
 use wasmer_types::{
     entity::PrimaryMap, DeserializeError, FunctionBodyLike, FunctionIndex, FunctionType,
-    LocalFunctionIndex, SignatureIndex,
+    LocalFunctionIndex, ModuleHash, SignatureIndex,
 };
 use wasmer_types::{CompileError, Features, ModuleInfo, Target};
 #[cfg(not(target_arch = "wasm32"))]
 use wasmer_types::{CustomSectionLike, CustomSectionProtection, SectionIndex};
 #[cfg(not(target_arch = "wasm32"))]
+use wasmer_types::{MemoryType, Pages, TableType, Type};
+#[cfg(not(target_arch = "wasm32"))]
 use wasmer_vm::{
     FunctionBodyPtr, SectionBodyPtr, SignatureRegistry, VMFunctionBody, VMSharedSignatureIndex,
     VMTrampoline,
@@ -44,7 +53,11 @@ pub struct Engine {
     #[cfg(not(target_arch = "wasm32"))]
     tunables: Arc<dyn Tunables + Send + Sync>,
     name: String,
+    deterministic_id: String,
     hash_algorithm: Option<HashAlgorithm>,
+    /// The epoch counter, shared by all the stores of this engine.
+    #[cfg(not(target_arch = "wasm32"))]
+    epoch: Arc<AtomicU64>,
 }
 
 impl Engine {
@@ -59,7 +72,7 @@ impl Engine {
         let tunables = BaseTunables::for_target(&target);
         let compiler = compiler_config.compiler();
         let name = format!("engine-{}", compiler.name());
-        Self {
+        let mut engine = Self {
             inner: Arc::new(Mutex::new(EngineInner {
                 compiler: Some(compiler),
                 features,
@@ -67,14 +80,29 @@ impl Engine {
                 code_memory: vec![],
                 #[cfg(not(target_arch = "wasm32"))]
                 signatures: SignatureRegistry::new(),
+                #[cfg(not(target_arch = "wasm32"))]
+                code_memory_limit: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                debug_info: false,
+                #[cfg(not(target_arch = "wasm32"))]
+                profiler: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                coredump_on_trap: false,
+                #[cfg(not(target_arch = "wasm32"))]
+                artifact_cache: vec![],
             })),
             target: Arc::new(target),
             engine_id: EngineId::default(),
             #[cfg(not(target_arch = "wasm32"))]
             tunables: Arc::new(tunables),
             name,
+            deterministic_id: String::new(),
             hash_algorithm: None,
-        }
+            #[cfg(not(target_arch = "wasm32"))]
+            epoch: Arc::new(AtomicU64::new(0)),
+        };
+        engine.update_deterministic_id();
+        engine
     }
 
     #[cfg(not(feature = "compiler"))]
@@ -102,12 +130,50 @@ impl Engine {
     }
 
     /// Returns the deterministic id of this engine
+    ///
+    /// The id changes with the version of Wasmer, the configuration of the
+    /// compiler, the enabled features, the tunables and the target, so that
+    /// artifacts cached under it are only loaded by compatible engines.
     pub fn deterministic_id(&self) -> &str {
-        // TODO: add a `deterministic_id` to the Compiler, so two
-        // compilers can actually serialize into a different deterministic_id
-        // if their configuration is different (eg. LLVM with optimizations vs LLVM
-        // without optimizations)
-        self.name.as_str()
+        self.deterministic_id.as_str()
+    }
+
+    fn update_deterministic_id(&mut self) {
+        let mut description = format!("wasmer-{}", env!("CARGO_PKG_VERSION"));
+        let mut cpu_features = *self.target.cpu_features();
+        {
+            let inner = self.inner();
+            #[cfg(feature = "compiler")]
+            {
+                if let Some(compiler) = inner.compiler.as_ref() {
+                    description.push_str(&format!(" {}", compiler.deterministic_id()));
+                    cpu_features = compiler.get_cpu_features_used(&cpu_features);
+                }
+                description.push_str(&format!(" {:?}", inner.features));
+            }
+            #[cfg(not(feature = "compiler"))]
+            let _ = inner;
+        }
+        description.push_str(&format!(" {} {:?}", self.target.triple(), cpu_features));
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Tunables can't be compared, so describe how they handle typical
+            // memories and tables instead
+            let memories = [
+                MemoryType::new(Pages(1), None, false),
+                MemoryType::new(Pages(1), Some(Pages::max_value()), false),
+                MemoryType::new(Pages(1), Some(Pages::max_value()), true),
+            ];
+            for memory in &memories {
+                let style = self.tunables.memory_style(memory);
+                description.push_str(&format!(" {:?}", style));
+            }
+            let table = TableType::new(Type::FuncRef, 1, None);
+            description.push_str(&format!(" {:?}", self.tunables.table_style(&table)));
+        }
+
+        let hash = xxhash_rust::xxh64::xxh64(description.as_bytes(), 0);
+        self.deterministic_id = format!("{}-{:016x}", self.name, hash);
     }
 
     /// Create a headless `Engine`
@@ -127,7 +193,7 @@ impl Engine {
         let target = Target::default();
         #[cfg(not(target_arch = "wasm32"))]
         let tunables = BaseTunables::for_target(&target);
-        Self {
+        let mut engine = Self {
             inner: Arc::new(Mutex::new(EngineInner {
                 #[cfg(feature = "compiler")]
                 compiler: None,
@@ -137,14 +203,29 @@ impl Engine {
                 code_memory: vec![],
                 #[cfg(not(target_arch = "wasm32"))]
                 signatures: SignatureRegistry::new(),
+                #[cfg(not(target_arch = "wasm32"))]
+                code_memory_limit: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                debug_info: false,
+                #[cfg(not(target_arch = "wasm32"))]
+                profiler: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                coredump_on_trap: false,
+                #[cfg(not(target_arch = "wasm32"))]
+                artifact_cache: vec![],
             })),
             target: Arc::new(target),
             engine_id: EngineId::default(),
             #[cfg(not(target_arch = "wasm32"))]
             tunables: Arc::new(tunables),
             name: "engine-headless".to_string(),
+            deterministic_id: String::new(),
             hash_algorithm: None,
-        }
+            #[cfg(not(target_arch = "wasm32"))]
+            epoch: Arc::new(AtomicU64::new(0)),
+        };
+        engine.update_deterministic_id();
+        engine
     }
 
     /// Get reference to `EngineInner`.
@@ -183,15 +264,128 @@ impl Engine {
     }
 
     /// Compile a WebAssembly binary
+    ///
+    /// If a code memory limit is set (see [`Self::set_code_memory_limit`]),
+    /// compiled artifacts are kept in a cache and reused when the same
+    /// binary is compiled again, until they get evicted.
     #[cfg(feature = "compiler")]
     #[cfg(not(target_arch = "wasm32"))]
+    #[tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(engine = %self.name, bytes = binary.len())
+    )]
     pub fn compile(&self, binary: &[u8]) -> Result<Arc<Artifact>, CompileError> {
-        Ok(Arc::new(Artifact::new(
+        let cache_key = self.inner().code_memory_limit.map(|_| ArtifactCacheKey {
+            hash: ModuleHash::sha256(binary),
+            tunables: Arc::as_ptr(&self.tunables) as *const () as usize,
+        });
+        if let Some(key) = &cache_key {
+            if let Some(artifact) = self.inner_mut().cached_artifact(key) {
+                tracing::debug!("reusing the cached artifact");
+                return Ok(artifact);
+            }
+        }
+
+        let artifact = Arc::new(Artifact::new(
             self,
             binary,
             self.tunables.as_ref(),
             self.hash_algorithm,
-        )?))
+        )?);
+
+        if let Some(key) = cache_key {
+            self.inner_mut()
+                .artifact_cache
+                .push((key, artifact.clone()));
+        }
+        Ok(artifact)
+    }
+
+    /// Limit the total number of bytes of compiled code kept alive by this
+    /// engine, or remove the limit with `None`.
+    ///
+    /// When the limit is reached, the code of modules that are not used
+    /// anymore (no `Module` nor instance refers to them) is released, least
+    /// recently used first. If that's not enough, compiling or loading a new
+    /// module fails with a [`CompileError::Resource`].
+    ///
+    /// Note that modules compiled from the same binary share their
+    /// artifact while it is cached, so they can't be renamed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_code_memory_limit(&self, limit: Option<usize>) {
+        let mut inner = self.inner_mut();
+        inner.code_memory_limit = limit;
+        if limit.is_none() {
+            inner.artifact_cache.clear();
+        }
+    }
+
+    /// Returns the code memory limit of this engine, if any.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn code_memory_limit(&self) -> Option<usize> {
+        self.inner().code_memory_limit
+    }
+
+    /// Returns the total number of bytes of compiled code kept alive by
+    /// this engine.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn code_memory_size(&self) -> usize {
+        self.inner().code_memory_size()
+    }
+
+    /// Describe the code compiled from now on to native debuggers.
+    ///
+    /// The functions of every loaded module are registered with the GDB JIT
+    /// interface, so GDB and LLDB show their names in backtraces, and the
+    /// offsets of the instructions in the WebAssembly binary as line
+    /// numbers. This needs the `gdb-jit` feature, and does nothing without
+    /// it.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_debug_info(&self, debug_info: bool) {
+        self.inner_mut().debug_info = debug_info;
+    }
+
+    /// Whether the compiled code is described to debuggers with a line
+    /// table, see [`Self::set_debug_info`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn debug_info(&self) -> bool {
+        self.inner().debug_info
+    }
+
+    /// Report the functions compiled from now on to profilers with the
+    /// given strategies, or stop reporting them with an empty slice.
+    ///
+    /// This lets `perf` attribute samples to the functions of the guest,
+    /// named after the `name` section of their module. It's only supported
+    /// on Linux, and fails if the files can't be created.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_profiling(&self, strategies: &[ProfilingStrategy]) -> std::io::Result<()> {
+        let profiler = if strategies.is_empty() {
+            None
+        } else {
+            Some(Arc::new(Profiler::new(strategies)?))
+        };
+        self.inner_mut().profiler = profiler;
+        Ok(())
+    }
+
+    /// Capture a coredump of the instance when a WebAssembly trap reaches
+    /// the host: the call stack, and the contents of the memories and
+    /// globals of the instance, as they were when the trap happened.
+    ///
+    /// Capturing a coredump copies the memories of the instance, so this is
+    /// disabled by default.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_coredump_on_trap(&self, coredump_on_trap: bool) {
+        self.inner_mut().coredump_on_trap = coredump_on_trap;
+    }
+
+    /// Whether a coredump is captured when a trap reaches the host, see
+    /// [`Self::set_coredump_on_trap`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn coredump_on_trap(&self) -> bool {
+        self.inner().coredump_on_trap
     }
 
     /// Compile a WebAssembly binary
@@ -207,6 +401,42 @@ impl Engine {
         ))
     }
 
+    /// Validate and compile a WebAssembly binary straight into serialized
+    /// artifact bytes, without loading it.
+    ///
+    /// The result can be deserialized later on, like the output of
+    /// `Module::serialize`.
+    #[cfg(feature = "compiler")]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn precompile_module(&self, binary: &[u8]) -> Result<Vec<u8>, CompileError> {
+        self.precompile_module_for(binary, &self.target, self.tunables.as_ref())
+    }
+
+    /// Like [`Self::precompile_module`], but compiling for the given
+    /// target and tunables instead of the ones of this engine.
+    ///
+    /// This allows cross-compiling artifacts for other platforms.
+    #[cfg(feature = "compiler")]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn precompile_module_for(
+        &self,
+        binary: &[u8],
+        target: &Target,
+        tunables: &dyn Tunables,
+    ) -> Result<Vec<u8>, CompileError> {
+        Artifact::precompile(self, binary, target, tunables, self.hash_algorithm)
+    }
+
+    /// Convert a serialized artifact, possibly produced by another Wasmer
+    /// release or for another target, into one for this engine.
+    ///
+    /// See [`Artifact::convert`].
+    #[cfg(feature = "compiler")]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn convert_artifact(&self, bytes: &[u8]) -> Result<Vec<u8>, DeserializeError> {
+        Artifact::convert(self, bytes, self.tunables.as_ref())
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     /// Deserializes a WebAssembly module which was previously serialized with
     /// [`Module::serialize`].
@@ -280,10 +510,34 @@ impl Engine {
         self.clone()
     }
 
+    /// Checks whether two engines are identical, that is, whether one is a
+    /// clone of the other. Identical engines share their compiled code and
+    /// signatures, so the artifacts of one can be used with the other.
+    pub fn same(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.inner, &b.inner)
+    }
+
+    /// Increment the epoch counter of this engine.
+    ///
+    /// Code compiled with epoch interruption traps once the epoch reaches
+    /// the deadline of its store. This is cheap and can be called from any
+    /// thread, for instance from a timer.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn increment_epoch(&self) {
+        self.epoch.fetch_add(1, SeqCst);
+    }
+
+    /// The epoch counter of this engine.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn epoch_counter(&self) -> &Arc<AtomicU64> {
+        &self.epoch
+    }
+
     /// Attach a Tunable to this engine
     #[cfg(not(target_arch = "wasm32"))]
     pub fn set_tunables(&mut self, tunables: impl Tunables + Send + Sync + 'static) {
         self.tunables = Arc::new(tunables);
+        self.update_deterministic_id();
     }
 
     /// Get a reference to attached Tunable of this engine
@@ -319,6 +573,32 @@ pub struct EngineInner {
     /// performantly.
     #[cfg(not(target_arch = "wasm32"))]
     signatures: SignatureRegistry,
+    /// The maximum number of bytes of code memory, if any.
+    #[cfg(not(target_arch = "wasm32"))]
+    code_memory_limit: Option<usize>,
+    /// Artifacts compiled while a code memory limit is set, least recently
+    /// used first.
+    #[cfg(not(target_arch = "wasm32"))]
+    artifact_cache: Vec<(ArtifactCacheKey, Arc<Artifact>)>,
+    /// Whether to describe the compiled code to debuggers with a line table.
+    #[cfg(not(target_arch = "wasm32"))]
+    debug_info: bool,
+    /// Where to report the compiled code for profiling, if anywhere.
+    #[cfg(not(target_arch = "wasm32"))]
+    profiler: Option<Arc<Profiler>>,
+    /// Whether to capture a coredump when a trap reaches the host.
+    #[cfg(not(target_arch = "wasm32"))]
+    coredump_on_trap: bool,
+}
+
+/// The key of a compiled artifact in the engine cache.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ArtifactCacheKey {
+    hash: ModuleHash,
+    // Artifacts depend on the tunables they were compiled with, which are
+    // not shared between clones of an engine.
+    tunables: usize,
 }
 
 impl EngineInner {
@@ -437,6 +717,11 @@ impl EngineInner {
             })
             .collect::<PrimaryMap<SectionIndex, _>>();
 
+        if let Err(e) = self.enforce_code_memory_limit() {
+            self.code_memory.pop();
+            return Err(e);
+        }
+
         Ok((
             allocated_functions_result,
             allocated_function_call_trampolines,
@@ -445,6 +730,62 @@ impl EngineInner {
         ))
     }
 
+    /// The total number of bytes of code memory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn code_memory_size(&self) -> usize {
+        self.code_memory.iter().map(CodeMemory::size).sum()
+    }
+
+    /// Release unused code memory until the code memory limit is honored,
+    /// evicting the least recently used cached artifacts if needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn enforce_code_memory_limit(&mut self) -> Result<(), CompileError> {
+        let limit = match self.code_memory_limit {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        loop {
+            self.code_memory.retain(|memory| !memory.is_unused());
+            let size = self.code_memory_size();
+            if size <= limit {
+                return Ok(());
+            }
+            // Only the cache refers to an artifact when its strong count is 1.
+            match self
+                .artifact_cache
+                .iter()
+                .position(|(_, artifact)| Arc::strong_count(artifact) == 1)
+            {
+                Some(index) => {
+                    tracing::debug!(limit, size, "evicting a cached artifact");
+                    self.artifact_cache.remove(index);
+                }
+                None => {
+                    return Err(CompileError::Resource(format!(
+                        "the code memory limit of {} bytes was exceeded ({} bytes in use)",
+                        limit, size
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Get a cached artifact, marking it as the most recently used.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn cached_artifact(&mut self, key: &ArtifactCacheKey) -> Option<Arc<Artifact>> {
+        let index = self.artifact_cache.iter().position(|(k, _)| k == key)?;
+        let entry = self.artifact_cache.remove(index);
+        let artifact = entry.1.clone();
+        self.artifact_cache.push(entry);
+        Some(artifact)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Get a handle keeping the last allocated code memory in use.
+    pub(crate) fn lease_code_memory(&mut self) -> Arc<CodeMemoryLease> {
+        self.code_memory.last_mut().unwrap().lease()
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     /// Make memory containing compiled code executable.
     pub(crate) fn publish_compiled_code(&mut self) {
@@ -479,6 +820,28 @@ impl EngineInner {
             .unwrap()
             .register_frame_info(frame_info);
     }
+
+    /// Whether to describe the compiled code to debuggers with a line table.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn debug_info(&self) -> bool {
+        self.debug_info
+    }
+
+    /// Where to report the compiled code for profiling, if anywhere.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_deref()
+    }
+
+    #[cfg(feature = "gdb-jit")]
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Register the code memory with debuggers
+    pub(crate) fn register_jit_debug(&mut self, jit_debug: JitDebugRegistration) {
+        self.code_memory
+            .last_mut()
+            .unwrap()
+            .register_jit_debug(jit_debug);
+    }
 }
 
 #[cfg(feature = "compiler")]
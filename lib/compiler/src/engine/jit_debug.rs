@@ -0,0 +1,36 @@
+//! Registration of compiled code with the GDB JIT interface.
+//!
+//! Native debuggers such as GDB and LLDB know nothing about code compiled
+//! at runtime. Instead, they set a breakpoint in `__jit_debug_register_code`
+//! and walk `__jit_debug_descriptor`, a list of in-memory object files
+//! describing the JIT code. When debug info is enabled, for every loaded
+//! module we hand them a minimal ELF file containing the symbols of its
+//! functions and a DWARF line table mapping the machine code to the offsets
+//! of the instructions in the WebAssembly binary.
+//!
+//! The interface is only built with the `gdb-jit` feature, as these symbols
+//! must be defined once per process and would clash with the ones of other
+//! JIT compilers linked in the same binary, such as LLVM.
+//!
+//! See <https://sourceware.org/gdb/current/onlinedocs/gdb.html/JIT-Interface.html>.
+
+/// A compiled function to describe to debuggers.
+pub(crate) struct JitDebugFunction {
+    /// The name of the function.
+    pub name: String,
+    /// The address of the first byte of the function.
+    pub address: usize,
+    /// The length in bytes of the function.
+    pub length: usize,
+    /// The code offsets of the instructions coming from the WebAssembly
+    /// code, with their offset in the WebAssembly binary, sorted by code
+    /// offset. Empty without debug info.
+    #[cfg_attr(not(feature = "gdb-jit"), allow(dead_code))]
+    pub lines: Vec<(usize, u32)>,
+}
+
+#[cfg(feature = "gdb-jit")]
+mod gdb;
+
+#[cfg(feature = "gdb-jit")]
+pub(crate) use self::gdb::{register, JitDebugRegistration};
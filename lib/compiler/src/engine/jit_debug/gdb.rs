@@ -0,0 +1,501 @@
+//! The GDB JIT interface, see the parent module.
+
+use std::ptr;
+use std::sync::Mutex;
+
+use super::JitDebugFunction;
+
+const JIT_NOACTION: u32 = 0;
+const JIT_REGISTER_FN: u32 = 1;
+const JIT_UNREGISTER_FN: u32 = 2;
+
+#[repr(C)]
+struct JitCodeEntry {
+    next_entry: *mut JitCodeEntry,
+    prev_entry: *mut JitCodeEntry,
+    symfile_addr: *const u8,
+    symfile_size: u64,
+}
+
+#[repr(C)]
+struct JitDescriptor {
+    version: u32,
+    action_flag: u32,
+    relevant_entry: *mut JitCodeEntry,
+    first_entry: *mut JitCodeEntry,
+}
+
+/// The list of object files read by debuggers.
+#[no_mangle]
+#[allow(non_upper_case_globals)]
+static mut __jit_debug_descriptor: JitDescriptor = JitDescriptor {
+    version: 1,
+    action_flag: JIT_NOACTION,
+    relevant_entry: ptr::null_mut(),
+    first_entry: ptr::null_mut(),
+};
+
+/// Debuggers put a breakpoint here to be notified when the list of object
+/// files in `__jit_debug_descriptor` changes.
+#[no_mangle]
+#[inline(never)]
+extern "C" fn __jit_debug_register_code() {
+    // Keep the call from being optimized away.
+    unsafe { ptr::read_volatile(&0u8) };
+}
+
+/// Serializes the updates of `__jit_debug_descriptor`.
+static DESCRIPTOR_LOCK: Mutex<()> = Mutex::new(());
+
+/// An RAII structure unregistering the code of a module from debuggers
+/// when it's dropped.
+pub struct JitDebugRegistration {
+    entry: Box<JitCodeEntry>,
+    // The object file the entry points to.
+    _object: Box<[u8]>,
+}
+
+// The entry is only accessed by debuggers and under `DESCRIPTOR_LOCK`.
+unsafe impl Send for JitDebugRegistration {}
+unsafe impl Sync for JitDebugRegistration {}
+
+impl Drop for JitDebugRegistration {
+    fn drop(&mut self) {
+        let _guard = DESCRIPTOR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let entry: *mut JitCodeEntry = &mut *self.entry;
+        unsafe {
+            let descriptor = ptr::addr_of_mut!(__jit_debug_descriptor);
+            let next = (*entry).next_entry;
+            let prev = (*entry).prev_entry;
+            if !next.is_null() {
+                (*next).prev_entry = prev;
+            }
+            if prev.is_null() {
+                (*descriptor).first_entry = next;
+            } else {
+                (*prev).next_entry = next;
+            }
+            (*descriptor).relevant_entry = entry;
+            (*descriptor).action_flag = JIT_UNREGISTER_FN;
+            __jit_debug_register_code();
+            (*descriptor).action_flag = JIT_NOACTION;
+            (*descriptor).relevant_entry = ptr::null_mut();
+        }
+    }
+}
+
+/// Registers the functions of a module with debuggers.
+///
+/// Returns `None` if there's nothing to register or if the host doesn't use
+/// ELF object files.
+pub(crate) fn register(
+    module_name: &str,
+    functions: &[JitDebugFunction],
+) -> Option<JitDebugRegistration> {
+    let object = build_object(module_name, functions)?.into_boxed_slice();
+    let mut entry = Box::new(JitCodeEntry {
+        next_entry: ptr::null_mut(),
+        prev_entry: ptr::null_mut(),
+        symfile_addr: object.as_ptr(),
+        symfile_size: object.len() as u64,
+    });
+
+    let _guard = DESCRIPTOR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let entry_ptr: *mut JitCodeEntry = &mut *entry;
+    unsafe {
+        let descriptor = ptr::addr_of_mut!(__jit_debug_descriptor);
+        let first = (*descriptor).first_entry;
+        (*entry_ptr).next_entry = first;
+        if !first.is_null() {
+            (*first).prev_entry = entry_ptr;
+        }
+        (*descriptor).first_entry = entry_ptr;
+        (*descriptor).relevant_entry = entry_ptr;
+        (*descriptor).action_flag = JIT_REGISTER_FN;
+        __jit_debug_register_code();
+        (*descriptor).action_flag = JIT_NOACTION;
+        (*descriptor).relevant_entry = ptr::null_mut();
+    }
+
+    Some(JitDebugRegistration {
+        entry,
+        _object: object,
+    })
+}
+
+/// The ELF machine of the host, if it uses ELF object files.
+fn elf_machine() -> Option<u16> {
+    if cfg!(any(not(unix), target_vendor = "apple")) {
+        None
+    } else if cfg!(target_arch = "x86_64") {
+        Some(62) // EM_X86_64
+    } else if cfg!(target_arch = "aarch64") {
+        Some(183) // EM_AARCH64
+    } else {
+        None
+    }
+}
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_NOBITS: u32 = 8;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+
+const ELF_HEADER_SIZE: usize = 64;
+const SECTION_HEADER_SIZE: usize = 64;
+const SYMBOL_SIZE: usize = 24;
+
+/// A section of the object file being built.
+struct Section {
+    name: &'static str,
+    kind: u32,
+    flags: u64,
+    address: u64,
+    data: Vec<u8>,
+    // The size of `SHT_NOBITS` sections, which have no data.
+    size: u64,
+    link: u32,
+    info: u32,
+    entry_size: u64,
+}
+
+impl Section {
+    fn new(name: &'static str, kind: u32, data: Vec<u8>) -> Self {
+        let size = data.len() as u64;
+        Self {
+            name,
+            kind,
+            flags: 0,
+            address: 0,
+            data,
+            size,
+            link: 0,
+            info: 0,
+            entry_size: 0,
+        }
+    }
+}
+
+/// Builds an executable ELF file describing the functions at their actual
+/// addresses, so debuggers don't need to relocate anything.
+fn build_object(module_name: &str, functions: &[JitDebugFunction]) -> Option<Vec<u8>> {
+    let machine = elf_machine()?;
+    let start = functions.iter().map(|f| f.address).min()?;
+    let end = functions.iter().map(|f| f.address + f.length).max()?;
+
+    // Section indices, in the order they are pushed below.
+    const TEXT: u16 = 1;
+    const STRTAB: u32 = 3;
+
+    let mut text = Section::new(".text", SHT_NOBITS, vec![]);
+    text.flags = SHF_ALLOC | SHF_EXECINSTR;
+    text.address = start as u64;
+    text.size = (end - start) as u64;
+
+    let mut strtab = vec![0];
+    let mut symtab = vec![0; SYMBOL_SIZE];
+    for function in functions {
+        let name = strtab.len() as u32;
+        strtab.extend_from_slice(function.name.as_bytes());
+        strtab.push(0);
+        symtab.extend_from_slice(&name.to_ne_bytes());
+        // STB_GLOBAL, STT_FUNC
+        symtab.push((1 << 4) | 2);
+        symtab.push(0);
+        symtab.extend_from_slice(&TEXT.to_ne_bytes());
+        symtab.extend_from_slice(&(function.address as u64).to_ne_bytes());
+        symtab.extend_from_slice(&(function.length as u64).to_ne_bytes());
+    }
+    let mut symtab = Section::new(".symtab", SHT_SYMTAB, symtab);
+    symtab.link = STRTAB;
+    // The index of the first global symbol.
+    symtab.info = 1;
+    symtab.entry_size = SYMBOL_SIZE as u64;
+
+    let mut sections = vec![text, symtab, Section::new(".strtab", SHT_STRTAB, strtab)];
+    if functions.iter().any(|f| !f.lines.is_empty()) {
+        sections.push(Section::new(".debug_abbrev", SHT_PROGBITS, debug_abbrev()));
+        sections.push(Section::new(
+            ".debug_info",
+            SHT_PROGBITS,
+            debug_info(module_name, start, end),
+        ));
+        sections.push(Section::new(
+            ".debug_line",
+            SHT_PROGBITS,
+            debug_line(module_name, functions),
+        ));
+    }
+
+    let mut shstrtab = vec![0];
+    let mut section_names = vec![];
+    for name in sections
+        .iter()
+        .map(|s| s.name)
+        .chain(std::iter::once(".shstrtab"))
+    {
+        section_names.push(shstrtab.len() as u32);
+        shstrtab.extend_from_slice(name.as_bytes());
+        shstrtab.push(0);
+    }
+    sections.push(Section::new(".shstrtab", SHT_STRTAB, shstrtab));
+
+    // Lay out the section data after the header, then the section headers.
+    let mut offsets = vec![];
+    let mut offset = ELF_HEADER_SIZE;
+    for section in &sections {
+        offset = round_up(offset, 8);
+        offsets.push(offset);
+        offset += section.data.len();
+    }
+    let section_headers_offset = round_up(offset, 8);
+    let section_count = sections.len() as u16 + 1;
+
+    let mut object =
+        Vec::with_capacity(section_headers_offset + section_count as usize * SECTION_HEADER_SIZE);
+    // e_ident
+    object.extend_from_slice(&[0x7f, b'E', b'L', b'F']);
+    object.push(2); // ELFCLASS64
+    object.push(if cfg!(target_endian = "little") { 1 } else { 2 });
+    object.push(1); // EV_CURRENT
+    object.resize(16, 0);
+    object.extend_from_slice(&2u16.to_ne_bytes()); // ET_EXEC
+    object.extend_from_slice(&machine.to_ne_bytes());
+    object.extend_from_slice(&1u32.to_ne_bytes()); // EV_CURRENT
+    object.extend_from_slice(&0u64.to_ne_bytes()); // e_entry
+    object.extend_from_slice(&0u64.to_ne_bytes()); // e_phoff
+    object.extend_from_slice(&(section_headers_offset as u64).to_ne_bytes());
+    object.extend_from_slice(&0u32.to_ne_bytes()); // e_flags
+    object.extend_from_slice(&(ELF_HEADER_SIZE as u16).to_ne_bytes());
+    object.extend_from_slice(&0u16.to_ne_bytes()); // e_phentsize
+    object.extend_from_slice(&0u16.to_ne_bytes()); // e_phnum
+    object.extend_from_slice(&(SECTION_HEADER_SIZE as u16).to_ne_bytes());
+    object.extend_from_slice(&section_count.to_ne_bytes());
+    object.extend_from_slice(&(section_count - 1).to_ne_bytes()); // e_shstrndx
+
+    for (section, offset) in sections.iter().zip(&offsets) {
+        object.resize(*offset, 0);
+        object.extend_from_slice(&section.data);
+    }
+
+    object.resize(section_headers_offset + SECTION_HEADER_SIZE, 0);
+    for ((section, offset), name) in sections.iter().zip(&offsets).zip(&section_names) {
+        object.extend_from_slice(&name.to_ne_bytes());
+        object.extend_from_slice(&section.kind.to_ne_bytes());
+        object.extend_from_slice(&section.flags.to_ne_bytes());
+        object.extend_from_slice(&section.address.to_ne_bytes());
+        object.extend_from_slice(&(*offset as u64).to_ne_bytes());
+        object.extend_from_slice(&section.size.to_ne_bytes());
+        object.extend_from_slice(&section.link.to_ne_bytes());
+        object.extend_from_slice(&section.info.to_ne_bytes());
+        object.extend_from_slice(&1u64.to_ne_bytes()); // sh_addralign
+        object.extend_from_slice(&section.entry_size.to_ne_bytes());
+    }
+
+    Some(object)
+}
+
+/// A single abbreviation, for a compile unit without children.
+fn debug_abbrev() -> Vec<u8> {
+    let mut abbrev = vec![1]; // abbreviation code
+    abbrev.push(0x11); // DW_TAG_compile_unit
+    abbrev.push(0); // DW_CHILDREN_no
+    abbrev.extend_from_slice(&[0x03, 0x08]); // DW_AT_name, DW_FORM_string
+    abbrev.extend_from_slice(&[0x10, 0x17]); // DW_AT_stmt_list, DW_FORM_sec_offset
+    abbrev.extend_from_slice(&[0x11, 0x01]); // DW_AT_low_pc, DW_FORM_addr
+    abbrev.extend_from_slice(&[0x12, 0x07]); // DW_AT_high_pc, DW_FORM_data8
+    abbrev.extend_from_slice(&[0, 0]); // end of the attributes
+    abbrev.push(0); // end of the abbreviations
+    abbrev
+}
+
+/// A DWARF 4 compile unit covering all the functions of the module.
+fn debug_info(module_name: &str, start: usize, end: usize) -> Vec<u8> {
+    let mut unit = vec![];
+    unit.extend_from_slice(&4u16.to_ne_bytes()); // version
+    unit.extend_from_slice(&0u32.to_ne_bytes()); // debug_abbrev_offset
+    unit.push(8); // address_size
+    unit.push(1); // abbreviation code
+    unit.extend_from_slice(module_name.as_bytes());
+    unit.push(0);
+    unit.extend_from_slice(&0u32.to_ne_bytes()); // DW_AT_stmt_list
+    unit.extend_from_slice(&(start as u64).to_ne_bytes());
+    unit.extend_from_slice(&((end - start) as u64).to_ne_bytes());
+    with_unit_length(unit)
+}
+
+/// A DWARF 4 line table where every function is a sequence and the lines
+/// are the offsets of the instructions in the WebAssembly binary.
+fn debug_line(module_name: &str, functions: &[JitDebugFunction]) -> Vec<u8> {
+    const DW_LNS_COPY: u8 = 1;
+    const DW_LNS_ADVANCE_PC: u8 = 2;
+    const DW_LNS_ADVANCE_LINE: u8 = 3;
+    const DW_LNE_END_SEQUENCE: u8 = 1;
+    const DW_LNE_SET_ADDRESS: u8 = 2;
+
+    let mut header = vec![1]; // minimum_instruction_length
+    header.push(1); // maximum_operations_per_instruction
+    header.push(1); // default_is_stmt
+    header.push(-5i8 as u8); // line_base
+    header.push(14); // line_range
+    header.push(13); // opcode_base
+    header.extend_from_slice(&[0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1]); // standard_opcode_lengths
+    header.push(0); // no include_directories
+    header.extend_from_slice(module_name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&[0, 0, 0]); // directory, modification time, length
+    header.push(0); // end of file_names
+
+    let mut program = vec![];
+    for function in functions.iter().filter(|f| !f.lines.is_empty()) {
+        program.extend_from_slice(&[0, 9, DW_LNE_SET_ADDRESS]);
+        program.extend_from_slice(&(function.address as u64).to_ne_bytes());
+        let mut address = 0;
+        let mut line = 1i64;
+        for &(code_offset, srcloc) in &function.lines {
+            if code_offset >= function.length {
+                break;
+            }
+            if code_offset > address {
+                program.push(DW_LNS_ADVANCE_PC);
+                leb128::write::unsigned(&mut program, (code_offset - address) as u64).unwrap();
+                address = code_offset;
+            }
+            let srcloc = i64::from(srcloc);
+            if srcloc != line {
+                program.push(DW_LNS_ADVANCE_LINE);
+                leb128::write::signed(&mut program, srcloc - line).unwrap();
+                line = srcloc;
+            }
+            program.push(DW_LNS_COPY);
+        }
+        if function.length > address {
+            program.push(DW_LNS_ADVANCE_PC);
+            leb128::write::unsigned(&mut program, (function.length - address) as u64).unwrap();
+        }
+        program.extend_from_slice(&[0, 1, DW_LNE_END_SEQUENCE]);
+    }
+
+    let mut unit = vec![];
+    unit.extend_from_slice(&4u16.to_ne_bytes()); // version
+    unit.extend_from_slice(&(header.len() as u32).to_ne_bytes()); // header_length
+    unit.extend_from_slice(&header);
+    unit.extend_from_slice(&program);
+    with_unit_length(unit)
+}
+
+/// Prefixes a DWARF unit with its 32-bit length.
+fn with_unit_length(unit: Vec<u8>) -> Vec<u8> {
+    let mut result = (unit.len() as u32).to_ne_bytes().to_vec();
+    result.extend(unit);
+    result
+}
+
+fn round_up(size: usize, multiple: usize) -> usize {
+    debug_assert!(multiple.is_power_of_two());
+    (size + (multiple - 1)) & !(multiple - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn functions(lines: bool) -> Vec<JitDebugFunction> {
+        vec![
+            JitDebugFunction {
+                name: "add".to_string(),
+                address: 0x1000,
+                length: 0x20,
+                lines: if lines {
+                    vec![(0, 42), (8, 45)]
+                } else {
+                    vec![]
+                },
+            },
+            JitDebugFunction {
+                name: "wasm-function[1]".to_string(),
+                address: 0x1040,
+                length: 0x10,
+                lines: vec![],
+            },
+        ]
+    }
+
+    fn section_names(object: &[u8]) -> Vec<String> {
+        let u64_at = |at: usize| u64::from_ne_bytes(object[at..at + 8].try_into().unwrap());
+        let u32_at = |at: usize| u32::from_ne_bytes(object[at..at + 4].try_into().unwrap());
+        let u16_at = |at: usize| u16::from_ne_bytes(object[at..at + 2].try_into().unwrap());
+        let shoff = u64_at(0x28) as usize;
+        let shnum = u16_at(0x3c) as usize;
+        let shstrndx = u16_at(0x3e) as usize;
+        let shstrtab = u64_at(shoff + shstrndx * SECTION_HEADER_SIZE + 0x18) as usize;
+        (1..shnum)
+            .map(|index| {
+                let name = shstrtab + u32_at(shoff + index * SECTION_HEADER_SIZE) as usize;
+                let len = object[name..].iter().position(|b| *b == 0).unwrap();
+                String::from_utf8(object[name..name + len].to_vec()).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn object_contains_symbols() {
+        let object = match build_object("test.wasm", &functions(false)) {
+            Some(object) => object,
+            // The host doesn't use ELF.
+            None => return,
+        };
+        assert_eq!(&object[..4], b"\x7fELF");
+        assert_eq!(
+            section_names(&object),
+            [".text", ".symtab", ".strtab", ".shstrtab"]
+        );
+        let strtab = String::from_utf8_lossy(&object);
+        assert!(strtab.contains("add\0wasm-function[1]\0"));
+    }
+
+    #[test]
+    fn object_contains_line_table_with_debug_info() {
+        let object = match build_object("test.wasm", &functions(true)) {
+            Some(object) => object,
+            None => return,
+        };
+        assert_eq!(
+            section_names(&object),
+            [
+                ".text",
+                ".symtab",
+                ".strtab",
+                ".debug_abbrev",
+                ".debug_info",
+                ".debug_line",
+                ".shstrtab"
+            ]
+        );
+    }
+
+    #[test]
+    fn register_and_unregister() {
+        let registration = match register("test.wasm", &functions(false)) {
+            Some(registration) => registration,
+            None => return,
+        };
+        let entry = &*registration.entry as *const JitCodeEntry as *mut JitCodeEntry;
+        let registered = |entry: *mut JitCodeEntry| {
+            let _guard = DESCRIPTOR_LOCK.lock().unwrap();
+            let mut current = unsafe { (*ptr::addr_of!(__jit_debug_descriptor)).first_entry };
+            while !current.is_null() {
+                if current == entry {
+                    return true;
+                }
+                current = unsafe { (*current).next_entry };
+            }
+            false
+        };
+        assert!(registered(entry));
+        drop(registration);
+        assert!(!registered(entry));
+    }
+}
@@ -6,15 +6,17 @@ use std::collections::HashMap;
 use std::ptr::{read_unaligned, write_unaligned};
 use wasmer_types::entity::PrimaryMap;
 use wasmer_types::RelocationLike;
-use wasmer_types::{LocalFunctionIndex, ModuleInfo};
+use wasmer_types::{FunctionIndex, LocalFunctionIndex, ModuleInfo};
 use wasmer_types::{RelocationKind, RelocationTarget, SectionIndex};
 use wasmer_vm::libcalls::function_pointer;
-use wasmer_vm::SectionBodyPtr;
+use wasmer_vm::{FunctionBodyPtr, SectionBodyPtr};
 
+#[allow(clippy::too_many_arguments)]
 fn apply_relocation(
     body: usize,
     r: &impl RelocationLike,
     allocated_functions: &PrimaryMap<LocalFunctionIndex, FunctionExtent>,
+    allocated_dynamic_function_trampolines: &PrimaryMap<FunctionIndex, FunctionBodyPtr>,
     allocated_sections: &PrimaryMap<SectionIndex, SectionBodyPtr>,
     libcall_trampolines: SectionIndex,
     libcall_trampoline_len: usize,
@@ -38,6 +40,9 @@ fn apply_relocation(
         RelocationTarget::CustomSection(custom_section) => {
             *allocated_sections[custom_section] as usize
         }
+        RelocationTarget::DynamicFunctionTrampoline(index) => {
+            *allocated_dynamic_function_trampolines[index] as usize
+        }
     };
 
     match r.kind() {
@@ -131,9 +136,11 @@ fn apply_relocation(
 
 /// Links a module, patching the allocated functions with the
 /// required relocations and jump tables.
+#[allow(clippy::too_many_arguments)]
 pub fn link_module<'a>(
     _module: &ModuleInfo,
     allocated_functions: &PrimaryMap<LocalFunctionIndex, FunctionExtent>,
+    allocated_dynamic_function_trampolines: &PrimaryMap<FunctionIndex, FunctionBodyPtr>,
     function_relocations: impl Iterator<
         Item = (
             LocalFunctionIndex,
@@ -159,6 +166,7 @@ pub fn link_module<'a>(
                 body,
                 r,
                 allocated_functions,
+                allocated_dynamic_function_trampolines,
                 allocated_sections,
                 libcall_trampolines,
                 trampoline_len,
@@ -173,6 +181,7 @@ pub fn link_module<'a>(
                 body,
                 r,
                 allocated_functions,
+                allocated_dynamic_function_trampolines,
                 allocated_sections,
                 libcall_trampolines,
                 trampoline_len,
@@ -1,5 +1,7 @@
 //! The Wasmer Engine.
 
+#[cfg(feature = "artifact-encryption")]
+mod encryption;
 mod error;
 #[cfg(not(target_arch = "wasm32"))]
 mod resolver;
@@ -20,14 +22,26 @@ mod code_memory;
 mod inner;
 #[cfg(feature = "translator")]
 #[cfg(not(target_arch = "wasm32"))]
+mod jit_debug;
+#[cfg(feature = "translator")]
+#[cfg(not(target_arch = "wasm32"))]
 mod link;
 #[cfg(feature = "translator")]
 #[cfg(not(target_arch = "wasm32"))]
+mod profiling;
+#[cfg(feature = "translator")]
+#[cfg(not(target_arch = "wasm32"))]
 mod unwind;
 
+#[cfg(feature = "artifact-encryption")]
+pub use self::encryption::{
+    decrypt_artifact, encrypt_artifact, is_encrypted_artifact, ArtifactKeyProvider,
+    CallbackKeyProvider, EnvKeyProvider, KeyProviderError, StaticKeyProvider, ARTIFACT_KEY_LEN,
+    ENCRYPTED_ARTIFACT_MAGIC_HEADER,
+};
 pub use self::error::{InstantiationError, LinkError};
 #[cfg(not(target_arch = "wasm32"))]
-pub use self::resolver::resolve_imports;
+pub use self::resolver::{check_imports, resolve_imports};
 #[cfg(not(target_arch = "wasm32"))]
 pub use self::trap::*;
 #[cfg(not(target_arch = "wasm32"))]
@@ -40,9 +54,12 @@ pub use self::artifact::Artifact;
 pub use self::builder::EngineBuilder;
 #[cfg(feature = "translator")]
 #[cfg(not(target_arch = "wasm32"))]
-pub use self::code_memory::CodeMemory;
+pub use self::code_memory::{CodeMemory, CodeMemoryLease};
 #[cfg(feature = "translator")]
 pub use self::inner::{Engine, EngineInner};
 #[cfg(feature = "translator")]
 #[cfg(not(target_arch = "wasm32"))]
 pub use self::link::link_module;
+#[cfg(feature = "translator")]
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::profiling::ProfilingStrategy;
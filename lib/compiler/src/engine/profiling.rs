@@ -0,0 +1,248 @@
+//! Reporting of compiled code to the Linux `perf` profiler.
+//!
+//! `perf` can't symbolize code compiled at runtime on its own. It supports
+//! two ways for JIT compilers to describe it:
+//!
+//! - a perf map, `/tmp/perf-PID.map`, listing the address, size and name of
+//!   every function, which `perf report` reads directly;
+//! - a jitdump file, `/tmp/jit-PID.dump`, which also holds a copy of the
+//!   code so it can be annotated, and is merged into the profile with
+//!   `perf inject --jit`. The file must be mapped as executable so that
+//!   `perf record` sees it, see
+//!   <https://github.com/torvalds/linux/blob/master/tools/perf/Documentation/jitdump-specification.txt>.
+
+use super::jit_debug::JitDebugFunction;
+#[cfg(target_os = "linux")]
+use jitdump::JitDump;
+use std::fs::File;
+#[cfg(target_os = "linux")]
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// A way to report the compiled code to profilers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfilingStrategy {
+    /// Write the functions to `/tmp/perf-PID.map`.
+    PerfMap,
+    /// Write the functions and their code to `/tmp/jit-PID.dump`.
+    JitDump,
+}
+
+/// Writes the compiled functions for the chosen profiling strategies.
+pub(crate) struct Profiler {
+    perf_map: Option<Mutex<File>>,
+    #[cfg(target_os = "linux")]
+    jitdump: Option<Mutex<JitDump>>,
+}
+
+impl Profiler {
+    /// Create the files of the given strategies.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new(strategies: &[ProfilingStrategy]) -> io::Result<Self> {
+        let pid = std::process::id();
+        let perf_map = if strategies.contains(&ProfilingStrategy::PerfMap) {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(format!("/tmp/perf-{}.map", pid))?;
+            Some(Mutex::new(file))
+        } else {
+            None
+        };
+        let jitdump = if strategies.contains(&ProfilingStrategy::JitDump) {
+            Some(Mutex::new(JitDump::new(pid)?))
+        } else {
+            None
+        };
+        Ok(Self { perf_map, jitdump })
+    }
+
+    /// Create the files of the given strategies.
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn new(_strategies: &[ProfilingStrategy]) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "profiling is only supported on Linux",
+        ))
+    }
+
+    /// Report newly published functions.
+    ///
+    /// Errors are ignored, as profiling must not make the program fail.
+    pub(crate) fn register(&self, functions: &[JitDebugFunction]) {
+        if let Some(perf_map) = &self.perf_map {
+            let mut file = perf_map.lock().unwrap();
+            let mut entries = String::new();
+            for function in functions {
+                entries.push_str(&format!(
+                    "{:x} {:x} {}\n",
+                    function.address, function.length, function.name
+                ));
+            }
+            let _ = file.write_all(entries.as_bytes());
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(jitdump) = &self.jitdump {
+            let mut jitdump = jitdump.lock().unwrap();
+            for function in functions {
+                let _ = jitdump.code_load(function);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod jitdump {
+    use super::JitDebugFunction;
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Write};
+    use std::os::unix::io::AsRawFd;
+
+    const JITDUMP_MAGIC: u32 = 0x4A69_5444;
+    const JITDUMP_VERSION: u32 = 1;
+    const JITDUMP_HEADER_SIZE: u32 = 40;
+    const JIT_CODE_LOAD: u32 = 0;
+    const JIT_CODE_CLOSE: u32 = 3;
+
+    /// An open jitdump file.
+    pub(super) struct JitDump {
+        file: File,
+        pid: u32,
+        // The executable mapping of the file, announcing it to `perf record`.
+        marker: *mut libc::c_void,
+        code_index: u64,
+    }
+
+    // The marker is never accessed, only unmapped.
+    unsafe impl Send for JitDump {}
+
+    impl JitDump {
+        pub(super) fn new(pid: u32) -> io::Result<Self> {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(format!("/tmp/jit-{}.dump", pid))?;
+            let page_size = region::page::size();
+            let marker = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    page_size,
+                    libc::PROT_READ | libc::PROT_EXEC,
+                    libc::MAP_PRIVATE,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if marker == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut header = Vec::with_capacity(JITDUMP_HEADER_SIZE as usize);
+            header.extend_from_slice(&JITDUMP_MAGIC.to_ne_bytes());
+            header.extend_from_slice(&JITDUMP_VERSION.to_ne_bytes());
+            header.extend_from_slice(&JITDUMP_HEADER_SIZE.to_ne_bytes());
+            header.extend_from_slice(&elf_machine().to_ne_bytes());
+            header.extend_from_slice(&0u32.to_ne_bytes()); // pad1
+            header.extend_from_slice(&pid.to_ne_bytes());
+            header.extend_from_slice(&timestamp().to_ne_bytes());
+            header.extend_from_slice(&0u64.to_ne_bytes()); // flags
+            file.write_all(&header)?;
+
+            Ok(Self {
+                file,
+                pid,
+                marker,
+                code_index: 0,
+            })
+        }
+
+        /// Write a `JIT_CODE_LOAD` record, with a copy of the function code.
+        pub(super) fn code_load(&mut self, function: &JitDebugFunction) -> io::Result<()> {
+            // The code is published, so it's readable.
+            let code = unsafe {
+                std::slice::from_raw_parts(function.address as *const u8, function.length)
+            };
+            let size = 16 + 40 + function.name.len() + 1 + code.len();
+
+            let mut record = Vec::with_capacity(size);
+            record.extend_from_slice(&JIT_CODE_LOAD.to_ne_bytes());
+            record.extend_from_slice(&(size as u32).to_ne_bytes());
+            record.extend_from_slice(&timestamp().to_ne_bytes());
+            record.extend_from_slice(&self.pid.to_ne_bytes());
+            record.extend_from_slice(&thread_id().to_ne_bytes());
+            record.extend_from_slice(&(function.address as u64).to_ne_bytes()); // vma
+            record.extend_from_slice(&(function.address as u64).to_ne_bytes()); // code_addr
+            record.extend_from_slice(&(function.length as u64).to_ne_bytes());
+            record.extend_from_slice(&self.code_index.to_ne_bytes());
+            record.extend_from_slice(function.name.as_bytes());
+            record.push(0);
+            record.extend_from_slice(code);
+            self.code_index += 1;
+            self.file.write_all(&record)
+        }
+    }
+
+    impl Drop for JitDump {
+        fn drop(&mut self) {
+            let mut record = Vec::with_capacity(16);
+            record.extend_from_slice(&JIT_CODE_CLOSE.to_ne_bytes());
+            record.extend_from_slice(&16u32.to_ne_bytes());
+            record.extend_from_slice(&timestamp().to_ne_bytes());
+            let _ = self.file.write_all(&record);
+            unsafe {
+                libc::munmap(self.marker, region::page::size());
+            }
+        }
+    }
+
+    /// The ELF machine of the host, as expected by `perf`.
+    fn elf_machine() -> u32 {
+        if cfg!(target_arch = "x86_64") {
+            62 // EM_X86_64
+        } else if cfg!(target_arch = "aarch64") {
+            183 // EM_AARCH64
+        } else if cfg!(target_arch = "riscv64") {
+            243 // EM_RISCV
+        } else {
+            0 // EM_NONE
+        }
+    }
+
+    /// The timestamp of the records, on the clock `perf record -k mono` uses.
+    fn timestamp() -> u64 {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        unsafe {
+            libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time);
+        }
+        time.tv_sec as u64 * 1_000_000_000 + time.tv_nsec as u64
+    }
+
+    fn thread_id() -> u32 {
+        unsafe { libc::syscall(libc::SYS_gettid) as u32 }
+    }
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perf_map_lists_functions() {
+        let profiler = Profiler::new(&[ProfilingStrategy::PerfMap]).unwrap();
+        profiler.register(&[JitDebugFunction {
+            name: "perf_map_lists_functions".to_string(),
+            address: 0x1000,
+            length: 0x20,
+            lines: vec![],
+        }]);
+        let map = std::fs::read_to_string(format!("/tmp/perf-{}.map", std::process::id())).unwrap();
+        assert!(map.contains("1000 20 perf_map_lists_functions\n"));
+    }
+}
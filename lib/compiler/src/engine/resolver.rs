@@ -56,6 +56,40 @@ fn get_runtime_size(context: &StoreObjects, extern_: &VMExtern) -> Option<u32> {
     }
 }
 
+/// Checks that `imports`, indexed like the imports of `module`, satisfy
+/// them, reporting every missing or incompatible import at once.
+#[allow(clippy::result_large_err)]
+pub fn check_imports(
+    module: &ModuleInfo,
+    imports: &[Option<&VMExtern>],
+    context: &StoreObjects,
+) -> Result<(), LinkError> {
+    let errors = module
+        .imports
+        .iter()
+        .filter_map(|(key, import_index)| {
+            let import_extern = get_extern_from_import(module, import_index);
+            let error = match imports.get(key.import_idx as usize).copied().flatten() {
+                None => ImportError::UnknownImport(import_extern),
+                Some(resolved) => {
+                    let extern_type = get_extern_type(context, resolved);
+                    let runtime_size = get_runtime_size(context, resolved);
+                    if extern_type.is_compatible_with(&import_extern, runtime_size) {
+                        return None;
+                    }
+                    ImportError::IncompatibleType(import_extern, extern_type)
+                }
+            };
+            Some((key.module.clone(), key.field.clone(), error))
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(LinkError::from_import_errors(errors))
+    }
+}
+
 /// This function allows to match all imports of a `ModuleInfo` with concrete definitions provided by
 /// a `Resolver`.
 ///
@@ -74,6 +108,9 @@ pub fn resolve_imports(
     let mut memory_imports = PrimaryMap::with_capacity(module.num_imported_memories);
     let mut global_imports = PrimaryMap::with_capacity(module.num_imported_globals);
 
+    let resolved = imports.iter().map(Some).collect::<Vec<_>>();
+    check_imports(module, &resolved, context)?;
+
     for (
         wasmer_types::ImportKey {
             module: module_name,
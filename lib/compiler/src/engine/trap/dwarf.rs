@@ -0,0 +1,129 @@
+//! Source locations of the instructions of a module, from the DWARF
+//! debugging information that compilers embed in its custom sections.
+//!
+//! The addresses of the DWARF line programs of WebAssembly modules are
+//! offsets in the contents of their code section.
+
+use gimli::{ColumnType, EndianSlice, LittleEndian};
+use std::collections::HashMap;
+use wasmer_types::{ModuleInfo, SourceLocation};
+
+type Reader<'a> = EndianSlice<'a, LittleEndian>;
+
+/// A row of the line table: the location, as a file index, a line and a
+/// column, of the instructions from `address` up to the next row, or `None`
+/// if they have none, such as at the end of a sequence.
+#[derive(Debug)]
+struct Row {
+    address: u64,
+    location: Option<(u32, u32, Option<u32>)>,
+}
+
+/// The line table of a module, mapping the offsets in its code section to
+/// the lines of its source files.
+#[derive(Debug)]
+pub(crate) struct SourceLines {
+    files: Vec<String>,
+    rows: Vec<Row>,
+}
+
+impl SourceLines {
+    /// Parses the line table of `module`, if it has DWARF debugging
+    /// information.
+    pub(crate) fn parse(module: &ModuleInfo) -> Option<Self> {
+        module.custom_section(".debug_line")?;
+        let dwarf = gimli::Dwarf::load(|id| -> Result<Reader<'_>, gimli::Error> {
+            let data = module.custom_section(id.name()).unwrap_or_default();
+            Ok(EndianSlice::new(data, LittleEndian))
+        })
+        .ok()?;
+
+        let mut files = Vec::new();
+        let mut rows = Vec::new();
+        let mut headers = dwarf.units();
+        while let Ok(Some(header)) = headers.next() {
+            let unit = match dwarf.unit(header) {
+                Ok(unit) => unit,
+                Err(_) => continue,
+            };
+            let program = match unit.line_program.clone() {
+                Some(program) => program,
+                None => continue,
+            };
+            // The indices in `files` of the files of this unit, by their
+            // index in its line program.
+            let mut unit_files = HashMap::new();
+            let mut program_rows = program.rows();
+            while let Ok(Some((header, row))) = program_rows.next_row() {
+                let line = match row.line() {
+                    Some(line) if !row.end_sequence() => line.get() as u32,
+                    _ => {
+                        rows.push(Row {
+                            address: row.address(),
+                            location: None,
+                        });
+                        continue;
+                    }
+                };
+                let file = *unit_files.entry(row.file_index()).or_insert_with(|| {
+                    let path = row
+                        .file(header)
+                        .and_then(|file| file_path(&dwarf, &unit, header, file))
+                        .unwrap_or_else(|| "<unknown>".to_string());
+                    files.push(path);
+                    files.len() as u32 - 1
+                });
+                let column = match row.column() {
+                    ColumnType::LeftEdge => None,
+                    ColumnType::Column(column) => Some(column.get() as u32),
+                };
+                rows.push(Row {
+                    address: row.address(),
+                    location: Some((file, line, column)),
+                });
+            }
+        }
+        if rows.is_empty() {
+            return None;
+        }
+
+        // A sequence can start where another one ends, and then takes
+        // precedence.
+        rows.sort_by_key(|row| (row.address, row.location.is_some()));
+        Some(Self { files, rows })
+    }
+
+    /// Returns the source location of the instruction at `address`, an
+    /// offset in the code section.
+    pub(crate) fn lookup(&self, address: u64) -> Option<SourceLocation> {
+        let index = self.rows.partition_point(|row| row.address <= address);
+        let (file, line, column) = self.rows.get(index.checked_sub(1)?)?.location?;
+        Some(SourceLocation::new(
+            self.files[file as usize].clone(),
+            line,
+            column,
+        ))
+    }
+}
+
+/// Returns the path of a file of a line program, joined to its directory
+/// unless it is absolute.
+fn file_path(
+    dwarf: &gimli::Dwarf<Reader<'_>>,
+    unit: &gimli::Unit<Reader<'_>>,
+    header: &gimli::LineProgramHeader<Reader<'_>>,
+    file: &gimli::FileEntry<Reader<'_>>,
+) -> Option<String> {
+    let name = dwarf.attr_string(unit, file.path_name()).ok()?;
+    let name = name.to_string_lossy();
+    let directory = file
+        .directory(header)
+        .and_then(|directory| dwarf.attr_string(unit, directory).ok());
+    match directory {
+        Some(directory) if !directory.is_empty() && !name.starts_with('/') => {
+            let directory = directory.to_string_lossy();
+            Some(format!("{}/{}", directory.trim_end_matches('/'), name))
+        }
+        _ => Some(name.into_owned()),
+    }
+}
@@ -15,18 +15,21 @@ use core::ops::Deref;
 use rkyv::vec::ArchivedVec;
 use std::cmp;
 use std::collections::BTreeMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 use wasmer_types::compilation::address_map::{
     ArchivedFunctionAddressMap, ArchivedInstructionAddressMap,
 };
 use wasmer_types::compilation::function::ArchivedCompiledFunctionFrameInfo;
 use wasmer_types::entity::{BoxedSlice, EntityRef, PrimaryMap};
 use wasmer_types::{
-    CompiledFunctionFrameInfo, FrameInfo, FunctionAddressMap, InstructionAddressMap,
-    LocalFunctionIndex, ModuleInfo, SourceLoc, TrapInformation,
+    CompiledFunctionFrameInfo, ExportIndex, FrameInfo, FunctionAddressMap, FunctionIndex,
+    InstructionAddressMap, LocalFunctionIndex, ModuleInfo, SourceLoc, SourceLocation,
+    TrapInformation,
 };
 use wasmer_vm::FunctionBodyPtr;
 
+use super::dwarf::SourceLines;
+
 use crate::ArtifactBuildFromArchive;
 
 lazy_static::lazy_static! {
@@ -66,6 +69,9 @@ struct ModuleInfoFrameInfo {
     functions: BTreeMap<usize, FunctionInfo>,
     module: Arc<ModuleInfo>,
     frame_infos: FrameInfosVariant,
+    /// The line table of the module, parsed from its DWARF debugging
+    /// information the first time a frame of the module is looked up.
+    source_lines: OnceLock<Option<SourceLines>>,
 }
 
 impl ModuleInfoFrameInfo {
@@ -76,6 +82,19 @@ impl ModuleInfoFrameInfo {
         self.frame_infos.get(local_index).unwrap()
     }
 
+    /// Returns the location in the source code of the instruction at
+    /// `srcloc`, if the module has DWARF debugging information.
+    fn source_location(&self, srcloc: SourceLoc) -> Option<SourceLocation> {
+        if srcloc.is_default() {
+            return None;
+        }
+        let address = (srcloc.bits() as usize).checked_sub(self.module.code_section_offset)?;
+        self.source_lines
+            .get_or_init(|| SourceLines::parse(&self.module))
+            .as_ref()?
+            .lookup(address as u64)
+    }
+
     /// Gets a function given a pc
     fn function_info(&self, pc: usize) -> Option<&FunctionInfo> {
         let (end, func) = self.functions.range(pc..).next()?;
@@ -132,21 +151,29 @@ impl GlobalFrameInfo {
         };
 
         let instr = match pos {
-            Some(pos) => instr_map.instructions().get(pos).srcloc,
+            // Instructions that don't come from the wasm code, such as the
+            // function prologue, have no source location.
+            Some(pos) if !instr_map.instructions().get(pos).srcloc.is_default() => {
+                instr_map.instructions().get(pos).srcloc
+            }
             // Some compilers don't emit yet the full trap information for each of
             // the instructions (such as LLVM).
             // In case no specific instruction is found, we return by default the
             // start offset of the function.
-            None => instr_map.start_srcloc(),
+            _ => instr_map.start_srcloc(),
         };
         let func_index = module.module.func_index(func.local_index);
-        Some(FrameInfo::new(
+        let frame = FrameInfo::new(
             module.module.name(),
             func_index.index() as u32,
-            module.module.function_names.get(&func_index).cloned(),
+            function_name(&module.module, func_index),
             instr_map.start_srcloc(),
             instr,
-        ))
+        );
+        Some(match module.source_location(instr) {
+            Some(source_location) => frame.with_source_location(source_location),
+            None => frame,
+        })
     }
 
     /// Fetches trap information about a program counter in a backtrace.
@@ -161,6 +188,19 @@ impl GlobalFrameInfo {
         Some(traps[idx])
     }
 
+    /// Replaces the module information of the module containing `pc`, so
+    /// that frames reflect changes made to it after registration, such as
+    /// a new module name.
+    pub fn update_module_info(&mut self, pc: usize, module: Arc<ModuleInfo>) {
+        let (end, module_info) = match self.ranges.range_mut(pc..).next() {
+            Some(entry) => entry,
+            None => return,
+        };
+        if module_info.start <= pc && pc <= *end {
+            module_info.module = module;
+        }
+    }
+
     /// Gets a module given a pc
     fn module_info(&self, pc: usize) -> Option<&ModuleInfoFrameInfo> {
         let (end, module_info) = self.ranges.range(pc..).next()?;
@@ -172,6 +212,21 @@ impl GlobalFrameInfo {
     }
 }
 
+/// Returns the name of a function, from the `name` section if present, or
+/// else from the first export of the function.
+pub(crate) fn function_name(module: &ModuleInfo, func_index: FunctionIndex) -> Option<String> {
+    if let Some(name) = module.function_names.get(&func_index) {
+        return Some(name.clone());
+    }
+    module
+        .exports
+        .iter()
+        .find_map(|(name, export)| match export {
+            ExportIndex::Function(index) if *index == func_index => Some(name.clone()),
+            _ => None,
+        })
+}
+
 impl Drop for GlobalFrameInfoRegistration {
     fn drop(&mut self) {
         if let Ok(mut info) = FRAME_INFO.write() {
@@ -322,6 +377,17 @@ pub enum FunctionAddressMapInstructionVariant<'a> {
 }
 
 impl FunctionAddressMapInstructionVariant<'_> {
+    pub fn len(&self) -> usize {
+        match self {
+            FunctionAddressMapInstructionVariant::Owned(instructions) => instructions.len(),
+            FunctionAddressMapInstructionVariant::Archived(instructions) => instructions.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn code_offset_by_key(&self, key: usize) -> Result<usize, usize> {
         match self {
             FunctionAddressMapInstructionVariant::Owned(instructions) => {
@@ -403,6 +469,7 @@ pub fn register(
             functions,
             module,
             frame_infos,
+            source_lines: OnceLock::new(),
         },
     );
     assert!(prev.is_none());
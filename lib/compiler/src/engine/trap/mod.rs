@@ -1,7 +1,9 @@
+mod dwarf;
 mod frame_info;
 mod stack;
 pub use frame_info::{
     register as register_frame_info, CompiledFunctionFrameInfoVariant, FrameInfosVariant,
     FunctionExtent, GlobalFrameInfoRegistration, FRAME_INFO,
 };
+pub(crate) use frame_info::function_name;
 pub use stack::get_trace_and_trapcode;
@@ -71,13 +71,13 @@ pub trait Tunables {
         let num_imports = module.num_imported_memories;
         let mut memories: PrimaryMap<LocalMemoryIndex, _> =
             PrimaryMap::with_capacity(module.memories.len() - num_imports);
+        // The definition locations are only for the local memories.
         for (index, mdl) in memory_definition_locations
             .iter()
             .enumerate()
-            .take(module.memories.len())
-            .skip(num_imports)
+            .take(module.memories.len() - num_imports)
         {
-            let mi = MemoryIndex::new(index);
+            let mi = MemoryIndex::new(num_imports + index);
             let ty = &module.memories[mi];
             let style = &memory_styles[mi];
             memories.push(InternalStoreHandle::new(
@@ -105,13 +105,13 @@ pub trait Tunables {
         let num_imports = module.num_imported_tables;
         let mut tables: PrimaryMap<LocalTableIndex, _> =
             PrimaryMap::with_capacity(module.tables.len() - num_imports);
+        // The definition locations are only for the local tables.
         for (index, tdl) in table_definition_locations
             .iter()
             .enumerate()
-            .take(module.tables.len())
-            .skip(num_imports)
+            .take(module.tables.len() - num_imports)
         {
-            let ti = TableIndex::new(index);
+            let ti = TableIndex::new(num_imports + index);
             let ty = &module.tables[ti];
             let style = &table_styles[ti];
             tables.push(InternalStoreHandle::new(
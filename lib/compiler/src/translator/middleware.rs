@@ -27,10 +27,24 @@ pub trait ModuleMiddleware: Debug + Send + Sync {
     fn transform_module_info(&self, _: &mut ModuleInfo) -> Result<(), MiddlewareError> {
         Ok(())
     }
+
+    /// Returns an id describing the configuration of this middleware.
+    ///
+    /// It is part of the deterministic id of the compilers, so middlewares
+    /// configured differently, for instance with different limits, must
+    /// return different ids. Defaults to the name of the middleware type.
+    fn deterministic_id(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
 }
 
 /// A function middleware specialized for a single function.
 pub trait FunctionMiddleware: Debug {
+    /// Processes a declaration of `count` locals of type `ty`, in the order
+    /// they follow the parameters of the function. This is called before
+    /// the first operator is fed.
+    fn declare_locals(&mut self, _count: u32, _ty: ValType) {}
+
     /// Processes the given operator.
     fn feed<'a>(
         &mut self,
@@ -60,6 +74,9 @@ pub struct MiddlewareReaderState<'a> {
 
     /// The pending operations added by the middleware.
     pending_operations: VecDeque<Operator<'a>>,
+
+    /// The offset in the module of the operator being fed.
+    operator_position: usize,
 }
 
 /// Trait for generating middleware chains from "prototype" (generator) chains.
@@ -99,6 +116,12 @@ impl<'a> MiddlewareReaderState<'a> {
     pub fn push_operator(&mut self, operator: Operator<'a>) {
         self.pending_operations.push_back(operator);
     }
+
+    /// The offset in the module of the original operator being fed, from
+    /// which the operators pushed by the middlewares are derived.
+    pub fn original_position(&self) -> usize {
+        self.operator_position
+    }
 }
 
 impl<'a> Extend<Operator<'a>> for MiddlewareReaderState<'a> {
@@ -121,6 +144,7 @@ impl<'a> MiddlewareBinaryReader<'a> {
             state: MiddlewareReaderState {
                 inner,
                 pending_operations: VecDeque::new(),
+                operator_position: original_offset,
             },
             chain: vec![],
         }
@@ -151,6 +175,9 @@ impl<'a> FunctionBinaryReader<'a> for MiddlewareBinaryReader<'a> {
             .inner
             .read::<ValType>()
             .map_err(from_binaryreadererror_wasmerror)?;
+        for stage in &mut self.chain {
+            stage.declare_locals(count, ty);
+        }
         Ok((count, ty))
     }
 
@@ -166,6 +193,7 @@ impl<'a> FunctionBinaryReader<'a> for MiddlewareBinaryReader<'a> {
 
         // Try to fill the `self.pending_operations` buffer, until it is non-empty.
         while self.state.pending_operations.is_empty() {
+            self.state.operator_position = self.state.inner.original_position();
             let raw_op = self
                 .state
                 .inner
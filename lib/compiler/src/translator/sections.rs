@@ -132,7 +132,7 @@ pub fn parse_import_section<'data>(
                 maximum,
             }) => {
                 if memory64 {
-                    unimplemented!("64bit memory not implemented yet");
+                    return Err(wasm_unsupported!("64-bit memories"));
                 }
                 environ.declare_memory_import(
                     MemoryType {
@@ -227,7 +227,7 @@ pub fn parse_memory_section(
             maximum,
         } = entry.map_err(from_binaryreadererror_wasmerror)?;
         if memory64 {
-            unimplemented!("64bit memory not implemented yet");
+            return Err(wasm_unsupported!("64-bit memories"));
         }
         environ.declare_memory(MemoryType {
             minimum: Pages(initial as u32),
@@ -0,0 +1,185 @@
+use wasmer::{Function, FunctionEnvMut, Instance, RuntimeError, Type, Value, WasmPtr};
+
+use crate::env::get_emscripten_funcs;
+use crate::EmEnv;
+
+/// An argument of [`emscripten_ccall`], following the types of the `ccall`
+/// helper of the Emscripten JavaScript glue code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CcallArg {
+    /// A value passed as is
+    Number(Value),
+    /// A string copied to the stack, passed as a pointer to its C string
+    String(String),
+    /// Bytes copied to the stack, passed as a pointer to the first one
+    Array(Vec<u8>),
+}
+
+/// How the value returned by [`emscripten_ccall`] is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CcallReturn {
+    /// The values returned by the function
+    #[default]
+    Number,
+    /// A pointer to a C string
+    String,
+}
+
+/// The value returned by [`emscripten_ccall`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CcallValue {
+    Number(Box<[Value]>),
+    /// The C string returned by the function, `None` for a null pointer
+    String(Option<String>),
+}
+
+impl Default for CcallValue {
+    fn default() -> Self {
+        CcallValue::Number(Box::default())
+    }
+}
+
+/// A call to a function exported by an Emscripten module.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Ccall {
+    /// The name of the function, with or without its leading underscore
+    pub function: String,
+    pub args: Vec<CcallArg>,
+    pub returns: CcallReturn,
+}
+
+impl Ccall {
+    pub fn new(function: impl Into<String>) -> Self {
+        Ccall {
+            function: function.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn arg(mut self, arg: CcallArg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    pub fn returns(mut self, returns: CcallReturn) -> Self {
+        self.returns = returns;
+        self
+    }
+}
+
+/// Calls a function exported by an Emscripten module the way `ccall` does.
+///
+/// Strings and arrays are copied to the stack for the duration of the call.
+/// The instance must have been set up by [`crate::set_up_emscripten_instance`].
+pub fn emscripten_ccall(
+    instance: &Instance,
+    env: &mut FunctionEnvMut<EmEnv>,
+    call: &Ccall,
+) -> Result<CcallValue, RuntimeError> {
+    debug!("emscripten::ccall {}", call.function);
+    let func = instance
+        .exports
+        .get_function(&call.function)
+        .or_else(|_| {
+            instance
+                .exports
+                .get_function(&format!("_{}", call.function))
+        })
+        .map_err(|_| {
+            RuntimeError::new(format!(
+                "The module doesn't export a \"{}\" function",
+                call.function
+            ))
+        })?
+        .clone();
+
+    let params = func.ty(env).params().to_vec();
+    if params.len() != call.args.len() {
+        return Err(RuntimeError::new(format!(
+            "\"{}\" expects {} arguments, but received {}",
+            call.function,
+            params.len(),
+            call.args.len()
+        )));
+    }
+
+    let stack = get_emscripten_funcs(env).stack_save_ref().cloned();
+    let stack = match stack {
+        Some(stack_save) => Some(stack_save.call(env)?),
+        None => None,
+    };
+
+    let result = call_with_args(env, &func, &params, &call.args);
+
+    if let Some(stack) = stack {
+        let stack_restore = get_emscripten_funcs(env).stack_restore_ref().cloned();
+        if let Some(stack_restore) = stack_restore {
+            stack_restore.call(env, stack)?;
+        }
+    }
+
+    let values = result?;
+    match call.returns {
+        CcallReturn::Number => Ok(CcallValue::Number(values)),
+        CcallReturn::String => match *values {
+            [Value::I32(0)] => Ok(CcallValue::String(None)),
+            [Value::I32(ptr)] => {
+                let memory = env.data().memory(0);
+                let bytes = WasmPtr::<u8>::new(ptr as u32)
+                    .read_until(&memory.view(env), |byte| *byte == 0)
+                    .map_err(|e| RuntimeError::new(e.to_string()))?;
+                Ok(CcallValue::String(Some(
+                    String::from_utf8_lossy(&bytes).into_owned(),
+                )))
+            }
+            _ => Err(RuntimeError::new(format!(
+                "\"{}\" doesn't return a pointer",
+                call.function
+            ))),
+        },
+    }
+}
+
+fn call_with_args(
+    env: &mut FunctionEnvMut<EmEnv>,
+    func: &Function,
+    params: &[Type],
+    args: &[CcallArg],
+) -> Result<Box<[Value]>, RuntimeError> {
+    let mut values = Vec::with_capacity(args.len());
+    for (arg, ty) in args.iter().zip(params) {
+        let value = match arg {
+            CcallArg::Number(value) => value.clone(),
+            CcallArg::String(s) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0);
+                Value::I32(copy_to_stack(env, &bytes)? as i32)
+            }
+            CcallArg::Array(bytes) => Value::I32(copy_to_stack(env, bytes)? as i32),
+        };
+        if value.ty() != *ty {
+            return Err(RuntimeError::new(format!(
+                "Expected an argument of type {:?}, received {:?}",
+                ty, arg
+            )));
+        }
+        values.push(value);
+    }
+
+    func.call(env, &values)
+}
+
+fn copy_to_stack(env: &mut FunctionEnvMut<EmEnv>, bytes: &[u8]) -> Result<u32, RuntimeError> {
+    let stack_alloc = get_emscripten_funcs(env)
+        .stack_alloc_ref()
+        .cloned()
+        .ok_or_else(|| RuntimeError::new("The module doesn't export a stack allocator"))?;
+    let offset = stack_alloc.call(env, bytes.len() as u32)?;
+
+    let memory = env.data().memory(0);
+    memory
+        .view(env)
+        .write(offset as u64, bytes)
+        .map_err(|e| RuntimeError::new(e.to_string()))?;
+    Ok(offset)
+}
@@ -9,6 +9,53 @@ pub fn ___seterrno(mut _ctx: FunctionEnvMut<EmEnv>, _value: i32) {
     // value
 }
 
+/// Converts an errno of the host into the one used by recent versions of
+/// Emscripten, which share their values with WASI.
+pub(crate) fn host_errno_to_wasi(errno: i32) -> i32 {
+    match errno {
+        0 => 0,
+        libc::E2BIG => 1,
+        libc::EACCES => 2,
+        libc::EAGAIN => 6,
+        libc::EBADF => 8,
+        libc::EBUSY => 10,
+        libc::ECHILD => 12,
+        libc::EDEADLK => 16,
+        libc::EDOM => 18,
+        libc::EEXIST => 20,
+        libc::EFAULT => 21,
+        libc::EFBIG => 22,
+        libc::EINTR => 27,
+        libc::EINVAL => 28,
+        libc::EIO => 29,
+        libc::EISDIR => 31,
+        libc::ELOOP => 32,
+        libc::EMFILE => 33,
+        libc::EMLINK => 34,
+        libc::ENAMETOOLONG => 37,
+        libc::ENFILE => 41,
+        libc::ENODEV => 43,
+        libc::ENOENT => 44,
+        libc::ENOEXEC => 45,
+        libc::ENOLCK => 46,
+        libc::ENOMEM => 48,
+        libc::ENOSPC => 51,
+        libc::ENOSYS => 52,
+        libc::ENOTDIR => 54,
+        libc::ENOTEMPTY => 55,
+        libc::ENOTTY => 59,
+        libc::ENXIO => 60,
+        libc::EPERM => 63,
+        libc::EPIPE => 64,
+        libc::ERANGE => 68,
+        libc::EROFS => 69,
+        libc::ESPIPE => 70,
+        libc::ESRCH => 71,
+        libc::EXDEV => 75,
+        _ => 29,
+    }
+}
+
 // pub enum ErrnoCodes {
 //     EPERM = 1,
 //     ENOENT = 2,
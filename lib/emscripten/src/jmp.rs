@@ -74,6 +74,14 @@ pub fn _longjmp(
     Err(LongJumpRet)
 }
 
+/// emscripten: _emscripten_throw_longjmp, used by recent versions once
+/// `setThrew` was called, unwinding to the `invoke_*` call that resumes
+/// after the `setjmp`.
+pub fn _emscripten_throw_longjmp(_ctx: FunctionEnvMut<EmEnv>) -> Result<(), LongJumpRet> {
+    debug!("emscripten::_emscripten_throw_longjmp");
+    Err(LongJumpRet)
+}
+
 // extern "C" {
 //     fn setjmp(env: *mut c_void) -> c_int;
 //     fn longjmp(env: *mut c_void, val: c_int) -> !;
@@ -19,14 +19,14 @@
 extern crate log;
 
 use lazy_static::lazy_static;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::f64;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use wasmer::{
     imports, namespace, AsStoreMut, AsStoreRef, ExportError, Exports, Function, FunctionEnv,
     FunctionEnvMut, FunctionType, Global, Imports, Instance, Memory, MemoryType, Module, Pages,
-    RuntimeError, Table, TableType, TypedFunction, Value, WasmPtr,
+    RuntimeError, Table, TableType, TypedFunction, Value, WasmPtr, WasmTypeList,
 };
 use wasmer_types::Type as ValType;
 
@@ -44,6 +44,7 @@ mod macros;
 
 // EMSCRIPTEN APIS
 mod bitwise;
+mod ccall;
 mod emscripten_target;
 mod env;
 mod errno;
@@ -61,6 +62,7 @@ mod memory;
 mod process;
 mod pthread;
 mod signal;
+mod stdio;
 mod storage;
 mod syscalls;
 mod time;
@@ -69,10 +71,15 @@ mod unistd;
 mod utils;
 mod varargs;
 
+use self::linking::Linker;
+
+pub use self::ccall::{emscripten_ccall, Ccall, CcallArg, CcallReturn, CcallValue};
+pub use self::linking::DylinkInfo;
+pub use self::stdio::EmscriptenStdio;
 pub use self::storage::{align_memory, static_alloc};
 pub use self::utils::{
     allocate_cstr_on_stack, allocate_on_stack, get_emscripten_memory_size, get_emscripten_metadata,
-    get_emscripten_table_size, is_emscripten_module,
+    get_emscripten_table_size, is_emscripten_module, is_modern_emscripten_module,
 };
 
 /// State of the emscripten environment (environment variables, CLI args)
@@ -104,6 +111,8 @@ pub struct EmEnv {
     // State that is passed to the wasm module (environment variables, CLI args, ...)
     #[allow(dead_code)]
     state: Arc<Mutex<EmscriptenState>>,
+    linker: Arc<Mutex<Linker>>,
+    stdio: Arc<Mutex<EmscriptenStdio>>,
 }
 
 impl Default for EmEnv {
@@ -120,6 +129,8 @@ impl EmEnv {
             data: Arc::new(Mutex::new(None)),
             funcs: Arc::new(Mutex::new(EmscriptenFunctions::new())),
             state: Arc::new(Mutex::new(EmscriptenState::default())),
+            linker: Arc::new(Mutex::new(Linker::default())),
+            stdio: Arc::new(Mutex::new(EmscriptenStdio::default())),
         }
     }
 
@@ -129,6 +140,8 @@ impl EmEnv {
             data: Arc::new(Mutex::new(None)),
             funcs: Arc::new(Mutex::new(EmscriptenFunctions::new())),
             state: Arc::new(Mutex::new(emstate)),
+            linker: Arc::new(Mutex::new(Linker::default())),
+            stdio: Arc::new(Mutex::new(EmscriptenStdio::default())),
         }
     }
 
@@ -152,6 +165,30 @@ impl EmEnv {
         *w = Some(EmscriptenData::new(data.clone(), mapped_dirs));
     }
 
+    /// Get the state of the dynamic linker
+    pub(crate) fn linker(&self) -> MutexGuard<'_, Linker> {
+        self.linker.lock().unwrap()
+    }
+
+    /// Marks mapped directories, by their guest path, as read-only. Must be
+    /// called after `set_data`.
+    pub fn set_read_only_dirs(&self, dirs: impl IntoIterator<Item = String>) {
+        let mut w = self.data.lock().unwrap();
+        if let Some(data) = w.as_mut() {
+            data.read_only_dirs = dirs.into_iter().collect();
+        }
+    }
+
+    /// Redirects the standard output and error streams of the module.
+    pub fn set_stdio(&self, stdio: EmscriptenStdio) {
+        *self.stdio.lock().unwrap() = stdio;
+    }
+
+    /// Get the writers of the standard streams
+    pub(crate) fn stdio(&self) -> MutexGuard<'_, EmscriptenStdio> {
+        self.stdio.lock().unwrap()
+    }
+
     pub fn get_env_var(&self, key: &str) -> Option<String> {
         let w = self.state.lock().ok()?;
         let result = w.env_vars.get(key).cloned();
@@ -302,6 +339,8 @@ pub struct EmscriptenData {
     pub temp_ret_0: i32,
 
     pub mapped_dirs: HashMap<String, PathBuf>,
+    /// The guest paths of the mapped directories that can't be modified
+    pub read_only_dirs: HashSet<String>,
 }
 
 impl EmscriptenData {
@@ -563,6 +602,10 @@ pub fn set_up_emscripten(
     if let Ok(func) = instance.exports.get_function("globalCtors") {
         func.call(store, &[])?;
     }
+    // Recent versions of Emscripten
+    if let Ok(func) = instance.exports.get_function("__wasm_call_ctors") {
+        func.call(store, &[])?;
+    }
 
     if let Ok(func) = instance
         .exports
@@ -651,15 +694,64 @@ pub fn emscripten_call_main(
     Ok(())
 }
 
-/// Top level function to execute emscripten
-pub fn run_emscripten_instance(
+/// Recent versions of Emscripten don't export `dynCall_*` functions anymore,
+/// function pointers are called through the exported table instead.
+fn get_dyn_call<Args, Rets>(
+    instance: &Instance,
+    env: &mut FunctionEnvMut<EmEnv>,
+    table: Option<&Table>,
+    name: &str,
+) -> Result<TypedFunction<Args, Rets>, ExportError>
+where
+    Args: WasmTypeList,
+    Rets: WasmTypeList,
+{
+    let export = instance.exports.get_typed_function(env, name);
+    let table = match (export, table) {
+        (Ok(func), _) => return Ok(func),
+        (Err(_), Some(table)) => table.clone(),
+        (Err(e), None) => return Err(e),
+    };
+
+    // The function pointer comes first, followed by the arguments
+    let ty = FunctionType::new(Args::wasm_types(), Rets::wasm_types());
+    let func_env = env.as_ref();
+    let func = Function::new_with_env(
+        env,
+        &func_env,
+        ty,
+        move |mut ctx: FunctionEnvMut<EmEnv>, args: &[Value]| {
+            let index = args[0].unwrap_i32() as u32;
+            match table.get(&mut ctx, index) {
+                Some(Value::FuncRef(Some(func))) => Ok(func.call(&mut ctx, &args[1..])?.into_vec()),
+                _ => Err(RuntimeError::new(format!(
+                    "Invalid function pointer {index} in the table"
+                ))),
+            }
+        },
+    );
+    func.typed(env).map_err(|_| ExportError::IncompatibleType)
+}
+
+/// Sets up an instance of an Emscripten module: finds the functions it
+/// exports, links it and runs its global constructors.
+pub fn set_up_emscripten_instance(
     instance: &mut Instance,
-    mut env: FunctionEnvMut<EmEnv>,
+    env: &mut FunctionEnvMut<EmEnv>,
     globals: &mut EmscriptenGlobals,
-    path: &str,
-    args: Vec<&str>,
-    entrypoint: Option<String>,
 ) -> Result<(), RuntimeError> {
+    // Recent versions of Emscripten define their own memory and table
+    if let Ok(memory) = instance.exports.get_memory("memory") {
+        globals.memory = memory.clone();
+    }
+    let exported_table = instance
+        .exports
+        .get_table("__indirect_function_table")
+        .ok()
+        .cloned();
+    if let Some(table) = &exported_table {
+        globals.table = table.clone();
+    }
     env.data_mut().set_memory(globals.memory.clone());
 
     // get emscripten export
@@ -686,210 +778,229 @@ pub fn run_emscripten_instance(
     }
     if let Ok(func) = instance.exports.get_typed_function(&env, "stackAlloc") {
         emfuncs.stack_alloc = Some(func);
+    } else if let Ok(func) = instance
+        .exports
+        .get_typed_function(&env, "_emscripten_stack_alloc")
+    {
+        emfuncs.stack_alloc = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_i") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_i") {
         emfuncs.dyn_call_i = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_ii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_ii") {
         emfuncs.dyn_call_ii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iii") {
         emfuncs.dyn_call_iii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiii") {
         emfuncs.dyn_call_iiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iifi") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iifi") {
         emfuncs.dyn_call_iifi = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_v") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_v") {
         emfuncs.dyn_call_v = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vi") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vi") {
         emfuncs.dyn_call_vi = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vii") {
         emfuncs.dyn_call_vii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viii") {
         emfuncs.dyn_call_viii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiii") {
         emfuncs.dyn_call_viiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_dii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_dii") {
         emfuncs.dyn_call_dii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_diiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_diiii") {
         emfuncs.dyn_call_diiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iiiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiiii") {
         emfuncs.dyn_call_iiiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iiiiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiiiii") {
         emfuncs.dyn_call_iiiiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iiiiiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiiiiii") {
         emfuncs.dyn_call_iiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_iiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiiiiiii") {
         emfuncs.dyn_call_iiiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_iiiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiiiiiiii") {
         emfuncs.dyn_call_iiiiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_iiiiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiiiiiiiii") {
         emfuncs.dyn_call_iiiiiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_iiiiiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(
+        instance,
+        env,
+        exported_table.as_ref(),
+        "dynCall_iiiiiiiiiii",
+    ) {
         emfuncs.dyn_call_iiiiiiiiiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vd") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vd") {
         emfuncs.dyn_call_vd = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viiiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiiii") {
         emfuncs.dyn_call_viiiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viiiiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiiiii") {
         emfuncs.dyn_call_viiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiiiiii") {
         emfuncs.dyn_call_viiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiiiiiii") {
         emfuncs.dyn_call_viiiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viiiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiiiiiiii") {
         emfuncs.dyn_call_viiiiiiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viiiiiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(
+        instance,
+        env,
+        exported_table.as_ref(),
+        "dynCall_viiiiiiiiii",
+    ) {
         emfuncs.dyn_call_viiiiiiiiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iij") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iij") {
         emfuncs.dyn_call_iij = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iji") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iji") {
         emfuncs.dyn_call_iji = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iiji") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiji") {
         emfuncs.dyn_call_iiji = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_iiijj") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_iiijj") {
         emfuncs.dyn_call_iiijj = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_j") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_j") {
         emfuncs.dyn_call_j = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_ji") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_ji") {
         emfuncs.dyn_call_ji = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_jii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_jii") {
         emfuncs.dyn_call_jii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_jij") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_jij") {
         emfuncs.dyn_call_jij = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_jjj") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_jjj") {
         emfuncs.dyn_call_jjj = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viiij") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiij") {
         emfuncs.dyn_call_viiij = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viiijiiii")
-    {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiijiiii") {
         emfuncs.dyn_call_viiijiiii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viiijiiiiii")
-    {
+    if let Ok(func) = get_dyn_call(
+        instance,
+        env,
+        exported_table.as_ref(),
+        "dynCall_viiijiiiiii",
+    ) {
         emfuncs.dyn_call_viiijiiiiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viij") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viij") {
         emfuncs.dyn_call_viij = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viiji") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viiji") {
         emfuncs.dyn_call_viiji = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viijiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viijiii") {
         emfuncs.dyn_call_viijiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viijj") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viijj") {
         emfuncs.dyn_call_viijj = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vj") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vj") {
         emfuncs.dyn_call_vj = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vjji") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vjji") {
         emfuncs.dyn_call_vjji = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vij") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vij") {
         emfuncs.dyn_call_vij = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viji") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viji") {
         emfuncs.dyn_call_viji = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vijiii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vijiii") {
         emfuncs.dyn_call_vijiii = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vijj") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vijj") {
         emfuncs.dyn_call_vijj = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viid") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viid") {
         emfuncs.dyn_call_viid = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_vidd") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_vidd") {
         emfuncs.dyn_call_vidd = Some(func);
     }
-    if let Ok(func) = instance.exports.get_typed_function(&env, "dynCall_viidii") {
+    if let Ok(func) = get_dyn_call(instance, env, exported_table.as_ref(), "dynCall_viidii") {
         emfuncs.dyn_call_viidii = Some(func);
     }
-    if let Ok(func) = instance
-        .exports
-        .get_typed_function(&env, "dynCall_viidddddddd")
-    {
+    if let Ok(func) = get_dyn_call(
+        instance,
+        env,
+        exported_table.as_ref(),
+        "dynCall_viidddddddd",
+    ) {
         emfuncs.dyn_call_viidddddddd = Some(func);
     }
     if let Ok(func) = instance.exports.get_typed_function(&env, "stackSave") {
         emfuncs.stack_save = Some(func);
+    } else if let Ok(func) = instance
+        .exports
+        .get_typed_function(&env, "emscripten_stack_get_current")
+    {
+        emfuncs.stack_save = Some(func);
     }
     if let Ok(func) = instance.exports.get_typed_function(&env, "stackRestore") {
         emfuncs.stack_restore = Some(func);
+    } else if let Ok(func) = instance
+        .exports
+        .get_typed_function(&env, "_emscripten_stack_restore")
+    {
+        emfuncs.stack_restore = Some(func);
     }
     if let Ok(func) = instance.exports.get_typed_function(&env, "setThrew") {
         emfuncs.set_threw = Some(func);
     }
     env.data().set_functions(emfuncs);
 
-    set_up_emscripten(&mut env, instance)?;
+    let memory_base = globals.data.relocatable.then_some(globals.data.memory_base);
+    linking::link_main_module(env, instance, &globals.table, memory_base)?;
+
+    set_up_emscripten(env, instance)?;
+
+    Ok(())
+}
 
-    let main_func_names = ["_main", "main"];
+/// Top level function to execute emscripten
+pub fn run_emscripten_instance(
+    instance: &mut Instance,
+    mut env: FunctionEnvMut<EmEnv>,
+    globals: &mut EmscriptenGlobals,
+    path: &str,
+    args: Vec<&str>,
+    entrypoint: Option<String>,
+) -> Result<(), RuntimeError> {
+    set_up_emscripten_instance(instance, &mut env, globals)?;
+
+    let main_func_names = ["_main", "main", "__main_argc_argv"];
     if let Some(ep) = entrypoint.as_ref() {
         debug!("Running entry point: {}", ep);
         emscripten_call_main(instance, ep, env, path, &args)?;
@@ -955,6 +1066,8 @@ pub struct EmscriptenGlobalsData {
     table_base: u32,
     temp_double_ptr: u32,
     use_old_abort_on_cannot_grow_memory: bool,
+    // Whether the module was built with `-sMAIN_MODULE`
+    relocatable: bool,
 }
 
 pub struct EmscriptenGlobals {
@@ -984,8 +1097,15 @@ impl EmscriptenGlobals {
             }
         }
 
-        let (table_min, table_max) = get_emscripten_table_size(module)?;
+        // Modules built with `-sMAIN_MODULE` are relocatable: their data is
+        // placed at `__memory_base` and their functions at `__table_base`
+        let dylink = DylinkInfo::from_module(module)?;
+
+        let (mut table_min, table_max) = get_emscripten_table_size(module)?;
         let (memory_min, memory_max, shared) = get_emscripten_memory_size(module)?;
+        if let Some(dylink) = &dylink {
+            table_min = table_min.max(1 + dylink.table_size);
+        }
 
         // Memory initialization
         let memory_type = MemoryType::new(memory_min, memory_max, shared);
@@ -999,12 +1119,16 @@ impl EmscriptenGlobals {
         let table = Table::new(&mut store, table_type, Value::FuncRef(None)).unwrap();
 
         let data = {
-            let static_bump = STATIC_BUMP;
+            let static_bump = match &dylink {
+                Some(dylink) => align_memory(dylink.memory_size),
+                None => STATIC_BUMP,
+            };
 
             let mut static_top = STATIC_BASE + static_bump;
 
             let memory_base = STATIC_BASE;
-            let table_base = 0;
+            // Keep the null function pointer out of the table
+            let table_base = if dylink.is_some() { 1 } else { 0 };
 
             let temp_double_ptr = static_top;
             static_top += 16;
@@ -1031,10 +1155,32 @@ impl EmscriptenGlobals {
                 table_base,
                 temp_double_ptr,
                 use_old_abort_on_cannot_grow_memory,
+                relocatable: dylink.is_some(),
             }
         };
 
-        emscripten_set_up_memory(store, env, &memory, &data)?;
+        if module.imports().memories().next().is_some() {
+            emscripten_set_up_memory(store, env, &memory, &data)?;
+        } else {
+            // Recent versions of Emscripten manage their heap by themselves
+            // in the memory they export
+            env.as_ref(store).set_memory(memory.clone());
+        }
+
+        if data.relocatable {
+            let stack_pointer = Global::new_mut(&mut store, Value::I32(data.stack_max as i32));
+            env.as_ref(store).linker().set_stack_pointer(stack_pointer);
+        }
+        for import in module.imports().globals() {
+            if matches!(import.module(), "GOT.mem" | "GOT.func") {
+                let global = Global::new_mut(&mut store, Value::I32(0));
+                env.as_ref(store).linker().add_main_got_entry(
+                    import.module(),
+                    import.name(),
+                    global,
+                );
+            }
+        }
 
         let mut null_function_names = vec![];
         for import in module.imports().functions() {
@@ -1075,6 +1221,7 @@ pub fn generate_emscripten_env(
     let mut env_ns: Exports = namespace! {
         "memory" => globals.memory.clone(),
         "table" => globals.table.clone(),
+        "__indirect_function_table" => globals.table.clone(),
 
         // Globals
         "STACKTOP" => Global::new(&mut store, Value::I32(globals.data.stacktop as i32)),
@@ -1095,6 +1242,11 @@ pub fn generate_emscripten_env(
         // IO
         "printf" => Function::new_typed_with_env(&mut store, env, crate::io::printf),
         "putchar" => Function::new_typed_with_env(&mut store, env, crate::io::putchar),
+        "_emscripten_out" => Function::new_typed_with_env(&mut store, env, crate::stdio::emscripten_out),
+        "_emscripten_err" => Function::new_typed_with_env(&mut store, env, crate::stdio::emscripten_err),
+        "_emscripten_console_log" => Function::new_typed_with_env(&mut store, env, crate::stdio::emscripten_console_log),
+        "_emscripten_console_warn" => Function::new_typed_with_env(&mut store, env, crate::stdio::emscripten_console_warn),
+        "_emscripten_console_error" => Function::new_typed_with_env(&mut store, env, crate::stdio::emscripten_console_error),
         "___lock" => Function::new_typed_with_env(&mut store, env, crate::lock::___lock),
         "___unlock" => Function::new_typed_with_env(&mut store, env, crate::lock::___unlock),
         "___wait" => Function::new_typed_with_env(&mut store, env, crate::lock::___wait),
@@ -1246,9 +1398,30 @@ pub fn generate_emscripten_env(
         "___syscall340" => Function::new_typed_with_env(&mut store, env, crate::syscalls::___syscall340),
         "___syscall345" => Function::new_typed_with_env(&mut store, env, crate::syscalls::___syscall345),
 
+        // Syscalls of recent versions of Emscripten
+        "__syscall_openat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_openat),
+        "__syscall_fcntl64" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_fcntl64),
+        "__syscall_ioctl" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_ioctl),
+        "__syscall_getcwd" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_getcwd),
+        "__syscall_chdir" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_chdir),
+        "__syscall_stat64" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_stat64),
+        "__syscall_lstat64" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_lstat64),
+        "__syscall_fstat64" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_fstat64),
+        "__syscall_newfstatat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_newfstatat),
+        "__syscall_mkdirat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_mkdirat),
+        "__syscall_rmdir" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_rmdir),
+        "__syscall_unlinkat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_unlinkat),
+        "__syscall_renameat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_renameat),
+        "__syscall_faccessat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_faccessat),
+        "__syscall_readlinkat" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_readlinkat),
+        "__syscall_dup" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_dup),
+        "__syscall_dup3" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_dup3),
+        "__syscall_getdents64" => Function::new_typed_with_env(&mut store, env, crate::syscalls::__syscall_getdents64),
+
         // Process
         "abort" => Function::new_typed_with_env(&mut store, env, crate::process::em_abort),
         "_abort" => Function::new_typed_with_env(&mut store, env, crate::process::_abort),
+        "_abort_js" => Function::new_typed_with_env(&mut store, env, crate::process::_abort),
         "_prctl" => Function::new_typed_with_env(&mut store, env, crate::process::_prctl),
         "abortStackOverflow" => Function::new_typed_with_env(&mut store, env, crate::process::abort_stack_overflow),
         "_llvm_trap" => Function::new_typed_with_env(&mut store, env, crate::process::_llvm_trap),
@@ -1297,6 +1470,7 @@ pub fn generate_emscripten_env(
         // Memory
         "abortOnCannotGrowMemory" => abort_on_cannot_grow_memory_export,
         "_emscripten_memcpy_big" => Function::new_typed_with_env(&mut store, env, crate::memory::_emscripten_memcpy_big),
+        "_emscripten_memcpy_js" => Function::new_typed_with_env(&mut store, env, crate::memory::_emscripten_memcpy_js),
         "_emscripten_get_heap_size" => Function::new_typed_with_env(&mut store, env, crate::memory::_emscripten_get_heap_size),
         "_emscripten_resize_heap" => Function::new_typed_with_env(&mut store, env, crate::memory::_emscripten_resize_heap),
         "enlargeMemory" => Function::new_typed_with_env(&mut store, env, crate::memory::enlarge_memory),
@@ -1326,6 +1500,9 @@ pub fn generate_emscripten_env(
         "_clock_settime" => Function::new_typed_with_env(&mut store, env, crate::time::_clock_settime),
         "___clock_gettime" => Function::new_typed_with_env(&mut store, env, crate::time::_clock_gettime),
         "_clock" => Function::new_typed_with_env(&mut store, env, crate::time::_clock),
+        "emscripten_get_now" => Function::new_typed_with_env(&mut store, env, crate::time::emscripten_get_now),
+        "_emscripten_get_now_is_monotonic" => Function::new_typed_with_env(&mut store, env, crate::time::_emscripten_get_now_is_monotonic),
+        "emscripten_date_now" => Function::new_typed_with_env(&mut store, env, crate::time::emscripten_date_now),
         "_difftime" => Function::new_typed_with_env(&mut store, env, crate::time::_difftime),
         "_asctime" => Function::new_typed_with_env(&mut store, env, crate::time::_asctime),
         "_asctime_r" => Function::new_typed_with_env(&mut store, env, crate::time::_asctime_r),
@@ -1365,6 +1542,7 @@ pub fn generate_emscripten_env(
         "__longjmp" => Function::new_typed_with_env(&mut store, env, crate::jmp::__longjmp),
         "_longjmp" => Function::new_typed_with_env(&mut store, env, crate::jmp::_longjmp),
         "_emscripten_longjmp" => Function::new_typed_with_env(&mut store, env, crate::jmp::_longjmp),
+        "_emscripten_throw_longjmp" => Function::new_typed_with_env(&mut store, env, crate::jmp::_emscripten_throw_longjmp),
 
         // Bitwise
         "_llvm_bswap_i64" => Function::new_typed_with_env(&mut store, env, crate::bitwise::_llvm_bswap_i64),
@@ -1534,7 +1712,14 @@ pub fn generate_emscripten_env(
         );
     }
 
-    let import_object: Imports = imports! {
+    if globals.data.relocatable {
+        env_ns.insert(
+            "__heap_base",
+            Global::new(&mut store, Value::I32(globals.data.dynamic_base as i32)),
+        );
+    }
+
+    let mut import_object: Imports = imports! {
         "env" => env_ns,
         "global" => {
           "NaN" => Global::new(&mut store, Value::F64(f64::NAN)),
@@ -1551,6 +1736,15 @@ pub fn generate_emscripten_env(
         },
     };
 
+    // Side modules loaded with `dlopen` link against the same functions
+    env.as_ref(store)
+        .linker()
+        .set_host_imports(import_object.clone());
+    let main_module_imports = env.as_ref(store).linker().main_module_imports();
+    for (module, name, global) in main_module_imports {
+        import_object.define(module, &name, global);
+    }
+
     import_object
 }
 
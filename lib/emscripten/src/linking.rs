@@ -1,28 +1,637 @@
+//! Dynamic linking of side modules (`-sSIDE_MODULE`) into a main module
+//! (`-sMAIN_MODULE`), following the conventions of Emscripten's dynamic
+//! linker.
+//!
+//! Side modules are relocatable: their data is placed in memory allocated by
+//! the main module's `malloc`, their functions are appended to the shared
+//! function table, and the addresses they take of symbols defined elsewhere
+//! go through the `GOT.mem` and `GOT.func` globals filled in here.
+
+use crate::env::get_emscripten_funcs;
+use crate::utils::read_string_from_wasm;
 use crate::EmEnv;
-use wasmer::FunctionEnvMut;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use wasmer::{
+    AsStoreMut, AsStoreRef, Extern, ExternType, Function, FunctionEnvMut, Global, Imports,
+    Instance, Module, Mutability, RuntimeError, Table, Value,
+};
+
+/// `RTLD_GLOBAL`, which makes the symbols of a library available to the
+/// libraries loaded after it
+const RTLD_GLOBAL: u32 = 0x100;
+/// `RTLD_DEFAULT`, to look a symbol up in the global scope
+const RTLD_DEFAULT: u32 = 0;
+/// The handle of the main module, as returned by `dlopen(NULL, ...)`
+const MAIN_HANDLE: u32 = 1;
+
+const DYLINK_MEM_INFO: u8 = 1;
+const DYLINK_NEEDED: u8 = 2;
+
+const DLERROR_BUFFER_SIZE: u32 = 256;
+
+/// The metadata of a relocatable module, read from its `dylink.0` custom
+/// section (or the `dylink` section of older versions of Emscripten)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DylinkInfo {
+    /// The size of the module's static data
+    pub memory_size: u32,
+    /// The alignment of the module's static data, as a power of 2
+    pub memory_align: u32,
+    /// The number of entries the module adds to the function table
+    pub table_size: u32,
+    /// The alignment of the module's table entries, as a power of 2
+    pub table_align: u32,
+    /// The libraries the module depends on
+    pub needed: Vec<String>,
+}
+
+impl DylinkInfo {
+    /// Reads the dynamic linking metadata of a module, if it is relocatable
+    pub fn from_module(module: &Module) -> Result<Option<Self>, String> {
+        if let Some(section) = module.custom_sections("dylink.0").next() {
+            return Self::parse(&section).map(Some);
+        }
+        if let Some(section) = module.custom_sections("dylink").next() {
+            return Self::parse_legacy(&section).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let mut info = Self::default();
+        let mut reader = Reader { bytes, pos: 0 };
+        while !reader.is_empty() {
+            let kind = reader.byte()?;
+            let size = reader.uleb()? as usize;
+            let payload = reader.take(size)?;
+            let mut payload = Reader {
+                bytes: payload,
+                pos: 0,
+            };
+            match kind {
+                DYLINK_MEM_INFO => {
+                    info.memory_size = payload.uleb()?;
+                    info.memory_align = payload.uleb()?;
+                    info.table_size = payload.uleb()?;
+                    info.table_align = payload.uleb()?;
+                }
+                DYLINK_NEEDED => info.needed = payload.strings()?,
+                // The export and import infos only matter to TLS and weak
+                // symbols
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+
+    fn parse_legacy(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        Ok(Self {
+            memory_size: reader.uleb()?,
+            memory_align: reader.uleb()?,
+            table_size: reader.uleb()?,
+            table_align: reader.uleb()?,
+            needed: reader.strings()?,
+        })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self.bytes.get(self.pos).ok_or("truncated dylink section")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn uleb(&mut self) -> Result<u32, String> {
+        let mut value = 0u32;
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("invalid LEB128 in dylink section".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or("truncated dylink section")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn strings(&mut self) -> Result<Vec<String>, String> {
+        let count = self.uleb()?;
+        (0..count)
+            .map(|_| {
+                let len = self.uleb()? as usize;
+                let bytes = self.take(len)?;
+                String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Symbol {
+    Function(Function),
+    /// The absolute address of a data symbol
+    Data(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GotKind {
+    Memory,
+    Function,
+}
+
+/// An import of `GOT.mem` or `GOT.func`, to be set to the address of the
+/// symbol once it's resolved
+#[derive(Debug, Clone)]
+struct GotEntry {
+    kind: GotKind,
+    name: String,
+    global: Global,
+}
+
+#[derive(Debug)]
+struct Library {
+    path: Option<PathBuf>,
+    symbols: HashMap<String, Symbol>,
+}
+
+/// The state of the dynamic linker, shared by the main module and the side
+/// modules it loads
+#[derive(Default)]
+pub(crate) struct Linker {
+    host_imports: Imports,
+    stack_pointer: Option<Global>,
+    main_got: Vec<GotEntry>,
+    table: Option<Table>,
+    /// The loaded libraries, the handle of a library being its index + 1
+    libraries: Vec<Library>,
+    global_symbols: HashMap<String, Symbol>,
+    /// The table indices of the functions whose address was taken
+    table_slots: Vec<(Function, u32)>,
+    /// The canonical paths of the libraries whose dependencies are being
+    /// loaded, to detect cycles
+    loading: Vec<PathBuf>,
+    error: Option<String>,
+    error_buffer: u32,
+}
+
+impl Linker {
+    /// Sets the functions provided by the host, which side modules can
+    /// import as well
+    pub(crate) fn set_host_imports(&mut self, imports: Imports) {
+        self.host_imports = imports;
+    }
+
+    /// Sets the stack pointer provided to a relocatable main module
+    pub(crate) fn set_stack_pointer(&mut self, stack_pointer: Global) {
+        self.stack_pointer = Some(stack_pointer);
+    }
+
+    /// Adds a `GOT.mem` or `GOT.func` import of the main module
+    pub(crate) fn add_main_got_entry(&mut self, module: &str, name: &str, global: Global) {
+        let kind = match module {
+            "GOT.func" => GotKind::Function,
+            _ => GotKind::Memory,
+        };
+        self.main_got.push(GotEntry {
+            kind,
+            name: name.to_string(),
+            global,
+        });
+    }
+
+    /// The globals to provide to the `GOT` imports of the main module, and
+    /// its stack pointer if it's relocatable
+    pub(crate) fn main_module_imports(&self) -> Vec<(&'static str, String, Global)> {
+        let got = self.main_got.iter().map(|entry| {
+            let module = match entry.kind {
+                GotKind::Memory => "GOT.mem",
+                GotKind::Function => "GOT.func",
+            };
+            (module, entry.name.clone(), entry.global.clone())
+        });
+        let stack_pointer = self
+            .stack_pointer
+            .iter()
+            .map(|global| ("env", "__stack_pointer".to_string(), global.clone()));
+        got.chain(stack_pointer).collect()
+    }
+
+    fn find_library(&self, path: &Path) -> Option<u32> {
+        self.libraries
+            .iter()
+            .position(|library| library.path.as_deref() == Some(path))
+            .map(|index| index as u32 + 1)
+    }
+
+    fn library(&self, handle: u32) -> Option<&Library> {
+        handle
+            .checked_sub(1)
+            .and_then(|index| self.libraries.get(index as usize))
+    }
 
-// TODO: Need to implement.
+    fn add_library(
+        &mut self,
+        path: Option<PathBuf>,
+        symbols: HashMap<String, Symbol>,
+        global: bool,
+    ) -> u32 {
+        if global {
+            self.add_global_symbols(&symbols);
+        }
+        self.libraries.push(Library { path, symbols });
+        self.libraries.len() as u32
+    }
+
+    fn add_global_symbols(&mut self, symbols: &HashMap<String, Symbol>) {
+        // The first definition of a symbol wins
+        for (name, symbol) in symbols {
+            self.global_symbols
+                .entry(name.clone())
+                .or_insert_with(|| symbol.clone());
+        }
+    }
+
+    fn lookup(&self, handle: u32, name: &str) -> Result<Option<Symbol>, String> {
+        if handle == RTLD_DEFAULT || handle == MAIN_HANDLE {
+            return Ok(self.global_symbols.get(name).cloned());
+        }
+        self.library(handle)
+            .map(|library| library.symbols.get(name).cloned())
+            .ok_or_else(|| format!("invalid handle {handle}"))
+    }
+
+    /// Resolves a symbol for a module being linked, the global scope taking
+    /// precedence over the module's own definitions
+    fn resolve(&self, local: &HashMap<String, Symbol>, name: &str) -> Option<Symbol> {
+        self.global_symbols
+            .get(name)
+            .or_else(|| local.get(name))
+            .cloned()
+            .or_else(|| match self.host_imports.get_export("env", name) {
+                Some(Extern::Function(func)) => Some(Symbol::Function(func)),
+                _ => None,
+            })
+    }
+}
+
+fn set_error(ctx: &FunctionEnvMut<EmEnv>, error: String) {
+    debug!("emscripten::dlerror {}", error);
+    ctx.data().linker().error = Some(error);
+}
+
+/// Returns the index of a function in the function table, adding it the
+/// first time its address is taken
+fn table_index(ctx: &mut FunctionEnvMut<EmEnv>, func: &Function) -> Result<u32, String> {
+    let table = {
+        let linker = ctx.data().linker();
+        if let Some((_, index)) = linker.table_slots.iter().find(|(f, _)| f == func) {
+            return Ok(*index);
+        }
+        linker.table.clone().ok_or("no function table")?
+    };
+    let index = table
+        .grow(ctx, 1, Value::FuncRef(Some(func.clone())))
+        .map_err(|e| e.to_string())?;
+    ctx.data().linker().table_slots.push((func.clone(), index));
+    Ok(index)
+}
+
+/// The exports of a module, the addresses of its data being relocated by
+/// `memory_base`
+fn exported_symbols(
+    store: &mut impl AsStoreMut,
+    instance: &Instance,
+    memory_base: u32,
+) -> HashMap<String, Symbol> {
+    let mut symbols = HashMap::new();
+    for (name, export) in instance.exports.iter() {
+        if name.starts_with("__wasm_") {
+            continue;
+        }
+        let symbol = match export {
+            Extern::Function(func) => Symbol::Function(func.clone()),
+            Extern::Global(global) if global.ty(store).mutability == Mutability::Const => {
+                match global.get(store) {
+                    Value::I32(address) => Symbol::Data(memory_base.wrapping_add(address as u32)),
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        symbols.insert(name.clone(), symbol);
+    }
+    symbols
+}
+
+fn fill_got(
+    ctx: &mut FunctionEnvMut<EmEnv>,
+    got: &[GotEntry],
+    local: &HashMap<String, Symbol>,
+) -> Result<(), String> {
+    for entry in got {
+        let symbol = ctx.data().linker().resolve(local, &entry.name);
+        let value = match (entry.kind, symbol) {
+            (GotKind::Memory, Some(Symbol::Data(address))) => address,
+            (GotKind::Function, Some(Symbol::Function(func))) => table_index(ctx, &func)?,
+            _ => return Err(format!("undefined symbol: {}", entry.name)),
+        };
+        entry
+            .global
+            .set(ctx, Value::I32(value as i32))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn call_export(
+    store: &mut impl AsStoreMut,
+    instance: &Instance,
+    name: &str,
+) -> Result<(), RuntimeError> {
+    if let Ok(func) = instance.exports.get_function(name) {
+        func.call(store, &[])?;
+    }
+    Ok(())
+}
+
+/// Registers the main module with the dynamic linker and resolves its own
+/// `GOT` imports. A relocatable main module has its data at `memory_base`.
+pub(crate) fn link_main_module(
+    ctx: &mut FunctionEnvMut<EmEnv>,
+    instance: &Instance,
+    table: &Table,
+    memory_base: Option<u32>,
+) -> Result<(), RuntimeError> {
+    let symbols = exported_symbols(ctx, instance, memory_base.unwrap_or(0));
+    let got = {
+        let mut linker = ctx.data().linker();
+        linker.table = Some(table.clone());
+        if linker.stack_pointer.is_none() {
+            linker.stack_pointer = instance.exports.get_global("__stack_pointer").ok().cloned();
+        }
+        linker.add_library(None, symbols, true);
+        std::mem::take(&mut linker.main_got)
+    };
+    fill_got(ctx, &got, &HashMap::new()).map_err(RuntimeError::new)?;
+    if memory_base.is_some() {
+        call_export(ctx, instance, "__wasm_apply_data_relocs")?;
+    }
+    Ok(())
+}
+
+/// Allocates and clears the static data of a side module
+fn allocate_data(ctx: &mut FunctionEnvMut<EmEnv>, info: &DylinkInfo) -> Result<u32, String> {
+    if info.memory_size == 0 {
+        return Ok(0);
+    }
+    let malloc = get_emscripten_funcs(ctx)
+        .malloc_ref()
+        .cloned()
+        .ok_or("the main module doesn't export malloc")?;
+    let align = 1u32 << info.memory_align.min(16);
+    let size = info
+        .memory_size
+        .checked_add(align)
+        .ok_or("the data segment is too large")?;
+    let ptr = malloc.call(ctx, size).map_err(|e| e.to_string())?;
+    if ptr == 0 {
+        return Err("out of memory".to_string());
+    }
+    // malloc is provided by the main module, which may not be well-behaved
+    let memory_base = ptr
+        .checked_add(align - 1)
+        .ok_or("malloc returned an invalid pointer")?
+        & !(align - 1);
+
+    let memory = ctx.data().memory(0);
+    memory
+        .view(ctx)
+        .write(memory_base as u64, &vec![0; info.memory_size as usize])
+        .map_err(|e| e.to_string())?;
+    Ok(memory_base)
+}
+
+fn load_library(ctx: &mut FunctionEnvMut<EmEnv>, path: &Path, flags: u32) -> Result<u32, String> {
+    let global = flags & RTLD_GLOBAL != 0;
+    {
+        let mut linker = ctx.data().linker();
+        if let Some(handle) = linker.find_library(path) {
+            if global {
+                let symbols = linker.library(handle).unwrap().symbols.clone();
+                linker.add_global_symbols(&symbols);
+            }
+            return Ok(handle);
+        }
+    }
+
+    // The imports of a library are resolved before its exports exist, so a
+    // library can't depend on itself, even indirectly
+    let error = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+    let canonical = std::fs::canonicalize(path).map_err(|e| error(&e))?;
+    if ctx.data().linker().loading.contains(&canonical) {
+        return Err(error(&"circular dependency"));
+    }
+    ctx.data().linker().loading.push(canonical);
+    let result = instantiate_library(ctx, path, global);
+    ctx.data().linker().loading.pop();
+    result
+}
+
+/// Load and instantiate the side module at `path`, once its dependencies are
+/// loaded.
+fn instantiate_library(
+    ctx: &mut FunctionEnvMut<EmEnv>,
+    path: &Path,
+    global: bool,
+) -> Result<u32, String> {
+    let error = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+    let bytes = std::fs::read(path).map_err(|e| error(&e))?;
+    let engine = ctx.as_store_ref().engine().clone();
+    let module = Module::new(&engine, bytes).map_err(|e| error(&e))?;
+    let info = DylinkInfo::from_module(&module)
+        .map_err(|e| error(&e))?
+        .ok_or_else(|| error(&"not a side module"))?;
+
+    // Dependencies are always loaded in the global scope
+    for needed in &info.needed {
+        let needed = match path.parent() {
+            Some(dir) => dir.join(needed),
+            None => PathBuf::from(needed),
+        };
+        load_library(ctx, &needed, RTLD_GLOBAL)?;
+    }
+
+    let memory_base = allocate_data(ctx, &info)?;
+    let (table, stack_pointer, host_imports) = {
+        let linker = ctx.data().linker();
+        (
+            linker.table.clone().ok_or("no function table")?,
+            linker.stack_pointer.clone(),
+            linker.host_imports.clone(),
+        )
+    };
+    let table_base = table.size(ctx);
+    if info.table_size > 0 {
+        table
+            .grow(ctx, info.table_size, Value::FuncRef(None))
+            .map_err(|e| error(&e))?;
+    }
+
+    let memory = ctx.data().memory(0);
+    let mut imports = Imports::new();
+    let mut got = vec![];
+    for import in module.imports() {
+        let (namespace, name) = (import.module(), import.name());
+        let export: Extern = match (namespace, name) {
+            ("env", "memory") => memory.clone().into(),
+            ("env", "__indirect_function_table") | ("env", "table") => table.clone().into(),
+            ("env", "__memory_base") => Global::new(ctx, Value::I32(memory_base as i32)).into(),
+            ("env", "__table_base") => Global::new(ctx, Value::I32(table_base as i32)).into(),
+            ("env", "__stack_pointer") => stack_pointer
+                .clone()
+                .ok_or_else(|| error(&"the main module doesn't export __stack_pointer"))?
+                .into(),
+            ("GOT.mem", _) | ("GOT.func", _) => {
+                let global = Global::new_mut(ctx, Value::I32(0));
+                got.push(GotEntry {
+                    kind: if namespace == "GOT.func" {
+                        GotKind::Function
+                    } else {
+                        GotKind::Memory
+                    },
+                    name: name.to_string(),
+                    global: global.clone(),
+                });
+                global.into()
+            }
+            ("env", _) if matches!(import.ty(), ExternType::Function(_)) => {
+                match ctx.data().linker().resolve(&HashMap::new(), name) {
+                    Some(Symbol::Function(func)) => func.into(),
+                    _ => return Err(error(&format_args!("undefined symbol: {name}"))),
+                }
+            }
+            _ => host_imports
+                .get_export(namespace, name)
+                .ok_or_else(|| error(&format_args!("undefined symbol: {namespace}.{name}")))?,
+        };
+        imports.define(namespace, name, export);
+    }
+
+    let instance = Instance::new(ctx, &module, &imports).map_err(|e| error(&e))?;
+    let symbols = exported_symbols(ctx, &instance, memory_base);
+    fill_got(ctx, &got, &symbols).map_err(|e| error(&e))?;
+
+    call_export(ctx, &instance, "__wasm_apply_data_relocs").map_err(|e| error(&e))?;
+    let handle = ctx
+        .data()
+        .linker()
+        .add_library(Some(path.to_path_buf()), symbols, global);
+    call_export(ctx, &instance, "__wasm_call_ctors").map_err(|e| error(&e))?;
+    call_export(ctx, &instance, "__post_instantiate").map_err(|e| error(&e))?;
+    Ok(handle)
+}
 
 /// emscripten: dlopen(filename: *const c_char, flag: c_int) -> *mut c_void
-pub fn _dlopen(mut _ctx: FunctionEnvMut<EmEnv>, _filename: u32, _flag: u32) -> i32 {
+pub fn _dlopen(mut ctx: FunctionEnvMut<EmEnv>, filename: u32, flag: u32) -> i32 {
     debug!("emscripten::_dlopen");
-    -1
+    if filename == 0 {
+        return MAIN_HANDLE as i32;
+    }
+
+    let memory = ctx.data().memory(0);
+    let path_ptr = emscripten_memory_pointer!(memory.view(&ctx), filename) as *const i8;
+    let path = crate::utils::get_cstr_path(ctx.as_mut(), path_ptr)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|| read_string_from_wasm(&memory.view(&ctx), filename));
+    debug!("=> path: {}, flag: {:#x}", path, flag);
+
+    match load_library(&mut ctx, Path::new(&path), flag) {
+        Ok(handle) => handle as i32,
+        Err(error) => {
+            set_error(&ctx, error);
+            0
+        }
+    }
 }
 
 /// emscripten: dlclose(handle: *mut c_void) -> c_int
-pub fn _dlclose(mut _ctx: FunctionEnvMut<EmEnv>, _filename: u32) -> i32 {
-    debug!("emscripten::_dlclose");
-    -1
+pub fn _dlclose(ctx: FunctionEnvMut<EmEnv>, handle: u32) -> i32 {
+    debug!("emscripten::_dlclose {}", handle);
+    // Like Emscripten, libraries are never unloaded as their functions may
+    // still be referenced by the function table
+    let loaded = ctx.data().linker().library(handle).is_some();
+    if loaded {
+        0
+    } else {
+        set_error(&ctx, format!("invalid handle {handle}"));
+        -1
+    }
 }
 
 /// emscripten: dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void
-pub fn _dlsym(mut _ctx: FunctionEnvMut<EmEnv>, _filepath: u32, _symbol: u32) -> i32 {
-    debug!("emscripten::_dlsym");
-    -1
+pub fn _dlsym(mut ctx: FunctionEnvMut<EmEnv>, handle: u32, symbol: u32) -> i32 {
+    let memory = ctx.data().memory(0);
+    let name = read_string_from_wasm(&memory.view(&ctx), symbol);
+    debug!("emscripten::_dlsym {} {}", handle, name);
+
+    let found = ctx.data().linker().lookup(handle, &name);
+    let address = match found {
+        Ok(Some(Symbol::Data(address))) => Ok(address),
+        Ok(Some(Symbol::Function(func))) => table_index(&mut ctx, &func),
+        Ok(None) => Err(format!("undefined symbol: {name}")),
+        Err(error) => Err(error),
+    };
+    match address {
+        Ok(address) => address as i32,
+        Err(error) => {
+            set_error(&ctx, error);
+            0
+        }
+    }
 }
 
 /// emscripten: dlerror() -> *mut c_char
-pub fn _dlerror(mut _ctx: FunctionEnvMut<EmEnv>) -> i32 {
+pub fn _dlerror(mut ctx: FunctionEnvMut<EmEnv>) -> i32 {
     debug!("emscripten::_dlerror");
-    -1
+    let (error, mut buffer) = {
+        let mut linker = ctx.data().linker();
+        match linker.error.take() {
+            Some(error) => (error, linker.error_buffer),
+            None => return 0,
+        }
+    };
+
+    if buffer == 0 {
+        buffer = crate::env::call_malloc(&mut ctx, DLERROR_BUFFER_SIZE);
+        ctx.data().linker().error_buffer = buffer;
+    }
+    let mut bytes = error.into_bytes();
+    bytes.truncate(DLERROR_BUFFER_SIZE as usize - 1);
+    bytes.push(0);
+
+    let memory = ctx.data().memory(0);
+    match memory.view(&ctx).write(buffer as u64, &bytes) {
+        Ok(()) => buffer as i32,
+        Err(_) => 0,
+    }
 }
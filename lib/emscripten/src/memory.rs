@@ -20,6 +20,12 @@ pub fn _emscripten_memcpy_big(ctx: FunctionEnvMut<EmEnv>, dest: u32, src: u32, l
     dest
 }
 
+/// emscripten: _emscripten_memcpy_js, which replaced `_emscripten_memcpy_big`
+/// in recent versions and doesn't return the destination anymore
+pub fn _emscripten_memcpy_js(ctx: FunctionEnvMut<EmEnv>, dest: u32, src: u32, len: u32) {
+    _emscripten_memcpy_big(ctx, dest, src, len);
+}
+
 fn get_heap_size(ctx: &FunctionEnvMut<EmEnv>) -> u32 {
     let memory = ctx.data().memory(0);
     memory.view(&ctx).size().bytes().0 as u32
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use libc::c_int;
+use wasmer::{FunctionEnvMut, WasmPtr};
+
+use crate::EmEnv;
+
+type SharedWriter = Arc<Mutex<dyn Write + Send>>;
+
+/// Where the standard output and error streams of an Emscripten module go.
+///
+/// Streams without a writer are the ones of the host process. Only what the
+/// module writes through its syscalls and the `print`/`printErr` family of
+/// imports is captured, output produced by the host `printf` isn't.
+#[derive(Clone, Default)]
+pub struct EmscriptenStdio {
+    stdout: Option<SharedWriter>,
+    stderr: Option<SharedWriter>,
+}
+
+impl EmscriptenStdio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send everything the module writes to `stdout` to a [`Write`].
+    pub fn stdout_writer(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.set_stdout_writer(writer);
+        self
+    }
+
+    /// Send everything the module writes to `stdout` to a [`Write`].
+    pub fn set_stdout_writer(&mut self, writer: impl Write + Send + 'static) {
+        self.stdout = Some(Arc::new(Mutex::new(writer)));
+    }
+
+    /// Send everything the module writes to `stderr` to a [`Write`].
+    pub fn stderr_writer(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.set_stderr_writer(writer);
+        self
+    }
+
+    /// Send everything the module writes to `stderr` to a [`Write`].
+    pub fn set_stderr_writer(&mut self, writer: impl Write + Send + 'static) {
+        self.stderr = Some(Arc::new(Mutex::new(writer)));
+    }
+
+    /// A handle on the writer of `stdout`, if it is redirected.
+    pub fn stdout(&self) -> Option<impl Write + Send + 'static> {
+        self.stdout.clone().map(StreamWriter)
+    }
+
+    /// A handle on the writer of `stderr`, if it is redirected.
+    pub fn stderr(&self) -> Option<impl Write + Send + 'static> {
+        self.stderr.clone().map(StreamWriter)
+    }
+
+    fn writer(&self, fd: c_int) -> Option<&SharedWriter> {
+        match fd {
+            1 => self.stdout.as_ref(),
+            2 => self.stderr.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Writes `buf` to the writer of `fd`, `None` when the file descriptor
+    /// isn't redirected.
+    pub(crate) fn write(&self, fd: c_int, buf: &[u8]) -> Option<io::Result<usize>> {
+        let mut writer = self.writer(fd)?.lock().unwrap();
+        Some(writer.write_all(buf).map(|()| buf.len()))
+    }
+}
+
+impl fmt::Debug for EmscriptenStdio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmscriptenStdio")
+            .field("stdout", &self.stdout.is_some())
+            .field("stderr", &self.stderr.is_some())
+            .finish()
+    }
+}
+
+struct StreamWriter(SharedWriter);
+
+impl Write for StreamWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+}
+
+/// Writes a line to `fd`, through its writer when it is redirected.
+fn print_line(ctx: &FunctionEnvMut<EmEnv>, fd: c_int, line: u32) {
+    let memory = ctx.data().memory(0);
+    let mut line = match WasmPtr::<u8>::new(line).read_until(&memory.view(ctx), |byte| *byte == 0) {
+        Ok(line) => line,
+        Err(e) => {
+            debug!("=> unable to read the line: {}", e);
+            return;
+        }
+    };
+    line.push(b'\n');
+
+    let result = match ctx.data().stdio().write(fd, &line) {
+        Some(result) => result.map(|_| ()),
+        None if fd == 1 => io::stdout().write_all(&line),
+        None => io::stderr().write_all(&line),
+    };
+    if let Err(e) = result {
+        debug!("=> error while printing: {}", e);
+    }
+}
+
+/// emscripten_out
+pub fn emscripten_out(ctx: FunctionEnvMut<EmEnv>, line: u32) {
+    debug!("emscripten::emscripten_out");
+    print_line(&ctx, 1, line);
+}
+
+/// emscripten_err
+pub fn emscripten_err(ctx: FunctionEnvMut<EmEnv>, line: u32) {
+    debug!("emscripten::emscripten_err");
+    print_line(&ctx, 2, line);
+}
+
+/// emscripten_console_log
+pub fn emscripten_console_log(ctx: FunctionEnvMut<EmEnv>, line: u32) {
+    debug!("emscripten::emscripten_console_log");
+    print_line(&ctx, 1, line);
+}
+
+/// emscripten_console_warn
+pub fn emscripten_console_warn(ctx: FunctionEnvMut<EmEnv>, line: u32) {
+    debug!("emscripten::emscripten_console_warn");
+    print_line(&ctx, 2, line);
+}
+
+/// emscripten_console_error
+pub fn emscripten_console_error(ctx: FunctionEnvMut<EmEnv>, line: u32) {
+    debug!("emscripten::emscripten_console_error");
+    print_line(&ctx, 2, line);
+}
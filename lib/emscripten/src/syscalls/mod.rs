@@ -1,9 +1,14 @@
 #[cfg(unix)]
 mod unix;
 
+#[cfg(unix)]
+mod modern;
+
 #[cfg(windows)]
 mod windows;
 
+#[cfg(unix)]
+pub use self::modern::*;
 #[cfg(unix)]
 pub use self::unix::*;
 
@@ -11,7 +16,7 @@ pub use self::unix::*;
 pub use self::windows::*;
 
 use crate::{
-    utils::{copy_stat_into_wasm, get_cstr_path, get_current_directory},
+    utils::{copy_stat_into_wasm, get_cstr_path, get_current_directory, is_read_only_path},
     EmEnv,
 };
 
@@ -83,6 +88,10 @@ pub fn ___syscall4(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarAr
     debug!("=> fd: {}, buf: {}, count: {}", fd, buf, count);
     let memory = ctx.data().memory(0);
     let buf_addr = emscripten_memory_pointer!(memory.view(&ctx), buf) as *const c_void;
+    let bytes = unsafe { slice::from_raw_parts(buf_addr as *const u8, count as usize) };
+    if let Some(result) = ctx.data().stdio().write(fd, bytes) {
+        return result.map_or(-1, |written| written as i32);
+    }
     unsafe { write(fd, buf_addr, count as _) as i32 }
 }
 
@@ -164,11 +173,22 @@ pub fn ___syscall36(_ctx: FunctionEnvMut<EmEnv>, _one: i32, _two: i32) -> i32 {
     -1
 }
 
+/// Whether the guest `open` flags could modify the file
+pub(crate) fn opens_for_writing(flags: c_int) -> bool {
+    const WASM_O_ACCMODE: c_int = 0o3;
+    const WASM_O_CREAT: c_int = 0o100;
+    const WASM_O_TRUNC: c_int = 0o1000;
+    flags & (WASM_O_ACCMODE | WASM_O_CREAT | WASM_O_TRUNC) != 0
+}
+
 // rename
 pub fn ___syscall38(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarArgs) -> i32 {
     debug!("emscripten::___syscall38 (rename)");
     let old_path = varargs.get_str(&ctx);
     let new_path = varargs.get_str(&ctx);
+    if is_read_only_path(&ctx, old_path) || is_read_only_path(&ctx, new_path) {
+        return -libc::EROFS;
+    }
     let real_old_path_owned = get_cstr_path(ctx.as_mut(), old_path as *const _);
     let real_old_path = if let Some(ref rp) = real_old_path_owned {
         rp.as_c_str().as_ptr()
@@ -195,6 +215,9 @@ pub fn ___syscall38(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs:
 pub fn ___syscall40(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarArgs) -> c_int {
     debug!("emscripten::___syscall40 (rmdir)");
     let pathname_addr = varargs.get_str(&ctx);
+    if is_read_only_path(&ctx, pathname_addr) {
+        return -libc::EROFS;
+    }
     let real_path_owned = get_cstr_path(ctx, pathname_addr as *const _);
     let real_path = if let Some(ref rp) = real_path_owned {
         rp.as_c_str().as_ptr()
@@ -497,7 +520,11 @@ pub fn ___syscall146(ctx: FunctionEnvMut<EmEnv>, _which: i32, mut varargs: VarAr
                 as *const c_void;
             let iov_len = (*guest_iov_addr).iov_len as _;
             // debug!("=> iov_addr: {:?}, {:?}", iov_base, iov_len);
-            let curr = write(fd, iov_base, iov_len);
+            let bytes = slice::from_raw_parts(iov_base as *const u8, iov_len);
+            let curr = match ctx.data().stdio().write(fd, bytes) {
+                Some(result) => result.map_or(-1, |written| written as _),
+                None => write(fd, iov_base, iov_len),
+            };
             debug!(
                 "=> iov_base: {}, iov_len: {}, curr = {}",
                 (*guest_iov_addr).iov_base,
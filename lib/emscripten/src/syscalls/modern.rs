@@ -0,0 +1,533 @@
+//! Syscalls as imported by recent versions of Emscripten, which pass their
+//! arguments directly (`__syscall_<name>`) instead of through a `VarArgs`
+//! buffer and expect a negated WASI errno on failure rather than -1.
+
+use crate::env::get_emscripten_data;
+use crate::errno::host_errno_to_wasi;
+use crate::utils::{get_cstr_path, get_current_directory, is_read_only_host_path};
+use crate::varargs::VarArgs;
+use crate::{EmEnv, LibcDirWrapper};
+use libc::{c_char, c_int, c_uint};
+use std::collections::hash_map::Entry;
+use std::ffi::{CStr, CString};
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use wasmer::FunctionEnvMut;
+
+// `libc` constants as provided by `emscripten`
+const WASM_AT_FDCWD: c_int = -100;
+const WASM_AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+const WASM_AT_REMOVEDIR: c_int = 0x200;
+const WASM_AT_EACCESS: c_int = 0x200;
+const WASM_O_CREAT: c_int = 0o100;
+const WASM_O_CLOEXEC: c_int = 0o2000000;
+
+const WASM_F_DUPFD: c_int = 0;
+const WASM_F_GETFD: c_int = 1;
+const WASM_F_SETFD: c_int = 2;
+const WASM_F_GETFL: c_int = 3;
+const WASM_F_SETFL: c_int = 4;
+const WASM_F_GETLK: c_int = 12;
+const WASM_F_SETLK: c_int = 13;
+const WASM_F_SETLKW: c_int = 14;
+const WASM_F_DUPFD_CLOEXEC: c_int = 1030;
+
+/// The size of a `struct dirent`: `d_ino` (u64), `d_off` (i64), `d_reclen`
+/// (u16), `d_type` (u8) and a 256 bytes `d_name`, padded to 8 bytes.
+const GUEST_DIRENT_SIZE: usize = 280;
+
+/// The `struct stat` of recent versions of Emscripten, which uses 64 bit
+/// sizes, inodes and timestamps.
+#[repr(C)]
+struct GuestStat64 {
+    st_dev: u32,
+    st_mode: u32,
+    st_nlink: u32,
+    st_uid: u32,
+    st_gid: u32,
+    st_rdev: u32,
+    st_size: i64,
+    st_blksize: i32,
+    st_blocks: i32,
+    st_atime: i64,
+    st_atime_nsec: u32,
+    __st_atime_padding: u32,
+    st_mtime: i64,
+    st_mtime_nsec: u32,
+    __st_mtime_padding: u32,
+    st_ctime: i64,
+    st_ctime_nsec: u32,
+    __st_ctime_padding: u32,
+    st_ino: u64,
+}
+
+fn errno(host_errno: c_int) -> c_int {
+    -host_errno_to_wasi(host_errno)
+}
+
+fn ret_or_errno(ret: c_int) -> c_int {
+    if ret < 0 {
+        let err = Error::last_os_error();
+        debug!("=> os error: {}", err);
+        errno(err.raw_os_error().unwrap_or(libc::EIO))
+    } else {
+        ret
+    }
+}
+
+fn translate_dirfd(dirfd: c_int) -> c_int {
+    if dirfd == WASM_AT_FDCWD {
+        libc::AT_FDCWD
+    } else {
+        dirfd
+    }
+}
+
+fn translate_at_flags(flags: c_int) -> c_int {
+    let mut host_flags = 0;
+    if flags & WASM_AT_SYMLINK_NOFOLLOW != 0 {
+        host_flags |= libc::AT_SYMLINK_NOFOLLOW;
+    }
+    if flags & WASM_AT_REMOVEDIR != 0 {
+        host_flags |= libc::AT_REMOVEDIR;
+    }
+    host_flags
+}
+
+/// Reads a path from the memory of the guest, translated through the
+/// mapped directories.
+fn guest_path(ctx: &mut FunctionEnvMut<EmEnv>, path: u32) -> CString {
+    let memory = ctx.data().memory(0);
+    let path_ptr = emscripten_memory_pointer!(memory.view(&*ctx), path) as *const c_char;
+    get_cstr_path(ctx.as_mut(), path_ptr)
+        .unwrap_or_else(|| unsafe { CStr::from_ptr(path_ptr) }.to_owned())
+}
+
+fn stat_into_wasm(
+    ctx: &FunctionEnvMut<EmEnv>,
+    buf: u32,
+    stat_fn: impl FnOnce(*mut libc::stat) -> c_int,
+) -> c_int {
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    let ret = ret_or_errno(stat_fn(&mut stat));
+    if ret < 0 {
+        return ret;
+    }
+
+    let guest_stat = GuestStat64 {
+        st_dev: stat.st_dev as _,
+        st_mode: stat.st_mode as _,
+        st_nlink: stat.st_nlink as _,
+        st_uid: stat.st_uid as _,
+        st_gid: stat.st_gid as _,
+        st_rdev: stat.st_rdev as _,
+        st_size: stat.st_size as _,
+        st_blksize: 4096,
+        st_blocks: stat.st_blocks as _,
+        st_atime: stat.st_atime as _,
+        st_atime_nsec: stat.st_atime_nsec as _,
+        __st_atime_padding: 0,
+        st_mtime: stat.st_mtime as _,
+        st_mtime_nsec: stat.st_mtime_nsec as _,
+        __st_mtime_padding: 0,
+        st_ctime: stat.st_ctime as _,
+        st_ctime_nsec: stat.st_ctime_nsec as _,
+        __st_ctime_padding: 0,
+        st_ino: stat.st_ino as _,
+    };
+    let memory = ctx.data().memory(0);
+    let stat_ptr = emscripten_memory_pointer!(memory.view(ctx), buf) as *mut GuestStat64;
+    unsafe { stat_ptr.write_unaligned(guest_stat) };
+    0
+}
+
+/// Reads a path relative to `dirfd` from the memory of the guest, which is
+/// only translated through the mapped directories when it is not relative to
+/// a directory fd.
+fn guest_path_at(ctx: &mut FunctionEnvMut<EmEnv>, dirfd: c_int, path: u32) -> CString {
+    let memory = ctx.data().memory(0);
+    let path_ptr = emscripten_memory_pointer!(memory.view(&*ctx), path) as *const c_char;
+    let raw = unsafe { CStr::from_ptr(path_ptr) };
+    if dirfd != WASM_AT_FDCWD && !raw.to_bytes().starts_with(b"/") {
+        return raw.to_owned();
+    }
+    guest_path(ctx, path)
+}
+
+/// Reads a path relative to `dirfd` that is about to be modified, failing
+/// with `EROFS` when it lies in a directory mapped read-only.
+fn writable_guest_path(
+    ctx: &mut FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+) -> Result<CString, c_int> {
+    let path = guest_path_at(ctx, dirfd, path);
+    let host_path = Path::new(path.to_str().map_err(|_| errno(libc::EINVAL))?);
+    let data = get_emscripten_data(ctx);
+    let data = data.as_ref().unwrap();
+    let read_only = if dirfd == WASM_AT_FDCWD || host_path.is_absolute() {
+        is_read_only_host_path(data, host_path)
+    } else {
+        match fd_path(dirfd) {
+            Some(dir) => is_read_only_host_path(data, &dir.join(host_path)),
+            // Without knowing where the directory is, the path can't be
+            // told apart from one in a read-only directory
+            None => !data.read_only_dirs.is_empty(),
+        }
+    };
+    if read_only {
+        return Err(errno(libc::EROFS));
+    }
+    Ok(path)
+}
+
+/// The path of the host directory opened as `fd`.
+#[cfg(target_os = "linux")]
+fn fd_path(fd: c_int) -> Option<PathBuf> {
+    std::fs::read_link(format!("/proc/self/fd/{fd}")).ok()
+}
+
+/// The path of the host directory opened as `fd`.
+#[cfg(target_os = "macos")]
+fn fd_path(fd: c_int) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut buf = vec![0u8; libc::PATH_MAX as usize];
+    if unsafe { libc::fcntl(fd, libc::F_GETPATH, buf.as_mut_ptr()) } < 0 {
+        return None;
+    }
+    let path = CStr::from_bytes_until_nul(&buf).ok()?;
+    Some(PathBuf::from(std::ffi::OsStr::from_bytes(path.to_bytes())))
+}
+
+/// The path of the host directory opened as `fd`.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn fd_path(_fd: c_int) -> Option<PathBuf> {
+    None
+}
+
+/// openat
+pub fn __syscall_openat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+    flags: c_int,
+    mut varargs: VarArgs,
+) -> c_int {
+    debug!("emscripten::__syscall_openat {} {}", dirfd, flags);
+    let mode: c_uint = if flags & WASM_O_CREAT != 0 {
+        varargs.get(&ctx)
+    } else {
+        0
+    };
+    let path = if super::opens_for_writing(flags) {
+        match writable_guest_path(&mut ctx, dirfd, path) {
+            Ok(path) => path,
+            Err(errno) => return errno,
+        }
+    } else {
+        guest_path_at(&mut ctx, dirfd, path)
+    };
+    ret_or_errno(unsafe { libc::openat(translate_dirfd(dirfd), path.as_ptr(), flags, mode) })
+}
+
+/// fcntl64
+pub fn __syscall_fcntl64(
+    ctx: FunctionEnvMut<EmEnv>,
+    fd: c_int,
+    cmd: c_int,
+    mut varargs: VarArgs,
+) -> c_int {
+    debug!("emscripten::__syscall_fcntl64 {} {}", fd, cmd);
+    let host_cmd = match cmd {
+        WASM_F_DUPFD => libc::F_DUPFD,
+        WASM_F_GETFD => libc::F_GETFD,
+        WASM_F_SETFD => libc::F_SETFD,
+        WASM_F_GETFL => libc::F_GETFL,
+        WASM_F_SETFL => libc::F_SETFL,
+        WASM_F_DUPFD_CLOEXEC => libc::F_DUPFD_CLOEXEC,
+        // Locks always succeed, like in the JavaScript runtime of Emscripten
+        WASM_F_GETLK | WASM_F_SETLK | WASM_F_SETLKW => return 0,
+        _ => return errno(libc::EINVAL),
+    };
+    let ret = match cmd {
+        WASM_F_GETFD | WASM_F_GETFL => unsafe { libc::fcntl(fd, host_cmd) },
+        _ => {
+            let arg: c_int = varargs.get(&ctx);
+            unsafe { libc::fcntl(fd, host_cmd, arg) }
+        }
+    };
+    ret_or_errno(ret)
+}
+
+/// getcwd
+pub fn __syscall_getcwd(mut ctx: FunctionEnvMut<EmEnv>, buf: u32, size: u32) -> c_int {
+    debug!("emscripten::__syscall_getcwd {} {}", buf, size);
+    let cwd = match get_current_directory(ctx.as_mut()) {
+        Some(cwd) => cwd,
+        None => return errno(libc::ENOENT),
+    };
+    let mut cwd = cwd.to_string_lossy().into_owned().into_bytes();
+    cwd.push(0);
+    if size == 0 {
+        return errno(libc::EINVAL);
+    }
+    if cwd.len() > size as usize {
+        return errno(libc::ERANGE);
+    }
+    let memory = ctx.data().memory(0);
+    if memory.view(&ctx).write(buf as u64, &cwd).is_err() {
+        return errno(libc::EFAULT);
+    }
+    cwd.len() as c_int
+}
+
+/// chdir
+pub fn __syscall_chdir(mut ctx: FunctionEnvMut<EmEnv>, path: u32) -> c_int {
+    debug!("emscripten::__syscall_chdir");
+    let path = guest_path(&mut ctx, path);
+    ret_or_errno(unsafe { libc::chdir(path.as_ptr()) })
+}
+
+/// stat64
+pub fn __syscall_stat64(mut ctx: FunctionEnvMut<EmEnv>, path: u32, buf: u32) -> c_int {
+    debug!("emscripten::__syscall_stat64");
+    let path = guest_path(&mut ctx, path);
+    stat_into_wasm(&ctx, buf, |stat| unsafe { libc::stat(path.as_ptr(), stat) })
+}
+
+/// lstat64
+pub fn __syscall_lstat64(mut ctx: FunctionEnvMut<EmEnv>, path: u32, buf: u32) -> c_int {
+    debug!("emscripten::__syscall_lstat64");
+    let path = guest_path(&mut ctx, path);
+    stat_into_wasm(&ctx, buf, |stat| unsafe {
+        libc::lstat(path.as_ptr(), stat)
+    })
+}
+
+/// fstat64
+pub fn __syscall_fstat64(ctx: FunctionEnvMut<EmEnv>, fd: c_int, buf: u32) -> c_int {
+    debug!("emscripten::__syscall_fstat64 {}", fd);
+    stat_into_wasm(&ctx, buf, |stat| unsafe { libc::fstat(fd, stat) })
+}
+
+/// newfstatat
+pub fn __syscall_newfstatat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+    buf: u32,
+    flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_newfstatat {} {}", dirfd, flags);
+    let path = guest_path_at(&mut ctx, dirfd, path);
+    stat_into_wasm(&ctx, buf, |stat| unsafe {
+        libc::fstatat(
+            translate_dirfd(dirfd),
+            path.as_ptr(),
+            stat,
+            translate_at_flags(flags),
+        )
+    })
+}
+
+/// mkdirat
+pub fn __syscall_mkdirat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+    mode: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_mkdirat {} {}", dirfd, mode);
+    let path = match writable_guest_path(&mut ctx, dirfd, path) {
+        Ok(path) => path,
+        Err(errno) => return errno,
+    };
+    ret_or_errno(unsafe { libc::mkdirat(translate_dirfd(dirfd), path.as_ptr(), mode as _) })
+}
+
+/// rmdir
+pub fn __syscall_rmdir(mut ctx: FunctionEnvMut<EmEnv>, path: u32) -> c_int {
+    debug!("emscripten::__syscall_rmdir");
+    let path = match writable_guest_path(&mut ctx, WASM_AT_FDCWD, path) {
+        Ok(path) => path,
+        Err(errno) => return errno,
+    };
+    ret_or_errno(unsafe { libc::rmdir(path.as_ptr()) })
+}
+
+/// unlinkat
+pub fn __syscall_unlinkat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+    flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_unlinkat {} {}", dirfd, flags);
+    let path = match writable_guest_path(&mut ctx, dirfd, path) {
+        Ok(path) => path,
+        Err(errno) => return errno,
+    };
+    ret_or_errno(unsafe {
+        libc::unlinkat(
+            translate_dirfd(dirfd),
+            path.as_ptr(),
+            translate_at_flags(flags),
+        )
+    })
+}
+
+/// renameat
+pub fn __syscall_renameat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    old_dirfd: c_int,
+    old_path: u32,
+    new_dirfd: c_int,
+    new_path: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_renameat {} {}", old_dirfd, new_dirfd);
+    let (old_path, new_path) = match (
+        writable_guest_path(&mut ctx, old_dirfd, old_path),
+        writable_guest_path(&mut ctx, new_dirfd, new_path),
+    ) {
+        (Ok(old_path), Ok(new_path)) => (old_path, new_path),
+        (Err(errno), _) | (_, Err(errno)) => return errno,
+    };
+    ret_or_errno(unsafe {
+        libc::renameat(
+            translate_dirfd(old_dirfd),
+            old_path.as_ptr(),
+            translate_dirfd(new_dirfd),
+            new_path.as_ptr(),
+        )
+    })
+}
+
+/// faccessat
+pub fn __syscall_faccessat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+    amode: c_int,
+    flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_faccessat {} {}", dirfd, amode);
+    let mut host_flags = 0;
+    if flags & WASM_AT_SYMLINK_NOFOLLOW != 0 {
+        host_flags |= libc::AT_SYMLINK_NOFOLLOW;
+    }
+    if flags & WASM_AT_EACCESS != 0 {
+        host_flags |= libc::AT_EACCESS;
+    }
+    let path = guest_path_at(&mut ctx, dirfd, path);
+    ret_or_errno(unsafe {
+        libc::faccessat(translate_dirfd(dirfd), path.as_ptr(), amode, host_flags)
+    })
+}
+
+/// readlinkat
+pub fn __syscall_readlinkat(
+    mut ctx: FunctionEnvMut<EmEnv>,
+    dirfd: c_int,
+    path: u32,
+    buf: u32,
+    bufsize: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_readlinkat {} {}", dirfd, bufsize);
+    let path = guest_path_at(&mut ctx, dirfd, path);
+    let mut target = vec![0u8; bufsize as usize];
+    let ret = unsafe {
+        libc::readlinkat(
+            translate_dirfd(dirfd),
+            path.as_ptr(),
+            target.as_mut_ptr() as *mut c_char,
+            target.len(),
+        )
+    };
+    let len = ret_or_errno(ret as c_int);
+    if len < 0 {
+        return len;
+    }
+    let memory = ctx.data().memory(0);
+    if memory
+        .view(&ctx)
+        .write(buf as u64, &target[..len as usize])
+        .is_err()
+    {
+        return errno(libc::EFAULT);
+    }
+    len
+}
+
+/// dup
+pub fn __syscall_dup(_ctx: FunctionEnvMut<EmEnv>, fd: c_int) -> c_int {
+    debug!("emscripten::__syscall_dup {}", fd);
+    ret_or_errno(unsafe { libc::dup(fd) })
+}
+
+/// dup3
+pub fn __syscall_dup3(
+    _ctx: FunctionEnvMut<EmEnv>,
+    fd: c_int,
+    new_fd: c_int,
+    flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_dup3 {} {} {}", fd, new_fd, flags);
+    if fd == new_fd {
+        return errno(libc::EINVAL);
+    }
+    let ret = ret_or_errno(unsafe { libc::dup2(fd, new_fd) });
+    if ret >= 0 && flags & WASM_O_CLOEXEC != 0 {
+        unsafe { libc::fcntl(ret, libc::F_SETFD, libc::FD_CLOEXEC) };
+    }
+    ret
+}
+
+/// getdents64
+pub fn __syscall_getdents64(ctx: FunctionEnvMut<EmEnv>, fd: c_int, dirp: u32, count: u32) -> c_int {
+    debug!("emscripten::__syscall_getdents64 {} {}", fd, count);
+    let memory = ctx.data().memory(0);
+    let view = memory.view(&ctx);
+
+    let mut data = get_emscripten_data(&ctx);
+    let opened_dirs = &mut data.as_mut().unwrap().opened_dirs;
+    let dir = match opened_dirs.entry(fd) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            // The stream needs to persist across calls to continue the listing
+            let dir = unsafe { libc::fdopendir(fd) };
+            if dir.is_null() {
+                return ret_or_errno(-1);
+            }
+            entry.insert(Box::new(LibcDirWrapper(dir)))
+        }
+    };
+
+    let mut pos = 0;
+    while pos + GUEST_DIRENT_SIZE <= count as usize {
+        let dirent = unsafe { libc::readdir(***dir) };
+        if dirent.is_null() {
+            break;
+        }
+        let dirent = unsafe { &*dirent };
+        #[cfg(not(target_os = "freebsd"))]
+        let ino = dirent.d_ino as u64;
+        #[cfg(target_os = "freebsd")]
+        let ino = dirent.d_fileno as u64;
+        let name = unsafe { CStr::from_ptr(dirent.d_name.as_ptr()) }.to_bytes();
+        let name = &name[..name.len().min(255)];
+        debug!("  => file {}", String::from_utf8_lossy(name));
+
+        let mut entry = [0u8; GUEST_DIRENT_SIZE];
+        entry[0..8].copy_from_slice(&ino.to_le_bytes());
+        entry[8..16].copy_from_slice(&((pos + GUEST_DIRENT_SIZE) as i64).to_le_bytes());
+        entry[16..18].copy_from_slice(&(GUEST_DIRENT_SIZE as u16).to_le_bytes());
+        entry[18] = dirent.d_type;
+        entry[19..19 + name.len()].copy_from_slice(name);
+        if view.write(dirp as u64 + pos as u64, &entry).is_err() {
+            return errno(libc::EFAULT);
+        }
+        pos += GUEST_DIRENT_SIZE;
+    }
+    pos as c_int
+}
@@ -162,6 +162,9 @@ pub fn ___syscall5(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarAr
     let pathname_addr = varargs.get_str(&ctx);
     let flags: i32 = varargs.get(&ctx);
     let mode: u32 = varargs.get(&ctx);
+    if super::opens_for_writing(flags) && utils::is_read_only_path(&ctx, pathname_addr) {
+        return -libc::EROFS;
+    }
     let real_path_owned = utils::get_cstr_path(ctx, pathname_addr as *const _);
     let real_path = if let Some(ref rp) = real_path_owned {
         rp.as_c_str().as_ptr()
@@ -181,11 +184,22 @@ pub fn ___syscall5(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarAr
 }
 
 /// link
-pub fn ___syscall9(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarArgs) -> c_int {
+pub fn ___syscall9(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarArgs) -> c_int {
     debug!("emscripten::___syscall9 (link) {}", _which);
 
     let oldname_ptr = varargs.get_str(&ctx);
     let newname_ptr = varargs.get_str(&ctx);
+    if utils::is_read_only_path(&ctx, newname_ptr) {
+        return -libc::EROFS;
+    }
+    let real_oldname_owned = utils::get_cstr_path(ctx.as_mut(), oldname_ptr as *const _);
+    let real_newname_owned = utils::get_cstr_path(ctx, newname_ptr as *const _);
+    let oldname_ptr = real_oldname_owned
+        .as_ref()
+        .map_or(oldname_ptr, |path| path.as_ptr());
+    let newname_ptr = real_newname_owned
+        .as_ref()
+        .map_or(newname_ptr, |path| path.as_ptr());
     let result = unsafe { link(oldname_ptr, newname_ptr) };
     debug!(
         "=> oldname: {}, newname: {}, result: {}",
@@ -215,6 +229,9 @@ pub fn ___syscall83(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs:
 
     let path1 = varargs.get_str(&ctx);
     let path2 = varargs.get_str(&ctx);
+    if utils::is_read_only_path(&ctx, path2) {
+        return -libc::EROFS;
+    }
     let real_path1_owned = utils::get_cstr_path(ctx.as_mut(), path1 as *const _);
     let real_path1 = if let Some(ref rp) = real_path1_owned {
         rp.as_c_str().as_ptr()
@@ -287,6 +304,9 @@ pub fn ___syscall194(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: Var
 pub fn ___syscall198(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarArgs) -> c_int {
     debug!("emscripten::___syscall198 (lchown) {}", _which);
     let path_ptr = varargs.get_str(&ctx);
+    if utils::is_read_only_path(&ctx, path_ptr) {
+        return -libc::EROFS;
+    }
     let real_path_owned = utils::get_cstr_path(ctx.as_mut(), path_ptr as *const _);
     let real_path = if let Some(ref rp) = real_path_owned {
         rp.as_c_str().as_ptr()
@@ -329,6 +349,9 @@ pub fn ___syscall212(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs:
     debug!("emscripten::___syscall212 (chown) {}", _which);
 
     let pathname_addr = varargs.get_str(&ctx);
+    if utils::is_read_only_path(&ctx, pathname_addr) {
+        return -libc::EROFS;
+    }
     let real_path_owned = utils::get_cstr_path(ctx.as_mut(), pathname_addr as *const _);
     let real_path = if let Some(ref rp) = real_path_owned {
         rp.as_c_str().as_ptr()
@@ -387,6 +410,9 @@ pub fn ___syscall34(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarA
 pub fn ___syscall39(mut ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarArgs) -> c_int {
     debug!("emscripten::___syscall39 (mkdir) {}", _which);
     let pathname_addr = varargs.get_str(&ctx);
+    if utils::is_read_only_path(&ctx, pathname_addr) {
+        return -libc::EROFS;
+    }
     let real_path_owned = utils::get_cstr_path(ctx.as_mut(), pathname_addr as *const _);
     let real_path = if let Some(ref rp) = real_path_owned {
         rp.as_c_str().as_ptr()
@@ -515,6 +541,31 @@ pub fn ___syscall54(ctx: FunctionEnvMut<EmEnv>, _which: c_int, mut varargs: VarA
     }
 }
 
+/// ioctl, as imported by recent versions of Emscripten
+pub fn __syscall_ioctl(
+    ctx: FunctionEnvMut<EmEnv>,
+    fd: c_int,
+    request: u32,
+    mut varargs: VarArgs,
+) -> c_int {
+    debug!("emscripten::__syscall_ioctl {} {}", fd, request);
+    match request {
+        WASM_FIOCLEX | WASM_FIONBIO | WASM_TIOCGWINSZ | WASM_TIOCSPGRP | WASM_TCGETS
+        | WASM_TCSETSW => {
+            let argp: u32 = varargs.get(&ctx);
+            let memory = ctx.data().memory(0);
+            let argp_ptr = emscripten_memory_pointer!(memory.view(&ctx), argp) as *mut c_void;
+            let ret = unsafe { ioctl(fd, translate_ioctl(request) as _, argp_ptr) };
+            if ret == -1 {
+                let errno = Error::last_os_error().raw_os_error().unwrap_or(libc::EIO);
+                return -crate::errno::host_errno_to_wasi(errno);
+            }
+            ret
+        }
+        _ => -crate::errno::host_errno_to_wasi(libc::EINVAL),
+    }
+}
+
 const SOCK_NON_BLOCK: i32 = 2048;
 const SOCK_CLOEXC: i32 = 0x80000;
 
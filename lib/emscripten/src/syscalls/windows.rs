@@ -1,4 +1,4 @@
-use crate::utils::{copy_cstr_into_wasm, get_cstr_path};
+use crate::utils::{copy_cstr_into_wasm, get_cstr_path, is_read_only_path};
 use crate::varargs::VarArgs;
 use crate::EmEnv;
 use libc::mkdir;
@@ -27,6 +27,9 @@ pub fn ___syscall5(mut ctx: FunctionEnvMut<EmEnv>, which: c_int, mut varargs: Va
     };
     let flags: i32 = varargs.get(&ctx);
     let mode: u32 = varargs.get(&ctx);
+    if super::opens_for_writing(flags) && is_read_only_path(&ctx, pathname_addr) {
+        return -libc::EROFS;
+    }
     let path_str = unsafe { std::ffi::CStr::from_ptr(real_path).to_str().unwrap() };
 
     match path_str {
@@ -105,6 +108,9 @@ pub fn ___syscall39(ctx: FunctionEnvMut<EmEnv>, which: c_int, mut varargs: VarAr
     #[cfg(not(feature = "debug"))]
     let _ = which;
     let pathname_addr = varargs.get_str(&ctx);
+    if is_read_only_path(&ctx, pathname_addr) {
+        return -libc::EROFS;
+    }
     let real_path_owned = get_cstr_path(ctx, pathname_addr);
     let real_path = if let Some(ref rp) = real_path_owned {
         rp.as_c_str().as_ptr()
@@ -312,3 +318,174 @@ pub fn ___syscall324(_ctx: FunctionEnvMut<EmEnv>, _which: c_int, _varargs: VarAr
     debug!("emscripten::___syscall324 (fallocate) {}", _which);
     unimplemented!("emscripten::___syscall324 (fallocate) {}", _which)
 }
+
+// The syscalls of recent versions of Emscripten are not supported on Windows
+const WASM_ENOSYS: c_int = 52;
+
+/// openat
+pub fn __syscall_openat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _dirfd: c_int,
+    _path: u32,
+    _flags: c_int,
+    _varargs: VarArgs,
+) -> c_int {
+    debug!("emscripten::__syscall_openat");
+    -WASM_ENOSYS
+}
+
+/// fcntl64
+pub fn __syscall_fcntl64(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _fd: c_int,
+    _cmd: c_int,
+    _varargs: VarArgs,
+) -> c_int {
+    debug!("emscripten::__syscall_fcntl64");
+    -WASM_ENOSYS
+}
+
+/// ioctl
+pub fn __syscall_ioctl(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _fd: c_int,
+    _request: u32,
+    _varargs: VarArgs,
+) -> c_int {
+    debug!("emscripten::__syscall_ioctl");
+    -WASM_ENOSYS
+}
+
+/// getcwd
+pub fn __syscall_getcwd(_ctx: FunctionEnvMut<EmEnv>, _buf: u32, _size: u32) -> c_int {
+    debug!("emscripten::__syscall_getcwd");
+    -WASM_ENOSYS
+}
+
+/// chdir
+pub fn __syscall_chdir(_ctx: FunctionEnvMut<EmEnv>, _path: u32) -> c_int {
+    debug!("emscripten::__syscall_chdir");
+    -WASM_ENOSYS
+}
+
+/// stat64
+pub fn __syscall_stat64(_ctx: FunctionEnvMut<EmEnv>, _path: u32, _buf: u32) -> c_int {
+    debug!("emscripten::__syscall_stat64");
+    -WASM_ENOSYS
+}
+
+/// lstat64
+pub fn __syscall_lstat64(_ctx: FunctionEnvMut<EmEnv>, _path: u32, _buf: u32) -> c_int {
+    debug!("emscripten::__syscall_lstat64");
+    -WASM_ENOSYS
+}
+
+/// fstat64
+pub fn __syscall_fstat64(_ctx: FunctionEnvMut<EmEnv>, _fd: c_int, _buf: u32) -> c_int {
+    debug!("emscripten::__syscall_fstat64");
+    -WASM_ENOSYS
+}
+
+/// newfstatat
+pub fn __syscall_newfstatat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _dirfd: c_int,
+    _path: u32,
+    _buf: u32,
+    _flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_newfstatat");
+    -WASM_ENOSYS
+}
+
+/// mkdirat
+pub fn __syscall_mkdirat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _dirfd: c_int,
+    _path: u32,
+    _mode: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_mkdirat");
+    -WASM_ENOSYS
+}
+
+/// rmdir
+pub fn __syscall_rmdir(_ctx: FunctionEnvMut<EmEnv>, _path: u32) -> c_int {
+    debug!("emscripten::__syscall_rmdir");
+    -WASM_ENOSYS
+}
+
+/// unlinkat
+pub fn __syscall_unlinkat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _dirfd: c_int,
+    _path: u32,
+    _flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_unlinkat");
+    -WASM_ENOSYS
+}
+
+/// renameat
+pub fn __syscall_renameat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _old_dirfd: c_int,
+    _old_path: u32,
+    _new_dirfd: c_int,
+    _new_path: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_renameat");
+    -WASM_ENOSYS
+}
+
+/// faccessat
+pub fn __syscall_faccessat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _dirfd: c_int,
+    _path: u32,
+    _amode: c_int,
+    _flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_faccessat");
+    -WASM_ENOSYS
+}
+
+/// readlinkat
+pub fn __syscall_readlinkat(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _dirfd: c_int,
+    _path: u32,
+    _buf: u32,
+    _bufsize: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_readlinkat");
+    -WASM_ENOSYS
+}
+
+/// dup
+pub fn __syscall_dup(_ctx: FunctionEnvMut<EmEnv>, _fd: c_int) -> c_int {
+    debug!("emscripten::__syscall_dup");
+    -WASM_ENOSYS
+}
+
+/// dup3
+pub fn __syscall_dup3(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _fd: c_int,
+    _new_fd: c_int,
+    _flags: c_int,
+) -> c_int {
+    debug!("emscripten::__syscall_dup3");
+    -WASM_ENOSYS
+}
+
+/// getdents64
+pub fn __syscall_getdents64(
+    _ctx: FunctionEnvMut<EmEnv>,
+    _fd: c_int,
+    _dirp: u32,
+    _count: u32,
+) -> c_int {
+    debug!("emscripten::__syscall_getdents64");
+    -WASM_ENOSYS
+}
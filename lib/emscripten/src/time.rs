@@ -84,6 +84,23 @@ pub fn _clock_getres(mut _ctx: FunctionEnvMut<EmEnv>, _clk_id: i32, _tp: i32) ->
     0
 }
 
+/// The current time of a clock, in nanoseconds.
+pub(crate) fn clock_nanos(clk_id: clockid_t) -> Option<i128> {
+    #[allow(unreachable_patterns)]
+    match clk_id {
+        CLOCK_REALTIME => Some(time::OffsetDateTime::now_utc().unix_timestamp_nanos()),
+
+        CLOCK_MONOTONIC | CLOCK_MONOTONIC_COARSE => {
+            lazy_static! {
+                static ref PRECISE0: std::time::Instant = std::time::Instant::now();
+            };
+            let precise_ns = *PRECISE0;
+            Some((std::time::Instant::now().signed_duration_since(precise_ns)).whole_nanoseconds())
+        }
+        _ => None,
+    }
+}
+
 /// emscripten: _clock_gettime
 #[allow(clippy::cast_ptr_alignment)]
 pub fn _clock_gettime(ctx: FunctionEnvMut<EmEnv>, clk_id: clockid_t, tp: c_int) -> c_int {
@@ -95,19 +112,8 @@ pub fn _clock_gettime(ctx: FunctionEnvMut<EmEnv>, clk_id: clockid_t, tp: c_int)
         tv_nsec: i32,
     }
 
-    #[allow(unreachable_patterns)]
-    let duration = match clk_id {
-        CLOCK_REALTIME => time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
-
-        CLOCK_MONOTONIC | CLOCK_MONOTONIC_COARSE => {
-            lazy_static! {
-                static ref PRECISE0: std::time::Instant = std::time::Instant::now();
-            };
-            let precise_ns = *PRECISE0;
-            (std::time::Instant::now().signed_duration_since(precise_ns)).whole_nanoseconds()
-        }
-        _ => panic!("Clock with id \"{}\" is not supported.", clk_id),
-    };
+    let duration = clock_nanos(clk_id)
+        .unwrap_or_else(|| panic!("Clock with id \"{}\" is not supported.", clk_id));
 
     unsafe {
         let memory = ctx.data().memory(0);
@@ -119,6 +125,24 @@ pub fn _clock_gettime(ctx: FunctionEnvMut<EmEnv>, clk_id: clockid_t, tp: c_int)
     0
 }
 
+/// emscripten: emscripten_get_now
+pub fn emscripten_get_now(_ctx: FunctionEnvMut<EmEnv>) -> f64 {
+    debug!("emscripten::emscripten_get_now");
+    clock_nanos(CLOCK_MONOTONIC).unwrap() as f64 / 1_000_000.0
+}
+
+/// emscripten: _emscripten_get_now_is_monotonic
+pub fn _emscripten_get_now_is_monotonic(_ctx: FunctionEnvMut<EmEnv>) -> i32 {
+    debug!("emscripten::_emscripten_get_now_is_monotonic");
+    1
+}
+
+/// emscripten: emscripten_date_now
+pub fn emscripten_date_now(_ctx: FunctionEnvMut<EmEnv>) -> f64 {
+    debug!("emscripten::emscripten_date_now");
+    clock_nanos(CLOCK_REALTIME).unwrap() as f64 / 1_000_000.0
+}
+
 pub fn _clock_settime(mut _ctx: FunctionEnvMut<EmEnv>, _clk_id: i32, _tp: i32) -> i32 {
     debug!("emscripten::_clock_settime");
     // clock_settime(clk_id, tp)
@@ -1,12 +1,13 @@
 use super::env;
 use super::env::{get_emscripten_data, get_emscripten_funcs};
 use crate::storage::align_memory;
-use crate::EmEnv;
+use crate::{EmEnv, EmscriptenData};
 use libc::stat;
 use std::ffi::CStr;
+use std::ffi::OsStr;
 use std::mem::size_of;
 use std::os::raw::c_char;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::slice;
 use wasmer::{FunctionEnvMut, GlobalInit, MemoryView, Module, Pages, WasmPtr};
 
@@ -23,24 +24,47 @@ pub fn is_emscripten_module(module: &Module) -> bool {
             return true;
         }
     }
-    false
+    is_modern_emscripten_module(module)
 }
 
+/// We check if a provided module was generated by a recent version of
+/// Emscripten, which defines its own memory and passes the arguments of
+/// syscalls directly.
+pub fn is_modern_emscripten_module(module: &Module) -> bool {
+    module.imports().functions().any(|import| {
+        let name = import.name();
+        import.module() == "env"
+            && (name.starts_with("__syscall_")
+                || name == "_emscripten_memcpy_js"
+                || name == "emscripten_memcpy_js"
+                || name == "emscripten_resize_heap"
+                || name == "_abort_js")
+    })
+}
+
+/// Recent versions of Emscripten export their table instead of importing it.
 pub fn get_emscripten_table_size(module: &Module) -> Result<(u32, Option<u32>), String> {
     if let Some(import) = module.imports().tables().next() {
         let ty = import.ty();
         Ok((ty.minimum, ty.maximum))
+    } else if let Some(export) = module.exports().tables().next() {
+        let ty = export.ty();
+        Ok((ty.minimum, ty.maximum))
     } else {
-        Err("Emscripten requires at least one imported table".to_string())
+        Err("Emscripten requires at least one imported or exported table".to_string())
     }
 }
 
+/// Recent versions of Emscripten export their memory instead of importing it.
 pub fn get_emscripten_memory_size(module: &Module) -> Result<(Pages, Option<Pages>, bool), String> {
     if let Some(import) = module.imports().memories().next() {
         let ty = import.ty();
         Ok((ty.minimum, ty.maximum, ty.shared))
+    } else if let Some(export) = module.exports().memories().next() {
+        let ty = export.ty();
+        Ok((ty.minimum, ty.maximum, ty.shared))
     } else {
-        Err("Emscripten requires at least one imported memory".to_string())
+        Err("Emscripten requires at least one imported or exported memory".to_string())
     }
 }
 
@@ -244,67 +268,151 @@ pub fn read_string_from_wasm(memory: &MemoryView, offset: u32) -> String {
         .unwrap()
 }
 
+/// A guest path translated into a path of the host through a mapped
+/// directory
+pub(crate) struct MappedPath {
+    pub host: PathBuf,
+    pub read_only: bool,
+}
+
+/// The components of a path, with `.` and `..` resolved lexically
+fn normalized_components(path: &Path) -> Vec<&OsStr> {
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => components.push(name),
+            Component::ParentDir => {
+                components.pop();
+            }
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+    components
+}
+
+/// Translates a guest path through the mapped directories.
+///
+/// Like with WASI, the innermost mapped directory containing the path wins,
+/// so directories can be mapped inside of other mapped directories. Relative
+/// paths are resolved against the directory mapped as `.`, unless they start
+/// with the (relative) name of another mapped directory.
+pub(crate) fn map_guest_path(data: &EmscriptenData, path: &Path) -> Option<MappedPath> {
+    let components = normalized_components(path);
+    let mut best: Option<(usize, &String, &PathBuf)> = None;
+    for (guest, host) in data.mapped_dirs.iter() {
+        let guest_path = Path::new(guest);
+        let guest_components = normalized_components(guest_path);
+        // Directories mapped with a relative name are also found at the
+        // root, except for the current directory
+        let applies = if path.has_root() {
+            guest_path.has_root() || !guest_components.is_empty()
+        } else {
+            !guest_path.has_root()
+        };
+        if applies
+            && components.starts_with(&guest_components)
+            && best.map_or(true, |(len, _, _)| guest_components.len() > len)
+        {
+            best = Some((guest_components.len(), guest, host));
+        }
+    }
+
+    let (len, guest, host) = best?;
+    let mut mapped = host.clone();
+    mapped.extend(&components[len..]);
+    Some(MappedPath {
+        host: mapped,
+        read_only: data.read_only_dirs.contains(guest),
+    })
+}
+
 /// This function trys to find an entry in mapdir
 /// translating paths into their correct value
 pub fn get_cstr_path(ctx: FunctionEnvMut<EmEnv>, path: *const i8) -> Option<std::ffi::CString> {
-    use std::collections::VecDeque;
-
-    let path_str =
-        unsafe { std::ffi::CStr::from_ptr(path as *const _).to_str().unwrap() }.to_string();
+    let path_str = unsafe { std::ffi::CStr::from_ptr(path as *const _) }.to_string_lossy();
     let data = get_emscripten_data(&ctx);
-    let path = PathBuf::from(path_str);
-    let mut prefix_added = false;
-    let mut components = path.components().collect::<VecDeque<_>>();
-    // TODO(mark): handle absolute/non-canonical/non-relative paths too (this
-    // functionality should be shared among the abis)
-    if components.len() == 1 {
-        components.push_front(std::path::Component::CurDir);
-        prefix_added = true;
+    let mapped = map_guest_path(data.as_ref().unwrap(), Path::new(path_str.as_ref()))?;
+    std::ffi::CString::new(mapped.host.to_string_lossy().as_bytes()).ok()
+}
+
+/// Whether a path of the guest lies in a directory mapped read-only
+pub(crate) fn is_read_only_path(ctx: &FunctionEnvMut<EmEnv>, path: *const c_char) -> bool {
+    let path_str = unsafe { std::ffi::CStr::from_ptr(path as *const _) }.to_string_lossy();
+    let data = get_emscripten_data(ctx);
+    let data = data.as_ref().unwrap();
+    match map_guest_path(data, Path::new(path_str.as_ref())) {
+        Some(mapped) => mapped.read_only || is_read_only_host_path(data, &mapped.host),
+        None => is_read_only_host_path(data, Path::new(path_str.as_ref())),
     }
-    let mut cumulative_path = PathBuf::new();
-    for c in components.into_iter() {
-        cumulative_path.push(c);
-        if let Some(val) = data
-            .as_ref()
-            .unwrap()
-            .mapped_dirs
-            .get(&cumulative_path.to_string_lossy().to_string())
-        {
-            let rest_of_path = if !prefix_added {
-                path.strip_prefix(cumulative_path).ok()?
-            } else {
-                &path
-            };
-            let rebased_path = val.join(rest_of_path);
-            return std::ffi::CString::new(rebased_path.to_string_lossy().as_bytes()).ok();
-        }
+}
+
+/// Whether a path of the host, relative to its current directory, lies in a
+/// directory mapped read-only.
+///
+/// The path is resolved first, so that `..` or symbolic links can't be used
+/// to reach a read-only directory through another mapped directory. Both
+/// where it is and what it points to are checked when it is a symbolic link.
+pub(crate) fn is_read_only_host_path(data: &EmscriptenData, path: &Path) -> bool {
+    let read_only_dirs: Vec<PathBuf> = data
+        .read_only_dirs
+        .iter()
+        .filter_map(|guest| data.mapped_dirs.get(guest))
+        .filter_map(|host| host.canonicalize().ok())
+        .collect();
+    if read_only_dirs.is_empty() {
+        return false;
     }
-    None
+
+    let is_read_only = |path: &Path| read_only_dirs.iter().any(|dir| path.starts_with(dir));
+    let location = match resolve_host_path(path) {
+        Some(location) => location,
+        // Refuse what can't be resolved rather than risking a write
+        None => return true,
+    };
+    is_read_only(&location)
+        || path
+            .canonicalize()
+            .map_or(false, |target| is_read_only(&target))
+}
+
+/// Resolves the parent directory of a host path, which may not exist yet.
+fn resolve_host_path(path: &Path) -> Option<PathBuf> {
+    let name = match path.components().next_back()? {
+        Component::Normal(name) => name,
+        _ => return path.canonicalize().ok(),
+    };
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = match parent.canonicalize() {
+        Ok(parent) => parent,
+        Err(_) => resolve_host_path(parent)?,
+    };
+    Some(parent.join(name))
 }
 
 /// gets the current directory
 /// handles mapdir logic
+///
+/// The current directory of the host is translated back into the guest path
+/// of the innermost mapped directory containing it.
 pub fn get_current_directory(ctx: FunctionEnvMut<EmEnv>) -> Option<PathBuf> {
-    if let Some(val) = get_emscripten_data(&ctx)
-        .as_ref()
-        .unwrap()
-        .mapped_dirs
-        .get(".")
-    {
-        return Some(val.clone());
-    }
-    std::env::current_dir()
-        .map(|cwd| {
-            if let Some(val) = get_emscripten_data(&ctx)
-                .as_ref()
-                .unwrap()
-                .mapped_dirs
-                .get(&cwd.to_string_lossy().to_string())
-            {
-                val.clone()
-            } else {
-                cwd
+    let cwd = std::env::current_dir().ok()?;
+    let data = get_emscripten_data(&ctx);
+    let mut best: Option<(usize, PathBuf)> = None;
+    for (guest, host) in data.as_ref().unwrap().mapped_dirs.iter() {
+        let guest = Path::new(guest);
+        // The current directory can't be expressed relatively to itself
+        if normalized_components(guest).is_empty() && !guest.has_root() {
+            continue;
+        }
+        if let Ok(rest) = cwd.strip_prefix(host) {
+            let depth = host.components().count();
+            if best.as_ref().map_or(true, |(len, _)| depth > *len) {
+                best = Some((depth, Path::new("/").join(guest).join(rest)));
             }
-        })
-        .ok()
+        }
+    }
+    Some(best.map_or(cwd, |(_, path)| path))
 }
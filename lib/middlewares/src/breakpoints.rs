@@ -0,0 +1,746 @@
+//! `breakpoints` is a middleware for pausing the execution of an
+//! instance on breakpoints, set on functions or on the offsets of
+//! operators, or after each operator when stepping, to inspect its
+//! locals, globals and memory.
+//!
+//! Every operator is preceded by a check of an exported global, which is
+//! only set while there are breakpoints or while stepping, so the
+//! instrumented code mostly runs at full speed otherwise. On a pause, the
+//! locals of the function are handed to the host through the functions
+//! that a [`Debugger`] attaches to the instance, before the host decides
+//! how to resume.
+//!
+//! The instrumentation grows with the number of locals of each function,
+//! it is meant for debugging and not for production use.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use wasmer::wasmparser::{BlockType, Operator, ValType};
+use wasmer::{
+    AsStoreMut, ExportError, ExportIndex, Function, FunctionEnv, FunctionEnvMut,
+    FunctionMiddleware, FunctionType, Global, GlobalInit, GlobalType, Instance,
+    LocalFunctionIndex, Memory, MemoryAccessError, MiddlewareError, MiddlewareReaderState,
+    ModuleMiddleware, Mutability, RuntimeError, StoreMut, TableType, Type, Value,
+};
+use wasmer_types::entity::{EntityRef, PrimaryMap};
+use wasmer_types::{FunctionIndex, GlobalIndex, MemoryIndex, ModuleInfo, SignatureIndex, TableIndex};
+
+/// The element of the hooks table called to decide whether to pause.
+const CHECK_HOOK: u32 = 0;
+/// The element of the hooks table called with the value of each local.
+const VALUE_HOOK: u32 = 1;
+/// The element of the hooks table called once the locals were given.
+const PAUSE_HOOK: u32 = 2;
+/// The number of elements of the hooks table.
+const HOOKS: u32 = 3;
+
+/// The codes of the types of the values given to the value hook.
+const I32_VALUE: i32 = 0;
+const I64_VALUE: i32 = 1;
+const F32_VALUE: i32 = 2;
+const F64_VALUE: i32 = 3;
+const UNAVAILABLE_VALUE: i32 = 4;
+
+#[derive(Debug, Clone)]
+struct BreakpointsIndexes {
+    /// The global that enables the checks, when non-zero.
+    enabled: GlobalIndex,
+    /// The table holding the hooks of the debugger.
+    hooks: TableIndex,
+    /// The signature of the check and pause hooks.
+    check_signature: SignatureIndex,
+    /// The signature of the value hook.
+    value_signature: SignatureIndex,
+    /// The number of functions imported by the module.
+    num_imported_functions: usize,
+    /// The types of the parameters of the local functions.
+    params: Arc<PrimaryMap<LocalFunctionIndex, Vec<Type>>>,
+}
+
+/// The module-level breakpoints middleware.
+///
+/// # Panic
+///
+/// An instance of `Breakpoints` should _not_ be shared among different
+/// modules, since it tracks module-specific information like the
+/// indexes of the global and the table it adds. Attempts to use a
+/// `Breakpoints` instance from multiple modules will result in a panic.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use wasmer::CompilerConfig;
+/// use wasmer_middlewares::Breakpoints;
+///
+/// fn create_breakpoints_middleware(compiler_config: &mut dyn CompilerConfig) {
+///     compiler_config.push_middleware(Arc::new(Breakpoints::new()));
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Breakpoints {
+    /// The indexes of the items added to the module.
+    indexes: Mutex<Option<BreakpointsIndexes>>,
+}
+
+/// The function-level breakpoints middleware.
+#[derive(Debug)]
+pub struct FunctionBreakpoints {
+    /// The indexes of the items added to the module.
+    indexes: BreakpointsIndexes,
+
+    /// The index of the function in the module.
+    function_index: FunctionIndex,
+
+    /// The types of the parameters and the locals of the function.
+    locals: Vec<Type>,
+
+    /// Whether the next operator is the first of the function.
+    entry: bool,
+}
+
+impl Breakpoints {
+    /// Creates a `Breakpoints` middleware.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ModuleMiddleware for Breakpoints {
+    /// Generates a `FunctionMiddleware` for a given function.
+    fn generate_function_middleware(
+        &self,
+        local_function_index: LocalFunctionIndex,
+    ) -> Box<dyn FunctionMiddleware> {
+        let indexes = self.indexes.lock().unwrap().clone().unwrap();
+        let function_index =
+            FunctionIndex::new(indexes.num_imported_functions + local_function_index.index());
+        let locals = indexes.params[local_function_index].clone();
+        Box::new(FunctionBreakpoints {
+            indexes,
+            function_index,
+            locals,
+            entry: true,
+        })
+    }
+
+    /// Transforms a `ModuleInfo` struct in-place. This is called before application on functions begins.
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) -> Result<(), MiddlewareError> {
+        let mut indexes = self.indexes.lock().unwrap();
+
+        if indexes.is_some() {
+            panic!("Breakpoints::transform_module_info: Attempting to use a `Breakpoints` middleware from multiple modules.");
+        }
+
+        // Export the globals and the memory, for the debugger to inspect them.
+        for index in module_info.globals.keys() {
+            module_info.exports.insert(
+                format!("wasmer_breakpoints_global_{}", index.as_u32()),
+                ExportIndex::Global(index),
+            );
+        }
+        if !module_info.memories.is_empty() {
+            module_info.exports.insert(
+                "wasmer_breakpoints_memory".to_string(),
+                ExportIndex::Memory(MemoryIndex::new(0)),
+            );
+        }
+
+        // Append a global enabling the checks, and a table for the hooks.
+        let enabled = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+        module_info.exports.insert(
+            "wasmer_breakpoints_enabled".to_string(),
+            ExportIndex::Global(enabled),
+        );
+
+        let hooks = module_info
+            .tables
+            .push(TableType::new(Type::FuncRef, HOOKS, Some(HOOKS)));
+        module_info.exports.insert(
+            "wasmer_breakpoints_hooks".to_string(),
+            ExportIndex::Table(hooks),
+        );
+
+        let check_signature = module_info.signatures.push(FunctionType::new(
+            vec![Type::I32, Type::I32, Type::I32],
+            vec![Type::I32],
+        ));
+        let value_signature = module_info.signatures.push(FunctionType::new(
+            vec![Type::I32, Type::I64, Type::I32],
+            Vec::<Type>::new(),
+        ));
+
+        let params = module_info
+            .functions
+            .values()
+            .skip(module_info.num_imported_functions)
+            .map(|signature| module_info.signatures[*signature].params().to_vec())
+            .collect();
+
+        *indexes = Some(BreakpointsIndexes {
+            enabled,
+            hooks,
+            check_signature,
+            value_signature,
+            num_imported_functions: module_info.num_imported_functions,
+            params: Arc::new(params),
+        });
+
+        Ok(())
+    }
+}
+
+impl FunctionBreakpoints {
+    /// Calls the hook at `element` of the hooks table with `signature`.
+    fn call_hook<'a>(&self, element: u32, signature: SignatureIndex) -> [Operator<'a>; 2] {
+        [
+            Operator::I32Const {
+                value: element as i32,
+            },
+            Operator::CallIndirect {
+                type_index: signature.as_u32(),
+                table_index: self.indexes.hooks.as_u32(),
+                table_byte: 0,
+            },
+        ]
+    }
+}
+
+impl FunctionMiddleware for FunctionBreakpoints {
+    fn declare_locals(&mut self, count: u32, ty: ValType) {
+        let ty = match ty {
+            ValType::I32 => Type::I32,
+            ValType::I64 => Type::I64,
+            ValType::F32 => Type::F32,
+            ValType::F64 => Type::F64,
+            ValType::V128 => Type::V128,
+            ValType::Ref(_) => Type::FuncRef,
+        };
+        self.locals
+            .extend(std::iter::repeat(ty).take(count as usize));
+    }
+
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        let location = [
+            Operator::I32Const {
+                value: self.function_index.as_u32() as i32,
+            },
+            Operator::I32Const {
+                value: state.original_position() as i32,
+            },
+            Operator::I32Const {
+                value: std::mem::take(&mut self.entry) as i32,
+            },
+        ];
+
+        // if globals[enabled] && check(location) {
+        //     value(index, local, type) for each local;
+        //     pause(location);
+        // }
+        state.extend(&[
+            Operator::GlobalGet {
+                global_index: self.indexes.enabled.as_u32(),
+            },
+            Operator::If {
+                blockty: BlockType::Empty,
+            },
+        ]);
+        state.extend(&location);
+        state.extend(&self.call_hook(CHECK_HOOK, self.indexes.check_signature));
+        state.push_operator(Operator::If {
+            blockty: BlockType::Empty,
+        });
+        for (index, ty) in self.locals.iter().enumerate() {
+            let local_index = index as u32;
+            state.push_operator(Operator::I32Const {
+                value: local_index as i32,
+            });
+            let local = Operator::LocalGet { local_index };
+            let code = match ty {
+                Type::I32 => {
+                    state.extend([local, Operator::I64ExtendI32U]);
+                    I32_VALUE
+                }
+                Type::I64 => {
+                    state.push_operator(local);
+                    I64_VALUE
+                }
+                Type::F32 => {
+                    state.extend([
+                        local,
+                        Operator::I32ReinterpretF32,
+                        Operator::I64ExtendI32U,
+                    ]);
+                    F32_VALUE
+                }
+                Type::F64 => {
+                    state.extend([local, Operator::I64ReinterpretF64]);
+                    F64_VALUE
+                }
+                _ => {
+                    state.push_operator(Operator::I64Const { value: 0 });
+                    UNAVAILABLE_VALUE
+                }
+            };
+            state.push_operator(Operator::I32Const { value: code });
+            state.extend(&self.call_hook(VALUE_HOOK, self.indexes.value_signature));
+        }
+        state.extend(&location);
+        state.extend(&self.call_hook(PAUSE_HOOK, self.indexes.check_signature));
+        state.extend(&[Operator::Drop, Operator::End, Operator::End]);
+
+        state.push_operator(operator);
+
+        Ok(())
+    }
+}
+
+/// Where the execution of an instance pauses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Breakpoint {
+    /// On the entry of a function.
+    Function(FunctionIndex),
+    /// Before the operator at this offset in the module.
+    Offset(u32),
+}
+
+/// How the execution resumes after a pause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    /// Runs until the next breakpoint.
+    Continue,
+    /// Pauses again before the next operator.
+    Step,
+    /// Stops the execution with a trap.
+    Abort,
+}
+
+#[derive(Debug, Default)]
+struct DebuggerState {
+    breakpoints: Vec<Breakpoint>,
+    stepping: bool,
+    locals: Vec<Option<Value>>,
+}
+
+/// Controls the execution of an [`Instance`] compiled with the
+/// [`Breakpoints`] middleware.
+///
+/// # Example
+///
+/// ```rust
+/// use wasmer::{AsStoreMut, ExportError, Instance};
+/// use wasmer_middlewares::breakpoints::{Breakpoint, Debugger, Resume};
+///
+/// fn debug(store: &mut impl AsStoreMut, instance: &Instance) -> Result<(), ExportError> {
+///     let debugger = Debugger::attach(store, instance, |pause| {
+///         println!("paused at {:#x}, locals: {:?}", pause.offset(), pause.locals());
+///         Resume::Step
+///     })?;
+///     debugger.set_breakpoint(store, Breakpoint::Offset(0x2a));
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Debugger {
+    state: Arc<Mutex<DebuggerState>>,
+    enabled: Global,
+}
+
+type PauseHandler = Box<dyn FnMut(&mut Pause<'_>) -> Resume + Send>;
+
+/// The environment of the hooks of a [`Debugger`].
+struct HookEnv {
+    debugger: Debugger,
+    on_pause: Mutex<PauseHandler>,
+    memory: Option<Memory>,
+    globals: Vec<Global>,
+}
+
+impl Debugger {
+    /// Attaches a debugger to `instance`, calling `on_pause` whenever its
+    /// execution pauses.
+    ///
+    /// # Errors
+    ///
+    /// The given [`Instance`] must have been processed with the
+    /// [`Breakpoints`] middleware at compile time, otherwise this will
+    /// fail with an [`ExportError`].
+    pub fn attach(
+        store: &mut impl AsStoreMut,
+        instance: &Instance,
+        on_pause: impl FnMut(&mut Pause<'_>) -> Resume + Send + 'static,
+    ) -> Result<Self, ExportError> {
+        let exports = &instance.exports;
+        let hooks = exports.get_table("wasmer_breakpoints_hooks")?;
+        let enabled = exports.get_global("wasmer_breakpoints_enabled")?.clone();
+        let memory = exports.get_memory("wasmer_breakpoints_memory").ok().cloned();
+        let globals = (0..)
+            .map_while(|index| {
+                exports
+                    .get_global(&format!("wasmer_breakpoints_global_{index}"))
+                    .ok()
+                    .cloned()
+            })
+            .collect();
+
+        let debugger = Self {
+            state: Default::default(),
+            enabled,
+        };
+        let env = FunctionEnv::new(
+            store,
+            HookEnv {
+                debugger: debugger.clone(),
+                on_pause: Mutex::new(Box::new(on_pause)),
+                memory,
+                globals,
+            },
+        );
+        let check = Function::new_typed_with_env(store, &env, check_hook);
+        let value = Function::new_typed_with_env(store, &env, value_hook);
+        let pause = Function::new_typed_with_env(store, &env, pause_hook);
+        for (element, hook) in [(CHECK_HOOK, check), (VALUE_HOOK, value), (PAUSE_HOOK, pause)] {
+            hooks
+                .set(store, element, Value::FuncRef(Some(hook)))
+                .expect("Can't set the hooks in `wasmer_breakpoints_hooks`");
+        }
+
+        Ok(debugger)
+    }
+
+    /// Sets a breakpoint, returns `false` if it was already set.
+    pub fn set_breakpoint(&self, store: &mut impl AsStoreMut, breakpoint: Breakpoint) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.breakpoints.contains(&breakpoint) {
+            return false;
+        }
+        state.breakpoints.push(breakpoint);
+        drop(state);
+        self.update(store);
+        true
+    }
+
+    /// Removes a breakpoint, returns `false` if it wasn't set.
+    pub fn remove_breakpoint(&self, store: &mut impl AsStoreMut, breakpoint: Breakpoint) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let len = state.breakpoints.len();
+        state.breakpoints.retain(|b| *b != breakpoint);
+        let removed = state.breakpoints.len() < len;
+        drop(state);
+        self.update(store);
+        removed
+    }
+
+    /// The breakpoints that are set, in the order they were set.
+    pub fn breakpoints(&self) -> Vec<Breakpoint> {
+        self.state.lock().unwrap().breakpoints.clone()
+    }
+
+    /// Pauses the execution before the next operator, like when a pause
+    /// resumes with [`Resume::Step`].
+    pub fn step(&self, store: &mut impl AsStoreMut) {
+        self.state.lock().unwrap().stepping = true;
+        self.update(store);
+    }
+
+    /// Enables the checks of the instrumented code only when they can
+    /// pause.
+    fn update(&self, store: &mut impl AsStoreMut) {
+        let state = self.state.lock().unwrap();
+        let enabled = state.stepping || !state.breakpoints.is_empty();
+        drop(state);
+        self.enabled
+            .set(store, Value::I32(enabled as i32))
+            .expect("Can't set `wasmer_breakpoints_enabled` in Instance");
+    }
+}
+
+impl fmt::Debug for Debugger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debugger")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+/// A pause of the execution of an instance, given to the handler of its
+/// [`Debugger`].
+pub struct Pause<'a> {
+    function: FunctionIndex,
+    offset: u32,
+    entry: bool,
+    locals: Vec<Option<Value>>,
+    store: StoreMut<'a>,
+    env: &'a HookEnv,
+}
+
+impl Pause<'_> {
+    /// The function in which the execution paused.
+    pub fn function(&self) -> FunctionIndex {
+        self.function
+    }
+
+    /// The offset in the module of the operator about to run.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Whether the execution paused on the entry of the function.
+    pub fn is_function_entry(&self) -> bool {
+        self.entry
+    }
+
+    /// The parameters and the locals of the function, in the order of
+    /// their index. `None` for the `v128` and reference locals, which
+    /// can't be inspected.
+    pub fn locals(&self) -> &[Option<Value>] {
+        &self.locals
+    }
+
+    /// The values of the globals of the instance, in the order of their
+    /// index.
+    pub fn globals(&mut self) -> Vec<Value> {
+        self.env
+            .globals
+            .iter()
+            .map(|global| global.get(&mut self.store))
+            .collect()
+    }
+
+    /// Reads `len` bytes of the first memory of the instance at `offset`.
+    pub fn read_memory(&self, offset: u64, len: usize) -> Result<Vec<u8>, MemoryAccessError> {
+        let memory = self
+            .env
+            .memory
+            .as_ref()
+            .ok_or(MemoryAccessError::HeapOutOfBounds)?;
+        let mut buf = vec![0; len];
+        memory.view(&self.store).read(offset, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Sets a breakpoint, returns `false` if it was already set.
+    pub fn set_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.env
+            .debugger
+            .set_breakpoint(&mut self.store, breakpoint)
+    }
+
+    /// Removes a breakpoint, returns `false` if it wasn't set.
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.env
+            .debugger
+            .remove_breakpoint(&mut self.store, breakpoint)
+    }
+
+    /// The breakpoints that are set, in the order they were set.
+    pub fn breakpoints(&self) -> Vec<Breakpoint> {
+        self.env.debugger.breakpoints()
+    }
+}
+
+impl fmt::Debug for Pause<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pause")
+            .field("function", &self.function)
+            .field("offset", &self.offset)
+            .field("entry", &self.entry)
+            .field("locals", &self.locals)
+            .finish()
+    }
+}
+
+/// Decides whether to pause at a location.
+fn check_hook(env: FunctionEnvMut<HookEnv>, function: u32, offset: u32, entry: i32) -> i32 {
+    let mut state = env.data().debugger.state.lock().unwrap();
+    let pause = state.stepping
+        || state.breakpoints.iter().any(|breakpoint| match breakpoint {
+            Breakpoint::Function(index) => entry != 0 && index.as_u32() == function,
+            Breakpoint::Offset(at) => *at == offset,
+        });
+    if pause {
+        state.locals.clear();
+    }
+    pause as i32
+}
+
+/// Receives the value of the next local before a pause.
+fn value_hook(env: FunctionEnvMut<HookEnv>, _index: u32, bits: i64, code: i32) {
+    let value = match code {
+        I32_VALUE => Some(Value::I32(bits as i32)),
+        I64_VALUE => Some(Value::I64(bits)),
+        F32_VALUE => Some(Value::F32(f32::from_bits(bits as u32))),
+        F64_VALUE => Some(Value::F64(f64::from_bits(bits as u64))),
+        _ => None,
+    };
+    env.data().debugger.state.lock().unwrap().locals.push(value);
+}
+
+/// Pauses, until the handler of the debugger resumes the execution.
+fn pause_hook(
+    mut env: FunctionEnvMut<HookEnv>,
+    function: u32,
+    offset: u32,
+    entry: i32,
+) -> Result<i32, RuntimeError> {
+    let (env, store) = env.data_and_store_mut();
+    let env = &*env;
+    let locals = std::mem::take(&mut env.debugger.state.lock().unwrap().locals);
+    let mut pause = Pause {
+        function: FunctionIndex::from_u32(function),
+        offset,
+        entry: entry != 0,
+        locals,
+        store,
+        env,
+    };
+    let mut on_pause = env.on_pause.lock().unwrap();
+    let resume = (*on_pause)(&mut pause);
+    drop(on_pause);
+
+    env.debugger.state.lock().unwrap().stepping = resume == Resume::Step;
+    env.debugger.update(&mut pause.store);
+    match resume {
+        Resume::Abort => Err(RuntimeError::new("execution aborted by the debugger")),
+        Resume::Continue | Resume::Step => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use wasmer::sys::EngineBuilder;
+    use wasmer::{imports, wat2wasm, CompilerConfig, Cranelift, Module, Store, TypedFunction};
+
+    fn bytecode() -> Vec<u8> {
+        wat2wasm(
+            br#"(module
+            (memory 1)
+            (data (i32.const 16) "hi")
+            (global $counter (mut i32) (i32.const 7))
+            (func $add (export "add") (param $a i32) (param $b f64) (result i32)
+                (local $sum i64)
+                local.get $a
+                i64.extend_i32_u
+                local.set $sum
+                local.get $a
+                i32.const 1
+                i32.add)
+            (func (export "main") (result i32)
+                i32.const 41
+                f64.const 0.5
+                call $add))
+            "#,
+        )
+        .unwrap()
+        .into()
+    }
+
+    fn instantiate() -> (Store, Instance) {
+        let mut compiler_config = Cranelift::default();
+        compiler_config.push_middleware(Arc::new(Breakpoints::new()));
+        let mut store = Store::new(EngineBuilder::new(compiler_config));
+        let module = Module::new(&store, bytecode()).unwrap();
+        let instance = Instance::new(&mut store, &module, &imports! {}).unwrap();
+        (store, instance)
+    }
+
+    #[test]
+    fn runs_without_breakpoints() {
+        let (mut store, instance) = instantiate();
+        let debugger = Debugger::attach(&mut store, &instance, |_| {
+            panic!("no breakpoint was set")
+        })
+        .unwrap();
+        assert!(debugger.breakpoints().is_empty());
+
+        let main: TypedFunction<(), i32> = instance
+            .exports
+            .get_function("main")
+            .unwrap()
+            .typed(&store)
+            .unwrap();
+        assert_eq!(main.call(&mut store).unwrap(), 42);
+    }
+
+    #[test]
+    fn pauses_on_function_entry_and_steps() {
+        let (mut store, instance) = instantiate();
+        let pauses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = pauses.clone();
+        let debugger = Debugger::attach(&mut store, &instance, move |pause| {
+            let mut pauses = recorded.lock().unwrap();
+            pauses.push((
+                pause.function(),
+                pause.is_function_entry(),
+                pause.locals().to_vec(),
+                pause.globals(),
+                pause.read_memory(16, 2).unwrap(),
+            ));
+            if pauses.len() < 4 {
+                Resume::Step
+            } else {
+                Resume::Continue
+            }
+        })
+        .unwrap();
+        let add = Breakpoint::Function(FunctionIndex::new(0));
+        assert!(debugger.set_breakpoint(&mut store, add));
+        assert!(!debugger.set_breakpoint(&mut store, add));
+
+        let main: TypedFunction<(), i32> = instance
+            .exports
+            .get_function("main")
+            .unwrap()
+            .typed(&store)
+            .unwrap();
+        assert_eq!(main.call(&mut store).unwrap(), 42);
+
+        let pauses = pauses.lock().unwrap();
+        assert_eq!(pauses.len(), 4);
+        let (function, entry, locals, globals, memory) = &pauses[0];
+        assert_eq!(*function, FunctionIndex::new(0));
+        assert!(*entry);
+        assert_eq!(
+            locals,
+            &[
+                Some(Value::I32(41)),
+                Some(Value::F64(0.5)),
+                Some(Value::I64(0))
+            ]
+        );
+        assert_eq!(globals[0], Value::I32(7));
+        assert_eq!(memory, b"hi");
+        // local.get $a, i64.extend_i32_u, local.set $sum
+        assert!(!pauses[1].1);
+        assert_eq!(pauses[3].2[2], Some(Value::I64(41)));
+
+        // Without the breakpoint, the next call doesn't pause.
+        assert!(debugger.remove_breakpoint(&mut store, add));
+        assert!(!debugger.remove_breakpoint(&mut store, add));
+        assert_eq!(main.call(&mut store).unwrap(), 42);
+        assert_eq!(pauses.len(), 4);
+    }
+
+    #[test]
+    fn aborts() {
+        let (mut store, instance) = instantiate();
+        let debugger = Debugger::attach(&mut store, &instance, |_| Resume::Abort).unwrap();
+        debugger.step(&mut store);
+
+        let main = instance.exports.get_function("main").unwrap();
+        let err = main.call(&mut store, &[]).unwrap_err();
+        assert_eq!(err.message(), "execution aborted by the debugger");
+    }
+}
@@ -0,0 +1,160 @@
+//! `deny_list` is a middleware for rejecting modules which use some
+//! operators, e.g. floating point instructions in a deterministic
+//! environment. Modules using a denied operator fail to compile.
+
+use std::fmt;
+use std::sync::Arc;
+use wasmer::wasmparser::Operator;
+use wasmer::{
+    FunctionMiddleware, LocalFunctionIndex, MiddlewareError, MiddlewareReaderState,
+    ModuleMiddleware,
+};
+
+/// The module-level deny-list middleware.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use wasmer::{wasmparser::Operator, CompilerConfig};
+/// use wasmer_middlewares::DenyList;
+///
+/// fn create_deny_list_middleware(compiler_config: &mut dyn CompilerConfig) {
+///     // Let's deny the memory growing operator.
+///     let is_denied = |operator: &Operator| matches!(operator, Operator::MemoryGrow { .. });
+///
+///     // Let's create the deny-list middleware.
+///     let deny_list = Arc::new(DenyList::new(is_denied));
+///
+///     // Finally, let's push the middleware.
+///     compiler_config.push_middleware(deny_list);
+/// }
+/// ```
+pub struct DenyList<F: Fn(&Operator) -> bool + Send + Sync> {
+    /// Function that returns `true` for the denied operators.
+    is_denied: Arc<F>,
+}
+
+/// The function-level deny-list middleware.
+pub struct FunctionDenyList<F: Fn(&Operator) -> bool + Send + Sync> {
+    /// Function that returns `true` for the denied operators.
+    is_denied: Arc<F>,
+
+    /// The function being compiled, for error messages.
+    local_function_index: LocalFunctionIndex,
+}
+
+impl<F: Fn(&Operator) -> bool + Send + Sync> DenyList<F> {
+    /// Creates a `DenyList` middleware rejecting the operators for
+    /// which `is_denied` returns `true`.
+    pub fn new(is_denied: F) -> Self {
+        Self {
+            is_denied: Arc::new(is_denied),
+        }
+    }
+}
+
+impl<F: Fn(&Operator) -> bool + Send + Sync> fmt::Debug for DenyList<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DenyList")
+            .field("is_denied", &"<function>")
+            .finish()
+    }
+}
+
+impl<F: Fn(&Operator) -> bool + Send + Sync + 'static> ModuleMiddleware for DenyList<F> {
+    /// Generates a `FunctionMiddleware` for a given function.
+    fn generate_function_middleware(
+        &self,
+        local_function_index: LocalFunctionIndex,
+    ) -> Box<dyn FunctionMiddleware> {
+        Box::new(FunctionDenyList {
+            is_denied: self.is_denied.clone(),
+            local_function_index,
+        })
+    }
+}
+
+impl<F: Fn(&Operator) -> bool + Send + Sync> fmt::Debug for FunctionDenyList<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionDenyList")
+            .field("is_denied", &"<function>")
+            .field("local_function_index", &self.local_function_index)
+            .finish()
+    }
+}
+
+impl<F: Fn(&Operator) -> bool + Send + Sync> FunctionMiddleware for FunctionDenyList<F> {
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        if (self.is_denied)(&operator) {
+            return Err(MiddlewareError::new(
+                "DenyList",
+                format!(
+                    "operator {:?} is not allowed (in local function {})",
+                    operator,
+                    self.local_function_index.as_u32()
+                ),
+            ));
+        }
+
+        state.push_operator(operator);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use wasmer::sys::EngineBuilder;
+    use wasmer::{wat2wasm, CompilerConfig, Cranelift, Module, Store};
+
+    fn is_float(operator: &Operator) -> bool {
+        matches!(operator, Operator::F32Add | Operator::F32Const { .. })
+    }
+
+    fn store() -> Store {
+        let mut compiler_config = Cranelift::default();
+        compiler_config.push_middleware(Arc::new(DenyList::new(is_float)));
+        Store::new(EngineBuilder::new(compiler_config))
+    }
+
+    #[test]
+    fn allowed_operators_compile() {
+        let bytecode = wat2wasm(
+            br#"(module
+            (func (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add))"#,
+        )
+        .unwrap();
+
+        Module::new(&store(), bytecode).unwrap();
+    }
+
+    #[test]
+    fn denied_operators_are_rejected() {
+        let bytecode = wat2wasm(
+            br#"(module
+            (func (result i32)
+                i32.const 1)
+            (func (param f32) (result f32)
+                local.get 0
+                f32.const 1
+                f32.add))"#,
+        )
+        .unwrap();
+
+        let error = Module::new(&store(), bytecode).unwrap_err().to_string();
+
+        assert!(error.contains("F32Const"), "{error}");
+        assert!(error.contains("local function 1"), "{error}");
+    }
+}
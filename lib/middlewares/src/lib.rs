@@ -1,8 +1,12 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod breakpoints;
+pub mod deny_list;
 pub mod metering;
 
 // The most commonly used symbol are exported at top level of the
 // module. Others are available via modules,
 // e.g. `wasmer_middlewares::metering::get_remaining_points`
+pub use breakpoints::Breakpoints;
+pub use deny_list::DenyList;
 pub use metering::Metering;
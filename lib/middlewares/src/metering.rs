@@ -3,6 +3,9 @@
 //! operators executed. The WebAssembly instance execution is stopped
 //! when the limit is reached.
 //!
+//! Instead of the per-instance limit, the instances can also share the
+//! fuel of their store, see `Store::add_fuel`.
+//!
 //! # Example
 //!
 //! [See the `metering` detailed and complete
@@ -200,6 +203,16 @@ impl<F: Fn(&Operator) -> u64 + Send + Sync + 'static> ModuleMiddleware for Meter
 
         Ok(())
     }
+
+    /// The cost function is only identified by its type, the initial
+    /// limit is part of the compiled code.
+    fn deterministic_id(&self) -> String {
+        format!(
+            "{}-{}",
+            std::any::type_name::<Self>(),
+            self.initial_limit
+        )
+    }
 }
 
 /// Returns `true` if and only if the given operator is an accounting operator.
@@ -613,4 +626,61 @@ mod tests {
             MeteringPoints::Exhausted
         );
     }
+
+    #[test]
+    fn store_fuel_works() {
+        let metering = Arc::new(Metering::new(10, cost_function));
+        let mut compiler_config = Cranelift::default();
+        compiler_config.push_middleware(metering);
+        let mut store = Store::new(EngineBuilder::new(compiler_config));
+        let module = Module::new(&store, bytecode()).unwrap();
+        assert_eq!(store.fuel_consumed(), None);
+
+        // Instances created after fuel was added share the fuel of the store.
+        store.add_fuel(10);
+        let first = Instance::new(&mut store, &module, &imports! {}).unwrap();
+        let second = Instance::new(&mut store, &module, &imports! {}).unwrap();
+        let add_one = |instance: &Instance| -> TypedFunction<i32, i32> {
+            instance
+                .exports
+                .get_typed_function(&store, "add_one")
+                .unwrap()
+        };
+        let (first_add_one, second_add_one) = (add_one(&first), add_one(&second));
+
+        first_add_one.call(&mut store, 1).unwrap();
+        second_add_one.call(&mut store, 1).unwrap();
+        assert_eq!(store.fuel_consumed(), Some(8));
+        assert_eq!(
+            get_remaining_points(&mut store, &first),
+            MeteringPoints::Remaining(2)
+        );
+
+        let err = first_add_one.call(&mut store, 1).unwrap_err();
+        assert_eq!(err.to_trap(), Some(wasmer::TrapCode::OutOfFuel));
+        assert_eq!(
+            get_remaining_points(&mut store, &second),
+            MeteringPoints::Exhausted
+        );
+
+        // Adding fuel allows the execution to resume.
+        store.add_fuel(4);
+        assert_eq!(second_add_one.call(&mut store, 1).unwrap(), 2);
+        assert_eq!(store.fuel_consumed(), Some(12));
+    }
+
+    #[test]
+    fn deterministic_id_depends_on_the_initial_limit() {
+        fn deterministic_id(initial_limit: u64) -> String {
+            let mut compiler_config = Cranelift::default();
+            compiler_config.push_middleware(Arc::new(Metering::new(initial_limit, cost_function)));
+            EngineBuilder::new(compiler_config)
+                .engine()
+                .deterministic_id()
+                .to_string()
+        }
+
+        assert_eq!(deterministic_id(10), deterministic_id(10));
+        assert_ne!(deterministic_id(10), deterministic_id(20));
+    }
 }
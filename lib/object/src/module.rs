@@ -254,6 +254,7 @@ pub fn emit_compilation(
     }
 
     // Add dynamic function trampolines
+    let mut dynamic_function_trampoline_symbol_ids = PrimaryMap::new();
     for (func_index, function) in compilation.dynamic_function_trampolines.into_iter() {
         let function_name =
             symbol_registry.symbol_to_name(Symbol::DynamicFunctionTrampoline(func_index));
@@ -269,6 +270,7 @@ pub fn emit_compilation(
             flags: SymbolFlags::None,
         });
         obj.add_symbol_data(symbol_id, section_id, &function.body, align);
+        dynamic_function_trampoline_symbol_ids.push(symbol_id);
     }
 
     let mut all_relocations = Vec::new();
@@ -391,6 +393,21 @@ pub fn emit_compilation(
                     )
                     .map_err(ObjectError::Write)?;
                 }
+                RelocationTarget::DynamicFunctionTrampoline(func_index) => {
+                    let target_symbol = dynamic_function_trampoline_symbol_ids[func_index];
+                    obj.add_relocation(
+                        section_id,
+                        Relocation {
+                            offset: relocation_address,
+                            size: relocation_size,
+                            kind: relocation_kind,
+                            encoding: relocation_encoding,
+                            symbol: target_symbol,
+                            addend: r.addend,
+                        },
+                    )
+                    .map_err(ObjectError::Write)?;
+                }
             };
         }
     }
@@ -14,7 +14,7 @@ use crate::entity::PrimaryMap;
 use crate::lib::std::fmt;
 use crate::lib::std::vec::Vec;
 use crate::{Addend, CodeOffset};
-use crate::{LibCall, LocalFunctionIndex};
+use crate::{FunctionIndex, LibCall, LocalFunctionIndex};
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 #[cfg(feature = "enable-serde")]
 use serde::{Deserialize, Serialize};
@@ -137,8 +137,7 @@ pub trait RelocationLike {
                 let reloc_addend = self.addend() as isize;
                 let reloc_delta_u32 = (target_func_address as u32)
                     .wrapping_sub(reloc_address as u32)
-                    .checked_add(reloc_addend as u32)
-                    .unwrap();
+                    .wrapping_add(reloc_addend as u32);
                 (reloc_address, reloc_delta_u32 as u64)
             }
             RelocationKind::X86PCRel8 => {
@@ -223,6 +222,9 @@ pub enum RelocationTarget {
     LibCall(LibCall),
     /// Custom sections generated by the compiler
     CustomSection(SectionIndex),
+    /// The dynamic function trampoline of an imported function, e.g. to
+    /// describe how to unwind it in the `.eh_frame` section.
+    DynamicFunctionTrampoline(FunctionIndex),
 }
 
 /// Relocations to apply to function bodies.
@@ -183,6 +183,10 @@ pub struct ModuleInfo {
 
     /// Number of imported globals in the module.
     pub num_imported_globals: usize,
+
+    /// The offset of the contents of the code section in the module, which
+    /// the addresses of the DWARF debugging information are relative to.
+    pub code_section_offset: usize,
 }
 
 /// Mirror version of ModuleInfo that can derive rkyv traits
@@ -210,6 +214,7 @@ pub struct ArchivableModuleInfo {
     num_imported_tables: usize,
     num_imported_memories: usize,
     num_imported_globals: usize,
+    code_section_offset: usize,
 }
 
 impl From<ModuleInfo> for ArchivableModuleInfo {
@@ -236,6 +241,7 @@ impl From<ModuleInfo> for ArchivableModuleInfo {
             num_imported_tables: it.num_imported_tables,
             num_imported_memories: it.num_imported_memories,
             num_imported_globals: it.num_imported_globals,
+            code_section_offset: it.code_section_offset,
         }
     }
 }
@@ -265,6 +271,7 @@ impl From<ArchivableModuleInfo> for ModuleInfo {
             num_imported_tables: it.num_imported_tables,
             num_imported_memories: it.num_imported_memories,
             num_imported_globals: it.num_imported_globals,
+            code_section_offset: it.code_section_offset,
         }
     }
 }
@@ -325,6 +332,7 @@ impl PartialEq for ModuleInfo {
             && self.num_imported_tables == other.num_imported_tables
             && self.num_imported_memories == other.num_imported_memories
             && self.num_imported_globals == other.num_imported_globals
+            && self.code_section_offset == other.code_section_offset
     }
 }
 
@@ -430,6 +438,22 @@ impl ModuleInfo {
             })
     }
 
+    /// Get the data of the custom section named `name`, borrowed from
+    /// the module.
+    pub fn custom_section(&self, name: &str) -> Option<&[u8]> {
+        self.custom_sections
+            .get(name)
+            .map(|section_index| &*self.custom_sections_data[*section_index])
+    }
+
+    /// Iterate over the names and data of all the custom sections of the
+    /// module, in the order they appear in it.
+    pub fn all_custom_sections(&self) -> impl Iterator<Item = (&str, &[u8])> + '_ {
+        self.custom_sections.iter().map(|(name, section_index)| {
+            (name.as_str(), &*self.custom_sections_data[*section_index])
+        })
+    }
+
     /// Convert a `LocalFunctionIndex` into a `FunctionIndex`.
     pub fn func_index(&self, local_func: LocalFunctionIndex) -> FunctionIndex {
         FunctionIndex::new(self.num_imported_functions + local_func.index())
@@ -211,7 +211,7 @@ pub struct MetadataHeader {
 impl MetadataHeader {
     /// Current ABI version. Increment this any time breaking changes are made
     /// to the format of the serialized data.
-    pub const CURRENT_VERSION: u32 = 7;
+    pub const CURRENT_VERSION: u32 = 8;
 
     /// Magic number to identify wasmer metadata.
     const MAGIC: [u8; 8] = *b"WASMER\0\0";
@@ -238,6 +238,23 @@ impl MetadataHeader {
 
     /// Parses the header and returns the length of the metadata following it.
     pub fn parse(bytes: &[u8]) -> Result<usize, DeserializeError> {
+        let (version, len) = Self::parse_any_version(bytes)?;
+        if version != Self::CURRENT_VERSION {
+            return Err(DeserializeError::Incompatible(
+                "The provided bytes were serialized by an incompatible version of Wasmer"
+                    .to_string(),
+            ));
+        }
+        Ok(len)
+    }
+
+    /// Parses the header and returns the ABI version it was written with,
+    /// together with the length of the metadata following it.
+    ///
+    /// Unlike [`Self::parse`], headers written by other versions of Wasmer
+    /// are accepted, which makes it possible to inspect (and upgrade) older
+    /// artifacts.
+    pub fn parse_any_version(bytes: &[u8]) -> Result<(u32, usize), DeserializeError> {
         if bytes.as_ptr() as usize % 8 != 0 {
             return Err(DeserializeError::CorruptedBinary(
                 "misaligned metadata".to_string(),
@@ -256,12 +273,6 @@ impl MetadataHeader {
                 "The provided bytes were not serialized by Wasmer".to_string(),
             ));
         }
-        if header.version != Self::CURRENT_VERSION {
-            return Err(DeserializeError::Incompatible(
-                "The provided bytes were serialized by an incompatible version of Wasmer"
-                    .to_string(),
-            ));
-        }
-        Ok(header.len as usize)
+        Ok((header.version, header.len as usize))
     }
 }
@@ -1,3 +1,4 @@
+use crate::lib::std::fmt;
 use crate::SourceLoc;
 
 /// Description of a frame in a backtrace.
@@ -16,6 +17,9 @@ pub struct FrameInfo {
     func_start: SourceLoc,
     /// The source location of the instruction
     instr: SourceLoc,
+    /// The location of the instruction in the source code of the module,
+    /// if it has DWARF debugging information.
+    source_location: Option<SourceLocation>,
 }
 
 impl FrameInfo {
@@ -33,9 +37,17 @@ impl FrameInfo {
             function_name,
             func_start,
             instr,
+            source_location: None,
         }
     }
 
+    /// Sets the location of the instruction of this frame in the source
+    /// code of the module.
+    pub fn with_source_location(mut self, source_location: SourceLocation) -> Self {
+        self.source_location = Some(source_location);
+        self
+    }
+
     /// Returns the WebAssembly function index for this frame.
     ///
     /// This function index is the index in the function index space of the
@@ -93,4 +105,58 @@ impl FrameInfo {
     pub fn func_offset(&self) -> usize {
         (self.instr.bits() - self.func_start.bits()) as usize
     }
+
+    /// Returns the location of the instruction of this frame in the source
+    /// code the module was compiled from, such as `src/lib.rs:42:5`.
+    ///
+    /// It is only available when the module carries DWARF debugging
+    /// information in its custom sections, as emitted by compilers in debug
+    /// builds.
+    pub fn source_location(&self) -> Option<&SourceLocation> {
+        self.source_location.as_ref()
+    }
+}
+
+/// A location in the source code a module was compiled from, found in its
+/// DWARF debugging information.
+///
+/// Unlike a [`SourceLoc`], which is an offset in the WebAssembly module,
+/// this refers to a line of the original source files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    file: String,
+    line: u32,
+    column: Option<u32>,
+}
+
+impl SourceLocation {
+    /// Creates a new [`SourceLocation`].
+    pub fn new(file: String, line: u32, column: Option<u32>) -> Self {
+        Self { file, line, column }
+    }
+
+    /// Returns the path of the source file, as recorded by the compiler.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Returns the line in the source file, starting at 1.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the column in the line, starting at 1, if known.
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        Ok(())
+    }
 }
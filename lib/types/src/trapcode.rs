@@ -69,6 +69,15 @@ pub enum TrapCode {
 
     /// An atomic memory access was attempted with an unaligned pointer.
     UnalignedAtomic = 10,
+
+    /// Execution was interrupted because the epoch deadline was reached.
+    Interrupt = 11,
+
+    /// Execution ran out of fuel.
+    OutOfFuel = 12,
+
+    /// A global frozen by the host was written to.
+    FrozenGlobal = 13,
 }
 
 impl TrapCode {
@@ -86,6 +95,9 @@ impl TrapCode {
             Self::BadConversionToInteger => "invalid conversion to integer",
             Self::UnreachableCodeReached => "unreachable",
             Self::UnalignedAtomic => "unaligned atomic access",
+            Self::Interrupt => "interrupted: epoch deadline reached",
+            Self::OutOfFuel => "out of fuel",
+            Self::FrozenGlobal => "write to a frozen global",
         }
     }
 }
@@ -104,6 +116,9 @@ impl Display for TrapCode {
             Self::BadConversionToInteger => "bad_toint",
             Self::UnreachableCodeReached => "unreachable",
             Self::UnalignedAtomic => "unalign_atom",
+            Self::Interrupt => "interrupt",
+            Self::OutOfFuel => "out_of_fuel",
+            Self::FrozenGlobal => "frozen_global",
         };
         f.write_str(identifier)
     }
@@ -125,6 +140,9 @@ impl FromStr for TrapCode {
             "bad_toint" => Ok(Self::BadConversionToInteger),
             "unreachable" => Ok(Self::UnreachableCodeReached),
             "unalign_atom" => Ok(Self::UnalignedAtomic),
+            "interrupt" => Ok(Self::Interrupt),
+            "out_of_fuel" => Ok(Self::OutOfFuel),
+            "frozen_global" => Ok(Self::FrozenGlobal),
             _ => Err(()),
         }
     }
@@ -148,7 +166,7 @@ mod tests {
     use super::*;
 
     // Everything but user-defined codes.
-    const CODES: [TrapCode; 11] = [
+    const CODES: [TrapCode; 14] = [
         TrapCode::StackOverflow,
         TrapCode::HeapAccessOutOfBounds,
         TrapCode::HeapMisaligned,
@@ -160,6 +178,9 @@ mod tests {
         TrapCode::BadConversionToInteger,
         TrapCode::UnreachableCodeReached,
         TrapCode::UnalignedAtomic,
+        TrapCode::Interrupt,
+        TrapCode::OutOfFuel,
+        TrapCode::FrozenGlobal,
     ];
 
     #[test]
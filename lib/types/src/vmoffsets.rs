@@ -202,6 +202,8 @@ pub struct VMOffsets {
     vmctx_gas_limiter_pointer: u32,
     vmctx_stack_limit_begin: u32,
     vmctx_stack_limit_initial_begin: u32,
+    vmctx_epoch_counter_ptr: u32,
+    vmctx_epoch_deadline_ptr: u32,
     size_of_vmctx: u32,
 }
 
@@ -231,6 +233,8 @@ impl VMOffsets {
             vmctx_gas_limiter_pointer: 0,
             vmctx_stack_limit_begin: 0,
             vmctx_stack_limit_initial_begin: 0,
+            vmctx_epoch_counter_ptr: 0,
+            vmctx_epoch_deadline_ptr: 0,
             size_of_vmctx: 0,
         };
         ret.precompute();
@@ -265,6 +269,8 @@ impl VMOffsets {
             vmctx_gas_limiter_pointer: 0,
             vmctx_stack_limit_begin: 0,
             vmctx_stack_limit_initial_begin: 0,
+            vmctx_epoch_counter_ptr: 0,
+            vmctx_epoch_deadline_ptr: 0,
             size_of_vmctx: 0,
         }
     }
@@ -356,7 +362,20 @@ impl VMOffsets {
             u32::from(self.pointer_size),
         );
         self.vmctx_stack_limit_initial_begin = self.vmctx_stack_limit_begin.checked_add(4).unwrap();
-        self.size_of_vmctx = self.vmctx_stack_limit_begin.checked_add(4).unwrap();
+        self.vmctx_epoch_counter_ptr = align(
+            self.vmctx_stack_limit_initial_begin.checked_add(4).unwrap(),
+            u32::from(self.pointer_size),
+        );
+        self.vmctx_epoch_deadline_ptr = offset_by(
+            self.vmctx_epoch_counter_ptr,
+            1,
+            u32::from(self.pointer_size),
+        );
+        self.size_of_vmctx = offset_by(
+            self.vmctx_epoch_deadline_ptr,
+            1,
+            u32::from(self.pointer_size),
+        );
     }
 }
 
@@ -536,6 +555,14 @@ impl VMOffsets {
     }
 }
 
+/// Offsets for `VMGlobalDefinition`.
+impl VMOffsets {
+    /// The offset of the `frozen` field.
+    pub const fn vmglobal_definition_frozen(&self) -> u8 {
+        16
+    }
+}
+
 /// Offsets for `VMSharedSignatureIndex`.
 impl VMOffsets {
     /// Return the size of `VMSharedSignatureIndex`.
@@ -638,6 +665,16 @@ impl VMOffsets {
         self.vmctx_builtin_functions_begin
     }
 
+    /// The offset of the pointer to the epoch counter of the engine.
+    pub fn vmctx_epoch_counter_ptr(&self) -> u32 {
+        self.vmctx_epoch_counter_ptr
+    }
+
+    /// The offset of the pointer to the epoch deadline of the store.
+    pub fn vmctx_epoch_deadline_ptr(&self) -> u32 {
+        self.vmctx_epoch_deadline_ptr
+    }
+
     /// Return the size of the `VMContext` allocation.
     pub fn size_of_vmctx(&self) -> u32 {
         self.size_of_vmctx
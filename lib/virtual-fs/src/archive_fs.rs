@@ -0,0 +1,566 @@
+//! A read-only filesystem backed by the contents of a tar or zip archive.
+//!
+//! The archive is decompressed into memory once when the filesystem is
+//! created, nothing is ever unpacked to disk.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, Cursor, Read, Seek},
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
+
+use crate::{
+    DirEntry, FileOpener, FileSystem, FileType, FsError, Metadata, OpenOptions, OpenOptionsConfig,
+    ReadDir, Result, VirtualFile,
+};
+
+/// The archive formats understood by [`ArchiveFileSystem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Guess the format of an archive from its file name.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Dir { modified: u64 },
+    File { data: Bytes, modified: u64 },
+}
+
+impl Entry {
+    fn metadata(&self) -> Metadata {
+        match self {
+            Entry::Dir { modified } => Metadata {
+                ft: FileType {
+                    dir: true,
+                    ..Default::default()
+                },
+                modified: *modified,
+                ..Default::default()
+            },
+            Entry::File { data, modified } => Metadata {
+                ft: FileType {
+                    file: true,
+                    ..Default::default()
+                },
+                len: data.len() as u64,
+                modified: *modified,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+// Archives without timestamps get a non-zero modified time because some
+// programs treat 0 as "missing".
+const DEFAULT_MODIFIED: u64 = 1;
+
+/// Read-only filesystem exposing the contents of a tar, tar.gz or zip
+/// archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveFileSystem {
+    entries: Arc<BTreeMap<PathBuf, Entry>>,
+}
+
+impl ArchiveFileSystem {
+    /// Load an archive from disk, detecting its format from the file name.
+    pub fn from_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let format = ArchiveFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "unable to determine the archive format of \"{}\"",
+                    path.display()
+                ),
+            )
+        })?;
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(io::BufReader::new(file), format)
+    }
+
+    /// Load an archive of the given format.
+    pub fn from_reader(reader: impl Read + Seek, format: ArchiveFormat) -> io::Result<Self> {
+        match format {
+            ArchiveFormat::Tar => Self::from_tar(reader),
+            ArchiveFormat::TarGz => Self::from_tar(flate2::read::GzDecoder::new(reader)),
+            ArchiveFormat::Zip => Self::from_zip(reader),
+        }
+    }
+
+    /// Load an uncompressed tar archive.
+    pub fn from_tar(reader: impl Read) -> io::Result<Self> {
+        let mut builder = EntriesBuilder::default();
+
+        for entry in tar::Archive::new(reader).entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.into_owned();
+            let modified = entry
+                .header()
+                .mtime()
+                .ok()
+                .filter(|mtime| *mtime != 0)
+                .map(|mtime| mtime.saturating_mul(1_000_000_000))
+                .unwrap_or(DEFAULT_MODIFIED);
+
+            match entry.header().entry_type() {
+                tar::EntryType::Directory => builder.insert_dir(&path, modified),
+                tar::EntryType::Regular | tar::EntryType::Continuous => {
+                    let mut data = Vec::with_capacity(entry.size() as usize);
+                    entry.read_to_end(&mut data)?;
+                    builder.insert_file(&path, data.into(), modified);
+                }
+                other => {
+                    tracing::debug!(
+                        path=%path.display(),
+                        entry_type=?other,
+                        "Skipping unsupported archive entry",
+                    );
+                }
+            }
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Load a zip archive.
+    pub fn from_zip(reader: impl Read + Seek) -> io::Result<Self> {
+        let mut builder = EntriesBuilder::default();
+        let mut archive = zip::ZipArchive::new(reader)?;
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let path = match file.enclosed_name() {
+                Some(path) => path,
+                None => {
+                    tracing::debug!(name = file.name(), "Skipping unsafe archive entry");
+                    continue;
+                }
+            };
+
+            if file.is_dir() {
+                builder.insert_dir(&path, DEFAULT_MODIFIED);
+            } else {
+                let mut data = Vec::with_capacity(file.size() as usize);
+                file.read_to_end(&mut data)?;
+                builder.insert_file(&path, data.into(), DEFAULT_MODIFIED);
+            }
+        }
+
+        Ok(builder.build())
+    }
+
+    fn entry(&self, path: &Path) -> Result<&Entry> {
+        self.entries
+            .get(&normalize(path))
+            .ok_or(FsError::EntryNotFound)
+    }
+}
+
+#[derive(Default)]
+struct EntriesBuilder {
+    entries: BTreeMap<PathBuf, Entry>,
+}
+
+impl EntriesBuilder {
+    fn insert_dir(&mut self, path: &Path, modified: u64) {
+        let path = normalize(path);
+        self.insert_parents(&path);
+        self.entries.insert(path, Entry::Dir { modified });
+    }
+
+    fn insert_file(&mut self, path: &Path, data: Bytes, modified: u64) {
+        let path = normalize(path);
+        if path == Path::new("/") {
+            return;
+        }
+        self.insert_parents(&path);
+        self.entries.insert(path, Entry::File { data, modified });
+    }
+
+    fn insert_parents(&mut self, path: &Path) {
+        for parent in path.ancestors().skip(1) {
+            self.entries
+                .entry(parent.to_path_buf())
+                .or_insert(Entry::Dir {
+                    modified: DEFAULT_MODIFIED,
+                });
+        }
+    }
+
+    fn build(mut self) -> ArchiveFileSystem {
+        self.entries
+            .entry(PathBuf::from("/"))
+            .or_insert(Entry::Dir {
+                modified: DEFAULT_MODIFIED,
+            });
+        ArchiveFileSystem {
+            entries: Arc::new(self.entries),
+        }
+    }
+}
+
+/// Turn a path into an absolute path without `.` or `..` components that
+/// can never escape the root of the archive.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::from("/");
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    normalized
+}
+
+impl FileSystem for ArchiveFileSystem {
+    fn readlink(&self, _path: &Path) -> Result<PathBuf> {
+        Err(FsError::InvalidInput)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<ReadDir> {
+        let path = normalize(path);
+        match self.entries.get(&path) {
+            Some(Entry::Dir { .. }) => {}
+            Some(Entry::File { .. }) => return Err(FsError::BaseNotDirectory),
+            None => return Err(FsError::EntryNotFound),
+        }
+
+        let entries = self
+            .entries
+            .iter()
+            .filter(|(p, _)| p.parent() == Some(path.as_path()))
+            .map(|(p, entry)| DirEntry {
+                path: p.clone(),
+                metadata: Ok(entry.metadata()),
+            })
+            .collect();
+
+        Ok(ReadDir::new(entries))
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<()> {
+        if self.entry(path).is_ok() {
+            return Err(FsError::AlreadyExists);
+        }
+        let parent = path.parent().unwrap_or_else(|| Path::new("/"));
+        match self.entry(parent) {
+            Ok(Entry::Dir { .. }) => Err(FsError::PermissionDenied),
+            Ok(_) | Err(FsError::EntryNotFound) => Err(FsError::BaseNotDirectory),
+            Err(other) => Err(other),
+        }
+    }
+
+    fn remove_dir(&self, path: &Path) -> Result<()> {
+        match self.entry(path)? {
+            Entry::Dir { .. } => Err(FsError::PermissionDenied),
+            Entry::File { .. } => Err(FsError::BaseNotDirectory),
+        }
+    }
+
+    fn rename<'a>(&'a self, from: &'a Path, _to: &'a Path) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.entry(from)?;
+            Err(FsError::PermissionDenied)
+        })
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata> {
+        self.entry(path).map(Entry::metadata)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> Result<Metadata> {
+        self.metadata(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        match self.entry(path)? {
+            Entry::File { .. } => Err(FsError::PermissionDenied),
+            Entry::Dir { .. } => Err(FsError::NotAFile),
+        }
+    }
+
+    fn new_open_options(&self) -> OpenOptions {
+        OpenOptions::new(self)
+    }
+
+    fn mount(
+        &self,
+        _name: String,
+        _path: &Path,
+        _fs: Box<dyn FileSystem + Send + Sync>,
+    ) -> Result<()> {
+        Err(FsError::Unsupported)
+    }
+}
+
+impl FileOpener for ArchiveFileSystem {
+    fn open(
+        &self,
+        path: &Path,
+        conf: &OpenOptionsConfig,
+    ) -> Result<Box<dyn VirtualFile + Send + Sync + 'static>> {
+        match self.entry(path) {
+            Ok(Entry::File { data, modified }) => {
+                if conf.would_mutate() {
+                    return Err(FsError::PermissionDenied);
+                }
+                Ok(Box::new(File {
+                    modified: *modified,
+                    content: Cursor::new(data.clone()),
+                }))
+            }
+            Ok(Entry::Dir { .. }) => Err(FsError::NotAFile),
+            Err(FsError::EntryNotFound) if conf.create() || conf.create_new() => {
+                Err(FsError::PermissionDenied)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct File {
+    modified: u64,
+    content: Cursor<Bytes>,
+}
+
+impl VirtualFile for File {
+    fn last_accessed(&self) -> u64 {
+        0
+    }
+
+    fn last_modified(&self) -> u64 {
+        self.modified
+    }
+
+    fn created_time(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        self.content.get_ref().len() as u64
+    }
+
+    fn set_len(&mut self, _new_size: u64) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn unlink(&mut self) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn poll_read_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        let remaining = self
+            .content
+            .get_ref()
+            .len()
+            .saturating_sub(self.content.position() as usize);
+        Poll::Ready(Ok(remaining))
+    }
+
+    fn poll_write_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()))
+    }
+}
+
+impl AsyncRead for File {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        AsyncRead::poll_read(Pin::new(&mut self.content), cx, buf)
+    }
+}
+
+impl AsyncSeek for File {
+    fn start_seek(mut self: Pin<&mut Self>, position: io::SeekFrom) -> io::Result<()> {
+        AsyncSeek::start_seek(Pin::new(&mut self.content), position)
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        AsyncSeek::poll_complete(Pin::new(&mut self.content), cx)
+    }
+}
+
+impl AsyncWrite for File {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    fn tar_archive() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, contents) in [("./assets/index.html", "<html/>"), ("hello.txt", "hi")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(1_700_000_000);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn zip_archive() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("empty/", options).unwrap();
+        writer.start_file("nested/dir/data.bin", options).unwrap();
+        writer.write_all(&[1, 2, 3]).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn read_tar_archive() {
+        let fs = ArchiveFileSystem::from_tar(tar_archive().as_slice()).unwrap();
+
+        let mut names: Vec<_> = fs
+            .read_dir("/".as_ref())
+            .unwrap()
+            .map(|entry| entry.unwrap().path)
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [PathBuf::from("/assets"), PathBuf::from("/hello.txt")]
+        );
+
+        let meta = fs.metadata("/assets/index.html".as_ref()).unwrap();
+        assert!(meta.is_file());
+        assert_eq!(meta.len, 7);
+        assert_eq!(meta.modified, 1_700_000_000_000_000_000);
+
+        let mut file = fs
+            .new_open_options()
+            .read(true)
+            .open("/assets/../assets/index.html")
+            .unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).await.unwrap();
+        assert_eq!(contents, "<html/>");
+    }
+
+    #[tokio::test]
+    async fn read_gzipped_tar_archive() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar_archive()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let fs =
+            ArchiveFileSystem::from_reader(Cursor::new(compressed), ArchiveFormat::TarGz).unwrap();
+
+        assert!(fs.metadata("/hello.txt".as_ref()).unwrap().is_file());
+    }
+
+    #[tokio::test]
+    async fn read_zip_archive() {
+        let fs = ArchiveFileSystem::from_zip(Cursor::new(zip_archive())).unwrap();
+
+        assert!(fs.metadata("/empty".as_ref()).unwrap().is_dir());
+        assert!(fs.metadata("/nested/dir".as_ref()).unwrap().is_dir());
+
+        let mut file = fs
+            .new_open_options()
+            .read(true)
+            .open("/nested/dir/data.bin")
+            .unwrap();
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents).await.unwrap();
+        assert_eq!(contents, [1, 2, 3]);
+    }
+
+    #[test]
+    fn archive_is_read_only() {
+        let fs = ArchiveFileSystem::from_tar(tar_archive().as_slice()).unwrap();
+
+        assert_eq!(
+            fs.create_dir("/new".as_ref()),
+            Err(FsError::PermissionDenied)
+        );
+        assert_eq!(
+            fs.remove_file("/hello.txt".as_ref()),
+            Err(FsError::PermissionDenied)
+        );
+        assert_eq!(
+            fs.new_open_options()
+                .write(true)
+                .open("/hello.txt")
+                .unwrap_err(),
+            FsError::PermissionDenied
+        );
+        assert_eq!(
+            fs.new_open_options()
+                .write(true)
+                .create(true)
+                .open("/new.txt")
+                .unwrap_err(),
+            FsError::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn detect_archive_format() {
+        let cases = [
+            ("assets.tar", Some(ArchiveFormat::Tar)),
+            ("assets.TAR.GZ", Some(ArchiveFormat::TarGz)),
+            ("assets.tgz", Some(ArchiveFormat::TarGz)),
+            ("assets.zip", Some(ArchiveFormat::Zip)),
+            ("assets.rar", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(ArchiveFormat::from_path(name.as_ref()), expected, "{name}");
+        }
+    }
+}
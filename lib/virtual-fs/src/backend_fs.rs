@@ -0,0 +1,514 @@
+use std::{
+    fmt,
+    io::{self, SeekFrom},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+use crate::{
+    DirEntry, FileOpener, FileSystem, FsError, Metadata, OpenOptions, OpenOptionsConfig, ReadDir,
+    Result, VirtualFile,
+};
+
+/// The storage behind a [`BackendFileSystem`].
+///
+/// This is a much smaller interface than [`FileSystem`] and [`VirtualFile`],
+/// intended for embedders who want to expose their own storage (a database,
+/// an object store, encrypted blobs, ...) to a guest. Files are addressed by
+/// their absolute path and every read or write carries its own offset, so
+/// implementations don't need to keep track of open files.
+///
+/// Only [`FsBackend::metadata()`], [`FsBackend::read_dir()`] and
+/// [`FsBackend::read()`] are required, everything else defaults to
+/// [`FsError::PermissionDenied`] which makes for a read-only filesystem.
+///
+/// The methods are called from the async runtime and should not block for
+/// long periods of time.
+pub trait FsBackend: fmt::Debug + Send + Sync + 'static {
+    fn metadata(&self, path: &Path) -> Result<Metadata>;
+
+    /// List the names of a directory's entries and their metadata.
+    fn read_dir(&self, path: &Path) -> Result<Vec<(String, Metadata)>>;
+
+    /// Read into `buf` starting at `offset`, returning the number of bytes
+    /// read (`0` at the end of the file).
+    fn read(&self, path: &Path, offset: u64, buf: &mut [u8]) -> Result<usize>;
+
+    /// Write `data` starting at `offset`, creating the file if it doesn't
+    /// exist yet and growing it if needed.
+    fn write(&self, _path: &Path, _offset: u64, _data: &[u8]) -> Result<usize> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn set_len(&self, _path: &Path, _len: u64) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn create_dir(&self, _path: &Path) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn remove_dir(&self, _path: &Path) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn remove_file(&self, _path: &Path) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn rename(&self, _from: &Path, _to: &Path) -> Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    /// Called whenever a file is opened.
+    ///
+    /// The default implementation takes care of `create`, `create_new` and
+    /// `truncate` using the other methods.
+    fn open(&self, path: &Path, conf: &OpenOptionsConfig) -> Result<()> {
+        match self.metadata(path) {
+            Ok(meta) if meta.is_dir() => return Err(FsError::NotAFile),
+            Ok(_) if conf.create_new() => return Err(FsError::AlreadyExists),
+            Ok(_) => {}
+            Err(FsError::EntryNotFound) if conf.create() || conf.create_new() => {
+                self.write(path, 0, &[])?;
+            }
+            Err(e) => return Err(e),
+        }
+
+        if conf.truncate() {
+            self.set_len(path, 0)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`FileSystem`] backed by an [`FsBackend`].
+///
+/// Like any other [`FileSystem`], it can be used as the root filesystem or be
+/// mounted at an arbitrary location (e.g. with `WasiEnvBuilder::mount()`).
+#[derive(Debug)]
+pub struct BackendFileSystem<B> {
+    backend: Arc<B>,
+}
+
+impl<B: FsBackend> BackendFileSystem<B> {
+    pub fn new(backend: B) -> Self {
+        BackendFileSystem {
+            backend: Arc::new(backend),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+}
+
+impl<B> Clone for BackendFileSystem<B> {
+    fn clone(&self) -> Self {
+        BackendFileSystem {
+            backend: Arc::clone(&self.backend),
+        }
+    }
+}
+
+impl<B: FsBackend> FileSystem for BackendFileSystem<B> {
+    fn readlink(&self, _path: &Path) -> Result<PathBuf> {
+        Err(FsError::InvalidInput)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<ReadDir> {
+        let entries = self
+            .backend
+            .read_dir(path)?
+            .into_iter()
+            .map(|(name, metadata)| DirEntry {
+                path: path.join(name),
+                metadata: Ok(metadata),
+            })
+            .collect();
+
+        Ok(ReadDir::new(entries))
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<()> {
+        self.backend.create_dir(path)
+    }
+
+    fn remove_dir(&self, path: &Path) -> Result<()> {
+        self.backend.remove_dir(path)
+    }
+
+    fn rename<'a>(&'a self, from: &'a Path, to: &'a Path) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move { self.backend.rename(from, to) })
+    }
+
+    fn metadata(&self, path: &Path) -> Result<Metadata> {
+        self.backend.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> Result<Metadata> {
+        self.backend.metadata(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        self.backend.remove_file(path)
+    }
+
+    fn new_open_options(&self) -> OpenOptions {
+        OpenOptions::new(self)
+    }
+
+    fn mount(
+        &self,
+        _name: String,
+        _path: &Path,
+        _fs: Box<dyn FileSystem + Send + Sync>,
+    ) -> Result<()> {
+        Err(FsError::Unsupported)
+    }
+}
+
+impl<B: FsBackend> FileOpener for BackendFileSystem<B> {
+    fn open(
+        &self,
+        path: &Path,
+        conf: &OpenOptionsConfig,
+    ) -> Result<Box<dyn VirtualFile + Send + Sync + 'static>> {
+        self.backend.open(path, conf)?;
+
+        Ok(Box::new(BackendFile {
+            backend: self.backend.clone(),
+            path: path.to_path_buf(),
+            cursor: 0,
+            conf: conf.clone(),
+        }))
+    }
+}
+
+/// An open file in a [`BackendFileSystem`].
+struct BackendFile<B> {
+    backend: Arc<B>,
+    path: PathBuf,
+    cursor: u64,
+    conf: OpenOptionsConfig,
+}
+
+impl<B: FsBackend> fmt::Debug for BackendFile<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendFile")
+            .field("path", &self.path)
+            .field("cursor", &self.cursor)
+            .field("conf", &self.conf)
+            .finish()
+    }
+}
+
+impl<B: FsBackend> BackendFile<B> {
+    fn metadata(&self) -> Option<Metadata> {
+        self.backend.metadata(&self.path).ok()
+    }
+}
+
+impl<B: FsBackend> VirtualFile for BackendFile<B> {
+    fn last_accessed(&self) -> u64 {
+        self.metadata().map(|m| m.accessed).unwrap_or_default()
+    }
+
+    fn last_modified(&self) -> u64 {
+        self.metadata().map(|m| m.modified).unwrap_or_default()
+    }
+
+    fn created_time(&self) -> u64 {
+        self.metadata().map(|m| m.created).unwrap_or_default()
+    }
+
+    fn size(&self) -> u64 {
+        self.metadata().map(|m| m.len).unwrap_or_default()
+    }
+
+    fn set_len(&mut self, new_size: u64) -> Result<()> {
+        if !self.conf.write() && !self.conf.append() {
+            return Err(FsError::PermissionDenied);
+        }
+        self.backend.set_len(&self.path, new_size)
+    }
+
+    fn unlink(&mut self) -> Result<()> {
+        self.backend.remove_file(&self.path)
+    }
+
+    fn poll_read_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        let remaining = self.size().saturating_sub(self.cursor);
+        Poll::Ready(Ok(remaining as usize))
+    }
+
+    fn poll_write_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(8192))
+    }
+}
+
+impl<B: FsBackend> AsyncRead for BackendFile<B> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if !self.conf.read() {
+            return Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()));
+        }
+
+        let read = self
+            .backend
+            .read(&self.path, self.cursor, buf.initialize_unfilled())?;
+        buf.advance(read);
+        self.cursor += read as u64;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<B: FsBackend> AsyncWrite for BackendFile<B> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if !self.conf.write() && !self.conf.append() {
+            return Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()));
+        }
+
+        if self.conf.append() {
+            self.cursor = self.size();
+        }
+        let written = self.backend.write(&self.path, self.cursor, data)?;
+        self.cursor += written as u64;
+
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<B: FsBackend> AsyncSeek for BackendFile<B> {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let (base, offset) = match position {
+            SeekFrom::Start(offset) => {
+                self.cursor = offset;
+                return Ok(());
+            }
+            SeekFrom::End(offset) => (self.size(), offset),
+            SeekFrom::Current(offset) => (self.cursor, offset),
+        };
+
+        self.cursor = base
+            .checked_add_signed(offset)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.cursor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::Mutex,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{mem_fs, FileType};
+
+    /// A flat key-value store, the kind of thing an embedder might put
+    /// behind a [`BackendFileSystem`].
+    #[derive(Debug, Default)]
+    struct KeyValueBackend {
+        files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+    }
+
+    fn metadata(ft: FileType, len: u64) -> Metadata {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+        Metadata {
+            ft,
+            accessed: now,
+            created: now,
+            modified: now,
+            len,
+        }
+    }
+
+    impl FsBackend for KeyValueBackend {
+        fn metadata(&self, path: &Path) -> Result<Metadata> {
+            if path == Path::new("/") {
+                return Ok(metadata(FileType::new_dir(), 0));
+            }
+            let files = self.files.lock().unwrap();
+            let file = files.get(path).ok_or(FsError::EntryNotFound)?;
+            Ok(metadata(FileType::new_file(), file.len() as u64))
+        }
+
+        fn read_dir(&self, path: &Path) -> Result<Vec<(String, Metadata)>> {
+            if path != Path::new("/") {
+                return Err(FsError::EntryNotFound);
+            }
+            let files = self.files.lock().unwrap();
+            Ok(files
+                .iter()
+                .map(|(path, contents)| {
+                    let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                    (name, metadata(FileType::new_file(), contents.len() as u64))
+                })
+                .collect())
+        }
+
+        fn read(&self, path: &Path, offset: u64, buf: &mut [u8]) -> Result<usize> {
+            let files = self.files.lock().unwrap();
+            let file = files.get(path).ok_or(FsError::EntryNotFound)?;
+            let remaining = file.get(offset as usize..).unwrap_or_default();
+            let len = remaining.len().min(buf.len());
+            buf[..len].copy_from_slice(&remaining[..len]);
+            Ok(len)
+        }
+
+        fn write(&self, path: &Path, offset: u64, data: &[u8]) -> Result<usize> {
+            let mut files = self.files.lock().unwrap();
+            let file = files.entry(path.to_path_buf()).or_default();
+            let end = offset as usize + data.len();
+            if file.len() < end {
+                file.resize(end, 0);
+            }
+            file[offset as usize..end].copy_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn set_len(&self, path: &Path, len: u64) -> Result<()> {
+            let mut files = self.files.lock().unwrap();
+            let file = files.get_mut(path).ok_or(FsError::EntryNotFound)?;
+            file.resize(len as usize, 0);
+            Ok(())
+        }
+
+        fn remove_file(&self, path: &Path) -> Result<()> {
+            let mut files = self.files.lock().unwrap();
+            files.remove(path).map(|_| ()).ok_or(FsError::EntryNotFound)
+        }
+    }
+
+    #[tokio::test]
+    async fn read_and_write_through_the_backend() {
+        let fs = BackendFileSystem::new(KeyValueBackend::default());
+
+        let mut f = fs
+            .new_open_options()
+            .write(true)
+            .create_new(true)
+            .open("/hello.txt")
+            .unwrap();
+        f.write_all(b"Hello, World!").await.unwrap();
+        f.seek(SeekFrom::Start(7)).await.unwrap();
+        f.write_all(b"Wasmer").await.unwrap();
+
+        let mut f = fs.new_open_options().read(true).open("/hello.txt").unwrap();
+        let mut contents = String::new();
+        f.read_to_string(&mut contents).await.unwrap();
+        assert_eq!(contents, "Hello, Wasmer");
+
+        let mut f = fs
+            .new_open_options()
+            .append(true)
+            .open("/hello.txt")
+            .unwrap();
+        f.write_all(b"!").await.unwrap();
+        assert_eq!(f.size(), 14);
+
+        let entries: Vec<_> = fs
+            .read_dir(Path::new("/"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path)
+            .collect();
+        assert_eq!(entries, [PathBuf::from("/hello.txt")]);
+    }
+
+    #[tokio::test]
+    async fn open_options_are_respected() {
+        let fs = BackendFileSystem::new(KeyValueBackend::default());
+        fs.backend()
+            .write(Path::new("/file.txt"), 0, b"data")
+            .unwrap();
+
+        assert_eq!(
+            fs.new_open_options()
+                .read(true)
+                .open("/missing.txt")
+                .unwrap_err(),
+            FsError::EntryNotFound
+        );
+        assert_eq!(
+            fs.new_open_options()
+                .write(true)
+                .create_new(true)
+                .open("/file.txt")
+                .unwrap_err(),
+            FsError::AlreadyExists
+        );
+        assert_eq!(
+            fs.new_open_options().read(true).open("/").unwrap_err(),
+            FsError::NotAFile
+        );
+
+        let mut f = fs.new_open_options().read(true).open("/file.txt").unwrap();
+        assert!(f.write_all(b"nope").await.is_err());
+
+        fs.new_open_options()
+            .write(true)
+            .truncate(true)
+            .open("/file.txt")
+            .unwrap();
+        assert_eq!(fs.metadata(Path::new("/file.txt")).unwrap().len, 0);
+
+        assert_eq!(
+            fs.create_dir(Path::new("/dir")).unwrap_err(),
+            FsError::PermissionDenied
+        );
+    }
+
+    #[tokio::test]
+    async fn mount_a_backend() {
+        let root = mem_fs::FileSystem::default();
+        let backend = BackendFileSystem::new(KeyValueBackend::default());
+        backend
+            .backend()
+            .write(Path::new("/file.txt"), 0, b"from the backend")
+            .unwrap();
+        FileSystem::mount(&root, "kv".to_string(), Path::new("/kv"), Box::new(backend)).unwrap();
+
+        let mut f = root
+            .new_open_options()
+            .read(true)
+            .open("/kv/file.txt")
+            .unwrap();
+        let mut contents = String::new();
+        f.read_to_string(&mut contents).await.unwrap();
+        assert_eq!(contents, "from the backend");
+    }
+}
@@ -0,0 +1,300 @@
+//! A write-only file whose output is buffered in memory until the host
+//! collects it, which is handy for capturing `stdout` and `stderr`.
+//!
+//! The buffer is bounded, once it is full any further writes wait until the
+//! host drains it through the [`CapturedOutput`] handle.
+
+use std::collections::VecDeque;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+use crate::VirtualFile;
+
+#[derive(Debug)]
+struct CaptureState {
+    data: VecDeque<u8>,
+    capacity: usize,
+    /// The [`CaptureFile`] was dropped, nothing more will be written.
+    closed: bool,
+    /// The [`CapturedOutput`] was dropped, nothing more will be read.
+    detached: bool,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+impl CaptureState {
+    fn wake_reader(&mut self) {
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_writer(&mut self) {
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The guest facing end of a capture, see [`CaptureFile::new`].
+#[derive(Debug)]
+pub struct CaptureFile {
+    state: Arc<Mutex<CaptureState>>,
+}
+
+/// The host facing end of a capture, gives access to everything that was
+/// written to the matching [`CaptureFile`].
+#[derive(Debug)]
+pub struct CapturedOutput {
+    state: Arc<Mutex<CaptureState>>,
+}
+
+impl CaptureFile {
+    /// Creates a capture that buffers up to `capacity` bytes.
+    ///
+    /// Writes that do not fit in the buffer block until the data is drained
+    /// through the returned [`CapturedOutput`]. Use `usize::MAX` to buffer
+    /// everything.
+    pub fn new(capacity: usize) -> (CaptureFile, CapturedOutput) {
+        let state = Arc::new(Mutex::new(CaptureState {
+            data: VecDeque::new(),
+            capacity: capacity.max(1),
+            closed: false,
+            detached: false,
+            read_waker: None,
+            write_waker: None,
+        }));
+        (
+            CaptureFile {
+                state: state.clone(),
+            },
+            CapturedOutput { state },
+        )
+    }
+}
+
+impl Drop for CaptureFile {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.wake_reader();
+    }
+}
+
+impl AsyncWrite for CaptureFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut state = self.state.lock().unwrap();
+        if state.detached {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let available = state.capacity.saturating_sub(state.data.len());
+        if available == 0 {
+            state.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let amt = available.min(buf.len());
+        state.data.extend(&buf[..amt]);
+        state.wake_reader();
+        Poll::Ready(Ok(amt))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for CaptureFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for CaptureFile {
+    fn start_seek(self: Pin<&mut Self>, _position: SeekFrom) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(0))
+    }
+}
+
+impl VirtualFile for CaptureFile {
+    fn last_accessed(&self) -> u64 {
+        0
+    }
+
+    fn last_modified(&self) -> u64 {
+        0
+    }
+
+    fn created_time(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        0
+    }
+
+    fn set_len(&mut self, _new_size: u64) -> crate::Result<()> {
+        Ok(())
+    }
+
+    fn unlink(&mut self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    fn is_open(&self) -> bool {
+        !self.state.lock().unwrap().detached
+    }
+
+    fn poll_read_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
+
+    fn poll_write_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        let mut state = self.state.lock().unwrap();
+        if state.detached {
+            return Poll::Ready(Ok(0));
+        }
+        match state.capacity.saturating_sub(state.data.len()) {
+            0 => {
+                state.write_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            available => Poll::Ready(Ok(available)),
+        }
+    }
+}
+
+impl CapturedOutput {
+    /// Removes and returns everything that has been written so far.
+    pub fn take_output(&self) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap();
+        let data = state.data.drain(..).collect();
+        state.wake_writer();
+        data
+    }
+
+    /// The number of bytes that are waiting to be taken.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` once the [`CaptureFile`] has been dropped, after which
+    /// no more output will arrive.
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+}
+
+impl Drop for CapturedOutput {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.detached = true;
+        state.data.clear();
+        state.wake_writer();
+    }
+}
+
+impl AsyncRead for CapturedOutput {
+    /// Streams the output as it is written, reaching the end once the
+    /// [`CaptureFile`] has been dropped.
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let mut state = self.state.lock().unwrap();
+        if state.data.is_empty() {
+            if !state.closed {
+                state.read_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            return Poll::Ready(Ok(()));
+        }
+
+        let amt = buf.remaining().min(state.data.len());
+        let (front, back) = state.data.as_slices();
+        if amt <= front.len() {
+            buf.put_slice(&front[..amt]);
+        } else {
+            buf.put_slice(front);
+            buf.put_slice(&back[..amt - front.len()]);
+        }
+        state.data.drain(..amt);
+        state.wake_writer();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn take_the_captured_output() {
+        let (mut file, output) = CaptureFile::new(usize::MAX);
+
+        file.write_all(b"Hello, ").await.unwrap();
+        file.write_all(b"World!").await.unwrap();
+
+        assert_eq!(output.len(), 13);
+        assert_eq!(output.take_output(), b"Hello, World!");
+        assert!(output.is_empty());
+        assert!(!output.is_closed());
+
+        drop(file);
+        assert!(output.is_closed());
+    }
+
+    #[tokio::test]
+    async fn writes_wait_for_the_buffer_to_drain() {
+        let (mut file, mut output) = CaptureFile::new(4);
+
+        let writer = tokio::spawn(async move {
+            file.write_all(b"0123456789").await.unwrap();
+        });
+
+        let mut data = Vec::new();
+        output.read_to_end(&mut data).await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(data, b"0123456789");
+    }
+
+    #[tokio::test]
+    async fn writes_fail_once_the_output_is_dropped() {
+        let (mut file, output) = CaptureFile::new(4);
+        drop(output);
+
+        let err = file.write_all(b"lost").await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
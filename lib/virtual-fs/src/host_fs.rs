@@ -7,8 +7,15 @@ use futures::future::BoxFuture;
 #[cfg(feature = "enable-serde")]
 use serde::{de, Deserialize, Serialize};
 use std::convert::TryInto;
+#[cfg(target_os = "linux")]
+use std::ffi::CString;
 use std::fs;
 use std::io::{self, Seek};
+#[cfg(target_os = "linux")]
+use std::os::unix::{
+    ffi::OsStrExt,
+    io::{AsRawFd, FromRawFd},
+};
 use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
@@ -24,8 +31,14 @@ pub struct FileSystem {
     #[cfg_attr(feature = "enable-serde", serde(skip, default = "default_handle"))]
     handle: Handle,
     root: PathBuf,
+    #[cfg_attr(feature = "enable-serde", serde(default))]
+    resolve_beneath: bool,
 }
 
+/// The maximum number of symlinks that are followed while resolving a path
+/// beneath the root, matching `MAXSYMLINKS` on Linux.
+const MAX_SYMLINKS: usize = 40;
+
 #[allow(dead_code)]
 fn default_handle() -> Handle {
     Handle::current()
@@ -71,11 +84,97 @@ impl FileSystem {
     pub fn new(handle: Handle, root: impl Into<PathBuf>) -> Result<Self> {
         let root = canonicalize(&root.into())?;
 
-        Ok(FileSystem { handle, root })
+        Ok(FileSystem {
+            handle,
+            root,
+            resolve_beneath: false,
+        })
+    }
+
+    /// Confines the resolution of paths to the root of the file system.
+    ///
+    /// Symlinks are still followed as long as they stay beneath the root,
+    /// anything that would lead outside of it (an absolute symlink or a `..`
+    /// past the root) fails with [`FsError::PermissionDenied`].
+    ///
+    /// On Linux files are opened with `openat2(RESOLVE_BENEATH)` so the check
+    /// can not be raced, and entries are created, removed and renamed with
+    /// the `*at` functions in their parent directory opened the same way. On
+    /// other platforms, or kernels without `openat2`, the path is resolved in
+    /// userspace before it is handed to the host.
+    pub fn with_resolve_beneath(mut self, resolve_beneath: bool) -> Self {
+        self.resolve_beneath = resolve_beneath;
+        self
     }
 }
 
 impl FileSystem {
+    /// Maps a path inside this file system to the path on the host, see
+    /// [`FileSystem::with_resolve_beneath`].
+    ///
+    /// When `follow_last` is false the last component is not resolved if it
+    /// is a symlink, like the `lstat` family of functions.
+    fn host_path(&self, path: &Path, follow_last: bool) -> Result<PathBuf> {
+        if self.resolve_beneath {
+            self.resolve_beneath(path, follow_last)
+        } else {
+            Ok(self.prepare_path(path))
+        }
+    }
+
+    /// Strips the root (or a leading `/`) from the path.
+    fn relative_path<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root)
+            .or_else(|_| path.strip_prefix("/"))
+            .unwrap_or(path)
+    }
+
+    fn resolve_beneath(&self, path: &Path, follow_last: bool) -> Result<PathBuf> {
+        let mut pending: Vec<_> = self
+            .relative_path(path)
+            .components()
+            .filter(|c| !matches!(c, Component::RootDir | Component::Prefix(..)))
+            .rev()
+            .map(|c| c.as_os_str().to_owned())
+            .collect();
+        let mut resolved = PathBuf::new();
+        let mut symlinks = 0;
+
+        while let Some(component) = pending.pop() {
+            if component == "." {
+                continue;
+            }
+            if component == ".." {
+                if !resolved.pop() {
+                    return Err(FsError::PermissionDenied);
+                }
+                continue;
+            }
+
+            let candidate = self.root.join(&resolved).join(&component);
+            let follow = follow_last || !pending.is_empty();
+            match fs::symlink_metadata(&candidate) {
+                Ok(metadata) if follow && metadata.file_type().is_symlink() => {
+                    symlinks += 1;
+                    if symlinks > MAX_SYMLINKS {
+                        return Err(FsError::InvalidInput);
+                    }
+                    let target = fs::read_link(&candidate)?;
+                    if target
+                        .components()
+                        .any(|c| matches!(c, Component::RootDir | Component::Prefix(..)))
+                    {
+                        return Err(FsError::PermissionDenied);
+                    }
+                    pending.extend(target.components().rev().map(|c| c.as_os_str().to_owned()));
+                }
+                _ => resolved.push(component),
+            }
+        }
+
+        Ok(self.root.join(resolved))
+    }
+
     fn prepare_path(&self, path: &Path) -> PathBuf {
         let path = normalize_path(path);
 
@@ -94,13 +193,13 @@ impl FileSystem {
 
 impl crate::FileSystem for FileSystem {
     fn readlink(&self, path: &Path) -> Result<PathBuf> {
-        let path = self.prepare_path(path);
+        let path = self.host_path(path, false)?;
 
         fs::read_link(path).map_err(Into::into)
     }
 
     fn read_dir(&self, path: &Path) -> Result<ReadDir> {
-        let path = self.prepare_path(path);
+        let path = self.host_path(path, true)?;
 
         let read_dir = fs::read_dir(path)?;
         let mut data = read_dir
@@ -128,7 +227,14 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn create_dir(&self, path: &Path) -> Result<()> {
-        let path = self.prepare_path(path);
+        #[cfg(target_os = "linux")]
+        if let Some(parent) = self.parent_beneath(path) {
+            let (parent, name) = parent?;
+            // SAFETY: the name is a valid C string for the duration of the call
+            return at_result(unsafe { libc::mkdirat(parent.as_raw_fd(), name.as_ptr(), 0o777) });
+        }
+
+        let path = self.host_path(path, false)?;
 
         if path.parent().is_none() {
             return Err(FsError::BaseNotDirectory);
@@ -138,7 +244,16 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn remove_dir(&self, path: &Path) -> Result<()> {
-        let path = self.prepare_path(path);
+        #[cfg(target_os = "linux")]
+        if let Some(parent) = self.parent_beneath(path) {
+            let (parent, name) = parent?;
+            // SAFETY: the name is a valid C string for the duration of the call
+            return at_result(unsafe {
+                libc::unlinkat(parent.as_raw_fd(), name.as_ptr(), libc::AT_REMOVEDIR)
+            });
+        }
+
+        let path = self.host_path(path, false)?;
 
         if path.parent().is_none() {
             return Err(FsError::BaseNotDirectory);
@@ -165,8 +280,13 @@ impl crate::FileSystem for FileSystem {
                 return Err(FsError::BaseNotDirectory);
             }
 
-            let from = self.prepare_path(from);
-            let to = self.prepare_path(to);
+            #[cfg(target_os = "linux")]
+            if let Some(result) = self.rename_beneath(from, to) {
+                return result;
+            }
+
+            let from = self.host_path(from, false)?;
+            let to = self.host_path(to, false)?;
 
             if !from.exists() {
                 return Err(FsError::EntryNotFound);
@@ -207,7 +327,14 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn remove_file(&self, path: &Path) -> Result<()> {
-        let path = self.prepare_path(path);
+        #[cfg(target_os = "linux")]
+        if let Some(parent) = self.parent_beneath(path) {
+            let (parent, name) = parent?;
+            // SAFETY: the name is a valid C string for the duration of the call
+            return at_result(unsafe { libc::unlinkat(parent.as_raw_fd(), name.as_ptr(), 0) });
+        }
+
+        let path = self.host_path(path, false)?;
 
         if path.parent().is_none() {
             return Err(FsError::BaseNotDirectory);
@@ -221,7 +348,7 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn metadata(&self, path: &Path) -> Result<Metadata> {
-        let path = self.prepare_path(path);
+        let path = self.host_path(path, true)?;
 
         fs::metadata(path)
             .and_then(TryInto::try_into)
@@ -229,7 +356,7 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn symlink_metadata(&self, path: &Path) -> Result<Metadata> {
-        let path = self.prepare_path(path);
+        let path = self.host_path(path, false)?;
 
         fs::symlink_metadata(path)
             .and_then(TryInto::try_into)
@@ -310,7 +437,7 @@ impl crate::FileOpener for FileSystem {
         path: &Path,
         conf: &OpenOptionsConfig,
     ) -> Result<Box<dyn VirtualFile + Send + Sync + 'static>> {
-        let path = self.prepare_path(path);
+        let host_path = self.prepare_path(path);
 
         // TODO: handle create implying write, etc.
         let read = conf.read();
@@ -329,22 +456,211 @@ impl crate::FileOpener for FileSystem {
             .create_new(conf.create_new())
             .create(conf.create())
             .append(append)
-            .truncate(conf.truncate())
-            .open(&path)
-            .map_err(Into::into)
-            .map(|file| {
-                Box::new(File::new(
-                    self.handle.clone(),
-                    file,
-                    path.to_owned(),
-                    read,
-                    write,
-                    append,
-                )) as Box<dyn VirtualFile + Send + Sync + 'static>
-            })
+            .truncate(conf.truncate());
+
+        let file = if self.resolve_beneath {
+            self.open_beneath(path, &oo, conf, append)?
+        } else {
+            oo.open(&host_path)?
+        };
+
+        Ok(Box::new(File::new(
+            self.handle.clone(),
+            file,
+            host_path,
+            read,
+            write,
+            append,
+        )))
+    }
+}
+
+impl FileSystem {
+    fn open_beneath(
+        &self,
+        path: &Path,
+        options: &fs::OpenOptions,
+        conf: &OpenOptionsConfig,
+        append: bool,
+    ) -> Result<fs::File> {
+        #[cfg(target_os = "linux")]
+        match openat2_beneath(&self.root, self.relative_path(path), conf, append) {
+            // Kernels older than 5.6 do not have `openat2`
+            Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => {}
+            Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+                return Err(FsError::PermissionDenied)
+            }
+            result => return result.map_err(Into::into),
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = (conf, append);
+
+        let path = self.resolve_beneath(path, true)?;
+        options.open(path).map_err(Into::into)
+    }
+
+    /// Opens the parent directory of `path` with `openat2(RESOLVE_BENEATH)`
+    /// and returns it with the name of the last component, so that the entry
+    /// can be modified with the `*at` functions without resolving its path
+    /// again.
+    ///
+    /// Returns `None` when paths aren't resolved beneath the root or the
+    /// kernel doesn't have `openat2`.
+    #[cfg(target_os = "linux")]
+    fn parent_beneath(&self, path: &Path) -> Option<Result<(fs::File, CString)>> {
+        if !self.resolve_beneath {
+            return None;
+        }
+        let path = self.relative_path(path);
+        let name = match path.file_name() {
+            Some(name) => name,
+            None => return Some(Err(FsError::BaseNotDirectory)),
+        };
+        let name = match CString::new(name.as_bytes()) {
+            Ok(name) => name,
+            Err(_) => return Some(Err(FsError::InvalidInput)),
+        };
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+        let flags = libc::O_PATH | libc::O_DIRECTORY | libc::O_CLOEXEC;
+        match openat2(&self.root, parent, flags, 0) {
+            Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => None,
+            Err(e) if e.raw_os_error() == Some(libc::EXDEV) => Some(Err(FsError::PermissionDenied)),
+            result => Some(result.map(|parent| (parent, name)).map_err(Into::into)),
+        }
+    }
+
+    /// Renames `from` to `to` with `renameat`, see
+    /// [`FileSystem::parent_beneath`].
+    #[cfg(target_os = "linux")]
+    fn rename_beneath(&self, from: &Path, to: &Path) -> Option<Result<()>> {
+        let (from_parent, from_name) = match self.parent_beneath(from)? {
+            Ok(parent) => parent,
+            Err(e) => return Some(Err(e)),
+        };
+        let (to_parent, to_name) = match self.parent_beneath(to)? {
+            Ok(parent) => parent,
+            Err(e) => return Some(Err(e)),
+        };
+        // SAFETY: the names are valid C strings for the duration of the call
+        let result = at_result(unsafe {
+            libc::renameat(
+                from_parent.as_raw_fd(),
+                from_name.as_ptr(),
+                to_parent.as_raw_fd(),
+                to_name.as_ptr(),
+            )
+        });
+        if result.is_ok() {
+            let now = libc::timespec {
+                tv_sec: 0,
+                tv_nsec: libc::UTIME_NOW,
+            };
+            let omit = libc::timespec {
+                tv_sec: 0,
+                tv_nsec: libc::UTIME_OMIT,
+            };
+            // SAFETY: the name and the times are valid for the duration of the call
+            unsafe {
+                libc::utimensat(
+                    to_parent.as_raw_fd(),
+                    to_name.as_ptr(),
+                    [omit, now].as_ptr(),
+                    libc::AT_SYMLINK_NOFOLLOW,
+                )
+            };
+        }
+        Some(result)
     }
 }
 
+/// Converts the return value of a `*at` function into a result.
+#[cfg(target_os = "linux")]
+fn at_result(ret: libc::c_int) -> Result<()> {
+    if ret == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    Err(match err.raw_os_error() {
+        Some(libc::ENOTEMPTY) => FsError::DirectoryNotEmpty,
+        _ => err.into(),
+    })
+}
+
+/// Opens `path` relative to `root` with `RESOLVE_BENEATH`, which makes the
+/// kernel fail with `EXDEV` if resolving the path would leave `root`.
+#[cfg(target_os = "linux")]
+fn openat2_beneath(
+    root: &Path,
+    path: &Path,
+    conf: &OpenOptionsConfig,
+    append: bool,
+) -> io::Result<fs::File> {
+    let mut flags = libc::O_CLOEXEC;
+    flags |= match (conf.read(), conf.write() || append) {
+        (true, true) => libc::O_RDWR,
+        (false, true) => libc::O_WRONLY,
+        (_, false) => libc::O_RDONLY,
+    };
+    if conf.create_new() {
+        flags |= libc::O_CREAT | libc::O_EXCL;
+    } else if conf.create() {
+        flags |= libc::O_CREAT;
+    }
+    if conf.truncate() {
+        flags |= libc::O_TRUNC;
+    }
+    if append {
+        flags |= libc::O_APPEND;
+    }
+
+    // the mode must be zero unless a file may be created
+    let mode = if flags & libc::O_CREAT != 0 { 0o666 } else { 0 };
+    openat2(root, path, flags, mode)
+}
+
+/// Opens `path` relative to `root` with `openat2(RESOLVE_BENEATH)`.
+#[cfg(target_os = "linux")]
+fn openat2(root: &Path, path: &Path, flags: libc::c_int, mode: u64) -> io::Result<fs::File> {
+    // `struct open_how` from `linux/openat2.h`
+    #[repr(C)]
+    struct OpenHow {
+        flags: u64,
+        mode: u64,
+        resolve: u64,
+    }
+
+    let how = OpenHow {
+        flags: flags as u64,
+        mode,
+        resolve: libc::RESOLVE_BENEATH | libc::RESOLVE_NO_MAGICLINKS,
+    };
+
+    let root = fs::File::open(root)?;
+    let path = if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    };
+    let path = CString::new(path.as_os_str().as_bytes())?;
+
+    // SAFETY: all the pointers are valid for the duration of the call
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_openat2,
+            root.as_raw_fd(),
+            path.as_ptr(),
+            &how as *const OpenHow,
+            std::mem::size_of::<OpenHow>(),
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: the kernel handed us a new file descriptor that nobody else owns
+    Ok(unsafe { fs::File::from_raw_fd(fd as libc::c_int) })
+}
+
 /// A thin wrapper around `std::fs::File`
 #[derive(Debug)]
 #[cfg_attr(feature = "enable-serde", derive(Serialize))]
@@ -1484,4 +1800,148 @@ mod tests {
             panic!("next: {:?}", s);
         }
     }
+
+    /// Plants symlinks that escape the root of the file system in `root`.
+    #[cfg(unix)]
+    fn plant_symlinks(temp: &TempDir) -> std::path::PathBuf {
+        use std::os::unix::fs::symlink;
+
+        let root = temp.path().join("root");
+        let outside = temp.path().join("outside");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("secret.txt"), b"secret").unwrap();
+        std::fs::write(root.join("sub").join("file.txt"), b"file").unwrap();
+
+        symlink("sub/file.txt", root.join("inside")).unwrap();
+        symlink("../outside/secret.txt", root.join("relative")).unwrap();
+        symlink(outside.join("secret.txt"), root.join("absolute")).unwrap();
+        symlink("../outside", root.join("dir")).unwrap();
+        symlink("../../outside/secret.txt", root.join("sub").join("nested")).unwrap();
+        root
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlinks_escape_the_root() {
+        let temp = TempDir::new().unwrap();
+        let root = plant_symlinks(&temp);
+        let fs = FileSystem::new(Handle::current(), root).unwrap();
+
+        for path in ["/inside", "/relative", "/absolute", "/dir/secret.txt"] {
+            assert!(
+                fs.new_open_options().read(true).open(path).is_ok(),
+                "{path}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resolve_beneath() {
+        let temp = TempDir::new().unwrap();
+        let root = plant_symlinks(&temp);
+        let fs = FileSystem::new(Handle::current(), root)
+            .unwrap()
+            .with_resolve_beneath(true);
+
+        assert!(fs.new_open_options().read(true).open("/inside").is_ok());
+        assert!(fs.metadata(Path::new("/inside")).unwrap().is_file());
+
+        for path in [
+            "/relative",
+            "/absolute",
+            "/dir/secret.txt",
+            "/sub/nested",
+            "/../outside/secret.txt",
+        ] {
+            assert_eq!(
+                fs.new_open_options().read(true).open(path).unwrap_err(),
+                FsError::PermissionDenied,
+                "{path}",
+            );
+            assert_eq!(
+                fs.metadata(Path::new(path)).unwrap_err(),
+                FsError::PermissionDenied,
+                "{path}",
+            );
+        }
+        assert_eq!(
+            fs.read_dir(Path::new("/dir")).unwrap_err(),
+            FsError::PermissionDenied,
+        );
+        assert_eq!(
+            fs.new_open_options()
+                .write(true)
+                .create(true)
+                .open("/dir/planted.txt")
+                .unwrap_err(),
+            FsError::PermissionDenied,
+        );
+
+        // The symlinks themselves can still be inspected
+        assert!(fs.symlink_metadata(Path::new("/absolute")).is_ok());
+        assert!(fs.readlink(Path::new("/relative")).is_ok());
+
+        // Entries can't be created, removed or renamed through them either
+        assert_eq!(
+            fs.create_dir(Path::new("/dir/planted")).unwrap_err(),
+            FsError::PermissionDenied,
+        );
+        assert_eq!(
+            fs.remove_file(Path::new("/dir/secret.txt")).unwrap_err(),
+            FsError::PermissionDenied,
+        );
+        assert_eq!(
+            fs.rename(Path::new("/dir/secret.txt"), Path::new("/stolen.txt"))
+                .await
+                .unwrap_err(),
+            FsError::PermissionDenied,
+        );
+        assert!(temp.path().join("outside/secret.txt").exists());
+
+        // but they can be beneath the root
+        fs.create_dir(Path::new("/sub/new")).unwrap();
+        fs.rename(Path::new("/sub/file.txt"), Path::new("/sub/new/file.txt"))
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.remove_dir(Path::new("/sub/new")).unwrap_err(),
+            FsError::DirectoryNotEmpty,
+        );
+        fs.remove_file(Path::new("/sub/new/file.txt")).unwrap();
+        fs.remove_dir(Path::new("/sub/new")).unwrap();
+        assert_eq!(read_dir_names(&fs, "/sub"), vec!["nested"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resolve_beneath_in_userspace() {
+        let temp = TempDir::new().unwrap();
+        let root = plant_symlinks(&temp);
+        let fs = FileSystem::new(Handle::current(), root)
+            .unwrap()
+            .with_resolve_beneath(true);
+
+        assert_eq!(
+            fs.resolve_beneath(Path::new("/inside"), true).unwrap(),
+            fs.root.join("sub/file.txt"),
+        );
+        assert_eq!(
+            fs.resolve_beneath(Path::new("/relative"), false).unwrap(),
+            fs.root.join("relative"),
+        );
+        assert_eq!(
+            fs.resolve_beneath(Path::new("/sub/../new.txt"), true)
+                .unwrap(),
+            fs.root.join("new.txt"),
+        );
+        for path in ["/relative", "/absolute", "/dir/secret.txt", "/sub/nested"] {
+            assert_eq!(
+                fs.resolve_beneath(Path::new(path), true).unwrap_err(),
+                FsError::PermissionDenied,
+                "{path}",
+            );
+        }
+    }
 }
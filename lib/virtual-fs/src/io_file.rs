@@ -0,0 +1,207 @@
+//! Adapters that turn plain [`std::io::Read`] and [`std::io::Write`]
+//! implementations into [`VirtualFile`]s.
+//!
+//! The wrapped reader or writer is called synchronously, so a read or write
+//! that blocks will block the thread of the caller.
+
+use std::io::{self, Read, SeekFrom, Write};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use derivative::Derivative;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+use crate::VirtualFile;
+
+/// A read-only [`VirtualFile`] backed by a [`std::io::Read`].
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ReaderFile {
+    #[derivative(Debug = "ignore")]
+    inner: Mutex<Box<dyn Read + Send + 'static>>,
+}
+
+impl ReaderFile {
+    pub fn new(reader: impl Read + Send + 'static) -> Self {
+        Self {
+            inner: Mutex::new(Box::new(reader)),
+        }
+    }
+}
+
+impl AsyncRead for ReaderFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let reader = self.get_mut().inner.get_mut().unwrap();
+        let amt = reader.read(buf.initialize_unfilled())?;
+        buf.advance(amt);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for ReaderFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for ReaderFile {
+    fn start_seek(self: Pin<&mut Self>, _position: SeekFrom) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(0))
+    }
+}
+
+impl VirtualFile for ReaderFile {
+    fn last_accessed(&self) -> u64 {
+        0
+    }
+
+    fn last_modified(&self) -> u64 {
+        0
+    }
+
+    fn created_time(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        0
+    }
+
+    fn set_len(&mut self, _new_size: u64) -> crate::Result<()> {
+        Err(crate::FsError::PermissionDenied)
+    }
+
+    fn unlink(&mut self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    /// A reader can not tell whether data is available, so it is always
+    /// reported as ready and reading may block.
+    fn poll_read_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(1))
+    }
+
+    fn poll_write_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
+}
+
+/// A write-only [`VirtualFile`] backed by a [`std::io::Write`].
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct WriterFile {
+    #[derivative(Debug = "ignore")]
+    inner: Mutex<Box<dyn Write + Send + 'static>>,
+}
+
+impl WriterFile {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            inner: Mutex::new(Box::new(writer)),
+        }
+    }
+}
+
+impl AsyncWrite for WriterFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let writer = self.get_mut().inner.get_mut().unwrap();
+        Poll::Ready(writer.write(buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let writer = self.get_mut().inner.get_mut().unwrap();
+        Poll::Ready(writer.write_vectored(bufs))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let writer = self.get_mut().inner.get_mut().unwrap();
+        Poll::Ready(writer.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncRead for WriterFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        _buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Err(io::ErrorKind::PermissionDenied.into()))
+    }
+}
+
+impl AsyncSeek for WriterFile {
+    fn start_seek(self: Pin<&mut Self>, _position: SeekFrom) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(0))
+    }
+}
+
+impl VirtualFile for WriterFile {
+    fn last_accessed(&self) -> u64 {
+        0
+    }
+
+    fn last_modified(&self) -> u64 {
+        0
+    }
+
+    fn created_time(&self) -> u64 {
+        0
+    }
+
+    fn size(&self) -> u64 {
+        0
+    }
+
+    fn set_len(&mut self, _new_size: u64) -> crate::Result<()> {
+        Ok(())
+    }
+
+    fn unlink(&mut self) -> crate::Result<()> {
+        Ok(())
+    }
+
+    fn poll_read_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
+
+    fn poll_write_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(8192))
+    }
+}
@@ -19,14 +19,19 @@ use thiserror::Error;
 pub mod arc_box_file;
 pub mod arc_file;
 pub mod arc_fs;
+#[cfg(feature = "archive-fs")]
+pub mod archive_fs;
+mod backend_fs;
 pub mod buffer_file;
 pub mod builder;
+pub mod capture_file;
 pub mod combine_file;
 pub mod cow_file;
 pub mod dual_write_file;
 pub mod empty_fs;
 #[cfg(feature = "host-fs")]
 pub mod host_fs;
+pub mod io_file;
 pub mod mem_fs;
 pub mod null_file;
 pub mod passthru_fs;
@@ -40,6 +45,7 @@ mod filesystems;
 pub(crate) mod ops;
 mod overlay_fs;
 pub mod pipe;
+mod staged_fs;
 mod static_file;
 #[cfg(feature = "static-fs")]
 pub mod static_fs;
@@ -52,18 +58,24 @@ pub mod limiter;
 pub use arc_box_file::*;
 pub use arc_file::*;
 pub use arc_fs::*;
+#[cfg(feature = "archive-fs")]
+pub use archive_fs::{ArchiveFileSystem, ArchiveFormat};
+pub use backend_fs::{BackendFileSystem, FsBackend};
 pub use buffer_file::*;
 pub use builder::*;
+pub use capture_file::*;
 pub use combine_file::*;
 pub use cow_file::*;
 pub use dual_write_file::*;
 pub use empty_fs::*;
 pub use filesystems::FileSystems;
+pub use io_file::*;
 pub use null_file::*;
 pub use overlay_fs::OverlayFileSystem;
 pub use passthru_fs::*;
 pub use pipe::*;
 pub use special_file::*;
+pub use staged_fs::{StagedChange, StagedFileSystem};
 pub use static_file::StaticFile;
 pub use tmp_fs::*;
 pub use trace_fs::TraceFileSystem;
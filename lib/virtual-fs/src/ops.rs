@@ -227,6 +227,27 @@ where
     Ok(())
 }
 
+/// Remove a directory and everything inside it.
+///
+/// This is analogous to [`std::fs::remove_dir_all()`].
+pub fn remove_dir_all<F>(fs: &F, path: impl AsRef<Path>) -> Result<(), FsError>
+where
+    F: FileSystem + ?Sized,
+{
+    let path = path.as_ref();
+
+    for entry in fs.read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_dir_all(fs, &entry.path)?;
+        } else {
+            fs.remove_file(&entry.path)?;
+        }
+    }
+
+    fs.remove_dir(path)
+}
+
 /// Recursively iterate over all paths inside a directory, ignoring any
 /// errors that may occur along the way.
 pub fn walk<F>(fs: &F, path: impl AsRef<Path>) -> Box<dyn Iterator<Item = DirEntry> + '_>
@@ -243,6 +243,18 @@ where
             fs.read_dir(path).is_ok() && ops::create_white_out(&self.primary, path).is_ok()
         });
 
+        // The whiteouts for anything that was deleted from the directory are
+        // no longer needed and would keep it from being removed
+        if had_at_least_one_success {
+            if let Ok(entries) = self.primary.read_dir(path) {
+                for entry in entries.flatten() {
+                    if entry.is_white_out().is_some() {
+                        self.primary.remove_file(&entry.path).ok();
+                    }
+                }
+            }
+        }
+
         // Attempt to remove it from the primary, if this succeeds then we may have also
         // added the whiteout file in the earlier step, but are required in this case to
         // properly delete the directory.
@@ -509,6 +521,9 @@ where
                 sub_conf.create_new = false;
                 sub_conf.append = false;
                 sub_conf.truncate = false;
+                // The original contents need to be read when the file gets
+                // copied to the primary
+                sub_conf.read |= require_mutations;
                 match fs.new_open_options().options(sub_conf.clone()).open(path) {
                     Err(e) if should_continue(e) => continue,
                     Ok(file) if require_mutations => {
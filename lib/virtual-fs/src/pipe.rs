@@ -5,8 +5,8 @@ use std::io::IoSlice;
 use std::io::{self, Read, Seek, SeekFrom};
 use std::ops::DerefMut;
 use std::pin::Pin;
-use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::{Arc, Weak};
 use std::task::Context;
 use std::task::Poll;
 use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
@@ -35,7 +35,30 @@ pub struct PipeRx {
     rx: Arc<Mutex<PipeReceiver>>,
 }
 
+/// A handle on the receiving end of a pipe that doesn't keep it open.
+#[derive(Debug, Clone)]
+pub struct WeakPipeRx {
+    rx: Weak<Mutex<PipeReceiver>>,
+}
+
+impl WeakPipeRx {
+    /// Returns the number of writes that haven't been read yet, or `None`
+    /// if the receiving end has been dropped.
+    pub fn pending(&self) -> Option<usize> {
+        let rx = self.rx.upgrade()?;
+        let rx = rx.lock().unwrap();
+        Some(rx.chan.len())
+    }
+}
+
 impl PipeRx {
+    /// Creates a handle on this end of the pipe that doesn't keep it open.
+    pub fn downgrade(&self) -> WeakPipeRx {
+        WeakPipeRx {
+            rx: Arc::downgrade(&self.rx),
+        }
+    }
+
     fn try_read(&mut self, buf: &mut [u8]) -> Option<usize> {
         let max_size = buf.len();
 
@@ -144,6 +167,11 @@ impl PipeTx {
             std::mem::swap(guard.deref_mut(), &mut null_tx);
         }
     }
+
+    /// Returns true if the receiving end of the pipe has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.tx.lock().unwrap().is_closed()
+    }
 }
 
 impl Seek for Pipe {
@@ -0,0 +1,294 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::future::BoxFuture;
+
+use crate::{mem_fs, ops, FileSystem, FsError, Metadata, OpenOptions, OverlayFileSystem, ReadDir};
+
+type DynFileSystem = Arc<dyn FileSystem + Send + Sync>;
+
+/// A [`FileSystem`] which keeps every modification in memory instead of
+/// applying it to the filesystem it wraps.
+///
+/// Reads fall through to the underlying filesystem until a file is modified,
+/// at which point it is copied into memory. Once the work is done, the
+/// staged modifications can be inspected with [`StagedFileSystem::changes()`]
+/// and then either applied with [`StagedFileSystem::commit()`] or thrown away
+/// with [`StagedFileSystem::discard()`].
+///
+/// Cloning a [`StagedFileSystem`] is cheap and all clones share the same
+/// staged modifications.
+#[derive(Debug, Clone)]
+pub struct StagedFileSystem {
+    base: DynFileSystem,
+    staging: mem_fs::FileSystem,
+    overlay: Arc<OverlayFileSystem<mem_fs::FileSystem, [DynFileSystem; 1]>>,
+}
+
+/// A modification recorded by a [`StagedFileSystem`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StagedChange {
+    /// A directory was created.
+    CreateDir(PathBuf),
+    /// A file was created or its contents were modified.
+    WriteFile(PathBuf),
+    /// A file or directory (and everything inside it) was removed.
+    Remove(PathBuf),
+}
+
+impl StagedChange {
+    pub fn path(&self) -> &Path {
+        match self {
+            StagedChange::CreateDir(path)
+            | StagedChange::WriteFile(path)
+            | StagedChange::Remove(path) => path,
+        }
+    }
+}
+
+impl StagedFileSystem {
+    pub fn new(base: DynFileSystem) -> Self {
+        let staging = mem_fs::FileSystem::default();
+        let overlay = OverlayFileSystem::new(staging.clone(), [base.clone()]);
+
+        StagedFileSystem {
+            base,
+            staging,
+            overlay: Arc::new(overlay),
+        }
+    }
+
+    /// The filesystem that modifications are committed to.
+    pub fn base(&self) -> &DynFileSystem {
+        &self.base
+    }
+
+    /// List the modifications that have been staged so far.
+    ///
+    /// Parent directories are always listed before their contents.
+    pub fn changes(&self) -> Vec<StagedChange> {
+        let mut changes = Vec::new();
+
+        for entry in ops::walk(&self.staging, "/") {
+            if let Some(removed) = ops::is_white_out(&entry.path) {
+                changes.push(StagedChange::Remove(removed));
+                continue;
+            }
+
+            match entry.file_type() {
+                Ok(ty) if ty.is_dir() => {
+                    // Directories are also created when a file deeper in the
+                    // tree gets copied into memory
+                    if !ops::is_dir(&self.base, &entry.path) {
+                        changes.push(StagedChange::CreateDir(entry.path));
+                    }
+                }
+                Ok(_) => changes.push(StagedChange::WriteFile(entry.path)),
+                Err(_) => {}
+            }
+        }
+
+        changes
+    }
+
+    /// Apply all staged modifications to the underlying filesystem.
+    ///
+    /// Modifications are removed from the staging area once they have been
+    /// committed, so a failed commit can be retried.
+    pub async fn commit(&self) -> Result<(), FsError> {
+        for change in self.changes() {
+            match &change {
+                StagedChange::Remove(path) => {
+                    if ops::is_dir(&self.base, path) {
+                        ops::remove_dir_all(&self.base, path)?;
+                    } else if ops::exists(&self.base, path) {
+                        self.base.remove_file(path)?;
+                    }
+                    ops::remove_white_out(&self.staging, path);
+                }
+                StagedChange::CreateDir(path) => {
+                    if ops::is_file(&self.base, path) {
+                        self.base.remove_file(path)?;
+                    }
+                    self.base.create_dir(path)?;
+                }
+                StagedChange::WriteFile(path) => {
+                    if ops::is_dir(&self.base, path) {
+                        ops::remove_dir_all(&self.base, path)?;
+                    }
+                    let contents = ops::read(&self.staging, path).await?;
+                    ops::write(&self.base, path, contents).await?;
+                    self.staging.remove_file(path)?;
+                }
+            }
+        }
+
+        self.discard()
+    }
+
+    /// Throw away all staged modifications.
+    pub fn discard(&self) -> Result<(), FsError> {
+        for entry in self.staging.read_dir(Path::new("/"))? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                ops::remove_dir_all(&self.staging, &entry.path)?;
+            } else {
+                self.staging.remove_file(&entry.path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FileSystem for StagedFileSystem {
+    fn readlink(&self, path: &Path) -> crate::Result<PathBuf> {
+        self.overlay.readlink(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> crate::Result<ReadDir> {
+        self.overlay.read_dir(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> crate::Result<()> {
+        self.overlay.create_dir(path)
+    }
+
+    fn remove_dir(&self, path: &Path) -> crate::Result<()> {
+        self.overlay.remove_dir(path)
+    }
+
+    fn rename<'a>(&'a self, from: &'a Path, to: &'a Path) -> BoxFuture<'a, crate::Result<()>> {
+        self.overlay.rename(from, to)
+    }
+
+    fn metadata(&self, path: &Path) -> crate::Result<Metadata> {
+        self.overlay.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> crate::Result<Metadata> {
+        self.overlay.symlink_metadata(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> crate::Result<()> {
+        self.overlay.remove_file(path)
+    }
+
+    fn new_open_options(&self) -> OpenOptions {
+        self.overlay.new_open_options()
+    }
+
+    fn mount(
+        &self,
+        name: String,
+        path: &Path,
+        fs: Box<dyn FileSystem + Send + Sync>,
+    ) -> crate::Result<()> {
+        self.overlay.mount(name, path, fs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn staged() -> (mem_fs::FileSystem, StagedFileSystem) {
+        let base = mem_fs::FileSystem::default();
+        let staged = StagedFileSystem::new(Arc::new(base.clone()));
+        (base, staged)
+    }
+
+    #[tokio::test]
+    async fn modifications_do_not_reach_the_base_filesystem() {
+        let (base, staged) = staged();
+        ops::write(&base, "/file.txt", b"original").await.unwrap();
+        ops::write(&base, "/deleted.txt", b"doomed").await.unwrap();
+
+        ops::write(&staged, "/file.txt", b"modified").await.unwrap();
+        ops::create_dir_all(&staged, "/dir/nested").unwrap();
+        ops::write(&staged, "/dir/nested/new.txt", b"new")
+            .await
+            .unwrap();
+        staged.remove_file(Path::new("/deleted.txt")).unwrap();
+
+        assert_eq!(
+            ops::read_to_string(&staged, "/file.txt").await.unwrap(),
+            "modified"
+        );
+        assert!(!ops::exists(&staged, "/deleted.txt"));
+        assert_eq!(
+            ops::read_to_string(&base, "/file.txt").await.unwrap(),
+            "original"
+        );
+        assert!(ops::exists(&base, "/deleted.txt"));
+        assert!(!ops::exists(&base, "/dir"));
+
+        let mut changes = staged.changes();
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        assert_eq!(
+            changes,
+            [
+                StagedChange::Remove("/deleted.txt".into()),
+                StagedChange::CreateDir("/dir".into()),
+                StagedChange::CreateDir("/dir/nested".into()),
+                StagedChange::WriteFile("/dir/nested/new.txt".into()),
+                StagedChange::WriteFile("/file.txt".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn commit_applies_the_changes() {
+        let (base, staged) = staged();
+        ops::write(&base, "/file.txt", b"original").await.unwrap();
+        ops::create_dir_all(&base, "/old/nested").unwrap();
+        ops::touch(&base, "/old/nested/file.txt").unwrap();
+
+        ops::write(&staged, "/file.txt", b"modified").await.unwrap();
+        ops::create_dir_all(&staged, "/dir").unwrap();
+        ops::write(&staged, "/dir/new.txt", b"new").await.unwrap();
+        staged
+            .remove_file(Path::new("/old/nested/file.txt"))
+            .unwrap();
+        staged.remove_dir(Path::new("/old/nested")).unwrap();
+
+        staged.commit().await.unwrap();
+
+        assert_eq!(
+            ops::read_to_string(&base, "/file.txt").await.unwrap(),
+            "modified"
+        );
+        assert_eq!(
+            ops::read_to_string(&base, "/dir/new.txt").await.unwrap(),
+            "new"
+        );
+        assert!(ops::is_dir(&base, "/old"));
+        assert!(!ops::exists(&base, "/old/nested"));
+        assert_eq!(staged.changes(), []);
+        assert_eq!(
+            ops::read_to_string(&staged, "/file.txt").await.unwrap(),
+            "modified"
+        );
+    }
+
+    #[tokio::test]
+    async fn discard_forgets_the_changes() {
+        let (base, staged) = staged();
+        ops::write(&base, "/file.txt", b"original").await.unwrap();
+
+        ops::write(&staged, "/file.txt", b"modified").await.unwrap();
+        ops::write(&staged, "/new.txt", b"new").await.unwrap();
+
+        staged.discard().unwrap();
+
+        assert_eq!(staged.changes(), []);
+        assert_eq!(
+            ops::read_to_string(&staged, "/file.txt").await.unwrap(),
+            "original"
+        );
+        assert!(!ops::exists(&staged, "/new.txt"));
+        assert!(!ops::exists(&base, "/new.txt"));
+    }
+}
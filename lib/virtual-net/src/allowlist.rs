@@ -0,0 +1,1095 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::{
+    DynVirtualNetworking, InterestHandler, IpCidr, IpRoute, NetworkError, Result, SocketStatus,
+    StreamSecurity, VirtualConnectionlessSocket, VirtualIcmpSocket, VirtualIoSource,
+    VirtualNetworking, VirtualRawSocket, VirtualSocket, VirtualTcpListener, VirtualTcpSocket,
+    VirtualUdpSocket,
+};
+
+/// The addresses covered by a [`NetworkRule`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RuleTarget {
+    /// A range of IP addresses.
+    Cidr(IpCidr),
+    /// A host name (in lower case), which covers the addresses that it
+    /// resolves to. A leading `*.` makes it cover every subdomain instead.
+    ///
+    /// Only the lookups done through the networking implementation are
+    /// seen, so denying a host name is best-effort, see
+    /// [`AllowListNetworking`].
+    Host(String),
+}
+
+/// A single entry of a networking allowlist or denylist: a range of IP
+/// addresses or a host name, and optionally the only port it applies to.
+///
+/// Rules are parsed from strings such as `10.0.0.0/8`, `1.1.1.1:53`,
+/// `192.168.0.0/16:443`, `::1`, `[fd00::]/8:80`, `api.example.com:443` or
+/// `*.example.com`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkRule {
+    pub target: RuleTarget,
+    pub port: Option<u16>,
+}
+
+impl NetworkRule {
+    /// Returns true if this rule covers the given IP address, regardless
+    /// of the port.
+    ///
+    /// Host name rules never match an address directly, see
+    /// [`NetworkRule::matches_host`].
+    pub fn matches_ip(&self, ip: IpAddr) -> bool {
+        match &self.target {
+            RuleTarget::Cidr(cidr) => cidr_matches(cidr, ip),
+            RuleTarget::Host(_) => false,
+        }
+    }
+
+    /// Returns true if this rule covers the given socket address.
+    pub fn matches(&self, addr: SocketAddr) -> bool {
+        self.matches_ip(addr.ip()) && self.matches_port(addr.port())
+    }
+
+    /// Returns true if this is a host name rule that covers `host`.
+    pub fn matches_host(&self, host: &str) -> bool {
+        let RuleTarget::Host(pattern) = &self.target else {
+            return false;
+        };
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+            None => host == *pattern,
+        }
+    }
+
+    fn matches_port(&self, port: u16) -> bool {
+        self.port.map_or(true, |p| p == port)
+    }
+}
+
+fn cidr_matches(cidr: &IpCidr, ip: IpAddr) -> bool {
+    match (cidr.ip, ip) {
+        (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+            u32::from(net) as u128,
+            u32::from(ip) as u128,
+            32,
+            cidr.prefix,
+        ),
+        (IpAddr::V6(net), IpAddr::V6(ip)) => {
+            prefix_matches(u128::from(net), u128::from(ip), 128, cidr.prefix)
+        }
+        (IpAddr::V6(_), IpAddr::V4(ip)) => cidr_matches(cidr, IpAddr::V6(ip.to_ipv6_mapped())),
+        (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+            .to_ipv4_mapped()
+            .is_some_and(|ip| cidr_matches(cidr, IpAddr::V4(ip))),
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    let prefix = prefix.min(bits);
+    if prefix == 0 {
+        return true;
+    }
+    let shift = (bits - prefix) as u32;
+    (net >> shift) == (ip >> shift)
+}
+
+impl fmt::Display for NetworkRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.target, self.port) {
+            (
+                RuleTarget::Cidr(IpCidr {
+                    ip: IpAddr::V6(ip),
+                    prefix,
+                }),
+                Some(port),
+            ) => {
+                write!(f, "[{ip}]/{prefix}:{port}")
+            }
+            (RuleTarget::Cidr(IpCidr { ip, prefix }), Some(port)) => {
+                write!(f, "{ip}/{prefix}:{port}")
+            }
+            (RuleTarget::Cidr(IpCidr { ip, prefix }), None) => write!(f, "{ip}/{prefix}"),
+            (RuleTarget::Host(host), Some(port)) => write!(f, "{host}:{port}"),
+            (RuleTarget::Host(host), None) => write!(f, "{host}"),
+        }
+    }
+}
+
+impl FromStr for NetworkRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("invalid network rule \"{s}\"");
+
+        // Split off the port first; IPv6 addresses need brackets when a
+        // port is given, otherwise the last colon is ambiguous.
+        let (addr, port) = if let Some(rest) = s.strip_prefix('[') {
+            let (ip, rest) = rest.split_once(']').ok_or_else(invalid)?;
+            let (prefix, port) = match rest.split_once(':') {
+                Some((prefix, port)) => (prefix, Some(port)),
+                None => (rest, None),
+            };
+            (format!("{ip}{prefix}"), port)
+        } else if s.matches(':').count() == 1 {
+            let (addr, port) = s.split_once(':').ok_or_else(invalid)?;
+            (addr.to_string(), Some(port))
+        } else {
+            (s.to_string(), None)
+        };
+        let port = port
+            .map(|port| port.parse::<u16>().map_err(|_| invalid()))
+            .transpose()?;
+
+        if is_host_name(&addr) {
+            return Ok(Self {
+                target: RuleTarget::Host(addr.trim_end_matches('.').to_ascii_lowercase()),
+                port,
+            });
+        }
+
+        let (ip, prefix) = match addr.split_once('/') {
+            Some((ip, prefix)) => (ip, Some(prefix.parse::<u8>().map_err(|_| invalid())?)),
+            None => (addr.as_str(), None),
+        };
+        let ip = ip.parse::<IpAddr>().map_err(|_| invalid())?;
+        let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max_prefix);
+        if prefix > max_prefix {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            target: RuleTarget::Cidr(IpCidr { ip, prefix }),
+            port,
+        })
+    }
+}
+
+/// Checks for something that looks like a DNS name, optionally starting
+/// with a `*.` wildcard. Names where the last label is numeric are rejected
+/// so that malformed IPv4 addresses aren't mistaken for host names.
+fn is_host_name(s: &str) -> bool {
+    let s = s.strip_prefix("*.").unwrap_or(s);
+    let s = s.strip_suffix('.').unwrap_or(s);
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    !s.is_empty()
+        && s.len() <= 253
+        && s.split('.').all(valid_label)
+        && !s
+            .rsplit('.')
+            .next()
+            .is_some_and(|last| last.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The most addresses remembered from name resolutions, past which the
+/// least recently resolved ones are forgotten.
+const MAX_RESOLVED_ADDRS: usize = 4096;
+
+/// The host name rules that applied to an address when the guest resolved
+/// it, as their port (`None` for every port) and whether they allow it.
+#[derive(Debug)]
+struct ResolvedAddr {
+    rules: Vec<(Option<u16>, bool)>,
+    generation: u64,
+}
+
+/// The addresses that host name rules applied to, bounded to
+/// [`MAX_RESOLVED_ADDRS`].
+#[derive(Debug, Default)]
+struct ResolvedAddrs {
+    addrs: HashMap<IpAddr, ResolvedAddr>,
+    /// Incremented on every resolution, to find the least recent one.
+    generation: u64,
+}
+
+impl ResolvedAddrs {
+    fn rules(&self, ip: IpAddr) -> impl Iterator<Item = (Option<u16>, bool)> + '_ {
+        self.addrs
+            .get(&ip)
+            .into_iter()
+            .flat_map(|addr| addr.rules.iter().copied())
+    }
+}
+
+#[derive(Debug)]
+struct Rules {
+    /// `None` lets through everything that isn't denied.
+    allow: Option<Vec<NetworkRule>>,
+    deny: Vec<NetworkRule>,
+    resolved: RwLock<ResolvedAddrs>,
+}
+
+impl Rules {
+    fn is_allowed(&self, addr: SocketAddr) -> bool {
+        let resolved = self.resolved.read().unwrap();
+        let learned = |allow: bool| {
+            resolved
+                .rules(addr.ip())
+                .any(|(port, a)| a == allow && port.map_or(true, |p| p == addr.port()))
+        };
+
+        if self.deny.iter().any(|rule| rule.matches(addr)) || learned(false) {
+            return false;
+        }
+        match &self.allow {
+            Some(allow) => allow.iter().any(|rule| rule.matches(addr)) || learned(true),
+            None => true,
+        }
+    }
+
+    /// Like [`Rules::is_allowed`] but for protocols without ports.
+    fn is_ip_allowed(&self, ip: IpAddr) -> bool {
+        let resolved = self.resolved.read().unwrap();
+        let learned = |allow: bool, any_port: bool| {
+            resolved
+                .rules(ip)
+                .any(|(port, a)| a == allow && (any_port || port.is_none()))
+        };
+
+        let denied = self
+            .deny
+            .iter()
+            .any(|rule| rule.port.is_none() && rule.matches_ip(ip));
+        if denied || learned(false, false) {
+            return false;
+        }
+        match &self.allow {
+            Some(allow) => allow.iter().any(|rule| rule.matches_ip(ip)) || learned(true, true),
+            None => true,
+        }
+    }
+
+    fn check(&self, addr: SocketAddr) -> Result<()> {
+        if self.is_allowed(addr) {
+            Ok(())
+        } else {
+            tracing::debug!(%addr, "network access denied by the allowlist");
+            Err(NetworkError::PermissionDenied)
+        }
+    }
+
+    /// Like [`Rules::check`] but for protocols without ports.
+    fn check_ip(&self, ip: IpAddr) -> Result<()> {
+        if self.is_ip_allowed(ip) {
+            Ok(())
+        } else {
+            tracing::debug!(%ip, "network access denied by the allowlist");
+            Err(NetworkError::PermissionDenied)
+        }
+    }
+
+    /// Remembers the addresses that `host` resolved to so that the host
+    /// name rules covering it apply to them.
+    ///
+    /// At most [`MAX_RESOLVED_ADDRS`] addresses are remembered, the least
+    /// recently resolved ones being forgotten first.
+    fn learn(&self, host: &str, ips: &[IpAddr]) {
+        let allow = self.allow.iter().flatten().map(|rule| (rule, true));
+        let deny = self.deny.iter().map(|rule| (rule, false));
+        let rules = allow
+            .chain(deny)
+            .filter(|(rule, _)| rule.matches_host(host))
+            .map(|(rule, allow)| (rule.port, allow))
+            .collect::<Vec<_>>();
+        if rules.is_empty() {
+            return;
+        }
+
+        let mut resolved = self.resolved.write().unwrap();
+        resolved.generation += 1;
+        let generation = resolved.generation;
+        for &ip in ips {
+            if !resolved.addrs.contains_key(&ip) && resolved.addrs.len() >= MAX_RESOLVED_ADDRS {
+                let oldest = resolved
+                    .addrs
+                    .iter()
+                    .min_by_key(|(_, addr)| addr.generation)
+                    .map(|(ip, _)| *ip);
+                if let Some(oldest) = oldest {
+                    resolved.addrs.remove(&oldest);
+                }
+            }
+            let addr = resolved.addrs.entry(ip).or_insert_with(|| ResolvedAddr {
+                rules: Vec::new(),
+                generation,
+            });
+            addr.generation = generation;
+            for rule in &rules {
+                if !addr.rules.contains(rule) {
+                    addr.rules.push(*rule);
+                }
+            }
+        }
+    }
+}
+
+/// Networking implementation that only lets the guest reach the addresses
+/// covered by a list of [`NetworkRule`]s, forwarding everything else to
+/// an inner implementation.
+///
+/// The rules are checked against peer addresses only: the peer of outgoing
+/// TCP connections, the peer of the connections accepted by TCP listeners
+/// (the other ones are closed), and the destination of every UDP datagram
+/// and ICMP packet. Local addresses are never checked, so the guest can bind
+/// any socket. Denied addresses take precedence over allowed ones. Raw
+/// sockets and bridging are always denied since they would bypass the
+/// allowlist.
+///
+/// Host name rules apply to the addresses the name resolved to when the
+/// guest looked it up through [`VirtualNetworking::resolve`], addresses
+/// that the guest learned some other way are only covered by the IP rules.
+/// Only the most recently resolved addresses are remembered, so the
+/// allowlist doesn't grow without bound.
+///
+/// Denying a host name is therefore best-effort: a guest that resolves the
+/// name itself (for instance over a DNS server it reaches directly) or
+/// that already knows its addresses can still connect to them. Use IP
+/// rules to reliably deny access to a host.
+#[derive(Debug, Clone)]
+pub struct AllowListNetworking {
+    inner: DynVirtualNetworking,
+    rules: Arc<Rules>,
+}
+
+impl AllowListNetworking {
+    /// Only allows the addresses covered by `rules`.
+    pub fn new(inner: DynVirtualNetworking, rules: impl IntoIterator<Item = NetworkRule>) -> Self {
+        Self::with_rules(inner, Some(rules.into_iter().collect()), Vec::new())
+    }
+
+    /// Allows every address except the ones covered by `rules`.
+    pub fn deny_list(
+        inner: DynVirtualNetworking,
+        rules: impl IntoIterator<Item = NetworkRule>,
+    ) -> Self {
+        Self::with_rules(inner, None, rules.into_iter().collect())
+    }
+
+    fn with_rules(
+        inner: DynVirtualNetworking,
+        allow: Option<Vec<NetworkRule>>,
+        deny: Vec<NetworkRule>,
+    ) -> Self {
+        Self {
+            inner,
+            rules: Arc::new(Rules {
+                allow,
+                deny,
+                resolved: RwLock::new(ResolvedAddrs::default()),
+            }),
+        }
+    }
+
+    /// Denies the addresses covered by `rules`, even if they are allowed.
+    pub fn with_denied(self, rules: impl IntoIterator<Item = NetworkRule>) -> Self {
+        let mut deny = self.rules.deny.clone();
+        deny.extend(rules);
+        Self::with_rules(self.inner, self.rules.allow.clone(), deny)
+    }
+
+    /// Returns the allow rules, or `None` for a pure denylist.
+    pub fn rules(&self) -> Option<&[NetworkRule]> {
+        self.rules.allow.as_deref()
+    }
+
+    /// Returns the deny rules.
+    pub fn denied(&self) -> &[NetworkRule] {
+        &self.rules.deny
+    }
+
+    /// Returns true if the allowlist lets the guest use the given address.
+    pub fn is_allowed(&self, addr: SocketAddr) -> bool {
+        self.rules.is_allowed(addr)
+    }
+
+    fn check(&self, addr: SocketAddr) -> Result<()> {
+        self.rules.check(addr)
+    }
+}
+
+#[async_trait::async_trait]
+impl VirtualNetworking for AllowListNetworking {
+    async fn bridge(
+        &self,
+        _network: &str,
+        _access_token: &str,
+        _security: StreamSecurity,
+    ) -> Result<()> {
+        Err(NetworkError::PermissionDenied)
+    }
+
+    async fn unbridge(&self) -> Result<()> {
+        Err(NetworkError::PermissionDenied)
+    }
+
+    async fn dhcp_acquire(&self) -> Result<Vec<IpAddr>> {
+        self.inner.dhcp_acquire().await
+    }
+
+    async fn ip_add(&self, ip: IpAddr, prefix: u8) -> Result<()> {
+        self.inner.ip_add(ip, prefix).await
+    }
+
+    async fn ip_remove(&self, ip: IpAddr) -> Result<()> {
+        self.inner.ip_remove(ip).await
+    }
+
+    async fn ip_clear(&self) -> Result<()> {
+        self.inner.ip_clear().await
+    }
+
+    async fn ip_list(&self) -> Result<Vec<IpCidr>> {
+        self.inner.ip_list().await
+    }
+
+    async fn mac(&self) -> Result<[u8; 6]> {
+        self.inner.mac().await
+    }
+
+    async fn gateway_set(&self, ip: IpAddr) -> Result<()> {
+        self.inner.gateway_set(ip).await
+    }
+
+    async fn route_add(
+        &self,
+        cidr: IpCidr,
+        via_router: IpAddr,
+        preferred_until: Option<Duration>,
+        expires_at: Option<Duration>,
+    ) -> Result<()> {
+        self.inner
+            .route_add(cidr, via_router, preferred_until, expires_at)
+            .await
+    }
+
+    async fn route_remove(&self, cidr: IpAddr) -> Result<()> {
+        self.inner.route_remove(cidr).await
+    }
+
+    async fn route_clear(&self) -> Result<()> {
+        self.inner.route_clear().await
+    }
+
+    async fn route_list(&self) -> Result<Vec<IpRoute>> {
+        self.inner.route_list().await
+    }
+
+    async fn bind_raw(&self) -> Result<Box<dyn VirtualRawSocket + Sync>> {
+        Err(NetworkError::PermissionDenied)
+    }
+
+    async fn listen_tcp(
+        &self,
+        addr: SocketAddr,
+        only_v6: bool,
+        reuse_port: bool,
+        reuse_addr: bool,
+    ) -> Result<Box<dyn VirtualTcpListener + Sync>> {
+        let listener = self
+            .inner
+            .listen_tcp(addr, only_v6, reuse_port, reuse_addr)
+            .await?;
+        Ok(Box::new(AllowListTcpListener {
+            inner: listener,
+            rules: self.rules.clone(),
+        }))
+    }
+
+    async fn bind_udp(
+        &self,
+        addr: SocketAddr,
+        reuse_port: bool,
+        reuse_addr: bool,
+    ) -> Result<Box<dyn VirtualUdpSocket + Sync>> {
+        let socket = self.inner.bind_udp(addr, reuse_port, reuse_addr).await?;
+        Ok(Box::new(AllowListUdpSocket {
+            inner: socket,
+            rules: self.rules.clone(),
+        }))
+    }
+
+    async fn bind_icmp(&self, addr: IpAddr) -> Result<Box<dyn VirtualIcmpSocket + Sync>> {
+        let socket = self.inner.bind_icmp(addr).await?;
+        Ok(Box::new(AllowListIcmpSocket {
+            inner: socket,
+            rules: self.rules.clone(),
+        }))
+    }
+
+    async fn connect_tcp(
+        &self,
+        addr: SocketAddr,
+        peer: SocketAddr,
+    ) -> Result<Box<dyn VirtualTcpSocket + Sync>> {
+        self.check(peer)?;
+        self.inner.connect_tcp(addr, peer).await
+    }
+
+    async fn resolve(
+        &self,
+        host: &str,
+        port: Option<u16>,
+        dns_server: Option<IpAddr>,
+    ) -> Result<Vec<IpAddr>> {
+        if let Some(dns_server) = dns_server {
+            self.check(SocketAddr::new(dns_server, 53))?;
+        }
+        if self
+            .rules
+            .deny
+            .iter()
+            .any(|rule| rule.port.is_none() && rule.matches_host(host))
+        {
+            tracing::debug!(%host, "name resolution denied by the denylist");
+            return Err(NetworkError::PermissionDenied);
+        }
+
+        let ips = self.inner.resolve(host, port, dns_server).await?;
+        self.rules.learn(host, &ips);
+        Ok(ips)
+    }
+
+    /// Listeners that were bound by the host are trusted.
+    fn adopt_tcp_listener(
+        &self,
+        listener: std::net::TcpListener,
+    ) -> Result<Box<dyn VirtualTcpListener + Sync>> {
+        self.inner.adopt_tcp_listener(listener)
+    }
+}
+
+/// TCP listener that closes the connections accepted from addresses that
+/// are not in the allowlist.
+#[derive(Debug)]
+struct AllowListTcpListener {
+    inner: Box<dyn VirtualTcpListener + Sync>,
+    rules: Arc<Rules>,
+}
+
+impl VirtualIoSource for AllowListTcpListener {
+    fn remove_handler(&mut self) {
+        self.inner.remove_handler()
+    }
+
+    fn poll_read_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        self.inner.poll_read_ready(cx)
+    }
+
+    fn poll_write_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        self.inner.poll_write_ready(cx)
+    }
+}
+
+impl VirtualTcpListener for AllowListTcpListener {
+    fn try_accept(&mut self) -> Result<(Box<dyn VirtualTcpSocket + Sync>, SocketAddr)> {
+        loop {
+            let (socket, peer) = self.inner.try_accept()?;
+            if self.rules.check(peer).is_ok() {
+                return Ok((socket, peer));
+            }
+        }
+    }
+
+    fn set_handler(&mut self, handler: Box<dyn InterestHandler + Send + Sync>) -> Result<()> {
+        self.inner.set_handler(handler)
+    }
+
+    fn addr_local(&self) -> Result<SocketAddr> {
+        self.inner.addr_local()
+    }
+
+    fn set_ttl(&mut self, ttl: u8) -> Result<()> {
+        self.inner.set_ttl(ttl)
+    }
+
+    fn ttl(&self) -> Result<u8> {
+        self.inner.ttl()
+    }
+}
+
+/// UDP socket that drops datagrams sent to addresses that are not in the
+/// allowlist.
+#[derive(Debug)]
+struct AllowListUdpSocket {
+    inner: Box<dyn VirtualUdpSocket + Sync>,
+    rules: Arc<Rules>,
+}
+
+impl VirtualIoSource for AllowListUdpSocket {
+    fn remove_handler(&mut self) {
+        self.inner.remove_handler()
+    }
+
+    fn poll_read_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        self.inner.poll_read_ready(cx)
+    }
+
+    fn poll_write_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        self.inner.poll_write_ready(cx)
+    }
+}
+
+impl VirtualSocket for AllowListUdpSocket {
+    fn set_ttl(&mut self, ttl: u32) -> Result<()> {
+        self.inner.set_ttl(ttl)
+    }
+
+    fn ttl(&self) -> Result<u32> {
+        self.inner.ttl()
+    }
+
+    fn addr_local(&self) -> Result<SocketAddr> {
+        self.inner.addr_local()
+    }
+
+    fn status(&self) -> Result<SocketStatus> {
+        self.inner.status()
+    }
+
+    fn set_handler(&mut self, handler: Box<dyn InterestHandler + Send + Sync>) -> Result<()> {
+        self.inner.set_handler(handler)
+    }
+}
+
+impl VirtualConnectionlessSocket for AllowListUdpSocket {
+    fn try_send_to(&mut self, data: &[u8], addr: SocketAddr) -> Result<usize> {
+        self.rules.check(addr)?;
+        self.inner.try_send_to(data, addr)
+    }
+
+    fn try_recv_from(&mut self, buf: &mut [MaybeUninit<u8>]) -> Result<(usize, SocketAddr)> {
+        self.inner.try_recv_from(buf)
+    }
+}
+
+impl VirtualUdpSocket for AllowListUdpSocket {
+    fn set_broadcast(&mut self, broadcast: bool) -> Result<()> {
+        self.inner.set_broadcast(broadcast)
+    }
+
+    fn broadcast(&self) -> Result<bool> {
+        self.inner.broadcast()
+    }
+
+    fn set_multicast_loop_v4(&mut self, val: bool) -> Result<()> {
+        self.inner.set_multicast_loop_v4(val)
+    }
+
+    fn multicast_loop_v4(&self) -> Result<bool> {
+        self.inner.multicast_loop_v4()
+    }
+
+    fn set_multicast_loop_v6(&mut self, val: bool) -> Result<()> {
+        self.inner.set_multicast_loop_v6(val)
+    }
+
+    fn multicast_loop_v6(&self) -> Result<bool> {
+        self.inner.multicast_loop_v6()
+    }
+
+    fn set_multicast_ttl_v4(&mut self, ttl: u32) -> Result<()> {
+        self.inner.set_multicast_ttl_v4(ttl)
+    }
+
+    fn multicast_ttl_v4(&self) -> Result<u32> {
+        self.inner.multicast_ttl_v4()
+    }
+
+    fn join_multicast_v4(&mut self, multiaddr: Ipv4Addr, iface: Ipv4Addr) -> Result<()> {
+        self.rules.check(SocketAddr::new(multiaddr.into(), 0))?;
+        self.inner.join_multicast_v4(multiaddr, iface)
+    }
+
+    fn leave_multicast_v4(&mut self, multiaddr: Ipv4Addr, iface: Ipv4Addr) -> Result<()> {
+        self.inner.leave_multicast_v4(multiaddr, iface)
+    }
+
+    fn join_multicast_v6(&mut self, multiaddr: Ipv6Addr, iface: u32) -> Result<()> {
+        self.rules.check(SocketAddr::new(multiaddr.into(), 0))?;
+        self.inner.join_multicast_v6(multiaddr, iface)
+    }
+
+    fn leave_multicast_v6(&mut self, multiaddr: Ipv6Addr, iface: u32) -> Result<()> {
+        self.inner.leave_multicast_v6(multiaddr, iface)
+    }
+
+    fn addr_peer(&self) -> Result<Option<SocketAddr>> {
+        self.inner.addr_peer()
+    }
+}
+
+/// ICMP socket that drops packets sent to addresses that are not in the
+/// allowlist.
+#[derive(Debug)]
+struct AllowListIcmpSocket {
+    inner: Box<dyn VirtualIcmpSocket + Sync>,
+    rules: Arc<Rules>,
+}
+
+impl VirtualIoSource for AllowListIcmpSocket {
+    fn remove_handler(&mut self) {
+        self.inner.remove_handler()
+    }
+
+    fn poll_read_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        self.inner.poll_read_ready(cx)
+    }
+
+    fn poll_write_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<usize>> {
+        self.inner.poll_write_ready(cx)
+    }
+}
+
+impl VirtualSocket for AllowListIcmpSocket {
+    fn set_ttl(&mut self, ttl: u32) -> Result<()> {
+        self.inner.set_ttl(ttl)
+    }
+
+    fn ttl(&self) -> Result<u32> {
+        self.inner.ttl()
+    }
+
+    fn addr_local(&self) -> Result<SocketAddr> {
+        self.inner.addr_local()
+    }
+
+    fn status(&self) -> Result<SocketStatus> {
+        self.inner.status()
+    }
+
+    fn set_handler(&mut self, handler: Box<dyn InterestHandler + Send + Sync>) -> Result<()> {
+        self.inner.set_handler(handler)
+    }
+}
+
+impl VirtualConnectionlessSocket for AllowListIcmpSocket {
+    fn try_send_to(&mut self, data: &[u8], addr: SocketAddr) -> Result<usize> {
+        self.rules.check_ip(addr.ip())?;
+        self.inner.try_send_to(data, addr)
+    }
+
+    fn try_recv_from(&mut self, buf: &mut [MaybeUninit<u8>]) -> Result<(usize, SocketAddr)> {
+        self.inner.try_recv_from(buf)
+    }
+}
+
+impl VirtualIcmpSocket for AllowListIcmpSocket {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rules() {
+        let prefix = |rule: &NetworkRule| match rule.target {
+            RuleTarget::Cidr(cidr) => cidr.prefix,
+            RuleTarget::Host(_) => panic!("{rule} is not a CIDR"),
+        };
+
+        let rule: NetworkRule = "10.0.0.0/8".parse().unwrap();
+        assert_eq!(prefix(&rule), 8);
+        assert_eq!(rule.port, None);
+
+        let rule: NetworkRule = "1.1.1.1:53".parse().unwrap();
+        assert_eq!(prefix(&rule), 32);
+        assert_eq!(rule.port, Some(53));
+
+        let rule: NetworkRule = "192.168.0.0/16:443".parse().unwrap();
+        assert_eq!(prefix(&rule), 16);
+        assert_eq!(rule.port, Some(443));
+
+        let rule: NetworkRule = "::1".parse().unwrap();
+        assert_eq!(prefix(&rule), 128);
+        assert_eq!(rule.port, None);
+
+        let rule: NetworkRule = "[fd00::]/8:80".parse().unwrap();
+        assert_eq!(prefix(&rule), 8);
+        assert_eq!(rule.port, Some(80));
+        assert_eq!(rule.to_string().parse::<NetworkRule>().unwrap(), rule);
+
+        let rule: NetworkRule = "API.example.com:443".parse().unwrap();
+        assert_eq!(rule.target, RuleTarget::Host("api.example.com".to_string()));
+        assert_eq!(rule.port, Some(443));
+        assert_eq!(rule.to_string(), "api.example.com:443");
+
+        let rule: NetworkRule = "*.example.com".parse().unwrap();
+        assert_eq!(rule.target, RuleTarget::Host("*.example.com".to_string()));
+        assert_eq!(rule.port, None);
+
+        assert!("10.0.0.0/33".parse::<NetworkRule>().is_err());
+        assert!("1.2.3.400".parse::<NetworkRule>().is_err());
+        assert!("example.com/8".parse::<NetworkRule>().is_err());
+        assert!("exa mple.com".parse::<NetworkRule>().is_err());
+        assert!("1.2.3.4:http".parse::<NetworkRule>().is_err());
+    }
+
+    #[test]
+    fn match_rules() {
+        let rule: NetworkRule = "10.0.0.0/8:443".parse().unwrap();
+        assert!(rule.matches("10.1.2.3:443".parse().unwrap()));
+        assert!(!rule.matches("10.1.2.3:80".parse().unwrap()));
+        assert!(!rule.matches("11.0.0.1:443".parse().unwrap()));
+        assert!(rule.matches("[::ffff:10.0.0.1]:443".parse().unwrap()));
+
+        let rule: NetworkRule = "0.0.0.0/0".parse().unwrap();
+        assert!(rule.matches("8.8.8.8:53".parse().unwrap()));
+        assert!(!rule.matches("[2001:db8::1]:53".parse().unwrap()));
+
+        let rule: NetworkRule = "api.example.com".parse().unwrap();
+        assert!(rule.matches_host("api.example.com"));
+        assert!(rule.matches_host("API.Example.com."));
+        assert!(!rule.matches_host("www.api.example.com"));
+        assert!(!rule.matches("127.0.0.1:80".parse().unwrap()));
+
+        let rule: NetworkRule = "*.example.com".parse().unwrap();
+        assert!(rule.matches_host("api.example.com"));
+        assert!(rule.matches_host("a.b.example.com"));
+        assert!(!rule.matches_host("example.com"));
+        assert!(!rule.matches_host("badexample.com"));
+    }
+
+    /// Resolves every host name to the same address.
+    #[derive(Debug)]
+    struct FixedResolver(IpAddr);
+
+    #[async_trait::async_trait]
+    impl VirtualNetworking for FixedResolver {
+        async fn resolve(
+            &self,
+            _host: &str,
+            _port: Option<u16>,
+            _dns_server: Option<IpAddr>,
+        ) -> Result<Vec<IpAddr>> {
+            Ok(vec![self.0])
+        }
+    }
+
+    /// Binds ICMP sockets that accept every packet.
+    #[derive(Debug)]
+    struct IcmpNetworking;
+
+    #[async_trait::async_trait]
+    impl VirtualNetworking for IcmpNetworking {
+        async fn bind_icmp(&self, _addr: IpAddr) -> Result<Box<dyn VirtualIcmpSocket + Sync>> {
+            Ok(Box::new(NullIcmpSocket))
+        }
+    }
+
+    #[derive(Debug)]
+    struct NullIcmpSocket;
+
+    impl VirtualIoSource for NullIcmpSocket {
+        fn remove_handler(&mut self) {}
+
+        fn poll_read_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<usize>> {
+            Poll::Pending
+        }
+
+        fn poll_write_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<usize>> {
+            Poll::Ready(Ok(0))
+        }
+    }
+
+    impl VirtualSocket for NullIcmpSocket {
+        fn set_ttl(&mut self, _ttl: u32) -> Result<()> {
+            Ok(())
+        }
+
+        fn ttl(&self) -> Result<u32> {
+            Ok(64)
+        }
+
+        fn addr_local(&self) -> Result<SocketAddr> {
+            Ok("0.0.0.0:0".parse().unwrap())
+        }
+
+        fn status(&self) -> Result<SocketStatus> {
+            Ok(SocketStatus::Opened)
+        }
+
+        fn set_handler(&mut self, _handler: Box<dyn InterestHandler + Send + Sync>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl VirtualConnectionlessSocket for NullIcmpSocket {
+        fn try_send_to(&mut self, data: &[u8], _addr: SocketAddr) -> Result<usize> {
+            Ok(data.len())
+        }
+
+        fn try_recv_from(&mut self, _buf: &mut [MaybeUninit<u8>]) -> Result<(usize, SocketAddr)> {
+            Err(NetworkError::WouldBlock)
+        }
+    }
+
+    impl VirtualIcmpSocket for NullIcmpSocket {}
+
+    #[tokio::test]
+    async fn icmp_packets_are_checked_against_the_rules() {
+        let net =
+            AllowListNetworking::new(Arc::new(IcmpNetworking), ["192.0.2.0/24".parse().unwrap()]);
+
+        let mut icmp = net.bind_icmp("0.0.0.0".parse().unwrap()).await.unwrap();
+        assert_eq!(
+            icmp.try_send_to(b"ping", "192.0.2.1:0".parse().unwrap()),
+            Ok(4)
+        );
+        assert_eq!(
+            icmp.try_send_to(b"ping", "198.51.100.1:0".parse().unwrap()),
+            Err(NetworkError::PermissionDenied)
+        );
+    }
+
+    #[tokio::test]
+    async fn host_rules_apply_to_resolved_addresses() {
+        let ip: IpAddr = "192.0.2.10".parse().unwrap();
+        let net = AllowListNetworking::new(
+            Arc::new(FixedResolver(ip)),
+            ["api.example.com:443".parse().unwrap()],
+        );
+        let addr = SocketAddr::new(ip, 443);
+
+        assert!(!net.is_allowed(addr));
+        assert_eq!(
+            net.resolve("api.example.com", None, None).await,
+            Ok(vec![ip])
+        );
+        assert!(net.is_allowed(addr));
+        assert!(!net.is_allowed(SocketAddr::new(ip, 80)));
+    }
+
+    #[test]
+    fn resolved_addresses_are_bounded() {
+        let net = AllowListNetworking::new(
+            Arc::new(FixedResolver(Ipv4Addr::LOCALHOST.into())),
+            ["*.example.com".parse().unwrap()],
+        );
+        let ip = |i: usize| IpAddr::from(Ipv4Addr::from(0x0a00_0000 + i as u32));
+        for i in 0..=MAX_RESOLVED_ADDRS {
+            net.rules.learn(&format!("host{i}.example.com"), &[ip(i)]);
+        }
+
+        assert_eq!(
+            net.rules.resolved.read().unwrap().addrs.len(),
+            MAX_RESOLVED_ADDRS
+        );
+        assert!(!net.is_allowed(SocketAddr::new(ip(0), 80)));
+        assert!(net.is_allowed(SocketAddr::new(ip(1), 80)));
+        assert!(net.is_allowed(SocketAddr::new(ip(MAX_RESOLVED_ADDRS), 80)));
+
+        // Resolving an address again makes it the most recent one.
+        net.rules.learn("host1.example.com", &[ip(1)]);
+        net.rules.learn("again.example.com", &[ip(0)]);
+        assert!(net.is_allowed(SocketAddr::new(ip(1), 80)));
+        assert!(!net.is_allowed(SocketAddr::new(ip(2), 80)));
+    }
+
+    #[tokio::test]
+    async fn denied_rules_take_precedence() {
+        let ip: IpAddr = "192.0.2.10".parse().unwrap();
+        let net = AllowListNetworking::new(
+            Arc::new(FixedResolver(ip)),
+            ["192.0.2.0/24".parse().unwrap()],
+        )
+        .with_denied([
+            "192.0.2.1".parse().unwrap(),
+            "tracker.example.com:443".parse().unwrap(),
+            "*.evil.example".parse().unwrap(),
+        ]);
+
+        assert!(net.is_allowed("192.0.2.2:80".parse().unwrap()));
+        assert!(!net.is_allowed("192.0.2.1:80".parse().unwrap()));
+        assert!(!net.is_allowed("198.51.100.1:80".parse().unwrap()));
+
+        assert_eq!(
+            net.resolve("www.evil.example", None, None).await,
+            Err(NetworkError::PermissionDenied)
+        );
+        net.resolve("tracker.example.com", None, None)
+            .await
+            .unwrap();
+        assert!(!net.is_allowed(SocketAddr::new(ip, 443)));
+        assert!(net.is_allowed(SocketAddr::new(ip, 80)));
+
+        let net = AllowListNetworking::deny_list(
+            Arc::new(FixedResolver(ip)),
+            ["10.0.0.0/8".parse().unwrap()],
+        );
+        assert!(net.is_allowed("192.0.2.2:80".parse().unwrap()));
+        assert!(!net.is_allowed("10.0.0.1:80".parse().unwrap()));
+    }
+
+    #[cfg(feature = "host-net")]
+    #[tokio::test]
+    async fn allowlist_denies_other_peers() {
+        use crate::VirtualTcpListenerExt;
+
+        let net = AllowListNetworking::new(
+            Arc::new(crate::host::LocalNetworking::new()),
+            ["127.0.0.1/32".parse().unwrap()],
+        );
+
+        let err = net
+            .connect_tcp(
+                "0.0.0.0:0".parse().unwrap(),
+                "192.0.2.1:80".parse().unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err, NetworkError::PermissionDenied);
+
+        let mut udp = net
+            .bind_udp("127.0.0.1:0".parse().unwrap(), false, false)
+            .await
+            .unwrap();
+        let err = udp
+            .try_send_to(b"hello", "192.0.2.1:9".parse().unwrap())
+            .unwrap_err();
+        assert_eq!(err, NetworkError::PermissionDenied);
+
+        let mut listener = net
+            .listen_tcp("127.0.0.1:0".parse().unwrap(), false, false, false)
+            .await
+            .unwrap();
+        let addr = listener.addr_local().unwrap();
+        let _client = std::net::TcpStream::connect(addr).unwrap();
+        let (_, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer.ip(), IpAddr::from([127, 0, 0, 1]));
+
+        // Only the peers of the accepted connections are checked.
+        let net = AllowListNetworking::new(
+            Arc::new(crate::host::LocalNetworking::new()),
+            ["192.0.2.0/24".parse().unwrap()],
+        );
+        let mut listener = net
+            .listen_tcp("127.0.0.1:0".parse().unwrap(), false, false, false)
+            .await
+            .unwrap();
+        let addr = listener.addr_local().unwrap();
+        let _client = std::net::TcpStream::connect(addr).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(
+            listener.try_accept().map(|(_, peer)| peer),
+            Err(NetworkError::WouldBlock)
+        );
+    }
+}
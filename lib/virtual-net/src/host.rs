@@ -67,20 +67,25 @@ impl VirtualNetworking for LocalNetworking {
         reuse_port: bool,
         reuse_addr: bool,
     ) -> Result<Box<dyn VirtualTcpListener + Sync>> {
-        let listener = std::net::TcpListener::bind(addr)
-            .map(|sock| {
-                sock.set_nonblocking(true).ok();
-                Box::new(LocalTcpListener {
-                    stream: mio::net::TcpListener::from_std(sock),
-                    selector: self.selector.clone(),
-                    handler_guard: HandlerGuardState::None,
-                    no_delay: None,
-                    keep_alive: None,
-                    backlog: Default::default(),
-                })
-            })
+        let listener = std::net::TcpListener::bind(addr).map_err(io_err_into_net_error)?;
+        self.adopt_tcp_listener(listener)
+    }
+
+    fn adopt_tcp_listener(
+        &self,
+        listener: std::net::TcpListener,
+    ) -> Result<Box<dyn VirtualTcpListener + Sync>> {
+        listener
+            .set_nonblocking(true)
             .map_err(io_err_into_net_error)?;
-        Ok(listener)
+        Ok(Box::new(LocalTcpListener {
+            stream: mio::net::TcpListener::from_std(listener),
+            selector: self.selector.clone(),
+            handler_guard: HandlerGuardState::None,
+            no_delay: None,
+            keep_alive: None,
+            backlog: Default::default(),
+        }))
     }
 
     async fn bind_udp(
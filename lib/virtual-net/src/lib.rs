@@ -1,5 +1,6 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod allowlist;
 #[cfg(feature = "remote")]
 pub mod client;
 pub mod composite;
@@ -16,6 +17,7 @@ pub mod tcp_pair;
 #[cfg(test)]
 mod tests;
 
+pub use allowlist::{AllowListNetworking, NetworkRule, RuleTarget};
 #[cfg(feature = "remote")]
 pub use client::{RemoteNetworkingClient, RemoteNetworkingClientDriver};
 pub use composite::CompositeTcpListener;
@@ -220,6 +222,15 @@ pub trait VirtualNetworking: fmt::Debug + Send + Sync + 'static {
     ) -> Result<Vec<IpAddr>> {
         Err(NetworkError::Unsupported)
     }
+
+    /// Takes over a TCP listener that was already bound on the host, for
+    /// instance one that was handed over by systemd socket activation
+    fn adopt_tcp_listener(
+        &self,
+        listener: std::net::TcpListener,
+    ) -> Result<Box<dyn VirtualTcpListener + Sync>> {
+        Err(NetworkError::Unsupported)
+    }
 }
 
 pub type DynVirtualNetworking = Arc<dyn VirtualNetworking>;
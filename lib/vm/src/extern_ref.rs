@@ -10,13 +10,34 @@ use crate::store::InternalStoreHandle;
 pub struct VMExternObj {
     #[derivative(Debug = "ignore")]
     contents: Box<dyn Any + Send + Sync + 'static>,
+    #[derivative(Debug = "ignore")]
+    on_drop: Option<DropHook>,
 }
 
+type DropHook = Box<dyn FnOnce(Box<dyn Any + Send + Sync + 'static>) + Send + Sync + 'static>;
+
 impl VMExternObj {
     /// Wraps the given value to expose it to Wasm code as an externref.
     pub fn new(val: impl Any + Send + Sync + 'static) -> Self {
         Self {
             contents: Box::new(val),
+            on_drop: None,
+        }
+    }
+
+    /// Wraps the given value like [`VMExternObj::new`], calling `on_drop`
+    /// with the value when the object is dropped.
+    pub fn with_drop_hook<T>(val: T, on_drop: impl FnOnce(T) + Send + Sync + 'static) -> Self
+    where
+        T: Any + Send + Sync + 'static,
+    {
+        Self {
+            contents: Box::new(val),
+            on_drop: Some(Box::new(move |contents| {
+                if let Ok(val) = contents.downcast::<T>() {
+                    on_drop(*val);
+                }
+            })),
         }
     }
 
@@ -25,6 +46,20 @@ impl VMExternObj {
     pub fn as_ref(&self) -> &(dyn Any + Send + Sync + 'static) {
         &*self.contents
     }
+
+    #[allow(clippy::should_implement_trait)]
+    /// Returns a mutable reference to the underlying value.
+    pub fn as_mut(&mut self) -> &mut (dyn Any + Send + Sync + 'static) {
+        &mut *self.contents
+    }
+}
+
+impl Drop for VMExternObj {
+    fn drop(&mut self) {
+        if let Some(on_drop) = self.on_drop.take() {
+            on_drop(std::mem::replace(&mut self.contents, Box::new(())));
+        }
+    }
 }
 
 /// Represents an opaque reference to any data within WebAssembly.
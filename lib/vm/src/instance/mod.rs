@@ -26,6 +26,7 @@ pub use allocator::InstanceAllocator;
 use memoffset::offset_of;
 use more_asserts::assert_lt;
 use std::alloc::Layout;
+use std::any::Any;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -90,6 +91,10 @@ pub(crate) struct Instance {
     /// will point to elements here for functions imported by this instance.
     imported_funcrefs: BoxedSlice<FunctionIndex, NonNull<VMCallerCheckedAnyfunc>>,
 
+    /// Objects that must outlive this instance, such as the code memory
+    /// its functions live in.
+    retained: Vec<Arc<dyn Any + Send + Sync>>,
+
     /// Additional context used by compiled WebAssembly code. This
     /// field is last, and represents a dynamically-sized array that
     /// extends beyond the nominal end of the struct (similar to a
@@ -411,7 +416,8 @@ impl Instance {
             .memories
             .get(memory_index)
             .unwrap_or_else(|| panic!("no memory for index {}", memory_index.index()));
-        mem.get_mut(self.context_mut()).grow(delta.into())
+        self.context_mut()
+            .grow_memory(mem, |memory| memory.grow(delta.into()))
     }
 
     /// Grow imported memory by the specified amount of pages.
@@ -432,7 +438,8 @@ impl Instance {
     {
         let import = self.imported_memory(memory_index);
         let mem = import.handle;
-        mem.get_mut(self.context_mut()).grow(delta.into())
+        self.context_mut()
+            .grow_memory(mem, |memory| memory.grow(delta.into()))
     }
 
     /// Returns the number of allocated wasm pages.
@@ -628,25 +635,7 @@ impl Instance {
         item: TableElement,
         len: u32,
     ) -> Result<(), Trap> {
-        // https://webassembly.github.io/bulk-memory-operations/core/exec/instructions.html#exec-table-init
-
-        let table = self.get_table(table_index);
-        let table_size = table.size() as usize;
-
-        if start_index
-            .checked_add(len)
-            .map_or(true, |n| n as usize > table_size)
-        {
-            return Err(Trap::lib(TrapCode::TableAccessOutOfBounds));
-        }
-
-        for i in start_index..(start_index + len) {
-            table
-                .set(i, item.clone())
-                .expect("should never panic because we already did the bounds check above");
-        }
-
-        Ok(())
+        self.get_table(table_index).fill(start_index, item, len)
     }
 
     /// Drop an element.
@@ -1039,7 +1028,7 @@ impl VMInstance {
                 .collect::<HashMap<_, _>>(),
         );
 
-        let handle = {
+        let mut handle = {
             let offsets = allocator.offsets().clone();
             // use dummy value to create an instance so we can get the vmctx pointer
             let funcrefs = PrimaryMap::new().into_boxed_slice();
@@ -1058,6 +1047,7 @@ impl VMInstance {
                 passive_data,
                 funcrefs,
                 imported_funcrefs,
+                retained: Vec::new(),
                 vmctx: VMContext {},
             };
 
@@ -1119,12 +1109,24 @@ impl VMInstance {
             instance.builtin_functions_ptr(),
             VMBuiltinFunctionsArray::initialized(),
         );
+        let epoch = context.epoch().clone();
+        ptr::write(
+            instance.vmctx_plus_offset(instance.offsets.vmctx_epoch_counter_ptr()),
+            epoch.counter_ptr(),
+        );
+        ptr::write(
+            instance.vmctx_plus_offset(instance.offsets.vmctx_epoch_deadline_ptr()),
+            epoch.deadline_ptr(),
+        );
 
         // Perform infallible initialization in this constructor, while fallible
         // initialization is deferred to the `initialize` method.
         initialize_passive_elements(instance);
         initialize_globals(instance);
 
+        // The vmctx points into the epoch state.
+        handle.retain(epoch);
+
         Ok(handle)
     }
 
@@ -1138,6 +1140,14 @@ impl VMInstance {
         unsafe { self.instance.as_mut() }
     }
 
+    /// Keep `owner` alive for as long as this instance exists.
+    ///
+    /// This is used to make sure that the code memory the functions of
+    /// this instance live in is not released while they can still be called.
+    pub fn retain(&mut self, owner: Arc<dyn Any + Send + Sync>) {
+        self.instance_mut().retained.push(owner);
+    }
+
     /// Finishes the instantiation process started by `Instance::new`.
     ///
     /// # Safety
@@ -1188,6 +1198,27 @@ impl VMInstance {
         self.instance().module_ref()
     }
 
+    /// Makes a global defined by this instance refer to another global of
+    /// the store, so that it is shared with other instances.
+    ///
+    /// The previous value of the global is discarded, so this should be
+    /// done before the start function is invoked.
+    ///
+    /// # Safety
+    ///
+    /// `global` must belong to `objects`, the store of this instance, and
+    /// have the same type as the global it replaces.
+    pub unsafe fn share_global(
+        &mut self,
+        index: LocalGlobalIndex,
+        global: InternalStoreHandle<VMGlobal>,
+        objects: &StoreObjects,
+    ) {
+        let instance = self.instance_mut();
+        *instance.globals_ptr().add(index.index()) = global.get(objects).vmglobal().as_ptr();
+        instance.globals[index] = global;
+    }
+
     /// Lookup an export with the given name.
     pub fn lookup(&mut self, field: &str) -> Option<VMExtern> {
         let export = *self.module_ref().exports.get(field)?;
@@ -1327,6 +1358,73 @@ impl VMInstance {
     pub fn get_local_table(&mut self, index: LocalTableIndex) -> &mut VMTable {
         self.instance_mut().get_local_table(index)
     }
+
+    /// Returns the handles of the memories defined by this instance.
+    pub fn local_memories(
+        &self,
+    ) -> impl ExactSizeIterator<Item = InternalStoreHandle<VMMemory>> + '_ {
+        self.instance().memories.values().copied()
+    }
+
+    /// Returns the handles of the tables defined by this instance.
+    pub fn local_tables(&self) -> impl ExactSizeIterator<Item = InternalStoreHandle<VMTable>> + '_ {
+        self.instance().tables.values().copied()
+    }
+
+    /// Returns the handles of the globals defined by this instance.
+    pub fn local_globals(
+        &self,
+    ) -> impl ExactSizeIterator<Item = InternalStoreHandle<VMGlobal>> + '_ {
+        self.instance().globals.values().copied()
+    }
+
+    /// Returns the handles of the memories of this instance, imported or
+    /// defined, in the order of their index.
+    pub fn memories(&self) -> impl Iterator<Item = InternalStoreHandle<VMMemory>> + '_ {
+        let instance = self.instance();
+        instance
+            .module
+            .memories
+            .keys()
+            .map(move |index| match instance.module.local_memory_index(index) {
+                Some(local_index) => instance.memories[local_index],
+                None => instance.imported_memory(index).handle,
+            })
+    }
+
+    /// Returns the handles of the tables of this instance, imported or
+    /// defined, in the order of their index.
+    pub fn tables(&self) -> impl Iterator<Item = InternalStoreHandle<VMTable>> + '_ {
+        let instance = self.instance();
+        instance
+            .module
+            .tables
+            .keys()
+            .map(move |index| match instance.module.local_table_index(index) {
+                Some(local_index) => instance.tables[local_index],
+                None => instance.imported_table(index).handle,
+            })
+    }
+
+    /// Returns the handles of the globals of this instance, imported or
+    /// defined, in the order of their index.
+    pub fn globals(&self) -> impl Iterator<Item = InternalStoreHandle<VMGlobal>> + '_ {
+        let instance = self.instance();
+        instance
+            .module
+            .globals
+            .keys()
+            .map(move |index| match instance.module.local_global_index(index) {
+                Some(local_index) => instance.globals[local_index],
+                None => instance.imported_global(index).handle,
+            })
+    }
+
+    /// Get a `VMFuncRef` for a function of this instance, defined or
+    /// imported.
+    pub fn func_ref(&self, function_index: FunctionIndex) -> Option<VMFuncRef> {
+        self.instance().func_ref(function_index)
+    }
 }
 
 /// Compute the offset for a memory data initializer.
@@ -13,6 +13,7 @@ use crate::{mmap::Mmap, store::MaybeInstanceOwned, vmcontext::VMMemoryDefinition
 use more_asserts::assert_ge;
 use std::cell::UnsafeCell;
 use std::convert::TryInto;
+use std::ops::Range;
 use std::ptr::NonNull;
 use std::rc::Rc;
 use std::slice;
@@ -29,6 +30,9 @@ struct WasmMmap {
     size: Pages,
     /// The owned memory definition used by the generated code
     vm_memory_definition: MaybeInstanceOwned<VMMemoryDefinition>,
+    /// The disjoint byte ranges of this memory which are read-only, sorted
+    /// by address.
+    read_only: Vec<Range<usize>>,
 }
 
 impl WasmMmap {
@@ -103,6 +107,7 @@ impl WasmMmap {
             new_mmap.as_mut_slice()[..copy_len].copy_from_slice(&self.alloc.as_slice()[..copy_len]);
 
             self.alloc = new_mmap;
+            self.protect_read_only()?;
         } else if delta_bytes > 0 {
             // Make the newly allocated pages accessible.
             self.alloc
@@ -138,10 +143,72 @@ impl WasmMmap {
 
     /// Resets the memory down to a zero size
     fn reset(&mut self) -> Result<(), MemoryError> {
+        for range in std::mem::take(&mut self.read_only) {
+            self.alloc
+                .protect(range.start, range.len(), false)
+                .map_err(MemoryError::Region)?;
+        }
         self.size.0 = 0;
         Ok(())
     }
 
+    /// Makes the bytes of the memory in `range` read-only, or writable again.
+    fn set_read_only(&mut self, range: Range<usize>, read_only: bool) -> Result<(), MemoryError> {
+        let page_size = region::page::size();
+        if range.start % page_size != 0 || range.end % page_size != 0 {
+            return Err(MemoryError::InvalidMemory {
+                reason: format!(
+                    "the range {range:?} is not aligned to the host page size ({page_size} bytes)"
+                ),
+            });
+        }
+        if range.start > range.end || range.end > self.size.bytes().0 {
+            return Err(MemoryError::InvalidMemory {
+                reason: format!(
+                    "the range {range:?} is out of the bounds of the memory ({} bytes)",
+                    self.size.bytes().0
+                ),
+            });
+        }
+        if range.is_empty() {
+            return Ok(());
+        }
+        self.alloc
+            .protect(range.start, range.len(), read_only)
+            .map_err(MemoryError::Region)?;
+
+        let mut ranges = Vec::with_capacity(self.read_only.len() + 1);
+        for existing in self.read_only.drain(..) {
+            if existing.end <= range.start || existing.start >= range.end {
+                ranges.push(existing);
+                continue;
+            }
+            if existing.start < range.start {
+                ranges.push(existing.start..range.start);
+            }
+            if existing.end > range.end {
+                ranges.push(range.end..existing.end);
+            }
+        }
+        if read_only {
+            ranges.push(range);
+        }
+        ranges.sort_by_key(|range| range.start);
+        self.read_only = ranges;
+        Ok(())
+    }
+
+    /// Makes the read-only ranges of the memory read-only in its current
+    /// allocation, after it has been moved.
+    fn protect_read_only(&mut self) -> Result<(), MemoryError> {
+        for range in &self.read_only {
+            self.alloc
+                .protect(range.start, range.len(), true)
+                .map_err(MemoryError::Region)?;
+        }
+        Ok(())
+    }
+
     /// Copies the memory
     /// (in this case it performs a copy-on-write to save memory)
     pub fn copy(&mut self) -> Result<Self, MemoryError> {
@@ -151,7 +218,7 @@ impl WasmMmap {
             .copy(Some(mem_length))
             .map_err(MemoryError::Generic)?;
         let base_ptr = alloc.as_mut_ptr();
-        Ok(Self {
+        let mut copy = Self {
             vm_memory_definition: MaybeInstanceOwned::Host(Box::new(UnsafeCell::new(
                 VMMemoryDefinition {
                     base: base_ptr,
@@ -160,7 +227,10 @@ impl WasmMmap {
             ))),
             alloc,
             size: self.size,
-        })
+            read_only: self.read_only.clone(),
+        };
+        copy.protect_read_only()?;
+        Ok(copy)
     }
 }
 
@@ -346,6 +416,7 @@ impl VMOwnedMemory {
             },
             alloc,
             size: Bytes::from(mem_length).try_into().unwrap(),
+            read_only: Vec::new(),
         };
 
         Ok(Self {
@@ -414,6 +485,11 @@ impl LinearMemory for VMOwnedMemory {
         Ok(())
     }
 
+    /// Makes the bytes of the memory in `range` read-only, or writable again.
+    fn set_read_only(&mut self, range: Range<usize>, read_only: bool) -> Result<(), MemoryError> {
+        self.mmap.set_read_only(range, read_only)
+    }
+
     /// Return a `VMMemoryDefinition` for exposing the memory to compiled wasm code.
     fn vmmemory(&self) -> NonNull<VMMemoryDefinition> {
         self.mmap.vm_memory_definition.as_ptr()
@@ -565,6 +641,12 @@ impl LinearMemory for VMSharedMemory {
         Ok(())
     }
 
+    /// Makes the bytes of the memory in `range` read-only, or writable again.
+    fn set_read_only(&mut self, range: Range<usize>, read_only: bool) -> Result<(), MemoryError> {
+        let mut guard = self.mmap.write().unwrap();
+        guard.set_read_only(range, read_only)
+    }
+
     /// Return a `VMMemoryDefinition` for exposing the memory to compiled wasm code.
     fn vmmemory(&self) -> NonNull<VMMemoryDefinition> {
         let guard = self.mmap.read().unwrap();
@@ -654,6 +736,11 @@ impl LinearMemory for VMMemory {
         Ok(())
     }
 
+    /// Makes the bytes of the memory in `range` read-only, or writable again.
+    fn set_read_only(&mut self, range: Range<usize>, read_only: bool) -> Result<(), MemoryError> {
+        self.0.set_read_only(range, read_only)
+    }
+
     /// Returns the memory style for this memory.
     fn style(&self) -> MemoryStyle {
         self.0.style()
@@ -811,6 +898,18 @@ where
         })
     }
 
+    /// Makes the bytes of the memory in `range` read-only, or readable and
+    /// writable again if `read_only` is false.
+    ///
+    /// The range must be aligned to the host page size. WebAssembly code
+    /// writing to a read-only range traps as if the access was out of
+    /// bounds.
+    fn set_read_only(&mut self, _range: Range<usize>, _read_only: bool) -> Result<(), MemoryError> {
+        Err(MemoryError::UnsupportedOperation {
+            message: "set_read_only() is not supported".to_string(),
+        })
+    }
+
     /// Return a `VMMemoryDefinition` for exposing the memory to compiled wasm code.
     fn vmmemory(&self) -> NonNull<VMMemoryDefinition>;
 
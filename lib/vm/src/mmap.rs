@@ -299,6 +299,26 @@ impl Mmap {
         Ok(())
     }
 
+    /// Make the memory starting at `start` and extending for `len` bytes
+    /// read-only, or readable and writable again. `start` and `len` must be
+    /// native page-size multiples and describe a range within `self`'s
+    /// accessible memory.
+    pub fn protect(&mut self, start: usize, len: usize, read_only: bool) -> Result<(), String> {
+        let page_size = region::page::size();
+        assert_eq!(start & (page_size - 1), 0);
+        assert_eq!(len & (page_size - 1), 0);
+        assert_le!(len, self.len());
+        assert_le!(start, self.len() - len);
+
+        let protection = if read_only {
+            region::Protection::READ
+        } else {
+            region::Protection::READ_WRITE
+        };
+        let ptr = self.ptr as *const u8;
+        unsafe { region::protect(ptr.add(start), len, protection) }.map_err(|e| e.to_string())
+    }
+
     /// Return the allocated memory as a slice of u8.
     pub fn as_slice(&self) -> &[u8] {
         unsafe { slice::from_raw_parts(self.ptr as *const u8, self.total_size) }
@@ -1,9 +1,13 @@
 use crate::{
-    VMExternObj, VMFunction, VMFunctionEnvironment, VMGlobal, VMInstance, VMMemory, VMTable,
+    LinearMemory, VMExternObj, VMFunction, VMFunctionEnvironment, VMGlobal, VMInstance, VMMemory,
+    VMTable,
 };
 use core::slice::Iter;
+use derivative::Derivative;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::{cell::UnsafeCell, fmt, marker::PhantomData, num::NonZeroUsize, ptr::NonNull};
-use wasmer_types::StoreId;
+use wasmer_types::{GlobalType, Mutability, Pages, StoreId, Type};
 
 /// Trait to represent an object managed by a context. This is implemented on
 /// the VM types managed by the context.
@@ -35,8 +39,14 @@ impl_context_object! {
     function_environments => VMFunctionEnvironment,
 }
 
+/// Callback called after a memory of a store has grown, with the memory,
+/// and its old and new sizes.
+pub type MemoryGrowCallback =
+    Box<dyn FnMut(InternalStoreHandle<VMMemory>, Pages, Pages) + Send + Sync>;
+
 /// Set of objects managed by a context.
-#[derive(Debug, Default)]
+#[derive(Derivative, Default)]
+#[derivative(Debug)]
 pub struct StoreObjects {
     id: StoreId,
     memories: Vec<VMMemory>,
@@ -46,6 +56,22 @@ pub struct StoreObjects {
     instances: Vec<VMInstance>,
     extern_objs: Vec<VMExternObj>,
     function_environments: Vec<VMFunctionEnvironment>,
+    epoch: Arc<VMEpoch>,
+    fuel: Option<StoreFuel>,
+    #[derivative(Debug = "ignore")]
+    memory_grow_callback: Option<MemoryGrowCallback>,
+    /// The largest sizes reached by the memories that have grown, by
+    /// handle index.
+    memory_peaks: Vec<Pages>,
+}
+
+/// The fuel of a store, held in globals shared by the metered instances
+/// created in it.
+#[derive(Debug)]
+struct StoreFuel {
+    remaining: InternalStoreHandle<VMGlobal>,
+    exhausted: InternalStoreHandle<VMGlobal>,
+    added: u64,
 }
 
 impl StoreObjects {
@@ -59,6 +85,114 @@ impl StoreObjects {
         self.id = id;
     }
 
+    /// Returns the epoch state of this store.
+    pub fn epoch(&self) -> &Arc<VMEpoch> {
+        &self.epoch
+    }
+
+    /// Sets the epoch counter this store is checked against, usually the
+    /// one of its engine.
+    ///
+    /// This must be done before any instance is created in this store.
+    pub fn set_epoch_counter(&mut self, counter: Arc<AtomicU64>) {
+        self.epoch = Arc::new(VMEpoch::new(counter));
+    }
+
+    /// Adds fuel to this store, enabling fuel consumption if it wasn't.
+    ///
+    /// This also clears the out of fuel state, so that execution can
+    /// resume.
+    pub fn add_fuel(&mut self, fuel: u64) {
+        let fuel_state = match self.fuel.take() {
+            Some(fuel_state) => fuel_state,
+            None => StoreFuel {
+                remaining: InternalStoreHandle::new(
+                    self,
+                    VMGlobal::new(GlobalType::new(Type::I64, Mutability::Var)),
+                ),
+                exhausted: InternalStoreHandle::new(
+                    self,
+                    VMGlobal::new(GlobalType::new(Type::I32, Mutability::Var)),
+                ),
+                added: 0,
+            },
+        };
+        unsafe {
+            let remaining = &mut fuel_state.remaining.get(self).vmglobal().as_mut().val;
+            remaining.u64 = remaining.u64.saturating_add(fuel);
+            fuel_state.exhausted.get(self).vmglobal().as_mut().val.i32 = 0;
+        }
+        self.fuel = Some(StoreFuel {
+            added: fuel_state.added.saturating_add(fuel),
+            ..fuel_state
+        });
+    }
+
+    /// Returns the globals holding the remaining fuel and whether it is
+    /// exhausted, if fuel consumption is enabled.
+    pub fn fuel_globals(
+        &self,
+    ) -> Option<(InternalStoreHandle<VMGlobal>, InternalStoreHandle<VMGlobal>)> {
+        self.fuel
+            .as_ref()
+            .map(|fuel| (fuel.remaining, fuel.exhausted))
+    }
+
+    /// Returns the remaining fuel, if fuel consumption is enabled.
+    pub fn fuel_remaining(&self) -> Option<u64> {
+        let fuel = self.fuel.as_ref()?;
+        Some(unsafe { fuel.remaining.get(self).vmglobal().as_ref().val.u64 })
+    }
+
+    /// Returns the fuel consumed since fuel consumption was enabled.
+    pub fn fuel_consumed(&self) -> Option<u64> {
+        let added = self.fuel.as_ref()?.added;
+        Some(added.saturating_sub(self.fuel_remaining()?))
+    }
+
+    /// Returns whether execution was stopped because the fuel ran out.
+    pub fn is_out_of_fuel(&self) -> bool {
+        self.fuel.as_ref().map_or(false, |fuel| unsafe {
+            fuel.exhausted.get(self).vmglobal().as_ref().val.i32 != 0
+        })
+    }
+
+    /// Sets the callback called after a memory of the store has grown.
+    pub fn set_memory_grow_callback(&mut self, callback: Option<MemoryGrowCallback>) {
+        self.memory_grow_callback = callback;
+    }
+
+    /// Grows a memory of the store, calling the memory grow callback if it
+    /// has grown.
+    pub fn grow_memory<T, E>(
+        &mut self,
+        memory: InternalStoreHandle<VMMemory>,
+        grow: impl FnOnce(&mut VMMemory) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let vm_memory = memory.get_mut(self);
+        let old_size = vm_memory.size();
+        let result = grow(vm_memory)?;
+        let new_size = memory.get(self).size();
+        if new_size > old_size {
+            let index = memory.index() - 1;
+            if self.memory_peaks.len() <= index {
+                self.memory_peaks.resize(index + 1, Pages(0));
+            }
+            self.memory_peaks[index] = self.memory_peaks[index].max(new_size);
+            if let Some(callback) = self.memory_grow_callback.as_mut() {
+                callback(memory, old_size, new_size);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns the largest size reached by a memory of the store since it
+    /// was created.
+    pub fn memory_peak(&self, memory: InternalStoreHandle<VMMemory>) -> Pages {
+        let peak = self.memory_peaks.get(memory.index() - 1).copied();
+        memory.get(self).size().max(peak.unwrap_or(Pages(0)))
+    }
+
     /// Returns a pair of mutable references from two handles.
     ///
     /// Panics if both handles point to the same object.
@@ -68,13 +202,15 @@ impl StoreObjects {
         b: InternalStoreHandle<T>,
     ) -> (&mut T, &mut T) {
         assert_ne!(a.index(), b.index());
+        // Handle indices start at 1.
+        let (a, b) = (a.index() - 1, b.index() - 1);
         let list = T::list_mut(self);
-        if a.index() < b.index() {
-            let (low, high) = list.split_at_mut(b.index());
-            (&mut low[a.index()], &mut high[0])
+        if a < b {
+            let (low, high) = list.split_at_mut(b);
+            (&mut low[a], &mut high[0])
         } else {
-            let (low, high) = list.split_at_mut(a.index());
-            (&mut high[0], &mut low[a.index()])
+            let (low, high) = list.split_at_mut(a);
+            (&mut high[0], &mut low[b])
         }
     }
 
@@ -83,6 +219,11 @@ impl StoreObjects {
         self.globals.iter()
     }
 
+    /// Return an immutable iterator over all instances
+    pub fn iter_instances(&self) -> Iter<VMInstance> {
+        self.instances.iter()
+    }
+
     /// Return an vector of all globals and converted to u128
     pub fn as_u128_globals(&self) -> Vec<u128> {
         self.iter_globals()
@@ -101,6 +242,58 @@ impl StoreObjects {
     }
 }
 
+/// The epoch state of a store, read by the epoch checks of compiled code.
+///
+/// Code compiled with epoch interruption traps once the epoch counter
+/// reaches the deadline.
+#[derive(Debug)]
+pub struct VMEpoch {
+    counter: Arc<AtomicU64>,
+    deadline: AtomicU64,
+}
+
+impl VMEpoch {
+    /// Creates an epoch state with the given counter, and no deadline.
+    pub fn new(counter: Arc<AtomicU64>) -> Self {
+        Self {
+            counter,
+            deadline: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    /// Returns the current epoch.
+    pub fn current(&self) -> u64 {
+        self.counter.load(Ordering::Relaxed)
+    }
+
+    /// Returns the deadline.
+    pub fn deadline(&self) -> u64 {
+        self.deadline.load(Ordering::Relaxed)
+    }
+
+    /// Sets the deadline to `ticks` epochs after the current one.
+    pub fn set_deadline(&self, ticks: u64) {
+        self.deadline
+            .store(self.current().saturating_add(ticks), Ordering::Relaxed);
+    }
+
+    /// Returns a pointer to the epoch counter, for the `vmctx`.
+    pub fn counter_ptr(&self) -> *const AtomicU64 {
+        Arc::as_ptr(&self.counter)
+    }
+
+    /// Returns a pointer to the deadline, for the `vmctx`.
+    pub fn deadline_ptr(&self) -> *const AtomicU64 {
+        &self.deadline
+    }
+}
+
+impl Default for VMEpoch {
+    fn default() -> Self {
+        Self::new(Arc::new(AtomicU64::new(0)))
+    }
+}
+
 /// Handle to an object managed by a context.
 ///
 /// Internally this is just an integer index into a context. A reference to the
@@ -311,6 +311,55 @@ impl VMTable {
         Ok(())
     }
 
+    /// Fill `len` elements of the table, starting at `start_index`, with
+    /// `item`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds of the table.
+    pub fn fill(&mut self, start_index: u32, item: TableElement, len: u32) -> Result<(), Trap> {
+        // https://webassembly.github.io/bulk-memory-operations/core/exec/instructions.html#exec-table-fill
+
+        if start_index
+            .checked_add(len)
+            .map_or(true, |n| n > self.size())
+        {
+            return Err(Trap::lib(TrapCode::TableAccessOutOfBounds));
+        }
+
+        for i in start_index..(start_index + len) {
+            self.set(i, item.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the elements of the table starting at `start_index` to `items`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds of the table, in
+    /// which case no element is set.
+    pub fn copy_from_slice(
+        &mut self,
+        start_index: u32,
+        items: &[TableElement],
+    ) -> Result<(), Trap> {
+        if u32::try_from(items.len())
+            .ok()
+            .and_then(|len| start_index.checked_add(len))
+            .map_or(true, |n| n > self.size())
+        {
+            return Err(Trap::lib(TrapCode::TableAccessOutOfBounds));
+        }
+
+        for (i, item) in (start_index..).zip(items) {
+            self.set(i, item.clone())?;
+        }
+
+        Ok(())
+    }
+
     /// Copies the table into a new table
     pub fn copy_on_write(&self) -> Result<Self, String> {
         let mut ret = Self::new(&self.table, &self.style)?;
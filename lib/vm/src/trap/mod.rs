@@ -10,8 +10,9 @@ mod traphandlers;
 
 pub use trap::Trap;
 pub use traphandlers::{
-    catch_traps, on_host_stack, raise_lib_trap, raise_user_trap, set_stack_size,
-    wasmer_call_trampoline, TrapHandlerFn, VMConfig,
+    block_on_wasm_stack, catch_traps, catch_traps_async, on_host_stack, raise_lib_trap,
+    raise_user_trap, set_stack_size, wasmer_call_trampoline, wasmer_call_trampoline_async,
+    AsyncCall, TrapHandlerFn, VMConfig,
 };
 pub use traphandlers::{init_traps, resume_panic};
 pub use wasmer_types::TrapCode;
@@ -15,13 +15,16 @@ use scopeguard::defer;
 use std::any::Any;
 use std::cell::Cell;
 use std::error::Error;
+use std::future::Future;
 use std::io;
 use std::mem;
 #[cfg(unix)]
 use std::mem::MaybeUninit;
+use std::pin::Pin;
 use std::ptr::{self, NonNull};
 use std::sync::atomic::{compiler_fence, AtomicPtr, AtomicUsize, Ordering};
 use std::sync::Once;
+use std::task::{Context, Poll};
 use wasmer_types::TrapCode;
 
 /// Configuration for the runtime VM
@@ -130,6 +133,8 @@ unsafe fn process_illegal_op(addr: usize) -> Option<TrapCode> {
             8 => Some(TrapCode::BadConversionToInteger),
             9 => Some(TrapCode::UnreachableCodeReached),
             10 => Some(TrapCode::UnalignedAtomic),
+            11 => Some(TrapCode::Interrupt),
+            13 => Some(TrapCode::FrozenGlobal),
             _ => None,
         },
     }
@@ -701,17 +706,197 @@ where
     on_wasm_stack(stack_size, trap_handler, closure).map_err(UnwindReason::into_trap)
 }
 
-// We need two separate thread-local variables here:
+/// Call the wasm function pointed to by `callee` asynchronously.
+///
+/// This is the same as [`wasmer_call_trampoline`], except that the call
+/// is suspended whenever a host function waits on a pending future with
+/// [`block_on_wasm_stack`], instead of blocking the thread.
+///
+/// # Safety
+///
+/// Same as [`wasmer_call_trampoline`] and [`catch_traps_async`].
+pub unsafe fn wasmer_call_trampoline_async<'a>(
+    trap_handler: Option<*const TrapHandlerFn<'static>>,
+    config: &VMConfig,
+    vmctx: VMFunctionContext,
+    trampoline: VMTrampoline,
+    callee: *const VMFunctionBody,
+    values_vec: *mut u8,
+) -> AsyncCall<'a, ()> {
+    catch_traps_async(trap_handler, config, move || {
+        mem::transmute::<_, extern "C" fn(VMFunctionContext, *const VMFunctionBody, *mut u8)>(
+            trampoline,
+        )(vmctx, callee, values_vec);
+    })
+}
+
+/// Returns a future running `closure` on a separate stack, catching any
+/// wasm traps that happen within its execution.
+///
+/// Unlike [`catch_traps`], the stack can be suspended by host functions
+/// waiting on a pending future with [`block_on_wasm_stack`], in which case
+/// the returned future is pending as well.
+///
+/// # Safety
+///
+/// Same as [`catch_traps`]. Additionally, since the returned future may be
+/// resumed on another thread, everything living on the wasm stack across a
+/// suspension must be `Send`. If the future is dropped while the stack is
+/// suspended, the stack is discarded without running any destructors.
+pub unsafe fn catch_traps_async<'a, F, R>(
+    trap_handler: Option<*const TrapHandlerFn<'static>>,
+    config: &VMConfig,
+    closure: F,
+) -> AsyncCall<'a, R>
+where
+    F: FnOnce() -> R + 'a,
+    R: 'a,
+{
+    let stack_size = config
+        .wasm_stack_size
+        .unwrap_or_else(|| DEFAULT_STACK_SIZE.load(Ordering::Relaxed));
+    let coro = ScopedCoroutine::with_stack(get_stack(stack_size), move |yielder, ()| {
+        YIELDER.with(|cell| cell.set(Some(yielder.into())));
+
+        Ok(closure())
+    });
+    AsyncCall {
+        coro: Some(coro),
+        trap_handler,
+    }
+}
+
+/// Runs a poll function from a host function called by wasm code, until it
+/// is ready.
+///
+/// When the wasm code was called with [`catch_traps_async`], the wasm stack
+/// is suspended whenever the poll function is pending, and resumed when the
+/// future returned by `catch_traps_async` is polled again. The poll function
+/// itself is always called on the host stack.
+///
+/// Returns `None` if the wasm code wasn't called asynchronously, or if this
+/// isn't called from the wasm stack.
+pub fn block_on_wasm_stack<T>(mut poll: impl FnMut(&mut Context<'_>) -> Poll<T>) -> Option<T> {
+    YIELDER.with(|cell| cell.get())?;
+    loop {
+        let cx = ASYNC_CX.with(|cell| cell.get())?;
+        // The context is valid for as long as the wasm stack is running.
+        if let Poll::Ready(value) = on_host_stack(|| poll(unsafe { &mut *cx.as_ptr() })) {
+            return Some(value);
+        }
+
+        let yielder = YIELDER.with(|cell| cell.replace(None))?;
+        unsafe {
+            yielder.as_ref().suspend(Suspend::Pending);
+        }
+        // We may have been resumed on another thread.
+        YIELDER.with(|cell| cell.set(Some(yielder)));
+    }
+}
+
+/// A future running wasm code on a separate stack, returned by
+/// [`catch_traps_async`].
+pub struct AsyncCall<'a, R> {
+    #[allow(clippy::type_complexity)]
+    coro: Option<ScopedCoroutine<'a, (), Suspend, Result<R, UnwindReason>, DefaultStack>>,
+    trap_handler: Option<*const TrapHandlerFn<'static>>,
+}
+
+// The safety requirements of `catch_traps_async` ensure that the suspended
+// stack can be sent to another thread.
+unsafe impl<'a, R: Send> Send for AsyncCall<'a, R> {}
+
+impl<'a, R> Future for AsyncCall<'a, R> {
+    type Output = Result<R, Trap>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // Each poll may happen on a different thread.
+        if let Err(trap) = lazy_per_thread_init() {
+            return Poll::Ready(Err(trap));
+        }
+        let coro = this
+            .coro
+            .as_mut()
+            .expect("`AsyncCall` polled after completion");
+
+        let prev_cx =
+            ASYNC_CX.with(|cell| cell.replace(Some(NonNull::from(cx).cast::<Context<'static>>())));
+        defer! {
+            YIELDER.with(|cell| cell.set(None));
+            ASYNC_CX.with(|cell| cell.set(prev_cx));
+        }
+
+        let result = TrapHandlerContext::install(this.trap_handler, coro.trap_handler(), || {
+            match coro.resume(()) {
+                CoroutineResult::Yield(Suspend::Pending) => None,
+                CoroutineResult::Yield(Suspend::Unwind(trap)) => {
+                    // This came from unwind_with which requires that there be
+                    // only Wasm code on the stack.
+                    unsafe {
+                        coro.force_reset();
+                    }
+                    Some(Err(trap))
+                }
+                CoroutineResult::Return(result) => Some(result),
+            }
+        });
+        match result {
+            None => Poll::Pending,
+            Some(result) => {
+                let coro = this.coro.take().unwrap();
+                STACK_POOL.push(coro.into_stack());
+                Poll::Ready(result.map_err(UnwindReason::into_trap))
+            }
+        }
+    }
+}
+
+impl<'a, R> Drop for AsyncCall<'a, R> {
+    fn drop(&mut self) {
+        if let Some(mut coro) = self.coro.take() {
+            if !coro.started() {
+                coro.force_unwind();
+            } else if !coro.done() {
+                // The stack is suspended in `block_on_wasm_stack`. Unwinding
+                // it would need to go through wasm frames, so just discard it.
+                unsafe {
+                    coro.force_reset();
+                }
+            }
+            STACK_POOL.push(coro.into_stack());
+        }
+    }
+}
+
+// We need three separate thread-local variables here:
 // - YIELDER is set within the new stack and is used to unwind back to the root
 //   of the stack from inside it.
 // - TRAP_HANDLER is set from outside the new stack and is solely used from
 //   signal handlers. It must be atomic since it is used by signal handlers.
+// - ASYNC_CX is set from outside the new stack when it is run by an
+//   `AsyncCall`, and is used to poll futures from inside it.
 //
 // We also do per-thread signal stack initialization on the first time
 // TRAP_HANDLER is accessed.
 thread_local! {
-    static YIELDER: Cell<Option<NonNull<Yielder<(), UnwindReason>>>> = Cell::new(None);
+    static YIELDER: Cell<Option<NonNull<Yielder<(), Suspend>>>> = Cell::new(None);
     static TRAP_HANDLER: AtomicPtr<TrapHandlerContext> = AtomicPtr::new(ptr::null_mut());
+    static ASYNC_CX: Cell<Option<NonNull<Context<'static>>>> = Cell::new(None);
+}
+
+// Allocating a new stack is pretty expensive since it involves several
+// system calls. We therefore keep a cache of pre-allocated stacks which
+// allows them to be reused multiple times.
+// FIXME(Amanieu): We should refactor this to avoid the lock.
+lazy_static::lazy_static! {
+    static ref STACK_POOL: crossbeam_queue::SegQueue<DefaultStack> = crossbeam_queue::SegQueue::new();
+}
+
+fn get_stack(stack_size: usize) -> DefaultStack {
+    STACK_POOL
+        .pop()
+        .unwrap_or_else(|| DefaultStack::new(stack_size).unwrap())
 }
 
 /// Read-only information that is used by signal handlers to handle and recover
@@ -870,6 +1055,14 @@ impl<T> TrapHandlerContextInner<T> {
     }
 }
 
+/// Why the wasm stack was suspended.
+enum Suspend {
+    /// Unwinding back to the root of the stack
+    Unwind(UnwindReason),
+    /// Waiting on a pending future in `block_on_wasm_stack`
+    Pending,
+}
+
 enum UnwindReason {
     /// A panic caused by the host
     Panic(Box<dyn Any + Send>),
@@ -905,7 +1098,7 @@ unsafe fn unwind_with(reason: UnwindReason) -> ! {
         .with(|cell| cell.replace(None))
         .expect("not running on Wasm stack");
 
-    yielder.as_ref().suspend(reason);
+    yielder.as_ref().suspend(Suspend::Unwind(reason));
 
     // on_wasm_stack will forcibly reset the coroutine stack after yielding.
     unreachable!();
@@ -919,16 +1112,7 @@ fn on_wasm_stack<F: FnOnce() -> T, T>(
     trap_handler: Option<*const TrapHandlerFn<'static>>,
     f: F,
 ) -> Result<T, UnwindReason> {
-    // Allocating a new stack is pretty expensive since it involves several
-    // system calls. We therefore keep a cache of pre-allocated stacks which
-    // allows them to be reused multiple times.
-    // FIXME(Amanieu): We should refactor this to avoid the lock.
-    lazy_static::lazy_static! {
-        static ref STACK_POOL: crossbeam_queue::SegQueue<DefaultStack> = crossbeam_queue::SegQueue::new();
-    }
-    let stack = STACK_POOL
-        .pop()
-        .unwrap_or_else(|| DefaultStack::new(stack_size).unwrap());
+    let stack = get_stack(stack_size);
     let mut stack = scopeguard::guard(stack, |stack| STACK_POOL.push(stack));
 
     // Create a coroutine with a new stack to run the function on.
@@ -939,16 +1123,21 @@ fn on_wasm_stack<F: FnOnce() -> T, T>(
         Ok(f())
     });
 
+    // Futures can't be awaited from a synchronous call, even when it is
+    // nested in an asynchronous one.
+    let prev_cx = ASYNC_CX.with(|cell| cell.replace(None));
+
     // Ensure that YIELDER is reset on exit even if the coroutine panics,
     defer! {
         YIELDER.with(|cell| cell.set(None));
+        ASYNC_CX.with(|cell| cell.set(prev_cx));
     }
 
     // Set up metadata for the trap handler for the duration of the coroutine
     // execution. This is restored to its previous value afterwards.
     TrapHandlerContext::install(trap_handler, coro.trap_handler(), || {
         match coro.resume(()) {
-            CoroutineResult::Yield(trap) => {
+            CoroutineResult::Yield(Suspend::Unwind(trap)) => {
                 // This came from unwind_with which requires that there be only
                 // Wasm code on the stack.
                 unsafe {
@@ -956,6 +1145,9 @@ fn on_wasm_stack<F: FnOnce() -> T, T>(
                 }
                 Err(trap)
             }
+            CoroutineResult::Yield(Suspend::Pending) => {
+                unreachable!("block_on_wasm_stack can't suspend a synchronous call")
+            }
             CoroutineResult::Return(result) => result,
         }
     })
@@ -488,12 +488,16 @@ mod test_vmtable_definition {
 pub struct VMGlobalDefinition {
     /// Raw value of the global.
     pub val: RawValue,
+    /// Whether the global was frozen by the host, in which case writes from
+    /// WebAssembly code trap.
+    pub frozen: u32,
 }
 
 #[cfg(test)]
 mod test_vmglobal_definition {
     use super::VMGlobalDefinition;
     use crate::{VMFuncRef, VMOffsets};
+    use memoffset::offset_of;
     use more_asserts::assert_ge;
     use std::mem::{align_of, size_of};
     use wasmer_types::ModuleInfo;
@@ -518,6 +522,16 @@ mod test_vmglobal_definition {
         );
     }
 
+    #[test]
+    fn check_vmglobal_definition_frozen_offset() {
+        let module = ModuleInfo::new();
+        let offsets = VMOffsets::new(size_of::<*mut u8>() as u8, &module);
+        assert_eq!(
+            offset_of!(VMGlobalDefinition, frozen),
+            usize::from(offsets.vmglobal_definition_frozen())
+        );
+    }
+
     #[test]
     fn check_vmglobal_begins_aligned() {
         let module = ModuleInfo::new();
@@ -531,6 +545,7 @@ impl VMGlobalDefinition {
     pub fn new() -> Self {
         Self {
             val: Default::default(),
+            frozen: 0,
         }
     }
 }
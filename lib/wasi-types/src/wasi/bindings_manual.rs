@@ -22,6 +22,71 @@ impl Rights {
         )
     }
 
+    /// The rights needed to read from a directory and the files inside it.
+    pub const fn dir_read() -> Self {
+        Self::from_bits_truncate(
+            Self::FD_READ.bits()
+                | Self::PATH_OPEN.bits()
+                | Self::FD_READDIR.bits()
+                | Self::PATH_READLINK.bits()
+                | Self::PATH_FILESTAT_GET.bits()
+                | Self::FD_FILESTAT_GET.bits()
+                | Self::PATH_LINK_SOURCE.bits()
+                | Self::POLL_FD_READWRITE.bits()
+                | Self::SOCK_SHUTDOWN.bits(),
+        )
+    }
+
+    /// The rights needed to modify and remove the files inside a directory.
+    pub const fn dir_write() -> Self {
+        // Moving a file out of a directory modifies it, so it
+        // requires the same rights as deleting the file
+        Self::from_bits_truncate(
+            Self::FD_DATASYNC.bits()
+                | Self::PATH_RENAME_SOURCE.bits()
+                | Self::FD_FDSTAT_SET_FLAGS.bits()
+                | Self::FD_WRITE.bits()
+                | Self::FD_SYNC.bits()
+                | Self::FD_ALLOCATE.bits()
+                | Self::PATH_OPEN.bits()
+                | Self::PATH_RENAME_TARGET.bits()
+                | Self::PATH_FILESTAT_SET_SIZE.bits()
+                | Self::PATH_FILESTAT_SET_TIMES.bits()
+                | Self::FD_FILESTAT_SET_SIZE.bits()
+                | Self::FD_FILESTAT_SET_TIMES.bits()
+                | Self::PATH_REMOVE_DIRECTORY.bits()
+                | Self::PATH_UNLINK_FILE.bits()
+                | Self::POLL_FD_READWRITE.bits()
+                | Self::SOCK_SHUTDOWN.bits(),
+        )
+    }
+
+    /// The rights needed to create new files and directories inside a
+    /// directory.
+    pub const fn dir_create() -> Self {
+        Self::from_bits_truncate(
+            Self::PATH_CREATE_DIRECTORY.bits()
+                | Self::PATH_CREATE_FILE.bits()
+                | Self::PATH_LINK_TARGET.bits()
+                | Self::PATH_OPEN.bits()
+                | Self::PATH_RENAME_TARGET.bits()
+                | Self::PATH_SYMLINK.bits(),
+        )
+    }
+
+    /// Looks up a single right by its name in snake case (e.g. `fd_read`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..u64::BITS)
+            .map(|bit| Self::from_bits_truncate(1 << bit))
+            .filter(|right| !right.is_empty())
+            .find(|right| {
+                right
+                    .to_str()
+                    .and_then(|s| s.strip_prefix("Rights::"))
+                    .is_some_and(|s| s.eq_ignore_ascii_case(name))
+            })
+    }
+
     /// expects a single right, returns None if out of bounds or > 1 bit set
     pub fn to_str(self) -> Option<&'static str> {
         Some(match self {
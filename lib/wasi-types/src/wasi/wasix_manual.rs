@@ -374,6 +374,22 @@ wai_bindgen_rust::bitflags::bitflags! {
     }
 }
 
+wai_bindgen_rust::bitflags::bitflags! {
+    #[doc = " Kinds of change reported by a file watch (see `fd_watch`)."]
+    #[doc = " Each event read from the watch is encoded as a little endian"]
+    #[doc = " `u32` event type, a `u32` name length and then the name of the"]
+    #[doc = " entry that changed."]
+    #[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
+    pub struct WatchEventType : u32 {
+        #[doc = " An entry was created in the watched directory."]
+        const CREATE = 1 << 0;
+        #[doc = " The contents or size of an entry were modified."]
+        const MODIFY = 1 << 1;
+        #[doc = " An entry was removed from the watched directory."]
+        const DELETE = 1 << 2;
+    }
+}
+
 #[doc = " Epoll operation."]
 #[repr(u32)]
 #[derive(Clone, Copy, PartialEq, Eq, num_enum :: TryFromPrimitive, Hash)]
@@ -329,6 +329,8 @@ fn call_module(
     };
 
     // Cleanup the environment
+    #[cfg(feature = "sys")]
+    ctx.record_memory_usage(&store);
     ctx.data(&store).blocking_on_exit(Some(code));
     unsafe { run_recycle(recycle, ctx, store) };
 
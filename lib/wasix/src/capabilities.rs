@@ -60,6 +60,12 @@ pub struct CapabilityThreadingV1 {
     /// Switches to a blocking sleep implementation instead
     /// of the asynchronous runtime based implementation
     pub enable_blocking_sleep: bool,
+
+    /// Busy-waits for the final stretch of every sleep (up to the given
+    /// threshold) instead of relying on the timers of the runtime, which
+    /// trades CPU time for more accurate wake ups
+    /// (default = off)
+    pub enable_precise_timers: Option<Duration>,
 }
 
 impl CapabilityThreadingV1 {
@@ -69,6 +75,7 @@ impl CapabilityThreadingV1 {
             enable_asynchronous_threading,
             enable_exponential_cpu_backoff,
             enable_blocking_sleep,
+            enable_precise_timers,
         } = other;
         self.enable_asynchronous_threading |= enable_asynchronous_threading;
         if let Some(val) = enable_exponential_cpu_backoff {
@@ -76,5 +83,8 @@ impl CapabilityThreadingV1 {
         }
         self.max_threads = max_threads.or(self.max_threads);
         self.enable_blocking_sleep |= enable_blocking_sleep;
+        if let Some(val) = enable_precise_timers {
+            self.enable_precise_timers = Some(val);
+        }
     }
 }
@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use ahash::AHashMap;
+use wasmer_wasix_types::wasi::{Errno, Fd as WasiFd};
+
+use super::Fd;
+
+/// Maximum number of file watches (see `fd_watch`) that can be active at the
+/// same time, as each of them periodically scans the file system.
+pub(crate) const MAX_WATCHES: usize = 64;
+
+/// Quotas applied to the file system of a WASI instance so that a single
+/// guest can not exhaust the resources of the host.
+///
+/// Every limit is optional, `None` means that the resource is not limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FsLimits {
+    /// Maximum number of file descriptors that can be open at the same time
+    /// (including stdio and the preopened directories).
+    pub max_open_fds: Option<u32>,
+    /// Maximum number of bytes that can be written to files over the
+    /// lifetime of the instance.
+    pub max_bytes_written: Option<u64>,
+    /// Maximum size that a file can be grown to.
+    pub max_file_size: Option<u64>,
+}
+
+impl FsLimits {
+    pub fn with_max_open_fds(mut self, max: u32) -> Self {
+        self.max_open_fds = Some(max);
+        self
+    }
+
+    pub fn with_max_bytes_written(mut self, max: u64) -> Self {
+        self.max_bytes_written = Some(max);
+        self
+    }
+
+    pub fn with_max_file_size(mut self, max: u64) -> Self {
+        self.max_file_size = Some(max);
+        self
+    }
+}
+
+/// Tracks the usage of the resources restricted by [`FsLimits`].
+///
+/// The state is shared between forked processes so they draw from the
+/// same quota.
+#[derive(Debug, Default)]
+pub(crate) struct FsQuota {
+    limits: FsLimits,
+    bytes_written: AtomicU64,
+    watches: AtomicUsize,
+}
+
+impl FsQuota {
+    pub fn new(limits: FsLimits) -> Self {
+        Self {
+            limits,
+            bytes_written: AtomicU64::new(0),
+            watches: AtomicUsize::new(0),
+        }
+    }
+
+    /// Checks that inserting `idx` into the file descriptor table will not
+    /// exceed the maximum number of open file descriptors.
+    pub fn check_new_fd(&self, fd_map: &AHashMap<WasiFd, Fd>, idx: WasiFd) -> Result<(), Errno> {
+        match self.limits.max_open_fds {
+            Some(max) if !fd_map.contains_key(&idx) && fd_map.len() >= max as usize => {
+                Err(Errno::Mfile)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a file may be grown to `size` bytes.
+    pub fn check_file_size(&self, size: u64) -> Result<(), Errno> {
+        match self.limits.max_file_size {
+            Some(max) if size > max => Err(Errno::Fbig),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reserves `len` bytes of the write quota for a write at `offset`.
+    ///
+    /// Any bytes that end up not being written must be handed back with
+    /// [`FsQuota::release_write`].
+    pub fn reserve_write(&self, offset: u64, len: u64) -> Result<(), Errno> {
+        self.check_file_size(offset.checked_add(len).ok_or(Errno::Fbig)?)?;
+
+        let max = match self.limits.max_bytes_written {
+            Some(max) => max,
+            None => return Ok(()),
+        };
+        self.bytes_written
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(len).filter(|total| *total <= max)
+            })
+            .map(|_| ())
+            .map_err(|_| Errno::Dquot)
+    }
+
+    /// Hands back part of a reservation made by [`FsQuota::reserve_write`].
+    pub fn release_write(&self, len: u64) {
+        if self.limits.max_bytes_written.is_some() {
+            self.bytes_written.fetch_sub(len, Ordering::AcqRel);
+        }
+    }
+
+    /// Reserves one of the [`MAX_WATCHES`] file watches.
+    ///
+    /// The watch must be handed back with [`FsQuota::release_watch`] once
+    /// it stops.
+    pub fn reserve_watch(&self) -> Result<(), Errno> {
+        self.watches
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                Some(active + 1).filter(|total| *total <= MAX_WATCHES)
+            })
+            .map(|_| ())
+            .map_err(|_| Errno::Nospc)
+    }
+
+    /// Hands back a watch reserved by [`FsQuota::reserve_watch`].
+    pub fn release_watch(&self) {
+        self.watches.fetch_sub(1, Ordering::AcqRel);
+    }
+}
@@ -1,6 +1,9 @@
 mod fd;
 mod inode_guard;
+mod limits;
 mod notification;
+mod proc_fs;
+mod watch;
 
 use std::{
     borrow::{Borrow, Cow},
@@ -41,7 +44,11 @@ pub(crate) use self::inode_guard::{
     InodeValFilePollGuard, InodeValFilePollGuardJoin, InodeValFilePollGuardMode,
     InodeValFileReadGuard, InodeValFileWriteGuard, WasiStateFileGuard, POLL_GUARD_MAX_RET,
 };
+pub use self::limits::FsLimits;
+pub(crate) use self::limits::FsQuota;
 pub use self::notification::NotificationInner;
+pub(crate) use self::proc_fs::{ProcFileSystem, ProcInfo};
+pub(crate) use self::watch::spawn_watch;
 use crate::syscalls::map_io_err;
 use crate::{bin_factory::BinaryPackage, state::PreopenedDir, ALL_RIGHTS};
 
@@ -507,6 +514,36 @@ impl WasiFdSeed {
     }
 }
 
+/// A file system that several instances can be attached to with
+/// [`WasiEnvBuilder::shared_fs`](crate::WasiEnvBuilder::shared_fs).
+///
+/// All attached instances see the same files and file descriptor table, so a
+/// file opened by one of them can be used by the others. The descriptors are
+/// only closed once the last instance exits and this handle is dropped.
+#[derive(Debug, Clone)]
+pub struct SharedWasiFs {
+    inodes: WasiInodes,
+    fs: Arc<WasiFs>,
+}
+
+impl SharedWasiFs {
+    pub fn new(inodes: WasiInodes, fs: WasiFs) -> Self {
+        Self {
+            inodes,
+            fs: Arc::new(fs),
+        }
+    }
+
+    /// The root file system that is shared.
+    pub fn root_fs(&self) -> &WasiFsRoot {
+        &self.fs.root_fs
+    }
+
+    pub(crate) fn attach(&self) -> (WasiInodes, WasiFs) {
+        (self.inodes.clone(), self.fs.share())
+    }
+}
+
 /// Warning, modifying these fields directly may cause invariants to break and
 /// should be considered unsafe.  These fields may be made private in a future release
 #[cfg_attr(feature = "enable-serde", derive(Serialize, Deserialize))]
@@ -525,6 +562,8 @@ pub struct WasiFs {
     pub root_fs: WasiFsRoot,
     pub root_inode: InodeGuard,
     pub has_unioned: Arc<Mutex<HashSet<PackageId>>>,
+    #[cfg_attr(feature = "enable-serde", serde(skip, default))]
+    pub(crate) quota: Arc<FsQuota>,
 
     // TODO: remove
     // using an atomic is a hack to enable customization after construction,
@@ -538,6 +577,15 @@ pub struct WasiFs {
     pub(crate) init_vfs_preopens: Vec<String>,
 }
 
+/// The rights of a preopened directory that depend on how it was
+/// configured, the others are always granted.
+const PREOPEN_RIGHTS: Rights = Rights::FD_ADVISE
+    .union(Rights::FD_TELL)
+    .union(Rights::FD_SEEK)
+    .union(Rights::dir_read())
+    .union(Rights::dir_write())
+    .union(Rights::dir_create());
+
 impl WasiFs {
     pub fn is_wasix(&self) -> bool {
         // NOTE: this will only be set once very early in the instance lifetime,
@@ -563,11 +611,38 @@ impl WasiFs {
             root_fs: self.root_fs.clone(),
             root_inode: self.root_inode.clone(),
             has_unioned: Arc::new(Mutex::new(HashSet::new())),
+            quota: self.quota.clone(),
+            init_preopens: self.init_preopens.clone(),
+            init_vfs_preopens: self.init_vfs_preopens.clone(),
+        }
+    }
+
+    /// Creates another view of this file system that shares the open file
+    /// descriptors, inodes and root file system with it. Only the current
+    /// directory is private to each view.
+    pub fn share(&self) -> Self {
+        Self {
+            preopen_fds: RwLock::new(self.preopen_fds.read().unwrap().clone()),
+            fd_map: self.fd_map.clone(),
+            next_fd: self.next_fd.clone(),
+            freed_fds: self.freed_fds.clone(),
+            current_dir: Mutex::new(self.current_dir.lock().unwrap().clone()),
+            is_wasix: AtomicBool::new(self.is_wasix.load(Ordering::Acquire)),
+            root_fs: self.root_fs.clone(),
+            root_inode: self.root_inode.clone(),
+            has_unioned: self.has_unioned.clone(),
+            quota: self.quota.clone(),
             init_preopens: self.init_preopens.clone(),
             init_vfs_preopens: self.init_vfs_preopens.clone(),
         }
     }
 
+    /// Returns true if the file descriptors are also used by another instance
+    /// (see [`WasiFs::share`]).
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.fd_map) > 1
+    }
+
     fn get_first_free_fd(&self) -> WasiFd {
         let mut freed_fds = self.freed_fds.write().unwrap();
 
@@ -591,6 +666,16 @@ impl WasiFs {
     /// Closes all the file handles.
     #[allow(clippy::await_holding_lock)]
     pub async fn close_all(&self) {
+        // The file descriptors belong to everyone sharing them so they are
+        // left open until the last one exits
+        if self.is_shared() {
+            let _ = tokio::join!(
+                self.flush(__WASI_STDOUT_FILENO),
+                self.flush(__WASI_STDERR_FILENO)
+            );
+            return;
+        }
+
         let mut to_close = {
             if let Ok(map) = self.fd_map.read() {
                 map.keys().copied().collect::<HashSet<_>>()
@@ -690,6 +775,7 @@ impl WasiFs {
             root_fs: fs_backing,
             root_inode,
             has_unioned: Arc::new(Mutex::new(HashSet::new())),
+            quota: Default::default(),
             init_preopens: Default::default(),
             init_vfs_preopens: Default::default(),
         };
@@ -1011,16 +1097,20 @@ impl WasiFs {
                             "." => continue 'path_iter,
                             _ => (),
                         }
+                        let component_name = component.as_os_str().to_string_lossy();
+                        let component_name =
+                            match self.case_insensitive_name(path, entries, &component_name) {
+                                Some(name) => Cow::Owned(name),
+                                None => component_name,
+                            };
                         // used for full resolution of symlinks
                         let mut loop_for_symlink = false;
-                        if let Some(entry) =
-                            entries.get(component.as_os_str().to_string_lossy().as_ref())
-                        {
+                        if let Some(entry) = entries.get(component_name.as_ref()) {
                             cur_inode = entry.clone();
                         } else {
                             let file = {
                                 let mut cd = path.clone();
-                                cd.push(component);
+                                cd.push(component_name.as_ref());
                                 cd
                             };
                             let metadata = self
@@ -1114,10 +1204,8 @@ impl WasiFs {
                                         ref mut entries, ..
                                     } = guard.deref_mut()
                                     {
-                                        entries.insert(
-                                            component.as_os_str().to_string_lossy().to_string(),
-                                            new_inode.clone(),
-                                        );
+                                        entries
+                                            .insert(component_name.to_string(), new_inode.clone());
                                     } else {
                                         unreachable!(
                                             "Attempted to insert special device into non-directory"
@@ -1143,10 +1231,7 @@ impl WasiFs {
                                     ref mut entries, ..
                                 } = guard.deref_mut()
                                 {
-                                    entries.insert(
-                                        component.as_os_str().to_string_lossy().to_string(),
-                                        new_inode.clone(),
-                                    );
+                                    entries.insert(component_name.to_string(), new_inode.clone());
                                 }
                             }
                             cur_inode = new_inode;
@@ -1170,6 +1255,10 @@ impl WasiFs {
 
                         if let Some(entry) = entries.get(component.as_ref()) {
                             cur_inode = entry.clone();
+                        } else if let Some(entry) =
+                            self.case_insensitive_preopen(entries, &component)
+                        {
+                            cur_inode = entry;
                         } else if let Some(root) = entries.get(&"/".to_string()) {
                             cur_inode = root.clone();
                             continue 'symlink_resolution;
@@ -1367,8 +1456,79 @@ impl WasiFs {
         for comp in components.rev() {
             parent_dir.push(comp);
         }
-        self.get_inode_at_path(inodes, base, &parent_dir.to_string_lossy(), follow_symlinks)
-            .map(|v| (v, new_entity_name))
+        let parent =
+            self.get_inode_at_path(inodes, base, &parent_dir.to_string_lossy(), follow_symlinks)?;
+
+        // Entries of case-insensitive preopens are referred to by their
+        // actual name, so that they are found again when they are opened,
+        // renamed or removed
+        let new_entity_name = match parent.read().deref() {
+            Kind::Dir { path, entries, .. } => self
+                .case_insensitive_name(path, entries, &new_entity_name)
+                .unwrap_or(new_entity_name),
+            _ => new_entity_name,
+        };
+        Ok((parent, new_entity_name))
+    }
+
+    /// Whether `path` (in the root file system) is inside a preopened
+    /// directory whose paths are resolved case-insensitively.
+    fn is_case_insensitive(&self, path: &Path) -> bool {
+        self.init_preopens
+            .iter()
+            .any(|preopen| preopen.case_insensitive && path.starts_with(&preopen.path))
+    }
+
+    /// Finds the entry of the directory at `dir` that `name` refers to when
+    /// the directory is resolved case-insensitively and no entry has exactly
+    /// that name, returning its actual name.
+    fn case_insensitive_name(
+        &self,
+        dir: &Path,
+        entries: &HashMap<String, InodeGuard>,
+        name: &str,
+    ) -> Option<String> {
+        if entries.contains_key(name) || !self.is_case_insensitive(dir) {
+            return None;
+        }
+        if self.root_fs.symlink_metadata(&dir.join(name)).is_ok() {
+            return None;
+        }
+
+        let lower = name.to_lowercase();
+        if let Some(key) = entries.keys().find(|key| key.to_lowercase() == lower) {
+            return Some(key.clone());
+        }
+        self.root_fs
+            .read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .find(|entry| entry.to_lowercase() == lower)
+    }
+
+    /// Finds a case-insensitive preopen whose alias matches `name` when it
+    /// differs only by case.
+    fn case_insensitive_preopen(
+        &self,
+        entries: &HashMap<String, InodeGuard>,
+        name: &str,
+    ) -> Option<InodeGuard> {
+        let lower = name.to_lowercase();
+        self.init_preopens
+            .iter()
+            .filter(|preopen| preopen.case_insensitive)
+            .find_map(|preopen| {
+                let key = match &preopen.alias {
+                    Some(alias) => alias.clone(),
+                    None => preopen.path.to_string_lossy().into_owned(),
+                };
+                if key.to_lowercase() == lower {
+                    entries.get(&key).cloned()
+                } else {
+                    None
+                }
+            })
     }
 
     pub fn get_fd(&self, fd: WasiFd) -> Result<Fd, Errno> {
@@ -1461,7 +1621,9 @@ impl WasiFs {
                 Kind::Dir { .. } => Filetype::Directory,
                 Kind::Symlink { .. } => Filetype::SymbolicLink,
                 Kind::Socket { socket } => match &socket.inner.protected.read().unwrap().kind {
-                    InodeSocketKind::TcpStream { .. } => Filetype::SocketStream,
+                    InodeSocketKind::TcpStream { .. } | InodeSocketKind::TcpListener { .. } => {
+                        Filetype::SocketStream
+                    }
                     InodeSocketKind::Raw { .. } => Filetype::SocketRaw,
                     InodeSocketKind::PreSocket { props, .. } => match props.ty {
                         Socktype::Stream => Filetype::SocketStream,
@@ -1623,7 +1785,7 @@ impl WasiFs {
         inode: InodeGuard,
     ) -> Result<WasiFd, Errno> {
         let idx = self.get_first_free_fd();
-        self.create_fd_ext(
+        if let Err(err) = self.create_fd_ext(
             rights,
             rights_inheriting,
             flags,
@@ -1631,7 +1793,10 @@ impl WasiFs {
             inode,
             idx,
             false,
-        )?;
+        ) {
+            self.freed_fds.write().unwrap().push(Reverse(idx));
+            return Err(err);
+        }
         Ok(idx)
     }
 
@@ -1680,6 +1845,7 @@ impl WasiFs {
         if exclusive && guard.contains_key(&idx) {
             return Err(Errno::Exist);
         }
+        self.quota.check_new_fd(&guard, idx)?;
         guard.insert(
             idx,
             Fd {
@@ -1697,8 +1863,13 @@ impl WasiFs {
 
     pub fn clone_fd(&self, fd: WasiFd) -> Result<WasiFd, Errno> {
         let fd = self.get_fd(fd)?;
+        let mut guard = self.fd_map.write().unwrap();
         let idx = self.get_first_free_fd();
-        self.fd_map.write().unwrap().insert(
+        if let Err(err) = self.quota.check_new_fd(&guard, idx) {
+            self.freed_fds.write().unwrap().push(Reverse(idx));
+            return Err(err);
+        }
+        guard.insert(
             idx,
             Fd {
                 rights: fd.rights,
@@ -1818,7 +1989,6 @@ impl WasiFs {
                 | Rights::PATH_FILESTAT_GET
                 | Rights::FD_FILESTAT_GET
                 | Rights::PATH_LINK_SOURCE
-                | Rights::PATH_RENAME_SOURCE
                 | Rights::POLL_FD_READWRITE
                 | Rights::SOCK_SHUTDOWN;
             let inode = self
@@ -1848,14 +2018,15 @@ impl WasiFs {
             self.preopen_fds.write().unwrap().push(fd);
         }
 
-        for PreopenedDir {
-            path,
-            alias,
-            read,
-            write,
-            create,
-        } in self.init_preopens.iter()
-        {
+        for preopen in self.init_preopens.iter() {
+            let PreopenedDir {
+                path,
+                alias,
+                read,
+                write,
+                create,
+                ..
+            } = preopen;
             debug!(
                 "Attempting to preopen {} with alias {:?}",
                 &path.to_string_lossy(),
@@ -1879,49 +2050,7 @@ impl WasiFs {
                 ));
             };
 
-            let rights = {
-                // TODO: review tell' and fd_readwrite
-                let mut rights = Rights::FD_ADVISE | Rights::FD_TELL | Rights::FD_SEEK;
-                if *read {
-                    rights |= Rights::FD_READ
-                        | Rights::PATH_OPEN
-                        | Rights::FD_READDIR
-                        | Rights::PATH_READLINK
-                        | Rights::PATH_FILESTAT_GET
-                        | Rights::FD_FILESTAT_GET
-                        | Rights::PATH_LINK_SOURCE
-                        | Rights::PATH_RENAME_SOURCE
-                        | Rights::POLL_FD_READWRITE
-                        | Rights::SOCK_SHUTDOWN;
-                }
-                if *write {
-                    rights |= Rights::FD_DATASYNC
-                        | Rights::FD_FDSTAT_SET_FLAGS
-                        | Rights::FD_WRITE
-                        | Rights::FD_SYNC
-                        | Rights::FD_ALLOCATE
-                        | Rights::PATH_OPEN
-                        | Rights::PATH_RENAME_TARGET
-                        | Rights::PATH_FILESTAT_SET_SIZE
-                        | Rights::PATH_FILESTAT_SET_TIMES
-                        | Rights::FD_FILESTAT_SET_SIZE
-                        | Rights::FD_FILESTAT_SET_TIMES
-                        | Rights::PATH_REMOVE_DIRECTORY
-                        | Rights::PATH_UNLINK_FILE
-                        | Rights::POLL_FD_READWRITE
-                        | Rights::SOCK_SHUTDOWN;
-                }
-                if *create {
-                    rights |= Rights::PATH_CREATE_DIRECTORY
-                        | Rights::PATH_CREATE_FILE
-                        | Rights::PATH_LINK_TARGET
-                        | Rights::PATH_OPEN
-                        | Rights::PATH_RENAME_TARGET
-                        | Rights::PATH_SYMLINK;
-                }
-
-                rights
-            };
+            let rights = preopen.effective_rights();
             let inode = if let Some(alias) = &alias {
                 self.create_inode(inodes, kind, true, alias.clone())
             } else {
@@ -1967,9 +2096,74 @@ impl WasiFs {
             self.preopen_fds.write().unwrap().push(fd);
         }
 
+        self.restrict_rootfd();
         Ok(())
     }
 
+    /// Removes from the root fd the directory rights that one of the
+    /// preopened directories lacks, since the preopens can also be reached
+    /// through it.
+    ///
+    /// The virtual preopens are read-only mounts of packages rather than
+    /// directories whose rights were configured, they are left out.
+    fn restrict_rootfd(&self) {
+        let mut root_rights = ALL_RIGHTS;
+        for preopen in self.init_preopens.iter() {
+            root_rights &= preopen.effective_rights() | !PREOPEN_RIGHTS;
+        }
+
+        let root_ino = self.root_inode.ino();
+        let preopen_fds = self.preopen_fds.read().unwrap();
+        let mut fd_map = self.fd_map.write().unwrap();
+        for fd in preopen_fds.iter() {
+            if let Some(entry) = fd_map.get_mut(fd) {
+                if entry.inode.ino() == root_ino {
+                    entry.rights &= root_rights;
+                    entry.rights_inheriting &= root_rights;
+                }
+            }
+        }
+    }
+
+    /// The rights allowed at `path` (in the root file system) by the
+    /// innermost preopened directory containing it.
+    ///
+    /// A preopen nested in another one, like a read-only mount under a
+    /// writable `/`, is also reachable through the fds of its ancestors,
+    /// which must not hand out the rights it was denied.
+    pub(crate) fn preopen_rights_at(&self, path: &Path) -> Rights {
+        self.init_preopens
+            .iter()
+            .filter(|preopen| path.starts_with(&preopen.path))
+            .max_by_key(|preopen| preopen.path.as_os_str().len())
+            .map_or(ALL_RIGHTS, |preopen| {
+                preopen.effective_rights() | !PREOPEN_RIGHTS
+            })
+    }
+
+    /// The rights allowed by the preopened directory containing the file or
+    /// directory of `inode`.
+    pub(crate) fn preopen_rights_of(&self, inode: &InodeGuard) -> Rights {
+        match inode.read().deref() {
+            Kind::Dir { path, .. } | Kind::File { path, .. } => self.preopen_rights_at(path),
+            _ => ALL_RIGHTS,
+        }
+    }
+
+    /// Fails with `Errno::Access` unless the preopened directory containing
+    /// the file or directory of `inode` allows all of `rights`.
+    pub(crate) fn check_preopen_rights(
+        &self,
+        inode: &InodeGuard,
+        rights: Rights,
+    ) -> Result<(), Errno> {
+        if self.preopen_rights_of(inode).contains(rights) {
+            Ok(())
+        } else {
+            Err(Errno::Access)
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn create_std_dev_inner(
         &self,
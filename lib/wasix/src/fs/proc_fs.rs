@@ -0,0 +1,272 @@
+//! A synthetic `/proc` file system that lets guests inspect themselves
+//! through `/proc/self`, which many ported Unix programs expect to exist.
+
+use std::{
+    ops::Deref,
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use ahash::AHashMap;
+use futures::future::BoxFuture;
+use virtual_fs::{
+    DirEntry, FileOpener, FileSystem, FileType, FsError, Metadata, OpenOptions, OpenOptionsConfig,
+    ReadDir, StaticFile, VirtualFile,
+};
+use wasmer_wasix_types::wasi::Fd as WasiFd;
+
+use super::{Fd, Kind};
+
+/// Size of the pages reported in `/proc/self/statm`.
+const PAGE_SIZE: u64 = 4096;
+
+/// What the guest can find out about itself through `/proc/self`.
+#[derive(Debug)]
+pub(crate) struct ProcInfo {
+    args: Vec<String>,
+    envs: Vec<Vec<u8>>,
+    fd_map: Arc<RwLock<AHashMap<WasiFd, Fd>>>,
+    pid: AtomicU32,
+    memory_size: AtomicU64,
+}
+
+impl ProcInfo {
+    pub(crate) fn new(
+        args: Vec<String>,
+        envs: Vec<Vec<u8>>,
+        fd_map: Arc<RwLock<AHashMap<WasiFd, Fd>>>,
+    ) -> Self {
+        ProcInfo {
+            args,
+            envs,
+            fd_map,
+            pid: AtomicU32::new(0),
+            memory_size: AtomicU64::new(0),
+        }
+    }
+
+    /// Records the pid of the process, forked processes share the mount of
+    /// their parent so only the first one is kept.
+    pub(crate) fn set_pid(&self, pid: u32) {
+        let _ = self
+            .pid
+            .compare_exchange(0, pid, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    /// Records the size of the memory of the guest, which is only known
+    /// while it makes a syscall.
+    pub(crate) fn set_memory_size(&self, size: u64) {
+        self.memory_size.store(size, Ordering::Relaxed);
+    }
+
+    fn cmdline(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        for arg in &self.args {
+            data.extend_from_slice(arg.as_bytes());
+            data.push(0);
+        }
+        data
+    }
+
+    fn environ(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        for env in &self.envs {
+            data.extend_from_slice(env);
+            data.push(0);
+        }
+        data
+    }
+
+    fn status(&self) -> Vec<u8> {
+        let name = self
+            .args
+            .first()
+            .map(|program| program.rsplit('/').next().unwrap_or(program))
+            .unwrap_or_default();
+        let memory_kb = self.memory_size.load(Ordering::Relaxed) / 1024;
+        format!(
+            "Name:\t{name}\nPid:\t{}\nFDSize:\t{}\nVmSize:\t{memory_kb} kB\nVmRSS:\t{memory_kb} kB\n",
+            self.pid.load(Ordering::Relaxed),
+            self.fds().len(),
+        )
+        .into_bytes()
+    }
+
+    fn statm(&self) -> Vec<u8> {
+        let pages = self.memory_size.load(Ordering::Relaxed) / PAGE_SIZE;
+        format!("{pages} {pages} 0 0 0 {pages} 0\n").into_bytes()
+    }
+
+    fn fds(&self) -> Vec<WasiFd> {
+        let mut fds: Vec<_> = self.fd_map.read().unwrap().keys().copied().collect();
+        fds.sort_unstable();
+        fds
+    }
+
+    /// Describes what a file descriptor refers to, like the target of the
+    /// `/proc/self/fd/<fd>` symlinks on Linux.
+    fn fd_target(&self, fd: WasiFd) -> Option<Vec<u8>> {
+        let fd_map = self.fd_map.read().unwrap();
+        let inode = &fd_map.get(&fd)?.inode;
+        let ino = inode.ino().as_u64();
+        let target = match inode.read().deref() {
+            Kind::File { path, .. } | Kind::Dir { path, .. } => path.display().to_string(),
+            Kind::Root { .. } => "/".to_string(),
+            Kind::Socket { .. } => format!("socket:[{ino}]"),
+            Kind::Pipe { .. } => format!("pipe:[{ino}]"),
+            Kind::Epoll { .. } => "anon_inode:[eventpoll]".to_string(),
+            Kind::EventNotifications { .. } => "anon_inode:[eventfd]".to_string(),
+            Kind::Symlink { .. } | Kind::Buffer { .. } => inode.name.to_string(),
+        };
+        Some(target.into_bytes())
+    }
+
+    fn lookup(&self, path: &Path) -> Option<Node> {
+        let components: Vec<_> = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_str()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+
+        let node = match components.as_slice() {
+            [] => Node::Dir(vec!["self".to_string()]),
+            ["self"] => Node::Dir(
+                ["cmdline", "environ", "fd", "statm", "status"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            ["self", "cmdline"] => Node::File(self.cmdline()),
+            ["self", "environ"] => Node::File(self.environ()),
+            ["self", "statm"] => Node::File(self.statm()),
+            ["self", "status"] => Node::File(self.status()),
+            ["self", "fd"] => Node::Dir(self.fds().iter().map(|fd| fd.to_string()).collect()),
+            ["self", "fd", fd] => Node::File(self.fd_target(fd.parse().ok()?)?),
+            _ => return None,
+        };
+        Some(node)
+    }
+}
+
+enum Node {
+    Dir(Vec<String>),
+    File(Vec<u8>),
+}
+
+impl Node {
+    fn metadata(&self) -> Metadata {
+        let (ft, len) = match self {
+            Node::Dir(_) => (FileType::new_dir(), 0),
+            Node::File(data) => (FileType::new_file(), data.len() as u64),
+        };
+        Metadata {
+            ft,
+            accessed: 0,
+            created: 0,
+            modified: 0,
+            len,
+        }
+    }
+}
+
+/// The read-only file system mounted at `/proc`, its contents are generated
+/// whenever they are looked at.
+#[derive(Debug, Clone)]
+pub(crate) struct ProcFileSystem {
+    info: Arc<ProcInfo>,
+}
+
+impl ProcFileSystem {
+    pub(crate) fn new(info: Arc<ProcInfo>) -> Self {
+        ProcFileSystem { info }
+    }
+
+    fn lookup(&self, path: &Path) -> virtual_fs::Result<Node> {
+        self.info.lookup(path).ok_or(FsError::EntryNotFound)
+    }
+}
+
+impl FileSystem for ProcFileSystem {
+    fn readlink(&self, path: &Path) -> virtual_fs::Result<PathBuf> {
+        self.lookup(path)?;
+        Err(FsError::InvalidInput)
+    }
+
+    fn read_dir(&self, path: &Path) -> virtual_fs::Result<ReadDir> {
+        match self.lookup(path)? {
+            Node::Dir(names) => {
+                let entries = names
+                    .into_iter()
+                    .filter_map(|name| {
+                        let path = path.join(name);
+                        let metadata = self.lookup(&path).map(|node| node.metadata());
+                        // Files descriptors may have been closed in between
+                        metadata.is_ok().then_some(DirEntry { path, metadata })
+                    })
+                    .collect();
+                Ok(ReadDir::new(entries))
+            }
+            Node::File(_) => Err(FsError::BaseNotDirectory),
+        }
+    }
+
+    fn create_dir(&self, _path: &Path) -> virtual_fs::Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn remove_dir(&self, _path: &Path) -> virtual_fs::Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn rename<'a>(
+        &'a self,
+        _from: &'a Path,
+        _to: &'a Path,
+    ) -> BoxFuture<'a, virtual_fs::Result<()>> {
+        Box::pin(async { Err(FsError::PermissionDenied) })
+    }
+
+    fn metadata(&self, path: &Path) -> virtual_fs::Result<Metadata> {
+        Ok(self.lookup(path)?.metadata())
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> virtual_fs::Result<Metadata> {
+        self.metadata(path)
+    }
+
+    fn remove_file(&self, _path: &Path) -> virtual_fs::Result<()> {
+        Err(FsError::PermissionDenied)
+    }
+
+    fn new_open_options(&self) -> OpenOptions {
+        OpenOptions::new(self)
+    }
+
+    fn mount(
+        &self,
+        _name: String,
+        _path: &Path,
+        _fs: Box<dyn FileSystem + Send + Sync>,
+    ) -> virtual_fs::Result<()> {
+        Err(FsError::Unsupported)
+    }
+}
+
+impl FileOpener for ProcFileSystem {
+    fn open(
+        &self,
+        path: &Path,
+        conf: &OpenOptionsConfig,
+    ) -> virtual_fs::Result<Box<dyn VirtualFile + Send + Sync + 'static>> {
+        match self.info.lookup(path) {
+            Some(Node::File(data)) => Ok(Box::new(StaticFile::new(data))),
+            Some(Node::Dir(_)) => Err(FsError::NotAFile),
+            None if conf.create() || conf.create_new() => Err(FsError::PermissionDenied),
+            None => Err(FsError::EntryNotFound),
+        }
+    }
+}
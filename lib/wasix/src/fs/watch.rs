@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use virtual_fs::{FileSystem, PipeTx, WeakPipeRx};
+use wasmer_wasix_types::wasi::{Errno, WatchEventType};
+
+use super::{FsQuota, WasiFsRoot};
+use crate::runtime::task_manager::VirtualTaskManager;
+
+/// How often the watched path is scanned for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Maximum number of events waiting to be read by the guest. Past this, the
+/// watched path isn't scanned until the guest catches up.
+const MAX_QUEUED_EVENTS: usize = 1024;
+
+/// The state of a single entry, used to detect modifications
+type EntryState = (u64, u64);
+
+/// Takes a snapshot of the entries of the watched path. A watched directory
+/// yields its direct children, a watched file yields only itself and a
+/// missing path yields nothing.
+fn snapshot(fs: &WasiFsRoot, path: &Path) -> HashMap<String, EntryState> {
+    let mut entries = HashMap::new();
+    match fs.metadata(path) {
+        Ok(meta) if meta.is_dir() => {
+            for entry in fs.read_dir(path).into_iter().flatten().flatten() {
+                if let Ok(meta) = entry.metadata {
+                    let name = entry.path.file_name().unwrap_or_default();
+                    entries.insert(
+                        name.to_string_lossy().into_owned(),
+                        (meta.modified, meta.len),
+                    );
+                }
+            }
+        }
+        Ok(meta) => {
+            let name = path.file_name().unwrap_or_default();
+            entries.insert(
+                name.to_string_lossy().into_owned(),
+                (meta.modified, meta.len),
+            );
+        }
+        Err(_) => {}
+    }
+    entries
+}
+
+/// Compares two snapshots and returns the changes between them.
+fn diff(
+    before: &HashMap<String, EntryState>,
+    after: &HashMap<String, EntryState>,
+) -> Vec<(WatchEventType, String)> {
+    let mut events = Vec::new();
+    for (name, state) in after {
+        match before.get(name) {
+            None => events.push((WatchEventType::CREATE, name.clone())),
+            Some(old) if old != state => events.push((WatchEventType::MODIFY, name.clone())),
+            Some(_) => {}
+        }
+    }
+    for name in before.keys() {
+        if !after.contains_key(name) {
+            events.push((WatchEventType::DELETE, name.clone()));
+        }
+    }
+    events
+}
+
+/// Encodes an event in the format the guest reads it from the watch.
+fn encode(kind: WatchEventType, name: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(8 + name.len());
+    buf.extend_from_slice(&kind.bits().to_le_bytes());
+    buf.extend_from_slice(&(name.len() as u32).to_le_bytes());
+    buf.extend_from_slice(name.as_bytes());
+    buf
+}
+
+/// Hands back the watch reserved in the quota once the watch stops.
+struct WatchReservation(Arc<FsQuota>);
+
+impl Drop for WatchReservation {
+    fn drop(&mut self) {
+        self.0.release_watch();
+    }
+}
+
+/// Watches a path of the file system and writes an event into `events`
+/// whenever one of its entries is created, modified or deleted. `reader` is
+/// the end of the pipe read by the guest.
+///
+/// The watch stops once the guest closes its end of the pipe.
+pub(crate) fn spawn_watch(
+    tasks: &Arc<dyn VirtualTaskManager>,
+    fs: WasiFsRoot,
+    path: PathBuf,
+    mut events: PipeTx,
+    reader: WeakPipeRx,
+    quota: Arc<FsQuota>,
+) -> Result<(), Errno> {
+    quota.reserve_watch()?;
+    let reservation = WatchReservation(quota);
+
+    let mut state = snapshot(&fs, &path);
+    let tasks_inner = tasks.clone();
+    tasks.task_shared(Box::new(move || {
+        Box::pin(async move {
+            let _reservation = reservation;
+            while !events.is_closed() {
+                tasks_inner.sleep_now(WATCH_INTERVAL).await;
+
+                // The changes made while the guest lags behind are reported
+                // together, against the last snapshot, once it catches up
+                match reader.pending() {
+                    Some(pending) if pending >= MAX_QUEUED_EVENTS => continue,
+                    Some(_) => {}
+                    None => break,
+                }
+
+                let next = snapshot(&fs, &path);
+                for (kind, name) in diff(&state, &next) {
+                    if events.write_all(&encode(kind, &name)).is_err() {
+                        return;
+                    }
+                }
+                state = next;
+            }
+            tracing::trace!(path = %path.display(), "file watch closed");
+        })
+    }))?;
+    Ok(())
+}
@@ -33,7 +33,7 @@ use super::{
     backoff::WasiProcessCpuBackoff,
     control_plane::{ControlPlaneError, WasiControlPlaneHandle},
     signal::{SignalDeliveryError, SignalHandlerAbi},
-    task_join_handle::OwnedTaskStatus,
+    task_join_handle::{OwnedTaskStatus, TaskJoinHandle},
     thread::WasiMemoryLayout,
     TaskStatus,
 };
@@ -469,6 +469,11 @@ impl WasiProcess {
         self.pid
     }
 
+    /// Get a join handle to watch the process status.
+    pub fn join_handle(&self) -> TaskJoinHandle {
+        self.finished.handle()
+    }
+
     /// Gets the process ID of the parent process
     pub fn ppid(&self) -> WasiProcessId {
         self.parent
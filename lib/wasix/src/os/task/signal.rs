@@ -29,6 +29,24 @@ pub struct WasiSignalInterval {
     pub last_signal: u128,
 }
 
+/// Whether a signal terminates a process that has no handler for it, which
+/// is the default action for all signals but the ones for job control,
+/// `SIGCHLD`, `SIGURG` and `SIGWINCH`.
+pub fn terminates_by_default(signal: Signal) -> bool {
+    !matches!(
+        signal,
+        Signal::Signone
+            | Signal::Sigchld
+            | Signal::Sigcont
+            | Signal::Sigstop
+            | Signal::Sigtstp
+            | Signal::Sigttin
+            | Signal::Sigttou
+            | Signal::Sigurg
+            | Signal::Sigwinch
+    )
+}
+
 pub fn default_signal_handler() -> Arc<DynSignalHandlerAbi> {
     #[derive(Debug)]
     struct DefaultHandler {}
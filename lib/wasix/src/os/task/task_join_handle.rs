@@ -1,14 +1,14 @@
 use std::{
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
 };
 
-use wasmer_wasix_types::wasi::{Errno, ExitCode};
+use wasmer_wasix_types::wasi::{Errno, ExitCode, Signal};
 
 use crate::WasiRuntimeError;
 
-use super::signal::{default_signal_handler, DynSignalHandlerAbi};
+use super::signal::{default_signal_handler, DynSignalHandlerAbi, SignalDeliveryError};
 
 #[derive(Clone, Debug)]
 pub enum TaskStatus {
@@ -80,6 +80,8 @@ pub struct OwnedTaskStatus {
     // where the previously sent values are lost.
     #[allow(dead_code)]
     watch_rx: tokio::sync::watch::Receiver<TaskStatus>,
+    // The signal that caused the task to finish (if any)
+    terminated_by: Arc<Mutex<Option<Signal>>>,
 }
 
 impl OwnedTaskStatus {
@@ -89,6 +91,7 @@ impl OwnedTaskStatus {
             signal_handler: default_signal_handler(),
             watch_tx: tx,
             watch_rx: rx,
+            terminated_by: Default::default(),
         }
     }
 
@@ -117,6 +120,22 @@ impl OwnedTaskStatus {
         })
     }
 
+    /// Marks the task as finished because of a signal, unless it has already
+    /// finished for another reason.
+    pub(crate) fn set_finished_by_signal(&self, signal: Signal, code: ExitCode) {
+        self.watch_tx.send_modify(|old| {
+            if !old.is_finished() {
+                *self.terminated_by.lock().unwrap() = Some(signal);
+                *old = TaskStatus::Finished(Ok(code));
+            }
+        });
+    }
+
+    /// The signal that caused the task to finish, if any.
+    pub fn terminated_by(&self) -> Option<Signal> {
+        *self.terminated_by.lock().unwrap()
+    }
+
     /// Marks the task as finished.
     pub(crate) fn set_finished(&self, res: Result<ExitCode, Arc<WasiRuntimeError>>) {
         let inner = match res {
@@ -163,6 +182,7 @@ impl OwnedTaskStatus {
         TaskJoinHandle {
             signal_handler: self.signal_handler.clone(),
             watch: self.watch_tx.subscribe(),
+            terminated_by: self.terminated_by.clone(),
         }
     }
 }
@@ -179,9 +199,15 @@ pub struct TaskJoinHandle {
     #[allow(unused)]
     signal_handler: Arc<DynSignalHandlerAbi>,
     watch: tokio::sync::watch::Receiver<TaskStatus>,
+    terminated_by: Arc<Mutex<Option<Signal>>>,
 }
 
 impl TaskJoinHandle {
+    /// The signal that caused the task to finish, if any.
+    pub fn terminated_by(&self) -> Option<Signal> {
+        *self.terminated_by.lock().unwrap()
+    }
+
     /// Retrieve the current status.
     pub fn status(&self) -> TaskStatus {
         self.watch.borrow().clone()
@@ -190,7 +216,6 @@ impl TaskJoinHandle {
     #[cfg(feature = "ctrlc")]
     pub fn install_ctrlc_handler(&self) {
         use wasmer::FromToNativeWasmType;
-        use wasmer_wasix_types::wasi::Signal;
 
         let signal_handler = self.signal_handler.clone();
 
@@ -205,6 +230,112 @@ impl TaskJoinHandle {
         });
     }
 
+    /// Forwards SIGINT and SIGTERM received by the host to the task so the
+    /// guest can shut down cleanly (e.g. from a handler registered with the
+    /// `__wasm_signal` export).
+    ///
+    /// If the task is still running `grace_period` after the first signal,
+    /// or when a second signal arrives, the host process exits with
+    /// `128 + <signal>` like it would have without forwarding.
+    #[cfg(feature = "ctrlc")]
+    pub fn forward_host_signals(&self, grace_period: std::time::Duration) {
+        use wasmer::FromToNativeWasmType;
+
+        async fn next_signal() -> Signal {
+            #[cfg(unix)]
+            {
+                use tokio::signal::unix::{signal, SignalKind};
+
+                if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+                    return tokio::select! {
+                        _ = tokio::signal::ctrl_c() => Signal::Sigint,
+                        _ = terminate.recv() => Signal::Sigterm,
+                    };
+                }
+            }
+
+            let _ = tokio::signal::ctrl_c().await;
+            Signal::Sigint
+        }
+
+        let handle = self.clone();
+        tokio::spawn(async move {
+            match handle.forward_signals(grace_period, next_signal).await {
+                Ok(None) => {}
+                Ok(Some(signal)) => std::process::exit(128 + signal.to_native()),
+                Err(err) => {
+                    tracing::error!("failed to process signal - {}", err);
+                    std::process::exit(1);
+                }
+            }
+        });
+    }
+
+    /// Forwards the first signal returned by `next_signal` to the task, then
+    /// gives it `grace_period` to finish.
+    ///
+    /// Returns the forwarded signal if the task is still running after the
+    /// grace period or when `next_signal` returns another signal, and `None`
+    /// if it finished in time.
+    pub async fn forward_signals<F>(
+        &self,
+        grace_period: std::time::Duration,
+        mut next_signal: impl FnMut() -> F,
+    ) -> Result<Option<Signal>, SignalDeliveryError>
+    where
+        F: std::future::Future<Output = Signal>,
+    {
+        use wasmer::FromToNativeWasmType;
+
+        let signal = next_signal().await;
+        self.signal_handler.signal(signal.to_native() as u8)?;
+
+        let mut handle = self.clone();
+        tokio::select! {
+            _ = handle.wait_finished() => return Ok(None),
+            _ = tokio::time::sleep(grace_period) => {
+                tracing::warn!(?signal, "the guest did not exit within the grace period");
+            }
+            _ = next_signal() => {}
+        }
+        Ok(Some(signal))
+    }
+
+    /// Sends `SIGWINCH` to the task whenever the host terminal is resized so
+    /// interactive guests know to query the new size with `tty_get`.
+    #[cfg(feature = "ctrlc")]
+    pub fn forward_window_changes(&self) {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            use wasmer::FromToNativeWasmType;
+
+            let mut window_change = match signal(SignalKind::window_change()) {
+                Ok(s) => s,
+                Err(err) => {
+                    tracing::debug!("failed to listen for window changes - {}", err);
+                    return;
+                }
+            };
+            let signal_handler = self.signal_handler.clone();
+            let mut handle = self.clone();
+
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = handle.wait_finished() => break,
+                        Some(()) = window_change.recv() => {
+                            if let Err(err) = signal_handler.signal(Signal::Sigwinch.to_native() as u8) {
+                                tracing::debug!("failed to process signal - {}", err);
+                                break;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    }
+
     /// Wait until the task finishes.
     pub async fn wait_finished(&mut self) -> Result<ExitCode, Arc<WasiRuntimeError>> {
         loop {
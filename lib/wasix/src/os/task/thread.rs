@@ -316,17 +316,26 @@ impl WasiThread {
     /// Note: if the exit code was already set earlier this method will
     /// just return that earlier set exit code
     pub fn set_or_get_exit_code_for_signal(&self, sig: Signal) -> ExitCode {
+        // SIGQUIT is also what the remaining threads receive when the main
+        // thread exits, so that is not treated as an error
         let default_exitcode: ExitCode = match sig {
-            Signal::Sigquit | Signal::Sigabrt => Errno::Success.into(),
+            Signal::Sigquit => Errno::Success.into(),
             _ => Errno::Intr.into(),
         };
         // This will only set the status code if its not already set
-        self.set_status_finished(Ok(default_exitcode));
+        self.state
+            .status
+            .set_finished_by_signal(sig, default_exitcode);
         self.try_join()
             .map(|r| r.unwrap_or(default_exitcode))
             .unwrap_or(default_exitcode)
     }
 
+    /// The signal that terminated the thread, if any.
+    pub fn terminated_by(&self) -> Option<Signal> {
+        self.state.status.terminated_by()
+    }
+
     /// Marks the thread as finished (which will cause anyone that
     /// joined on it to wake up)
     pub fn set_status_finished(&self, res: Result<ExitCode, WasiRuntimeError>) {
@@ -79,7 +79,7 @@ mod sys {
     use {
         libc::{
             c_int, tcsetattr, termios, ECHO, ECHOCTL, ECHOE, ECHOK, ECHONL, ICANON, ICRNL, IEXTEN,
-            IGNCR, ISIG, IXON, ONLCR, OPOST, TCSANOW,
+            IGNCR, ISIG, IXON, ONLCR, OPOST, TCSANOW, VMIN, VTIME,
         },
         std::mem,
         std::os::unix::io::AsRawFd,
@@ -133,7 +133,7 @@ mod sys {
 
     pub fn is_mode_line_feeds() -> bool {
         if let Ok(termios) = ::termios::Termios::from_fd(0) {
-            (termios.c_lflag & ::termios::ONLCR) != 0
+            (termios.c_oflag & ::termios::ONLCR) != 0
         } else {
             false
         }
@@ -187,6 +187,9 @@ mod sys {
         let mut termios = unsafe { termios.assume_init() };
 
         termios.c_lflag &= !ICANON;
+        // Return from `read` as soon as a single byte is available
+        termios.c_cc[VMIN] = 1;
+        termios.c_cc[VTIME] = 0;
 
         unsafe { tcsetattr(0, TCSANOW, &termios) };
         Ok(())
@@ -208,7 +211,7 @@ mod sys {
         io_result(unsafe { ::libc::tcgetattr(0, termios.as_mut_ptr()) })?;
         let mut termios = unsafe { termios.assume_init() };
 
-        termios.c_lflag &= !ONLCR;
+        termios.c_oflag &= !ONLCR;
 
         unsafe { tcsetattr(0, TCSANOW, &termios) };
         Ok(())
@@ -219,7 +222,7 @@ mod sys {
         io_result(unsafe { ::libc::tcgetattr(0, termios.as_mut_ptr()) })?;
         let mut termios = unsafe { termios.assume_init() };
 
-        termios.c_lflag |= ONLCR;
+        termios.c_oflag |= ONLCR;
 
         unsafe { tcsetattr(0, TCSANOW, &termios) };
         Ok(())
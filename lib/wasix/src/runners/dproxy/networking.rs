@@ -220,4 +220,11 @@ impl VirtualNetworking for LocalWithLoopbackNetworking {
     ) -> Result<Vec<IpAddr>, NetworkError> {
         self.inner_networking.resolve(host, port, dns_server).await
     }
+
+    fn adopt_tcp_listener(
+        &self,
+        listener: std::net::TcpListener,
+    ) -> Result<Box<dyn VirtualTcpListener + Sync>, NetworkError> {
+        self.inner_networking.adopt_tcp_listener(listener)
+    }
 }
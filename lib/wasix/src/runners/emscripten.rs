@@ -1,21 +1,56 @@
 //! WebC container support for running Emscripten modules
 
-use std::sync::Arc;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use anyhow::{anyhow, Context, Error};
 use serde::{Deserialize, Serialize};
-use wasmer::{FunctionEnv, Instance, Module, Store};
+use wasmer::{
+    Extern, Function, FunctionEnv, FunctionEnvMut, Imports, Instance, Module, RuntimeError, Store,
+    Value,
+};
 use wasmer_emscripten::{
-    generate_emscripten_env, is_emscripten_module, run_emscripten_instance, EmEnv,
-    EmscriptenGlobals,
+    emscripten_ccall, generate_emscripten_env, is_emscripten_module, run_emscripten_instance,
+    set_up_emscripten_instance, EmEnv, EmscriptenGlobals,
 };
+pub use wasmer_emscripten::{Ccall, CcallArg, CcallReturn, CcallValue, EmscriptenStdio};
 use webc::metadata::{annotations::Emscripten, Command};
 
-use crate::{bin_factory::BinaryPackage, Runtime};
+use crate::{
+    bin_factory::BinaryPackage, is_wasi_module, is_wasix_module, runners::MappedDirectory, Runtime,
+    WasiEnv, WasiEnvBuilder, WasiError, WasiFunctionEnv,
+};
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct EmscriptenRunner {
     args: Vec<String>,
+    mounts: Vec<EmscriptenMount>,
+    #[serde(skip)]
+    stdio: EmscriptenStdio,
+}
+
+/// A host directory made available to an Emscripten module.
+///
+/// Emscripten modules access the file system of the host directly, the
+/// paths inside of `guest` are translated to paths inside of `host`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmscriptenMount {
+    /// The directory on the host
+    pub host: PathBuf,
+    /// Where the directory is seen by the guest, `.` mapping the paths
+    /// relative to the current directory
+    pub guest: String,
+    /// Whether the guest is prevented from modifying the directory
+    pub read_only: bool,
+}
+
+impl From<MappedDirectory> for EmscriptenMount {
+    fn from(MappedDirectory { host, guest }: MappedDirectory) -> Self {
+        EmscriptenMount {
+            host,
+            guest,
+            read_only: false,
+        }
+    }
 }
 
 impl EmscriptenRunner {
@@ -39,6 +74,37 @@ impl EmscriptenRunner {
     pub fn set_args(&mut self, args: Vec<String>) {
         self.args = args;
     }
+
+    /// Builder method to map host directories into the guest
+    pub fn with_mounts<I, M>(mut self, mounts: I) -> Self
+    where
+        I: IntoIterator<Item = M>,
+        M: Into<EmscriptenMount>,
+    {
+        self.set_mounts(mounts);
+        self
+    }
+
+    /// Map host directories into the guest
+    pub fn set_mounts<I, M>(&mut self, mounts: I)
+    where
+        I: IntoIterator<Item = M>,
+        M: Into<EmscriptenMount>,
+    {
+        self.mounts = mounts.into_iter().map(Into::into).collect();
+    }
+
+    /// Builder method to capture the output of the guest
+    pub fn with_stdio(mut self, stdio: EmscriptenStdio) -> Self {
+        self.set_stdio(stdio);
+        self
+    }
+
+    /// Capture the output of the guest instead of writing it to the host's
+    /// standard streams
+    pub fn set_stdio(&mut self, stdio: EmscriptenStdio) {
+        self.stdio = stdio;
+    }
 }
 
 impl crate::runners::Runner for EmscriptenRunner {
@@ -64,26 +130,120 @@ impl crate::runners::Runner for EmscriptenRunner {
         let mut module = runtime.load_module_sync(cmd.atom())?;
         module.set_name(command_name);
 
-        let mut store = runtime.new_store();
-        let (mut globals, env) = prepare_emscripten_env(&mut store, &module, command_name)?;
+        let main_args = main_args.unwrap_or_default();
+        let wasi = needs_wasi_env(&module).then(|| {
+            WasiEnvBuilder::new(command_name)
+                .args(&main_args)
+                .runtime(runtime.clone())
+        });
 
-        exec_module(
+        let mut store = runtime.new_store();
+        run_emscripten_module(
             &mut store,
             &module,
-            &mut globals,
-            env,
             command_name,
-            main_args.unwrap_or_default(),
-        )?;
-
-        Ok(())
+            main_args,
+            &self.mounts,
+            self.stdio.clone(),
+            wasi,
+        )
     }
 }
 
+/// Whether the WASI imports of an Emscripten module should be satisfied by a
+/// WASI environment.
+///
+/// Recent versions of Emscripten import WASI functions for their standard
+/// streams and to read, write and close files, see [`run_emscripten_module`]
+/// for how they share the files opened by the Emscripten syscalls.
+pub fn needs_wasi_env(module: &Module) -> bool {
+    is_wasi_module(module) || is_wasix_module(module)
+}
+
+/// Runs an Emscripten module.
+///
+/// Some toolchains produce modules that import WASI functions on top of the
+/// Emscripten `env` namespace, their WASI imports are satisfied by the
+/// environment built from `wasi`, which shares the memory of the Emscripten
+/// environment. The standard streams redirected by `stdio` are redirected
+/// for both environments.
+///
+/// Recent versions of Emscripten open files with their own syscalls but
+/// read, write and close them with WASI functions, so the host files opened
+/// by the Emscripten environment are handed over to the WASI environment.
+pub fn run_emscripten_module(
+    store: &mut Store,
+    module: &Module,
+    name: &str,
+    args: Vec<String>,
+    mounts: &[EmscriptenMount],
+    stdio: EmscriptenStdio,
+    wasi: Option<WasiEnvBuilder>,
+) -> Result<(), Error> {
+    let wasi = redirect_wasi_stdio(wasi, &stdio);
+    let (mut globals, env) = prepare_emscripten_env(store, module, name, mounts, stdio)?;
+    exec_module(
+        store,
+        module,
+        &mut globals,
+        env,
+        name,
+        wasi,
+        |instance, env, globals| {
+            let args = args.iter().map(|arg| arg.as_str()).collect();
+            run_emscripten_instance(instance, env, globals, name, args, None)
+        },
+    )
+}
+
+/// Calls a function exported by an Emscripten module instead of its `main`,
+/// marshaling its arguments and return value the way `ccall` does.
+pub fn invoke_emscripten_module(
+    store: &mut Store,
+    module: &Module,
+    name: &str,
+    call: &Ccall,
+    mounts: &[EmscriptenMount],
+    stdio: EmscriptenStdio,
+    wasi: Option<WasiEnvBuilder>,
+) -> Result<CcallValue, Error> {
+    let wasi = redirect_wasi_stdio(wasi, &stdio);
+    let (mut globals, env) = prepare_emscripten_env(store, module, name, mounts, stdio)?;
+    exec_module(
+        store,
+        module,
+        &mut globals,
+        env,
+        name,
+        wasi,
+        |instance, mut env, globals| {
+            set_up_emscripten_instance(instance, &mut env, globals)?;
+            emscripten_ccall(instance, &mut env, call)
+        },
+    )
+}
+
+fn redirect_wasi_stdio(
+    wasi: Option<WasiEnvBuilder>,
+    stdio: &EmscriptenStdio,
+) -> Option<WasiEnvBuilder> {
+    wasi.map(|mut builder| {
+        if let Some(stdout) = stdio.stdout() {
+            builder.set_stdout_writer(stdout);
+        }
+        if let Some(stderr) = stdio.stderr() {
+            builder.set_stderr_writer(stderr);
+        }
+        builder
+    })
+}
+
 fn prepare_emscripten_env(
     store: &mut Store,
     module: &Module,
     name: &str,
+    mounts: &[EmscriptenMount],
+    stdio: EmscriptenStdio,
 ) -> Result<(EmscriptenGlobals, FunctionEnv<EmEnv>), anyhow::Error> {
     if !is_emscripten_module(module) {
         return Err(anyhow!("Atom {name:?} is not an emscripten module"));
@@ -92,33 +252,239 @@ fn prepare_emscripten_env(
     let env = FunctionEnv::new(store, EmEnv::new());
     let emscripten_globals = EmscriptenGlobals::new(store, &env, module);
     let emscripten_globals = emscripten_globals.map_err(|e| anyhow!("{}", e))?;
-    env.as_mut(store)
-        .set_data(&emscripten_globals.data, Default::default());
+    let mapped_dirs: HashMap<String, PathBuf> = mounts
+        .iter()
+        .map(|mount| (mount.guest.clone(), mount.host.clone()))
+        .collect();
+    let env_mut = env.as_mut(store);
+    env_mut.set_data(&emscripten_globals.data, mapped_dirs);
+    env_mut.set_read_only_dirs(
+        mounts
+            .iter()
+            .filter(|mount| mount.read_only)
+            .map(|mount| mount.guest.clone()),
+    );
+    env_mut.set_stdio(stdio);
 
     Ok((emscripten_globals, env))
 }
 
-fn exec_module(
+fn exec_module<T: Default>(
     store: &mut Store,
     module: &Module,
     globals: &mut EmscriptenGlobals,
     em_env: FunctionEnv<EmEnv>,
     name: &str,
-    args: Vec<String>,
-) -> Result<(), anyhow::Error> {
-    let import_object = generate_emscripten_env(store, &em_env, globals);
+    wasi: Option<WasiEnvBuilder>,
+    run: impl FnOnce(
+        &mut Instance,
+        FunctionEnvMut<EmEnv>,
+        &mut EmscriptenGlobals,
+    ) -> Result<T, RuntimeError>,
+) -> Result<T, anyhow::Error> {
+    let mut import_object = generate_emscripten_env(store, &em_env, globals);
+
+    let mut wasi_env = match wasi {
+        Some(builder) => {
+            let wasi_env = builder.finalize(store)?;
+            let wasi_imports = wasi_env.import_object_for_all_wasi_versions(store, module)?;
+            import_object.extend(&wasi_imports);
+            legalize_wasi_imports(store, module, &mut import_object);
+            #[cfg(unix)]
+            share_opened_files(store, &mut import_object, &wasi_env);
+            Some(wasi_env)
+        }
+        None => None,
+    };
 
     let mut instance = Instance::new(store, module, &import_object)
         .map_err(|e| anyhow!("Cant instantiate emscripten module {name:?}: {e}"))?;
 
-    run_emscripten_instance(
-        &mut instance,
-        em_env.into_mut(store),
-        globals,
-        name,
-        args.iter().map(|arg| arg.as_str()).collect(),
-        None,
-    )?;
+    if let Some(wasi_env) = &mut wasi_env {
+        // Emscripten modules import their memory, which is the one created
+        // for the Emscripten environment
+        wasi_env
+            .initialize_with_memory(store, instance.clone(), Some(globals.memory.clone()), false)
+            .context("Unable to initialize the WASI environment")?;
+    }
+
+    let result = run(&mut instance, em_env.into_mut(store), globals);
+
+    let Some(wasi_env) = wasi_env else {
+        return Ok(result?);
+    };
+    let exit_code = match &result {
+        Ok(_) => None,
+        Err(e) => match e.downcast_ref::<WasiError>() {
+            Some(WasiError::Exit(code)) => Some(*code),
+            _ => None,
+        },
+    };
+    wasi_env.on_exit(store, exit_code);
+
+    match result {
+        // Calling `proc_exit(0)` is how some programs finish
+        Err(_) if exit_code.is_some_and(|code| code.is_success()) => Ok(T::default()),
+        result => Ok(result?),
+    }
+}
+
+/// Modules built without `-sWASM_BIGINT` split the 64 bit argument of
+/// `fd_seek` and `clock_time_get` in two halves, which are joined again
+/// before calling the WASI functions.
+fn legalize_wasi_imports(store: &mut Store, module: &Module, imports: &mut Imports) {
+    let env = FunctionEnv::new(store, ());
+    for import in module.imports().functions() {
+        if !matches!(import.name(), "fd_seek" | "clock_time_get") {
+            continue;
+        }
+        let Some(Extern::Function(func)) = imports.get_export(import.module(), import.name())
+        else {
+            continue;
+        };
+        if func.ty(store).params() == import.ty().params() {
+            continue;
+        }
 
-    Ok(())
+        // The 64 bit argument is the second one of both functions
+        let legalized = Function::new_with_env(
+            store,
+            &env,
+            import.ty().clone(),
+            move |mut ctx: FunctionEnvMut<()>, args: &[Value]| {
+                let (low, high) = match args {
+                    [_, Value::I32(low), Value::I32(high), ..] => (*low, *high),
+                    _ => return Err(RuntimeError::new("invalid arguments")),
+                };
+                let mut joined = vec![args[0].clone()];
+                joined.push(Value::I64((low as u32 as i64) | ((high as i64) << 32)));
+                joined.extend_from_slice(&args[3..]);
+                Ok(func.call(&mut ctx, &joined)?.into_vec())
+            },
+        );
+        imports.define(import.module(), import.name(), legalized);
+    }
+}
+
+/// Hands the host files opened by the Emscripten syscalls over to the WASI
+/// environment, under the file descriptor returned to the guest.
+#[cfg(unix)]
+fn share_opened_files(store: &mut Store, imports: &mut Imports, wasi_env: &WasiFunctionEnv) {
+    // The `fcntl` commands duplicating a file descriptor
+    const F_DUPFD: i32 = 0;
+    const F_DUPFD_CLOEXEC: i32 = 1030;
+
+    for name in [
+        "__syscall_openat",
+        "__syscall_dup",
+        "__syscall_dup3",
+        "__syscall_fcntl64",
+    ] {
+        let Some(Extern::Function(syscall)) = imports.get_export("env", name) else {
+            continue;
+        };
+        let ty = syscall.ty(store);
+        let wrapper = Function::new_with_env(
+            store,
+            &wasi_env.env,
+            ty,
+            move |mut ctx: FunctionEnvMut<WasiEnv>, args: &[Value]| {
+                let replaced = match (name, args) {
+                    ("__syscall_fcntl64", [_, Value::I32(cmd), ..])
+                        if !matches!(*cmd, F_DUPFD | F_DUPFD_CLOEXEC) =>
+                    {
+                        return Ok(syscall.call(&mut ctx, args)?.into_vec());
+                    }
+                    // The file replaced by `dup3` is closed by the WASI
+                    // environment, which owns it
+                    ("__syscall_dup3", [_, Value::I32(new_fd), ..]) => {
+                        let state = ctx.data().state();
+                        if *new_fd >= 0 {
+                            state.fs.close_fd(*new_fd as u32).ok();
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+
+                let ret = syscall.call(&mut ctx, args)?;
+                let fd = match ret.first() {
+                    Some(Value::I32(fd)) if *fd >= 0 => *fd,
+                    _ => return Ok(ret.into_vec()),
+                };
+                let fd = adopt_host_fd(ctx.data(), fd, replaced).unwrap_or_else(|errno| {
+                    // Emscripten expects negated WASI errnos
+                    -(errno as i32)
+                });
+                Ok(vec![Value::I32(fd)])
+            },
+        );
+        imports.define("env", name, wrapper);
+    }
+}
+
+/// Adds a file descriptor of the host to the WASI environment, which takes
+/// ownership of it.
+///
+/// Unless it `replaces` one of the guest, a file descriptor already used by
+/// the WASI environment is moved to a free number first, which is returned.
+#[cfg(unix)]
+fn adopt_host_fd(env: &WasiEnv, fd: i32, replaces: bool) -> Result<i32, crate::types::wasi::Errno> {
+    use std::os::unix::io::FromRawFd;
+
+    use crate::{
+        fs::{Fd, Kind},
+        types::wasi::{Errno, Fdflags},
+        ALL_RIGHTS,
+    };
+
+    let state = env.state();
+    let mut fd = fd;
+    if !replaces {
+        while state.fs.get_fd(fd as u32).is_ok() {
+            let moved = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, fd + 1) };
+            unsafe { libc::close(fd) };
+            if moved < 0 {
+                return Err(Errno::Mfile);
+            }
+            fd = moved;
+        }
+    }
+
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    let (read, write) = match flags & libc::O_ACCMODE {
+        libc::O_RDONLY => (true, false),
+        libc::O_WRONLY => (false, true),
+        _ => (true, true),
+    };
+    let append = flags & libc::O_APPEND != 0;
+    let handle = tokio::runtime::Handle::try_current().map_err(|_| Errno::Io)?;
+    let file = unsafe { std::fs::File::from_raw_fd(fd) };
+    let file = virtual_fs::host_fs::File::new(handle, file, PathBuf::new(), read, write, append);
+
+    let kind = Kind::File {
+        handle: Some(Arc::new(std::sync::RwLock::new(Box::new(file)))),
+        path: PathBuf::new(),
+        fd: None,
+    };
+    let inode =
+        state
+            .fs
+            .create_inode_with_default_stat(&state.inodes, kind, false, fd.to_string().into());
+    let mut open_flags = 0;
+    if read {
+        open_flags |= Fd::READ;
+    }
+    if write {
+        open_flags |= Fd::WRITE;
+    }
+    let fd_flags = if append {
+        Fdflags::APPEND
+    } else {
+        Fdflags::empty()
+    };
+    state.fs.with_fd(
+        ALL_RIGHTS, ALL_RIGHTS, fd_flags, open_flags, inode, fd as u32,
+    )?;
+    Ok(fd)
 }
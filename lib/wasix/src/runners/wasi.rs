@@ -12,11 +12,13 @@ use crate::{
     bin_factory::BinaryPackage,
     capabilities::Capabilities,
     journal::{DynJournal, SnapshotTrigger},
-    runners::{wasi_common::CommonWasiOptions, MappedDirectory, MountedDirectory},
+    runners::{wasi_common::CommonWasiOptions, FsPolicy, MappedDirectory, MountedDirectory},
     runtime::task_manager::VirtualTaskManagerExt,
+    state::{ClockSource, ResourceUsageTracker, SyscallTracer, UserIdentity},
     Runtime, WasiEnvBuilder, WasiError, WasiRuntimeError,
 };
 use wasmer_types::ModuleHash;
+use wasmer_wasix_types::wasi::Rights;
 
 use super::wasi_common::{MappedCommand, MAPPED_CURRENT_DIR_DEFAULT_PATH};
 
@@ -77,7 +79,52 @@ impl WasiRunner {
         I: IntoIterator<Item = D>,
         D: Into<MappedDirectory>,
     {
-        self.with_mounted_directories(dirs.into_iter().map(Into::into).map(MountedDirectory::from))
+        let policy = self.wasi.fs_policy;
+        self.with_mounted_directories(
+            dirs.into_iter()
+                .map(Into::into)
+                .map(|d: MappedDirectory| d.mount(policy)),
+        )
+    }
+
+    /// Hand a TCP listener that was bound by the host to the guest, see
+    /// [`WasiEnvBuilder::preopen_listener`].
+    pub fn with_listener(&mut self, listener: std::net::TcpListener) -> &mut Self {
+        self.wasi.listeners.push(Arc::new(listener));
+        self
+    }
+
+    pub fn with_listeners(
+        &mut self,
+        listeners: impl IntoIterator<Item = std::net::TcpListener>,
+    ) -> &mut Self {
+        for listener in listeners {
+            self.with_listener(listener);
+        }
+        self
+    }
+
+    /// Sets how symlinks inside of mapped directories are resolved.
+    ///
+    /// This only applies to directories that are mapped afterwards.
+    pub fn with_fs_policy(&mut self, policy: FsPolicy) -> &mut Self {
+        self.wasi.fs_policy = policy;
+        self
+    }
+
+    /// Preopen the directory mounted at `guest` separately and grant it
+    /// exactly `rights`, see [`crate::state::PreopenDirBuilder::rights`].
+    pub fn with_preopen_rights(&mut self, guest: impl Into<String>, rights: Rights) -> &mut Self {
+        self.wasi.preopen_rights.push((guest.into(), rights));
+        self
+    }
+
+    /// Preopen the directory mounted at `guest` separately and resolve the
+    /// paths inside of it case-insensitively, see
+    /// [`crate::state::PreopenDirBuilder::case_insensitive`].
+    pub fn with_case_insensitive_dir(&mut self, guest: impl Into<String>) -> &mut Self {
+        self.wasi.case_insensitive_dirs.push(guest.into());
+        self
     }
 
     pub fn with_home_mapped(&mut self, is_home_mapped: bool) -> &mut Self {
@@ -155,6 +202,53 @@ impl WasiRunner {
         self
     }
 
+    /// Sets the clock that is reported to the guest.
+    pub fn with_clock(&mut self, clock: ClockSource) -> &mut Self {
+        self.wasi.clock = clock;
+        self
+    }
+
+    /// Forwards SIGINT and SIGTERM to the guest instead of terminating it
+    /// right away, giving it `grace_period` to exit on its own.
+    ///
+    /// This only has an effect when the `ctrlc` feature is enabled.
+    pub fn with_signal_forwarding(&mut self, grace_period: std::time::Duration) -> &mut Self {
+        self.wasi.signal_grace_period = Some(grace_period);
+        self
+    }
+
+    /// Makes the guest appear to run as `user`.
+    pub fn with_user(&mut self, user: UserIdentity) -> &mut Self {
+        self.wasi.user = Some(user);
+        self
+    }
+
+    /// Accounts the CPU time, syscalls and I/O of the guest in `tracker`.
+    pub fn with_resource_usage(&mut self, tracker: ResourceUsageTracker) -> &mut Self {
+        self.wasi.resource_usage = Some(tracker);
+        self
+    }
+
+    /// Logs or reports the syscalls made by the guest to `tracer`.
+    pub fn with_syscall_tracer(&mut self, tracer: SyscallTracer) -> &mut Self {
+        self.wasi.syscall_tracer = Some(tracer);
+        self
+    }
+
+    /// Mounts `/proc/self` so the guest can inspect its own arguments,
+    /// environment, file descriptors and memory usage.
+    pub fn with_proc_fs(&mut self, enabled: bool) -> &mut Self {
+        self.wasi.proc_fs = enabled;
+        self
+    }
+
+    /// Seeds the randomness that is handed out to the guest so that runs
+    /// are reproducible.
+    pub fn with_random_seed(&mut self, seed: u64) -> &mut Self {
+        self.wasi.random_seed = Some(seed);
+        self
+    }
+
     pub fn with_snapshot_trigger(&mut self, on: SnapshotTrigger) -> &mut Self {
         self.wasi.snapshot_on.push(on);
         self
@@ -283,7 +377,10 @@ impl WasiRunner {
 
         #[cfg(feature = "ctrlc")]
         {
-            builder = builder.attach_ctrl_c();
+            builder = match self.wasi.signal_grace_period {
+                Some(grace_period) => builder.forward_signals(grace_period),
+                None => builder.attach_ctrl_c(),
+            };
         }
 
         #[cfg(feature = "journal")]
@@ -364,8 +461,10 @@ impl crate::runners::Runner for WasiRunner {
         let command_name = command_name.to_string();
         let tasks = runtime.task_manager().clone();
         let pkg = pkg.clone();
+        #[cfg(feature = "ctrlc")]
+        let signal_grace_period = self.wasi.signal_grace_period;
 
-        let exit_code = tasks.spawn_and_block_on(
+        let (exit_code, signal) = tasks.spawn_and_block_on(
             async move {
                 let mut task_handle =
                     crate::bin_factory::spawn_exec(pkg, &command_name, store, env, &runtime)
@@ -373,9 +472,16 @@ impl crate::runners::Runner for WasiRunner {
                         .context("Spawn failed")?;
 
                 #[cfg(feature = "ctrlc")]
-                task_handle.install_ctrlc_handler();
+                match signal_grace_period {
+                    Some(grace_period) => task_handle.forward_host_signals(grace_period),
+                    None => task_handle.install_ctrlc_handler(),
+                }
+                #[cfg(feature = "ctrlc")]
+                if runtime.tty().is_some() {
+                    task_handle.forward_window_changes();
+                }
 
-                task_handle
+                let exit_code = task_handle
                     .wait_finished()
                     .await
                     .map_err(|err| {
@@ -395,6 +501,12 @@ impl crate::runners::Runner for WasiRunner {
                                     WasiRuntimeError::Wasi(WasiError::Exit(a)) => {
                                         WasiRuntimeError::Wasi(WasiError::Exit(*a))
                                     }
+                                    WasiRuntimeError::Signal { signal, exit_code } => {
+                                        WasiRuntimeError::Signal {
+                                            signal: *signal,
+                                            exit_code: *exit_code,
+                                        }
+                                    }
                                     WasiRuntimeError::Wasi(WasiError::UnknownWasiVersion) => {
                                         WasiRuntimeError::Wasi(WasiError::UnknownWasiVersion)
                                     }
@@ -423,12 +535,15 @@ impl crate::runners::Runner for WasiRunner {
                                 })
                         })
                     })
-                    .context("Unable to wait for the process to exit")
+                    .context("Unable to wait for the process to exit")?;
+                Ok::<_, anyhow::Error>((exit_code, task_handle.terminated_by()))
             }
             .in_current_span(),
         )??;
 
-        if exit_code.raw() == 0 {
+        if let Some(signal) = signal {
+            Err(WasiRuntimeError::Signal { signal, exit_code }.into())
+        } else if exit_code.raw() == 0 {
             Ok(())
         } else {
             Err(WasiRuntimeError::Wasi(crate::WasiError::Exit(exit_code)).into())
@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -10,12 +11,14 @@ use futures::future::BoxFuture;
 use tokio::runtime::Handle;
 use virtual_fs::{FileSystem, FsError, OverlayFileSystem, RootFileSystemBuilder, TmpFileSystem};
 use wasmer::Imports;
+use wasmer_wasix_types::wasi::Rights;
 use webc::metadata::annotations::Wasi as WasiAnnotation;
 
 use crate::{
     bin_factory::BinaryPackage,
     capabilities::Capabilities,
     journal::{DynJournal, SnapshotTrigger},
+    state::{ClockSource, ResourceUsageTracker, SyscallTracer, UserIdentity},
     WasiEnvBuilder,
 };
 
@@ -47,6 +50,17 @@ pub(crate) struct CommonWasiOptions {
     pub(crate) snapshot_interval: Option<std::time::Duration>,
     pub(crate) current_dir: Option<PathBuf>,
     pub(crate) additional_imports: Imports,
+    pub(crate) clock: ClockSource,
+    pub(crate) random_seed: Option<u64>,
+    pub(crate) fs_policy: FsPolicy,
+    pub(crate) listeners: Vec<Arc<std::net::TcpListener>>,
+    pub(crate) preopen_rights: Vec<(String, Rights)>,
+    pub(crate) case_insensitive_dirs: Vec<String>,
+    pub(crate) user: Option<UserIdentity>,
+    pub(crate) resource_usage: Option<ResourceUsageTracker>,
+    pub(crate) syscall_tracer: Option<SyscallTracer>,
+    pub(crate) proc_fs: bool,
+    pub(crate) signal_grace_period: Option<std::time::Duration>,
 }
 
 impl CommonWasiOptions {
@@ -62,10 +76,36 @@ impl CommonWasiOptions {
                 .with_tmp(!self.is_tmp_mapped)
                 .build()
         });
-        let fs = prepare_filesystem(root_fs, &self.mounts, container_fs)?;
+        let fs = prepare_filesystem(root_fs.clone(), &self.mounts, container_fs)?;
+        if let Some(user) = &self.user {
+            // Added after mounting so a mapped /etc takes precedence
+            user.populate(&root_fs);
+        }
 
         builder.add_preopen_dir("/")?;
 
+        for (guest, rights) in &self.preopen_rights {
+            let case_insensitive = self.case_insensitive_dirs.contains(guest);
+            builder.add_preopen_build(|p| {
+                p.directory(guest)
+                    .alias(guest)
+                    .rights(*rights)
+                    .case_insensitive(case_insensitive)
+            })?;
+        }
+        for guest in &self.case_insensitive_dirs {
+            if self.preopen_rights.iter().all(|(dir, _)| dir != guest) {
+                builder.add_preopen_build(|p| {
+                    p.directory(guest)
+                        .alias(guest)
+                        .read(true)
+                        .write(true)
+                        .create(true)
+                        .case_insensitive(true)
+                })?;
+            }
+        }
+
         if self.mounts.iter().all(|m| m.guest != ".") {
             // The user hasn't mounted "." to anything, so let's map it to "/"
             builder.add_map_dir(".", "/")?;
@@ -88,8 +128,30 @@ impl CommonWasiOptions {
 
         *builder.capabilities_mut() = self.capabilities.clone();
 
+        builder.set_clock(self.clock);
+        if let Some(user) = &self.user {
+            builder.set_user(user.clone());
+        }
+        if let Some(seed) = self.random_seed {
+            builder.set_random_seed(seed);
+        }
+        if let Some(tracker) = &self.resource_usage {
+            builder.set_resource_usage(tracker.clone());
+        }
+        if let Some(tracer) = &self.syscall_tracer {
+            builder.set_syscall_tracer(tracer.clone());
+        }
+        builder.set_proc_fs(self.proc_fs);
+
         builder.add_imports(&self.additional_imports);
 
+        for listener in &self.listeners {
+            let listener = listener
+                .try_clone()
+                .context("Unable to duplicate a preopened listener")?;
+            builder.add_preopen_listener(listener);
+        }
+
         Ok(())
     }
 
@@ -271,22 +333,64 @@ pub struct MappedDirectory {
     pub guest: String,
 }
 
-impl From<MappedDirectory> for MountedDirectory {
-    fn from(value: MappedDirectory) -> Self {
+impl MappedDirectory {
+    /// Turns the mapping into a [`MountedDirectory`] that resolves paths
+    /// according to `policy`.
+    ///
+    /// # Panics
+    ///
+    /// This requires enabling the `host-fs` feature flag.
+    pub fn mount(self, policy: FsPolicy) -> MountedDirectory {
         cfg_if::cfg_if! {
             if #[cfg(feature = "host-fs")] {
-                let MappedDirectory { host, guest } = value;
-                let fs: Arc<dyn FileSystem + Send + Sync> =
-                    Arc::new(virtual_fs::host_fs::FileSystem::new(Handle::current(), host).unwrap());
+                let MappedDirectory { host, guest } = self;
+                let fs = virtual_fs::host_fs::FileSystem::new(Handle::current(), host)
+                    .unwrap()
+                    .with_resolve_beneath(policy == FsPolicy::Strict);
 
-                MountedDirectory { guest, fs }
+                MountedDirectory { guest, fs: Arc::new(fs) }
             } else {
-                unreachable!("The `host-fs` feature needs to be enabled to map {value:?}")
+                unreachable!("The `host-fs` feature needs to be enabled to map {self:?} ({policy:?})")
             }
         }
     }
 }
 
+impl From<MappedDirectory> for MountedDirectory {
+    fn from(value: MappedDirectory) -> Self {
+        value.mount(FsPolicy::default())
+    }
+}
+
+/// Controls how paths inside a [`MappedDirectory`] are resolved on the host.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FsPolicy {
+    /// Symlinks are followed by the host, even when they point outside of
+    /// the mapped directory.
+    #[default]
+    Permissive,
+    /// Paths never resolve to anything outside of the mapped directory.
+    ///
+    /// Symlinks that would lead outside of it (absolute symlinks or too many
+    /// `..`s) are refused, so that symlinks planted in a mapped directory can
+    /// not be used to escape the sandbox.
+    Strict,
+}
+
+impl FromStr for FsPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "permissive" => Ok(FsPolicy::Permissive),
+            "strict" => Ok(FsPolicy::Strict),
+            _ => Err(format!(
+                "unknown file system policy \"{s}\", expected \"permissive\" or \"strict\""
+            )),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct RelativeOrAbsolutePathHack<F>(F);
 
@@ -475,6 +579,58 @@ mod tests {
             .is_file());
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    #[cfg_attr(not(feature = "host-fs"), ignore)]
+    async fn strict_policy_refuses_symlinks_out_of_the_mapped_directory() {
+        let temp = TempDir::new().unwrap();
+        let mapped = temp.path().join("mapped");
+        std::fs::create_dir(&mapped).unwrap();
+        std::fs::write(temp.path().join("secret.txt"), b"secret").unwrap();
+        std::fs::write(mapped.join("file.txt"), b"Hello, World!").unwrap();
+        std::os::unix::fs::symlink("file.txt", mapped.join("link")).unwrap();
+        std::os::unix::fs::symlink("../secret.txt", mapped.join("escape")).unwrap();
+        let dir = MappedDirectory {
+            guest: "/home".to_string(),
+            host: mapped,
+        };
+
+        let permissive = prepare_filesystem(
+            RootFileSystemBuilder::default().build(),
+            &[dir.clone().mount(FsPolicy::Permissive)],
+            None,
+        )
+        .unwrap();
+        let strict = prepare_filesystem(
+            RootFileSystemBuilder::default().build(),
+            &[dir.mount(FsPolicy::Strict)],
+            None,
+        )
+        .unwrap();
+
+        assert!(permissive.metadata("/home/escape".as_ref()).is_ok());
+        assert!(strict.metadata("/home/link".as_ref()).unwrap().is_file());
+        assert_eq!(
+            strict.metadata("/home/escape".as_ref()).unwrap_err(),
+            FsError::PermissionDenied
+        );
+        assert_eq!(
+            strict
+                .new_open_options()
+                .read(true)
+                .open("/home/escape")
+                .unwrap_err(),
+            FsError::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn parse_fs_policy() {
+        assert_eq!("strict".parse(), Ok(FsPolicy::Strict));
+        assert_eq!("permissive".parse(), Ok(FsPolicy::Permissive));
+        assert!("lenient".parse::<FsPolicy>().is_err());
+    }
+
     fn unix_timestamp_nanos(instant: SystemTime) -> Option<u64> {
         let duration = instant.duration_since(SystemTime::UNIX_EPOCH).ok()?;
         Some(duration.as_nanos() as u64)
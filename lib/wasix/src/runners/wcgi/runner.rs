@@ -6,6 +6,7 @@ use futures::{stream::FuturesUnordered, StreamExt};
 use http::{Request, Response};
 use tower::ServiceBuilder;
 use tower_http::{catch_panic::CatchPanicLayer, cors::CorsLayer, trace::TraceLayer};
+use wasmer_wasix_types::wasi::Rights;
 use wcgi_host::CgiDialect;
 use webc::metadata::{
     annotations::{Wasi, Wcgi},
@@ -18,7 +19,7 @@ use crate::{
     runners::{
         wasi_common::CommonWasiOptions,
         wcgi::handler::{Handler, SharedState},
-        MappedDirectory,
+        FsPolicy, MappedDirectory,
     },
     runtime::task_manager::VirtualTaskManagerExt,
     Runtime, WasiEnvBuilder,
@@ -257,7 +258,22 @@ impl Config {
     }
 
     pub fn map_directory(&mut self, dir: MappedDirectory) -> &mut Self {
-        self.wasi.mounts.push(dir.into());
+        self.wasi.mounts.push(dir.mount(self.wasi.fs_policy));
+        self
+    }
+
+    /// Sets how symlinks inside of mapped directories are resolved.
+    ///
+    /// This only applies to directories that are mapped afterwards.
+    pub fn fs_policy(&mut self, policy: FsPolicy) -> &mut Self {
+        self.wasi.fs_policy = policy;
+        self
+    }
+
+    /// Preopen the directory mounted at `guest` separately and grant it
+    /// exactly `rights`, see [`crate::state::PreopenDirBuilder::rights`].
+    pub fn preopen_rights(&mut self, guest: impl Into<String>, rights: Rights) -> &mut Self {
+        self.wasi.preopen_rights.push((guest.into(), rights));
         self
     }
 
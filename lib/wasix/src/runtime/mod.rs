@@ -156,6 +156,13 @@ pub async fn load_module(
     match result {
         Ok(module) => return Ok(module),
         Err(CacheError::NotFound) => {}
+        Err(e @ CacheError::Deserialize(wasmer::DeserializeError::CorruptedBinary(_))) => {
+            tracing::info!(
+                %wasm_hash,
+                error=&e as &dyn std::error::Error,
+                "Recompiling a module whose cached artifact is corrupted",
+            );
+        }
         Err(other) => {
             tracing::warn!(
                 %wasm_hash,
@@ -1,6 +1,6 @@
 use wasmer::{Engine, Module};
 
-use crate::runtime::module_cache::{CacheError, ModuleCache, ModuleHash};
+use crate::runtime::module_cache::{CacheError, CacheStats, ModuleCache, ModuleHash};
 
 /// [`FallbackCache`] is a combinator for the [`ModuleCache`] trait that enables
 /// the chaining of two caching strategies together, typically via
@@ -23,6 +23,13 @@ use crate::runtime::module_cache::{CacheError, ModuleCache, ModuleHash};
 ///
 /// This "cache promotion" strategy helps keep frequently accessed modules in
 /// the faster primary cache.
+///
+/// ## Statistics
+///
+/// The [`ModuleCache::stats()`] of a [`FallbackCache`] are the ones seen by
+/// its users: a lookup only misses when both caches miss, and promotions
+/// don't count as saves. The statistics of each cache can be retrieved
+/// through [`FallbackCache::primary()`] and [`FallbackCache::fallback()`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FallbackCache<Primary, Fallback> {
     primary: Primary,
@@ -97,6 +104,20 @@ where
         )?;
         Ok(())
     }
+
+    fn stats(&self) -> CacheStats {
+        let primary = self.primary.stats();
+        let fallback = self.fallback.stats();
+        // Every lookup missing the primary cache goes to the fallback
+        CacheStats {
+            hits: primary.hits + fallback.hits,
+            misses: fallback.misses,
+            stores: fallback.stores,
+            corrupt_loads: primary.corrupt_loads + fallback.corrupt_loads,
+            bytes_loaded: primary.bytes_loaded + fallback.bytes_loaded,
+            bytes_stored: fallback.bytes_stored,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -240,4 +261,27 @@ mod tests {
         assert_eq!(primary.load(key, &engine).await.unwrap(), module);
         assert_eq!(fallback.load(key, &engine).await.unwrap(), module);
     }
+
+    #[tokio::test]
+    async fn stats_are_the_ones_seen_by_callers() {
+        let engine = Engine::default();
+        let module = Module::new(&engine, ADD_WAT).unwrap();
+        let [first, second, missing] = [0, 1, 2].map(|i| ModuleHash::xxhash_from_bytes([i; 8]));
+        let fallback = SharedCache::default();
+        fallback.save(first, &engine, &module).await.unwrap();
+        let cache = FallbackCache::new(SharedCache::default(), fallback);
+
+        // A fallback hit, promoted to the primary cache, then a primary hit
+        cache.load(first, &engine).await.unwrap();
+        cache.load(first, &engine).await.unwrap();
+        cache.save(second, &engine, &module).await.unwrap();
+        cache.load(missing, &engine).await.unwrap_err();
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        // The module saved to the fallback beforehand counts, not the promotion
+        assert_eq!(stats.stores, 2);
+        assert_eq!(cache.primary().stats().stores, 2);
+    }
 }
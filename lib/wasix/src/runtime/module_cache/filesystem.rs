@@ -1,20 +1,30 @@
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use tempfile::NamedTempFile;
 use tokio::io::AsyncWriteExt;
 use wasmer::{Engine, Module};
 
-use crate::runtime::module_cache::{CacheError, ModuleCache, ModuleHash};
+use crate::runtime::module_cache::{
+    stats::StatsRecorder, CacheError, CacheStats, ModuleCache, ModuleHash,
+};
 use crate::runtime::task_manager::tokio::TokioTaskManager;
 use crate::runtime::task_manager::VirtualTaskManagerExt;
 
 /// A cache that saves modules to a folder on the host filesystem using
 /// [`Module::serialize()`].
+///
+/// The size of the folder can be bounded with
+/// [`FileSystemCache::with_max_size()`], the least recently used modules
+/// being deleted when a new one is saved.
 #[derive(Debug, Clone)]
 pub struct FileSystemCache {
     cache_dir: PathBuf,
     task_manager: Arc<TokioTaskManager>,
+    max_size: Option<u64>,
+    stats: Arc<StatsRecorder>,
 }
 
 impl FileSystemCache {
@@ -22,13 +32,151 @@ impl FileSystemCache {
         FileSystemCache {
             cache_dir: cache_dir.into(),
             task_manager,
+            max_size: None,
+            stats: Arc::default(),
         }
     }
 
+    /// Keep the total size of the saved modules under `max_size` bytes.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
     pub fn cache_dir(&self) -> &Path {
         &self.cache_dir
     }
 
+    pub fn max_size(&self) -> Option<u64> {
+        self.max_size
+    }
+
+    /// The modules saved in the cache, for every engine.
+    ///
+    /// This blocks while the cache directory is read.
+    pub fn modules(&self) -> Vec<CachedModule> {
+        module_files(&self.cache_dir)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let path = entry.path();
+                Some(CachedModule {
+                    engine: path.parent()?.file_name()?.to_str()?.to_string(),
+                    key: path.file_stem()?.to_str()?.to_string(),
+                    size: metadata.len(),
+                })
+            })
+            .collect()
+    }
+
+    /// Write the modules for which `filter` returns `true` to a tarball,
+    /// returning them.
+    ///
+    /// The tarball starts with a manifest listing the modules, and can be
+    /// restored with [`FileSystemCache::import()`], for instance to warm up
+    /// the cache of another machine. This blocks while the modules are
+    /// read.
+    pub fn export(
+        &self,
+        writer: impl Write,
+        mut filter: impl FnMut(&CachedModule) -> bool,
+    ) -> std::io::Result<Vec<CachedModule>> {
+        let mut modules = self.modules();
+        modules.retain(|module| filter(module));
+        modules.sort_by(|a, b| (&a.engine, &a.key).cmp(&(&b.engine, &b.key)));
+
+        let manifest = CacheManifest {
+            wasmer_version: env!("CARGO_PKG_VERSION").to_string(),
+            modules,
+        };
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+
+        let mut builder = tar::Builder::new(writer);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(now());
+        builder.append_data(&mut header, MANIFEST, manifest_bytes.as_slice())?;
+
+        let mut exported = Vec::new();
+        for module in manifest.modules {
+            let path = self.cache_dir.join(module.path());
+            // Modules may be evicted concurrently
+            match std::fs::File::open(&path) {
+                Ok(mut file) => builder.append_file(module.path(), &mut file)?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            exported.push(module);
+        }
+        builder.into_inner()?.flush()?;
+
+        Ok(exported)
+    }
+
+    /// Restore the modules of a tarball created by
+    /// [`FileSystemCache::export()`], returning them.
+    ///
+    /// Only the modules listed in the manifest are restored, replacing the
+    /// cached ones. This blocks while the modules are written.
+    pub fn import(&self, reader: impl Read) -> std::io::Result<Vec<CachedModule>> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+        let mut archive = tar::Archive::new(reader);
+        let mut entries = archive.entries()?;
+        let manifest: CacheManifest = match entries.next() {
+            Some(entry) => {
+                let entry = entry?;
+                if entry.path()?.as_ref() != Path::new(MANIFEST) {
+                    return Err(invalid(format!(
+                        "the archive doesn't start with {MANIFEST}"
+                    )));
+                }
+                serde_json::from_reader(entry)?
+            }
+            None => return Err(invalid("the archive is empty".to_string())),
+        };
+        for module in &manifest.modules {
+            if !is_valid_name(&module.engine) || !is_valid_name(&module.key) {
+                return Err(invalid(format!(
+                    "invalid module \"{}\" in the manifest",
+                    module.path().display()
+                )));
+            }
+        }
+
+        let mut imported = Vec::new();
+        for entry in entries {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+            let Some(module) = manifest
+                .modules
+                .iter()
+                .find(|module| module.path() == entry_path)
+            else {
+                tracing::debug!(path=%entry_path.display(), "Skipping a file missing from the manifest");
+                continue;
+            };
+            if entry.size() != module.size {
+                return Err(invalid(format!(
+                    "the size of \"{}\" doesn't match the manifest",
+                    entry_path.display()
+                )));
+            }
+
+            let path = self.cache_dir.join(&entry_path);
+            let parent = path
+                .parent()
+                .expect("Unreachable - always created by joining onto cache_dir");
+            std::fs::create_dir_all(parent)?;
+            let mut temp = NamedTempFile::new_in(parent)?;
+            std::io::copy(&mut entry, &mut temp)?;
+            temp.persist(&path).map_err(|e| e.error)?;
+            imported.push(module.clone());
+        }
+
+        Ok(imported)
+    }
+
     fn path(&self, key: ModuleHash, deterministic_id: &str) -> PathBuf {
         let artifact_version = wasmer_types::MetadataHeader::CURRENT_VERSION;
         self.cache_dir
@@ -49,19 +197,56 @@ impl ModuleCache for FileSystemCache {
             .spawn({
                 let task_manager = self.task_manager.clone();
                 let engine = engine.clone();
+                let stats = self.stats.clone();
 
                 async move {
-                    let bytes = read_file(&path).await?;
+                    let bytes = match read_file(&path).await {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            stats.miss();
+                            return Err(e);
+                        }
+                    };
+
+                    if let Err(e) = verify_checksum(&path, &bytes) {
+                        stats.corrupt();
+                        tracing::debug!(
+                            %key,
+                            path=%path.display(),
+                            error=&e as &dyn std::error::Error,
+                            "Deleting the cache file, the module will be compiled and saved again",
+                        );
+                        if let Err(e) = tokio::fs::remove_file(&path).await {
+                            tracing::warn!(
+                                %key,
+                                path=%path.display(),
+                                error=&e as &dyn std::error::Error,
+                                "Unable to remove the corrupted cache file",
+                            );
+                        }
+                        return Err(e);
+                    }
 
                     task_manager
                     .spawn_await({
 
-                        move || match deserialize(&bytes, &engine) {
+                        move || match deserialize(artifact(&bytes), &engine) {
                             Ok(m) => {
                                 tracing::debug!("Cache hit!");
+                                stats.hit(artifact(&bytes).len() as u64);
+                                // The modification time tells which modules
+                                // were used recently
+                                if let Err(e) = filetime::set_file_mtime(&path, filetime::FileTime::now()) {
+                                    tracing::debug!(
+                                        path=%path.display(),
+                                        error=&e as &dyn std::error::Error,
+                                        "Unable to update the modification time of the cache file",
+                                    );
+                                }
                                 Ok(m)
                             }
                             Err(e) => {
+                                stats.corrupt();
                                 tracing::debug!(
                                     %key,
                                     path=%path.display(),
@@ -104,6 +289,9 @@ impl ModuleCache for FileSystemCache {
             .spawn({
                 let task_manager = self.task_manager.clone();
                 let module = module.clone();
+                let cache_dir = self.cache_dir.clone();
+                let max_size = self.max_size;
+                let stats = self.stats.clone();
 
                 async move {
                     let parent = path
@@ -132,6 +320,9 @@ impl ModuleCache for FileSystemCache {
                         .unwrap()?;
 
                     let mut writer = tokio::io::BufWriter::new(&mut file);
+                    if let Err(error) = writer.write_all(&checksum_header(&serialized)).await {
+                        return Err(CacheError::FileWrite { path, error });
+                    }
                     if let Err(error) = writer.write_all(&serialized).await {
                         return Err(CacheError::FileWrite { path, error });
                     }
@@ -140,14 +331,84 @@ impl ModuleCache for FileSystemCache {
                     }
 
                     temp.persist(&path).map_err(CacheError::other)?;
+                    stats.store(serialized.len() as u64);
                     tracing::debug!(path=%path.display(), "Saved to disk");
 
+                    if let Some(max_size) = max_size {
+                        task_manager
+                            .spawn_await(move || evict(&cache_dir, max_size, &path))
+                            .await
+                            .unwrap();
+                    }
+
                     Ok(())
                 }
             })
             .await
             .unwrap()
     }
+
+    fn stats(&self) -> CacheStats {
+        self.stats.stats()
+    }
+}
+
+/// Marks the files whose artifact is preceded by its checksum.
+const CHECKSUM_MAGIC: &[u8; 8] = b"wasmerck";
+/// The magic bytes and the checksum.
+const CHECKSUM_HEADER_LEN: usize = CHECKSUM_MAGIC.len() + 8;
+
+/// The header written before a saved artifact, made of [`CHECKSUM_MAGIC`]
+/// followed by the xxhash of the artifact.
+///
+/// The checksum comes first so that truncated files are detected too.
+fn checksum_header(artifact: &[u8]) -> [u8; CHECKSUM_HEADER_LEN] {
+    let mut header = [0; CHECKSUM_HEADER_LEN];
+    header[..CHECKSUM_MAGIC.len()].copy_from_slice(CHECKSUM_MAGIC);
+    header[CHECKSUM_MAGIC.len()..]
+        .copy_from_slice(&xxhash_rust::xxh64::xxh64(artifact, 0).to_le_bytes());
+    header
+}
+
+/// Split a cache file into its artifact and the checksum saved with it.
+///
+/// Files saved by older versions don't have a checksum, their artifact is
+/// validated when it is deserialized instead. Returns `None` for a file
+/// truncated in the middle of its header.
+fn split_checksum(bytes: &[u8]) -> Option<(&[u8], Option<u64>)> {
+    if !bytes.starts_with(CHECKSUM_MAGIC) {
+        return Some((bytes, None));
+    }
+    let header = bytes.get(..CHECKSUM_HEADER_LEN)?;
+    let checksum = u64::from_le_bytes(header[CHECKSUM_MAGIC.len()..].try_into().unwrap());
+    Some((&bytes[CHECKSUM_HEADER_LEN..], Some(checksum)))
+}
+
+fn artifact(bytes: &[u8]) -> &[u8] {
+    split_checksum(bytes).map_or(bytes, |(artifact, _)| artifact)
+}
+
+fn verify_checksum(path: &Path, bytes: &[u8]) -> Result<(), CacheError> {
+    let corrupted = |reason: String| {
+        CacheError::Deserialize(wasmer::DeserializeError::CorruptedBinary(format!(
+            "the module cached at \"{}\" is corrupted ({reason})",
+            path.display()
+        )))
+    };
+    let (artifact, expected) = match split_checksum(bytes) {
+        Some((artifact, Some(expected))) => (artifact, expected),
+        Some((_, None)) => return Ok(()),
+        None => return Err(corrupted("truncated checksum".to_string())),
+    };
+
+    let actual = xxhash_rust::xxh64::xxh64(artifact, 0);
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(corrupted(format!(
+            "checksum {actual:016x} instead of {expected:016x}"
+        )))
+    }
 }
 
 async fn read_file(path: &Path) -> Result<Vec<u8>, CacheError> {
@@ -161,6 +422,107 @@ async fn read_file(path: &Path) -> Result<Vec<u8>, CacheError> {
     }
 }
 
+/// The first file of the tarballs created by [`FileSystemCache::export()`].
+const MANIFEST: &str = "manifest.json";
+
+/// The contents of [`MANIFEST`].
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct CacheManifest {
+    wasmer_version: String,
+    modules: Vec<CachedModule>,
+}
+
+/// A module saved in a [`FileSystemCache`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CachedModule {
+    /// The [`Engine::deterministic_id()`] of the engine which compiled the
+    /// module, followed by the version of the artifact format.
+    pub engine: String,
+    /// The hash of the module, in hexadecimal.
+    pub key: String,
+    /// The size of the cache file, in bytes.
+    pub size: u64,
+}
+
+impl CachedModule {
+    /// The path of the module, relative to the cache directory.
+    fn path(&self) -> PathBuf {
+        Path::new(&self.engine)
+            .join(&self.key)
+            .with_extension("bin")
+    }
+}
+
+/// Whether `name` can be used as a file name without escaping the cache
+/// directory.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The files of the modules saved in `cache_dir`.
+fn module_files(cache_dir: &Path) -> impl Iterator<Item = std::fs::DirEntry> {
+    // Modules are saved in one folder per engine
+    std::fs::read_dir(cache_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(|dir| {
+            std::fs::read_dir(dir.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+        })
+        .filter(|entry| entry.path().extension() == Some("bin".as_ref()))
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+}
+
+/// Deletes the least recently used modules until the ones in `cache_dir`
+/// take at most `max_size` bytes, sparing the one at `keep`.
+fn evict(cache_dir: &Path, max_size: u64, keep: &Path) {
+    let mut modules: Vec<(SystemTime, u64, PathBuf)> = module_files(cache_dir)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            Some((modified, metadata.len(), entry.path()))
+        })
+        .collect();
+
+    let mut total_size: u64 = modules.iter().map(|(_, size, _)| size).sum();
+    modules.sort();
+    for (_, size, path) in modules {
+        if total_size <= max_size {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                tracing::debug!(path=%path.display(), "Evicted from the cache");
+                total_size -= size;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => total_size -= size,
+            Err(e) => tracing::warn!(
+                path=%path.display(),
+                error=&e as &dyn std::error::Error,
+                "Unable to evict a module from the cache",
+            ),
+        }
+    }
+}
+
 fn deserialize(bytes: &[u8], engine: &Engine) -> Result<Module, CacheError> {
     // We used to compress our compiled modules using LZW encoding in the past.
     // This was removed because it has a negative impact on startup times for
@@ -276,6 +638,34 @@ mod tests {
         assert_eq!(exports, ["add"]);
     }
 
+    #[tokio::test]
+    async fn evict_least_recently_used_modules() {
+        let temp = TempDir::new().unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, ADD_WAT).unwrap();
+        let size = (module.serialize().unwrap().len() + CHECKSUM_HEADER_LEN) as u64;
+        let cache =
+            FileSystemCache::new(temp.path(), create_tokio_task_manager()).with_max_size(2 * size);
+        let [first, second, third] = [1, 2, 3].map(|i| ModuleHash::xxhash_from_bytes([i; 8]));
+        let set_age = |key, secs| {
+            let time = SystemTime::now() - std::time::Duration::from_secs(secs);
+            let path = cache.path(key, engine.deterministic_id());
+            filetime::set_file_mtime(path, filetime::FileTime::from_system_time(time)).unwrap();
+        };
+
+        cache.save(first, &engine, &module).await.unwrap();
+        cache.save(second, &engine, &module).await.unwrap();
+        set_age(first, 200);
+        set_age(second, 100);
+        // Loading a module makes it the most recently used one
+        cache.load(first, &engine).await.unwrap();
+        cache.save(third, &engine, &module).await.unwrap();
+
+        assert!(cache.path(first, engine.deterministic_id()).exists());
+        assert!(!cache.path(second, engine.deterministic_id()).exists());
+        assert!(cache.path(third, engine.deterministic_id()).exists());
+    }
+
     /// For backwards compatibility, make sure we can still work with LZW
     /// compressed modules.
     #[tokio::test]
@@ -299,4 +689,121 @@ mod tests {
             .collect();
         assert_eq!(exports, ["add"]);
     }
+
+    #[tokio::test]
+    async fn stats() {
+        let temp = TempDir::new().unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, ADD_WAT).unwrap();
+        let [key, corrupt] = [0, 1].map(|i| ModuleHash::xxhash_from_bytes([i; 8]));
+        let cache = FileSystemCache::new(temp.path(), create_tokio_task_manager());
+        let corrupt_path = cache.path(corrupt, engine.deterministic_id());
+        std::fs::create_dir_all(corrupt_path.parent().unwrap()).unwrap();
+        std::fs::write(&corrupt_path, b"not a module").unwrap();
+        let size = module.serialize().unwrap().len() as u64;
+
+        cache.load(key, &engine).await.unwrap_err();
+        cache.save(key, &engine, &module).await.unwrap();
+        cache.load(key, &engine).await.unwrap();
+        cache.load(corrupt, &engine).await.unwrap_err();
+
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                stores: 1,
+                corrupt_loads: 1,
+                bytes_loaded: size,
+                bytes_stored: size,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupted_artifacts_are_detected_with_their_checksum() {
+        let temp = TempDir::new().unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, ADD_WAT).unwrap();
+        let key = ModuleHash::xxhash_from_bytes([0; 8]);
+        let cache = FileSystemCache::new(temp.path(), create_tokio_task_manager());
+        let path = cache.path(key, engine.deterministic_id());
+        cache.save(key, &engine, &module).await.unwrap();
+        let mut bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(CHECKSUM_MAGIC));
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+        let err = cache.load(key, &engine).await.unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                CacheError::Deserialize(wasmer::DeserializeError::CorruptedBinary(_))
+            ),
+            "{err:?}"
+        );
+        assert!(!path.exists());
+        assert_eq!(cache.stats().corrupt_loads, 1);
+    }
+
+    #[tokio::test]
+    async fn truncated_artifacts_are_detected_with_their_checksum() {
+        let temp = TempDir::new().unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, ADD_WAT).unwrap();
+        let key = ModuleHash::xxhash_from_bytes([0; 8]);
+        let cache = FileSystemCache::new(temp.path(), create_tokio_task_manager());
+        let path = cache.path(key, engine.deterministic_id());
+        cache.save(key, &engine, &module).await.unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        for len in [CHECKSUM_HEADER_LEN - 1, bytes.len() - 1] {
+            std::fs::write(&path, &bytes[..len]).unwrap();
+            let err = cache.load(key, &engine).await.unwrap_err();
+
+            assert!(
+                matches!(
+                    err,
+                    CacheError::Deserialize(wasmer::DeserializeError::CorruptedBinary(_))
+                ),
+                "{err:?}"
+            );
+            assert!(!path.exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn export_and_import() {
+        let engine = Engine::default();
+        let module = Module::new(&engine, ADD_WAT).unwrap();
+        let [exported, skipped] = [0, 1].map(|i| ModuleHash::xxhash_from_bytes([i; 8]));
+        let source_dir = TempDir::new().unwrap();
+        let source = FileSystemCache::new(source_dir.path(), create_tokio_task_manager());
+        source.save(exported, &engine, &module).await.unwrap();
+        source.save(skipped, &engine, &module).await.unwrap();
+
+        let mut tarball = Vec::new();
+        let modules = source
+            .export(&mut tarball, |m| m.key == exported.to_string())
+            .unwrap();
+        let target_dir = TempDir::new().unwrap();
+        let target = FileSystemCache::new(target_dir.path(), create_tokio_task_manager());
+        let imported = target.import(tarball.as_slice()).unwrap();
+
+        assert_eq!(imported, modules);
+        assert_eq!(target.modules(), modules);
+        target.load(exported, &engine).await.unwrap();
+        target.load(skipped, &engine).await.unwrap_err();
+    }
+
+    #[test]
+    fn module_names_cannot_escape_the_cache_directory() {
+        assert!(is_valid_name("engine-cranelift-0123456789abcdef-v9"));
+        assert!(!is_valid_name(".."));
+        assert!(!is_valid_name("../evil"));
+        assert!(!is_valid_name("/etc"));
+        assert!(!is_valid_name(""));
+    }
 }
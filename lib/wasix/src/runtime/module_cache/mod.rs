@@ -30,24 +30,31 @@
 //! The `module_cache` module provides combinators for extending and combining
 //! caching strategies. For example, you could use the [`FallbackCache`] to
 //! chain a fast in-memory cache with a slower file-based cache as a fallback.
+//!
+//! ## Statistics
+//!
+//! Caches keep track of their hits, misses and saves, which can be retrieved
+//! with [`ModuleCache::stats()`] to check whether they are effective.
 
 mod fallback;
 #[cfg(feature = "sys-thread")]
 mod filesystem;
 mod shared;
+mod stats;
 mod thread_local;
 mod types;
 
 pub use self::{
     fallback::FallbackCache,
     shared::SharedCache,
+    stats::CacheStats,
     thread_local::ThreadLocalCache,
     types::{CacheError, ModuleCache},
 };
 use wasmer_types::ModuleHash;
 
 #[cfg(feature = "sys-thread")]
-pub use self::filesystem::FileSystemCache;
+pub use self::filesystem::{CachedModule, FileSystemCache};
 
 /// Get a [`ModuleCache`] which should be good enough for most in-memory use
 /// cases.
@@ -1,13 +1,14 @@
 use dashmap::DashMap;
 use wasmer::{Engine, Module};
 
-use crate::runtime::module_cache::{CacheError, ModuleCache};
+use crate::runtime::module_cache::{stats::StatsRecorder, CacheError, CacheStats, ModuleCache};
 use wasmer_types::ModuleHash;
 
 /// A [`ModuleCache`] based on a <code>[DashMap]<[ModuleHash], [Module]></code>.
 #[derive(Debug, Default, Clone)]
 pub struct SharedCache {
     modules: DashMap<(ModuleHash, String), Module>,
+    stats: StatsRecorder,
 }
 
 impl SharedCache {
@@ -25,10 +26,14 @@ impl ModuleCache for SharedCache {
         match self.modules.get(&key) {
             Some(m) => {
                 tracing::debug!("Cache hit!");
+                self.stats.hit(0);
                 Ok(m.value().clone())
             }
 
-            None => Err(CacheError::NotFound),
+            None => {
+                self.stats.miss();
+                Err(CacheError::NotFound)
+            }
         }
     }
 
@@ -41,9 +46,14 @@ impl ModuleCache for SharedCache {
     ) -> Result<(), CacheError> {
         let key = (key, engine.deterministic_id().to_string());
         self.modules.insert(key, module.clone());
+        self.stats.store(0);
 
         Ok(())
     }
+
+    fn stats(&self) -> CacheStats {
+        self.stats.stats()
+    }
 }
 
 #[cfg(test)]
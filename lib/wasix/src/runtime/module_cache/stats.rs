@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Statistics about the operations on a [`ModuleCache`], retrieved with
+/// [`ModuleCache::stats()`].
+///
+/// [`ModuleCache`]: crate::runtime::module_cache::ModuleCache
+/// [`ModuleCache::stats()`]: crate::runtime::module_cache::ModuleCache::stats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CacheStats {
+    /// Modules loaded from the cache.
+    pub hits: u64,
+    /// Modules looked up but not found in the cache.
+    pub misses: u64,
+    /// Modules saved to the cache.
+    pub stores: u64,
+    /// Cached modules which couldn't be deserialized, and were discarded.
+    pub corrupt_loads: u64,
+    /// The size of the serialized modules which were loaded, in bytes.
+    pub bytes_loaded: u64,
+    /// The size of the serialized modules which were saved, in bytes.
+    pub bytes_stored: u64,
+}
+
+impl CacheStats {
+    /// The number of modules looked up in the cache.
+    pub fn loads(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// The proportion of lookups which found a module, if there were any.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        match self.loads() {
+            0 => None,
+            loads => Some(self.hits as f64 / loads as f64),
+        }
+    }
+}
+
+impl std::ops::AddAssign for CacheStats {
+    fn add_assign(&mut self, other: Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.stores += other.stores;
+        self.corrupt_loads += other.corrupt_loads;
+        self.bytes_loaded += other.bytes_loaded;
+        self.bytes_stored += other.bytes_stored;
+    }
+}
+
+/// Counters updated by the caches as they are used.
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stores: AtomicU64,
+    corrupt_loads: AtomicU64,
+    bytes_loaded: AtomicU64,
+    bytes_stored: AtomicU64,
+}
+
+impl StatsRecorder {
+    pub(crate) fn hit(&self, bytes: u64) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.bytes_loaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn store(&self, bytes: u64) {
+        self.stores.fetch_add(1, Ordering::Relaxed);
+        self.bytes_stored.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// A module was found but couldn't be used, which also counts as a miss.
+    pub(crate) fn corrupt(&self) {
+        self.corrupt_loads.fetch_add(1, Ordering::Relaxed);
+        self.miss();
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            stores: self.stores.load(Ordering::Relaxed),
+            corrupt_loads: self.corrupt_loads.load(Ordering::Relaxed),
+            bytes_loaded: self.bytes_loaded.load(Ordering::Relaxed),
+            bytes_stored: self.bytes_stored.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Clone for StatsRecorder {
+    fn clone(&self) -> Self {
+        let stats = self.stats();
+        StatsRecorder {
+            hits: AtomicU64::new(stats.hits),
+            misses: AtomicU64::new(stats.misses),
+            stores: AtomicU64::new(stats.stores),
+            corrupt_loads: AtomicU64::new(stats.corrupt_loads),
+            bytes_loaded: AtomicU64::new(stats.bytes_loaded),
+            bytes_stored: AtomicU64::new(stats.bytes_stored),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::{cell::RefCell, collections::HashMap};
 
 use wasmer::{Engine, Module};
 
-use crate::runtime::module_cache::{CacheError, ModuleCache};
+use crate::runtime::module_cache::{stats::StatsRecorder, CacheError, CacheStats, ModuleCache};
 use wasmer_types::ModuleHash;
 
 std::thread_local! {
@@ -13,7 +13,9 @@ std::thread_local! {
 /// A cache that will cache modules in a thread-local variable.
 #[derive(Debug, Clone, Default)]
 #[non_exhaustive]
-pub struct ThreadLocalCache {}
+pub struct ThreadLocalCache {
+    stats: StatsRecorder,
+}
 
 impl ThreadLocalCache {
     fn lookup(&self, key: ModuleHash, deterministic_id: &str) -> Option<Module> {
@@ -34,9 +36,13 @@ impl ModuleCache for ThreadLocalCache {
         match self.lookup(key, engine.deterministic_id()) {
             Some(m) => {
                 tracing::debug!("Cache hit!");
+                self.stats.hit(0);
                 Ok(m)
             }
-            None => Err(CacheError::NotFound),
+            None => {
+                self.stats.miss();
+                Err(CacheError::NotFound)
+            }
         }
     }
 
@@ -48,8 +54,13 @@ impl ModuleCache for ThreadLocalCache {
         module: &Module,
     ) -> Result<(), CacheError> {
         self.insert(key, module, engine.deterministic_id());
+        self.stats.store(0);
         Ok(())
     }
+
+    fn stats(&self) -> CacheStats {
+        self.stats.stats()
+    }
 }
 
 #[cfg(test)]
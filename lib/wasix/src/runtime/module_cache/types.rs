@@ -3,7 +3,7 @@ use std::{fmt::Debug, ops::Deref, path::PathBuf};
 use wasmer::{Engine, Module};
 use wasmer_types::ModuleHash;
 
-use crate::runtime::module_cache::FallbackCache;
+use crate::runtime::module_cache::{CacheStats, FallbackCache};
 
 /// A cache for compiled WebAssembly modules.
 ///
@@ -41,6 +41,14 @@ pub trait ModuleCache: Debug {
         module: &Module,
     ) -> Result<(), CacheError>;
 
+    /// Statistics about the modules loaded from and saved to this cache.
+    ///
+    /// Implementations which don't keep track of them return empty
+    /// statistics.
+    fn stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+
     /// Chain a second [`ModuleCache`] that will be used as a fallback if
     /// lookups on the primary cache fail.
     ///
@@ -87,6 +95,10 @@ where
     ) -> Result<(), CacheError> {
         (**self).save(key, engine, module).await
     }
+
+    fn stats(&self) -> CacheStats {
+        (**self).stats()
+    }
 }
 
 /// Possible errors that may occur during [`ModuleCache`] operations.
@@ -6,9 +6,13 @@ use std::{
     sync::Arc,
 };
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use thiserror::Error;
-use virtual_fs::{ArcFile, FileSystem, FsError, TmpFileSystem, VirtualFile};
+use virtual_fs::{
+    ArcFile, CaptureFile, CapturedOutput, FileSystem, FsError, ReaderFile, TmpFileSystem,
+    VirtualFile, WriterFile,
+};
 use wasmer::{AsStoreMut, Extern, Imports, Instance, Module, Store};
 use wasmer_config::package::PackageId;
 
@@ -17,9 +21,15 @@ use crate::journal::{DynJournal, SnapshotTrigger};
 use crate::{
     bin_factory::{BinFactory, BinaryPackage},
     capabilities::Capabilities,
-    fs::{WasiFs, WasiFsRoot, WasiInodes},
+    fs::{
+        FsLimits, FsQuota, Kind, ProcFileSystem, ProcInfo, SharedWasiFs, WasiFs, WasiFsRoot,
+        WasiInodes,
+    },
+    net::socket::{InodeSocket, InodeSocketKind},
     os::task::control_plane::{ControlPlaneConfig, ControlPlaneError, WasiControlPlane},
-    state::WasiState,
+    state::{
+        ClockSource, ResourceUsageTracker, SyscallTracer, UserIdentity, WasiClock, WasiState,
+    },
     syscalls::{
         rewind_ext2,
         types::{__WASI_STDERR_FILENO, __WASI_STDIN_FILENO, __WASI_STDOUT_FILENO},
@@ -28,6 +38,7 @@ use crate::{
     Runtime, WasiEnv, WasiError, WasiFunctionEnv, WasiRuntimeError,
 };
 use wasmer_types::ModuleHash;
+use wasmer_wasix_types::wasi::{Fdflags, Rights};
 
 use super::env::WasiEnvInit;
 
@@ -57,6 +68,10 @@ pub struct WasiEnvBuilder {
     pub(super) preopens: Vec<PreopenedDir>,
     /// Pre-opened virtual directories that will be accessible from WASI.
     vfs_preopens: Vec<String>,
+    /// Additional filesystems mounted at a guest path.
+    mounts: Vec<(PathBuf, Box<dyn FileSystem + Send + Sync>)>,
+    /// Listening sockets that are handed to the guest.
+    listeners: Vec<std::net::TcpListener>,
     #[allow(clippy::type_complexity)]
     pub(super) setup_fs_fn:
         Option<Box<dyn Fn(&WasiInodes, &mut WasiFs) -> Result<(), String> + Send>>,
@@ -64,6 +79,8 @@ pub struct WasiEnvBuilder {
     pub(super) stderr: Option<Box<dyn VirtualFile + Send + Sync + 'static>>,
     pub(super) stdin: Option<Box<dyn VirtualFile + Send + Sync + 'static>>,
     pub(super) fs: Option<WasiFsRoot>,
+    /// A file system shared with other instances, used instead of `fs`.
+    pub(super) shared_fs: Option<SharedWasiFs>,
     pub(super) runtime: Option<Arc<dyn crate::Runtime + Send + Sync + 'static>>,
     pub(super) current_dir: Option<PathBuf>,
 
@@ -80,6 +97,25 @@ pub struct WasiEnvBuilder {
     pub(super) capabilites: Capabilities,
     pub(super) additional_imports: Imports,
 
+    /// Quotas applied to the file system of the instance.
+    pub(super) fs_limits: FsLimits,
+
+    /// Where `clock_time_get` gets the time from.
+    pub(super) clock: ClockSource,
+    /// Seed for `random_get`, the host's randomness is used if not set.
+    pub(super) random_seed: Option<u64>,
+    /// The user the guest appears to be running as.
+    pub(super) user: Option<UserIdentity>,
+    /// Where the resources consumed by the instance are accounted.
+    pub(super) resource_usage: Option<ResourceUsageTracker>,
+    /// Where the syscalls made by the instance are reported.
+    pub(super) syscall_tracer: Option<SyscallTracer>,
+    /// Whether `/proc/self` is mounted for the guest to inspect itself.
+    pub(super) proc_fs: bool,
+
+    /// Whether the I/O syscalls suspend the guest instead of blocking.
+    pub(super) async_io: bool,
+
     #[cfg(feature = "journal")]
     pub(super) snapshot_on: Vec<SnapshotTrigger>,
 
@@ -91,6 +127,10 @@ pub struct WasiEnvBuilder {
 
     #[cfg(feature = "ctrlc")]
     pub(super) attach_ctrl_c: bool,
+
+    /// How long the guest gets to exit after a forwarded SIGINT or SIGTERM.
+    #[cfg(feature = "ctrlc")]
+    pub(super) signal_grace_period: Option<std::time::Duration>,
 }
 
 impl std::fmt::Debug for WasiEnvBuilder {
@@ -100,6 +140,7 @@ impl std::fmt::Debug for WasiEnvBuilder {
             .field("args", &self.args)
             .field("envs", &self.envs)
             .field("preopens", &self.preopens)
+            .field("listeners", &self.listeners)
             .field("uses", &self.uses)
             .field("setup_fs_fn exists", &self.setup_fs_fn.is_some())
             .field("stdout_override exists", &self.stdout.is_some())
@@ -121,6 +162,8 @@ pub enum WasiStateCreationError {
     PreopenedDirectoryNotFound(PathBuf),
     #[error("preopened directory error: `{0}`")]
     PreopenedDirectoryError(String),
+    #[error("preopened listener error: `{0}`")]
+    PreopenedListenerError(String),
     #[error("mapped dir alias has wrong format: `{0}`")]
     MappedDirAliasFormattingError(String),
     #[error("wasi filesystem creation error: `{0}`")]
@@ -149,8 +192,6 @@ fn validate_mapped_dir_alias(alias: &str) -> Result<(), WasiStateCreationError>
 
 pub type SetupFsFn = Box<dyn Fn(&WasiInodes, &mut WasiFs) -> Result<(), String> + Send>;
 
-// TODO add other WasiFS APIs here like swapping out stdout, for example (though we need to
-// return stdout somehow, it's unclear what that API should look like)
 impl WasiEnvBuilder {
     /// Creates an empty [`WasiEnvBuilder`].
     pub fn new(program_name: impl Into<String>) -> Self {
@@ -182,6 +223,21 @@ impl WasiEnvBuilder {
         self
     }
 
+    /// Forwards SIGINT and SIGTERM received by the host to the guest, which
+    /// then has `grace_period` to exit before the host process is terminated.
+    ///
+    /// See [`crate::os::task::TaskJoinHandle::forward_host_signals()`].
+    #[cfg(feature = "ctrlc")]
+    pub fn forward_signals(mut self, grace_period: std::time::Duration) -> Self {
+        self.set_forward_signals(grace_period);
+        self
+    }
+
+    #[cfg(feature = "ctrlc")]
+    pub fn set_forward_signals(&mut self, grace_period: std::time::Duration) {
+        self.signal_grace_period = Some(grace_period);
+    }
+
     /// Add an environment variable pair.
     ///
     /// Both the key and value of an environment variable must not
@@ -399,6 +455,21 @@ impl WasiEnvBuilder {
         }
     }
 
+    /// Preopen a TCP listener that was bound by the host.
+    ///
+    /// The guest can accept connections on the socket without having to
+    /// create or bind it itself, which is how socket activation works. The
+    /// listeners get the file descriptors that follow the preopened
+    /// directories, in the order they were added.
+    pub fn preopen_listener(mut self, listener: std::net::TcpListener) -> Self {
+        self.add_preopen_listener(listener);
+        self
+    }
+
+    pub fn add_preopen_listener(&mut self, listener: std::net::TcpListener) {
+        self.listeners.push(listener);
+    }
+
     /// Preopen a directory
     ///
     /// This opens the given directory at the virtual root, `/`, and allows
@@ -547,6 +618,86 @@ impl WasiEnvBuilder {
         Ok(self)
     }
 
+    /// Mount a filesystem at `guest_path` inside the root filesystem.
+    ///
+    /// This can be used to layer several filesystems at the same location,
+    /// e.g. by mounting a [`virtual_fs::OverlayFileSystem`] with read-only
+    /// lower layers and a writable upper layer. Custom storage can be exposed
+    /// by implementing [`virtual_fs::FsBackend`] and mounting it as a
+    /// [`virtual_fs::BackendFileSystem`].
+    pub fn mount<G>(
+        mut self,
+        guest_path: G,
+        fs: Box<dyn FileSystem + Send + Sync>,
+    ) -> Result<Self, WasiStateCreationError>
+    where
+        G: Into<PathBuf>,
+    {
+        self.add_mount(guest_path, fs)?;
+        Ok(self)
+    }
+
+    /// Mount a filesystem at `guest_path` inside the root filesystem.
+    pub fn add_mount<G>(
+        &mut self,
+        guest_path: G,
+        fs: Box<dyn FileSystem + Send + Sync>,
+    ) -> Result<(), WasiStateCreationError>
+    where
+        G: Into<PathBuf>,
+    {
+        let guest_path = guest_path.into();
+        if !guest_path.is_absolute() {
+            return Err(WasiStateCreationError::WasiFsSetupError(format!(
+                "mount point '{}' must be an absolute path",
+                guest_path.display()
+            )));
+        }
+        self.mounts.push((guest_path, fs));
+
+        Ok(())
+    }
+
+    /// Mount the contents of a tar, tar.gz or zip archive on the host as a
+    /// read-only directory at `guest_path`.
+    ///
+    /// The archive is loaded into memory, nothing is unpacked to disk.
+    #[cfg(feature = "host-fs")]
+    pub fn mount_archive<P, G>(
+        mut self,
+        archive: P,
+        guest_path: G,
+    ) -> Result<Self, WasiStateCreationError>
+    where
+        P: AsRef<Path>,
+        G: Into<PathBuf>,
+    {
+        self.add_archive_mount(archive, guest_path)?;
+        Ok(self)
+    }
+
+    /// Mount the contents of a tar, tar.gz or zip archive on the host as a
+    /// read-only directory at `guest_path`.
+    #[cfg(feature = "host-fs")]
+    pub fn add_archive_mount<P, G>(
+        &mut self,
+        archive: P,
+        guest_path: G,
+    ) -> Result<(), WasiStateCreationError>
+    where
+        P: AsRef<Path>,
+        G: Into<PathBuf>,
+    {
+        let archive = archive.as_ref();
+        let fs = virtual_fs::ArchiveFileSystem::from_path(archive).map_err(|err| {
+            WasiStateCreationError::WasiFsSetupError(format!(
+                "Could not load the archive at '{}': {err}",
+                archive.display()
+            ))
+        })?;
+        self.add_mount(guest_path, Box::new(fs))
+    }
+
     /// Specifies one or more journal files that Wasmer will use to restore
     /// the state of the WASM process.
     ///
@@ -611,6 +762,59 @@ impl WasiEnvBuilder {
         self.stdin = Some(new_file);
     }
 
+    /// Send everything the guest writes to `stdout` to a [`std::io::Write`].
+    pub fn stdout_writer(mut self, writer: impl std::io::Write + Send + 'static) -> Self {
+        self.set_stdout_writer(writer);
+        self
+    }
+
+    /// Send everything the guest writes to `stdout` to a [`std::io::Write`].
+    pub fn set_stdout_writer(&mut self, writer: impl std::io::Write + Send + 'static) {
+        self.stdout = Some(Box::new(WriterFile::new(writer)));
+    }
+
+    /// Send everything the guest writes to `stderr` to a [`std::io::Write`].
+    pub fn stderr_writer(mut self, writer: impl std::io::Write + Send + 'static) -> Self {
+        self.set_stderr_writer(writer);
+        self
+    }
+
+    /// Send everything the guest writes to `stderr` to a [`std::io::Write`].
+    pub fn set_stderr_writer(&mut self, writer: impl std::io::Write + Send + 'static) {
+        self.stderr = Some(Box::new(WriterFile::new(writer)));
+    }
+
+    /// Feed the guest's `stdin` from a [`std::io::Read`].
+    pub fn stdin_reader(mut self, reader: impl std::io::Read + Send + 'static) -> Self {
+        self.set_stdin_reader(reader);
+        self
+    }
+
+    /// Feed the guest's `stdin` from a [`std::io::Read`].
+    pub fn set_stdin_reader(&mut self, reader: impl std::io::Read + Send + 'static) {
+        self.stdin = Some(Box::new(ReaderFile::new(reader)));
+    }
+
+    /// Capture the guest's `stdout` in a buffer of at most `capacity` bytes.
+    ///
+    /// The output can be collected through the returned handle, either while
+    /// the guest is running or after it finished. Once the buffer is full the
+    /// guest blocks on writes until the handle is drained.
+    pub fn capture_stdout(&mut self, capacity: usize) -> CapturedOutput {
+        let (file, output) = CaptureFile::new(capacity);
+        self.stdout = Some(Box::new(file));
+        output
+    }
+
+    /// Capture the guest's `stderr` in a buffer of at most `capacity` bytes.
+    ///
+    /// See [`WasiEnvBuilder::capture_stdout`].
+    pub fn capture_stderr(&mut self, capacity: usize) -> CapturedOutput {
+        let (file, output) = CaptureFile::new(capacity);
+        self.stderr = Some(Box::new(file));
+        output
+    }
+
     /// Sets the FileSystem to be used with this WASI instance.
     ///
     /// This is usually used in case a custom `virtual_fs::FileSystem` is needed.
@@ -631,6 +835,22 @@ impl WasiEnvBuilder {
         self
     }
 
+    /// Attaches the instance to a file system that is shared with other
+    /// instances, e.g. one returned by [`WasiEnv::share_fs`].
+    ///
+    /// The instances see the same files and file descriptors, only the
+    /// current directory is their own. As the file system has already been
+    /// set up, this can't be combined with [`WasiEnvBuilder::fs`], mounts,
+    /// preopens, stdio overrides or [`WasiEnvBuilder::setup_fs`].
+    pub fn shared_fs(mut self, fs: SharedWasiFs) -> Self {
+        self.set_shared_fs(fs);
+        self
+    }
+
+    pub fn set_shared_fs(&mut self, fs: SharedWasiFs) {
+        self.shared_fs = Some(fs);
+    }
+
     /// Configure the WASI filesystem before running.
     // TODO: improve ergonomics on this function
     pub fn setup_fs(mut self, setup_fs_fn: SetupFsFn) -> Self {
@@ -663,6 +883,108 @@ impl WasiEnvBuilder {
         self.capabilites = capabilities;
     }
 
+    /// Limits the resources (file descriptors, bytes written and file sizes)
+    /// the instance may use through its file system.
+    ///
+    /// Exceeding a limit makes the offending syscall fail with `EMFILE`,
+    /// `EDQUOT` or `EFBIG` respectively.
+    pub fn fs_limits(mut self, limits: FsLimits) -> Self {
+        self.set_fs_limits(limits);
+        self
+    }
+
+    pub fn set_fs_limits(&mut self, limits: FsLimits) {
+        self.fs_limits = limits;
+    }
+
+    /// Sets the clock that is reported to the guest by `clock_time_get`.
+    pub fn clock(mut self, clock: ClockSource) -> Self {
+        self.set_clock(clock);
+        self
+    }
+
+    pub fn set_clock(&mut self, clock: ClockSource) {
+        self.clock = clock;
+    }
+
+    /// Makes `random_get` return a deterministic sequence of bytes derived
+    /// from `seed` instead of using the host's randomness.
+    ///
+    /// The bytes are generated with ChaCha20, so the sequence stays the
+    /// same across versions of Wasmer and platforms.
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.set_random_seed(seed);
+        self
+    }
+
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random_seed = Some(seed);
+    }
+
+    /// Makes the guest appear to run as `user` by setting `HOME`, `USER` and
+    /// `LOGNAME` and adding it to `/etc/passwd` and `/etc/group`.
+    pub fn user(mut self, user: UserIdentity) -> Self {
+        self.set_user(user);
+        self
+    }
+
+    pub fn set_user(&mut self, user: UserIdentity) {
+        self.user = Some(user);
+    }
+
+    /// Accounts the CPU time, syscalls and I/O of the instance (and of the
+    /// threads and processes it spawns) in `tracker`.
+    pub fn resource_usage(mut self, tracker: ResourceUsageTracker) -> Self {
+        self.set_resource_usage(tracker);
+        self
+    }
+
+    pub fn set_resource_usage(&mut self, tracker: ResourceUsageTracker) {
+        self.resource_usage = Some(tracker);
+    }
+
+    /// Logs or reports the syscalls made by the instance (and by the threads
+    /// and processes it spawns) to `tracer`.
+    pub fn syscall_tracer(mut self, tracer: SyscallTracer) -> Self {
+        self.set_syscall_tracer(tracer);
+        self
+    }
+
+    pub fn set_syscall_tracer(&mut self, tracer: SyscallTracer) {
+        self.syscall_tracer = Some(tracer);
+    }
+
+    /// Mounts a read-only `/proc` where the guest finds its arguments,
+    /// environment, file descriptors and memory usage under `/proc/self`,
+    /// like on Linux.
+    pub fn proc_fs(mut self, enabled: bool) -> Self {
+        self.set_proc_fs(enabled);
+        self
+    }
+
+    pub fn set_proc_fs(&mut self, enabled: bool) {
+        self.proc_fs = enabled;
+    }
+
+    /// Use asynchronous versions of `fd_read`, `fd_write`, `fd_pread` and
+    /// `fd_pwrite`.
+    ///
+    /// While a file, pipe or socket is not ready the guest is suspended and
+    /// control is handed back to the async runtime instead of blocking the
+    /// thread. The exported functions of the instance then have to be invoked
+    /// with [`Function::call_async`](wasmer::Function::call_async), calling
+    /// them synchronously traps as soon as one of these syscalls is used.
+    #[cfg(feature = "sys")]
+    pub fn async_io(mut self, enabled: bool) -> Self {
+        self.set_async_io(enabled);
+        self
+    }
+
+    #[cfg(feature = "sys")]
+    pub fn set_async_io(&mut self, enabled: bool) {
+        self.async_io = enabled;
+    }
+
     #[cfg(feature = "journal")]
     pub fn add_snapshot_trigger(&mut self, on: SnapshotTrigger) {
         self.snapshot_on.push(on);
@@ -718,6 +1040,99 @@ impl WasiEnvBuilder {
         self
     }
 
+    /// Sets up the file system of an instance that doesn't share it.
+    fn build_fs(&mut self) -> Result<(WasiInodes, WasiFs), WasiStateCreationError> {
+        // Determine the STDIN
+        let stdin: Box<dyn VirtualFile + Send + Sync + 'static> = self
+            .stdin
+            .take()
+            .unwrap_or_else(|| Box::new(ArcFile::new(Box::<super::Stdin>::default())));
+
+        let fs_backing = self
+            .fs
+            .take()
+            .unwrap_or_else(|| WasiFsRoot::Sandbox(Arc::new(TmpFileSystem::new())));
+
+        for (guest_path, fs) in self.mounts.drain(..) {
+            let mount_err = |err: FsError| {
+                WasiStateCreationError::WasiFsSetupError(format!(
+                    "Could not mount a filesystem at '{}': {err}",
+                    guest_path.display()
+                ))
+            };
+            if let Some(parent) = guest_path.parent() {
+                virtual_fs::create_dir_all(&fs_backing, parent).map_err(mount_err)?;
+            }
+            fs_backing
+                .mount(guest_path.display().to_string(), &guest_path, fs)
+                .map_err(mount_err)?;
+        }
+
+        if let Some(user) = &self.user {
+            if let WasiFsRoot::Sandbox(root_fs) = &fs_backing {
+                user.populate(root_fs);
+            } else {
+                tracing::debug!(
+                    "failed to add the user [{}] to /etc/passwd as the root file system is not sandboxed",
+                    user.name
+                );
+            }
+        }
+
+        if let Some(dir) = &self.current_dir {
+            match fs_backing.read_dir(dir) {
+                Ok(_) => {
+                    // All good
+                }
+                Err(FsError::EntryNotFound) => {
+                    fs_backing.create_dir(dir).map_err(|err| {
+                        WasiStateCreationError::WasiFsSetupError(format!(
+                            "Could not create specified current directory at '{}': {err}",
+                            dir.display()
+                        ))
+                    })?;
+                }
+                Err(err) => {
+                    return Err(WasiStateCreationError::WasiFsSetupError(format!(
+                        "Could check specified current directory at '{}': {err}",
+                        dir.display()
+                    )));
+                }
+            }
+        }
+
+        // self.preopens are checked in [`PreopenDirBuilder::build`]
+        let inodes = crate::state::WasiInodes::new();
+        let mut wasi_fs =
+            WasiFs::new_with_preopen(&inodes, &self.preopens, &self.vfs_preopens, fs_backing)
+                .map_err(WasiStateCreationError::WasiFsCreationError)?;
+
+        // set up the file system, overriding base files and calling the setup function
+        wasi_fs
+            .swap_file(__WASI_STDIN_FILENO, stdin)
+            .map_err(WasiStateCreationError::FileSystemError)?;
+
+        if let Some(stdout_override) = self.stdout.take() {
+            wasi_fs
+                .swap_file(__WASI_STDOUT_FILENO, stdout_override)
+                .map_err(WasiStateCreationError::FileSystemError)?;
+        }
+
+        if let Some(stderr_override) = self.stderr.take() {
+            wasi_fs
+                .swap_file(__WASI_STDERR_FILENO, stderr_override)
+                .map_err(WasiStateCreationError::FileSystemError)?;
+        }
+
+        if let Some(f) = &self.setup_fs_fn {
+            f(&inodes, &mut wasi_fs).map_err(WasiStateCreationError::WasiFsSetupError)?;
+        }
+
+        wasi_fs.quota = Arc::new(FsQuota::new(self.fs_limits));
+
+        Ok((inodes, wasi_fs))
+    }
+
     /// Consumes the [`WasiEnvBuilder`] and produces a [`WasiEnvInit`], which
     /// can be used to construct a new [`WasiEnv`].
     ///
@@ -735,6 +1150,20 @@ impl WasiEnvBuilder {
             }
         }
 
+        if let Some(user) = &self.user {
+            // Variables that were set explicitly take precedence
+            for (key, value) in [
+                ("HOME", &user.home),
+                ("USER", &user.name),
+                ("LOGNAME", &user.name),
+            ] {
+                if !self.envs.iter().any(|(k, _)| k == key) {
+                    self.envs
+                        .push((key.to_string(), value.clone().into_bytes()));
+                }
+            }
+        }
+
         enum InvalidCharacter {
             Nul,
             Equal,
@@ -785,68 +1214,29 @@ impl WasiEnvBuilder {
         //     .clone()
         //     .unwrap_or_else(|| Arc::new(PluggableRuntimeImplementation::default()));
 
-        // Determine the STDIN
-        let stdin: Box<dyn VirtualFile + Send + Sync + 'static> = self
-            .stdin
-            .take()
-            .unwrap_or_else(|| Box::new(ArcFile::new(Box::<super::Stdin>::default())));
-
-        let fs_backing = self
-            .fs
-            .take()
-            .unwrap_or_else(|| WasiFsRoot::Sandbox(Arc::new(TmpFileSystem::new())));
-
-        if let Some(dir) = &self.current_dir {
-            match fs_backing.read_dir(dir) {
-                Ok(_) => {
-                    // All good
-                }
-                Err(FsError::EntryNotFound) => {
-                    fs_backing.create_dir(dir).map_err(|err| {
-                        WasiStateCreationError::WasiFsSetupError(format!(
-                            "Could not create specified current directory at '{}': {err}",
-                            dir.display()
-                        ))
-                    })?;
+        let (inodes, wasi_fs) = match self.shared_fs.take() {
+            Some(shared) => {
+                if self.fs.is_some()
+                    || !self.mounts.is_empty()
+                    || !self.preopens.is_empty()
+                    || !self.vfs_preopens.is_empty()
+                    || self.stdin.is_some()
+                    || self.stdout.is_some()
+                    || self.stderr.is_some()
+                    || self.setup_fs_fn.is_some()
+                    || self.proc_fs
+                {
+                    return Err(WasiStateCreationError::WasiFsSetupError(
+                        "a shared file system can not be configured again".to_string(),
+                    ));
                 }
-                Err(err) => {
-                    return Err(WasiStateCreationError::WasiFsSetupError(format!(
-                        "Could check specified current directory at '{}': {err}",
-                        dir.display()
-                    )));
+                let (inodes, wasi_fs) = shared.attach();
+                if let (Some(user), WasiFsRoot::Sandbox(root_fs)) = (&self.user, &wasi_fs.root_fs) {
+                    user.populate(root_fs);
                 }
+                (inodes, wasi_fs)
             }
-        }
-
-        // self.preopens are checked in [`PreopenDirBuilder::build`]
-        let inodes = crate::state::WasiInodes::new();
-        let wasi_fs = {
-            // self.preopens are checked in [`PreopenDirBuilder::build`]
-            let mut wasi_fs =
-                WasiFs::new_with_preopen(&inodes, &self.preopens, &self.vfs_preopens, fs_backing)
-                    .map_err(WasiStateCreationError::WasiFsCreationError)?;
-
-            // set up the file system, overriding base files and calling the setup function
-            wasi_fs
-                .swap_file(__WASI_STDIN_FILENO, stdin)
-                .map_err(WasiStateCreationError::FileSystemError)?;
-
-            if let Some(stdout_override) = self.stdout.take() {
-                wasi_fs
-                    .swap_file(__WASI_STDOUT_FILENO, stdout_override)
-                    .map_err(WasiStateCreationError::FileSystemError)?;
-            }
-
-            if let Some(stderr_override) = self.stderr.take() {
-                wasi_fs
-                    .swap_file(__WASI_STDERR_FILENO, stderr_override)
-                    .map_err(WasiStateCreationError::FileSystemError)?;
-            }
-
-            if let Some(f) = &self.setup_fs_fn {
-                f(&inodes, &mut wasi_fs).map_err(WasiStateCreationError::WasiFsSetupError)?;
-            }
-            wasi_fs
+            None => self.build_fs()?,
         };
 
         if let Some(dir) = &self.current_dir {
@@ -863,15 +1253,49 @@ impl WasiEnvBuilder {
             wasi_fs.has_unioned.lock().unwrap().insert(id.clone());
         }
 
+        let envs = conv_env_vars(self.envs);
+        let proc = if self.proc_fs {
+            let info = Arc::new(ProcInfo::new(
+                self.args.clone(),
+                envs.clone(),
+                wasi_fs.fd_map.clone(),
+            ));
+            wasi_fs
+                .root_fs
+                .mount(
+                    "proc".to_string(),
+                    Path::new("/proc"),
+                    Box::new(ProcFileSystem::new(info.clone())),
+                )
+                .map_err(|err| {
+                    WasiStateCreationError::WasiFsSetupError(format!(
+                        "could not mount /proc: {err}"
+                    ))
+                })?;
+            Some(info)
+        } else {
+            None
+        };
+
+        let preopen = wasi_fs.init_vfs_preopens.clone();
         let state = WasiState {
             fs: wasi_fs,
             secret: rand::thread_rng().gen::<[u8; 32]>(),
             inodes,
             args: self.args.clone(),
-            preopen: self.vfs_preopens.clone(),
+            preopen,
             futexs: Default::default(),
             clock_offset: Default::default(),
-            envs: std::sync::Mutex::new(conv_env_vars(self.envs)),
+            clock: Arc::new(WasiClock::new(self.clock)),
+            rng: self
+                .random_seed
+                .map(|seed| Arc::new(std::sync::Mutex::new(ChaCha20Rng::seed_from_u64(seed)))),
+            envs: std::sync::Mutex::new(envs),
+            proc,
+            usage: self
+                .resource_usage
+                .unwrap_or_else(ResourceUsageTracker::untracked),
+            syscall_tracer: self.syscall_tracer,
         };
 
         let runtime = self.runtime.unwrap_or_else(|| {
@@ -892,6 +1316,31 @@ impl WasiEnvBuilder {
             }
         });
 
+        // The listeners need the networking of the runtime to be polled
+        for listener in self.listeners {
+            let socket = runtime
+                .networking()
+                .adopt_tcp_listener(listener)
+                .map_err(|err| WasiStateCreationError::PreopenedListenerError(err.to_string()))?;
+            let kind = Kind::Socket {
+                socket: InodeSocket::new(InodeSocketKind::TcpListener {
+                    socket,
+                    accept_timeout: None,
+                }),
+            };
+            let inode = state.fs.create_inode_with_default_stat(
+                &state.inodes,
+                kind,
+                false,
+                "socket".into(),
+            );
+            let rights = Rights::all_socket();
+            state
+                .fs
+                .create_fd(rights, rights, Fdflags::empty(), 0, inode)
+                .map_err(|err| WasiStateCreationError::PreopenedListenerError(err.to_string()))?;
+        }
+
         let uses = self.uses;
         let map_commands = self.map_commands;
 
@@ -926,6 +1375,7 @@ impl WasiEnvBuilder {
             #[cfg(feature = "journal")]
             snapshot_on: self.snapshot_on,
             additional_imports: self.additional_imports,
+            async_io: self.async_io,
         };
 
         Ok(init)
@@ -1024,8 +1474,25 @@ impl WasiEnvBuilder {
             );
         }
 
+        #[cfg(feature = "ctrlc")]
+        let signal_grace_period = self.signal_grace_period;
+
         let (instance, env) = self.instantiate_ext(module, module_hash, store)?;
 
+        #[cfg(feature = "ctrlc")]
+        {
+            let env = env.data(&store);
+            if let Some(grace_period) = signal_grace_period {
+                env.process
+                    .finished
+                    .handle()
+                    .forward_host_signals(grace_period);
+            }
+            if env.runtime.tty().is_some() {
+                env.process.finished.handle().forward_window_changes();
+            }
+        }
+
         // Bootstrap the process
         // Unsafe: The bootstrap must be executed in the same thread that runs the
         //         actual WASM code
@@ -1040,6 +1507,7 @@ impl WasiEnvBuilder {
         env.data(&store).thread.set_status_running();
 
         let result = crate::run_wasi_func_start(start, store);
+        let result = super::attach_signal(result, env.data(&store).thread.terminated_by());
         let (result, exit_code) = super::wasi_exit_code(result);
 
         let pid = env.data(&store).pid();
@@ -1068,12 +1536,17 @@ impl WasiEnvBuilder {
     ) -> Result<(), WasiRuntimeError> {
         #[cfg(feature = "ctrlc")]
         let attach_ctrl_c = self.attach_ctrl_c;
+        #[cfg(feature = "ctrlc")]
+        let signal_grace_period = self.signal_grace_period;
 
         let (_, env) = self.instantiate_ext(module, module_hash, &mut store)?;
 
         // Install the ctrl-c handler
         #[cfg(feature = "ctrlc")]
-        if attach_ctrl_c {
+        if let Some(grace_period) = signal_grace_period {
+            let process = &env.data(&store).process;
+            process.finished.handle().forward_host_signals(grace_period);
+        } else if attach_ctrl_c {
             tokio::spawn({
                 let process = env.data(&store).process.clone();
                 async move {
@@ -1083,6 +1556,11 @@ impl WasiEnvBuilder {
                 }
             });
         }
+        #[cfg(feature = "ctrlc")]
+        if env.data(&store).runtime.tty().is_some() {
+            let process = &env.data(&store).process;
+            process.finished.handle().forward_window_changes();
+        }
 
         env.run_async(store)?;
         Ok(())
@@ -1110,6 +1588,8 @@ pub struct PreopenDirBuilder {
     read: bool,
     write: bool,
     create: bool,
+    rights: Option<Rights>,
+    case_insensitive: bool,
 }
 
 /// The built version of `PreopenDirBuilder`
@@ -1120,6 +1600,30 @@ pub(crate) struct PreopenedDir {
     pub(crate) read: bool,
     pub(crate) write: bool,
     pub(crate) create: bool,
+    pub(crate) rights: Option<Rights>,
+    pub(crate) case_insensitive: bool,
+}
+
+impl PreopenedDir {
+    /// The rights of the fd of the directory, the ones it was given or the
+    /// ones derived from its read, write and create flags.
+    pub(crate) fn effective_rights(&self) -> Rights {
+        if let Some(rights) = self.rights {
+            return rights;
+        }
+        // TODO: review tell' and fd_readwrite
+        let mut rights = Rights::FD_ADVISE | Rights::FD_TELL | Rights::FD_SEEK;
+        if self.read {
+            rights |= Rights::dir_read();
+        }
+        if self.write {
+            rights |= Rights::dir_write();
+        }
+        if self.create {
+            rights |= Rights::dir_create();
+        }
+        rights
+    }
 }
 
 impl PreopenDirBuilder {
@@ -1175,9 +1679,49 @@ impl PreopenDirBuilder {
         self
     }
 
+    /// Grant exactly `rights` to the preopened directory (and the files
+    /// opened through it) instead of the rights implied by [`read`],
+    /// [`write`] and [`create`].
+    ///
+    /// For example, a directory that the guest may only list would get
+    /// [`Rights::FD_READDIR`] and [`Rights::PATH_FILESTAT_GET`], while an
+    /// append-only log directory would leave out [`Rights::FD_SEEK`], which
+    /// forces every file opened for writing into append mode.
+    ///
+    /// [`read`]: PreopenDirBuilder::read
+    /// [`write`]: PreopenDirBuilder::write
+    /// [`create`]: PreopenDirBuilder::create
+    pub fn rights(&mut self, rights: Rights) -> &mut Self {
+        self.read = rights.intersects(Rights::FD_READ | Rights::FD_READDIR);
+        self.write = rights.contains(Rights::FD_WRITE);
+        self.create = rights.intersects(Rights::PATH_CREATE_FILE | Rights::PATH_CREATE_DIRECTORY);
+        self.rights = Some(rights);
+
+        self
+    }
+
+    /// Resolve paths inside of the preopened directory (and its alias)
+    /// case-insensitively, the way Windows does.
+    ///
+    /// This only applies when no entry has exactly the requested name, so
+    /// it helps guests that were written against a case-insensitive file
+    /// system without changing how files that are spelled correctly are
+    /// found.
+    pub fn case_insensitive(&mut self, toggle: bool) -> &mut Self {
+        self.case_insensitive = toggle;
+
+        self
+    }
+
     pub(crate) fn build(&self) -> Result<PreopenedDir, WasiStateCreationError> {
+        if self.rights.is_some_and(|rights| rights.is_empty()) {
+            return Err(WasiStateCreationError::PreopenedDirectoryError(
+                "Preopened directories must be granted at least one right".to_string(),
+            ));
+        }
+
         // ensure at least one is set
-        if !(self.read || self.write || self.create) {
+        if self.rights.is_none() && !(self.read || self.write || self.create) {
             return Err(WasiStateCreationError::PreopenedDirectoryError("Preopened directories must have at least one of read, write, create permissions set".to_string()));
         }
 
@@ -1204,6 +1748,8 @@ impl PreopenDirBuilder {
             read: self.read,
             write: self.write,
             create: self.create,
+            rights: self.rights,
+            case_insensitive: self.case_insensitive,
         })
     }
 }
@@ -0,0 +1,99 @@
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use wasmer::WasmRef;
+use wasmer_wasix_types::wasi::{Errno, Snapshot0Clockid, Timestamp};
+
+use crate::syscalls::{platform_clock_res_get, platform_clock_time_get};
+
+/// The amount of time the [`ClockSource::Virtual`] clock advances by every
+/// time it is read (one millisecond).
+pub const VIRTUAL_CLOCK_TICK: Timestamp = 1_000_000;
+
+/// Where the guest gets the current time from.
+///
+/// Anything other than [`ClockSource::System`] makes the time observed by
+/// the guest independent of the host, which is useful for reproducible runs.
+/// Note that this only affects `clock_time_get` and `clock_res_get`, sleeping
+/// and timeouts still happen in real time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClockSource {
+    /// Use the clocks of the host.
+    #[default]
+    System,
+    /// Every clock always reports the same time (in nanoseconds since the
+    /// Unix epoch).
+    Fixed(Timestamp),
+    /// Every clock starts at the Unix epoch and advances by
+    /// [`VIRTUAL_CLOCK_TICK`] every time it is read.
+    Virtual,
+}
+
+impl FromStr for ClockSource {
+    type Err = String;
+
+    /// Parses `system`, `virtual` or `fixed:<seconds since the Unix epoch>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "system" => return Ok(ClockSource::System),
+            "virtual" => return Ok(ClockSource::Virtual),
+            _ => {}
+        }
+
+        let secs = s.strip_prefix("fixed:").ok_or_else(|| {
+            format!(
+                "unknown clock \"{s}\", expected \"system\", \"virtual\" or \"fixed:<timestamp>\""
+            )
+        })?;
+        secs.parse::<u64>()
+            .ok()
+            .and_then(|secs| secs.checked_mul(1_000_000_000))
+            .map(ClockSource::Fixed)
+            .ok_or_else(|| {
+                format!("invalid timestamp \"{secs}\", expected seconds since the Unix epoch")
+            })
+    }
+}
+
+/// The clock used by `clock_time_get`.
+#[derive(Debug, Default)]
+pub(crate) struct WasiClock {
+    source: ClockSource,
+    ticks: AtomicU64,
+}
+
+impl WasiClock {
+    pub fn new(source: ClockSource) -> Self {
+        Self {
+            source,
+            ticks: AtomicU64::new(0),
+        }
+    }
+
+    /// The resolution of `clock_id` as observed by the guest.
+    pub fn res_get(
+        &self,
+        clock_id: Snapshot0Clockid,
+        resolution: WasmRef<Timestamp>,
+    ) -> Result<i64, Errno> {
+        match self.source {
+            ClockSource::System | ClockSource::Fixed(_) => {
+                platform_clock_res_get(clock_id, resolution)
+            }
+            ClockSource::Virtual => Ok(VIRTUAL_CLOCK_TICK as i64),
+        }
+    }
+
+    pub fn time_get(&self, clock_id: Snapshot0Clockid, precision: Timestamp) -> Result<i64, Errno> {
+        match self.source {
+            ClockSource::System => platform_clock_time_get(clock_id, precision),
+            ClockSource::Fixed(time) => Ok(time as i64),
+            ClockSource::Virtual => {
+                let ticks = self.ticks.fetch_add(1, Ordering::AcqRel) + 1;
+                Ok(ticks.wrapping_mul(VIRTUAL_CLOCK_TICK) as i64)
+            }
+        }
+    }
+}
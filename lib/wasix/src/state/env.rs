@@ -27,7 +27,7 @@ use crate::journal::{DynJournal, JournalEffector, SnapshotTrigger};
 use crate::{
     bin_factory::{BinFactory, BinaryPackage},
     capabilities::Capabilities,
-    fs::{WasiFsRoot, WasiInodes},
+    fs::{SharedWasiFs, WasiFsRoot, WasiInodes},
     import_object_for_all_wasi_versions,
     os::task::{
         control_plane::ControlPlaneError,
@@ -42,7 +42,7 @@ use crate::{
 use wasmer_types::ModuleHash;
 
 pub(crate) use super::handles::*;
-use super::WasiState;
+use super::{ResourceUsage, WasiState};
 
 /// Various [`TypedFunction`] and [`Global`] handles for an active WASI(X) instance.
 ///
@@ -272,6 +272,9 @@ pub struct WasiEnvInit {
     /// normal WASIX syscalls.
     pub additional_imports: Imports,
 
+    /// Use the asynchronous versions of the I/O syscalls.
+    pub async_io: bool,
+
     /// Indicates triggers that will cause a snapshot to be taken
     #[cfg(feature = "journal")]
     pub snapshot_on: Vec<SnapshotTrigger>,
@@ -282,9 +285,10 @@ impl WasiEnvInit {
         let inodes = WasiInodes::new();
 
         // TODO: preserve preopens?
-        let fs =
+        let mut fs =
             crate::fs::WasiFs::new_with_preopen(&inodes, &[], &[], self.state.fs.root_fs.clone())
                 .unwrap();
+        fs.quota = self.state.fs.quota.clone();
 
         Self {
             state: WasiState {
@@ -295,8 +299,13 @@ impl WasiEnvInit {
                 clock_offset: std::sync::Mutex::new(
                     self.state.clock_offset.lock().unwrap().clone(),
                 ),
+                clock: self.state.clock.clone(),
+                rng: self.state.rng.clone(),
                 args: self.state.args.clone(),
                 envs: std::sync::Mutex::new(self.state.envs.lock().unwrap().deref().clone()),
+                proc: self.state.proc.clone(),
+                usage: self.state.usage.clone(),
+                syscall_tracer: self.state.syscall_tracer.clone(),
                 preopen: self.state.preopen.clone(),
             },
             runtime: self.runtime.clone(),
@@ -314,6 +323,7 @@ impl WasiEnvInit {
             #[cfg(feature = "journal")]
             snapshot_on: self.snapshot_on.clone(),
             additional_imports: self.additional_imports.clone(),
+            async_io: self.async_io,
         }
     }
 }
@@ -574,6 +584,10 @@ impl WasiEnv {
         };
         env.owned_handles.push(thread);
 
+        if let Some(proc) = &env.state.proc {
+            proc.set_pid(env.process.pid().raw());
+        }
+
         // TODO: should not be here - should be callers responsibility!
         for pkg in &init.webc_dependencies {
             env.use_package(pkg)?;
@@ -603,6 +617,8 @@ impl WasiEnv {
         }
 
         let additional_imports = init.additional_imports.clone();
+        #[cfg(feature = "sys")]
+        let async_io = init.async_io;
 
         let env = Self::from_init(init, module_hash)?;
         let pid = env.process.pid();
@@ -610,6 +626,8 @@ impl WasiEnv {
         let mut store = store.as_store_mut();
 
         let tasks = env.runtime.task_manager().clone();
+        #[cfg(feature = "sys")]
+        env.state.usage.install(&mut store);
         let mut func_env = WasiFunctionEnv::new(&mut store, env);
 
         // Determine if shared memory needs to be created and imported
@@ -629,6 +647,14 @@ impl WasiEnv {
         // Let's instantiate the module with the imports.
         let (mut import_object, instance_init_callback) =
             import_object_for_all_wasi_versions(&module, &mut store, &func_env.env);
+        #[cfg(feature = "sys")]
+        if async_io {
+            crate::syscalls::async_io::define_async_io_imports(
+                &mut import_object,
+                &mut store,
+                &func_env.env,
+            );
+        }
 
         for ((namespace, name), value) in &additional_imports {
             // Note: We don't want to let downstream users override WASIX
@@ -646,7 +672,14 @@ impl WasiEnv {
         };
 
         // Construct the instance.
-        let instance = match Instance::new(&mut store, &module, &import_object) {
+        let started = std::time::Instant::now();
+        let instance = Instance::new(&mut store, &module, &import_object);
+        func_env
+            .data(&store)
+            .state
+            .usage
+            .add_instantiation_time(started.elapsed());
+        let instance = match instance {
             Ok(a) => a,
             Err(err) => {
                 tracing::error!(
@@ -678,6 +711,12 @@ impl WasiEnv {
                 .blocking_on_exit(Some(Errno::Noexec.into()));
             return Err(err.into());
         }
+        {
+            let env = func_env.data(&store);
+            if let Some(view) = env.try_memory_view(&store) {
+                env.state.usage.record_memory_pages(view.size().0);
+            }
+        }
 
         // If this module exports an _initialize function, run that first.
         if call_initialize {
@@ -708,6 +747,18 @@ impl WasiEnv {
         &self.state.fs.root_fs
     }
 
+    /// Returns a handle that lets other instances use the same file system
+    /// and file descriptors as this one (see [`WasiEnvBuilder::shared_fs`]).
+    pub fn share_fs(&self) -> SharedWasiFs {
+        SharedWasiFs::new(self.state.inodes.clone(), self.state.fs.share())
+    }
+
+    /// Returns the resources consumed by this instance so far (see
+    /// [`ResourceUsageTracker`](crate::ResourceUsageTracker)).
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.state.usage.usage()
+    }
+
     /// Overrides the runtime implementation for this environment
     pub fn set_runtime<R>(&mut self, runtime: R)
     where
@@ -733,11 +784,7 @@ impl WasiEnv {
             let signals = env.thread.pop_signals();
             if !signals.is_empty() {
                 for sig in signals {
-                    if sig == Signal::Sigint
-                        || sig == Signal::Sigquit
-                        || sig == Signal::Sigkill
-                        || sig == Signal::Sigabrt
-                    {
+                    if crate::os::task::signal::terminates_by_default(sig) {
                         let exit_code = env.thread.set_or_get_exit_code_for_signal(sig);
                         return Err(WasiError::Exit(exit_code));
                     } else {
@@ -48,10 +48,14 @@ impl WasiFunctionEnv {
         // Create a new store and put the memory object in it
         // (but only if it has imported memory)
         let mut store = env.runtime.new_store();
+
         let memory = env
             .tasks()
             .build_memory(&mut store.as_store_mut(), spawn_type)?;
 
+        #[cfg(feature = "sys")]
+        env.state.usage.install(&mut store);
+
         // Build the context object and import the memory
         let mut ctx = WasiFunctionEnv::new(&mut store, env);
         let (mut import_object, init) =
@@ -60,7 +64,13 @@ impl WasiFunctionEnv {
             import_object.define("env", "memory", memory);
         }
 
-        let instance = Instance::new(&mut store, &module, &import_object).map_err(|err| {
+        let started = std::time::Instant::now();
+        let instance = Instance::new(&mut store, &module, &import_object);
+        ctx.data(&store)
+            .state
+            .usage
+            .add_instantiation_time(started.elapsed());
+        let instance = instance.map_err(|err| {
             tracing::warn!("failed to create instance - {}", err);
             WasiThreadError::InstanceCreateFailed(Box::new(err))
         })?;
@@ -291,10 +301,28 @@ impl WasiFunctionEnv {
             self.data(store).tid()
         );
 
+        #[cfg(feature = "sys")]
+        self.record_memory_usage(store);
+
         // Cleans up all the open files (if this is the main thread)
         self.data(store).blocking_on_exit(exit_code);
     }
 
+    /// Records the memory used by the instance of the main thread in the
+    /// resource usage tracker, before it exits.
+    #[cfg(feature = "sys")]
+    pub(crate) fn record_memory_usage(&self, store: &impl AsStoreRef) {
+        let env = self.data(store);
+        if !env.thread.is_main() {
+            return;
+        }
+        if let Some(instance) = env.try_clone_instance() {
+            env.state
+                .usage
+                .record_memory_usage(|| instance.memory_usage(store));
+        }
+    }
+
     /// Bootstraps this main thread and context with any journals that
     /// may be present
     ///
@@ -0,0 +1,135 @@
+use std::{path::Path, str::FromStr};
+
+use virtual_fs::{FileSystem, TmpFileSystem};
+
+/// The user the guest is told it is running as.
+///
+/// WASI has no notion of users, so this is emulated the way most programs
+/// look it up: the `HOME`, `USER` and `LOGNAME` environment variables are set
+/// (unless they were set explicitly) and `/etc/passwd` and `/etc/group`
+/// entries are added to the sandboxed root file system.
+///
+/// Note that `filestat` has no ownership fields, so files never appear to be
+/// owned by anyone regardless of this setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserIdentity {
+    pub name: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub home: String,
+}
+
+impl UserIdentity {
+    pub fn new(name: impl Into<String>, uid: u32, gid: u32) -> Self {
+        let name = name.into();
+        let home = if uid == 0 {
+            "/root".to_string()
+        } else {
+            format!("/home/{name}")
+        };
+
+        UserIdentity {
+            name,
+            uid,
+            gid,
+            home,
+        }
+    }
+
+    /// Overrides the home directory, which defaults to `/home/<name>` (or
+    /// `/root` for uid 0).
+    pub fn with_home(mut self, home: impl Into<String>) -> Self {
+        self.home = home.into();
+        self
+    }
+
+    /// The contents of `/etc/passwd`.
+    pub fn passwd(&self) -> String {
+        let UserIdentity {
+            name,
+            uid,
+            gid,
+            home,
+        } = self;
+        format!("{name}:x:{uid}:{gid}::{home}:/bin/sh\n")
+    }
+
+    /// The contents of `/etc/group`.
+    pub fn group(&self) -> String {
+        let UserIdentity { name, gid, .. } = self;
+        format!("{name}:x:{gid}:\n")
+    }
+
+    /// Adds `/etc/passwd` and `/etc/group` to a root file system. Files that
+    /// already exist (e.g. because they are provided by a mounted directory)
+    /// are left alone.
+    ///
+    /// The home directory isn't created because it may well be inside a
+    /// directory mapped from the host.
+    pub(crate) fn populate(&self, root_fs: &TmpFileSystem) {
+        let _ = root_fs.create_dir(Path::new("/etc"));
+
+        for (path, contents) in [("/etc/passwd", self.passwd()), ("/etc/group", self.group())] {
+            let path = Path::new(path);
+            if root_fs.metadata(path).is_ok() {
+                continue;
+            }
+            if let Err(err) = root_fs
+                .new_open_options_ext()
+                .insert_ro_file(path, contents.into_bytes().into())
+            {
+                tracing::debug!("failed to add {} - {}", path.display(), err);
+            }
+        }
+    }
+}
+
+impl Default for UserIdentity {
+    fn default() -> Self {
+        UserIdentity::new("wasmer", 1000, 1000)
+    }
+}
+
+impl FromStr for UserIdentity {
+    type Err = String;
+
+    /// Parses `<name>[:<uid>[:<gid>]]`, the gid defaults to the uid and the
+    /// uid to 1000.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let name = parts.next().unwrap_or_default();
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '/') {
+            return Err(format!("invalid user name \"{name}\""));
+        }
+
+        let parse_id = |id: &str| {
+            id.parse::<u32>()
+                .map_err(|_| format!("invalid user or group id \"{id}\""))
+        };
+        let uid = parts.next().map(parse_id).transpose()?.unwrap_or(1000);
+        let gid = parts.next().map(parse_id).transpose()?.unwrap_or(uid);
+
+        Ok(UserIdentity::new(name, uid, gid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_user_identity() {
+        assert_eq!(
+            "alice".parse::<UserIdentity>().unwrap(),
+            UserIdentity::new("alice", 1000, 1000)
+        );
+        assert_eq!(
+            "alice:501".parse::<UserIdentity>().unwrap(),
+            UserIdentity::new("alice", 501, 501)
+        );
+        assert_eq!("root:0:0".parse::<UserIdentity>().unwrap().home, "/root");
+        assert!("".parse::<UserIdentity>().is_err());
+        assert!("alice:bob".parse::<UserIdentity>().is_err());
+        assert!("alice:1:2:3".parse::<UserIdentity>().is_err());
+    }
+}
@@ -16,35 +16,45 @@
 #![allow(clippy::cognitive_complexity, clippy::too_many_arguments)]
 
 mod builder;
+mod clock;
 mod env;
 mod func_env;
 mod handles;
+mod identity;
 mod run;
+mod trace;
 mod types;
+mod usage;
 
 use std::{
     collections::{BTreeMap, HashMap},
     path::Path,
-    sync::Mutex,
+    sync::{Arc, Mutex},
     task::Waker,
     time::Duration,
 };
 
+use rand_chacha::ChaCha20Rng;
 use run::*;
 #[cfg(feature = "enable-serde")]
 use serde::{Deserialize, Serialize};
 use virtual_fs::{FileOpener, FileSystem, FsError, OpenOptions, VirtualFile};
-use wasmer_wasix_types::wasi::{Errno, Fd as WasiFd, Rights, Snapshot0Clockid};
+use wasmer_wasix_types::wasi::{Errno, Fd as WasiFd, Rights, Snapshot0Clockid, Timestamp};
 
+pub(crate) use self::clock::WasiClock;
 pub use self::{
     builder::*,
+    clock::{ClockSource, VIRTUAL_CLOCK_TICK},
     env::{WasiEnv, WasiEnvInit, WasiInstanceHandles},
     func_env::WasiFunctionEnv,
+    identity::UserIdentity,
+    trace::{SyscallEvent, SyscallTracer},
     types::*,
+    usage::{ResourceUsage, ResourceUsageTracker},
 };
 pub use crate::fs::{InodeGuard, InodeWeakGuard};
 use crate::{
-    fs::{fs_error_into_wasi_err, WasiFs, WasiFsRoot, WasiInodes, WasiStateFileGuard},
+    fs::{fs_error_into_wasi_err, ProcInfo, WasiFs, WasiFsRoot, WasiInodes, WasiStateFileGuard},
     syscalls::types::*,
     utils::WasiParkingLot,
 };
@@ -133,8 +143,21 @@ pub(crate) struct WasiState {
     pub inodes: WasiInodes,
     pub futexs: Mutex<WasiFutexState>,
     pub clock_offset: Mutex<HashMap<Snapshot0Clockid, i64>>,
+    #[cfg_attr(feature = "enable-serde", serde(skip))]
+    pub clock: Arc<WasiClock>,
+    /// Source of `random_get` when a random seed was configured, otherwise
+    /// the randomness comes from the host.
+    #[cfg_attr(feature = "enable-serde", serde(skip))]
+    pub rng: Option<Arc<Mutex<ChaCha20Rng>>>,
     pub args: Vec<String>,
     pub envs: Mutex<Vec<Vec<u8>>>,
+    /// What `/proc/self` shows, when it is mounted.
+    #[cfg_attr(feature = "enable-serde", serde(skip))]
+    pub(crate) proc: Option<Arc<ProcInfo>>,
+    #[cfg_attr(feature = "enable-serde", serde(skip))]
+    pub usage: ResourceUsageTracker,
+    #[cfg_attr(feature = "enable-serde", serde(skip))]
+    pub syscall_tracer: Option<SyscallTracer>,
 
     // TODO: should not be here, since this requires active work to resolve.
     // State should only hold active runtime state that can be reproducibly re-created.
@@ -247,6 +270,20 @@ impl WasiState {
         Ok(ret)
     }
 
+    /// Reads a clock as it is seen by the guest, taking into account the
+    /// configured clock source and any adjustments made with `clock_time_set`
+    pub(crate) fn clock_time_get(
+        &self,
+        clock_id: Snapshot0Clockid,
+        precision: Timestamp,
+    ) -> Result<i64, Errno> {
+        let mut time = self.clock.time_get(clock_id, precision)?;
+        if let Some(offset) = self.clock_offset.lock().unwrap().get(&clock_id) {
+            time += *offset;
+        }
+        Ok(time)
+    }
+
     /// Forking the WasiState is used when either fork or vfork is called
     pub fn fork(&self) -> Self {
         WasiState {
@@ -255,8 +292,13 @@ impl WasiState {
             inodes: self.inodes.clone(),
             futexs: Default::default(),
             clock_offset: Mutex::new(self.clock_offset.lock().unwrap().clone()),
+            clock: self.clock.clone(),
+            rng: self.rng.clone(),
             args: self.args.clone(),
             envs: Mutex::new(self.envs.lock().unwrap().clone()),
+            proc: self.proc.clone(),
+            usage: self.usage.clone(),
+            syscall_tracer: self.syscall_tracer.clone(),
             preopen: self.preopen.clone(),
         }
     }
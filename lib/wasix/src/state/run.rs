@@ -1,6 +1,6 @@
 use virtual_mio::InlineWaker;
 use wasmer::{RuntimeError, Store};
-use wasmer_wasix_types::wasi::ExitCode;
+use wasmer_wasix_types::wasi::{ExitCode, Signal};
 
 use crate::{os::task::thread::RewindResultType, RewindStateOption, WasiError, WasiRuntimeError};
 
@@ -181,6 +181,7 @@ fn handle_result(
         Err(Err(e)) => Err(e.into()),
     };
 
+    let result = attach_signal(result, env.data(&store).thread.terminated_by());
     let (result, exit_code) = wasi_exit_code(result);
     env.on_exit(&mut store, Some(exit_code));
     sender.send(result.map(|_| store)).ok();
@@ -196,6 +197,7 @@ pub(super) fn wasi_exit_code(
 ) -> (Result<(), WasiRuntimeError>, ExitCode) {
     let exit_code = match &result {
         Ok(_) => Errno::Success.into(),
+        Err(WasiRuntimeError::Signal { exit_code, .. }) => *exit_code,
         Err(err) => match err.as_exit_code() {
             Some(code) if code.is_success() => {
                 // This is actually not an error, so we need to fix up the
@@ -210,3 +212,24 @@ pub(super) fn wasi_exit_code(
 
     (result, exit_code)
 }
+
+/// Reports an exit caused by `signal` (see
+/// [`WasiThread::terminated_by`](crate::WasiThread::terminated_by)) as a
+/// [`WasiRuntimeError::Signal`].
+#[allow(clippy::result_large_err)]
+pub(super) fn attach_signal(
+    result: Result<(), WasiRuntimeError>,
+    signal: Option<Signal>,
+) -> Result<(), WasiRuntimeError> {
+    let Some(signal) = signal else {
+        return result;
+    };
+    let exit_code = match &result {
+        Ok(()) => Errno::Success.into(),
+        Err(err) => match err.as_exit_code() {
+            Some(code) => code,
+            None => return result,
+        },
+    };
+    Err(WasiRuntimeError::Signal { signal, exit_code })
+}
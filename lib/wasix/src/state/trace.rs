@@ -0,0 +1,166 @@
+use std::{fmt, sync::Arc};
+
+use wasmer_wasix_types::wasi::Fd as WasiFd;
+
+use crate::{WasiProcessId, WasiThreadId};
+
+/// A syscall made by a WASI instance, reported by a [`SyscallTracer`] once
+/// it returned.
+#[derive(Debug, Clone)]
+pub struct SyscallEvent {
+    /// The process that made the syscall.
+    pub pid: WasiProcessId,
+    /// The thread that made the syscall.
+    pub tid: WasiThreadId,
+    /// The name of the syscall, as imported by the module.
+    pub name: &'static str,
+    /// The file descriptor the syscall operates on, for the `fd_*`,
+    /// `path_*`, `sock_*` and `epoll_*` syscalls that take one as their
+    /// first argument.
+    pub fd: Option<WasiFd>,
+    /// The arguments of the syscall, decoded into their WASI types (flags,
+    /// enums, pointers, ...) and formatted.
+    pub args: Vec<String>,
+    /// The result of the syscall: its errno, or the reason it stopped the
+    /// thread, like an exit.
+    pub result: String,
+}
+
+impl fmt::Display for SyscallEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}:{}] {}({}) = {}",
+            self.pid,
+            self.tid,
+            self.name,
+            self.args.join(", "),
+            self.result
+        )
+    }
+}
+
+/// Logs or passes to a callback the syscalls made by a WASI instance, and by
+/// the threads and processes it spawns, with their decoded arguments.
+///
+/// The syscalls can be filtered by name, with patterns where `*` matches any
+/// sequence of characters (like `fd_*` or `sock_recv*`), and by the file
+/// descriptor they operate on. Only the syscalls whose name matches are
+/// instrumented when the imports of the instance are generated, the others
+/// run as usual.
+///
+/// ```
+/// # use wasmer_wasix::SyscallTracer;
+/// let tracer = SyscallTracer::new(|event| eprintln!("{event}"))
+///     .with_names(["fd_read", "fd_write"])
+///     .with_fd(1);
+/// assert!(tracer.traces("fd_write"));
+/// assert!(!tracer.traces("path_open"));
+/// ```
+#[derive(Clone)]
+pub struct SyscallTracer {
+    names: Vec<String>,
+    fds: Vec<WasiFd>,
+    callback: Arc<dyn Fn(&SyscallEvent) + Send + Sync>,
+}
+
+impl SyscallTracer {
+    /// Traces all the syscalls, passing them to `callback`.
+    pub fn new(callback: impl Fn(&SyscallEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            names: Vec::new(),
+            fds: Vec::new(),
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// Traces all the syscalls, logging them as `tracing` events at the
+    /// `INFO` level with the `wasmer_wasix::syscall_trace` target.
+    pub fn logging() -> Self {
+        Self::new(|event| {
+            tracing::info!(
+                target: "wasmer_wasix::syscall_trace",
+                pid = %event.pid,
+                tid = %event.tid,
+                "{event}"
+            )
+        })
+    }
+
+    /// Only traces the syscalls whose name matches one of `patterns`.
+    pub fn with_names<S: Into<String>>(mut self, patterns: impl IntoIterator<Item = S>) -> Self {
+        self.names.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only traces the syscalls that operate on `fd`, this can be given
+    /// several times.
+    pub fn with_fd(mut self, fd: WasiFd) -> Self {
+        self.fds.push(fd);
+        self
+    }
+
+    /// Whether the syscall named `name` is traced.
+    pub fn traces(&self, name: &str) -> bool {
+        self.names.is_empty() || self.names.iter().any(|pattern| matches(pattern, name))
+    }
+
+    /// Whether a syscall that operates on `fd` (if any) is traced.
+    pub(crate) fn traces_fd(&self, fd: Option<WasiFd>) -> bool {
+        self.fds.is_empty() || fd.map_or(false, |fd| self.fds.contains(&fd))
+    }
+
+    pub(crate) fn record(&self, event: &SyscallEvent) {
+        (self.callback)(event)
+    }
+}
+
+impl fmt::Debug for SyscallTracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyscallTracer")
+            .field("names", &self.names)
+            .field("fds", &self.fds)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Matches `name` against `pattern`, where `*` matches any sequence of
+/// characters.
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // The pattern ends with `*` or with this part.
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("fd_write", "fd_write"));
+        assert!(!matches("fd_write", "fd_write2"));
+        assert!(matches("fd_*", "fd_write"));
+        assert!(!matches("fd_*", "path_open"));
+        assert!(matches("*", "path_open"));
+        assert!(matches("sock_*_from", "sock_recv_from"));
+        assert!(!matches("sock_*_from", "sock_recv"));
+        assert!(matches("*_get", "args_sizes_get"));
+        assert!(matches("a*b*c", "abc"));
+        assert!(!matches("a*bc*c", "abc"));
+    }
+}
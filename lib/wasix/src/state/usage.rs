@@ -0,0 +1,262 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// The resources consumed by a WASI instance, see [`ResourceUsageTracker`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// CPU time (user and system) spent by the threads of the instance while
+    /// they were running WebAssembly code or the syscalls it made.
+    pub cpu_time: Duration,
+    /// Number of syscalls made by the instance.
+    pub syscalls: u64,
+    /// Number of bytes read from files, pipes, sockets and stdin.
+    pub bytes_read: u64,
+    /// Number of bytes written to files, pipes, sockets and stdout/stderr.
+    pub bytes_written: u64,
+    /// Wall-clock time spent by the threads of the instance running
+    /// WebAssembly code or the syscalls it made.
+    pub execution_time: Duration,
+    /// Wall-clock time spent instantiating the module, for the instance and
+    /// the threads it spawned.
+    pub instantiation_time: Duration,
+    /// Number of instructions executed, counted by the fuel consumed when
+    /// the module is compiled with the metering middleware and the store
+    /// has fuel, `None` otherwise.
+    pub instructions: Option<u64>,
+    /// Number of times a memory of the instance has grown.
+    pub memory_grows: u64,
+    /// The largest size reached by a memory of the instance.
+    pub peak_memory_pages: u32,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    enabled: AtomicBool,
+    cpu_time_ns: AtomicU64,
+    syscalls: AtomicU64,
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+    execution_time_ns: AtomicU64,
+    instantiation_time_ns: AtomicU64,
+    metered: AtomicBool,
+    instructions: AtomicU64,
+    memory_grows: AtomicU64,
+    peak_memory_pages: AtomicU32,
+    #[cfg(feature = "sys")]
+    memory_usage: std::sync::Mutex<Option<wasmer::MemoryUsage>>,
+}
+
+/// Accumulates the resources consumed by a WASI instance, shared by all its
+/// threads and the processes it forks.
+///
+/// Bytes read and written are always counted. The times, the number of
+/// syscalls, the instructions and the memory growths are only tracked when a
+/// tracker is given to
+/// [`WasiEnvBuilder::resource_usage()`](crate::WasiEnvBuilder::resource_usage),
+/// as they rely on the call hook and on a memory grow hook of the stores
+/// that run the instance. The call hook set by the embedder keeps being
+/// called, after the one of the tracker.
+#[derive(Debug, Clone)]
+pub struct ResourceUsageTracker {
+    counters: Arc<Counters>,
+}
+
+impl Default for ResourceUsageTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceUsageTracker {
+    pub fn new() -> Self {
+        let counters = Counters {
+            enabled: AtomicBool::new(true),
+            ..Default::default()
+        };
+        Self {
+            counters: Arc::new(counters),
+        }
+    }
+
+    /// A tracker that only counts the bytes read and written, used when the
+    /// embedder didn't ask for resource usage tracking.
+    pub(crate) fn untracked() -> Self {
+        Self {
+            counters: Default::default(),
+        }
+    }
+
+    /// Returns the resources consumed so far.
+    pub fn usage(&self) -> ResourceUsage {
+        let c = &self.counters;
+        ResourceUsage {
+            cpu_time: Duration::from_nanos(c.cpu_time_ns.load(Ordering::Relaxed)),
+            syscalls: c.syscalls.load(Ordering::Relaxed),
+            bytes_read: c.bytes_read.load(Ordering::Relaxed),
+            bytes_written: c.bytes_written.load(Ordering::Relaxed),
+            execution_time: Duration::from_nanos(c.execution_time_ns.load(Ordering::Relaxed)),
+            instantiation_time: Duration::from_nanos(
+                c.instantiation_time_ns.load(Ordering::Relaxed),
+            ),
+            instructions: c
+                .metered
+                .load(Ordering::Relaxed)
+                .then(|| c.instructions.load(Ordering::Relaxed)),
+            memory_grows: c.memory_grows.load(Ordering::Relaxed),
+            peak_memory_pages: c.peak_memory_pages.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the memory used by the instance of the main thread when it
+    /// exited, the last one to exit when processes were spawned, if
+    /// tracking is enabled.
+    #[cfg(feature = "sys")]
+    pub fn memory_usage(&self) -> Option<wasmer::MemoryUsage> {
+        self.counters.memory_usage.lock().unwrap().clone()
+    }
+
+    pub(crate) fn add_bytes_read(&self, bytes: usize) {
+        self.counters
+            .bytes_read
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_bytes_written(&self, bytes: usize) {
+        self.counters
+            .bytes_written
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Accounts for the time spent instantiating a module, if tracking is
+    /// enabled.
+    pub(crate) fn add_instantiation_time(&self, elapsed: Duration) {
+        if self.counters.enabled.load(Ordering::Relaxed) {
+            self.counters
+                .instantiation_time_ns
+                .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Records the size of a memory of the instance, keeping the largest.
+    pub(crate) fn record_memory_pages(&self, pages: u32) {
+        self.counters
+            .peak_memory_pages
+            .fetch_max(pages, Ordering::Relaxed);
+    }
+
+    /// Records the memory used by the main instance, if tracking is
+    /// enabled.
+    #[cfg(feature = "sys")]
+    pub(crate) fn record_memory_usage(&self, usage: impl FnOnce() -> wasmer::MemoryUsage) {
+        if self.counters.enabled.load(Ordering::Relaxed) {
+            *self.counters.memory_usage.lock().unwrap() = Some(usage());
+        }
+    }
+
+    /// Installs the call hook that counts the syscalls and measures the CPU
+    /// time of the thread running in `store`, and the hook that follows the
+    /// growth of its memories, if tracking is enabled.
+    #[cfg(feature = "sys")]
+    pub(crate) fn install(&self, store: &mut impl wasmer::AsStoreMut) {
+        use wasmer::CallHook;
+
+        if !self.counters.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let counters = self.counters.clone();
+        store.as_store_mut().on_memory_grow_event(move |event| {
+            counters.memory_grows.fetch_add(1, Ordering::Relaxed);
+            counters
+                .peak_memory_pages
+                .fetch_max(event.new_size.0, Ordering::Relaxed);
+        });
+
+        let counters = self.counters.clone();
+        // Host functions can call back into WebAssembly, only the outermost
+        // call is measured so that nothing is counted twice.
+        let mut depth = 0usize;
+        let mut started = Duration::ZERO;
+        let mut started_at = Instant::now();
+        let mut fuel_consumed = None;
+        let mut previous = store.as_store_mut().take_call_hook();
+        store.as_store_mut().call_hook(move |store, hook, ty| {
+            match hook {
+                CallHook::CallingWasm => {
+                    if depth == 0 {
+                        started = thread_cpu_time();
+                        started_at = Instant::now();
+                        fuel_consumed = store.fuel_consumed();
+                    }
+                    depth += 1;
+                }
+                CallHook::ReturningFromWasm => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        let elapsed = thread_cpu_time().saturating_sub(started);
+                        counters
+                            .cpu_time_ns
+                            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+                        let elapsed = started_at.elapsed();
+                        counters
+                            .execution_time_ns
+                            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+                        if let (Some(before), Some(after)) = (fuel_consumed, store.fuel_consumed())
+                        {
+                            counters.metered.store(true, Ordering::Relaxed);
+                            counters
+                                .instructions
+                                .fetch_add(after.saturating_sub(before), Ordering::Relaxed);
+                        }
+                    }
+                }
+                CallHook::CallingHost => {
+                    counters.syscalls.fetch_add(1, Ordering::Relaxed);
+                }
+                CallHook::ReturningFromHost => {}
+            }
+            match previous.as_mut() {
+                Some(previous) => previous(store, hook, ty),
+                None => Ok(()),
+            }
+        });
+    }
+}
+
+/// CPU time consumed so far by the calling thread.
+#[cfg(all(feature = "sys", target_os = "linux"))]
+fn thread_cpu_time() -> Duration {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    if unsafe { libc::getrusage(libc::RUSAGE_THREAD, usage.as_mut_ptr()) } != 0 {
+        return Duration::ZERO;
+    }
+    let usage = unsafe { usage.assume_init() };
+    let time = |t: libc::timeval| {
+        Duration::from_secs(t.tv_sec as u64) + Duration::from_micros(t.tv_usec as u64)
+    };
+    time(usage.ru_utime) + time(usage.ru_stime)
+}
+
+/// CPU time consumed so far by the calling thread.
+#[cfg(all(feature = "sys", unix, not(target_os = "linux")))]
+fn thread_cpu_time() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) } != 0 {
+        return Duration::ZERO;
+    }
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/// CPU time consumed so far by the calling thread.
+#[cfg(all(feature = "sys", not(unix)))]
+fn thread_cpu_time() -> Duration {
+    Duration::ZERO
+}
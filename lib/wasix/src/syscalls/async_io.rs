@@ -0,0 +1,475 @@
+//! Asynchronous versions of the I/O syscalls, used when
+//! [`WasiEnvBuilder::async_io`](crate::WasiEnvBuilder::async_io) is enabled.
+//!
+//! Instead of blocking the calling thread until a file, pipe or socket is
+//! ready, these syscalls suspend the guest and hand control back to the
+//! executor that drives [`Function::call_async`]. The data is staged in a
+//! host buffer so that no reference into the guest memory is held while the
+//! guest is suspended.
+//!
+//! File descriptors that are not backed by a file, pipe or socket (and all
+//! file descriptors while journaling is enabled) fall back to the regular
+//! synchronous implementation.
+
+use std::{
+    future::Future,
+    io::SeekFrom,
+    mem::MaybeUninit,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    task::{ready, Context, Poll},
+};
+
+use virtual_fs::{
+    AsyncRead, AsyncReadExt, AsyncSeek, AsyncWrite, AsyncWriteExt, Pipe, ReadBuf, VirtualFile,
+};
+use wasmer::{
+    AsStoreMut, Function, FunctionEnv, FunctionEnvMut, FunctionType, HostFuture, Imports,
+    RuntimeError, Type, Value,
+};
+
+use super::*;
+use crate::{
+    net::socket::{InodeSocket, TimeType},
+    os::task::{process::WasiProcess, thread::WasiThread},
+};
+
+/// The most data that is staged for a single read or write, anything beyond
+/// that is reported as a short read or write.
+const MAX_BUFFER_SIZE: usize = 1024 * 1024;
+
+type FileHandle = Arc<RwLock<Box<dyn VirtualFile + Send + Sync + 'static>>>;
+
+/// What an asynchronous read or write operates on.
+enum Target {
+    File { handle: FileHandle, is_stdio: bool },
+    Pipe(Pipe),
+    Socket(InodeSocket),
+}
+
+impl Target {
+    /// Regular files have a cursor and count against the file system quotas.
+    fn is_file(&self) -> bool {
+        matches!(
+            self,
+            Target::File {
+                is_stdio: false,
+                ..
+            }
+        )
+    }
+}
+
+/// Replaces the I/O syscalls of every WASI namespace in `imports` with their
+/// asynchronous versions.
+pub(crate) fn define_async_io_imports(
+    imports: &mut Imports,
+    store: &mut impl AsStoreMut,
+    env: &FunctionEnv<WasiEnv>,
+) {
+    for namespace in ["wasi_unstable", "wasi_snapshot_preview1", "wasix_32v1"] {
+        define_namespace::<Memory32>(imports, namespace, store, env);
+    }
+    define_namespace::<Memory64>(imports, "wasix_64v1", store, env);
+}
+
+fn define_namespace<M: MemorySize + 'static>(
+    imports: &mut Imports,
+    namespace: &str,
+    store: &mut impl AsStoreMut,
+    env: &FunctionEnv<WasiEnv>,
+) {
+    let ptr_ty = if M::is_64bit() { Type::I64 } else { Type::I32 };
+    let rw = FunctionType::new([Type::I32, ptr_ty, ptr_ty, ptr_ty], [Type::I32]);
+    let prw = FunctionType::new([Type::I32, ptr_ty, ptr_ty, Type::I64, ptr_ty], [Type::I32]);
+
+    let fd_read = Function::new_with_env_async(store, env, rw.clone(), |ctx, args| {
+        let (fd, iovs, iovs_len, nread) = (
+            fd(args, 0),
+            ptr(args, 1),
+            offset::<M>(args, 2),
+            ptr(args, 3),
+        );
+        into_host_future(fd_read_async::<M>(ctx, fd, iovs, iovs_len, None, nread))
+    });
+    let fd_pread = Function::new_with_env_async(store, env, prw.clone(), |ctx, args| {
+        let (fd, iovs, iovs_len, nread) = (
+            fd(args, 0),
+            ptr(args, 1),
+            offset::<M>(args, 2),
+            ptr(args, 4),
+        );
+        let at = args[3].unwrap_i64() as Filesize;
+        into_host_future(fd_read_async::<M>(ctx, fd, iovs, iovs_len, Some(at), nread))
+    });
+    let fd_write = Function::new_with_env_async(store, env, rw, |ctx, args| {
+        let (fd, iovs, iovs_len, nwritten) = (
+            fd(args, 0),
+            ptr(args, 1),
+            offset::<M>(args, 2),
+            ptr(args, 3),
+        );
+        into_host_future(fd_write_async::<M>(ctx, fd, iovs, iovs_len, None, nwritten))
+    });
+    let fd_pwrite = Function::new_with_env_async(store, env, prw, |ctx, args| {
+        let (fd, iovs, iovs_len, nwritten) = (
+            fd(args, 0),
+            ptr(args, 1),
+            offset::<M>(args, 2),
+            ptr(args, 4),
+        );
+        let at = args[3].unwrap_i64() as Filesize;
+        into_host_future(fd_write_async::<M>(
+            ctx,
+            fd,
+            iovs,
+            iovs_len,
+            Some(at),
+            nwritten,
+        ))
+    });
+
+    imports.define(namespace, "fd_read", fd_read);
+    imports.define(namespace, "fd_pread", fd_pread);
+    imports.define(namespace, "fd_write", fd_write);
+    imports.define(namespace, "fd_pwrite", fd_pwrite);
+}
+
+fn fd(args: &[Value], n: usize) -> WasiFd {
+    args[n].unwrap_i32() as WasiFd
+}
+
+fn offset<M: MemorySize>(args: &[Value], n: usize) -> M::Offset {
+    let offset = match args[n] {
+        Value::I32(offset) => offset as u32 as u64,
+        Value::I64(offset) => offset as u64,
+        _ => 0,
+    };
+    M::Offset::try_from(offset).unwrap_or_default()
+}
+
+fn ptr<T, M: MemorySize>(args: &[Value], n: usize) -> WasmPtr<T, M> {
+    WasmPtr::new(offset::<M>(args, n))
+}
+
+fn into_host_future<'a>(
+    syscall: impl Future<Output = Result<Errno, WasiError>> + Send + 'a,
+) -> HostFuture<'a> {
+    Box::pin(async move {
+        match syscall.await {
+            Ok(errno) => Ok(vec![Value::I32(errno as i32)]),
+            Err(err) => Err(RuntimeError::user(Box::new(err))),
+        }
+    })
+}
+
+/// Looks up what `fd` refers to, returns `None` if the syscall should be
+/// handled synchronously.
+fn target(env: &WasiEnv, fd: WasiFd, rights: Rights) -> Result<Option<(Target, Fd)>, Errno> {
+    if env.enable_journal {
+        return Ok(None);
+    }
+
+    let fd_entry = env.state.fs.get_fd(fd)?;
+    if !fd_entry.is_stdio && !fd_entry.rights.contains(rights) {
+        return Err(Errno::Access);
+    }
+
+    let target = match fd_entry.inode.read().deref() {
+        Kind::File {
+            handle: Some(handle),
+            ..
+        } => Target::File {
+            handle: handle.clone(),
+            is_stdio: fd_entry.is_stdio,
+        },
+        Kind::File { handle: None, .. } => return Err(Errno::Badf),
+        Kind::Pipe { pipe } => Target::Pipe(pipe.clone()),
+        Kind::Socket { socket } => Target::Socket(socket.clone()),
+        _ => return Ok(None),
+    };
+    Ok(Some((target, fd_entry)))
+}
+
+/// Drives `work` to completion, giving up when the process exits or a
+/// signal is pending, the asynchronous counterpart of `__asyncify_light`.
+async fn interruptible<T>(
+    thread: WasiThread,
+    process: WasiProcess,
+    nonblocking: bool,
+    work: impl Future<Output = Result<T, Errno>>,
+) -> Result<Result<T, Errno>, WasiError> {
+    let mut work = std::pin::pin!(work);
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(res) = work.as_mut().poll(cx) {
+            return Poll::Ready(Ok(res));
+        }
+        if nonblocking {
+            return Poll::Ready(Ok(Err(Errno::Again)));
+        }
+        let exit = thread.try_join().or_else(|| process.try_join());
+        if let Some(exit) = exit {
+            let code = exit.unwrap_or_else(|_| Errno::Child.into());
+            return Poll::Ready(Err(WasiError::Exit(code)));
+        }
+        if thread.has_signals_or_subscribe(cx.waker()) {
+            return Poll::Ready(Ok(Err(Errno::Intr)));
+        }
+        Poll::Pending
+    })
+    .await
+}
+
+/// Seeks `handle` (unless it is stdio) and performs `op` while holding the
+/// lock, so that nothing else can move the cursor in between.
+fn poll_file<T>(
+    handle: &FileHandle,
+    seek: Option<u64>,
+    cx: &mut Context<'_>,
+    mut op: impl FnMut(
+        Pin<&mut Box<dyn VirtualFile + Send + Sync + 'static>>,
+        &mut Context<'_>,
+    ) -> Poll<std::io::Result<T>>,
+) -> Poll<Result<T, Errno>> {
+    let mut guard = handle.write().map_err(|_| Errno::Fault)?;
+    let mut file = Pin::new(&mut *guard);
+    if let Some(seek) = seek {
+        file.as_mut()
+            .start_seek(SeekFrom::Start(seek))
+            .map_err(map_io_err)?;
+        ready!(file.as_mut().poll_complete(cx)).map_err(map_io_err)?;
+    }
+    op(file, cx).map_err(map_io_err)
+}
+
+async fn fd_read_async<M: MemorySize>(
+    mut ctx: FunctionEnvMut<'_, WasiEnv>,
+    fd: WasiFd,
+    iovs: WasmPtr<__wasi_iovec_t<M>, M>,
+    iovs_len: M::Offset,
+    at: Option<Filesize>,
+    nread: WasmPtr<M::Offset, M>,
+) -> Result<Errno, WasiError> {
+    wasi_try_ok!(WasiEnv::process_signals_and_exit(&mut ctx)?);
+
+    let env = ctx.data();
+    let (target, fd_entry) = match wasi_try_ok!(target(env, fd, Rights::FD_READ)) {
+        Some(target) => target,
+        None => {
+            return match at {
+                Some(at) => fd_pread::<M>(ctx, fd, iovs, iovs_len, at, nread),
+                None => fd_read::<M>(ctx, fd, iovs, iovs_len, nread),
+            }
+        }
+    };
+    let len = {
+        let memory = unsafe { env.memory_view(&ctx) };
+        let iovs_arr = wasi_try_mem_ok!(iovs.slice(&memory, iovs_len));
+        let len = wasi_try_mem_ok!(iovs_arr.access())
+            .iter()
+            .map(|iov| iov.buf_len.try_into().unwrap_or(usize::MAX))
+            .fold(0usize, usize::saturating_add);
+        len.min(MAX_BUFFER_SIZE)
+    };
+
+    let nonblocking = fd_entry.flags.contains(Fdflags::NONBLOCK);
+    let offset = at.unwrap_or_else(|| fd_entry.offset.load(Ordering::Acquire));
+    let is_file = target.is_file();
+    let tasks = env.tasks().clone();
+    let (thread, process) = (env.thread.clone(), env.process.clone());
+
+    let mut buf = vec![0u8; len];
+    let res = interruptible(thread, process, nonblocking, async {
+        match &target {
+            Target::File { handle, is_stdio } => {
+                let seek = (!is_stdio).then_some(offset);
+                std::future::poll_fn(|cx| {
+                    poll_file(handle, seek, cx, |file, cx| {
+                        let mut read_buf = ReadBuf::new(&mut buf);
+                        ready!(file.poll_read(cx, &mut read_buf))?;
+                        Poll::Ready(Ok(read_buf.filled().len()))
+                    })
+                })
+                .await
+                .map_err(|err| match err {
+                    Errno::Again if *is_stdio => Errno::Badf,
+                    err => err,
+                })
+            }
+            Target::Pipe(pipe) => {
+                let mut pipe = pipe.clone();
+                AsyncReadExt::read(&mut pipe, &mut buf)
+                    .await
+                    .map_err(map_io_err)
+            }
+            Target::Socket(socket) => {
+                let timeout = socket
+                    .opt_time(TimeType::ReadTimeout)
+                    .ok()
+                    .flatten()
+                    .unwrap_or(Duration::from_secs(30));
+                // Safety: `MaybeUninit<u8>` has the same layout as `u8`
+                let uninit =
+                    unsafe { &mut *(buf.as_mut_slice() as *mut [u8] as *mut [MaybeUninit<u8>]) };
+                match socket
+                    .recv(tasks.deref(), uninit, Some(timeout), nonblocking)
+                    .await
+                {
+                    Err(Errno::Connaborted) | Err(Errno::Connreset) => Ok(0),
+                    res => res,
+                }
+            }
+        }
+    })
+    .await?;
+
+    let (errno, bytes_read) = match res {
+        Ok(bytes_read) => {
+            let env = ctx.data();
+            let memory = unsafe { env.memory_view(&ctx) };
+            let iovs_arr = wasi_try_mem_ok!(iovs.slice(&memory, iovs_len));
+            let bytes_read = wasi_try_ok!(read_bytes(&buf[..bytes_read], &memory, iovs_arr));
+            if at.is_none() && is_file {
+                fd_entry
+                    .offset
+                    .fetch_add(bytes_read as u64, Ordering::AcqRel);
+            }
+            (Errno::Success, bytes_read)
+        }
+        Err(err) => (err, 0),
+    };
+    ctx.data().state.usage.add_bytes_read(bytes_read);
+
+    let bytes_read: M::Offset = wasi_try_ok!(bytes_read.try_into().map_err(|_| Errno::Overflow));
+    let env = ctx.data();
+    let memory = unsafe { env.memory_view(&ctx) };
+    wasi_try_mem_ok!(nread.write(&memory, bytes_read));
+    Ok(errno)
+}
+
+async fn fd_write_async<M: MemorySize>(
+    mut ctx: FunctionEnvMut<'_, WasiEnv>,
+    fd: WasiFd,
+    iovs: WasmPtr<__wasi_ciovec_t<M>, M>,
+    iovs_len: M::Offset,
+    at: Option<Filesize>,
+    nwritten: WasmPtr<M::Offset, M>,
+) -> Result<Errno, WasiError> {
+    wasi_try_ok!(WasiEnv::process_signals_and_exit(&mut ctx)?);
+
+    let env = ctx.data();
+    let (target, fd_entry) = match wasi_try_ok!(target(env, fd, Rights::FD_WRITE)) {
+        Some(target) => target,
+        None => {
+            return match at {
+                Some(at) => fd_pwrite::<M>(ctx, fd, iovs, iovs_len, at, nwritten),
+                None => fd_write::<M>(ctx, fd, iovs, iovs_len, nwritten),
+            }
+        }
+    };
+
+    // Stage the data, the guest memory can not be borrowed while suspended
+    let data = {
+        let memory = unsafe { env.memory_view(&ctx) };
+        let iovs_arr = wasi_try_mem_ok!(iovs.slice(&memory, iovs_len));
+        let mut data = Vec::new();
+        for iov in wasi_try_mem_ok!(iovs_arr.access()).iter() {
+            let remaining = (MAX_BUFFER_SIZE - data.len()) as u64;
+            let len: u64 = iov.buf_len.into();
+            let len = M::Offset::try_from(len.min(remaining)).unwrap_or_default();
+            let buf = wasi_try_mem_ok!(WasmPtr::<u8, M>::new(iov.buf).slice(&memory, len));
+            data.extend_from_slice(wasi_try_mem_ok!(buf.access()).as_ref());
+            if data.len() == MAX_BUFFER_SIZE {
+                break;
+            }
+        }
+        data
+    };
+
+    let nonblocking = fd_entry.flags.contains(Fdflags::NONBLOCK);
+    let offset = at.unwrap_or_else(|| fd_entry.offset.load(Ordering::Acquire));
+    let is_file = target.is_file();
+    let state = env.state.clone();
+    let reserved = if is_file {
+        let write_offset = if fd_entry.flags.contains(Fdflags::APPEND) {
+            offset.max(fd_entry.inode.stat.read().unwrap().st_size)
+        } else {
+            offset
+        };
+        wasi_try_ok!(state
+            .fs
+            .quota
+            .reserve_write(write_offset, data.len() as u64));
+        data.len() as u64
+    } else {
+        0
+    };
+    let tasks = env.tasks().clone();
+    let (thread, process) = (env.thread.clone(), env.process.clone());
+
+    let res = interruptible(thread, process, nonblocking, async {
+        match &target {
+            Target::File { handle, is_stdio } => {
+                let seek = (!is_stdio).then_some(offset);
+                let written = std::future::poll_fn(|cx| {
+                    poll_file(handle, seek, cx, |file, cx| file.poll_write(cx, &data))
+                })
+                .await?;
+                if *is_stdio {
+                    std::future::poll_fn(|cx| {
+                        poll_file(handle, None, cx, |file, cx| file.poll_flush(cx))
+                    })
+                    .await?;
+                }
+                Ok(written)
+            }
+            Target::Pipe(pipe) => {
+                let mut pipe = pipe.clone();
+                AsyncWriteExt::write(&mut pipe, &data)
+                    .await
+                    .map_err(map_io_err)
+            }
+            Target::Socket(socket) => {
+                let timeout = socket
+                    .opt_time(TimeType::WriteTimeout)
+                    .ok()
+                    .flatten()
+                    .unwrap_or(Duration::from_secs(30));
+                socket
+                    .send(tasks.deref(), &data, Some(timeout), nonblocking)
+                    .await
+            }
+        }
+    })
+    .await;
+    let used = match &res {
+        Ok(Ok(written)) => *written as u64,
+        _ => 0,
+    };
+    state.fs.quota.release_write(reserved.saturating_sub(used));
+    let bytes_written = wasi_try_ok!(res?);
+    state.usage.add_bytes_written(bytes_written);
+
+    // Keep the cursor and the size of the file up to date
+    if is_file || !matches!(target, Target::File { .. }) {
+        let mut stat = fd_entry.inode.stat.write().unwrap();
+        if is_file {
+            let end = offset + bytes_written as u64;
+            if at.is_none() {
+                fd_entry
+                    .offset
+                    .fetch_add(bytes_written as u64, Ordering::AcqRel);
+            }
+            stat.st_size = stat.st_size.max(end);
+        } else {
+            stat.st_size += bytes_written as u64;
+        }
+    }
+
+    let bytes_written: M::Offset =
+        wasi_try_ok!(bytes_written.try_into().map_err(|_| Errno::Overflow));
+    let env = ctx.data();
+    let memory = unsafe { env.memory_view(&ctx) };
+    wasi_try_mem_ok!(nwritten.write(&memory, bytes_written));
+    Ok(Errno::Success)
+}
@@ -1,5 +1,7 @@
 #![allow(unused, clippy::too_many_arguments, clippy::cognitive_complexity)]
 
+#[cfg(feature = "sys")]
+pub(crate) mod async_io;
 pub mod types {
     pub use wasmer_wasix_types::{types::*, wasi};
 }
@@ -17,6 +19,7 @@ pub mod wasm;
 pub mod windows;
 
 pub mod journal;
+mod trace;
 pub mod wasi;
 pub mod wasix;
 
@@ -55,6 +58,7 @@ pub(crate) use std::{
 };
 use std::{io::IoSlice, marker::PhantomData, mem::MaybeUninit, task::Waker, time::Instant};
 
+pub(crate) use self::trace::syscall;
 pub(crate) use bytes::{Bytes, BytesMut};
 pub(crate) use cooked_waker::IntoWaker;
 pub use journal::*;
@@ -878,6 +882,41 @@ pub(crate) fn get_current_time_in_nanos() -> Result<Timestamp, Errno> {
     Ok(now as Timestamp)
 }
 
+/// Sleeps for `duration`, with a precision better than a millisecond when
+/// a `spin_threshold` is given (see
+/// [`crate::capabilities::CapabilityThreadingV1::enable_precise_timers`]).
+///
+/// The timers of the task manager only have a millisecond granularity so
+/// they are used for the bulk of the sleep, after which the final stretch
+/// busy-waits until the deadline, which avoids the jitter of being
+/// scheduled again by the runtime. Without a threshold the whole sleep is
+/// left to the task manager.
+pub(crate) async fn sleep_precise(
+    tasks: Arc<dyn VirtualTaskManager>,
+    duration: Duration,
+    spin_threshold: Option<Duration>,
+) {
+    let Some(spin_threshold) = spin_threshold else {
+        tasks.sleep_now(duration).await;
+        return;
+    };
+
+    let now = || platform_clock_time_get(Snapshot0Clockid::Monotonic, 1).unwrap_or(i64::MAX);
+    let deadline = (now() as u128).saturating_add(duration.as_nanos());
+
+    let margin = spin_threshold.max(Duration::from_millis(1));
+    let coarse = duration.saturating_sub(margin);
+    if coarse >= Duration::from_millis(1) {
+        tasks
+            .sleep_now(Duration::from_millis(coarse.as_millis() as u64))
+            .await;
+    }
+
+    while (now() as u128) < deadline {
+        std::hint::spin_loop();
+    }
+}
+
 pub(crate) fn get_stack_lower(env: &WasiEnv) -> u64 {
     env.layout.stack_lower
 }
@@ -0,0 +1,158 @@
+//! Instrumentation of the syscalls imported by the instances, which reports
+//! them to the [`SyscallTracer`](crate::SyscallTracer) of their environment.
+
+use std::{any::Any, fmt::Debug};
+
+use wasmer::{
+    AsStoreMut, FromToNativeWasmType, Function, FunctionEnv, FunctionEnvMut, IntoResult,
+    WasmTypeList,
+};
+use wasmer_wasix_types::wasi::{Errno, Fd as WasiFd};
+
+use crate::{SyscallEvent, WasiEnv, WasiError};
+
+/// Creates the function imported as the syscall `name`, instrumented if the
+/// syscalls of the environment are traced and `name` is one of them.
+pub(crate) fn syscall<Args, Rets>(
+    store: &mut impl AsStoreMut,
+    env: &FunctionEnv<WasiEnv>,
+    name: &'static str,
+    func: impl Syscall<Args, Rets>,
+) -> Function {
+    func.into_function(store, env, name)
+}
+
+/// The implementation of a syscall, a host function taking the environment
+/// and the arguments of the syscall.
+pub(crate) trait Syscall<Args, Rets> {
+    fn into_function(
+        self,
+        store: &mut impl AsStoreMut,
+        env: &FunctionEnv<WasiEnv>,
+        name: &'static str,
+    ) -> Function;
+}
+
+/// An argument of a syscall, formatted when it is traced.
+trait SyscallArg: Debug {
+    fn as_any(&self) -> &(dyn Any + 'static);
+}
+
+impl<T: Debug + 'static> SyscallArg for T {
+    fn as_any(&self) -> &(dyn Any + 'static) {
+        self
+    }
+}
+
+/// The result of a syscall, formatted when it is traced.
+pub(crate) trait SyscallResult {
+    fn describe(&self) -> String;
+}
+
+impl SyscallResult for Errno {
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl SyscallResult for i32 {
+    fn describe(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T: Debug> SyscallResult for Result<T, WasiError> {
+    fn describe(&self) -> String {
+        match self {
+            Ok(value) => format!("{value:?}"),
+            Err(err) => err.to_string(),
+        }
+    }
+}
+
+/// Returns the file descriptor a syscall operates on, the first argument of
+/// the syscalls that take one.
+fn fd_argument(name: &str, args: &[&(dyn SyscallArg + 'static)]) -> Option<WasiFd> {
+    if !["fd_", "path_", "sock_", "epoll_"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        return None;
+    }
+    let arg: &dyn SyscallArg = *args.first()?;
+    arg.as_any().downcast_ref::<WasiFd>().copied()
+}
+
+macro_rules! impl_syscall {
+    ( $( $x:ident ),* ) => {
+        #[allow(non_snake_case)]
+        impl<$( $x, )* Rets, RetsAsResult, Func> Syscall<( $( $x, )* ), Rets> for Func
+        where
+            $( $x: FromToNativeWasmType + Debug + 'static, )*
+            Rets: WasmTypeList,
+            RetsAsResult: IntoResult<Rets> + SyscallResult,
+            Func: Fn(FunctionEnvMut<'_, WasiEnv>, $( $x, )*) -> RetsAsResult
+                + Send
+                + Sync
+                + 'static,
+        {
+            fn into_function(
+                self,
+                store: &mut impl AsStoreMut,
+                env: &FunctionEnv<WasiEnv>,
+                name: &'static str,
+            ) -> Function {
+                let tracer = env
+                    .as_ref(&*store)
+                    .state
+                    .syscall_tracer
+                    .clone()
+                    .filter(|tracer| tracer.traces(name));
+                let Some(tracer) = tracer else {
+                    return Function::new_typed_with_env(store, env, self);
+                };
+
+                let func = self;
+                Function::new_typed_with_env(
+                    store,
+                    env,
+                    move |ctx: FunctionEnvMut<'_, WasiEnv> $(, $x: $x )*| {
+                        let args: &[&(dyn SyscallArg + 'static)] = &[$( &$x ),*];
+                        let fd = fd_argument(name, args);
+                        if !tracer.traces_fd(fd) {
+                            return func(ctx $(, $x )*);
+                        }
+                        let args = args.iter().map(|arg| format!("{arg:?}")).collect();
+                        let (pid, tid) = (ctx.data().pid(), ctx.data().tid());
+
+                        let result = func(ctx $(, $x )*);
+                        tracer.record(&SyscallEvent {
+                            pid,
+                            tid,
+                            name,
+                            fd,
+                            args,
+                            result: result.describe(),
+                        });
+                        result
+                    },
+                )
+            }
+        }
+    };
+}
+
+impl_syscall!();
+impl_syscall!(A1);
+impl_syscall!(A1, A2);
+impl_syscall!(A1, A2, A3);
+impl_syscall!(A1, A2, A3, A4);
+impl_syscall!(A1, A2, A3, A4, A5);
+impl_syscall!(A1, A2, A3, A4, A5, A6);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7, A8);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7, A8, A9);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12);
+impl_syscall!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12, A13);
@@ -51,6 +51,7 @@ pub(crate) fn fd_allocate_internal(
         match guard.deref_mut() {
             Kind::File { handle, .. } => {
                 if let Some(handle) = handle {
+                    state.fs.quota.check_file_size(new_size)?;
                     let mut handle = handle.write().unwrap();
                     handle.set_len(new_size).map_err(fs_error_into_wasi_err)?;
                 } else {
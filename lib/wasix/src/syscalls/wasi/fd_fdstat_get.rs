@@ -17,7 +17,24 @@ pub fn fd_fdstat_get<M: MemorySize>(
 ) -> Errno {
     let env = ctx.data();
     let (memory, mut state, inodes) = unsafe { env.get_memory_and_wasi_state_and_inodes(&ctx, 0) };
-    let stat = wasi_try!(state.fs.fdstat(fd));
+    let mut stat = wasi_try!(state.fs.fdstat(fd));
+
+    // The standard streams are only reported as terminals (which is what
+    // `isatty` looks for) when they are connected to one on the host
+    if let Some(tty) = env.runtime.tty() {
+        if stat.fs_filetype == Filetype::CharacterDevice {
+            let tty = tty.tty_get();
+            let is_tty = match fd {
+                __WASI_STDIN_FILENO => tty.stdin_tty,
+                __WASI_STDOUT_FILENO => tty.stdout_tty,
+                __WASI_STDERR_FILENO => tty.stderr_tty,
+                _ => true,
+            };
+            if !is_tty {
+                stat.fs_filetype = Filetype::Unknown;
+            }
+        }
+    }
 
     let buf = buf_ptr.deref(&memory);
 
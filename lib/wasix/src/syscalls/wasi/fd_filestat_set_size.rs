@@ -47,6 +47,7 @@ pub(crate) fn fd_filestat_set_size_internal(
         match guard.deref_mut() {
             Kind::File { handle, .. } => {
                 if let Some(handle) = handle {
+                    state.fs.quota.check_file_size(st_size)?;
                     let mut handle = handle.write().unwrap();
                     handle.set_len(st_size).map_err(fs_error_into_wasi_err)?;
                 } else {
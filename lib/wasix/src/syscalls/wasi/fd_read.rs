@@ -102,6 +102,7 @@ pub(crate) fn fd_read_internal_handler<M: MemorySize>(
             0
         }
     };
+    ctx.data().state.usage.add_bytes_read(bytes_read);
     Span::current().record("nread", bytes_read);
 
     let bytes_read: M::Offset = wasi_try_ok!(bytes_read.try_into().map_err(|_| Errno::Overflow));
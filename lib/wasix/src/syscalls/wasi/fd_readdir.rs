@@ -33,6 +33,9 @@ pub fn fd_readdir<M: MemorySize>(
     let buf_arr = wasi_try_mem!(buf.slice(&memory, buf_len));
     let bufused_ref = bufused.deref(&memory);
     let working_dir = wasi_try!(state.fs.get_fd(fd));
+    if !working_dir.rights.contains(Rights::FD_READDIR) {
+        return Errno::Access;
+    }
     let mut cur_cookie = cookie;
     let mut buf_idx = 0usize;
 
@@ -41,6 +41,7 @@ pub(crate) fn fd_renumber_internal(
     let (_, mut state) = unsafe { env.get_memory_and_wasi_state(&ctx, 0) };
 
     let mut fd_map = state.fs.fd_map.write().unwrap();
+    wasi_try!(state.fs.quota.check_new_fd(&fd_map, to));
     let fd_entry = wasi_try!(fd_map.get_mut(&from).ok_or(Errno::Badf));
 
     let new_fd_entry = Fd {
@@ -50,6 +50,7 @@ pub fn fd_write<M: MemorySize>(
         env.enable_journal,
     )?);
 
+    ctx.data().state.usage.add_bytes_written(bytes_written);
     Span::current().record("nwritten", bytes_written);
 
     let mut env = ctx.data();
@@ -98,6 +99,7 @@ pub fn fd_pwrite<M: MemorySize>(
         enable_snapshot_capture,
     )?);
 
+    ctx.data().state.usage.add_bytes_written(bytes_written);
     Span::current().record("nwritten", bytes_written);
 
     let mut env = ctx.data();
@@ -118,6 +120,20 @@ pub(crate) enum FdWriteSource<'a, M: MemorySize> {
     Buffer(Cow<'a, [u8]>),
 }
 
+impl<'a, M: MemorySize> FdWriteSource<'a, M> {
+    /// Total number of bytes that the guest asked to write
+    fn len(&self, memory: &MemoryView) -> Result<u64, Errno> {
+        match self {
+            FdWriteSource::Iovs { iovs, iovs_len } => {
+                let iovs_arr = iovs.slice(memory, *iovs_len).map_err(mem_error_to_wasi)?;
+                let iovs_arr = iovs_arr.access().map_err(mem_error_to_wasi)?;
+                Ok(iovs_arr.iter().map(|iovs| iovs.buf_len.into()).sum())
+            }
+            FdWriteSource::Buffer(data) => Ok(data.len() as u64),
+        }
+    }
+}
+
 #[allow(clippy::await_holding_lock)]
 pub(crate) fn fd_write_internal<M: MemorySize>(
     ctx: &FunctionEnvMut<'_, WasiEnv>,
@@ -150,6 +166,22 @@ pub(crate) fn fd_write_internal<M: MemorySize>(
                         let handle = handle.clone();
                         drop(guard);
 
+                        // Writes to regular files count against the quotas of the
+                        // file system, the reservation is corrected once we know
+                        // how much was actually written
+                        let reserved = if is_stdio {
+                            0
+                        } else {
+                            let len = wasi_try_ok_ok!(data.len(&memory));
+                            let write_offset = if fd_flags.contains(Fdflags::APPEND) {
+                                offset.max(fd_entry.inode.stat.read().unwrap().st_size)
+                            } else {
+                                offset
+                            };
+                            wasi_try_ok_ok!(state.fs.quota.reserve_write(write_offset, len));
+                            len
+                        };
+
                         let res = __asyncify_light(
                             env,
                             if fd_entry.flags.contains(Fdflags::NONBLOCK) {
@@ -205,10 +237,13 @@ pub(crate) fn fd_write_internal<M: MemorySize>(
                                 Ok(written)
                             },
                         );
-                        let written = wasi_try_ok_ok!(res?.map_err(|err| match err {
+                        let res = res?.map_err(|err| match err {
                             Errno::Timedout => Errno::Again,
                             a => a,
-                        }));
+                        });
+                        let used = res.as_ref().map_or(0, |written| *written as u64);
+                        state.fs.quota.release_write(reserved.saturating_sub(used));
+                        let written = wasi_try_ok_ok!(res);
 
                         (written, true, true)
                     } else {
@@ -122,6 +122,9 @@ pub(crate) fn path_create_directory_internal(
                             return Err(Errno::Notdir);
                         }
                     } else {
+                        state
+                            .fs
+                            .check_preopen_rights(&cur_dir_inode, Rights::PATH_CREATE_DIRECTORY)?;
                         created_directory = true;
                         state.fs_create_dir(&adjusted_path)?;
                     }
@@ -110,6 +110,12 @@ pub(crate) fn path_link_internal(
         state
             .fs
             .get_parent_inode_at_path(inodes, new_fd, &target_path_arg, false)?;
+    state
+        .fs
+        .check_preopen_rights(&source_inode, Rights::PATH_LINK_SOURCE)?;
+    state
+        .fs
+        .check_preopen_rights(&target_parent_inode, Rights::PATH_LINK_TARGET)?;
 
     if source_inode.stat.write().unwrap().st_nlink == Linkcount::max_value() {
         return Err(Errno::Mlink);
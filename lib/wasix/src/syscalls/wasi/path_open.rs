@@ -63,6 +63,11 @@ pub fn path_open<M: MemorySize>(
     let mut path_string = unsafe { get_input_str_ok!(&memory, path, path_len) };
     Span::current().record("path", path_string.as_str());
 
+    // The memory usage in `/proc/self` is only refreshed when files are opened
+    if let Some(proc) = &state.proc {
+        proc.set_memory_size(memory.data_size());
+    }
+
     // Convert relative paths into absolute paths
     if path_string.starts_with("./") {
         path_string = ctx.data().state.fs.relative_path_to_absolute(path_string);
@@ -156,6 +161,53 @@ pub(crate) fn path_open_internal(
     let adjusted_rights = /*fs_rights_base &*/ working_dir_rights_inheriting;
     let mut open_options = state.fs_new_open_options();
 
+    // The file may be inside a preopen nested in the one of the working
+    // directory, whose rights it must not escape
+    let preopen_rights = match &maybe_inode {
+        Ok(inode) => state.fs.preopen_rights_of(inode),
+        Err(_) => state
+            .fs
+            .get_parent_inode_at_path(
+                inodes,
+                dirfd,
+                &path_arg,
+                dirflags & __WASI_LOOKUP_SYMLINK_FOLLOW != 0,
+            )
+            .map_or(crate::ALL_RIGHTS, |(parent, _)| {
+                state.fs.preopen_rights_of(&parent)
+            }),
+    };
+    if !preopen_rights.contains(Rights::PATH_OPEN) {
+        return Ok(Err(Errno::Access));
+    }
+    let adjusted_rights = adjusted_rights & preopen_rights;
+
+    // A file descriptor can never gain rights that the directory it was
+    // opened from is not allowed to hand out
+    let is_dir = maybe_inode
+        .as_ref()
+        .is_ok_and(|inode| matches!(inode.read().deref(), Kind::Dir { .. } | Kind::Root { .. }));
+    if fs_rights_base.contains(Rights::FD_WRITE)
+        && !adjusted_rights.contains(Rights::FD_WRITE)
+        && !is_dir
+    {
+        return Ok(Err(Errno::Notcapable));
+    }
+    if o_flags.contains(Oflags::TRUNC) && !adjusted_rights.contains(Rights::PATH_FILESTAT_SET_SIZE)
+    {
+        return Ok(Err(Errno::Notcapable));
+    }
+    // Without the right to seek, files can only ever be appended to
+    let fs_flags = if fs_rights_base.contains(Rights::FD_WRITE)
+        && !adjusted_rights.contains(Rights::FD_SEEK)
+    {
+        fs_flags | Fdflags::APPEND
+    } else {
+        fs_flags
+    };
+    let fs_rights_inheriting =
+        fs_rights_inheriting & working_dir_rights_inheriting & preopen_rights;
+
     let target_rights = match maybe_inode {
         Ok(_) => {
             let write_permission = adjusted_rights.contains(Rights::FD_WRITE);
@@ -191,8 +243,8 @@ pub(crate) fn path_open_internal(
     };
 
     let parent_rights = virtual_fs::OpenOptionsConfig {
-        read: working_dir.rights.contains(Rights::FD_READ),
-        write: working_dir.rights.contains(Rights::FD_WRITE),
+        read: (working_dir.rights & preopen_rights).contains(Rights::FD_READ),
+        write: (working_dir.rights & preopen_rights).contains(Rights::FD_WRITE),
         // The parent is a directory, which is why these options
         // aren't inherited from the parent (append / truncate doesn't work on directories)
         create_new: true,
@@ -304,6 +356,9 @@ pub(crate) fn path_open_internal(
             if o_flags.contains(Oflags::DIRECTORY) {
                 return Ok(Err(Errno::Notdir));
             }
+            if !(working_dir.rights & preopen_rights).contains(Rights::PATH_CREATE_FILE) {
+                return Ok(Err(Errno::Access));
+            }
 
             // Trailing slash matters. But the underlying opener normalizes it away later.
             if path.ends_with('/') {
@@ -49,6 +49,9 @@ pub(crate) fn path_remove_directory_internal(
     let env = ctx.data();
     let (memory, state, inodes) = unsafe { env.get_memory_and_wasi_state_and_inodes(&ctx, 0) };
     let working_dir = state.fs.get_fd(fd)?;
+    if !working_dir.rights.contains(Rights::PATH_REMOVE_DIRECTORY) {
+        return Err(Errno::Access);
+    }
 
     let path = std::path::PathBuf::from(path);
     let path_vec = path
@@ -149,6 +152,10 @@ pub(crate) fn path_remove_directory_internal(
         }
     }
 
+    state
+        .fs
+        .check_preopen_rights(&cur_dir_inode, Rights::PATH_REMOVE_DIRECTORY)?;
+
     if let Kind::Dir {
         parent,
         path: child_path,
@@ -73,9 +73,13 @@ pub fn path_rename_internal(
     }
 
     // this is to be sure the source file is fetch from filesystem if needed
+    let source_inode =
+        wasi_try_ok!(state
+            .fs
+            .get_inode_at_path(inodes, source_fd, source_path, true));
     wasi_try_ok!(state
         .fs
-        .get_inode_at_path(inodes, source_fd, source_path, true));
+        .check_preopen_rights(&source_inode, Rights::PATH_RENAME_SOURCE));
     // Create the destination inode if the file exists.
     let _ = state
         .fs
@@ -92,6 +96,9 @@ pub fn path_rename_internal(
         Path::new(target_path),
         true
     ));
+    wasi_try_ok!(state
+        .fs
+        .check_preopen_rights(&target_parent_inode, Rights::PATH_RENAME_TARGET));
     let mut need_create = true;
     let host_adjusted_target_path = {
         let guard = target_parent_inode.read();
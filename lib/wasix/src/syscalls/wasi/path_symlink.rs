@@ -85,6 +85,9 @@ pub fn path_symlink_internal(
         state
             .fs
             .get_parent_inode_at_path(inodes, fd, new_path_path, true)?;
+    state
+        .fs
+        .check_preopen_rights(&target_parent_inode, Rights::PATH_SYMLINK)?;
 
     // short circuit if anything is wrong, before we create an inode
     {
@@ -222,122 +222,93 @@ where
     let tid = ctx.data().tid();
     let subs_len = subs.len();
 
-    // Determine if we are in silent polling mode
-    let mut env = ctx.data();
-    let state = ctx.data().state.deref();
-    let memory = unsafe { env.memory_view(&ctx) };
+    // The clocks (timeouts) that are subscribed to along with how long it
+    // takes (from now) for them to expire
+    let mut clock_subs: Vec<(SubscriptionClock, Userdata, Duration)> =
+        Vec::with_capacity(subs.len());
 
-    // These are used when we capture what clocks (timeouts) are being
-    // subscribed too
-    let clock_cnt = subs
-        .iter()
-        .filter(|a| a.2.type_ == Eventtype::Clock)
-        .count();
-    let mut clock_subs: Vec<(SubscriptionClock, u64)> = Vec::with_capacity(subs.len());
-    let mut time_to_sleep = Duration::MAX;
+    // Subscriptions that can not be polled are reported back to the
+    // caller as events carrying the error
+    let mut failed_evts = Vec::new();
 
     // First we extract all the subscriptions into an array so that they
     // can be processed
     let mut env = ctx.data();
     let state = ctx.data().state.deref();
-    let mut memory = unsafe { env.memory_view(&ctx) };
+    let started = wasi_try_ok!(platform_clock_time_get(Snapshot0Clockid::Monotonic, 1));
     for (fd, peb, s) in subs.iter_mut() {
-        let fd = match s.type_ {
-            Eventtype::FdRead => {
-                let file_descriptor = unsafe { s.data.fd_readwrite.file_descriptor };
-                match file_descriptor {
-                    __WASI_STDIN_FILENO | __WASI_STDOUT_FILENO | __WASI_STDERR_FILENO => (),
-                    fd => {
-                        let fd_entry = match state.fs.get_fd(fd) {
-                            Ok(a) => a,
-                            Err(err) => return Ok(err),
-                        };
-                        if !fd_entry.rights.contains(Rights::POLL_FD_READWRITE) {
-                            return Ok(Errno::Access);
-                        }
-                    }
-                }
-                *fd = Some(file_descriptor);
-                *peb |= (PollEvent::PollIn as PollEventSet);
-                file_descriptor
-            }
-            Eventtype::FdWrite => {
+        match s.type_ {
+            Eventtype::FdRead | Eventtype::FdWrite => {
                 let file_descriptor = unsafe { s.data.fd_readwrite.file_descriptor };
-                match file_descriptor {
-                    __WASI_STDIN_FILENO | __WASI_STDOUT_FILENO | __WASI_STDERR_FILENO => (),
-                    fd => {
-                        let fd_entry = match state.fs.get_fd(fd) {
-                            Ok(a) => a,
-                            Err(err) => return Ok(err),
-                        };
-                        if !fd_entry.rights.contains(Rights::POLL_FD_READWRITE) {
-                            return Ok(Errno::Access);
+                let res = match file_descriptor {
+                    __WASI_STDIN_FILENO | __WASI_STDOUT_FILENO | __WASI_STDERR_FILENO => Ok(()),
+                    fd => state.fs.get_fd(fd).and_then(|fd_entry| {
+                        if fd_entry.rights.contains(Rights::POLL_FD_READWRITE) {
+                            Ok(())
+                        } else {
+                            Err(Errno::Access)
                         }
-                    }
+                    }),
+                };
+                if let Err(err) = res {
+                    failed_evts.push(failed_event(s, err));
+                    continue;
                 }
+
                 *fd = Some(file_descriptor);
-                *peb |= (PollEvent::PollOut as PollEventSet);
-                file_descriptor
+                *peb |= match s.type_ {
+                    Eventtype::FdRead => PollEvent::PollIn as PollEventSet,
+                    _ => PollEvent::PollOut as PollEventSet,
+                };
             }
             Eventtype::Clock => {
                 let clock_info = unsafe { s.data.clock };
-                if clock_info.clock_id == Clockid::Realtime
-                    || clock_info.clock_id == Clockid::Monotonic
+                if clock_info.clock_id != Clockid::Realtime
+                    && clock_info.clock_id != Clockid::Monotonic
                 {
-                    // Ignore duplicates
-                    if clock_subs
-                        .iter()
-                        .any(|c| c.0.clock_id == clock_info.clock_id && c.1 == s.userdata)
-                    {
-                        continue;
-                    }
-
-                    // If the timeout duration is zero then this is an immediate check rather than
-                    // a sleep itself
-                    if clock_info.timeout == 0 {
-                        time_to_sleep = Duration::MAX;
-                    } else if clock_info.timeout == 1 {
-                        time_to_sleep = Duration::ZERO;
-                        clock_subs.push((clock_info, s.userdata));
-                    } else {
-                        // if the timeout is specified as an absolute time in the future,
-                        // we should calculate the duration we need to sleep
-                        time_to_sleep = if clock_info
-                            .flags
-                            .contains(Subclockflags::SUBSCRIPTION_CLOCK_ABSTIME)
-                        {
-                            let now = wasi_try_ok!(platform_clock_time_get(
-                                Snapshot0Clockid::Monotonic,
-                                1
-                            )) as u64;
-
-                            Duration::from_nanos(clock_info.timeout)
-                                - Duration::from_nanos(now as u64)
-                        } else {
-                            // if the timeout is not absolute, just use it as duration
-                            Duration::from_nanos(clock_info.timeout)
-                        };
+                    tracing::debug!(
+                        clock_id = clock_info.clock_id as u32,
+                        "polling not implemented for this clock"
+                    );
+                    failed_evts.push(failed_event(s, Errno::Inval));
+                    continue;
+                }
 
-                        clock_subs.push((clock_info, s.userdata));
-                    }
+                // Ignore duplicates
+                if clock_subs
+                    .iter()
+                    .any(|c| c.0.clock_id == clock_info.clock_id && c.1 == s.userdata)
+                {
                     continue;
-                } else {
-                    error!("polling not implemented for these clocks yet");
-                    return Ok(Errno::Inval);
                 }
+
+                let timeout = match clock_info.timeout {
+                    // A zero timeout means there is no timeout at all, while a
+                    // timeout of one is a non-blocking check of the other
+                    // subscriptions
+                    0 => continue,
+                    1 => Duration::ZERO,
+                    // If the timeout is specified as an absolute time we
+                    // need to calculate how long it is until that time
+                    timeout
+                        if clock_info
+                            .flags
+                            .contains(Subclockflags::SUBSCRIPTION_CLOCK_ABSTIME) =>
+                    {
+                        let now = wasi_try_ok!(state.clock_time_get(clock_info.clock_id.into(), 1));
+                        Duration::from_nanos(timeout.saturating_sub(now as u64))
+                    }
+                    timeout => Duration::from_nanos(timeout),
+                };
+                clock_subs.push((clock_info, s.userdata, timeout));
             }
-            Eventtype::Unknown => {
-                continue;
-            }
-        };
+            Eventtype::Unknown => {}
+        }
     }
 
-    let mut events_seen: u32 = 0;
-
     let batch = {
         // Build the batch of things we are going to poll
         let state = ctx.data().state.clone();
-        let tasks = ctx.data().tasks().clone();
         let mut guards = {
             // We start by building a list of files we are going to poll
             // and open a read lock on them all
@@ -346,8 +317,10 @@ where
             #[allow(clippy::significant_drop_in_scrutinee)]
             for (fd, peb, s) in subs {
                 if let Some(fd) = fd {
-                    let wasi_file_ref = wasi_try_ok!(poll_fd_guard(&state, peb, fd, s));
-                    fd_guards.push(wasi_file_ref);
+                    match poll_fd_guard(&state, peb, fd, s) {
+                        Ok(guard) => fd_guards.push(guard),
+                        Err(err) => failed_evts.push(failed_event(&s, err)),
+                    }
                 }
             }
 
@@ -365,37 +338,52 @@ where
         PollBatch::new(pid, tid, guards)
     };
 
+    // We sleep until the earliest clock expires, or not at all if some of
+    // the subscriptions already failed
+    let time_to_sleep = if failed_evts.is_empty() {
+        clock_subs.iter().map(|c| c.2).min()
+    } else {
+        Some(Duration::ZERO)
+    };
+
     // If the time is infinite then we omit the time_to_sleep parameter
     let timeout = match time_to_sleep {
-        Duration::ZERO => {
+        Some(Duration::ZERO) => {
             Span::current().record("timeout_ns", "nonblocking");
             Some(Duration::ZERO)
         }
-        Duration::MAX => {
+        None => {
             Span::current().record("timeout_ns", "infinite");
             None
         }
-        time => {
-            Span::current().record("timeout_ns", time.as_millis());
+        Some(time) => {
+            Span::current().record("timeout_ns", time.as_nanos());
             Some(time)
         }
     };
 
-    // Function to process a timeout
-    let process_timeout = {
+    // Returns the events of the clocks that have expired, after the timeout
+    // has triggered the earliest clock is considered expired even if we
+    // are unable to tell how much time has passed (e.g. after a deep sleep)
+    let expired_clocks = {
         let clock_subs = clock_subs.clone();
-        |ctx: &FunctionEnvMut<'a, WasiEnv>| {
-            // The timeout has triggered so lets add that event
-            if clock_subs.is_empty() {
-                tracing::warn!("triggered_timeout (without any clock subscriptions)",);
+        move |timed_out: bool| {
+            let now = platform_clock_time_get(Snapshot0Clockid::Monotonic, 1).unwrap_or(started);
+            let mut elapsed = Duration::from_nanos(now.saturating_sub(started) as u64);
+            if timed_out {
+                elapsed = elapsed.max(time_to_sleep.unwrap_or_default());
             }
+
             let mut evts = Vec::new();
-            for (clock_info, userdata) in clock_subs {
+            for (clock_info, userdata, timeout) in clock_subs.iter() {
+                if *timeout > elapsed {
+                    continue;
+                }
                 let evt = Event {
-                    userdata,
+                    userdata: *userdata,
                     error: Errno::Success,
                     type_: Eventtype::Clock,
-                    u: EventUnion { clock: 0 },
+                    u: empty_event_union(),
                 };
                 Span::current().record(
                     "seen",
@@ -410,6 +398,17 @@ where
         }
     };
 
+    // Function to process a timeout
+    let process_timeout = |ctx: &FunctionEnvMut<'a, WasiEnv>| {
+        // The timeout has triggered so lets add that event
+        if clock_subs.is_empty() && failed_evts.is_empty() {
+            tracing::warn!("triggered_timeout (without any clock subscriptions)",);
+        }
+        let mut evts = failed_evts.clone();
+        evts.extend(expired_clocks(true));
+        evts
+    };
+
     #[cfg(feature = "sys")]
     if env.capabilities.threading.enable_blocking_sleep && subs_len == 1 {
         // Here, `poll_oneoff` is merely in a sleeping state
@@ -425,9 +424,10 @@ where
     }
 
     let tasks = env.tasks().clone();
+    let spin_threshold = env.capabilities.threading.enable_precise_timers;
     let timeout = async move {
         if let Some(timeout) = timeout {
-            tasks.sleep_now(timeout).await;
+            sleep_precise(tasks, timeout, spin_threshold).await;
         } else {
             InfiniteSleep::default().await
         }
@@ -436,6 +436,7 @@ where
     // Build the trigger using the timeout
     let trigger = async move {
         tokio::select! {
+            biased;
             res = batch => res,
             _ = timeout => Err(Errno::Timedout)
         }
@@ -444,11 +445,10 @@ where
     // We replace the process events callback with another callback
     // which will interpret the error codes
     let process_events = {
-        let clock_subs = clock_subs.clone();
         |ctx: &FunctionEnvMut<'a, WasiEnv>, events: Result<Vec<Event>, Errno>| {
             // Process the result
             match events {
-                Ok(evts) => {
+                Ok(mut evts) => {
                     // If its a timeout then return an event for it
                     if evts.len() == 1 {
                         Span::current().record("seen", &format!("{:?}", evts.first().unwrap()));
@@ -456,6 +456,11 @@ where
                         Span::current().record("seen", &format!("trigger_cnt=({})", evts.len()));
                     }
 
+                    // Any clocks that expired in the meantime are reported
+                    // along with the file descriptors
+                    evts.extend(failed_evts.iter().cloned());
+                    evts.extend(expired_clocks(false));
+
                     // Process the events
                     process_events(ctx, evts)
                 }
@@ -489,3 +494,24 @@ where
     }
     Ok(Errno::Success)
 }
+
+/// Event contents that are fully zeroed, so that no uninitialized bytes are
+/// written to the guest for events that carry no data (e.g. clocks)
+fn empty_event_union() -> EventUnion {
+    EventUnion {
+        fd_readwrite: EventFdReadwrite {
+            nbytes: 0,
+            flags: Eventrwflags::empty(),
+        },
+    }
+}
+
+/// Builds the event that reports a subscription which could not be polled
+fn failed_event(s: &Subscription, error: Errno) -> Event {
+    Event {
+        userdata: s.userdata,
+        error,
+        type_: s.type_,
+        u: empty_event_union(),
+    }
+}
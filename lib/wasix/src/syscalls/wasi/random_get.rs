@@ -1,4 +1,6 @@
 use super::*;
+use rand::RngCore;
+
 use crate::syscalls::*;
 
 /// ### `random_get()`
@@ -18,7 +20,13 @@ pub fn random_get<M: MemorySize>(
     let memory = unsafe { env.memory_view(&ctx) };
     let buf_len64: u64 = buf_len.into();
     let mut u8_buffer = vec![0; buf_len64 as usize];
-    let res = getrandom::getrandom(&mut u8_buffer);
+    let res = match &env.state.rng {
+        Some(rng) => {
+            rng.lock().unwrap().fill_bytes(&mut u8_buffer);
+            Ok(())
+        }
+        None => getrandom::getrandom(&mut u8_buffer),
+    };
     match res {
         Ok(()) => {
             let buf = wasi_try_mem!(buf.slice(&memory, buf_len));
@@ -1,16 +1,20 @@
 use super::*;
 use crate::syscalls::*;
 
-use wasmer::Memory;
-use wasmer_wasix_types::wasi::ThreadStart;
+use crate::os::task::thread::WasiMemoryLayout;
 
 /// ### `thread_spawn()`
-/// Creates a new thread by spawning that shares the same
-/// memory address space, file handles and main event loops.
+/// Creates a new thread, as defined by the wasi-threads proposal, that
+/// shares the same memory address space, file handles and main event
+/// loops.
+///
+/// The new thread runs the `wasi_thread_start` function exported by the
+/// program, with its thread ID and `start_arg` as arguments. The stack of
+/// the thread is allocated by the program itself.
 ///
 /// ## Parameters
 ///
-/// * `start_ptr` - Pointer to the structure that describes the thread to be launched
+/// * `start_arg` - Opaque value passed to `wasi_thread_start`
 ///
 /// ## Return
 ///
@@ -19,9 +23,9 @@ use wasmer_wasix_types::wasi::ThreadStart;
 #[instrument(level = "trace", skip_all, ret)]
 pub fn thread_spawn<M: MemorySize>(
     mut ctx: FunctionEnvMut<'_, WasiEnv>,
-    start_ptr: WasmPtr<ThreadStart<M>, M>,
+    start_arg: M::Offset,
 ) -> i32 {
-    thread_spawn_internal_from_wasi(&mut ctx, start_ptr)
+    thread_spawn_internal_with_layout::<M>(&mut ctx, WasiMemoryLayout::default(), start_arg)
         .map(|tid| tid as i32)
         .map_err(|errno| errno as i32)
         .unwrap_or_else(|err| -err)
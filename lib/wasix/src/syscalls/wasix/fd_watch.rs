@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use virtual_fs::{Pipe, PipeRx, PipeTx};
+
+use super::*;
+use crate::{fs::spawn_watch, syscalls::*};
+
+/// ### `fd_watch()`
+/// Watches a file or directory for changes. Fails with `Errno::Nospc` when
+/// too many watches are active.
+/// Inputs:
+/// - `Fd fd`
+///     The directory that `path` is relative to
+/// - `const char *path`
+///     String containing the path to watch
+/// - `u32 path_len`
+///     The length of the `path` string
+/// Output:
+/// - `Fd ret_fd`
+///     A file handle that becomes readable whenever an entry is created,
+///     modified or deleted (see `WatchEventType` for the encoding)
+#[instrument(level = "trace", skip_all, fields(%fd, path = field::Empty, ret_fd = field::Empty), ret)]
+pub fn fd_watch<M: MemorySize>(
+    mut ctx: FunctionEnvMut<'_, WasiEnv>,
+    fd: WasiFd,
+    path: WasmPtr<u8, M>,
+    path_len: M::Offset,
+    ret_fd: WasmPtr<WasiFd, M>,
+) -> Result<Errno, WasiError> {
+    let env = ctx.data();
+    let (memory, _) = unsafe { env.get_memory_and_wasi_state(&ctx, 0) };
+    let mut path_string = unsafe { get_input_str_ok!(&memory, path, path_len) };
+
+    // Convert relative paths into absolute paths
+    if path_string.starts_with("./") {
+        path_string = ctx.data().state.fs.relative_path_to_absolute(path_string);
+    }
+    Span::current().record("path", path_string.as_str());
+
+    let fd = wasi_try_ok!(fd_watch_internal(&mut ctx, fd, &path_string));
+
+    let env = ctx.data();
+    let (memory, _) = unsafe { env.get_memory_and_wasi_state(&ctx, 0) };
+    Span::current().record("ret_fd", fd);
+    wasi_try_mem_ok!(ret_fd.write(&memory, fd));
+
+    Ok(Errno::Success)
+}
+
+pub(crate) fn fd_watch_internal(
+    ctx: &mut FunctionEnvMut<'_, WasiEnv>,
+    fd: WasiFd,
+    path: &str,
+) -> Result<WasiFd, Errno> {
+    let env = ctx.data();
+    let (_, state, inodes) = unsafe { env.get_memory_and_wasi_state_and_inodes(&ctx, 0) };
+
+    let dir = state.fs.get_fd(fd)?;
+    if !dir.rights.contains(Rights::PATH_FILESTAT_GET) {
+        return Err(Errno::Access);
+    }
+    let inode = state.fs.get_inode_at_path(inodes, fd, path, true)?;
+    let host_path = match inode.read().deref() {
+        Kind::Dir { path, .. } | Kind::File { path, .. } => path.clone(),
+        Kind::Root { .. } => PathBuf::from("/"),
+        _ => return Err(Errno::Inval),
+    };
+
+    let (events, guest) = Pipe::channel();
+    let events: PipeTx = events.into();
+    let reader = PipeRx::from(guest.clone()).downgrade();
+    spawn_watch(
+        env.tasks(),
+        state.fs.root_fs.clone(),
+        host_path,
+        events,
+        reader,
+        state.fs.quota.clone(),
+    )?;
+
+    let inode = state.fs.create_inode_with_default_stat(
+        inodes,
+        Kind::Pipe { pipe: guest },
+        false,
+        "watch".to_string().into(),
+    );
+    let rights = Rights::FD_READ | Rights::POLL_FD_READWRITE | Rights::FD_FDSTAT_SET_FLAGS;
+    state
+        .fs
+        .create_fd(rights, rights, Fdflags::empty(), 0, inode)
+}
@@ -4,6 +4,7 @@ mod epoll_create;
 mod epoll_ctl;
 mod epoll_wait;
 mod fd_pipe;
+mod fd_watch;
 mod futex_wait;
 mod futex_wake;
 mod futex_wake_all;
@@ -73,6 +74,7 @@ pub use epoll_create::*;
 pub use epoll_ctl::*;
 pub use epoll_wait::*;
 pub use fd_pipe::*;
+pub use fd_watch::*;
 pub use futex_wait::*;
 pub use futex_wake::*;
 pub use futex_wake_all::*;
@@ -74,6 +74,7 @@ pub(super) fn sock_recv_internal_handler<M: MemorySize>(
             0
         }
     };
+    ctx.data().state.usage.add_bytes_read(bytes_read);
     Span::current().record("nread", bytes_read);
 
     let env = ctx.data();
@@ -132,6 +132,7 @@ pub(super) fn sock_recv_from_internal<M: MemorySize>(
             }
         }
     };
+    env.state.usage.add_bytes_read(bytes_read);
     Span::current()
         .record("nread", bytes_read)
         .record("peer", &format!("{:?}", peer));
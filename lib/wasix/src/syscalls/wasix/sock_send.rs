@@ -74,6 +74,7 @@ pub fn sock_send<M: MemorySize>(
             })?;
     }
 
+    ctx.data().state.usage.add_bytes_written(bytes_written);
     Span::current().record("nsent", bytes_written);
 
     let env = ctx.data();
@@ -40,8 +40,9 @@ pub(crate) fn thread_sleep_internal<M: MemorySize + 'static>(
     if duration > 0 {
         let duration = Duration::from_nanos(duration);
         let tasks = env.tasks().clone();
+        let spin_threshold = env.capabilities.threading.enable_precise_timers;
         let res = __asyncify_with_deep_sleep::<M, _, _>(ctx, async move {
-            tasks.sleep_now(duration).await;
+            sleep_precise(tasks, duration, spin_threshold).await;
         })?;
     }
     Ok(Errno::Success)
@@ -59,9 +59,6 @@ pub fn thread_spawn_internal_from_wasi<M: MemorySize>(
     // Now we use the environment and memory references
     let env = ctx.data();
     let memory = unsafe { env.memory_view(&ctx) };
-    let runtime = env.runtime.clone();
-    let tasks = env.tasks().clone();
-    let start_ptr_offset = start_ptr.offset();
 
     // Read the properties about the stack which we will use for asyncify
     let layout = {
@@ -79,6 +76,17 @@ pub fn thread_spawn_internal_from_wasi<M: MemorySize>(
             stack_size,
         }
     };
+    thread_spawn_internal_with_layout::<M>(ctx, layout, start_ptr.offset())
+}
+
+/// Creates a new thread running `wasi_thread_start` with `start_ptr_offset`
+/// as its argument, using the given memory layout for its stack.
+pub fn thread_spawn_internal_with_layout<M: MemorySize>(
+    ctx: &mut FunctionEnvMut<'_, WasiEnv>,
+    layout: WasiMemoryLayout,
+    start_ptr_offset: M::Offset,
+) -> Result<Tid, Errno> {
+    let env = ctx.data();
     tracing::trace!(
         from_tid = env.thread.id().raw(),
         "thread_spawn with layout {:?}",
@@ -131,7 +139,10 @@ pub fn thread_spawn_internal_using_layout<M: MemorySize>(
     // TODO: Currently asynchronous threading does not work with multi
     //       threading in JS but it does work for the main thread. This will
     //       require more work to find out why.
-    thread_env.enable_deep_sleep = if cfg!(feature = "js") {
+    //
+    //       The stacks of the threads spawned through wasi-threads are
+    //       managed by the program, so they can't be unwound.
+    thread_env.enable_deep_sleep = if cfg!(feature = "js") || thread_env.layout.stack_size == 0 {
         false
     } else {
         unsafe { env.capable_of_deep_sleep() }
@@ -44,6 +44,25 @@ pub fn is_wasix_module(module: &Module) -> bool {
     }
 }
 
+/// Returns true if the module imports WASI preview2 interfaces (e.g.
+/// `wasi:cli/stdout@0.2.0`).
+///
+/// Those interfaces are defined in WIT for the component model, which isn't
+/// supported: their resources, lists and results only exist through the
+/// canonical ABI of components, so these modules can't be run.
+pub fn is_wasi_preview2_module(module: &Module) -> bool {
+    module
+        .imports()
+        .any(|import| import.module().starts_with(PREVIEW2_NAMESPACE_PREFIX))
+}
+
+/// Returns true if `bytes` start with the header of a WebAssembly component
+/// rather than a core module. Components are how WASI preview2 programs are
+/// distributed, and they can't be run either.
+pub fn is_component(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\0asm") && bytes.get(6..8) == Some(&[0x01, 0x00][..])
+}
+
 pub fn map_io_err(err: std::io::Error) -> Errno {
     From::<std::io::Error>::from(err)
 }
@@ -155,6 +174,9 @@ const WASIX_64V1_NAMESPACE: &str = "wasix_64v1";
 /// Namespace for the `wasix` version.
 const WASIX_HTTP_V1_NAMESPACE: &str = "wasix_http_client_v1";
 
+/// Prefix of the namespaces of the WASI preview2 interfaces.
+const PREVIEW2_NAMESPACE_PREFIX: &str = "wasi:";
+
 /// Detect the version of WASI being used based on the import
 /// namespaces.
 ///
@@ -258,6 +280,27 @@ mod test {
         assert!(WasiVersion::Wasix64v1 != WasiVersion::Latest);
     }
 
+    #[test]
+    fn preview2_modules_and_components_are_detected() {
+        let store = wasmer::Store::default();
+        let preview2 = Module::new(
+            &store,
+            r#"(module (import "wasi:cli/environment@0.2.0" "get-arguments" (func (param i32))))"#,
+        )
+        .unwrap();
+        assert!(is_wasi_preview2_module(&preview2));
+        let preview1 = Module::new(
+            &store,
+            r#"(module (import "wasi_snapshot_preview1" "proc_exit" (func (param i32))))"#,
+        )
+        .unwrap();
+        assert!(!is_wasi_preview2_module(&preview1));
+
+        assert!(is_component(b"\0asm\x0d\x00\x01\x00"));
+        assert!(!is_component(b"\0asm\x01\x00\x00\x00"));
+        assert!(!is_component(b"\0as"));
+    }
+
     #[test]
     fn wasi_version_ordering() {
         assert!(WasiVersion::Snapshot0 <= WasiVersion::Snapshot0);
@@ -0,0 +1,68 @@
+#![cfg(not(feature = "js"))]
+
+mod common;
+
+use common::Guest;
+use virtual_fs::{AsyncWriteExt, Pipe};
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_read"
+        (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    ;; Reads up to 32 bytes from stdin to address 128 and writes them back
+    ;; to stdout, using an iovec at address 0.
+    (func (export "echo") (result i32)
+        (local $errno i32)
+        (i32.store (i32.const 0) (i32.const 128))
+        (i32.store (i32.const 4) (i32.const 32))
+        (local.set $errno
+            (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (if (local.get $errno) (then (return (local.get $errno))))
+        (i32.store (i32.const 4) (i32.load (i32.const 8)))
+        (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+)
+"#;
+
+impl Guest {
+    async fn echo(&mut self) -> Errno {
+        let f = self.instance.exports.get_function("echo").unwrap();
+        let ret = f.call_async(&mut self.store, &[]).await.unwrap();
+        Errno::try_from(ret[0].unwrap_i32() as u16).unwrap()
+    }
+}
+
+// A single threaded runtime deadlocks if `fd_read` blocks the thread while
+// waiting for the input.
+#[tokio::test(flavor = "current_thread")]
+async fn reads_yield_to_the_runtime() {
+    let (mut stdin_tx, stdin_rx) = Pipe::channel();
+    let mut builder = WasiEnv::builder("async-io").stdin(Box::new(stdin_rx));
+    let stdout = builder.capture_stdout(1024);
+    let mut guest = Guest::new(MODULE, builder.async_io(true));
+
+    let writer = tokio::spawn(async move {
+        tokio::task::yield_now().await;
+        stdin_tx.write_all(b"ping").await.unwrap();
+        stdin_tx
+    });
+
+    assert_eq!(guest.echo().await, Errno::Success);
+    assert_eq!(stdout.take_output(), b"ping");
+    writer.await.unwrap();
+}
+
+#[tokio::test]
+async fn synchronous_calls_trap() {
+    let (_stdin_tx, stdin_rx) = Pipe::channel();
+    let builder = WasiEnv::builder("async-io").stdin(Box::new(stdin_rx));
+    let mut guest = Guest::new(MODULE, builder.async_io(true));
+
+    let echo = guest.instance.exports.get_function("echo").unwrap();
+    let err = echo.call(&mut guest.store, &[]).unwrap_err();
+    assert!(err.message().contains("call_async"), "{err}");
+}
@@ -0,0 +1,103 @@
+#![cfg(not(feature = "js"))]
+
+mod common;
+
+use common::{Guest, GuestBuilder};
+use virtual_fs::FileSystem;
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, WasiEnv};
+
+// The root fd is 3, so the preopens get 4 and 5.
+const CASE_INSENSITIVE_FD: i32 = 4;
+const CASE_SENSITIVE_FD: i32 = 5;
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_unlink_file"
+        (func $path_unlink_file (param i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    (func (export "open") (param $fd i32) (param $path i32) (param $len i32) (result i32)
+        (call $path_open (local.get $fd) (i32.const 0) (local.get $path) (local.get $len)
+            (i32.const 0) (i64.const -1) (i64.const -1) (i32.const 0) (i32.const 0)))
+    (func (export "unlink") (param i32 i32 i32) (result i32)
+        (call $path_unlink_file (local.get 0) (local.get 1) (local.get 2)))
+)
+"#;
+
+/// A guest with a case insensitive preopen, `win`, and a case sensitive one,
+/// `posix`, both holding a `ReadMe.TXT`.
+fn fixture() -> GuestBuilder {
+    let builder = WasiEnv::builder("case-insensitive")
+        .preopen_build(|p| {
+            p.directory("/win")
+                .alias("win")
+                .read(true)
+                .write(true)
+                .create(true)
+                .case_insensitive(true)
+        })
+        .unwrap()
+        .preopen_build(|p| {
+            p.directory("/posix")
+                .alias("posix")
+                .read(true)
+                .write(true)
+                .create(true)
+        })
+        .unwrap();
+    Guest::builder(MODULE, builder)
+        .dirs(&["/win", "/win/Sub", "/posix"])
+        .files(&["/win/Sub/ReadMe.TXT", "/posix/ReadMe.TXT"])
+}
+
+impl Guest {
+    fn path_call(&mut self, name: &str, fd: i32, path: &str) -> Errno {
+        self.write_bytes(64, path.as_bytes());
+        self.call(name, &[fd.into(), 64.into(), (path.len() as i32).into()])
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn paths_differing_by_case_are_resolved() {
+    let (mut guest, _) = fixture().build();
+
+    assert_eq!(
+        guest.path_call("open", CASE_INSENSITIVE_FD, "Sub/ReadMe.TXT"),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.path_call("open", CASE_INSENSITIVE_FD, "sub/readme.txt"),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.path_call("open", CASE_INSENSITIVE_FD, "SUB/missing.txt"),
+        Errno::Noent
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn other_preopens_are_case_sensitive() {
+    let (mut guest, _) = fixture().build();
+
+    assert_eq!(
+        guest.path_call("open", CASE_SENSITIVE_FD, "ReadMe.TXT"),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.path_call("open", CASE_SENSITIVE_FD, "readme.txt"),
+        Errno::Noent
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn entries_are_removed_by_their_actual_name() {
+    let (mut guest, fs) = fixture().build();
+
+    assert_eq!(
+        guest.path_call("unlink", CASE_INSENSITIVE_FD, "SUB/README.TXT"),
+        Errno::Success
+    );
+    assert!(fs.metadata("/win/Sub/ReadMe.TXT".as_ref()).is_err());
+}
@@ -0,0 +1,104 @@
+//! The fixture of the tests calling the functions exported by a WASI module
+//! one at a time.
+
+// Each test only uses some of the helpers.
+#![allow(dead_code)]
+
+use virtual_fs::{mem_fs, FileSystem};
+use wasmer::{Instance, Module, Store, Value};
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, WasiEnvBuilder, WasiFunctionEnv};
+
+/// A module instantiated with a WASI environment.
+pub struct Guest {
+    pub store: Store,
+    pub instance: Instance,
+    pub env: WasiFunctionEnv,
+}
+
+impl Guest {
+    /// Instantiates a module written in WAT with the environment of
+    /// `builder`.
+    pub fn new(wat: &str, builder: WasiEnvBuilder) -> Self {
+        let mut store = Store::default();
+        let module = Module::new(&store, wat).unwrap();
+        let (instance, env) = builder.instantiate(module, &mut store).unwrap();
+        Guest {
+            store,
+            instance,
+            env,
+        }
+    }
+
+    /// Starts building a guest whose environment, taken from `builder`, is
+    /// backed by an in-memory file system.
+    pub fn builder(wat: &'static str, builder: WasiEnvBuilder) -> GuestBuilder {
+        let fs = mem_fs::FileSystem::default();
+        GuestBuilder {
+            wat,
+            builder: builder.fs(Box::new(fs.clone())),
+            fs,
+        }
+    }
+
+    /// Calls an exported function returning an errno.
+    pub fn call(&mut self, name: &str, args: &[Value]) -> Errno {
+        let f = self.instance.exports.get_function(name).unwrap();
+        let ret = f.call(&mut self.store, args).unwrap();
+        Errno::try_from(ret[0].unwrap_i32() as u16).unwrap()
+    }
+
+    pub fn read_bytes(&self, offset: u64, len: usize) -> Vec<u8> {
+        let memory = self.instance.exports.get_memory("memory").unwrap();
+        let mut buf = vec![0; len];
+        memory.view(&self.store).read(offset, &mut buf).unwrap();
+        buf
+    }
+
+    pub fn read_u32(&self, offset: u64) -> u32 {
+        u32::from_le_bytes(self.read_bytes(offset, 4).try_into().unwrap())
+    }
+
+    pub fn read_u64(&self, offset: u64) -> u64 {
+        u64::from_le_bytes(self.read_bytes(offset, 8).try_into().unwrap())
+    }
+
+    pub fn write_bytes(&self, offset: u64, bytes: &[u8]) {
+        let memory = self.instance.exports.get_memory("memory").unwrap();
+        memory.view(&self.store).write(offset, bytes).unwrap();
+    }
+}
+
+/// A [`Guest`] being set up, see [`Guest::builder`].
+pub struct GuestBuilder {
+    wat: &'static str,
+    builder: WasiEnvBuilder,
+    fs: mem_fs::FileSystem,
+}
+
+impl GuestBuilder {
+    /// Creates directories, parents before their children.
+    pub fn dirs(self, dirs: &[&str]) -> Self {
+        for dir in dirs {
+            self.fs.create_dir(dir.as_ref()).unwrap();
+        }
+        self
+    }
+
+    /// Creates empty files.
+    pub fn files(self, files: &[&str]) -> Self {
+        for file in files {
+            self.fs
+                .new_open_options()
+                .write(true)
+                .create(true)
+                .open(file)
+                .unwrap();
+        }
+        self
+    }
+
+    /// Instantiates the module, returning it with its file system.
+    pub fn build(self) -> (Guest, mem_fs::FileSystem) {
+        (Guest::new(self.wat, self.builder), self.fs)
+    }
+}
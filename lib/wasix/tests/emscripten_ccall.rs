@@ -0,0 +1,77 @@
+#![cfg(all(not(feature = "js"), feature = "webc_runner_rt_emscripten"))]
+
+use wasmer::{Module, Store, Value};
+use wasmer_wasix::runners::emscripten::{
+    invoke_emscripten_module, Ccall, CcallArg, CcallReturn, CcallValue,
+};
+
+const MODULE: &str = r#"
+(module
+    (import "env" "emscripten_resize_heap" (func (param i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 1 funcref)
+    (data (i32.const 100) "greeting\00")
+    (global $stack (mut i32) (i32.const 131072))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+
+    (func (export "__main_argc_argv") (param i32 i32) (result i32)
+        unreachable)
+
+    ;; The length of a C string, plus a number
+    (func (export "strlen_plus") (param $s i32) (param $n i32) (result i32)
+        (local $len i32)
+        (block $done
+            (loop $next
+                (br_if $done (i32.eqz (i32.load8_u (i32.add (local.get $s) (local.get $len)))))
+                (local.set $len (i32.add (local.get $len) (i32.const 1)))
+                (br $next)))
+        (i32.add (local.get $len) (local.get $n)))
+
+    (func (export "_greeting") (result i32)
+        (i32.const 100))
+)
+"#;
+
+fn invoke(call: &Ccall) -> CcallValue {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    invoke_emscripten_module(
+        &mut store,
+        &module,
+        "ccall",
+        call,
+        &[],
+        Default::default(),
+        None,
+    )
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn strings_are_passed_on_the_stack() {
+    let call = Ccall::new("strlen_plus")
+        .arg(CcallArg::String("Hello".to_string()))
+        .arg(CcallArg::Number(Value::I32(10)));
+
+    assert_eq!(
+        invoke(&call),
+        CcallValue::Number(vec![Value::I32(15)].into_boxed_slice())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn returned_strings_are_read() {
+    let call = Ccall::new("greeting").returns(CcallReturn::String);
+
+    assert_eq!(
+        invoke(&call),
+        CcallValue::String(Some("greeting".to_string()))
+    );
+}
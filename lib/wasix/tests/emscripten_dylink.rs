@@ -0,0 +1,196 @@
+#![cfg(all(not(feature = "js"), feature = "webc_runner_rt_emscripten"))]
+
+use wasmer::{Module, Store};
+use wasmer_wasix::runners::emscripten::run_emscripten_module;
+
+// A main module loading the side module whose path it receives as argument.
+// `_main` traps when one of its checks fails.
+const MAIN_MODULE: &str = r#"
+(module
+    (import "env" "dlopen" (func $dlopen (param i32 i32) (result i32)))
+    (import "env" "dlsym" (func $dlsym (param i32 i32) (result i32)))
+    (import "env" "dlerror" (func $dlerror (result i32)))
+    (import "env" "emscripten_resize_heap" (func (param i32) (result i32)))
+    (type $binary (func (param i32 i32) (result i32)))
+    (type $unary (func (param i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 1 funcref)
+    (data (i32.const 100) "side_add\00")
+    (data (i32.const 120) "side_value\00")
+    (data (i32.const 140) "call_main_double\00")
+    (data (i32.const 160) "missing\00")
+    (data (i32.const 180) "/definitely/missing.so\00")
+    (data (i32.const 1024) "\07\00\00\00")
+    (global $heap (mut i32) (i32.const 65536))
+    (global $stack (export "__stack_pointer") (mut i32) (i32.const 131072))
+    (global (export "main_data") i32 (i32.const 1024))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+
+    (func (export "malloc") (param $size i32) (result i32)
+        (global.get $heap)
+        (global.set $heap (i32.add (global.get $heap) (local.get $size))))
+
+    (func (export "main_double") (param i32) (result i32)
+        (i32.mul (local.get 0) (i32.const 2)))
+
+    (func (export "__main_argc_argv") (param $argc i32) (param $argv i32) (result i32)
+        (local $handle i32)
+        (local $func i32)
+
+        (local.set $handle
+            (call $dlopen (i32.load offset=4 (local.get $argv)) (i32.const 0x102)))
+        (if (i32.eqz (local.get $handle))
+            (then unreachable))
+
+        ;; Functions of the side module are added to the table, and its data
+        ;; is relocated
+        (local.set $func (call $dlsym (local.get $handle) (i32.const 100)))
+        (if (i32.ne (call_indirect (type $binary) (i32.const 2) (i32.const 3) (local.get $func))
+                (i32.const 45))
+            (then unreachable))
+        (if (i32.ne (i32.load (call $dlsym (local.get $handle) (i32.const 120))) (i32.const 40))
+            (then unreachable))
+
+        ;; The side module reaches the main module through its GOT
+        (local.set $func (call $dlsym (local.get $handle) (i32.const 140)))
+        (if (i32.ne (call_indirect (type $unary) (i32.const 21) (local.get $func)) (i32.const 49))
+            (then unreachable))
+
+        ;; Failures are reported by dlerror
+        (if (call $dlsym (local.get $handle) (i32.const 160))
+            (then unreachable))
+        (if (i32.eqz (call $dlerror))
+            (then unreachable))
+        (if (call $dlerror)
+            (then unreachable))
+        (if (call $dlopen (i32.const 180) (i32.const 0))
+            (then unreachable))
+        (if (i32.eqz (call $dlerror))
+            (then unreachable))
+
+        (i32.const 0))
+)
+"#;
+
+const SIDE_MODULE: &str = r#"
+(module
+    (@custom "dylink.0" "\01\04\04\02\01\00")
+    (import "env" "memory" (memory 1))
+    (import "env" "__indirect_function_table" (table 0 funcref))
+    (import "env" "__memory_base" (global $memory_base i32))
+    (import "env" "__table_base" (global $table_base i32))
+    (import "GOT.func" "main_double" (global $main_double (mut i32)))
+    (import "GOT.mem" "main_data" (global $main_data (mut i32)))
+    (type $unary (func (param i32) (result i32)))
+    (data (global.get $memory_base) "\28\00\00\00")
+    (elem (global.get $table_base) $side_add)
+    (global (export "side_value") i32 (i32.const 0))
+
+    (func $side_add (export "side_add") (param i32 i32) (result i32)
+        (i32.add
+            (i32.add (local.get 0) (local.get 1))
+            (i32.load (global.get $memory_base))))
+
+    (func (export "call_main_double") (param i32) (result i32)
+        (i32.add
+            (call_indirect (type $unary) (local.get 0) (global.get $main_double))
+            (i32.load (global.get $main_data))))
+)
+"#;
+
+// A main module whose `dlopen` of the library it receives as argument must
+// fail, with an error reported by `dlerror`
+const FAILING_MAIN_MODULE: &str = r#"
+(module
+    (import "env" "dlopen" (func $dlopen (param i32 i32) (result i32)))
+    (import "env" "dlerror" (func $dlerror (result i32)))
+    (import "env" "emscripten_resize_heap" (func (param i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 1 funcref)
+    (global $heap (mut i32) (i32.const 65536))
+    (global $stack (export "__stack_pointer") (mut i32) (i32.const 131072))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+
+    (func (export "malloc") (param $size i32) (result i32)
+        (global.get $heap)
+        (global.set $heap (i32.add (global.get $heap) (local.get $size))))
+
+    (func (export "__main_argc_argv") (param $argc i32) (param $argv i32) (result i32)
+        (if (call $dlopen (i32.load offset=4 (local.get $argv)) (i32.const 0x102))
+            (then unreachable))
+        (if (i32.eqz (call $dlerror))
+            (then unreachable))
+        (i32.const 0))
+)
+"#;
+
+// A side module needing itself
+const CYCLIC_SIDE_MODULE: &str = r#"
+(module
+    (@custom "dylink.0" "\01\04\00\00\00\00\02\0b\01\09cyclic.so")
+    (func (export "cyclic") (result i32)
+        (i32.const 1))
+)
+"#;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn side_modules_are_loaded_with_dlopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let side_path = dir.path().join("side.so");
+    std::fs::write(
+        &side_path,
+        wasmer::wat2wasm(SIDE_MODULE.as_bytes()).unwrap(),
+    )
+    .unwrap();
+
+    let mut store = Store::default();
+    let module = Module::new(&store, MAIN_MODULE).unwrap();
+    run_emscripten_module(
+        &mut store,
+        &module,
+        "main",
+        vec![side_path.to_str().unwrap().to_string()],
+        &[],
+        Default::default(),
+        None,
+    )
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cyclic_dependencies_fail_to_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let side_path = dir.path().join("cyclic.so");
+    std::fs::write(
+        &side_path,
+        wasmer::wat2wasm(CYCLIC_SIDE_MODULE.as_bytes()).unwrap(),
+    )
+    .unwrap();
+
+    let mut store = Store::default();
+    let module = Module::new(&store, FAILING_MAIN_MODULE).unwrap();
+    run_emscripten_module(
+        &mut store,
+        &module,
+        "main",
+        vec![side_path.to_str().unwrap().to_string()],
+        &[],
+        Default::default(),
+        None,
+    )
+    .unwrap();
+}
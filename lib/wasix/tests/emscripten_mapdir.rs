@@ -0,0 +1,111 @@
+#![cfg(all(unix, not(feature = "js"), feature = "webc_runner_rt_emscripten"))]
+
+use wasmer::{Module, Store};
+use wasmer_wasix::runners::emscripten::{run_emscripten_module, EmscriptenMount};
+
+// `_main` traps when one of its checks fails.
+const MODULE: &str = r#"
+(module
+    (import "env" "__syscall_openat" (func $openat (param i32 i32 i32 i32) (result i32)))
+    (import "env" "__syscall_mkdirat" (func $mkdirat (param i32 i32 i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 1 funcref)
+    (data (i32.const 100) "/data/ro/file.txt\00")
+    (data (i32.const 200) "/data/ro/new.txt\00")
+    (data (i32.const 300) "/data/created\00")
+    (data (i32.const 400) "/data/ro/created\00")
+    (data (i32.const 500) "/data/ro/../written.txt\00")
+    ;; The varargs of openat, with the mode 0644
+    (data (i32.const 600) "\a4\01\00\00")
+    (data (i32.const 700) "/data/link/new.txt\00")
+    (data (i32.const 800) "/data/ro\00")
+    (data (i32.const 900) "through-fd\00")
+    (global $stack (mut i32) (i32.const 131072))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+
+    (func (export "__main_argc_argv") (param i32 i32) (result i32)
+        (local $dir i32)
+        ;; The innermost mapped directory wins
+        (if (i32.lt_s (call $openat (i32.const -100) (i32.const 100) (i32.const 0) (i32.const 0))
+                (i32.const 0))
+            (then unreachable))
+
+        ;; Read-only directories can't be modified (65 is O_WRONLY | O_CREAT,
+        ;; -69 is EROFS)
+        (if (i32.ne (call $openat (i32.const -100) (i32.const 200) (i32.const 65) (i32.const 600))
+                (i32.const -69))
+            (then unreachable))
+        (if (i32.ne (call $mkdirat (i32.const -100) (i32.const 400) (i32.const 493))
+                (i32.const -69))
+            (then unreachable))
+
+        ;; Nor be reached through a symbolic link in another directory
+        (if (i32.ne (call $openat (i32.const -100) (i32.const 700) (i32.const 65) (i32.const 600))
+                (i32.const -69))
+            (then unreachable))
+
+        ;; Nor through a directory fd (65536 is O_DIRECTORY)
+        (local.set $dir (call $openat (i32.const -100) (i32.const 800) (i32.const 65536) (i32.const 0)))
+        (if (i32.lt_s (local.get $dir) (i32.const 0))
+            (then unreachable))
+        (if (i32.ne (call $mkdirat (local.get $dir) (i32.const 900) (i32.const 493))
+                (i32.const -69))
+            (then unreachable))
+
+        ;; The other directories can
+        (if (call $mkdirat (i32.const -100) (i32.const 300) (i32.const 493))
+            (then unreachable))
+        (if (i32.lt_s (call $openat (i32.const -100) (i32.const 500) (i32.const 65) (i32.const 600))
+                (i32.const 0))
+            (then unreachable))
+
+        (i32.const 0))
+)
+"#;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mapped_directories_are_honored() {
+    let data = tempfile::tempdir().unwrap();
+    let read_only = tempfile::tempdir().unwrap();
+    std::fs::write(read_only.path().join("file.txt"), "").unwrap();
+    std::os::unix::fs::symlink(read_only.path(), data.path().join("link")).unwrap();
+
+    let mounts = [
+        EmscriptenMount {
+            host: data.path().to_path_buf(),
+            guest: "/data".to_string(),
+            read_only: false,
+        },
+        EmscriptenMount {
+            host: read_only.path().to_path_buf(),
+            guest: "/data/ro".to_string(),
+            read_only: true,
+        },
+    ];
+
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    run_emscripten_module(
+        &mut store,
+        &module,
+        "mapdir",
+        Vec::new(),
+        &mounts,
+        Default::default(),
+        None,
+    )
+    .unwrap();
+
+    assert!(data.path().join("created").is_dir());
+    assert!(data.path().join("written.txt").is_file());
+    assert!(!read_only.path().join("new.txt").exists());
+    assert!(!read_only.path().join("created").exists());
+    assert!(!read_only.path().join("through-fd").exists());
+}
@@ -0,0 +1,155 @@
+#![cfg(all(not(feature = "js"), feature = "webc_runner_rt_emscripten"))]
+
+use wasmer::{Module, Store};
+use wasmer_wasix::{
+    runners::emscripten::{needs_wasi_env, run_emscripten_module},
+    WasiEnvBuilder,
+};
+
+// Recent versions of Emscripten define and export their memory and table,
+// and don't export `dynCall_*` functions anymore. `_main` traps when one of
+// its checks fails.
+const MODULE: &str = r#"
+(module
+    (import "env" "emscripten_resize_heap" (func $resize_heap (param i32) (result i32)))
+    (import "env" "__syscall_openat" (func $openat (param i32 i32 i32 i32) (result i32)))
+    (import "env" "__syscall_getcwd" (func $getcwd (param i32 i32) (result i32)))
+    (import "env" "invoke_vi" (func $invoke_vi (param i32 i32)))
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 2 funcref)
+    (elem (i32.const 1) $store_flag)
+    (data (i32.const 100) "/definitely/missing\00")
+    (global $stack (mut i32) (i32.const 131072))
+
+    (func $store_flag (param i32)
+        (i32.store (i32.const 0) (local.get 0)))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+    (func (export "setThrew") (param i32 i32))
+
+    (func (export "__main_argc_argv") (param $argc i32) (param $argv i32) (result i32)
+        (if (i32.ne (local.get $argc) (i32.const 2))
+            (then unreachable))
+
+        ;; Function pointers are called through the exported table
+        (call $invoke_vi (i32.const 1) (i32.const 42))
+        (if (i32.ne (i32.load (i32.const 0)) (i32.const 42))
+            (then unreachable))
+
+        ;; The exported memory grows
+        (if (i32.eqz (call $resize_heap (i32.const 196608)))
+            (then unreachable))
+        (if (i32.lt_u (memory.size) (i32.const 3))
+            (then unreachable))
+
+        ;; Syscalls return negated WASI errnos
+        (if (i32.ne (call $openat (i32.const -100) (i32.const 100) (i32.const 0) (i32.const 0))
+                (i32.const -44))
+            (then unreachable))
+        (if (i32.le_s (call $getcwd (i32.const 200) (i32.const 4096)) (i32.const 0))
+            (then unreachable))
+        (if (i32.ne (call $fd_write (i32.const 12345) (i32.const 0) (i32.const 0) (i32.const 300))
+                (i32.const 8))
+            (then unreachable))
+
+        (i32.const 0))
+)
+"#;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn modules_from_recent_emcc_versions_run() {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+
+    // The WASI functions are provided by a WASI environment
+    assert!(needs_wasi_env(&module));
+
+    run_emscripten_module(
+        &mut store,
+        &module,
+        "modern",
+        vec!["arg".to_string()],
+        &[],
+        Default::default(),
+        Some(WasiEnvBuilder::new("modern").args(["arg"])),
+    )
+    .unwrap();
+}
+
+// Files opened by the Emscripten syscalls are written, seeked and closed with
+// WASI functions, `fd_seek` takes its offset in two halves without
+// `-sWASM_BIGINT`.
+#[cfg(unix)]
+const FILES_MODULE: &str = r#"
+(module
+    (import "env" "__syscall_openat" (func $openat (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_seek" (func $fd_seek (param i32 i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_close" (func $fd_close (param i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 1 funcref)
+    (data (i32.const 100) "/dev/null\00")
+    (data (i32.const 200) "data")
+    (global $stack (mut i32) (i32.const 131072))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+
+    (func (export "__main_argc_argv") (param i32 i32) (result i32)
+        (local $fd i32)
+        ;; O_RDWR
+        (local.set $fd (call $openat (i32.const -100) (i32.const 100) (i32.const 2) (i32.const 0)))
+        (if (i32.lt_s (local.get $fd) (i32.const 0))
+            (then unreachable))
+
+        (i32.store (i32.const 300) (i32.const 200))
+        (i32.store (i32.const 304) (i32.const 4))
+        (if (i32.ne (call $fd_write (local.get $fd) (i32.const 300) (i32.const 1) (i32.const 308))
+                (i32.const 0))
+            (then unreachable))
+        (if (i32.ne (i32.load (i32.const 308)) (i32.const 4))
+            (then unreachable))
+
+        (if (i32.ne (call $fd_seek (local.get $fd) (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 312))
+                (i32.const 0))
+            (then unreachable))
+
+        (if (i32.ne (call $fd_close (local.get $fd)) (i32.const 0))
+            (then unreachable))
+        (if (i32.ne (call $fd_write (local.get $fd) (i32.const 300) (i32.const 1) (i32.const 308))
+                (i32.const 8))
+            (then unreachable))
+
+        (i32.const 0))
+)
+"#;
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn files_opened_by_emscripten_syscalls_are_shared_with_wasi() {
+    let mut store = Store::default();
+    let module = Module::new(&store, FILES_MODULE).unwrap();
+
+    run_emscripten_module(
+        &mut store,
+        &module,
+        "files",
+        Vec::new(),
+        &[],
+        Default::default(),
+        Some(WasiEnvBuilder::new("files")),
+    )
+    .unwrap();
+}
@@ -0,0 +1,70 @@
+#![cfg(all(not(feature = "js"), feature = "webc_runner_rt_emscripten"))]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use wasmer::{Module, Store};
+use wasmer_wasix::runners::emscripten::{run_emscripten_module, EmscriptenStdio};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "env" "emscripten_err" (func $emscripten_err (param i32)))
+    (import "env" "emscripten_resize_heap" (func (param i32) (result i32)))
+    (memory (export "memory") 2)
+    (table (export "__indirect_function_table") 1 funcref)
+    (data (i32.const 100) "Hello, ")
+    (data (i32.const 110) "World!\n")
+    (data (i32.const 120) "oops\00")
+    ;; Two iovecs, for "Hello, " and "World!\n"
+    (data (i32.const 200) "\64\00\00\00\07\00\00\00\6e\00\00\00\07\00\00\00")
+    (global $stack (mut i32) (i32.const 131072))
+
+    (func (export "_emscripten_stack_alloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+    (func (export "emscripten_stack_get_current") (result i32)
+        (global.get $stack))
+    (func (export "_emscripten_stack_restore") (param i32)
+        (global.set $stack (local.get 0)))
+
+    (func (export "__main_argc_argv") (param i32 i32) (result i32)
+        (if (call $fd_write (i32.const 1) (i32.const 200) (i32.const 2) (i32.const 300))
+            (then unreachable))
+        (if (i32.ne (i32.load (i32.const 300)) (i32.const 14))
+            (then unreachable))
+        (call $emscripten_err (i32.const 120))
+        (i32.const 0))
+)
+"#;
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn output_is_captured() {
+    let stdout = SharedBuffer::default();
+    let stderr = SharedBuffer::default();
+    let stdio = EmscriptenStdio::new()
+        .stdout_writer(stdout.clone())
+        .stderr_writer(stderr.clone());
+
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    run_emscripten_module(&mut store, &module, "stdio", Vec::new(), &[], stdio, None).unwrap();
+
+    assert_eq!(stdout.0.lock().unwrap().as_slice(), b"Hello, World!\n");
+    assert_eq!(stderr.0.lock().unwrap().as_slice(), b"oops\n");
+}
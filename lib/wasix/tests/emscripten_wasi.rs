@@ -0,0 +1,82 @@
+#![cfg(all(not(feature = "js"), feature = "webc_runner_rt_emscripten"))]
+
+use virtual_fs::AsyncReadExt;
+use wasmer::{Module, Store};
+use wasmer_wasix::{runners::emscripten::run_emscripten_module, Pipe, WasiEnvBuilder, WasiError};
+
+// Emscripten modules import their memory and table from `env`
+const MODULE: &str = r#"
+(module
+    (import "env" "memory" (memory 256))
+    (import "env" "table" (table 0 funcref))
+    (import "env" "emscripten_memcpy_big" (func (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+    (data (i32.const 8000000) "hello from emscripten\n")
+    (global $stack (mut i32) (i32.const 9000000))
+
+    ;; Used to pass the arguments to main
+    (func (export "stackAlloc") (param $size i32) (result i32)
+        (global.set $stack (i32.sub (global.get $stack) (local.get $size)))
+        (global.get $stack))
+
+
+    (func (export "_main") (param $argc i32) (param $argv i32) (result i32)
+        (i32.store (i32.const 8000100) (i32.const 8000000))
+        (i32.store (i32.const 8000104) (i32.const 22))
+        (drop (call $fd_write (i32.const 1) (i32.const 8000100) (i32.const 1) (i32.const 8000108)))
+        ;; Exit with a status when given an argument
+        (if (i32.gt_s (local.get $argc) (i32.const 1))
+            (then (call $proc_exit (i32.const 3))))
+        (i32.const 0))
+)
+"#;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wasi_imports_share_the_emscripten_memory() {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    let (stdout_tx, mut stdout_rx) = Pipe::channel();
+    let wasi = WasiEnvBuilder::new("hybrid").stdout(Box::new(stdout_tx));
+
+    run_emscripten_module(
+        &mut store,
+        &module,
+        "hybrid",
+        Vec::new(),
+        &[],
+        Default::default(),
+        Some(wasi),
+    )
+    .unwrap();
+
+    let mut stdout = String::new();
+    stdout_rx.read_to_string(&mut stdout).await.unwrap();
+    assert_eq!(stdout, "hello from emscripten\n");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wasi_exit_codes_are_reported() {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    let args = vec!["fail".to_string()];
+    let wasi = WasiEnvBuilder::new("hybrid").args(&args);
+
+    let err = run_emscripten_module(
+        &mut store,
+        &module,
+        "hybrid",
+        args,
+        &[],
+        Default::default(),
+        Some(wasi),
+    )
+    .unwrap_err();
+
+    let err = err.downcast::<wasmer::RuntimeError>().unwrap();
+    assert!(matches!(
+        err.downcast_ref::<WasiError>(),
+        Some(WasiError::Exit(code)) if code.raw() == 3
+    ));
+}
@@ -0,0 +1,65 @@
+#![cfg(not(feature = "js"))]
+
+use wasmer::{Module, Store, TrapCode};
+use wasmer_wasix::{
+    wasmer_wasix_types::wasi::{Errno, ExitCode, Signal},
+    WasiEnv, WasiRuntimeResult,
+};
+
+/// Runs a module whose `_start` function has the given body.
+fn run(body: &str) -> WasiRuntimeResult {
+    let wat = format!(
+        r#"
+        (module
+            (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+            (import "wasi_snapshot_preview1" "proc_raise"
+                (func $proc_raise (param i32) (result i32)))
+            (memory (export "memory") 1)
+            (func (export "_start") {body}))
+        "#
+    );
+    let mut store = Store::default();
+    let module = Module::new(&store, wat).unwrap();
+    let result = WasiEnv::builder("exit-status").run_with_store(module, &mut store);
+    WasiRuntimeResult::from_result(&result).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn normal_exit() {
+    let result = run("(call $proc_exit (i32.const 3))");
+    assert_eq!(result.exit_code, ExitCode::from(3));
+    assert_eq!(result.signal, None);
+    assert_eq!(result.trap, None);
+    assert_eq!(result.host_exit_code(), 3);
+
+    assert_eq!(run("").host_exit_code(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn raised_signals_terminate_the_guest() {
+    let result = run("(drop (call $proc_raise (i32.const 6)))");
+    assert_eq!(result.signal, Some(Signal::Sigabrt));
+    assert_eq!(result.exit_code, Errno::Intr.into());
+    assert_eq!(result.host_exit_code(), 128 + 6);
+
+    let result = run("(drop (call $proc_raise (i32.const 15)))");
+    assert_eq!(result.signal, Some(Signal::Sigterm));
+    assert_eq!(result.host_exit_code(), 128 + 15);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signals_ignored_by_default() {
+    // SIGWINCH is ignored so the guest carries on and exits normally
+    let result = run("(drop (call $proc_raise (i32.const 28)))
+         (call $proc_exit (i32.const 5))");
+    assert_eq!(result.signal, None);
+    assert_eq!(result.host_exit_code(), 5);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn traps_are_distinguished_from_exits() {
+    let result = run("unreachable");
+    assert_eq!(result.trap, Some(TrapCode::UnreachableCodeReached));
+    assert_eq!(result.signal, None);
+    assert_eq!(result.host_exit_code(), 128 + 6);
+}
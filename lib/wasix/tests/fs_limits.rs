@@ -0,0 +1,141 @@
+#![cfg(not(feature = "js"))]
+
+mod common;
+
+use common::{Guest, GuestBuilder};
+use wasmer::Value;
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, FsLimits, WasiEnv};
+
+// stdio, the root fd and the preopen
+const INITIAL_FDS: u32 = 5;
+const PREOPEN_FD: i32 = 4;
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_filestat_set_size"
+        (func $fd_filestat_set_size (param i32 i64) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_close"
+        (func $fd_close (param i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 32) "file.txt")
+
+    ;; Creates file.txt in the preopen, the opened fd is written to address 0.
+    (func (export "open") (result i32)
+        (call $path_open (i32.const 4) (i32.const 0) (i32.const 32) (i32.const 8)
+            (i32.const 1) (i64.const -1) (i64.const -1) (i32.const 0) (i32.const 0)))
+    ;; Writes `len` bytes using an iovec at address 8.
+    (func (export "write") (param $fd i32) (param $len i32) (result i32)
+        (i32.store (i32.const 8) (i32.const 64))
+        (i32.store (i32.const 12) (local.get $len))
+        (call $fd_write (local.get $fd) (i32.const 8) (i32.const 1) (i32.const 4)))
+    (func (export "set_size") (param i32 i64) (result i32)
+        (call $fd_filestat_set_size (local.get 0) (local.get 1)))
+    (func (export "close") (param i32) (result i32)
+        (call $fd_close (local.get 0)))
+)
+"#;
+
+fn fixture(limits: FsLimits) -> GuestBuilder {
+    let builder = WasiEnv::builder("fs-limits")
+        .fs_limits(limits)
+        .preopen_build(|p| {
+            p.directory("/data")
+                .alias("data")
+                .read(true)
+                .write(true)
+                .create(true)
+        })
+        .unwrap();
+    Guest::builder(MODULE, builder).dirs(&["/data"])
+}
+
+impl Guest {
+    /// Opens `file.txt`, returning the new fd.
+    fn open(&mut self) -> Result<i32, Errno> {
+        match self.call("open", &[]) {
+            Errno::Success => Ok(self.read_u32(0) as i32),
+            errno => Err(errno),
+        }
+    }
+}
+
+#[tokio::test]
+async fn max_open_fds() {
+    let (mut guest, _) = fixture(FsLimits::default().with_max_open_fds(INITIAL_FDS + 1)).build();
+
+    let fd = guest.open().unwrap();
+    assert_eq!(fd, PREOPEN_FD + 1);
+    assert_eq!(guest.open(), Err(Errno::Mfile));
+
+    // Closing a file frees up its slot again
+    assert_eq!(guest.call("close", &[Value::I32(fd)]), Errno::Success);
+    assert_eq!(guest.open(), Ok(fd));
+}
+
+#[tokio::test]
+async fn max_bytes_written() {
+    let (mut guest, _) = fixture(FsLimits::default().with_max_bytes_written(8)).build();
+    let fd = guest.open().unwrap();
+
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(5)]),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(5)]),
+        Errno::Dquot
+    );
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(3)]),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(1)]),
+        Errno::Dquot
+    );
+}
+
+#[tokio::test]
+async fn max_file_size() {
+    let (mut guest, _) = fixture(FsLimits::default().with_max_file_size(4)).build();
+    let fd = guest.open().unwrap();
+
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(5)]),
+        Errno::Fbig
+    );
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(4)]),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.call("write", &[Value::I32(fd), Value::I32(1)]),
+        Errno::Fbig
+    );
+
+    assert_eq!(
+        guest.call("set_size", &[Value::I32(fd), Value::I64(5)]),
+        Errno::Fbig
+    );
+    assert_eq!(
+        guest.call("set_size", &[Value::I32(fd), Value::I64(2)]),
+        Errno::Success
+    );
+}
+
+#[tokio::test]
+async fn unlimited_by_default() {
+    let (mut guest, _) = fixture(FsLimits::default()).build();
+
+    let fds = (0..16).map(|_| guest.open().unwrap()).collect::<Vec<_>>();
+    for fd in fds {
+        assert_eq!(
+            guest.call("write", &[Value::I32(fd), Value::I32(1024)]),
+            Errno::Success
+        );
+    }
+}
@@ -0,0 +1,124 @@
+#![cfg(not(feature = "js"))]
+
+use std::time::Duration;
+
+mod common;
+
+use common::Guest;
+use wasmer_wasix::{
+    wasmer_wasix_types::wasi::{Errno, WatchEventType},
+    WasiEnv,
+};
+
+const MODULE: &str = r#"
+(module
+    (import "wasix_32v1" "fd_watch"
+        (func $fd_watch (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_unlink_file"
+        (func $path_unlink_file (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_read"
+        (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_fdstat_set_flags"
+        (func $fd_fdstat_set_flags (param i32 i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 64) ".")
+    (data (i32.const 80) "watched.txt")
+    (data (i32.const 128) "hello")
+
+    ;; Watches the preopened directory and writes the watch fd to address 0
+    (func (export "watch") (result i32)
+        (call $fd_watch (i32.const 4) (i32.const 64) (i32.const 1) (i32.const 0)))
+
+    ;; Creates watched.txt and writes the new fd to address 4
+    (func (export "create") (result i32)
+        (call $path_open (i32.const 4) (i32.const 0) (i32.const 80) (i32.const 11)
+            (i32.const 1) (i64.const -1) (i64.const -1) (i32.const 0) (i32.const 4)))
+
+    (func (export "write") (param $fd i32) (result i32)
+        (i32.store (i32.const 16) (i32.const 128))
+        (i32.store (i32.const 20) (i32.const 5))
+        (call $fd_write (local.get $fd) (i32.const 16) (i32.const 1) (i32.const 8)))
+
+    (func (export "unlink") (result i32)
+        (call $path_unlink_file (i32.const 4) (i32.const 80) (i32.const 11)))
+
+    (func (export "nonblocking") (param $fd i32) (result i32)
+        (call $fd_fdstat_set_flags (local.get $fd) (i32.const 4)))
+
+    ;; Reads an event into address 256 and its length to address 8
+    (func (export "read") (param $fd i32) (result i32)
+        (i32.store (i32.const 16) (i32.const 256))
+        (i32.store (i32.const 20) (i32.const 64))
+        (call $fd_read (local.get $fd) (i32.const 16) (i32.const 1) (i32.const 8)))
+)
+"#;
+
+impl Guest {
+    /// Reads the next event from the watch
+    fn event(&mut self, watch: i32) -> (WatchEventType, String) {
+        assert_eq!(self.call("read", &[watch.into()]), Errno::Success);
+        let len = self.read_u32(8) as usize;
+        let buf = self.read_bytes(256, len);
+        let kind = u32::from_le_bytes(buf[0..4].try_into().unwrap());
+        let name_len = u32::from_le_bytes(buf[4..8].try_into().unwrap()) as usize;
+        assert_eq!(len, 8 + name_len);
+        (
+            WatchEventType::from_bits(kind).unwrap(),
+            String::from_utf8(buf[8..].to_vec()).unwrap(),
+        )
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn changes_in_a_watched_directory_are_reported() {
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("fs-watch").preopen_dir("/").unwrap(),
+    );
+
+    assert_eq!(guest.call("watch", &[]), Errno::Success);
+    let watch = guest.read_u32(0) as i32;
+
+    // Nothing has changed yet
+    assert_eq!(guest.call("nonblocking", &[watch.into()]), Errno::Success);
+    assert_eq!(guest.call("read", &[watch.into()]), Errno::Again);
+
+    assert_eq!(guest.call("create", &[]), Errno::Success);
+    let file = guest.read_u32(4) as i32;
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(
+        guest.event(watch),
+        (WatchEventType::CREATE, "watched.txt".to_string())
+    );
+
+    assert_eq!(guest.call("write", &[file.into()]), Errno::Success);
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(
+        guest.event(watch),
+        (WatchEventType::MODIFY, "watched.txt".to_string())
+    );
+
+    assert_eq!(guest.call("unlink", &[]), Errno::Success);
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(
+        guest.event(watch),
+        (WatchEventType::DELETE, "watched.txt".to_string())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_number_of_watches_is_limited() {
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("fs-watch").preopen_dir("/").unwrap(),
+    );
+
+    for _ in 0..64 {
+        assert_eq!(guest.call("watch", &[]), Errno::Success);
+    }
+    assert_eq!(guest.call("watch", &[]), Errno::Nospc);
+}
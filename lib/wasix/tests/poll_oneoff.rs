@@ -0,0 +1,310 @@
+#![cfg(not(feature = "js"))]
+
+use std::time::{Duration, Instant};
+
+mod common;
+
+use common::Guest;
+use wasmer::Value;
+use wasmer_wasix::{
+    wasmer_wasix_types::wasi::{Errno, Eventtype},
+    ClockSource, WasiEnv,
+};
+
+const MODULE: &str = r#"
+(module
+    (import "wasix_32v1" "poll_oneoff"
+        (func $poll_oneoff (param i32 i32 i32 i32) (result i32)))
+    (import "wasix_32v1" "fd_pipe"
+        (func $fd_pipe (param i32 i32) (result i32)))
+    (import "wasix_32v1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    ;; The subscriptions are read from address 1024 and the events are
+    ;; written to address 4096, the number of events goes to address 0.
+    (func (export "poll") (param $nsubscriptions i32) (result i32)
+        (call $poll_oneoff (i32.const 1024) (i32.const 4096) (local.get $nsubscriptions) (i32.const 0)))
+    ;; The two ends of the pipe are written to addresses 8 and 12.
+    (func (export "pipe") (result i32)
+        (call $fd_pipe (i32.const 8) (i32.const 12)))
+    ;; Writes 4 bytes from address 32 using an iovec at address 16.
+    (func (export "write") (param $fd i32) (result i32)
+        (i32.store (i32.const 16) (i32.const 32))
+        (i32.store (i32.const 20) (i32.const 4))
+        (call $fd_write (local.get $fd) (i32.const 16) (i32.const 1) (i32.const 24)))
+)
+"#;
+
+const SUBSCRIPTIONS: u64 = 1024;
+const SUBSCRIPTION_SIZE: u64 = 48;
+const EVENTS: u64 = 4096;
+const EVENT_SIZE: u64 = 32;
+
+enum Sub {
+    Clock {
+        userdata: u64,
+        timeout: Duration,
+    },
+    AbsClock {
+        userdata: u64,
+        clock_id: u32,
+        time: u64,
+    },
+    FdRead {
+        userdata: u64,
+        fd: u32,
+    },
+    FdWrite {
+        userdata: u64,
+        fd: u32,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct Evt {
+    userdata: u64,
+    error: Errno,
+    type_: Eventtype,
+    nbytes: u64,
+}
+
+impl Guest {
+    fn pipe(&mut self) -> (i32, i32) {
+        assert_eq!(self.call("pipe", &[]), Errno::Success);
+        (self.read_u32(8) as i32, self.read_u32(12) as i32)
+    }
+
+    fn poll(&mut self, subs: &[Sub]) -> Vec<Evt> {
+        for (n, sub) in subs.iter().enumerate() {
+            let mut buf = [0u8; SUBSCRIPTION_SIZE as usize];
+            let (userdata, type_) = match sub {
+                Sub::Clock { userdata, timeout } => {
+                    // Monotonic clock with a relative timeout
+                    buf[16..20].copy_from_slice(&1u32.to_le_bytes());
+                    buf[24..32].copy_from_slice(&(timeout.as_nanos() as u64).to_le_bytes());
+                    (*userdata, Eventtype::Clock)
+                }
+                Sub::AbsClock {
+                    userdata,
+                    clock_id,
+                    time,
+                } => {
+                    buf[16..20].copy_from_slice(&clock_id.to_le_bytes());
+                    buf[24..32].copy_from_slice(&time.to_le_bytes());
+                    buf[40..42].copy_from_slice(&1u16.to_le_bytes());
+                    (*userdata, Eventtype::Clock)
+                }
+                Sub::FdRead { userdata, fd } => {
+                    buf[16..20].copy_from_slice(&fd.to_le_bytes());
+                    (*userdata, Eventtype::FdRead)
+                }
+                Sub::FdWrite { userdata, fd } => {
+                    buf[16..20].copy_from_slice(&fd.to_le_bytes());
+                    (*userdata, Eventtype::FdWrite)
+                }
+            };
+            buf[0..8].copy_from_slice(&userdata.to_le_bytes());
+            buf[8] = type_ as u8;
+            self.write_bytes(SUBSCRIPTIONS + n as u64 * SUBSCRIPTION_SIZE, &buf);
+        }
+
+        let errno = self.call("poll", &[Value::I32(subs.len() as i32)]);
+        assert_eq!(errno, Errno::Success);
+
+        let nevents = self.read_u32(0);
+        (0..nevents as u64)
+            .map(|n| {
+                let buf = self.read_bytes(EVENTS + n * EVENT_SIZE, EVENT_SIZE as usize);
+                Evt {
+                    userdata: u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+                    error: Errno::try_from(u16::from_le_bytes([buf[8], buf[9]])).unwrap(),
+                    type_: match buf[10] {
+                        0 => Eventtype::Clock,
+                        1 => Eventtype::FdRead,
+                        2 => Eventtype::FdWrite,
+                        ty => panic!("unknown event type {ty}"),
+                    },
+                    nbytes: u64::from_le_bytes(buf[16..24].try_into().unwrap()),
+                }
+            })
+            .collect()
+    }
+}
+
+fn clock_event(userdata: u64) -> Evt {
+    Evt {
+        userdata,
+        error: Errno::Success,
+        type_: Eventtype::Clock,
+        nbytes: 0,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_earliest_clock_fires() {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("poll"));
+
+    let start = Instant::now();
+    let events = guest.poll(&[
+        Sub::Clock {
+            userdata: 1,
+            timeout: Duration::from_secs(10),
+        },
+        Sub::Clock {
+            userdata: 2,
+            timeout: Duration::from_millis(20),
+        },
+        Sub::Clock {
+            userdata: 3,
+            timeout: Duration::from_secs(5),
+        },
+    ]);
+
+    assert_eq!(events, [clock_event(2)]);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(20), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(5), "{elapsed:?}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sub_millisecond_timeouts_are_not_cut_short() {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("poll"));
+
+    for timeout in [100, 500, 1500] {
+        let timeout = Duration::from_micros(timeout);
+        let start = Instant::now();
+        let events = guest.poll(&[Sub::Clock {
+            userdata: 7,
+            timeout,
+        }]);
+        assert_eq!(events, [clock_event(7)]);
+        assert!(start.elapsed() >= timeout, "{:?}", start.elapsed());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn precise_timers_wake_up_on_time() {
+    let mut builder = WasiEnv::builder("poll");
+    builder.capabilities_mut().threading.enable_precise_timers = Some(Duration::from_millis(2));
+    let mut guest = Guest::new(MODULE, builder);
+
+    for timeout in [300, 2500, 10_000] {
+        let timeout = Duration::from_micros(timeout);
+        let start = Instant::now();
+        let events = guest.poll(&[Sub::Clock {
+            userdata: 7,
+            timeout,
+        }]);
+        let elapsed = start.elapsed();
+        assert_eq!(events, [clock_event(7)]);
+        assert!(elapsed >= timeout, "{elapsed:?}");
+        assert!(elapsed < timeout + Duration::from_millis(50), "{elapsed:?}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn absolute_timeouts_use_the_guest_clock() {
+    let now = 1_700_000_000 * 1_000_000_000;
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("poll").clock(ClockSource::Fixed(now)),
+    );
+
+    let start = Instant::now();
+    let events = guest.poll(&[Sub::AbsClock {
+        userdata: 5,
+        clock_id: 0,
+        time: now + 10_000_000,
+    }]);
+    assert_eq!(events, [clock_event(5)]);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(10), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(5), "{elapsed:?}");
+
+    // Deadlines that already passed fire straight away
+    let events = guest.poll(&[Sub::AbsClock {
+        userdata: 6,
+        clock_id: 0,
+        time: now - 1,
+    }]);
+    assert_eq!(events, [clock_event(6)]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pipe_readiness() {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("poll"));
+    let (read_end, write_end) = guest.pipe();
+
+    // Nothing has been written yet, so only the timeout fires
+    let events = guest.poll(&[
+        Sub::FdRead {
+            userdata: 1,
+            fd: read_end as u32,
+        },
+        Sub::Clock {
+            userdata: 2,
+            timeout: Duration::from_millis(10),
+        },
+    ]);
+    assert_eq!(events, [clock_event(2)]);
+
+    assert_eq!(
+        guest.call("write", &[Value::I32(write_end)]),
+        Errno::Success
+    );
+
+    let start = Instant::now();
+    let mut events = guest.poll(&[
+        Sub::FdRead {
+            userdata: 1,
+            fd: read_end as u32,
+        },
+        Sub::FdWrite {
+            userdata: 3,
+            fd: write_end as u32,
+        },
+        Sub::Clock {
+            userdata: 2,
+            timeout: Duration::from_secs(10),
+        },
+    ]);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    events.sort_by_key(|e| e.userdata);
+    assert_eq!(events.len(), 2, "{events:?}");
+    assert_eq!(events[0].userdata, 1);
+    assert_eq!(events[0].type_, Eventtype::FdRead);
+    assert_eq!(events[0].error, Errno::Success);
+    assert_eq!(events[0].nbytes, 4);
+    assert_eq!(events[1].userdata, 3);
+    assert_eq!(events[1].type_, Eventtype::FdWrite);
+    assert_eq!(events[1].error, Errno::Success);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_subscriptions_are_reported_as_events() {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("poll"));
+
+    let start = Instant::now();
+    let events = guest.poll(&[
+        Sub::FdRead {
+            userdata: 1,
+            fd: 1234,
+        },
+        Sub::Clock {
+            userdata: 2,
+            timeout: Duration::from_secs(10),
+        },
+    ]);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        events,
+        [Evt {
+            userdata: 1,
+            error: Errno::Badf,
+            type_: Eventtype::FdRead,
+            nbytes: 0,
+        }]
+    );
+}
@@ -0,0 +1,83 @@
+#![cfg(not(feature = "js"))]
+
+use std::{
+    io::Read,
+    net::{TcpListener, TcpStream},
+};
+
+mod common;
+
+use common::Guest;
+use wasmer::Value;
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_fdstat_get"
+        (func $fd_fdstat_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "sock_accept"
+        (func $sock_accept (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 64) "hello")
+
+    ;; The fdstat is written to address 0.
+    (func (export "fdstat") (param $fd i32) (result i32)
+        (call $fd_fdstat_get (local.get $fd) (i32.const 0)))
+    ;; Accepts a connection, writes its fd to address 32 and then sends
+    ;; the 5 bytes at address 64 using an iovec at address 48.
+    (func (export "accept_and_greet") (param $fd i32) (result i32)
+        (local $errno i32)
+        (local.set $errno
+            (call $sock_accept (local.get $fd) (i32.const 0) (i32.const 32)))
+        (if (i32.ne (local.get $errno) (i32.const 0))
+            (then (return (local.get $errno))))
+        (i32.store (i32.const 48) (i32.const 64))
+        (i32.store (i32.const 52) (i32.const 5))
+        (call $fd_write (i32.load (i32.const 32)) (i32.const 48) (i32.const 1) (i32.const 56)))
+)
+"#;
+
+/// stdio takes 0-2 and the root directory 3, so the first preopened
+/// listener comes right after them.
+const LISTENER_FD: i32 = 4;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn preopened_listener_is_a_socket() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("listen").preopen_listener(listener),
+    );
+
+    assert_eq!(
+        guest.call("fdstat", &[Value::I32(LISTENER_FD)]),
+        Errno::Success
+    );
+    // __WASI_FILETYPE_SOCKET_STREAM
+    assert_eq!(guest.read_bytes(0, 1)[0], 6);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn guest_accepts_on_a_preopened_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("listen").preopen_listener(listener),
+    );
+
+    let client = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).unwrap();
+        buf
+    });
+
+    assert_eq!(
+        guest.call("accept_and_greet", &[Value::I32(LISTENER_FD)]),
+        Errno::Success
+    );
+    assert_eq!(&client.join().unwrap(), b"hello");
+}
@@ -0,0 +1,466 @@
+#![cfg(not(feature = "js"))]
+
+mod common;
+
+use common::{Guest, GuestBuilder};
+use tokio::io::AsyncReadExt;
+use virtual_fs::{mem_fs, FileSystem};
+use wasmer_wasix::{
+    wasmer_wasix_types::wasi::{Errno, Oflags, Rights},
+    WasiEnv,
+};
+
+// The root fd is 3, so the preopens get 4 to 8.
+const ROOT_FD: i32 = 3;
+const READ_ONLY_FD: i32 = 4;
+const READ_WRITE_FD: i32 = 5;
+const APPEND_ONLY_FD: i32 = 6;
+const LIST_ONLY_FD: i32 = 7;
+// `/` itself, preopened with every right like the runners do.
+const SLASH_FD: i32 = 8;
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_unlink_file"
+        (func $path_unlink_file (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_remove_directory"
+        (func $path_remove_directory (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_create_directory"
+        (func $path_create_directory (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_rename"
+        (func $path_rename (param i32 i32 i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_seek"
+        (func $fd_seek (param i32 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_readdir"
+        (func $fd_readdir (param i32 i32 i32 i64 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    ;; The opened fd is written to address 0.
+    (func (export "open") (param $fd i32) (param $path i32) (param $len i32)
+        (param $oflags i32) (param $base i64) (param $inheriting i64) (result i32)
+        (call $path_open (local.get $fd) (i32.const 0) (local.get $path) (local.get $len)
+            (local.get $oflags) (local.get $base) (local.get $inheriting) (i32.const 0) (i32.const 0)))
+    (func (export "unlink") (param i32 i32 i32) (result i32)
+        (call $path_unlink_file (local.get 0) (local.get 1) (local.get 2)))
+    (func (export "rmdir") (param i32 i32 i32) (result i32)
+        (call $path_remove_directory (local.get 0) (local.get 1) (local.get 2)))
+    (func (export "mkdir") (param i32 i32 i32) (result i32)
+        (call $path_create_directory (local.get 0) (local.get 1) (local.get 2)))
+    (func (export "rename") (param i32 i32 i32 i32 i32 i32) (result i32)
+        (call $path_rename (local.get 0) (local.get 1) (local.get 2)
+            (local.get 3) (local.get 4) (local.get 5)))
+    ;; Writes a single byte using an iovec at address 8.
+    (func (export "write") (param $fd i32) (result i32)
+        (i32.store (i32.const 8) (i32.const 16))
+        (i32.store (i32.const 12) (i32.const 1))
+        (call $fd_write (local.get $fd) (i32.const 8) (i32.const 1) (i32.const 4)))
+    (func (export "rewind") (param $fd i32) (result i32)
+        (call $fd_seek (local.get $fd) (i64.const 0) (i32.const 0) (i32.const 24)))
+    ;; Directory entries are written to address 1024.
+    (func (export "readdir") (param $fd i32) (result i32)
+        (call $fd_readdir (local.get $fd) (i32.const 1024) (i32.const 1024) (i64.const 0) (i32.const 4)))
+)
+"#;
+
+/// A guest with preopens granting different rights.
+fn fixture() -> GuestBuilder {
+    let builder = WasiEnv::builder("preopen-rights")
+        .preopen_build(|p| p.directory("/ro").alias("ro").read(true))
+        .unwrap()
+        .preopen_build(|p| {
+            p.directory("/rw")
+                .alias("rw")
+                .read(true)
+                .write(true)
+                .create(true)
+        })
+        .unwrap()
+        .preopen_build(|p| {
+            p.directory("/logs").alias("logs").rights(
+                Rights::PATH_OPEN
+                    | Rights::PATH_CREATE_FILE
+                    | Rights::FD_WRITE
+                    | Rights::FD_FILESTAT_GET,
+            )
+        })
+        .unwrap()
+        .preopen_build(|p| {
+            p.directory("/list")
+                .alias("list")
+                .rights(Rights::FD_READDIR | Rights::PATH_FILESTAT_GET)
+        })
+        .unwrap()
+        .preopen_build(|p| p.directory("/").read(true).write(true).create(true))
+        .unwrap();
+    Guest::builder(MODULE, builder)
+        .dirs(&["/ro", "/ro/sub", "/rw", "/logs", "/list"])
+        .files(&[
+            "/ro/file.txt",
+            "/ro/sub/file.txt",
+            "/rw/file.txt",
+            "/logs/app.log",
+            "/list/file.txt",
+        ])
+}
+
+async fn contents(fs: &mem_fs::FileSystem, path: &str) -> Vec<u8> {
+    let mut buf = Vec::new();
+    fs.new_open_options()
+        .read(true)
+        .open(path)
+        .unwrap()
+        .read_to_end(&mut buf)
+        .await
+        .unwrap();
+    buf
+}
+
+impl Guest {
+    /// Copy `paths` into guest memory, returning a (pointer, length) pair
+    /// for each of them.
+    fn paths<const N: usize>(&mut self, paths: [&str; N]) -> [(i32, i32); N] {
+        let mut offset = 256;
+        paths.map(|path| {
+            self.write_bytes(offset, path.as_bytes());
+            let ptr = (offset as i32, path.len() as i32);
+            offset += path.len() as u64;
+            ptr
+        })
+    }
+
+    fn opened_fd(&self) -> i32 {
+        self.read_u32(0) as i32
+    }
+
+    fn open(&mut self, fd: i32, path: &str, oflags: Oflags, base: Rights, inh: Rights) -> Errno {
+        let [(ptr, len)] = self.paths([path]);
+        self.call(
+            "open",
+            &[
+                fd.into(),
+                ptr.into(),
+                len.into(),
+                (oflags.bits() as i32).into(),
+                (base.bits() as i64).into(),
+                (inh.bits() as i64).into(),
+            ],
+        )
+    }
+
+    fn path_call(&mut self, name: &str, fd: i32, path: &str) -> Errno {
+        let [(ptr, len)] = self.paths([path]);
+        self.call(name, &[fd.into(), ptr.into(), len.into()])
+    }
+
+    fn rename(&mut self, from_fd: i32, from: &str, to_fd: i32, to: &str) -> Errno {
+        let [(from_ptr, from_len), (to_ptr, to_len)] = self.paths([from, to]);
+        self.call(
+            "rename",
+            &[
+                from_fd.into(),
+                from_ptr.into(),
+                from_len.into(),
+                to_fd.into(),
+                to_ptr.into(),
+                to_len.into(),
+            ],
+        )
+    }
+
+    fn write(&mut self, fd: i32) -> Errno {
+        self.call("write", &[fd.into()])
+    }
+}
+
+#[tokio::test]
+async fn read_only_preopen_rejects_modifications() {
+    let (mut guest, _) = fixture().build();
+
+    // Reading is fine, but the fd can't be used to write
+    let errno = guest.open(
+        READ_ONLY_FD,
+        "file.txt",
+        Oflags::empty(),
+        Rights::FD_READ,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Success);
+    let fd = guest.opened_fd();
+    assert_eq!(guest.write(fd), Errno::Access);
+
+    // Asking for write access is refused outright
+    let errno = guest.open(
+        READ_ONLY_FD,
+        "file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Notcapable);
+
+    let errno = guest.open(
+        READ_ONLY_FD,
+        "new.txt",
+        Oflags::CREATE,
+        Rights::FD_READ,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Access);
+
+    assert_eq!(
+        guest.path_call("unlink", READ_ONLY_FD, "file.txt"),
+        Errno::Access
+    );
+    assert_eq!(guest.path_call("rmdir", READ_ONLY_FD, "sub"), Errno::Access);
+    assert_eq!(guest.path_call("mkdir", READ_ONLY_FD, "new"), Errno::Access);
+    assert_eq!(
+        guest.rename(READ_ONLY_FD, "file.txt", READ_WRITE_FD, "moved.txt"),
+        Errno::Access
+    );
+    assert_eq!(
+        guest.rename(READ_WRITE_FD, "file.txt", READ_ONLY_FD, "moved.txt"),
+        Errno::Access
+    );
+}
+
+#[tokio::test]
+async fn rights_are_inherited_by_subdirectories() {
+    let (mut guest, _) = fixture().build();
+
+    // Request every right for the sub-directory, only the ones held by the
+    // preopen may be passed on
+    let errno = guest.open(
+        READ_ONLY_FD,
+        "sub",
+        Oflags::DIRECTORY,
+        Rights::all() - Rights::FD_WRITE,
+        Rights::all(),
+    );
+    assert_eq!(errno, Errno::Success);
+    let sub = guest.opened_fd();
+
+    let errno = guest.open(
+        sub,
+        "file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Notcapable);
+
+    let errno = guest.open(
+        sub,
+        "new.txt",
+        Oflags::CREATE,
+        Rights::FD_READ,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Access);
+    assert_eq!(guest.path_call("unlink", sub, "file.txt"), Errno::Access);
+}
+
+#[tokio::test]
+async fn read_write_preopen_allows_modifications() {
+    let (mut guest, _) = fixture().build();
+
+    let errno = guest.open(
+        READ_WRITE_FD,
+        "file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Success);
+    let fd = guest.opened_fd();
+    assert_eq!(guest.write(fd), Errno::Success);
+
+    let errno = guest.open(
+        READ_WRITE_FD,
+        "new.txt",
+        Oflags::CREATE,
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Success);
+
+    assert_eq!(
+        guest.path_call("mkdir", READ_WRITE_FD, "dir"),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.path_call("rmdir", READ_WRITE_FD, "dir"),
+        Errno::Success
+    );
+    assert_eq!(
+        guest.path_call("unlink", READ_WRITE_FD, "new.txt"),
+        Errno::Success
+    );
+}
+
+#[tokio::test]
+async fn preopen_without_seek_rights_is_append_only() {
+    let (mut guest, fs) = fixture().build();
+
+    // Truncating existing files is not allowed
+    let errno = guest.open(
+        APPEND_ONLY_FD,
+        "app.log",
+        Oflags::TRUNC,
+        Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Notcapable);
+
+    for _ in 0..2 {
+        let errno = guest.open(
+            APPEND_ONLY_FD,
+            "app.log",
+            Oflags::CREATE,
+            Rights::FD_WRITE | Rights::FD_SEEK,
+            Rights::empty(),
+        );
+        assert_eq!(errno, Errno::Success);
+        let fd = guest.opened_fd();
+        assert_eq!(guest.call("rewind", &[fd.into()]), Errno::Access);
+        assert_eq!(guest.write(fd), Errno::Success);
+    }
+    assert_eq!(contents(&fs, "/logs/app.log").await.len(), 2);
+
+    assert_eq!(
+        guest.path_call("unlink", APPEND_ONLY_FD, "app.log"),
+        Errno::Access
+    );
+    assert_eq!(
+        guest.call("readdir", &[APPEND_ONLY_FD.into()]),
+        Errno::Access
+    );
+}
+
+#[tokio::test]
+async fn list_only_preopen_can_not_open_files() {
+    let (mut guest, _) = fixture().build();
+
+    assert_eq!(
+        guest.call("readdir", &[LIST_ONLY_FD.into()]),
+        Errno::Success
+    );
+
+    let errno = guest.open(
+        LIST_ONLY_FD,
+        "file.txt",
+        Oflags::empty(),
+        Rights::FD_READ,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Access);
+}
+
+#[tokio::test]
+async fn root_fd_can_not_bypass_the_preopens() {
+    let (mut guest, fs) = fixture().build();
+
+    // The root fd only keeps the rights shared by all the preopens, here
+    // it can't even open files since the list-only preopen can't
+    let errno = guest.open(
+        ROOT_FD,
+        "ro/file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Access);
+    let errno = guest.open(
+        ROOT_FD,
+        "ro/new.txt",
+        Oflags::CREATE,
+        Rights::FD_READ,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Access);
+
+    assert_eq!(
+        guest.path_call("unlink", ROOT_FD, "ro/file.txt"),
+        Errno::Access
+    );
+    assert_eq!(guest.path_call("rmdir", ROOT_FD, "ro/sub"), Errno::Access);
+    assert_eq!(guest.path_call("mkdir", ROOT_FD, "ro/new"), Errno::Access);
+    assert_eq!(
+        guest.rename(ROOT_FD, "ro/file.txt", READ_WRITE_FD, "moved.txt"),
+        Errno::Access
+    );
+    assert!(contents(&fs, "/ro/file.txt").await.is_empty());
+}
+
+#[tokio::test]
+async fn enclosing_preopen_can_not_bypass_the_nested_ones() {
+    let (mut guest, fs) = fixture().build();
+
+    let errno = guest.open(
+        SLASH_FD,
+        "ro/file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Notcapable);
+    let errno = guest.open(
+        SLASH_FD,
+        "ro/new.txt",
+        Oflags::CREATE,
+        Rights::FD_READ,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Access);
+
+    // Directories opened through it keep the rights of the nested preopen
+    let errno = guest.open(
+        SLASH_FD,
+        "ro",
+        Oflags::DIRECTORY,
+        Rights::all() - Rights::FD_WRITE,
+        Rights::all(),
+    );
+    assert_eq!(errno, Errno::Success);
+    let ro = guest.opened_fd();
+    let errno = guest.open(
+        ro,
+        "file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Notcapable);
+
+    assert_eq!(
+        guest.path_call("unlink", SLASH_FD, "ro/file.txt"),
+        Errno::Access
+    );
+    assert_eq!(guest.path_call("rmdir", SLASH_FD, "ro/sub"), Errno::Access);
+    assert_eq!(guest.path_call("mkdir", SLASH_FD, "ro/new"), Errno::Access);
+    assert_eq!(
+        guest.rename(SLASH_FD, "ro/file.txt", SLASH_FD, "rw/moved.txt"),
+        Errno::Access
+    );
+    assert_eq!(
+        guest.rename(SLASH_FD, "rw/file.txt", SLASH_FD, "ro/moved.txt"),
+        Errno::Access
+    );
+    assert!(contents(&fs, "/ro/file.txt").await.is_empty());
+
+    // Outside of the nested preopens, it keeps all of its rights
+    let errno = guest.open(
+        SLASH_FD,
+        "rw/file.txt",
+        Oflags::empty(),
+        Rights::FD_READ | Rights::FD_WRITE,
+        Rights::empty(),
+    );
+    assert_eq!(errno, Errno::Success);
+    let fd = guest.opened_fd();
+    assert_eq!(guest.write(fd), Errno::Success);
+    assert_eq!(guest.path_call("mkdir", SLASH_FD, "dir"), Errno::Success);
+}
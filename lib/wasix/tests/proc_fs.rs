@@ -0,0 +1,103 @@
+#![cfg(not(feature = "js"))]
+
+mod common;
+
+use common::Guest;
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_read"
+        (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    ;; Opens the path at address 64 with the given flags and writes the new fd
+    ;; to address 0
+    (func (export "open") (param $len i32) (param $oflags i32) (result i32)
+        (call $path_open (i32.const 4) (i32.const 0) (i32.const 64) (local.get $len)
+            (local.get $oflags) (i64.const -1) (i64.const -1) (i32.const 0) (i32.const 0)))
+
+    ;; Reads into address 256 and writes the length to address 8
+    (func (export "read") (param $fd i32) (result i32)
+        (i32.store (i32.const 16) (i32.const 256))
+        (i32.store (i32.const 20) (i32.const 1024))
+        (call $fd_read (local.get $fd) (i32.const 16) (i32.const 1) (i32.const 8)))
+)
+"#;
+
+const O_CREAT: i32 = 1;
+
+impl Guest {
+    fn open(&mut self, path: &str, oflags: i32) -> Errno {
+        self.write_bytes(64, path.as_bytes());
+        self.call("open", &[(path.len() as i32).into(), oflags.into()])
+    }
+
+    fn read_file(&mut self, path: &str) -> Vec<u8> {
+        assert_eq!(self.open(path, 0), Errno::Success, "{path}");
+        let fd = self.read_u32(0) as i32;
+        assert_eq!(self.call("read", &[fd.into()]), Errno::Success);
+        let len = self.read_u32(8);
+        self.read_bytes(256, len as usize)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_guest_can_inspect_itself() {
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("proc-fs")
+            .args(["--verbose", "input.txt"])
+            .env("GREETING", "hello")
+            .preopen_dir("/")
+            .unwrap()
+            .proc_fs(true),
+    );
+
+    assert_eq!(
+        guest.read_file("proc/self/cmdline"),
+        b"proc-fs\0--verbose\0input.txt\0"
+    );
+    assert_eq!(guest.read_file("proc/self/environ"), b"GREETING=hello\0");
+
+    let status = String::from_utf8(guest.read_file("proc/self/status")).unwrap();
+    assert!(status.contains("Name:\tproc-fs\n"), "{status}");
+    // A single page of memory
+    assert!(status.contains("VmSize:\t64 kB\n"), "{status}");
+
+    // The preopened directory
+    assert_eq!(guest.read_file("proc/self/fd/4"), b"/");
+    assert_eq!(guest.open("proc/self/fd/1234", 0), Errno::Noent);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proc_is_read_only() {
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("proc-fs")
+            .args(["--verbose", "input.txt"])
+            .env("GREETING", "hello")
+            .preopen_dir("/")
+            .unwrap()
+            .proc_fs(true),
+    );
+
+    assert_eq!(guest.open("proc/self/new.txt", O_CREAT), Errno::Perm);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proc_is_not_mounted_by_default() {
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("proc-fs")
+            .args(["--verbose", "input.txt"])
+            .env("GREETING", "hello")
+            .preopen_dir("/")
+            .unwrap()
+            .proc_fs(false),
+    );
+
+    assert_eq!(guest.open("proc/self/cmdline", 0), Errno::Noent);
+}
@@ -0,0 +1,105 @@
+#![cfg(not(feature = "js"))]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+mod common;
+
+use common::Guest;
+use wasmer::{Module, Pages, Store};
+use wasmer_wasix::{ResourceUsageTracker, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 128) "hello\n")
+
+    (func $write
+        (i32.store (i32.const 16) (i32.const 128))
+        (i32.store (i32.const 20) (i32.const 6))
+        (drop (call $fd_write (i32.const 1) (i32.const 16) (i32.const 1) (i32.const 8))))
+
+    (func (export "_start")
+        (local $i i32)
+        (call $write)
+        (call $write)
+        (drop (memory.grow (i32.const 2)))
+        ;; Burn some CPU time
+        (loop $busy
+            (local.set $i (i32.add (local.get $i) (i32.const 1)))
+            (br_if $busy (i32.lt_u (local.get $i) (i32.const 50000000)))))
+)
+"#;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn usage_is_accounted_in_the_tracker() {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    let tracker = ResourceUsageTracker::new();
+
+    WasiEnv::builder("usage")
+        .resource_usage(tracker.clone())
+        .run_with_store(module, &mut store)
+        .unwrap();
+
+    let usage = tracker.usage();
+    assert_eq!(usage.bytes_written, 12);
+    assert_eq!(usage.bytes_read, 0);
+    // Both writes, plus whatever the environment does when the guest exits
+    assert!(usage.syscalls >= 2, "{usage:?}");
+    assert!(usage.cpu_time > Duration::ZERO, "{usage:?}");
+    assert!(usage.execution_time > Duration::ZERO, "{usage:?}");
+    assert!(usage.instantiation_time > Duration::ZERO, "{usage:?}");
+    assert_eq!(usage.memory_grows, 1);
+    assert_eq!(usage.peak_memory_pages, 3);
+    // The module isn't metered
+    assert_eq!(usage.instructions, None);
+
+    let memory_usage = tracker.memory_usage().unwrap();
+    assert_eq!(memory_usage.memories.len(), 1);
+    assert_eq!(memory_usage.memories[0].minimum, Pages(1));
+    assert_eq!(memory_usage.memories[0].peak, Pages(3));
+    assert_eq!(memory_usage.data_segments_size, 6);
+    assert_eq!(memory_usage.stack, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_io_is_accounted_by_default() {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("usage"));
+
+    let start = guest.instance.exports.get_function("_start").unwrap();
+    start.call(&mut guest.store, &[]).unwrap();
+
+    let usage = guest.env.data(&guest.store).resource_usage();
+    assert_eq!(usage.bytes_written, 12);
+    assert_eq!(usage.syscalls, 0);
+    assert_eq!(usage.cpu_time, Duration::ZERO);
+    assert_eq!(usage.memory_grows, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn the_call_hook_of_the_embedder_is_kept() {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = calls.clone();
+    store.call_hook(move |_, _, _| {
+        counted.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    });
+    let tracker = ResourceUsageTracker::new();
+
+    WasiEnv::builder("usage")
+        .resource_usage(tracker.clone())
+        .run_with_store(module, &mut store)
+        .unwrap();
+
+    assert!(tracker.usage().syscalls >= 2);
+    // Calling `_start` and both writes, and their returns
+    assert!(calls.load(Ordering::Relaxed) >= 6);
+}
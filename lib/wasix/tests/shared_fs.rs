@@ -0,0 +1,100 @@
+#![cfg(not(feature = "js"))]
+
+mod common;
+
+use common::Guest;
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, SharedWasiFs, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_pread"
+        (func $fd_pread (param i32 i32 i32 i64 i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 64) "shared.txt")
+    (data (i32.const 128) "hello")
+
+    ;; Creates shared.txt in the preopened directory and writes the new fd
+    ;; to address 0
+    (func (export "create") (result i32)
+        (call $path_open (i32.const 4) (i32.const 0) (i32.const 64) (i32.const 10)
+            (i32.const 1) (i64.const -1) (i64.const -1) (i32.const 0) (i32.const 0)))
+
+    (func (export "write") (param $fd i32) (result i32)
+        (i32.store (i32.const 16) (i32.const 128))
+        (i32.store (i32.const 20) (i32.const 5))
+        (call $fd_write (local.get $fd) (i32.const 16) (i32.const 1) (i32.const 8)))
+
+    ;; Reads the start of the file into address 256 and the length to address 8
+    (func (export "read") (param $fd i32) (result i32)
+        (i32.store (i32.const 16) (i32.const 256))
+        (i32.store (i32.const 20) (i32.const 64))
+        (call $fd_pread (local.get $fd) (i32.const 16) (i32.const 1) (i64.const 0) (i32.const 8)))
+)
+"#;
+
+impl Guest {
+    fn share_fs(&self) -> SharedWasiFs {
+        self.env.data(&self.store).share_fs()
+    }
+
+    fn create(&mut self) -> i32 {
+        assert_eq!(self.call("create", &[]), Errno::Success);
+        self.read_u32(0) as i32
+    }
+
+    fn write(&mut self, fd: i32) {
+        assert_eq!(self.call("write", &[fd.into()]), Errno::Success);
+    }
+
+    fn read(&mut self, fd: i32) -> Result<String, Errno> {
+        match self.call("read", &[fd.into()]) {
+            Errno::Success => {}
+            err => return Err(err),
+        }
+        let buf = self.read_bytes(256, self.read_u32(8) as usize);
+        Ok(String::from_utf8(buf).unwrap())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn instances_share_open_files() {
+    let mut first = Guest::new(MODULE, WasiEnv::builder("first").preopen_dir("/").unwrap());
+    let mut second = Guest::new(
+        MODULE,
+        WasiEnv::builder("second").shared_fs(first.share_fs()),
+    );
+
+    let fd = first.create();
+    first.write(fd);
+    assert_eq!(second.read(fd).unwrap(), "hello");
+
+    // Both instances allocate from the same file descriptor table
+    let other_fd = second.create();
+    assert_ne!(other_fd, fd);
+    assert_eq!(first.read(other_fd).unwrap(), "hello");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn file_descriptors_are_private_by_default() {
+    let mut first = Guest::new(MODULE, WasiEnv::builder("first").preopen_dir("/").unwrap());
+    let mut second = Guest::new(MODULE, WasiEnv::builder("second").preopen_dir("/").unwrap());
+
+    let fd = first.create();
+    assert_eq!(second.read(fd).unwrap_err(), Errno::Badf);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shared_fs_cannot_be_reconfigured() {
+    let first = Guest::new(MODULE, WasiEnv::builder("first"));
+
+    let err = WasiEnv::builder("second")
+        .shared_fs(first.share_fs())
+        .fs(Box::<virtual_fs::mem_fs::FileSystem>::default())
+        .build_init()
+        .unwrap_err();
+    assert!(err.to_string().contains("shared"), "{err}");
+}
@@ -0,0 +1,117 @@
+#![cfg(not(feature = "js"))]
+
+use std::{future::Future, time::Duration};
+
+mod common;
+
+use common::Guest;
+use wasmer_wasix::{
+    wasmer_wasix_types::wasi::{Errno, ExitCode, Signal},
+    WasiEnv, WasiError,
+};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "sched_yield"
+        (func $sched_yield (result i32)))
+    (memory (export "memory") 1)
+
+    (func (export "spin")
+        (loop $again
+            (drop (call $sched_yield))
+            (br $again)))
+)
+"#;
+
+/// Signals a guest that never registered a signal handler and returns the
+/// exit code it ended up with.
+fn exit_code_after(signal: Signal) -> ExitCode {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("signals"));
+
+    guest.env.data(&guest.store).process.signal_process(signal);
+
+    let spin = guest.instance.exports.get_function("spin").unwrap();
+    let err = spin.call(&mut guest.store, &[]).unwrap_err();
+    match err.downcast::<WasiError>() {
+        Ok(WasiError::Exit(code)) => code,
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unhandled_sigint_terminates_the_guest() {
+    assert_eq!(exit_code_after(Signal::Sigint), Errno::Intr.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unhandled_sigterm_terminates_the_guest() {
+    assert_eq!(exit_code_after(Signal::Sigterm), Errno::Intr.into());
+}
+
+/// Returns `signal` once, then never returns again.
+fn signal_once(
+    signal: Signal,
+) -> impl FnMut() -> std::pin::Pin<Box<dyn Future<Output = Signal> + Send>> {
+    let mut sent = false;
+    move || {
+        if std::mem::replace(&mut sent, true) {
+            Box::pin(std::future::pending())
+        } else {
+            Box::pin(std::future::ready(signal))
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forwarded_signals_reach_the_guest() {
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("signals"));
+    let process = guest.env.data(&guest.store).process.clone();
+    let handle = process.join_handle();
+
+    let forwarding = tokio::spawn(async move {
+        handle
+            .forward_signals(Duration::from_secs(60), signal_once(Signal::Sigterm))
+            .await
+            .unwrap()
+    });
+
+    // The guest handles the forwarded signal the next time it yields
+    let spin = guest.instance.exports.get_function("spin").unwrap();
+    let err = spin.call(&mut guest.store, &[]).unwrap_err();
+    let code = match err.downcast::<WasiError>() {
+        Ok(WasiError::Exit(code)) => code,
+        other => panic!("unexpected error: {other:?}"),
+    };
+    assert_eq!(code, Errno::Intr.into());
+
+    // Exiting within the grace period lets the host carry on
+    process.terminate(code);
+    assert_eq!(forwarding.await.unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn guests_still_running_after_the_grace_period_are_terminated() {
+    let guest = Guest::new(MODULE, WasiEnv::builder("signals"));
+    let handle = guest.env.data(&guest.store).process.join_handle();
+
+    let signal = handle
+        .forward_signals(Duration::from_millis(50), signal_once(Signal::Sigint))
+        .await
+        .unwrap();
+    assert_eq!(signal, Some(Signal::Sigint));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_second_signal_ends_the_grace_period() {
+    let guest = Guest::new(MODULE, WasiEnv::builder("signals"));
+    let handle = guest.env.data(&guest.store).process.join_handle();
+
+    let signal = tokio::time::timeout(
+        Duration::from_secs(10),
+        handle.forward_signals(Duration::from_secs(60), || async { Signal::Sigterm }),
+    )
+    .await
+    .expect("the second signal was ignored")
+    .unwrap();
+    assert_eq!(signal, Some(Signal::Sigterm));
+}
@@ -0,0 +1,131 @@
+#![cfg(not(feature = "js"))]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+mod common;
+
+use common::Guest;
+use tokio::io::AsyncReadExt;
+use wasmer::Value;
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_read"
+        (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 64) "Hello, World!")
+
+    ;; Writes `len` bytes from `ptr` using an iovec at address 0, the number
+    ;; of bytes written goes to address 8.
+    (func (export "write") (param $fd i32) (param $ptr i32) (param $len i32) (result i32)
+        (i32.store (i32.const 0) (local.get $ptr))
+        (i32.store (i32.const 4) (local.get $len))
+        (call $fd_write (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 8)))
+    ;; Reads up to 32 bytes from stdin to address 128, the number of bytes
+    ;; read is written to address 8.
+    (func (export "read") (result i32)
+        (i32.store (i32.const 0) (i32.const 128))
+        (i32.store (i32.const 4) (i32.const 32))
+        (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+)
+"#;
+
+impl Guest {
+    /// Writes the first `len` bytes of "Hello, World!" to `fd`.
+    fn write(&mut self, fd: i32, len: i32) -> Errno {
+        let mut written = 0;
+        while written < len {
+            let args = [
+                Value::I32(fd),
+                Value::I32(64 + written),
+                Value::I32(len - written),
+            ];
+            match self.call("write", &args) {
+                Errno::Success => written += self.read_u32(8) as i32,
+                errno => return errno,
+            }
+        }
+        Errno::Success
+    }
+
+    fn read(&mut self) -> Vec<u8> {
+        assert_eq!(self.call("read", &[]), Errno::Success);
+        self.read_bytes(128, self.read_u32(8) as usize)
+    }
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn capture_stdout_and_stderr() {
+    let mut builder = WasiEnv::builder("stdio");
+    let stdout = builder.capture_stdout(1024);
+    let stderr = builder.capture_stderr(1024);
+    let mut guest = Guest::new(MODULE, builder);
+
+    assert_eq!(guest.write(1, 5), Errno::Success);
+    assert_eq!(guest.write(2, 13), Errno::Success);
+    assert_eq!(guest.write(1, 13), Errno::Success);
+
+    assert_eq!(stdout.take_output(), b"HelloHello, World!");
+    assert_eq!(stderr.take_output(), b"Hello, World!");
+    assert!(stdout.is_empty());
+
+    drop(guest);
+    assert!(stdout.is_closed());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn captured_output_applies_backpressure() {
+    let mut builder = WasiEnv::builder("stdio");
+    let mut stdout = builder.capture_stdout(4);
+
+    let guest = tokio::task::spawn_blocking(move || {
+        let mut guest = Guest::new(MODULE, builder);
+        assert_eq!(guest.write(1, 13), Errno::Success);
+        assert_eq!(guest.write(1, 5), Errno::Success);
+    });
+
+    let mut output = Vec::new();
+    stdout.read_to_end(&mut output).await.unwrap();
+    guest.await.unwrap();
+    assert_eq!(output, b"Hello, World!Hello");
+}
+
+#[tokio::test]
+async fn std_io_readers_and_writers() {
+    let out = SharedBuffer::default();
+    let err = SharedBuffer::default();
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("stdio")
+            .stdin_reader(&b"some input"[..])
+            .stdout_writer(out.clone())
+            .stderr_writer(err.clone()),
+    );
+
+    assert_eq!(guest.read(), b"some input");
+    assert_eq!(guest.read(), b"");
+    assert_eq!(guest.write(1, 5), Errno::Success);
+    assert_eq!(guest.write(2, 13), Errno::Success);
+
+    assert_eq!(*out.0.lock().unwrap(), b"Hello");
+    assert_eq!(*err.0.lock().unwrap(), b"Hello, World!");
+}
@@ -0,0 +1,76 @@
+#![cfg(not(feature = "js"))]
+
+use std::sync::{Arc, Mutex};
+
+use wasmer::{Module, Store};
+use wasmer_wasix::{SyscallEvent, SyscallTracer, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "clock_time_get"
+        (func $clock_time_get (param i32 i64 i32) (result i32)))
+    (memory (export "memory") 1)
+    (data (i32.const 128) "hello\n")
+
+    (func $write (param $fd i32)
+        (i32.store (i32.const 16) (i32.const 128))
+        (i32.store (i32.const 20) (i32.const 6))
+        (drop (call $fd_write (local.get $fd) (i32.const 16) (i32.const 1) (i32.const 8))))
+
+    (func (export "_start")
+        (call $write (i32.const 1))
+        (call $write (i32.const 2))
+        (drop (call $clock_time_get (i32.const 0) (i64.const 1) (i32.const 32))))
+)
+"#;
+
+fn run(tracer: impl FnOnce(SyscallTracer) -> SyscallTracer) -> Vec<SyscallEvent> {
+    let mut store = Store::default();
+    let module = Module::new(&store, MODULE).unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let tracer = tracer(SyscallTracer::new(move |event| {
+        recorded.lock().unwrap().push(event.clone())
+    }));
+
+    WasiEnv::builder("trace")
+        .syscall_tracer(tracer)
+        .run_with_store(module, &mut store)
+        .unwrap();
+
+    let events = events.lock().unwrap().clone();
+    events
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn syscalls_are_traced_with_their_arguments() {
+    let events = run(|tracer| tracer);
+
+    let names: Vec<_> = events.iter().map(|event| event.name).collect();
+    assert_eq!(&names[..3], ["fd_write", "fd_write", "clock_time_get"]);
+
+    let write = &events[0];
+    assert_eq!(write.fd, Some(1));
+    assert_eq!(write.args.len(), 4);
+    assert_eq!(write.args[0], "1");
+    assert_eq!(write.result, "Errno::success");
+    assert!(write.to_string().contains("] fd_write(1, "), "{write}");
+
+    let clock = &events[2];
+    assert_eq!(clock.fd, None);
+    assert_eq!(clock.args[1], "1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn syscalls_are_filtered_by_name_and_fd() {
+    let events = run(|tracer| tracer.with_names(["clock_*"]));
+    let names: Vec<_> = events.iter().map(|event| event.name).collect();
+    assert_eq!(names, ["clock_time_get"]);
+
+    let events = run(|tracer| tracer.with_names(["fd_*"]).with_fd(2));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name, "fd_write");
+    assert_eq!(events[0].fd, Some(2));
+}
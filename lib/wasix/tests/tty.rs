@@ -0,0 +1,76 @@
+#![cfg(not(feature = "js"))]
+
+use std::sync::Arc;
+
+mod common;
+
+use common::Guest;
+use tokio::runtime::Handle;
+use wasmer_wasix::{
+    os::TtyBridge,
+    runtime::task_manager::tokio::TokioTaskManager,
+    wasmer_wasix_types::wasi::{Errno, Filetype},
+    PluggableRuntime, WasiEnv, WasiTtyState,
+};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_fdstat_get"
+        (func $fd_fdstat_get (param i32 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    ;; Writes the fdstat of a file descriptor to address 0
+    (func (export "fdstat") (param $fd i32) (result i32)
+        (call $fd_fdstat_get (local.get $fd) (i32.const 0)))
+)
+"#;
+
+/// A terminal on stdin and stderr with stdout redirected elsewhere.
+#[derive(Debug)]
+struct StdoutRedirected;
+
+impl TtyBridge for StdoutRedirected {
+    fn reset(&self) {}
+
+    fn tty_get(&self) -> WasiTtyState {
+        WasiTtyState {
+            stdout_tty: false,
+            ..Default::default()
+        }
+    }
+
+    fn tty_set(&self, _tty_state: WasiTtyState) {}
+}
+
+/// Returns the file type the guest sees for each of the standard streams.
+fn stdio_filetypes(tty: Option<Arc<dyn TtyBridge + Send + Sync>>) -> Vec<u8> {
+    let mut rt = PluggableRuntime::new(Arc::new(TokioTaskManager::new(Handle::current())));
+    if let Some(tty) = tty {
+        rt.set_tty(tty);
+    }
+
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("isatty").runtime(Arc::new(rt)));
+    (0..3)
+        .map(|fd| {
+            assert_eq!(guest.call("fdstat", &[fd.into()]), Errno::Success);
+            guest.read_bytes(0, 1)[0]
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn redirected_stdio_is_not_a_terminal() {
+    assert_eq!(
+        stdio_filetypes(Some(Arc::new(StdoutRedirected))),
+        [
+            Filetype::CharacterDevice as u8,
+            Filetype::Unknown as u8,
+            Filetype::CharacterDevice as u8
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stdio_is_a_character_device_without_a_tty() {
+    assert_eq!(stdio_filetypes(None), [Filetype::CharacterDevice as u8; 3]);
+}
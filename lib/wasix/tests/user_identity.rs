@@ -0,0 +1,84 @@
+#![cfg(not(feature = "js"))]
+
+use std::path::Path;
+
+mod common;
+
+use common::Guest;
+use tokio::io::AsyncReadExt;
+use virtual_fs::{FileSystem, TmpFileSystem};
+use wasmer_wasix::{wasmer_wasix_types::wasi::Errno, UserIdentity, WasiEnv};
+
+const MODULE: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "environ_sizes_get"
+        (func $environ_sizes_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "environ_get"
+        (func $environ_get (param i32 i32) (result i32)))
+    (memory (export "memory") 1)
+
+    ;; The size of the environment is written to address 4, the pointers to
+    ;; address 64 and the variables themselves to address 1024.
+    (func (export "environ") (result i32)
+        (local $errno i32)
+        (local.set $errno (call $environ_sizes_get (i32.const 0) (i32.const 4)))
+        (if (i32.ne (local.get $errno) (i32.const 0))
+            (then (return (local.get $errno))))
+        (call $environ_get (i32.const 64) (i32.const 1024)))
+)
+"#;
+
+impl Guest {
+    fn environ(&mut self) -> Vec<String> {
+        assert_eq!(self.call("environ", &[]), Errno::Success);
+
+        let buf = self.read_bytes(1024, self.read_u32(4) as usize);
+        buf.split(|b| *b == 0)
+            .filter(|var| !var.is_empty())
+            .map(|var| String::from_utf8(var.to_vec()).unwrap())
+            .collect()
+    }
+}
+
+async fn read_to_string(fs: &TmpFileSystem, path: &str) -> String {
+    let mut f = fs.new_open_options().read(true).open(path).unwrap();
+    let mut contents = String::new();
+    f.read_to_string(&mut contents).await.unwrap();
+    contents
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn user_identity_is_visible_to_the_guest() {
+    let fs = TmpFileSystem::new();
+    let user = "alice:501:20".parse::<UserIdentity>().unwrap();
+    let mut guest = Guest::new(
+        MODULE,
+        WasiEnv::builder("whoami")
+            .sandbox_fs(fs.clone())
+            .env("LOGNAME", "bob")
+            .user(user),
+    );
+
+    let environ = guest.environ();
+    assert!(environ.contains(&"HOME=/home/alice".to_string()));
+    assert!(environ.contains(&"USER=alice".to_string()));
+    // Explicitly set variables take precedence
+    assert!(environ.contains(&"LOGNAME=bob".to_string()));
+    assert!(!environ.contains(&"LOGNAME=alice".to_string()));
+
+    assert_eq!(
+        read_to_string(&fs, "/etc/passwd").await,
+        "alice:x:501:20::/home/alice:/bin/sh\n"
+    );
+    assert_eq!(read_to_string(&fs, "/etc/group").await, "alice:x:20:\n");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn no_user_identity_by_default() {
+    let fs = TmpFileSystem::new();
+    let mut guest = Guest::new(MODULE, WasiEnv::builder("whoami").sandbox_fs(fs.clone()));
+
+    let environ = guest.environ();
+    assert!(!environ.iter().any(|var| var.starts_with("HOME=")));
+    assert!(fs.metadata(Path::new("/etc/passwd")).is_err());
+}
@@ -0,0 +1,51 @@
+#![cfg(not(feature = "js"))]
+
+use wasmer::{Module, Store};
+use wasmer_wasix::WasiEnv;
+
+#[test]
+fn test_wasi_thread_spawn() {
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        br#"
+    (module
+        (import "env" "memory" (memory 1 1 shared))
+        (import "wasi" "thread-spawn" (func $thread_spawn (param i32) (result i32)))
+
+        ;; The new thread sets the flag pointed to by its start argument.
+        (func (export "wasi_thread_start") (param $tid i32) (param $arg i32)
+            (i32.atomic.store (local.get $arg) (local.get $tid))
+            (drop (memory.atomic.notify (local.get $arg) (i32.const 1)))
+        )
+
+        (func (export "_start")
+            (local $tid i32)
+            (local $i i32)
+            ;; The start argument is opaque to the runtime: fill it with
+            ;; values that don't describe a valid stack.
+            (local.set $i (i32.const 20))
+            (loop $fill
+                (i32.store (local.get $i) (i32.const 0x100))
+                (local.set $i (i32.add (local.get $i) (i32.const 4)))
+                (br_if $fill (i32.lt_u (local.get $i) (i32.const 80)))
+            )
+            (local.set $tid (call $thread_spawn (i32.const 16)))
+            (if (i32.le_s (local.get $tid) (i32.const 0)) (then unreachable))
+            (loop $wait
+                (drop (memory.atomic.wait32 (i32.const 16) (i32.const 0) (i64.const -1)))
+                (br_if $wait (i32.eqz (i32.atomic.load (i32.const 16))))
+            )
+            (if (i32.ne (i32.atomic.load (i32.const 16)) (local.get $tid)) (then unreachable))
+        )
+    )
+    "#,
+    )
+    .unwrap();
+
+    let builder = WasiEnv::builder("wasi-threads");
+    std::thread::spawn(move || builder.run_with_store(module, &mut store))
+        .join()
+        .unwrap()
+        .unwrap();
+}
@@ -70,7 +70,7 @@ fn test_trap_trace(config: crate::Config) -> Result<()> {
     assert_eq!(trace[0].function_name(), Some("hello"));
     assert_eq!(trace[1].module_name(), "hello_mod");
     assert_eq!(trace[1].func_index(), 0);
-    assert_eq!(trace[1].function_name(), None);
+    assert_eq!(trace[1].function_name(), Some("run"));
     assert!(
         e.message().contains("unreachable"),
         "wrong message: {}",
@@ -185,7 +185,7 @@ RuntimeError: unreachable
     at die (m[0]:0x23)
     at <unnamed> (m[1]:0x27)
     at foo (m[2]:0x2c)
-    at <unnamed> (m[3]:0x31)"
+    at bar (m[3]:0x31)"
     );
     Ok(())
 }
@@ -239,9 +239,9 @@ RuntimeError: unreachable
     at die (a[0]:0x23)
     at <unnamed> (a[1]:0x27)
     at foo (a[2]:0x2c)
-    at <unnamed> (a[3]:0x31)
+    at bar (a[3]:0x31)
     at middle (b[1]:0x29)
-    at <unnamed> (b[2]:0x2e)"
+    at bar2 (b[2]:0x2e)"
     );
     Ok(())
 }